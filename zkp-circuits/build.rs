@@ -15,5 +15,14 @@ fn main() {
         println!("cargo:rustc-env=RUST_OPT_LEVEL=3");
     }
     
+    // Record the size of a previously built verifier module for build_info()
+    if let Ok(path) = env::var("REPID_VERIFIER_WASM") {
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            println!("cargo:rustc-env=REPID_VERIFIER_WASM_BYTES={}", metadata.len());
+        }
+        println!("cargo:rerun-if-changed={}", path);
+    }
+    println!("cargo:rerun-if-env-changed=REPID_VERIFIER_WASM");
+
     println!("cargo:rerun-if-changed=src/");
 }
\ No newline at end of file
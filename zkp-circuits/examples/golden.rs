@@ -0,0 +1,45 @@
+//! Write or check golden proof fixtures
+//!
+//! cargo run --example golden -- write tests/golden.json
+//! cargo run --example golden -- check tests/golden.json
+
+use std::process::ExitCode;
+
+use repid_zkp_circuits::fixtures::{check_golden, write_golden};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (mode, path) = match args.as_slice() {
+        [mode, path] => (mode.as_str(), path.as_str()),
+        _ => {
+            eprintln!("usage: golden <write|check> <path>");
+            return ExitCode::from(2);
+        }
+    };
+
+    let outcome = match mode {
+        "write" => write_golden(path).map(|golden| {
+            println!("wrote {} fixtures to {}", golden.fixtures.len(), path);
+            true
+        }),
+        "check" => check_golden(path).map(|changed| {
+            for name in &changed {
+                println!("changed: {}", name);
+            }
+            changed.is_empty()
+        }),
+        _ => {
+            eprintln!("unknown mode '{}', expected write or check", mode);
+            return ExitCode::from(2);
+        }
+    };
+
+    match outcome {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use repid_zkp_circuits::decoding::decode_fri_proof;
+use repid_zkp_circuits::limits::ProofLimits;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_fri_proof(data, &ProofLimits::default());
+});
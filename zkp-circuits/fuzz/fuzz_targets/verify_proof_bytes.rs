@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use repid_zkp_circuits::custom_stark::CustomStarkVerifier;
+use repid_zkp_circuits::decoding::decode_stark_proof;
+use repid_zkp_circuits::limits::ProofLimits;
+
+fuzz_target!(|data: &[u8]| {
+    // Decoded proofs must never panic the verifier, whatever the operation type
+    if let Ok(proof) = decode_stark_proof(data, &ProofLimits::default()) {
+        let verifier = CustomStarkVerifier::new(40, 4);
+        for operation in ["threshold_verification", "committed_threshold_verification", "biometric_4fa"] {
+            let _ = verifier.verify_proof(&proof, operation);
+        }
+    }
+});
@@ -0,0 +1,90 @@
+//! Absent Categories
+//!
+//! A requested category the wallet has no score in is not silently dropped
+//! or read as a zero score. It is proven as an explicit zero column with a
+//! set "absent" flag (`AbsenceAir`), so traces and debug dumps tell a missing
+//! category apart from a genuine score of zero.
+
+use serde::{Deserialize, Serialize};
+
+use crate::RepIDCategory;
+
+/// Witness scores of a threshold request, with every requested category present
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestedScores {
+    /// The wallet's requested scores in the given order, then a zero per absent category
+    pub scores: Vec<(RepIDCategory, u32)>,
+    /// Whether each entry of `scores` stands in for an absent category
+    pub absent: Vec<bool>,
+}
+
+impl RequestedScores {
+    /// Keep the scores of `categories` and fill every one missing from `user_scores` with an explicit zero
+    pub fn new(categories: &[RepIDCategory], user_scores: &[(RepIDCategory, u32)]) -> Self {
+        let mut scores: Vec<_> = user_scores.iter().filter(|(category, _)| categories.contains(category)).cloned().collect();
+        let mut absent = vec![false; scores.len()];
+        for category in categories {
+            if !scores.iter().any(|(scored, _)| scored == category) {
+                scores.push((category.clone(), 0));
+                absent.push(true);
+            }
+        }
+        Self { scores, absent }
+    }
+
+    /// Requested categories proven as absent
+    pub fn absent_categories(&self) -> Vec<RepIDCategory> {
+        self.scores
+            .iter()
+            .zip(&self.absent)
+            .filter(|(_, absent)| **absent)
+            .map(|((category, _), _)| category.clone())
+            .collect()
+    }
+
+    pub fn has_absent(&self) -> bool {
+        self.absent.contains(&true)
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::air::{check_witness, AbsenceAir, CustomAir};
+    use crate::custom_stark::{BabyBearField, ExecutionTrace};
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_absent_categories_are_flagged_zeros() {
+        let categories = [RepIDCategory::Technical, RepIDCategory::DeFi, RepIDCategory::Governance];
+        let requested = RequestedScores::new(&categories, &[(RepIDCategory::Technical, 80), (RepIDCategory::DeFi, 0)]);
+        assert_eq!(requested.scores.len(), 3);
+        assert_eq!(requested.absent, vec![false, false, true]);
+        assert_eq!(requested.absent_categories(), vec![RepIDCategory::Governance]);
+        assert!(!RequestedScores::new(&categories[..1], &[(RepIDCategory::Technical, 80)]).has_absent());
+
+        // A flagged column must hold zero; a genuine zero is not flagged
+        let air = AbsenceAir::new(3, vec![0, 1, 2]);
+        let mut trace = ExecutionTrace::new(air.width(), 1);
+        trace.set(0, 0, BabyBearField::new(80));
+        trace.set(0, air.flag_column(2), BabyBearField::ONE);
+        check_witness(&trace, &air).unwrap();
+        trace.set(0, 2, BabyBearField::new(5));
+        assert!(check_witness(&trace, &air).is_err());
+        trace.set(0, 2, BabyBearField::ZERO);
+        trace.set(0, air.flag_column(1), BabyBearField::new(2));
+        assert!(check_witness(&trace, &air).is_err());
+
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: categories.to_vec(),
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Technical, 80)], "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+    }
+}
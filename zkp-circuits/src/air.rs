@@ -0,0 +1,2552 @@
+//! Constraint Systems for the Custom STARK
+//!
+//! AIR definitions as polynomial constraints over an execution trace, plus a
+//! cheap witness checker that pinpoints failing constraints before any
+//! proving work. Constraints are `Expr` trees over trace cells (at a row
+//! rotation), fixed columns and constants, so the degree the prover and
+//! verifier must support is read off the constraints themselves;
+//! `TraceLayout` picks a trace length and blowup able to carry them, and
+//! `ConstraintSystem::check_degrees` rejects constraints the configured
+//! blowup cannot.
+
+use std::collections::HashMap;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::sync::{Arc, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::custom_stark::{BabyBearField, ExecutionTrace};
+use crate::decay::DecayFactor;
+use crate::domain::{TwoAdicSubgroup, TWO_ADICITY};
+use crate::fixed_point::Q16;
+use crate::normalization::{RoundingPolicy, ScoreScale, CANONICAL_MAX};
+use crate::polynomial::{Evaluations, Polynomial};
+use crate::saturation::{CategoryCap, SaturationCurve, EXP_NEG_CUTOFF, E_INV_Q16, TAYLOR_TERMS};
+use crate::time_predicate::TimePredicate;
+use crate::ZKPError;
+
+/// Polynomial over trace cells, fixed columns and constants
+///
+/// Cheap to clone: subexpressions shared between constraints are evaluated
+/// once per row by the compiled system.
+#[derive(Debug, Clone)]
+pub struct Expr(Arc<ExprNode>);
+
+#[derive(Debug)]
+struct ExprNode {
+    kind: ExprKind,
+    degree: usize,
+    /// Highest trace column read, if any
+    max_column: Option<usize>,
+}
+
+#[derive(Debug)]
+enum ExprKind {
+    Constant(BabyBearField),
+    /// Trace cell `rotation` rows from the evaluated one, wrapping around the trace
+    Cell { column: usize, rotation: isize },
+    /// Fixed column of the constraint system
+    Fixed(usize),
+    Add(Expr, Expr),
+    Sub(Expr, Expr),
+    Mul(Expr, Expr),
+    Neg(Expr),
+}
+
+impl Expr {
+    fn node(kind: ExprKind) -> Self {
+        let (degree, max_column) = match &kind {
+            ExprKind::Constant(_) => (0, None),
+            ExprKind::Cell { column, .. } => (1, Some(*column)),
+            ExprKind::Fixed(_) => (1, None),
+            ExprKind::Add(a, b) | ExprKind::Sub(a, b) => (a.degree().max(b.degree()), a.0.max_column.max(b.0.max_column)),
+            ExprKind::Mul(a, b) => (a.degree() + b.degree(), a.0.max_column.max(b.0.max_column)),
+            ExprKind::Neg(a) => (a.degree(), a.0.max_column),
+        };
+        Self(Arc::new(ExprNode { kind, degree, max_column }))
+    }
+
+    /// Trace cell of `column` on the evaluated row
+    pub fn cell(column: usize) -> Self {
+        Self::rotated(column, 0)
+    }
+
+    /// Trace cell of `column` `rotation` rows after the evaluated one
+    pub fn rotated(column: usize, rotation: isize) -> Self {
+        Self::node(ExprKind::Cell { column, rotation })
+    }
+
+    pub fn constant(value: BabyBearField) -> Self {
+        Self::node(ExprKind::Constant(value.canonicalize()))
+    }
+
+    /// Degree in trace cells and fixed columns
+    pub fn degree(&self) -> usize {
+        self.0.degree
+    }
+
+    fn as_constant(&self) -> Option<BabyBearField> {
+        match self.0.kind {
+            ExprKind::Constant(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn add_expr(a: Expr, b: Expr) -> Expr {
+        match (a.as_constant(), b.as_constant()) {
+            (Some(x), Some(y)) => Expr::constant(x + y),
+            (Some(BabyBearField::ZERO), _) => b,
+            (_, Some(BabyBearField::ZERO)) => a,
+            _ => Expr::node(ExprKind::Add(a, b)),
+        }
+    }
+
+    fn sub_expr(a: Expr, b: Expr) -> Expr {
+        match (a.as_constant(), b.as_constant()) {
+            (Some(x), Some(y)) => Expr::constant(x - y),
+            (_, Some(BabyBearField::ZERO)) => a,
+            (Some(BabyBearField::ZERO), _) => -b,
+            _ => Expr::node(ExprKind::Sub(a, b)),
+        }
+    }
+
+    fn mul_expr(a: Expr, b: Expr) -> Expr {
+        match (a.as_constant(), b.as_constant()) {
+            (Some(x), Some(y)) => Expr::constant(x * y),
+            (Some(BabyBearField::ZERO), _) | (_, Some(BabyBearField::ZERO)) => Expr::constant(BabyBearField::ZERO),
+            (Some(BabyBearField::ONE), _) => b,
+            (_, Some(BabyBearField::ONE)) => a,
+            _ => Expr::node(ExprKind::Mul(a, b)),
+        }
+    }
+}
+
+impl From<BabyBearField> for Expr {
+    fn from(value: BabyBearField) -> Self {
+        Expr::constant(value)
+    }
+}
+
+impl Neg for Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Expr {
+        match self.as_constant() {
+            Some(value) => Expr::constant(-value),
+            None => Expr::node(ExprKind::Neg(self)),
+        }
+    }
+}
+
+impl Neg for &Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Expr {
+        -self.clone()
+    }
+}
+
+/// Arithmetic between every pairing of `Expr`, `&Expr` and field constants
+macro_rules! expr_ops {
+    ($($trait:ident $method:ident $build:ident),*) => {$(
+        impl $trait<Expr> for Expr {
+            type Output = Expr;
+            fn $method(self, rhs: Expr) -> Expr {
+                Expr::$build(self, rhs)
+            }
+        }
+        impl $trait<&Expr> for Expr {
+            type Output = Expr;
+            fn $method(self, rhs: &Expr) -> Expr {
+                Expr::$build(self, rhs.clone())
+            }
+        }
+        impl $trait<Expr> for &Expr {
+            type Output = Expr;
+            fn $method(self, rhs: Expr) -> Expr {
+                Expr::$build(self.clone(), rhs)
+            }
+        }
+        impl $trait<&Expr> for &Expr {
+            type Output = Expr;
+            fn $method(self, rhs: &Expr) -> Expr {
+                Expr::$build(self.clone(), rhs.clone())
+            }
+        }
+        impl $trait<BabyBearField> for Expr {
+            type Output = Expr;
+            fn $method(self, rhs: BabyBearField) -> Expr {
+                Expr::$build(self, Expr::constant(rhs))
+            }
+        }
+        impl $trait<BabyBearField> for &Expr {
+            type Output = Expr;
+            fn $method(self, rhs: BabyBearField) -> Expr {
+                Expr::$build(self.clone(), Expr::constant(rhs))
+            }
+        }
+        impl $trait<Expr> for BabyBearField {
+            type Output = Expr;
+            fn $method(self, rhs: Expr) -> Expr {
+                Expr::$build(Expr::constant(self), rhs)
+            }
+        }
+        impl $trait<&Expr> for BabyBearField {
+            type Output = Expr;
+            fn $method(self, rhs: &Expr) -> Expr {
+                Expr::$build(Expr::constant(self), rhs.clone())
+            }
+        }
+    )*};
+}
+
+expr_ops!(Add add add_expr, Sub sub sub_expr, Mul mul mul_expr);
+
+impl std::iter::Sum for Expr {
+    fn sum<I: Iterator<Item = Expr>>(iter: I) -> Expr {
+        iter.fold(Expr::constant(BabyBearField::ZERO), |acc, term| acc + term)
+    }
+}
+
+/// Step of a compiled constraint program, reading earlier steps by index
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Constant(BabyBearField),
+    /// Index into `Program::cells`
+    Cell(usize),
+    Fixed(usize),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Mul(usize, usize),
+    Neg(usize),
+}
+
+/// Constraints flattened into one straight-line program with shared subexpressions
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Program {
+    ops: Vec<Op>,
+    /// Step holding each constraint's value, in constraint order
+    outputs: Vec<usize>,
+    /// (column, rotation) of every distinct cell read
+    cells: Vec<(usize, isize)>,
+}
+
+impl Program {
+    fn compile(constraints: &[Constraint]) -> Self {
+        let mut program = Program::default();
+        let mut seen: HashMap<*const ExprNode, usize> = HashMap::new();
+        let mut cells: HashMap<(usize, isize), usize> = HashMap::new();
+        for constraint in constraints {
+            let output = program.push(&constraint.expr, &mut seen, &mut cells);
+            program.outputs.push(output);
+        }
+        program
+    }
+
+    fn push(
+        &mut self,
+        expr: &Expr,
+        seen: &mut HashMap<*const ExprNode, usize>,
+        cells: &mut HashMap<(usize, isize), usize>,
+    ) -> usize {
+        let key = Arc::as_ptr(&expr.0);
+        if let Some(&index) = seen.get(&key) {
+            return index;
+        }
+        let op = match &expr.0.kind {
+            ExprKind::Constant(value) => Op::Constant(*value),
+            ExprKind::Cell { column, rotation } => {
+                let next = self.cells.len();
+                let index = *cells.entry((*column, *rotation)).or_insert(next);
+                if index == next {
+                    self.cells.push((*column, *rotation));
+                }
+                Op::Cell(index)
+            }
+            ExprKind::Fixed(index) => Op::Fixed(*index),
+            ExprKind::Add(a, b) => Op::Add(self.push(a, seen, cells), self.push(b, seen, cells)),
+            ExprKind::Sub(a, b) => Op::Sub(self.push(a, seen, cells), self.push(b, seen, cells)),
+            ExprKind::Mul(a, b) => Op::Mul(self.push(a, seen, cells), self.push(b, seen, cells)),
+            ExprKind::Neg(a) => Op::Neg(self.push(a, seen, cells)),
+        };
+        self.ops.push(op);
+        seen.insert(key, self.ops.len() - 1);
+        self.ops.len() - 1
+    }
+
+    /// (column, rotation) of every cell the program reads, in `cell` index order
+    pub(crate) fn cells(&self) -> &[(usize, isize)] {
+        &self.cells
+    }
+
+    /// Evaluate every step into `values`, reading cell `i` of `cells()` as
+    /// `cell(i)` and fixed column `j` as `fixed(j)`
+    pub(crate) fn run(
+        &self,
+        cell: impl Fn(usize) -> BabyBearField,
+        fixed: impl Fn(usize) -> BabyBearField,
+        values: &mut Vec<BabyBearField>,
+    ) {
+        values.clear();
+        for op in &self.ops {
+            let value = match *op {
+                Op::Constant(value) => value,
+                Op::Cell(index) => cell(index),
+                Op::Fixed(index) => fixed(index),
+                Op::Add(a, b) => values[a] + values[b],
+                Op::Sub(a, b) => values[a] - values[b],
+                Op::Mul(a, b) => values[a] * values[b],
+                Op::Neg(a) => -values[a],
+            };
+            values.push(value);
+        }
+    }
+
+    /// Constraint values out of a finished `run`
+    pub(crate) fn outputs<'a>(&'a self, values: &'a [BabyBearField]) -> impl Iterator<Item = BabyBearField> + 'a {
+        self.outputs.iter().map(move |&index| values[index])
+    }
+}
+
+/// Named polynomial that must vanish on every row of the trace
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    pub label: String,
+    pub expr: Expr,
+}
+
+/// Constraints of a trace of fixed height, with the fixed columns they select rows by
+///
+/// Rotations wrap around the trace, so `c - c[-1]` over every row holds a
+/// column constant; fixed columns are public and rebuilt by the verifier.
+#[derive(Debug, Clone)]
+pub struct ConstraintSystem {
+    height: usize,
+    width: usize,
+    /// Nonzero (row, value) entries of each fixed column, by row
+    fixed: Vec<Vec<(usize, BabyBearField)>>,
+    constraints: Vec<Constraint>,
+    program: OnceLock<Program>,
+}
+
+impl ConstraintSystem {
+    pub fn new(height: usize) -> Self {
+        Self {
+            height,
+            width: 0,
+            fixed: Vec::new(),
+            constraints: Vec::new(),
+            program: OnceLock::new(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Trace columns read or reserved
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Reserve the first `width` columns even when no constraint reads them
+    pub fn reserve(&mut self, width: usize) {
+        self.width = self.width.max(width);
+    }
+
+    /// Require `expr` to vanish on every row
+    pub fn constrain(&mut self, label: impl Into<String>, expr: Expr) {
+        self.width = self.width.max(expr.0.max_column.map_or(0, |column| column + 1));
+        self.constraints.push(Constraint { label: label.into(), expr });
+        self.program = OnceLock::new();
+    }
+
+    /// Require `expr` to vanish on `row`
+    pub fn constrain_at(&mut self, row: usize, label: impl Into<String>, expr: Expr) {
+        let selector = self.selector([row]);
+        self.constrain(label, selector * expr);
+    }
+
+    /// Fixed column holding `values` at their rows and zero elsewhere
+    pub fn fixed(&mut self, values: impl IntoIterator<Item = (usize, BabyBearField)>) -> Expr {
+        let mut entries: Vec<(usize, BabyBearField)> = values
+            .into_iter()
+            .map(|(row, value)| (row, value.canonicalize()))
+            .filter(|(row, value)| *row < self.height && *value != BabyBearField::ZERO)
+            .collect();
+        entries.sort_by_key(|(row, _)| *row);
+        entries.dedup_by_key(|(row, _)| *row);
+        if entries.is_empty() {
+            return Expr::constant(BabyBearField::ZERO);
+        }
+        let index = match self.fixed.iter().position(|existing| *existing == entries) {
+            Some(index) => index,
+            None => {
+                self.fixed.push(entries);
+                self.fixed.len() - 1
+            }
+        };
+        Expr::node(ExprKind::Fixed(index))
+    }
+
+    /// Fixed column that is one on `rows` and zero elsewhere
+    pub fn selector(&mut self, rows: impl IntoIterator<Item = usize>) -> Expr {
+        self.fixed(rows.into_iter().map(|row| (row, BabyBearField::ONE)))
+    }
+
+    pub fn first_row(&mut self) -> Expr {
+        self.selector([0])
+    }
+
+    pub fn last_row(&mut self) -> Expr {
+        let last = self.height.saturating_sub(1);
+        self.selector([last])
+    }
+
+    /// Hold `column` at one value on every row, so any row may read it
+    pub fn wire(&mut self, label: impl Into<String>, column: usize) {
+        self.constrain(label, Expr::cell(column) - Expr::rotated(column, -1));
+    }
+
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Highest degree of any constraint, at least one
+    pub fn max_degree(&self) -> usize {
+        self.constraints.iter().map(|constraint| constraint.expr.degree()).max().unwrap_or(1).max(1)
+    }
+
+    /// Reject a constraint above what `blowup_factor` supports, naming the first one
+    pub fn check_degrees(&self, blowup_factor: usize) -> Result<(), ZKPError> {
+        let supported = max_degree_for_blowup(blowup_factor);
+        match self.constraints.iter().find(|constraint| constraint.expr.degree() > supported) {
+            Some(constraint) => Err(ZKPError::ConfigError(format!(
+                "Constraint '{}' has degree {}, blowup {} supports at most {}; use a blowup of at least {}",
+                constraint.label,
+                constraint.expr.degree(),
+                blowup_factor,
+                supported,
+                min_blowup_for_degree(constraint.expr.degree())
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Distinct row rotations the constraints read, ascending, always including zero
+    pub fn rotations(&self) -> Vec<isize> {
+        let mut rotations: Vec<isize> = self.program().cells().iter().map(|&(_, rotation)| rotation).collect();
+        rotations.push(0);
+        rotations.sort_unstable();
+        rotations.dedup();
+        rotations
+    }
+
+    /// Degree bound of the quotient: below `(d - 1) * n`, and at least the trace's `n`
+    pub fn quotient_degree_bound(&self) -> usize {
+        self.height * self.max_degree().saturating_sub(1).max(1)
+    }
+
+    pub fn num_fixed(&self) -> usize {
+        self.fixed.len()
+    }
+
+    /// Dense values of fixed column `index`
+    pub fn fixed_values(&self, index: usize) -> Vec<BabyBearField> {
+        let mut values = vec![BabyBearField::ZERO; self.height];
+        for &(row, value) in &self.fixed[index] {
+            values[row] = value;
+        }
+        values
+    }
+
+    /// Polynomial interpolating each fixed column over the trace subgroup
+    pub fn fixed_polynomials(&self) -> Result<Vec<Polynomial>, ZKPError> {
+        let domain = TwoAdicSubgroup::new(self.height.trailing_zeros())?;
+        (0..self.num_fixed()).map(|index| Evaluations::new(domain, self.fixed_values(index))?.interpolate()).collect()
+    }
+
+    /// The same constraints over `height` rows, with the fixed columns repeated every `self.height()` rows
+    ///
+    /// A trace repeating every `self.height()` rows satisfies the tiled
+    /// system exactly when one period satisfies this one; rotations wrap
+    /// into the neighbouring period instead of around the trace.
+    pub fn tiled(&self, height: usize) -> Result<Self, ZKPError> {
+        if self.height == 0 || !height.is_multiple_of(self.height) {
+            return Err(ZKPError::ConfigError(format!(
+                "A constraint system of {} rows cannot tile {} rows",
+                self.height, height
+            )));
+        }
+        let periods = height / self.height;
+        let fixed = self
+            .fixed
+            .iter()
+            .map(|entries| {
+                (0..periods)
+                    .flat_map(|period| entries.iter().map(move |&(row, value)| (period * self.height + row, value)))
+                    .collect()
+            })
+            .collect();
+        Ok(Self {
+            height,
+            width: self.width,
+            fixed,
+            constraints: self.constraints.clone(),
+            program: OnceLock::new(),
+        })
+    }
+
+    pub(crate) fn program(&self) -> &Program {
+        self.program.get_or_init(|| Program::compile(&self.constraints))
+    }
+
+    /// Row `row + rotation`, wrapping around the trace
+    fn rotate(&self, row: usize, rotation: isize) -> usize {
+        (row as isize + rotation).rem_euclid(self.height as isize) as usize
+    }
+
+    fn evaluate_rows(&self, trace: &ExecutionTrace, mut visit: impl FnMut(usize, &mut dyn Iterator<Item = BabyBearField>) -> bool) {
+        let program = self.program();
+        let fixed: Vec<Vec<BabyBearField>> = (0..self.num_fixed()).map(|index| self.fixed_values(index)).collect();
+        let mut values = Vec::new();
+        for row in 0..self.height {
+            let fixed_row: Vec<BabyBearField> = fixed.iter().map(|values| values[row]).collect();
+            program.run(
+                |index| {
+                    let (column, rotation) = program.cells()[index];
+                    trace.get(self.rotate(row, rotation), column)
+                },
+                |index| fixed_row[index],
+                &mut values,
+            );
+            if !visit(row, &mut program.outputs(&values)) {
+                break;
+            }
+        }
+    }
+
+    /// Constraint values on every row of the trace (all zero when satisfied)
+    pub fn evaluate(&self, trace: &ExecutionTrace) -> Vec<Vec<BabyBearField>> {
+        let mut rows = Vec::with_capacity(self.height);
+        self.evaluate_rows(trace, |_, outputs| {
+            rows.push(outputs.collect());
+            true
+        });
+        rows
+    }
+
+    /// First unsatisfied constraint, scanning rows in order
+    pub fn check(&self, trace: &ExecutionTrace) -> Result<(), ConstraintViolation> {
+        if trace.width < self.width || trace.height != self.height {
+            return Err(ConstraintViolation {
+                row: 0,
+                index: 0,
+                label: format!(
+                    "trace of {}x{} does not fit a constraint system of {}x{}",
+                    trace.height, trace.width, self.height, self.width
+                ),
+                residual: 0,
+            });
+        }
+
+        let mut violation = None;
+        self.evaluate_rows(trace, |row, outputs| {
+            violation = outputs.enumerate().find(|(_, value)| *value != BabyBearField::ZERO).map(|(index, value)| {
+                ConstraintViolation {
+                    row,
+                    index,
+                    label: self.constraints[index].label.clone(),
+                    residual: value.0,
+                }
+            });
+            violation.is_none()
+        });
+        violation.map_or(Ok(()), Err)
+    }
+}
+
+/// Algebraic constraint system over an execution trace
+pub trait CustomAir {
+    /// Number of trace columns the AIR reads
+    fn width(&self) -> usize;
+
+    /// Add the AIR's constraints to a system of the trace's height
+    fn add_constraints(&self, system: &mut ConstraintSystem);
+
+    /// Constraint system of the AIR over `height` rows
+    fn constraint_system(&self, height: usize) -> ConstraintSystem {
+        let mut system = ConstraintSystem::new(height);
+        self.add_constraints(&mut system);
+        system.reserve(self.width());
+        system
+    }
+
+    /// Evaluate every row of the trace
+    fn evaluate(&self, trace: &ExecutionTrace) -> Vec<Vec<BabyBearField>> {
+        self.constraint_system(trace.height).evaluate(trace)
+    }
+}
+
+/// First unsatisfied constraint found in a witness
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Constraint '{label}' (#{index}) violated at row {row} with residual {residual}")]
+pub struct ConstraintViolation {
+    pub row: usize,
+    pub index: usize,
+    pub label: String,
+    pub residual: u64,
+}
+
+impl ConstraintViolation {
+    /// Locate the first non-zero residual in evaluated constraints
+    pub fn first_in(constraints: &[Vec<BabyBearField>]) -> Option<Self> {
+        constraints.iter().enumerate().find_map(|(row, row_constraints)| {
+            row_constraints
+                .iter()
+                .position(|value| *value != BabyBearField::ZERO)
+                .map(|index| Self {
+                    row,
+                    index,
+                    label: format!("constraint_{}", index),
+                    residual: row_constraints[index].0,
+                })
+        })
+    }
+}
+
+impl From<ConstraintViolation> for ZKPError {
+    fn from(violation: ConstraintViolation) -> Self {
+        ZKPError::CircuitError(violation.to_string())
+    }
+}
+
+/// Evaluate all constraints directly on the trace, returning the first failure
+pub fn check_witness(trace: &ExecutionTrace, air: &dyn CustomAir) -> Result<(), ConstraintViolation> {
+    air.constraint_system(trace.height).check(trace)
+}
+
+/// Smallest blowup whose LDE domain can degree-test the quotient of degree-`max_degree` constraints
+///
+/// Composed over `n` rows a degree-`d` constraint has degree `d * (n - 1)`;
+/// divided by the vanishing polynomial of the trace the quotient stays below
+/// `(d - 1) * n`. FRI only distinguishes it from an arbitrary function on a
+/// domain strictly larger, so the blowup is at least `d`, rounded up to a
+/// power of two.
+pub fn min_blowup_for_degree(max_degree: usize) -> usize {
+    max_degree.max(2).next_power_of_two()
+}
+
+/// Highest constraint degree an LDE `blowup_factor` times the trace carries
+pub fn max_degree_for_blowup(blowup_factor: usize) -> usize {
+    blowup_factor
+}
+
+/// Trace length and blowup selected for an AIR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceLayout {
+    /// Witness rows padded to a power of two
+    pub trace_length: usize,
+    pub blowup_factor: usize,
+    pub max_degree: usize,
+}
+
+impl TraceLayout {
+    /// Layout for `rows` witness rows, raising `min_blowup` to what `max_degree` needs
+    pub fn select(rows: usize, max_degree: usize, min_blowup: usize) -> Result<Self, ZKPError> {
+        if rows == 0 || max_degree == 0 {
+            return Err(ZKPError::ConfigError("Trace layout needs at least one row and a positive degree".to_string()));
+        }
+        let blowup_factor = min_blowup.max(min_blowup_for_degree(max_degree)).next_power_of_two();
+        let trace_length = rows.next_power_of_two();
+        let log_lde_height = trace_length.trailing_zeros() + blowup_factor.trailing_zeros();
+        if log_lde_height > TWO_ADICITY {
+            return Err(ZKPError::ConfigError(format!(
+                "{} rows at constraint degree {} need an LDE of 2^{} points, beyond the 2^{} BabyBear subgroup",
+                rows, max_degree, log_lde_height, TWO_ADICITY
+            )));
+        }
+        Ok(Self { trace_length, blowup_factor, max_degree })
+    }
+
+    pub fn lde_height(&self) -> usize {
+        self.trace_length * self.blowup_factor
+    }
+}
+
+/// Largest magnitude a running score total may reach
+///
+/// Partial sums stay within `[-MAX_AGGREGATE_SCORE, MAX_AGGREGATE_SCORE)`, so
+/// each step between them is below half the modulus and the field sum equals
+/// the integer sum; shifted by the bound they fit `AGGREGATE_RANGE_BITS` bits.
+pub const MAX_AGGREGATE_SCORE: u64 = 1 << 28;
+
+/// Bits range-checked for shifted totals and comparison distances
+pub const AGGREGATE_RANGE_BITS: usize = 29;
+
+/// Bits range-checked for a nonnegative score below `MAX_AGGREGATE_SCORE`
+pub const SCORE_RANGE_BITS: usize = 28;
+
+/// Ring operations shared by field values and `Expr`s
+///
+/// Gadgets written over it compute their witness and their constraints from
+/// one definition.
+pub trait Arithmetic: Clone + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + From<BabyBearField> {}
+
+impl<T> Arithmetic for T where T: Clone + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + From<BabyBearField> {}
+
+/// Distance that is below `2^bits` exactly when `flag` records `a >= b`
+///
+/// With `a` and `b` within the aggregate bounds, a flag of 1 yields `a - b`
+/// and a flag of 0 yields `b - 1 - a`; the wrong flag wraps the distance
+/// around the field, so range-checking it proves the comparison. Works on
+/// field values for witnesses and on `Expr`s for constraints.
+pub fn at_least<T: Arithmetic>(flag: T, a: T, b: T) -> T {
+    let one = T::from(BabyBearField::ONE);
+    flag.clone() * (a.clone() - b.clone()) + (one.clone() - flag) * (b - one - a)
+}
+
+/// Range check spreading a value's bits down the rows of two columns
+///
+/// Rows `1..=bits` shift one bit each, most significant first, into an
+/// accumulator that is zero on the first row and must equal the value on the
+/// last, proving the value is below `2^bits`; other rows keep their bit zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeCheck {
+    /// Column of the bits; the accumulator follows it
+    pub bit_column: usize,
+    pub bits: usize,
+}
+
+impl RangeCheck {
+    /// Columns the check occupies
+    pub const COLUMNS: usize = 2;
+
+    pub fn new(bit_column: usize, bits: usize) -> Self {
+        Self { bit_column, bits }
+    }
+
+    pub fn accumulator_column(&self) -> usize {
+        self.bit_column + 1
+    }
+
+    /// Rows a trace needs to carry the check
+    pub fn rows(&self) -> usize {
+        self.bits + 1
+    }
+
+    fn shifts(&self, row: usize) -> bool {
+        (1..=self.bits).contains(&row)
+    }
+
+    /// Write the bits of `value`; a value of `2^bits` or more keeps only its low bits
+    pub fn fill(&self, trace: &mut ExecutionTrace, value: u64) {
+        let mut accumulator = 0u64;
+        for row in 0..trace.height {
+            let bit = if self.shifts(row) { (value >> (self.bits - row)) & 1 } else { 0 };
+            if self.shifts(row) {
+                accumulator = 2 * accumulator + bit;
+            }
+            trace.set(row, self.bit_column, BabyBearField::new(bit));
+            trace.set(row, self.accumulator_column(), BabyBearField::new(accumulator));
+        }
+    }
+
+    /// Prove `value` below `2^bits` on the last row, labelling the match `name`
+    pub fn constrain(&self, system: &mut ConstraintSystem, name: &str, value: Expr) {
+        let shift = system.selector(1..=self.bits);
+        let first = system.first_row();
+        let last = system.last_row();
+        let bit = Expr::cell(self.bit_column);
+        let accumulator = Expr::cell(self.accumulator_column());
+        let previous = Expr::rotated(self.accumulator_column(), -1);
+        let one = BabyBearField::ONE;
+        system.constrain(format!("{}_bit_boolean", name), &bit * (&bit - one));
+        system.constrain(format!("{}_bit_window", name), (one - &shift) * &bit);
+        system.constrain(format!("{}_accumulator", name), &accumulator - (one - first + shift) * previous - bit);
+        system.constrain(name, last * (accumulator - value));
+    }
+}
+
+/// Range check spreading a value's bits across the columns of one row
+///
+/// For values that change from row to row, where `RangeCheck` needs a
+/// column pair per value; bits are least significant first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitDecomposition {
+    pub column_offset: usize,
+    pub bits: usize,
+}
+
+impl BitDecomposition {
+    pub fn new(column_offset: usize, bits: usize) -> Self {
+        Self { column_offset, bits }
+    }
+
+    /// Write the bits of `value` on `row`; a value of `2^bits` or more keeps only its low bits
+    pub fn fill(&self, trace: &mut ExecutionTrace, row: usize, value: u64) {
+        for bit in 0..self.bits {
+            trace.set(row, self.column_offset + bit, BabyBearField::new((value >> bit) & 1));
+        }
+    }
+
+    fn recomposed(&self) -> Expr {
+        (0..self.bits).map(|bit| Expr::cell(self.column_offset + bit) * BabyBearField::new(1 << bit)).sum()
+    }
+
+    /// Prove `value` below `2^bits` on rows where `gate` is one, labelling the match `name`
+    pub fn constrain(&self, system: &mut ConstraintSystem, name: &str, gate: Expr, value: Expr) {
+        for bit in 0..self.bits {
+            let cell = Expr::cell(self.column_offset + bit);
+            system.constrain(format!("{}_bit_boolean", name), &cell * (&cell - BabyBearField::ONE));
+        }
+        system.constrain(name, gate * (value - self.recomposed()));
+    }
+}
+
+/// Largest score, exclusive, a decay gadget divides
+pub const MAX_DECAYED_SCORE: u64 = 1 << (2 * DECAY_LIMB_BITS);
+
+/// Bits in each half of a decayed score
+const DECAY_LIMB_BITS: usize = 10;
+
+/// Decay of one score column by a public `DecayFactor`
+///
+/// The score splits into two 10-bit limbs and `score * retention / 2^16` is
+/// divided in two steps whose products stay below the modulus:
+/// `low * retention = carry * 2^10 + low_remainder`, then
+/// `high * retention + carry = quotient * 2^6 + high_remainder`, every part
+/// range-checked. A nonzero floor adds two comparisons selecting
+/// `max(quotient, min(floor, score))`.
+///
+/// Column layout from `column_offset`: 0 high, 1 low, 2 carry, 3
+/// low_remainder, 4 quotient, 5 high_remainder, 6..18 their range checks;
+/// with a floor, 18 reached flag, 19 min(floor, score), 20 kept flag, 21..25
+/// the two comparison range checks and 25 the decayed score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecayGadget {
+    pub score_column: usize,
+    pub column_offset: usize,
+    pub factor: DecayFactor,
+}
+
+impl DecayGadget {
+    pub fn new(score_column: usize, column_offset: usize, factor: DecayFactor) -> Self {
+        Self {
+            score_column,
+            column_offset,
+            factor,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        if self.factor.floor == 0 {
+            18
+        } else {
+            26
+        }
+    }
+
+    /// Column of the decayed score
+    pub fn output_column(&self) -> usize {
+        if self.factor.floor == 0 {
+            self.column_offset + 4
+        } else {
+            self.column_offset + 25
+        }
+    }
+
+    /// Range checks on high, low, carry, low_remainder, quotient and high_remainder
+    fn part_ranges(&self) -> [RangeCheck; 6] {
+        let remainder_bits = Q16::FRAC_BITS as usize - DECAY_LIMB_BITS;
+        [DECAY_LIMB_BITS, DECAY_LIMB_BITS, Q16::FRAC_BITS as usize, DECAY_LIMB_BITS, 2 * DECAY_LIMB_BITS, remainder_bits]
+            .into_iter()
+            .enumerate()
+            .map(|(i, bits)| RangeCheck::new(self.column_offset + 6 + 2 * i, bits))
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("six decay parts")
+    }
+
+    fn reached_range(&self) -> RangeCheck {
+        RangeCheck::new(self.column_offset + 21, AGGREGATE_RANGE_BITS)
+    }
+
+    fn kept_range(&self) -> RangeCheck {
+        RangeCheck::new(self.column_offset + 23, AGGREGATE_RANGE_BITS)
+    }
+
+    /// Columns holding one value on every row
+    pub fn data_columns(&self) -> Vec<usize> {
+        let mut columns: Vec<usize> = (0..6).map(|i| self.column_offset + i).collect();
+        if self.factor.floor > 0 {
+            columns.extend([18, 19, 20, 25].map(|i| self.column_offset + i));
+        }
+        columns
+    }
+
+    /// Write the decay of `score`, below `MAX_DECAYED_SCORE`, on every row
+    pub fn fill(&self, trace: &mut ExecutionTrace, score: u32) {
+        let retention = self.factor.retention.raw() as u64;
+        let (high, low) = (score as u64 >> DECAY_LIMB_BITS, score as u64 & ((1 << DECAY_LIMB_BITS) - 1));
+        let (carry, low_remainder) = ((low * retention) >> DECAY_LIMB_BITS, (low * retention) & ((1 << DECAY_LIMB_BITS) - 1));
+        let shift = Q16::FRAC_BITS as usize - DECAY_LIMB_BITS;
+        let (quotient, high_remainder) = ((high * retention + carry) >> shift, (high * retention + carry) & ((1 << shift) - 1));
+        let parts = [high, low, carry, low_remainder, quotient, high_remainder];
+        let floor = self.factor.floor as u64;
+        let lower = floor.min(score as u64);
+        let flags = [(score as u64 >= floor) as u64, (quotient >= lower) as u64];
+        for row in 0..trace.height {
+            for (i, part) in parts.iter().enumerate() {
+                trace.set(row, self.column_offset + i, BabyBearField::new(*part));
+            }
+            if self.factor.floor > 0 {
+                trace.set(row, self.column_offset + 18, BabyBearField::new(flags[0]));
+                trace.set(row, self.column_offset + 19, BabyBearField::new(lower));
+                trace.set(row, self.column_offset + 20, BabyBearField::new(flags[1]));
+                trace.set(row, self.output_column(), BabyBearField::new(quotient.max(lower)));
+            }
+        }
+        for (range, part) in self.part_ranges().iter().zip(parts) {
+            range.fill(trace, part);
+        }
+        if self.factor.floor > 0 {
+            let (score, floor) = (BabyBearField::from_u32(score), BabyBearField::from_u32(self.factor.floor));
+            let (quotient, lower) = (BabyBearField::new(quotient), BabyBearField::new(lower));
+            let [reached, kept] = flags.map(BabyBearField::new);
+            self.reached_range().fill(trace, at_least(reached, score, floor).0);
+            self.kept_range().fill(trace, at_least(kept, quotient, lower).0);
+        }
+    }
+
+    /// Add the decay constraints, labelled with the prefix `name`
+    pub fn constrain(&self, system: &mut ConstraintSystem, name: &str) {
+        let one = BabyBearField::ONE;
+        let cell = |i: usize| Expr::cell(self.column_offset + i);
+        let [high, low, carry, low_remainder, quotient, high_remainder] = [0, 1, 2, 3, 4, 5].map(cell);
+        let score = Expr::cell(self.score_column);
+        let retention = BabyBearField::new(self.factor.retention.raw() as u64);
+        let limb = BabyBearField::new(1 << DECAY_LIMB_BITS);
+        let shift = BabyBearField::new(1 << (Q16::FRAC_BITS as usize - DECAY_LIMB_BITS));
+
+        system.constrain(format!("{}_limbs", name), &score - (&high * limb + &low));
+        system.constrain(format!("{}_low_product", name), &low * retention - (&carry * limb + &low_remainder));
+        system.constrain(format!("{}_high_product", name), &high * retention + &carry - (&quotient * shift + &high_remainder));
+        let parts = [&high, &low, &carry, &low_remainder, &quotient, &high_remainder];
+        let part_names = ["high", "low", "carry", "low_remainder", "quotient", "high_remainder"];
+        for ((range, part), part_name) in self.part_ranges().iter().zip(parts).zip(part_names) {
+            range.constrain(system, &format!("{}_{}_range", name, part_name), part.clone());
+        }
+
+        if self.factor.floor > 0 {
+            let floor = Expr::constant(BabyBearField::from_u32(self.factor.floor));
+            let [reached, lower, kept] = [18, 19, 20].map(cell);
+            let output = Expr::cell(self.output_column());
+            system.constrain(format!("{}_reached_boolean", name), &reached * (&reached - one));
+            system.constrain(format!("{}_lower", name), &lower - (&reached * &floor + (one - &reached) * &score));
+            system.constrain(format!("{}_kept_boolean", name), &kept * (&kept - one));
+            system.constrain(format!("{}_floor", name), output - (&kept * &quotient + (one - &kept) * &lower));
+            self.reached_range().constrain(system, &format!("{}_reached", name), at_least(reached, score, floor));
+            self.kept_range().constrain(system, &format!("{}_kept", name), at_least(kept, quotient, lower));
+        }
+    }
+}
+
+/// Decay of a threshold section's leading score columns
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThresholdDecay {
+    /// Factor of each decayed category column; the columns after them are penalties
+    pub factors: Vec<DecayFactor>,
+    /// Floor the decayed category total is raised to
+    pub min_threshold: u32,
+}
+
+/// Threshold verification AIR
+///
+/// Column layout: 0 threshold, 1 time_window, 2 timestamp, 3..3+n category
+/// scores (penalties included as negatives), 3+n final_score, 4+n
+/// meets_threshold, 5+n validity flag, 6+n..6+2n running score totals, then
+/// range checks on every shifted running total, the shifted final score and
+/// the threshold comparison. With decay, a `DecayGadget` per category column
+/// derives its decayed score, followed by the flag keeping their total above
+/// the floor and its range check. A private threshold ends the layout with
+/// the range check on the threshold.
+///
+/// Every row repeats the same values; the range checks need `ROWS` of them.
+#[derive(Debug, Clone)]
+pub struct ThresholdAir {
+    pub num_scores: usize,
+    pub threshold: u32,
+    pub time_window: u64,
+    /// Evaluation instant, public alongside threshold and time_window
+    pub timestamp: u64,
+    /// Decay makes final_score the floored total of decayed category scores
+    pub decay: Option<ThresholdDecay>,
+    /// Compare against the witness in column 0 instead of `threshold`
+    pub private_threshold: bool,
+}
+
+impl ThresholdAir {
+    /// Trace height carrying the range checks
+    pub const ROWS: usize = (AGGREGATE_RANGE_BITS + 1).next_power_of_two();
+
+    pub fn new(num_scores: usize, threshold: u32, time_window: u64, timestamp: u64, decay: Option<ThresholdDecay>) -> Self {
+        Self {
+            num_scores,
+            threshold,
+            time_window,
+            timestamp,
+            decay,
+            private_threshold: false,
+        }
+    }
+
+    /// Leave the threshold a range-checked witness, for sections proving a hidden cutoff
+    pub fn with_private_threshold(mut self) -> Self {
+        self.private_threshold = true;
+        self
+    }
+
+    pub fn score_column(&self, index: usize) -> usize {
+        3 + index
+    }
+
+    pub fn final_score_column(&self) -> usize {
+        3 + self.num_scores
+    }
+
+    pub fn meets_threshold_column(&self) -> usize {
+        4 + self.num_scores
+    }
+
+    pub fn validity_column(&self) -> usize {
+        5 + self.num_scores
+    }
+
+    /// Total of score columns `0..=index`
+    pub fn running_sum_column(&self, index: usize) -> usize {
+        6 + self.num_scores + index
+    }
+
+    /// Range check on running total `index` plus `MAX_AGGREGATE_SCORE`
+    pub fn running_sum_range(&self, index: usize) -> RangeCheck {
+        RangeCheck::new(6 + 2 * self.num_scores + 2 * index, AGGREGATE_RANGE_BITS)
+    }
+
+    /// Range check on the final score plus `MAX_AGGREGATE_SCORE`
+    pub fn final_score_range(&self) -> RangeCheck {
+        RangeCheck::new(6 + 4 * self.num_scores, AGGREGATE_RANGE_BITS)
+    }
+
+    /// Range check proving meets_threshold against the threshold
+    pub fn comparison_range(&self) -> RangeCheck {
+        RangeCheck::new(8 + 4 * self.num_scores, AGGREGATE_RANGE_BITS)
+    }
+
+    /// Decay of every category column, laid out after the comparison
+    pub fn decay_gadgets(&self) -> Vec<DecayGadget> {
+        let mut column_offset = 10 + 4 * self.num_scores;
+        self.decay.iter()
+            .flat_map(|decay| decay.factors.iter().enumerate())
+            .map(|(i, factor)| {
+                let gadget = DecayGadget::new(self.score_column(i), column_offset, *factor);
+                column_offset += gadget.width();
+                gadget
+            })
+            .collect()
+    }
+
+    /// Decayed score of category `index`
+    pub fn decayed_column(&self, index: usize) -> usize {
+        self.decay_gadgets()[index].output_column()
+    }
+
+    /// 1 when the decayed category total reaches the floor, 0 when raised to it
+    pub fn floor_flag_column(&self) -> usize {
+        10 + 4 * self.num_scores + self.decay_gadgets().iter().map(DecayGadget::width).sum::<usize>()
+    }
+
+    /// Range check proving the floor flag
+    pub fn floor_range(&self) -> RangeCheck {
+        RangeCheck::new(self.floor_flag_column() + 1, AGGREGATE_RANGE_BITS)
+    }
+
+    /// Range check keeping a private threshold a nonnegative score
+    pub fn threshold_range(&self) -> RangeCheck {
+        let public_width = match &self.decay {
+            Some(_) => self.floor_flag_column() + 3,
+            None => 10 + 4 * self.num_scores,
+        };
+        RangeCheck::new(public_width, SCORE_RANGE_BITS)
+    }
+
+    fn decayed_categories(&self) -> usize {
+        self.decay.as_ref().map_or(0, |decay| decay.factors.len())
+    }
+
+    /// Columns holding one value repeated on every row, with their names
+    fn row_invariants(&self) -> Vec<(usize, String)> {
+        let mut columns: Vec<(usize, String)> = Vec::new();
+        if self.private_threshold {
+            columns.push((0, "threshold".to_string()));
+        }
+        columns.extend((0..self.num_scores).map(|i| (self.score_column(i), format!("score_{}", i))));
+        columns.push((self.final_score_column(), "final_score".to_string()));
+        columns.push((self.meets_threshold_column(), "meets_threshold".to_string()));
+        columns.extend((0..self.num_scores).map(|i| (self.running_sum_column(i), format!("running_sum_{}", i))));
+        if self.decay.is_some() {
+            for (i, gadget) in self.decay_gadgets().iter().enumerate() {
+                columns.extend(gadget.data_columns().into_iter().map(|column| {
+                    (column, format!("decay_{}_column_{}", i, column - gadget.column_offset))
+                }));
+            }
+            columns.push((self.floor_flag_column(), "floor_flag".to_string()));
+        }
+        columns
+    }
+
+    /// Write every decay gadget and range check from the values on the first row
+    ///
+    /// Decayed category scores must be below `MAX_DECAYED_SCORE`.
+    pub fn fill_gadgets(&self, trace: &mut ExecutionTrace) {
+        for gadget in self.decay_gadgets() {
+            let score = trace.get(0, gadget.score_column).0 as u32;
+            gadget.fill(trace, score);
+        }
+        let offset = BabyBearField::new(MAX_AGGREGATE_SCORE);
+        for i in 0..self.num_scores {
+            let running_sum = trace.get(0, self.running_sum_column(i));
+            self.running_sum_range(i).fill(trace, (running_sum + offset).0);
+        }
+        let final_score = trace.get(0, self.final_score_column());
+        self.final_score_range().fill(trace, (final_score + offset).0);
+        let meets_threshold = trace.get(0, self.meets_threshold_column());
+        let distance = at_least(meets_threshold, final_score, trace.get(0, 0));
+        self.comparison_range().fill(trace, distance.0);
+        if let Some(decay) = &self.decay {
+            let kept = trace.get(0, self.floor_flag_column());
+            let total = self
+                .decay_gadgets()
+                .iter()
+                .fold(BabyBearField::ZERO, |acc, gadget| acc + trace.get(0, gadget.output_column()));
+            let distance = at_least(kept, total, BabyBearField::from_u32(decay.min_threshold));
+            self.floor_range().fill(trace, distance.0);
+        }
+        if self.private_threshold {
+            self.threshold_range().fill(trace, trace.get(0, 0).0);
+        }
+    }
+}
+
+impl CustomAir for ThresholdAir {
+    fn width(&self) -> usize {
+        let range = self.threshold_range();
+        match self.private_threshold {
+            true => range.bit_column + RangeCheck::COLUMNS,
+            false => range.bit_column,
+        }
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let one = BabyBearField::ONE;
+        let cell = Expr::cell;
+        let offset = BabyBearField::new(MAX_AGGREGATE_SCORE);
+        let final_score = cell(self.final_score_column());
+        let meets_threshold = cell(self.meets_threshold_column());
+        let threshold = match self.private_threshold {
+            true => cell(0),
+            false => Expr::constant(BabyBearField::from_u32(self.threshold)),
+        };
+
+        // Without decay the final score sums every column; with it the decayed
+        // categories, raised to the floor, replace the category columns
+        let penalty_sum: Expr = (self.decayed_categories()..self.num_scores).map(|i| cell(self.score_column(i))).sum();
+        let floor = self.decay.as_ref().map(|decay| {
+            let kept = cell(self.floor_flag_column());
+            let total: Expr = self.decay_gadgets().iter().map(|gadget| cell(gadget.output_column())).sum();
+            let min_threshold = Expr::constant(BabyBearField::from_u32(decay.min_threshold));
+            let floored = &kept * &total + (one - &kept) * &min_threshold;
+            (kept, total, min_threshold, floored)
+        });
+        let category_sum = match &floor {
+            Some((_, _, _, floored)) => floored.clone(),
+            None => Expr::constant(BabyBearField::ZERO),
+        };
+
+        if !self.private_threshold {
+            system.constrain("threshold_consistency", cell(0) - &threshold);
+        }
+        system.constrain("time_window_consistency", cell(1) - BabyBearField::new(self.time_window));
+        system.constrain("meets_threshold_boolean", &meets_threshold * (&meets_threshold - one));
+        system.constrain("final_score_sum", &final_score - (category_sum + penalty_sum));
+        system.constrain("validity_flag", cell(self.validity_column()) - one);
+        system.constrain("timestamp_consistency", cell(2) - BabyBearField::new(self.timestamp));
+
+        // Each running total adds one score column
+        let mut previous = Expr::constant(BabyBearField::ZERO);
+        for i in 0..self.num_scores {
+            let running_sum = cell(self.running_sum_column(i));
+            system.constrain(format!("running_sum_{}", i), &running_sum - (previous + cell(self.score_column(i))));
+            previous = running_sum;
+        }
+
+        // Every row repeats the previous one, wrapping around, so the checks on
+        // the last row bind all of them
+        for (column, name) in self.row_invariants() {
+            system.wire(format!("{}_constant", name), column);
+        }
+
+        // Running totals and the final score stay within the aggregate bounds,
+        // and the comparison distance proves meets_threshold
+        for i in 0..self.num_scores {
+            let running_sum = cell(self.running_sum_column(i));
+            self.running_sum_range(i).constrain(system, &format!("running_sum_{}_range", i), running_sum + offset);
+        }
+        self.final_score_range().constrain(system, "final_score_range", &final_score + offset);
+        self.comparison_range()
+            .constrain(system, "meets_threshold_correctness", at_least(meets_threshold, final_score, threshold));
+
+        if let Some((kept, total, min_threshold, _)) = floor {
+            system.constrain("floor_flag_boolean", &kept * (&kept - one));
+            self.floor_range().constrain(system, "decay_floor", at_least(kept, total, min_threshold));
+            for (i, gadget) in self.decay_gadgets().iter().enumerate() {
+                gadget.constrain(system, &format!("decay_{}", i));
+            }
+        }
+        if self.private_threshold {
+            self.threshold_range().constrain(system, "threshold_range", cell(0));
+        }
+    }
+}
+
+/// Category count AIR: at least `k` categories each reach `min_per_category`
+///
+/// Column layout: 0 min_per_category, 1 k, 2 timestamp, 3..3+n category
+/// scores, 3+n..3+2n reached flags, 3+2n count of reached floors, 4+2n
+/// validity flag, then per score a range check on the score and one proving
+/// its flag, and last the range check on the count's margin over `k`.
+#[derive(Debug, Clone)]
+pub struct CategoryCountAir {
+    pub num_scores: usize,
+    pub min_per_category: u32,
+    pub k: u32,
+    pub timestamp: u64,
+}
+
+impl CategoryCountAir {
+    /// Trace height carrying the range checks
+    pub const ROWS: usize = ThresholdAir::ROWS;
+
+    pub fn new(num_scores: usize, min_per_category: u32, k: u32, timestamp: u64) -> Self {
+        Self {
+            num_scores,
+            min_per_category,
+            k,
+            timestamp,
+        }
+    }
+
+    pub fn score_column(&self, index: usize) -> usize {
+        3 + index
+    }
+
+    /// 1 when score `index` reaches the floor
+    pub fn reached_column(&self, index: usize) -> usize {
+        3 + self.num_scores + index
+    }
+
+    pub fn count_column(&self) -> usize {
+        3 + 2 * self.num_scores
+    }
+
+    pub fn validity_column(&self) -> usize {
+        4 + 2 * self.num_scores
+    }
+
+    /// Range check keeping score `index` a nonnegative score
+    pub fn score_range(&self, index: usize) -> RangeCheck {
+        RangeCheck::new(5 + 2 * self.num_scores + 4 * index, SCORE_RANGE_BITS)
+    }
+
+    /// Range check proving reached flag `index` against the floor
+    pub fn reached_range(&self, index: usize) -> RangeCheck {
+        RangeCheck::new(7 + 2 * self.num_scores + 4 * index, AGGREGATE_RANGE_BITS)
+    }
+
+    /// Range check on `count - k`, at most the number of scores
+    pub fn count_range(&self) -> RangeCheck {
+        let bits = (usize::BITS - self.num_scores.leading_zeros()) as usize;
+        RangeCheck::new(5 + 6 * self.num_scores, bits)
+    }
+
+    /// Write the range checks from the scores, flags and count on the first row
+    pub fn fill_gadgets(&self, trace: &mut ExecutionTrace) {
+        let min = BabyBearField::from_u32(self.min_per_category);
+        for i in 0..self.num_scores {
+            let score = trace.get(0, self.score_column(i));
+            let reached = trace.get(0, self.reached_column(i));
+            self.score_range(i).fill(trace, score.0);
+            self.reached_range(i).fill(trace, at_least(reached, score, min).0);
+        }
+        let count = trace.get(0, self.count_column());
+        self.count_range().fill(trace, (count - BabyBearField::from_u32(self.k)).0);
+    }
+}
+
+impl CustomAir for CategoryCountAir {
+    fn width(&self) -> usize {
+        7 + 6 * self.num_scores
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let one = BabyBearField::ONE;
+        let cell = Expr::cell;
+        let min = Expr::constant(BabyBearField::from_u32(self.min_per_category));
+        let count = cell(self.count_column());
+        let flag_sum: Expr = (0..self.num_scores).map(|i| cell(self.reached_column(i))).sum();
+
+        system.constrain("min_per_category_consistency", cell(0) - &min);
+        system.constrain("k_consistency", cell(1) - BabyBearField::from_u32(self.k));
+        system.constrain("timestamp_consistency", cell(2) - BabyBearField::new(self.timestamp));
+        system.constrain("count_sum", &count - flag_sum);
+        system.constrain("validity_flag", cell(self.validity_column()) - one);
+
+        // Each flag is boolean and set exactly when its score reaches the floor
+        for i in 0..self.num_scores {
+            let score = cell(self.score_column(i));
+            let reached = cell(self.reached_column(i));
+            system.constrain(format!("score_{}_reached_boolean", i), &reached * (&reached - one));
+            self.score_range(i).constrain(system, &format!("score_{}_range", i), score.clone());
+            self.reached_range(i)
+                .constrain(system, &format!("score_{}_reached_correctness", i), at_least(reached, score, min.clone()));
+        }
+        self.count_range().constrain(system, "count_reaches_k", count - BabyBearField::from_u32(self.k));
+    }
+}
+
+/// Score history AIR: one score event per row, accumulated down the trace
+///
+/// Column layout: 0 threshold, 1 time_window, 2 timestamp, 3 event time,
+/// 4 signed score delta, 5 active flag, 6 running total, 7 meets_threshold,
+/// then on every row the bits of the shifted total, of the event's offset
+/// into the window, of its distance to the timestamp and of its step from
+/// the previous event, and last the bits proving meets_threshold.
+/// Padding rows follow the events with the active flag cleared, a zero delta
+/// and the last event's time, so the last row carries the final total.
+#[derive(Debug, Clone)]
+pub struct ScoreHistoryAir {
+    pub threshold: u32,
+    pub time_window: u64,
+    /// Evaluation instant; events must fall within `time_window` before it
+    pub timestamp: u64,
+}
+
+impl ScoreHistoryAir {
+    pub const EVENT_TIME_COLUMN: usize = 3;
+    pub const SCORE_COLUMN: usize = 4;
+    pub const ACTIVE_COLUMN: usize = 5;
+    pub const TOTAL_COLUMN: usize = 6;
+    pub const MEETS_THRESHOLD_COLUMN: usize = 7;
+
+    /// Widest window, in seconds, whose offsets the bit decompositions order
+    pub const MAX_WINDOW: u64 = 1 << AGGREGATE_RANGE_BITS;
+
+    pub fn new(threshold: u32, time_window: u64, timestamp: u64) -> Self {
+        Self {
+            threshold,
+            time_window,
+            timestamp,
+        }
+    }
+
+    /// Earliest event time inside the window
+    pub fn window_start(&self) -> u64 {
+        self.timestamp.saturating_sub(self.time_window)
+    }
+
+    /// Seconds from the window start to the timestamp
+    pub fn window_length(&self) -> u64 {
+        self.timestamp - self.window_start()
+    }
+
+    /// Bits of an offset into the window
+    fn window_bits(&self) -> usize {
+        (u64::BITS - self.window_length().leading_zeros()) as usize
+    }
+
+    /// Bits of the running total plus `MAX_AGGREGATE_SCORE`
+    pub fn total_bits(&self) -> BitDecomposition {
+        BitDecomposition::new(8, AGGREGATE_RANGE_BITS)
+    }
+
+    /// Bits of the event time's offset from the window start
+    pub fn offset_bits(&self) -> BitDecomposition {
+        BitDecomposition::new(8 + AGGREGATE_RANGE_BITS, self.window_bits())
+    }
+
+    /// Bits of the timestamp's distance past the event time
+    pub fn remaining_bits(&self) -> BitDecomposition {
+        BitDecomposition::new(8 + AGGREGATE_RANGE_BITS + self.window_bits(), self.window_bits())
+    }
+
+    /// Bits of the event time's step past the previous event
+    pub fn step_bits(&self) -> BitDecomposition {
+        BitDecomposition::new(8 + AGGREGATE_RANGE_BITS + 2 * self.window_bits(), self.window_bits())
+    }
+
+    /// Bits proving meets_threshold on the last row
+    pub fn comparison_bits(&self) -> BitDecomposition {
+        BitDecomposition::new(8 + AGGREGATE_RANGE_BITS + 3 * self.window_bits(), AGGREGATE_RANGE_BITS)
+    }
+
+    /// Write every bit decomposition from the event, total and flag columns
+    pub fn fill_gadgets(&self, trace: &mut ExecutionTrace) {
+        let start = BabyBearField::new(self.window_start());
+        let length = BabyBearField::new(self.window_length());
+        let threshold = BabyBearField::from_u32(self.threshold);
+        for row in 0..trace.height {
+            let total = trace.get(row, Self::TOTAL_COLUMN);
+            let offset = trace.get(row, Self::EVENT_TIME_COLUMN) - start;
+            self.total_bits().fill(trace, row, (total + BabyBearField::new(MAX_AGGREGATE_SCORE)).0);
+            self.offset_bits().fill(trace, row, offset.0);
+            self.remaining_bits().fill(trace, row, (length - offset).0);
+            if row > 0 {
+                let previous = trace.get(row - 1, Self::EVENT_TIME_COLUMN);
+                self.step_bits().fill(trace, row, (trace.get(row, Self::EVENT_TIME_COLUMN) - previous).0);
+            }
+            let meets_threshold = trace.get(row, Self::MEETS_THRESHOLD_COLUMN);
+            self.comparison_bits().fill(trace, row, at_least(meets_threshold, total, threshold).0);
+        }
+    }
+}
+
+impl CustomAir for ScoreHistoryAir {
+    fn width(&self) -> usize {
+        self.comparison_bits().column_offset + AGGREGATE_RANGE_BITS
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let one = BabyBearField::ONE;
+        let cell = Expr::cell;
+        let first = system.first_row();
+        let last = system.last_row();
+        let not_first = one - &first;
+        let event_time = cell(Self::EVENT_TIME_COLUMN);
+        let score = cell(Self::SCORE_COLUMN);
+        let active = cell(Self::ACTIVE_COLUMN);
+        let total = cell(Self::TOTAL_COLUMN);
+        let meets_threshold = cell(Self::MEETS_THRESHOLD_COLUMN);
+        let threshold = Expr::constant(BabyBearField::from_u32(self.threshold));
+        let offset = &event_time - BabyBearField::new(self.window_start());
+
+        system.constrain("threshold_consistency", cell(0) - &threshold);
+        system.constrain("time_window_consistency", cell(1) - BabyBearField::new(self.time_window));
+        system.constrain("timestamp_consistency", cell(2) - BabyBearField::new(self.timestamp));
+
+        // Row 0 opens the history: at least one event, accumulated from zero,
+        // and an event row never follows a padding row
+        system.constrain("active_boolean", &active * (&active - one));
+        let previous_active = Expr::rotated(Self::ACTIVE_COLUMN, -1);
+        system.constrain("padding_after_events", &not_first * &active * (one - previous_active));
+        system.constrain("first_row_active", &first * (&active - one));
+        system.constrain("padding_score_zero", (one - &active) * &score);
+        let previous_total = Expr::rotated(Self::TOTAL_COLUMN, -1);
+        system.constrain("total_accumulation", &total - &not_first * previous_total - score);
+
+        // Totals stay within the aggregate bounds and events inside the window, in order
+        let always = Expr::constant(one);
+        self.total_bits().constrain(system, "total_range", always.clone(), &total + BabyBearField::new(MAX_AGGREGATE_SCORE));
+        self.offset_bits().constrain(system, "event_after_window_start", always.clone(), offset.clone());
+        let remaining = BabyBearField::new(self.window_length()) - &offset;
+        self.remaining_bits().constrain(system, "event_not_after_timestamp", always, remaining);
+        let step = &event_time - Expr::rotated(Self::EVENT_TIME_COLUMN, -1);
+        self.step_bits().constrain(system, "events_ordered", not_first, step);
+
+        // meets_threshold is constant down the trace and decided by the last row's total
+        system.constrain("meets_threshold_boolean", &meets_threshold * (&meets_threshold - one));
+        system.wire("meets_threshold_constant", Self::MEETS_THRESHOLD_COLUMN);
+        self.comparison_bits()
+            .constrain(system, "meets_threshold_correctness", last, at_least(meets_threshold, total, threshold));
+    }
+}
+
+/// Weighted score AIR in Q16.16, shared with `HierarchicalScorer`
+///
+/// Column layout from `column_offset`: 0..n scores, n..2n raw Q16
+/// contributions, 2n total, 2n+1 integer part, 2n+2 fractional remainder,
+/// then the remainder's bits.
+#[derive(Debug, Clone)]
+pub struct WeightedScoreAir {
+    pub column_offset: usize,
+    pub weights: Vec<Q16>,
+}
+
+impl WeightedScoreAir {
+    pub fn new(column_offset: usize, weights: Vec<Q16>) -> Self {
+        Self { column_offset, weights }
+    }
+
+    pub fn score_column(&self, index: usize) -> usize {
+        self.column_offset + index
+    }
+
+    pub fn contribution_column(&self, index: usize) -> usize {
+        self.column_offset + self.weights.len() + index
+    }
+
+    pub fn total_column(&self) -> usize {
+        self.column_offset + 2 * self.weights.len()
+    }
+
+    pub fn integer_column(&self) -> usize {
+        self.total_column() + 1
+    }
+
+    pub fn remainder_column(&self) -> usize {
+        self.total_column() + 2
+    }
+
+    /// Bits keeping the remainder below one
+    pub fn remainder_bits(&self) -> BitDecomposition {
+        BitDecomposition::new(self.total_column() + 3, Q16::FRAC_BITS as usize)
+    }
+
+    /// Single-row witness for `scores` (one per weight)
+    pub fn generate_trace(&self, scores: &[u32]) -> ExecutionTrace {
+        let mut trace = ExecutionTrace::new(self.width(), 1);
+        let mut total = Q16::ZERO;
+        for (i, (&score, weight)) in scores.iter().zip(&self.weights).enumerate() {
+            let contribution = weight.mul_int(score as i64);
+            trace.set(0, self.score_column(i), BabyBearField::from_u32(score));
+            trace.set(0, self.contribution_column(i), contribution.to_field());
+            total = total + contribution;
+        }
+        trace.set(0, self.total_column(), total.to_field());
+        trace.set(0, self.integer_column(), BabyBearField::from_i64(total.to_int()));
+        trace.set(0, self.remainder_column(), BabyBearField::new(total.frac() as u64));
+        self.remainder_bits().fill(&mut trace, 0, total.frac() as u64);
+        trace
+    }
+}
+
+impl CustomAir for WeightedScoreAir {
+    fn width(&self) -> usize {
+        self.remainder_bits().column_offset + Q16::FRAC_BITS as usize
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let cell = Expr::cell;
+        let mut sum = Expr::constant(BabyBearField::ZERO);
+        for (i, weight) in self.weights.iter().enumerate() {
+            let contribution = cell(self.contribution_column(i));
+            system.constrain(format!("contribution_{}", i), &contribution - cell(self.score_column(i)) * weight.to_field());
+            sum = sum + contribution;
+        }
+
+        let total = cell(self.total_column());
+        let remainder = cell(self.remainder_column());
+        let shift = BabyBearField::new(Q16::ONE.raw() as u64);
+        system.constrain("total_sum", &total - sum);
+        system.constrain("integer_decomposition", total - (cell(self.integer_column()) * shift + &remainder));
+
+        // Remainder bits keep the integer part a floor, not any decomposition
+        self.remainder_bits().constrain(system, "remainder_range", Expr::constant(BabyBearField::ONE), remainder);
+    }
+}
+
+/// Largest high limb of a limbed value, so `high * 2^16 + low` stays below the modulus
+pub const MAX_TIMESTAMP_HIGH: u64 = (BabyBearField::MODULUS >> 16) - 1;
+
+/// Bits in each limb of a limbed value
+const TIME_LIMB_BITS: usize = 16;
+
+/// Split of a value below the modulus into 16-bit limbs, for comparisons past 2^29
+///
+/// Column layout from `column_offset`: 0 low limb, 1 high limb, then range
+/// checks on the low limb, the high limb and the high limb's room below
+/// `MAX_TIMESTAMP_HIGH`, which keeps the split unique.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampLimbs {
+    pub column_offset: usize,
+}
+
+impl TimestampLimbs {
+    pub const COLUMNS: usize = 2 + 3 * RangeCheck::COLUMNS;
+
+    pub fn new(column_offset: usize) -> Self {
+        Self { column_offset }
+    }
+
+    /// Limbs of a public value, clamped to the largest limbed value
+    pub fn constant(value: u64) -> [BabyBearField; 2] {
+        let value = value.min((MAX_TIMESTAMP_HIGH << TIME_LIMB_BITS) | 0xffff);
+        [value & 0xffff, value >> TIME_LIMB_BITS].map(BabyBearField::new)
+    }
+
+    fn ranges(&self) -> [RangeCheck; 3] {
+        [2, 4, 6].map(|i| RangeCheck::new(self.column_offset + i, TIME_LIMB_BITS))
+    }
+
+    /// Write the limbs of `value` on every row and return them
+    pub fn fill(&self, trace: &mut ExecutionTrace, value: u64) -> [BabyBearField; 2] {
+        let limbs = [value & 0xffff, value >> TIME_LIMB_BITS];
+        for row in 0..trace.height {
+            trace.set(row, self.column_offset, BabyBearField::new(limbs[0]));
+            trace.set(row, self.column_offset + 1, BabyBearField::new(limbs[1]));
+        }
+        let [low, high, room] = self.ranges();
+        low.fill(trace, limbs[0]);
+        high.fill(trace, limbs[1]);
+        room.fill(trace, (BabyBearField::new(MAX_TIMESTAMP_HIGH) - BabyBearField::new(limbs[1])).0);
+        limbs.map(BabyBearField::new)
+    }
+
+    /// Split `value` into the limbs, labelling the constraints with the prefix `name`
+    pub fn constrain(&self, system: &mut ConstraintSystem, name: &str, value: Expr) -> [Expr; 2] {
+        let [low, high] = [0, 1].map(|i| Expr::cell(self.column_offset + i));
+        let shift = BabyBearField::new(1 << TIME_LIMB_BITS);
+        system.constrain(format!("{}_limbs", name), value - (&high * shift + &low));
+        let [low_range, high_range, room] = self.ranges();
+        low_range.constrain(system, &format!("{}_low", name), low.clone());
+        high_range.constrain(system, &format!("{}_high", name), high.clone());
+        room.constrain(system, &format!("{}_high_bound", name), BabyBearField::new(MAX_TIMESTAMP_HIGH) - &high);
+        [low, high]
+    }
+}
+
+/// Proof that one limbed value is at most another
+///
+/// Subtracts limb by limb with a borrow flag and range-checks both limbs of
+/// the difference, which wraps around the field when the order is wrong.
+/// Column layout from `column_offset`: 0 borrow, then the range checks on the
+/// difference's low and high limbs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimbComparison {
+    pub column_offset: usize,
+}
+
+impl LimbComparison {
+    pub const COLUMNS: usize = 1 + 2 * RangeCheck::COLUMNS;
+
+    pub fn new(column_offset: usize) -> Self {
+        Self { column_offset }
+    }
+
+    fn ranges(&self) -> [RangeCheck; 2] {
+        [1, 3].map(|i| RangeCheck::new(self.column_offset + i, TIME_LIMB_BITS))
+    }
+
+    fn difference<T: Arithmetic>(borrow: T, smaller: [T; 2], larger: [T; 2]) -> [T; 2] {
+        let [a_low, a_high] = smaller;
+        let [b_low, b_high] = larger;
+        let shift = T::from(BabyBearField::new(1 << TIME_LIMB_BITS));
+        [b_low - a_low + borrow.clone() * shift, b_high - a_high - borrow]
+    }
+
+    /// Write the comparison of `smaller <= larger` and return the limbs of their difference
+    pub fn fill(&self, trace: &mut ExecutionTrace, smaller: [BabyBearField; 2], larger: [BabyBearField; 2]) -> [BabyBearField; 2] {
+        let borrow = BabyBearField::new((larger[0].0 < smaller[0].0) as u64);
+        for row in 0..trace.height {
+            trace.set(row, self.column_offset, borrow);
+        }
+        let difference = Self::difference(borrow, smaller, larger);
+        for (range, limb) in self.ranges().iter().zip(difference) {
+            range.fill(trace, limb.0);
+        }
+        difference
+    }
+
+    /// Prove `smaller <= larger`, labelled `name`, and return the limbs of their difference
+    pub fn constrain(&self, system: &mut ConstraintSystem, name: &str, smaller: [Expr; 2], larger: [Expr; 2]) -> [Expr; 2] {
+        let borrow = Expr::cell(self.column_offset);
+        system.constrain(format!("{}_borrow", name), &borrow * (&borrow - BabyBearField::ONE));
+        let difference = Self::difference(borrow, smaller, larger);
+        let [low, high] = self.ranges();
+        low.constrain(system, &format!("{}_low_limb", name), difference[0].clone());
+        high.constrain(system, name, difference[1].clone());
+        difference
+    }
+}
+
+/// Attestation freshness AIR, evaluated over columns appended to another section
+///
+/// Column layout from `column_offset`: 0 now, 1 max_age, 2..2+n issued_at,
+/// 2+n..2+2n age of each attestation, then per attestation the limbs of its
+/// issuance time and the comparisons proving it is not after `now` and its
+/// age is at most `max_age`.
+#[derive(Debug, Clone)]
+pub struct FreshnessAir {
+    pub column_offset: usize,
+    pub num_attestations: usize,
+    pub now: u64,
+    pub max_age: u64,
+}
+
+impl FreshnessAir {
+    /// Columns of one attestation's limbs and comparisons
+    const ATTESTATION_COLUMNS: usize = TimestampLimbs::COLUMNS + 2 * LimbComparison::COLUMNS;
+
+    pub fn new(column_offset: usize, num_attestations: usize, now: u64, max_age: u64) -> Self {
+        Self {
+            column_offset,
+            num_attestations,
+            now,
+            max_age,
+        }
+    }
+
+    pub fn issued_column(&self, index: usize) -> usize {
+        self.column_offset + 2 + index
+    }
+
+    pub fn age_column(&self, index: usize) -> usize {
+        self.column_offset + 2 + self.num_attestations + index
+    }
+
+    fn gadgets(&self, index: usize) -> (TimestampLimbs, LimbComparison, LimbComparison) {
+        let offset = self.column_offset + 2 + 2 * self.num_attestations + index * Self::ATTESTATION_COLUMNS;
+        (
+            TimestampLimbs::new(offset),
+            LimbComparison::new(offset + TimestampLimbs::COLUMNS),
+            LimbComparison::new(offset + TimestampLimbs::COLUMNS + LimbComparison::COLUMNS),
+        )
+    }
+
+    /// Write `now`, `max_age` and each attestation's issuance time, age and comparisons on every row
+    pub fn fill(&self, trace: &mut ExecutionTrace, issued_at: &[u64]) {
+        let now = BabyBearField::new(self.now);
+        for row in 0..trace.height {
+            trace.set(row, self.column_offset, now);
+            trace.set(row, self.column_offset + 1, BabyBearField::new(self.max_age));
+            for (i, &issued) in issued_at.iter().enumerate() {
+                let issued = BabyBearField::new(issued);
+                trace.set(row, self.issued_column(i), issued);
+                trace.set(row, self.age_column(i), now - issued);
+            }
+        }
+        for (i, &issued) in issued_at.iter().enumerate() {
+            let (limbs, not_future, fresh) = self.gadgets(i);
+            let issued = limbs.fill(trace, issued);
+            let age = not_future.fill(trace, issued, TimestampLimbs::constant(self.now));
+            fresh.fill(trace, age, TimestampLimbs::constant(self.max_age));
+        }
+    }
+}
+
+impl CustomAir for FreshnessAir {
+    fn width(&self) -> usize {
+        self.gadgets(self.num_attestations).0.column_offset
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let now = Expr::cell(self.column_offset);
+        let max_age = Expr::cell(self.column_offset + 1);
+        system.constrain("now_consistency", &now - BabyBearField::new(self.now));
+        system.constrain("max_age_consistency", max_age - BabyBearField::new(self.max_age));
+
+        let public_limbs = |value: u64| TimestampLimbs::constant(value).map(Expr::constant);
+        for i in 0..self.num_attestations {
+            let issued = Expr::cell(self.issued_column(i));
+            system.constrain(format!("attestation_{}_age", i), Expr::cell(self.age_column(i)) - (&now - &issued));
+
+            // Future-dated attestations wrap the first difference around the field
+            let (limbs, not_future, fresh) = self.gadgets(i);
+            let issued = limbs.constrain(system, &format!("attestation_{}_issued", i), issued);
+            let age = not_future.constrain(system, &format!("attestation_{}_not_future", i), issued, public_limbs(self.now));
+            fresh.constrain(system, &format!("attestation_{}_fresh", i), age, public_limbs(self.max_age));
+        }
+    }
+}
+
+/// Timestamp predicate AIR, evaluated over columns appended to another section
+///
+/// Column layout from `column_offset`: the private timestamp each predicate
+/// constrains, in predicate order, then per predicate the timestamp's limbs
+/// and the one or two comparisons the predicate is made of.
+#[derive(Debug, Clone)]
+pub struct TimePredicateAir {
+    pub column_offset: usize,
+    pub predicates: Vec<TimePredicate>,
+}
+
+impl TimePredicateAir {
+    /// Columns of one predicate's limbs and comparisons
+    const PREDICATE_COLUMNS: usize = TimestampLimbs::COLUMNS + 2 * LimbComparison::COLUMNS;
+
+    pub fn new(column_offset: usize, predicates: Vec<TimePredicate>) -> Self {
+        Self { column_offset, predicates }
+    }
+
+    pub fn timestamp_column(&self, index: usize) -> usize {
+        self.column_offset + index
+    }
+
+    fn gadgets(&self, index: usize) -> (TimestampLimbs, [LimbComparison; 2]) {
+        let offset = self.column_offset + self.predicates.len() + index * Self::PREDICATE_COLUMNS;
+        let comparison = |i: usize| LimbComparison::new(offset + TimestampLimbs::COLUMNS + i * LimbComparison::COLUMNS);
+        (TimestampLimbs::new(offset), [comparison(0), comparison(1)])
+    }
+
+    /// Write `timestamps` (one per predicate) and their comparisons into every row of `trace`
+    pub fn fill(&self, trace: &mut ExecutionTrace, timestamps: &[u64]) {
+        for row in 0..trace.height {
+            for (i, &timestamp) in timestamps.iter().enumerate() {
+                trace.set(row, self.timestamp_column(i), BabyBearField::new(timestamp));
+            }
+        }
+        for (i, (predicate, &timestamp)) in self.predicates.iter().zip(timestamps).enumerate() {
+            let (limbs, [first, second]) = self.gadgets(i);
+            let time = limbs.fill(trace, timestamp);
+            let zero = [BabyBearField::ZERO; 2];
+            let constant = TimestampLimbs::constant;
+            match *predicate {
+                TimePredicate::Before { bound } => {
+                    first.fill(trace, time, constant(bound));
+                    second.fill(trace, zero, zero);
+                }
+                TimePredicate::After { bound } => {
+                    first.fill(trace, constant(bound), time);
+                    second.fill(trace, zero, zero);
+                }
+                TimePredicate::WithinRange { start, end } => {
+                    first.fill(trace, constant(start), time);
+                    second.fill(trace, time, constant(end));
+                }
+                TimePredicate::ElapsedAtLeast { now, min_secs } => {
+                    let elapsed = first.fill(trace, time, constant(now));
+                    second.fill(trace, constant(min_secs), elapsed);
+                }
+                TimePredicate::ElapsedAtMost { now, max_secs } => {
+                    let elapsed = first.fill(trace, time, constant(now));
+                    second.fill(trace, elapsed, constant(max_secs));
+                }
+            }
+        }
+    }
+}
+
+impl CustomAir for TimePredicateAir {
+    fn width(&self) -> usize {
+        self.gadgets(self.predicates.len()).0.column_offset
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let constant = |value: u64| TimestampLimbs::constant(value).map(Expr::constant);
+        let zero = || [0, 0].map(|_| Expr::constant(BabyBearField::ZERO));
+        for (i, predicate) in self.predicates.iter().enumerate() {
+            let name = format!("timestamp_{}_{}", i, predicate.name());
+            let (limbs, [first, second]) = self.gadgets(i);
+            let time = limbs.constrain(system, &format!("timestamp_{}", i), Expr::cell(self.timestamp_column(i)));
+            match *predicate {
+                TimePredicate::Before { bound } => {
+                    first.constrain(system, &name, time, constant(bound));
+                    second.constrain(system, &name, zero(), zero());
+                }
+                TimePredicate::After { bound } => {
+                    first.constrain(system, &name, constant(bound), time);
+                    second.constrain(system, &name, zero(), zero());
+                }
+                TimePredicate::WithinRange { start, end } => {
+                    first.constrain(system, &name, constant(start), time.clone());
+                    second.constrain(system, &name, time, constant(end));
+                }
+                TimePredicate::ElapsedAtLeast { now, min_secs } => {
+                    let elapsed = first.constrain(system, &name, time, constant(now));
+                    second.constrain(system, &name, constant(min_secs), elapsed);
+                }
+                TimePredicate::ElapsedAtMost { now, max_secs } => {
+                    let elapsed = first.constrain(system, &name, time, constant(now));
+                    second.constrain(system, &name, elapsed, constant(max_secs));
+                }
+            }
+        }
+    }
+}
+
+/// Equalities and range checks of a gadget block over its witness parts
+///
+/// Written once over `Arithmetic` so the witness fill (over field values) and
+/// the constraints (over `Expr`s) cannot drift apart. A block lays out its
+/// parts from its offset, then one `RangeCheck` per range.
+struct Relations<T> {
+    /// (label suffix, value that must vanish)
+    zero: Vec<(String, T)>,
+    /// (label suffix, value, bits it must fit in)
+    ranges: Vec<(String, T, usize)>,
+    /// Value the block computes
+    output: T,
+}
+
+impl<T: Arithmetic> Relations<T> {
+    fn new(output: T) -> Self {
+        Self {
+            zero: Vec::new(),
+            ranges: Vec::new(),
+            output,
+        }
+    }
+
+    fn vanish(&mut self, label: impl Into<String>, value: T) {
+        self.zero.push((label.into(), value));
+    }
+
+    fn range(&mut self, label: impl Into<String>, value: T, bits: usize) {
+        self.ranges.push((label.into(), value, bits));
+    }
+
+    fn boolean(&mut self, label: impl Into<String>, flag: &T) {
+        let one = T::from(BabyBearField::ONE);
+        self.vanish(label, flag.clone() * (flag.clone() - one));
+    }
+
+    /// Columns of a block with `parts` witness parts
+    fn width(&self, parts: usize) -> usize {
+        parts + RangeCheck::COLUMNS * self.ranges.len()
+    }
+}
+
+impl Relations<BabyBearField> {
+    /// Write `parts` on every row from `offset`, then the range checks after them
+    fn fill(&self, trace: &mut ExecutionTrace, offset: usize, parts: &[BabyBearField]) {
+        for row in 0..trace.height {
+            for (i, &part) in parts.iter().enumerate() {
+                trace.set(row, offset + i, part);
+            }
+        }
+        for (k, (_, value, bits)) in self.ranges.iter().enumerate() {
+            RangeCheck::new(offset + parts.len() + RangeCheck::COLUMNS * k, *bits).fill(trace, value.0);
+        }
+    }
+}
+
+impl Relations<Expr> {
+    /// Add every relation, labelled with the prefix `name`, for parts from `offset`
+    fn constrain(self, system: &mut ConstraintSystem, name: &str, offset: usize, parts: usize) {
+        for (label, value) in self.zero {
+            system.constrain(format!("{}_{}", name, label), value);
+        }
+        for (k, (label, value, bits)) in self.ranges.into_iter().enumerate() {
+            let range = RangeCheck::new(offset + parts + RangeCheck::COLUMNS * k, bits);
+            range.constrain(system, &format!("{}_{}", name, label), value);
+        }
+    }
+}
+
+/// Parts of `n` cells from `offset`
+fn part_cells(offset: usize, n: usize) -> Vec<Expr> {
+    (offset..offset + n).map(Expr::cell).collect()
+}
+
+/// Largest cap each curve is proven for, so its products stay below the modulus
+fn max_cap(curve: &SaturationCurve) -> u64 {
+    match curve {
+        SaturationCurve::HardCap => MAX_AGGREGATE_SCORE - 1,
+        SaturationCurve::Sqrt => (1 << 15) - 1,
+        SaturationCurve::Logistic { .. } => (1 << 13) - 1,
+    }
+}
+
+/// Largest logistic scale, so the scaled score's remainder fits ten bits
+const MAX_LOGISTIC_SCALE: u64 = 1 << 10;
+
+/// Q16.16 powers of e^-1 for each whole part below the cutoff
+fn exp_neg_table() -> [u64; EXP_NEG_CUTOFF as usize] {
+    let mut table = [1 << Q16::FRAC_BITS; EXP_NEG_CUTOFF as usize];
+    for whole in 1..table.len() {
+        table[whole] = (table[whole - 1] * E_INV_Q16) >> Q16::FRAC_BITS;
+    }
+    table
+}
+
+/// Category saturation AIR, evaluated over columns appended to a threshold section
+///
+/// Column layout from `column_offset`: the raw score behind each capped score
+/// column, then per cap a block recomputing its curve: a flag selecting
+/// `min(raw, cap)`, and for `Sqrt` the integer root of `min(raw, cap) * cap`
+/// with its two bounds. `Logistic` divides the score by the scale into whole
+/// and Q16 fractional parts, looks e^-whole up through a one-hot selector,
+/// runs `SaturationCurve`'s eight Taylor terms on the fraction with every
+/// division a range-checked remainder, and divides `cap * (1 - e)` by
+/// `1 + e`. Each capped column must equal its block's output.
+#[derive(Debug, Clone)]
+pub struct SaturationAir {
+    pub column_offset: usize,
+    /// (saturated score column, cap) per capped category
+    pub capped: Vec<(usize, CategoryCap)>,
+}
+
+impl SaturationAir {
+    pub fn new(column_offset: usize, capped: Vec<(usize, CategoryCap)>) -> Self {
+        Self { column_offset, capped }
+    }
+
+    pub fn raw_column(&self, index: usize) -> usize {
+        self.column_offset + index
+    }
+
+    /// Witness parts of a curve's block
+    fn parts(curve: &SaturationCurve) -> usize {
+        match curve {
+            SaturationCurve::HardCap => 1,
+            SaturationCurve::Sqrt => 2,
+            SaturationCurve::Logistic { .. } => 57,
+        }
+    }
+
+    /// First column of cap `index`'s block
+    fn block_offset(&self, index: usize) -> usize {
+        let zeros = |cap: &CategoryCap| vec![BabyBearField::ZERO; Self::parts(&cap.curve)];
+        self.column_offset
+            + self.capped.len()
+            + self.capped[..index]
+                .iter()
+                .map(|(_, cap)| Self::relations(cap, BabyBearField::ZERO, &zeros(cap)).width(Self::parts(&cap.curve)))
+                .sum::<usize>()
+    }
+
+    /// Reject caps whose curves this AIR cannot evaluate without wrapping
+    pub fn check_caps(&self) -> Result<(), ZKPError> {
+        for (_, cap) in &self.capped {
+            if cap.max_contribution as u64 > max_cap(&cap.curve) {
+                return Err(ZKPError::LimitExceeded {
+                    limit: "max_contribution".to_string(),
+                    actual: cap.max_contribution as u64,
+                    max: max_cap(&cap.curve),
+                });
+            }
+            if let SaturationCurve::Logistic { scale } = cap.curve {
+                if scale as u64 > MAX_LOGISTIC_SCALE {
+                    return Err(ZKPError::LimitExceeded {
+                        limit: "logistic_scale".to_string(),
+                        actual: scale as u64,
+                        max: MAX_LOGISTIC_SCALE,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn relations<T: Arithmetic>(cap: &CategoryCap, raw: T, parts: &[T]) -> Relations<T> {
+        let f = |value: u64| T::from(BabyBearField::new(value));
+        let one = f(1);
+        let limit = match cap.curve {
+            SaturationCurve::Logistic { scale } => EXP_NEG_CUTOFF * scale.max(1) as u64,
+            _ => cap.max_contribution as u64,
+        };
+
+        // Flag and selection of min(raw, limit) shared by every curve
+        let reached = parts[0].clone();
+        let bounded = reached.clone() * f(limit) + (one.clone() - reached.clone()) * raw.clone();
+        let mut relations = Relations::new(bounded.clone());
+        relations.boolean("reached_boolean", &reached);
+        relations.range("raw_range", raw.clone(), SCORE_RANGE_BITS);
+        relations.range("reached", at_least(reached, raw, f(limit)), AGGREGATE_RANGE_BITS);
+
+        match cap.curve {
+            SaturationCurve::HardCap => relations,
+            SaturationCurve::Sqrt => {
+                let root = parts[1].clone();
+                let gap = bounded * f(cap.max_contribution as u64) - root.clone() * root.clone();
+                relations.range("root", root.clone(), 15);
+                relations.range("root_low", gap.clone(), 16);
+                relations.range("root_high", root.clone() + root.clone() - gap, 16);
+                relations.output = root;
+                relations
+            }
+            SaturationCurve::Logistic { scale } => {
+                let scale = scale.max(1) as u64;
+                let q16 = f(1 << Q16::FRAC_BITS);
+                let byte = f(1 << 8);
+
+                // score * 2^16 = x * scale + remainder, x = whole * 2^16 + fraction
+                let remainder = parts[1].clone();
+                let selectors = &parts[2..19];
+                let whole = selectors.iter().enumerate().fold(f(0), |acc, (j, a)| acc + f(j as u64) * a.clone());
+                let (fraction_high, fraction_low) = (parts[19].clone(), parts[20].clone());
+                let fraction = fraction_high.clone() * byte.clone() + fraction_low.clone();
+                let x = whole * q16.clone() + fraction.clone();
+                relations.vanish("division", x * f(scale) + remainder.clone() - bounded * q16.clone());
+                relations.range("remainder", remainder.clone(), 10);
+                relations.range("remainder_upper", f(scale - 1) - remainder, 10);
+                for (j, selector) in selectors.iter().enumerate() {
+                    relations.boolean(format!("whole_{}_boolean", j), selector);
+                }
+                let selected = selectors.iter().fold(f(0), |acc, a| acc + a.clone());
+                relations.vanish("whole_one_hot", selected - one.clone());
+                relations.range("fraction_high", fraction_high.clone(), 8);
+                relations.range("fraction_low", fraction_low.clone(), 8);
+
+                // term_k = term_{k-1} * fraction / (2^16 * k), split at the fraction's byte
+                let mut term = fraction.clone();
+                let mut sum = q16.clone() - fraction;
+                for k in 2..=TAYLOR_TERMS {
+                    let base = 21 + 4 * (k as usize - 2);
+                    let [carry, low, rest, next] = [0, 1, 2, 3].map(|i| parts[base + i].clone());
+                    let divisor = (1 << 8) * k;
+                    relations.vanish(format!("term_{}_low", k), term.clone() * fraction_low.clone() - (carry.clone() * byte.clone() + low.clone()));
+                    relations.vanish(
+                        format!("term_{}", k),
+                        term * fraction_high.clone() + carry.clone() - (next.clone() * f(divisor) + rest.clone()),
+                    );
+                    relations.range(format!("term_{}_carry", k), carry, 16);
+                    relations.range(format!("term_{}_low_byte", k), low, 8);
+                    relations.range(format!("term_{}_remainder", k), rest.clone(), 11);
+                    relations.range(format!("term_{}_remainder_upper", k), f(divisor - 1) - rest, 11);
+                    relations.range(format!("term_{}_range", k), next.clone(), 16);
+                    sum = if k % 2 == 0 { sum + next.clone() } else { sum - next.clone() };
+                    term = next;
+                }
+                let (sum_high, sum_low) = (parts[49].clone(), parts[50].clone());
+                relations.vanish("series", sum - (sum_high.clone() * byte.clone() + sum_low.clone()));
+                relations.range("series_high", sum_high.clone(), 9);
+                relations.range("series_low", sum_low.clone(), 8);
+
+                // e = e^-whole * series / 2^16, split at the series' byte
+                let table = exp_neg_table();
+                let power = selectors.iter().zip(table).fold(f(0), |acc, (a, value)| acc + f(value) * a.clone());
+                let [carry, low, rest, e] = [51, 52, 53, 54].map(|i| parts[i].clone());
+                relations.vanish("exp_low", power.clone() * sum_low - (carry.clone() * byte.clone() + low.clone()));
+                relations.vanish("exp", power * sum_high + carry.clone() - (e.clone() * byte + rest.clone()));
+                relations.range("exp_carry", carry, 16);
+                relations.range("exp_low_byte", low, 8);
+                relations.range("exp_remainder", rest, 8);
+                relations.range("exp_range", e.clone(), 17);
+
+                // cap * (1 - e) = saturated * (1 + e) + remainder
+                let (rest, saturated) = (parts[55].clone(), parts[56].clone());
+                let cap_value = f(cap.max_contribution as u64);
+                relations.vanish(
+                    "ratio",
+                    cap_value * (q16.clone() - e.clone()) - (saturated.clone() * (q16.clone() + e.clone()) + rest.clone()),
+                );
+                relations.range("ratio_remainder", rest.clone(), 18);
+                relations.range("ratio_remainder_upper", q16 + e - one - rest, 18);
+                relations.range("ratio_range", saturated.clone(), 13);
+                relations.output = saturated;
+                relations
+            }
+        }
+    }
+
+    /// Witness parts of `cap`'s block at `raw`
+    fn witness(cap: &CategoryCap, raw: u64) -> Vec<u64> {
+        let limit = match cap.curve {
+            SaturationCurve::Logistic { scale } => EXP_NEG_CUTOFF * scale.max(1) as u64,
+            _ => cap.max_contribution as u64,
+        };
+        let bounded = raw.min(limit);
+        let mut parts = vec![(raw >= limit) as u64];
+        match cap.curve {
+            SaturationCurve::HardCap => {}
+            SaturationCurve::Sqrt => parts.push((bounded * cap.max_contribution as u64).isqrt()),
+            SaturationCurve::Logistic { scale } => {
+                let scale = scale.max(1) as u64;
+                let scaled = bounded << Q16::FRAC_BITS;
+                let (x, remainder) = (scaled / scale, scaled % scale);
+                let (whole, fraction) = (x >> Q16::FRAC_BITS, x & 0xffff);
+                parts.push(remainder);
+                parts.extend((0..=EXP_NEG_CUTOFF).map(|j| (j == whole) as u64));
+                parts.extend([fraction >> 8, fraction & 0xff]);
+
+                let mut term = fraction;
+                let mut sum = (1i64 << Q16::FRAC_BITS) - fraction as i64;
+                for k in 2..=TAYLOR_TERMS {
+                    let low_product = term * (fraction & 0xff);
+                    let (carry, low) = (low_product >> 8, low_product & 0xff);
+                    let high_product = term * (fraction >> 8) + carry;
+                    let next = high_product / ((1 << 8) * k);
+                    parts.extend([carry, low, high_product % ((1 << 8) * k), next]);
+                    sum += if k % 2 == 0 { next as i64 } else { -(next as i64) };
+                    term = next;
+                }
+                let sum = sum.max(0) as u64;
+                parts.extend([sum >> 8, sum & 0xff]);
+
+                let power = exp_neg_table().get(whole as usize).copied().unwrap_or(0);
+                let low_product = power * (sum & 0xff);
+                let (carry, low) = (low_product >> 8, low_product & 0xff);
+                let high_product = power * (sum >> 8) + carry;
+                let e = high_product >> 8;
+                parts.extend([carry, low, high_product & 0xff, e]);
+
+                let one = 1u64 << Q16::FRAC_BITS;
+                let numerator = cap.max_contribution as u64 * (one - e);
+                parts.extend([numerator % (one + e), numerator / (one + e)]);
+            }
+        }
+        parts
+    }
+
+    /// Write the raw score behind each capped column and its curve's block
+    pub fn fill(&self, trace: &mut ExecutionTrace, raw: &[u32]) {
+        for row in 0..trace.height {
+            for (i, &score) in raw.iter().enumerate() {
+                trace.set(row, self.raw_column(i), BabyBearField::from_u32(score));
+            }
+        }
+        for (i, ((_, cap), &score)) in self.capped.iter().zip(raw).enumerate() {
+            let parts: Vec<BabyBearField> = Self::witness(cap, score as u64).into_iter().map(BabyBearField::new).collect();
+            Self::relations(cap, BabyBearField::from_u32(score), &parts).fill(trace, self.block_offset(i), &parts);
+        }
+    }
+}
+
+impl CustomAir for SaturationAir {
+    fn width(&self) -> usize {
+        self.block_offset(self.capped.len())
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        for (i, (score_column, cap)) in self.capped.iter().enumerate() {
+            let offset = self.block_offset(i);
+            let parts = Self::parts(&cap.curve);
+            let relations = Self::relations(cap, Expr::cell(self.raw_column(i)), &part_cells(offset, parts));
+            system.constrain(format!("cap_{}_saturation", i), Expr::cell(*score_column) - &relations.output);
+            relations.constrain(system, &format!("cap_{}", i), offset, parts);
+        }
+    }
+}
+
+/// Widest scale span the normalization division is proven for
+pub const MAX_SCALE_SPAN: u64 = 1 << 20;
+
+/// Score normalization AIR, evaluated over columns appended to a threshold section
+///
+/// Column layout from `column_offset`: per scaled score a block holding the
+/// native score clamped into the scale, the signed rounding remainder and,
+/// under `Bankers`, the witnesses of its tie rule, then the block's range
+/// checks. Each target column must hold the native score's offset into the
+/// scale times `CANONICAL_MAX`, divided by the span and rounded under the
+/// scale's policy: a floor remainder lies in `[0, span)`; a banker's
+/// remainder within half the span, at exactly half only beside an even
+/// quotient.
+#[derive(Debug, Clone)]
+pub struct NormalizationAir {
+    pub column_offset: usize,
+    /// (normalized score column, scale) per scaled category
+    pub scaled: Vec<(usize, ScoreScale)>,
+}
+
+impl NormalizationAir {
+    pub fn new(column_offset: usize, scaled: Vec<(usize, ScoreScale)>) -> Self {
+        Self { column_offset, scaled }
+    }
+
+    pub fn native_column(&self, index: usize) -> usize {
+        self.block_offset(index)
+    }
+
+    pub fn remainder_column(&self, index: usize) -> usize {
+        self.block_offset(index) + 1
+    }
+
+    fn parts(scale: &ScoreScale) -> usize {
+        match scale.rounding {
+            RoundingPolicy::Floor => 2,
+            RoundingPolicy::Bankers => 8,
+        }
+    }
+
+    fn block_offset(&self, index: usize) -> usize {
+        let zeros = |scale: &ScoreScale| vec![BabyBearField::ZERO; Self::parts(scale)];
+        self.column_offset
+            + self.scaled[..index]
+                .iter()
+                .map(|(_, scale)| Self::relations(scale, BabyBearField::ZERO, &zeros(scale)).width(Self::parts(scale)))
+                .sum::<usize>()
+    }
+
+    /// Reject scales too wide for the division to stay below the modulus
+    pub fn check_scales(&self) -> Result<(), ZKPError> {
+        match self.scaled.iter().find(|(_, scale)| scale.span() as u64 >= MAX_SCALE_SPAN) {
+            Some((_, scale)) => Err(ZKPError::LimitExceeded {
+                limit: "scale_span".to_string(),
+                actual: scale.span() as u64,
+                max: MAX_SCALE_SPAN - 1,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn relations<T: Arithmetic>(scale: &ScoreScale, normalized: T, parts: &[T]) -> Relations<T> {
+        let f = |value: u64| T::from(BabyBearField::new(value));
+        let one = f(1);
+        let span = f(scale.span() as u64);
+        let span_bits = MAX_SCALE_SPAN.trailing_zeros() as usize;
+        let (native, remainder) = (parts[0].clone(), parts[1].clone());
+        let offset = native - f(scale.min as u64);
+
+        let mut relations = Relations::new(normalized.clone());
+        relations.vanish(
+            "division",
+            offset.clone() * f(CANONICAL_MAX as u64) - (normalized.clone() * span.clone() + remainder.clone()),
+        );
+        relations.range("offset", offset.clone(), span_bits);
+        relations.range("offset_upper", span.clone() - offset, span_bits);
+        match scale.rounding {
+            RoundingPolicy::Floor => {
+                relations.range("rounding", remainder.clone(), span_bits);
+                relations.range("rounding_upper", span - one - remainder, span_bits);
+                relations.range("quotient", normalized, 10);
+            }
+            RoundingPolicy::Bankers => {
+                // Twice the remainder shifted by the span lies in [0, 2 * span];
+                // its ends are the ties, allowed only beside an even quotient
+                let shifted = remainder.clone() + remainder + span.clone();
+                let room = span.clone() + span - shifted.clone();
+                let [low_inverse, low_zero, high_inverse, high_zero, half, parity] =
+                    [2, 3, 4, 5, 6, 7].map(|i| parts[i].clone());
+                relations.range("rounding", shifted.clone(), span_bits + 1);
+                relations.range("rounding_upper", room.clone(), span_bits + 1);
+                relations.vanish("rounding_low_inverse", shifted.clone() * low_inverse - (one.clone() - low_zero.clone()));
+                relations.vanish("rounding_low_zero", shifted * low_zero.clone());
+                relations.vanish("rounding_high_inverse", room.clone() * high_inverse - (one - high_zero.clone()));
+                relations.vanish("rounding_high_zero", room * high_zero.clone());
+                relations.boolean("quotient_parity_boolean", &parity);
+                relations.vanish("quotient_parity", normalized - (half.clone() + half.clone() + parity.clone()));
+                relations.range("quotient", half, 9);
+                relations.vanish("rounding_tie_even", (low_zero + high_zero) * parity);
+            }
+        }
+        relations
+    }
+
+    /// Write the block of each scaled score from its native value
+    pub fn fill(&self, trace: &mut ExecutionTrace, native: &[u32]) {
+        for (i, ((_, scale), &score)) in self.scaled.iter().zip(native).enumerate() {
+            let normalized = BabyBearField::from_u32(scale.normalize(score));
+            let remainder = BabyBearField::from_i64(scale.remainder(score));
+            let mut parts = vec![BabyBearField::from_u32(scale.clamp(score)), remainder];
+            if scale.rounding == RoundingPolicy::Bankers {
+                let shifted = remainder + remainder + BabyBearField::from_u32(scale.span());
+                let room = BabyBearField::from_u32(2 * scale.span()) - shifted;
+                for value in [shifted, room] {
+                    parts.push(value.inverse().unwrap_or(BabyBearField::ZERO));
+                    parts.push(BabyBearField::new((value == BabyBearField::ZERO) as u64));
+                }
+                parts.extend([normalized.0 >> 1, normalized.0 & 1].map(BabyBearField::new));
+            }
+            Self::relations(scale, normalized, &parts).fill(trace, self.block_offset(i), &parts);
+        }
+    }
+}
+
+impl CustomAir for NormalizationAir {
+    fn width(&self) -> usize {
+        self.block_offset(self.scaled.len())
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        for (i, (target_column, scale)) in self.scaled.iter().enumerate() {
+            let offset = self.block_offset(i);
+            let parts = Self::parts(scale);
+            let relations = Self::relations(scale, Expr::cell(*target_column), &part_cells(offset, parts));
+            relations.constrain(system, &format!("scale_{}", i), offset, parts);
+        }
+    }
+}
+
+/// Absent category AIR, evaluated over columns appended to a threshold section
+///
+/// Column layout from `column_offset`: one boolean flag per score column;
+/// a flagged score column must hold zero, so an absent category contributes
+/// nothing and stays distinguishable from a genuine zero score.
+#[derive(Debug, Clone)]
+pub struct AbsenceAir {
+    pub column_offset: usize,
+    /// Score column each flag stands beside
+    pub score_columns: Vec<usize>,
+}
+
+impl AbsenceAir {
+    pub fn new(column_offset: usize, score_columns: Vec<usize>) -> Self {
+        Self { column_offset, score_columns }
+    }
+
+    pub fn flag_column(&self, index: usize) -> usize {
+        self.column_offset + index
+    }
+
+    /// Write the absence flag of every score column on every row
+    pub fn fill(&self, trace: &mut ExecutionTrace, absent: &[bool]) {
+        for row in 0..trace.height {
+            for (i, &flag) in absent.iter().enumerate().take(self.score_columns.len()) {
+                trace.set(row, self.flag_column(i), BabyBearField::new(flag as u64));
+            }
+        }
+    }
+}
+
+impl CustomAir for AbsenceAir {
+    fn width(&self) -> usize {
+        self.column_offset + self.score_columns.len()
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        for (i, score_column) in self.score_columns.iter().enumerate() {
+            let absent = Expr::cell(self.flag_column(i));
+            system.constrain(format!("absent_{}_boolean", i), &absent * (&absent - BabyBearField::ONE));
+            system.constrain(format!("absent_{}_zero", i), absent * Expr::cell(*score_column));
+        }
+    }
+}
+
+/// Biometric 4FA AIR
+///
+/// Column layout: 0 challenge, 1 biometric hash, 2..6 factor flags,
+/// 6 all_verified, 7 validity flag.
+#[derive(Debug, Clone)]
+pub struct BiometricAir {
+    pub webauthn_challenge: BabyBearField,
+}
+
+impl BiometricAir {
+    pub fn new(webauthn_challenge: BabyBearField) -> Self {
+        Self { webauthn_challenge }
+    }
+}
+
+impl CustomAir for BiometricAir {
+    fn width(&self) -> usize {
+        8
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let factors: Vec<Expr> = (2..6).map(Expr::cell).collect();
+
+        // all_verified should be 1 only if all factors are 1
+        let all_factors = factors.iter().fold(Expr::constant(BabyBearField::ONE), |acc, factor| acc * factor);
+        system.constrain("challenge_consistency", Expr::cell(0) - self.webauthn_challenge);
+        system.constrain("all_factors_verified", Expr::cell(6) - all_factors);
+        for (i, factor) in factors.iter().enumerate() {
+            system.constrain(format!("factor_{}_boolean", i), factor * (factor - BabyBearField::ONE));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threshold_trace(scores: &[u32], final_score: u32, meets: u32) -> ExecutionTrace {
+        let air = ThresholdAir::new(scores.len(), 100, 86400, 0, None);
+        let mut trace = ExecutionTrace::new(air.width(), ThresholdAir::ROWS);
+        for row in 0..trace.height {
+            trace.set(row, 0, BabyBearField::from_u32(100));
+            trace.set(row, 1, BabyBearField::new(86400));
+            let mut running_sum = 0;
+            for (i, &score) in scores.iter().enumerate() {
+                running_sum += score;
+                trace.set(row, air.score_column(i), BabyBearField::from_u32(score));
+                trace.set(row, air.running_sum_column(i), BabyBearField::from_u32(running_sum));
+            }
+            trace.set(row, air.final_score_column(), BabyBearField::from_u32(final_score));
+            trace.set(row, air.meets_threshold_column(), BabyBearField::from_u32(meets));
+            trace.set(row, air.validity_column(), BabyBearField::ONE);
+        }
+        air.fill_gadgets(&mut trace);
+        trace
+    }
+
+    #[test]
+    fn test_check_witness_accepts_valid_trace() {
+        let air = ThresholdAir::new(2, 100, 86400, 0, None);
+        assert!(check_witness(&threshold_trace(&[75, 50], 125, 1), &air).is_ok());
+        assert!(check_witness(&threshold_trace(&[25, 50], 75, 0), &air).is_ok());
+        assert!(check_witness(&threshold_trace(&[60, 40], 100, 1), &air).is_ok());
+    }
+
+    #[test]
+    fn test_check_witness_reports_exact_violation() {
+        let air = ThresholdAir::new(2, 100, 86400, 0, None);
+
+        let mut trace = threshold_trace(&[75, 50], 125, 1);
+        trace.set(2, air.meets_threshold_column(), BabyBearField::ZERO);
+        let violation = check_witness(&trace, &air).unwrap_err();
+        assert_eq!(violation.row, 2);
+        assert_eq!(violation.label, "meets_threshold_constant");
+
+        let inflated = threshold_trace(&[75, 50], 150, 1);
+        let violation = check_witness(&inflated, &air).unwrap_err();
+        assert_eq!((violation.row, violation.label.as_str()), (0, "final_score_sum"));
+    }
+
+    #[test]
+    fn test_threshold_comparison_is_range_checked() {
+        let air = ThresholdAir::new(2, 100, 86400, 0, None);
+        let last_row = ThresholdAir::ROWS - 1;
+
+        // A flag consistent on every row still fails once its distance wraps
+        for (scores, final_score, meets) in [([75, 50], 125, 0), ([25, 50], 75, 1), ([60, 39], 99, 1)] {
+            let violation = check_witness(&threshold_trace(&scores, final_score, meets), &air).unwrap_err();
+            assert_eq!((violation.row, violation.label.as_str()), (last_row, "meets_threshold_correctness"));
+        }
+    }
+
+    #[test]
+    fn test_running_sums_are_range_checked() {
+        let air = ThresholdAir::new(2, 100, 86400, 0, None);
+        let mut trace = threshold_trace(&[75, 50], 125, 1);
+
+        // A total past the aggregate bound cannot be decomposed into its bits
+        let huge = BabyBearField::new(MAX_AGGREGATE_SCORE + 75);
+        for row in 0..trace.height {
+            trace.set(row, air.score_column(0), huge);
+            trace.set(row, air.running_sum_column(0), huge);
+            trace.set(row, air.running_sum_column(1), huge + BabyBearField::from_u32(50));
+            trace.set(row, air.final_score_column(), huge + BabyBearField::from_u32(50));
+        }
+        air.fill_gadgets(&mut trace);
+        let violation = check_witness(&trace, &air).unwrap_err();
+        assert_eq!((violation.row, violation.label.as_str()), (ThresholdAir::ROWS - 1, "running_sum_0_range"));
+    }
+
+    fn decayed_trace(air: &ThresholdAir, scores: &[u32], final_score: u32) -> ExecutionTrace {
+        let mut trace = ExecutionTrace::new(air.width(), ThresholdAir::ROWS);
+        for row in 0..trace.height {
+            trace.set(row, 0, BabyBearField::from_u32(air.threshold));
+            trace.set(row, 1, BabyBearField::new(air.time_window));
+            let mut running_sum = 0;
+            for (i, &score) in scores.iter().enumerate() {
+                running_sum += score;
+                trace.set(row, air.score_column(i), BabyBearField::from_u32(score));
+                trace.set(row, air.running_sum_column(i), BabyBearField::from_u32(running_sum));
+            }
+            trace.set(row, air.final_score_column(), BabyBearField::from_u32(final_score));
+            trace.set(row, air.meets_threshold_column(), BabyBearField::new((final_score >= air.threshold) as u64));
+            trace.set(row, air.validity_column(), BabyBearField::ONE);
+            trace.set(row, air.floor_flag_column(), BabyBearField::ONE);
+        }
+        air.fill_gadgets(&mut trace);
+        trace
+    }
+
+    #[test]
+    fn test_decayed_final_score_is_bound_to_the_scores() {
+        let half = DecayFactor { retention: Q16::from_ratio(1, 2), floor: 0 };
+        let tenth = DecayFactor { retention: Q16::from_ratio(1, 10), floor: 40 };
+        let air = ThresholdAir::new(2, 80, 86400, 0, Some(ThresholdDecay { factors: vec![half, tenth], min_threshold: 0 }));
+
+        // 100 halves to 50; 50 keeps a tenth but stops at its floor of 40
+        let trace = decayed_trace(&air, &[100, 50], 90);
+        assert!(check_witness(&trace, &air).is_ok());
+        assert_eq!(trace.get(0, air.decayed_column(1)), BabyBearField::from_u32(40));
+
+        let undecayed = decayed_trace(&air, &[100, 50], 150);
+        let violation = check_witness(&undecayed, &air).unwrap_err();
+        assert_eq!((violation.row, violation.label.as_str()), (0, "final_score_sum"));
+
+        // Decayed columns forged along with the total still break the gadgets
+        for (category, forged, label) in [(0, 100, "decay_0_high_product"), (1, 4, "decay_1_floor")] {
+            let mut trace = trace.clone();
+            let final_score = 90 + forged - trace.get(0, air.decayed_column(category)).0 as u32;
+            for row in 0..trace.height {
+                trace.set(row, air.decayed_column(category), BabyBearField::from_u32(forged));
+                trace.set(row, air.final_score_column(), BabyBearField::from_u32(final_score));
+            }
+            let violation = check_witness(&trace, &air).unwrap_err();
+            assert_eq!((violation.row, violation.label.as_str()), (0, label));
+        }
+    }
+}
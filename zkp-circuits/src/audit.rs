@@ -0,0 +1,219 @@
+//! Audit Sampling
+//!
+//! Verifiable random samples of wallets for reputation audits. The auditor
+//! commits to the population before a public beacon value is known; sample
+//! indices are then derived from the beacon and the commitment alone, and
+//! every sampled wallet carries a Merkle path into the committed population.
+//! Anyone can recompute the indices and check the paths, so an audit cannot
+//! cherry-pick users or reshape the population after seeing the beacon.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::{hash_nodes, wallet_tag, InclusionWitness};
+use crate::poseidon2;
+use crate::{Result, ZKPError, F};
+
+/// Published commitment to an audit population
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PopulationCommitment {
+    /// Merkle root over wallet tags in population order, padded with zero leaves
+    pub root: F,
+    pub size: u64,
+}
+
+impl PopulationCommitment {
+    /// Poseidon2(root, size), the value a beacon-derived sample is bound to
+    pub fn digest(&self) -> F {
+        poseidon2::hash_elements(&[self.root, F::new(self.size)])
+    }
+
+    /// Tree depth implied by the population size
+    pub fn depth(&self) -> usize {
+        self.size.next_power_of_two().trailing_zeros() as usize
+    }
+}
+
+/// Population indices sampled by `beacon`, without replacement
+///
+/// Indices come from a Blake3 stream over the beacon and the commitment
+/// digest; draws past the largest multiple of the population size are
+/// rejected so every index is equally likely.
+pub fn sample_indices(beacon: &[u8; 32], population: &PopulationCommitment, sample_size: usize) -> Result<Vec<u64>> {
+    if sample_size as u64 > population.size {
+        return Err(ZKPError::InvalidInput(format!(
+            "Cannot sample {} wallets from a population of {}",
+            sample_size, population.size
+        )));
+    }
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"RepID_AuditSample");
+    hasher.update(beacon);
+    hasher.update(&population.digest().0.to_le_bytes());
+    let mut stream = hasher.finalize_xof();
+
+    let limit = u64::MAX - u64::MAX % population.size.max(1);
+    let mut indices = Vec::with_capacity(sample_size);
+    let mut draw = [0u8; 8];
+    while indices.len() < sample_size {
+        stream.fill(&mut draw);
+        let value = u64::from_le_bytes(draw);
+        if value >= limit {
+            continue;
+        }
+        let index = value % population.size;
+        if !indices.contains(&index) {
+            indices.push(index);
+        }
+    }
+    Ok(indices)
+}
+
+/// Auditor-side population
+#[derive(Debug, Clone)]
+pub struct AuditPopulation {
+    wallets: Vec<String>,
+    /// Tree levels from the padded leaves upwards
+    levels: Vec<Vec<F>>,
+}
+
+impl AuditPopulation {
+    /// Population in the given order; each wallet may appear once
+    pub fn new(wallets: Vec<String>) -> Result<Self> {
+        if wallets.is_empty() {
+            return Err(ZKPError::InvalidInput("Audit population is empty".to_string()));
+        }
+        let mut leaves: Vec<F> = wallets.iter().map(|wallet| wallet_tag(wallet)).collect();
+        if let Some(i) = (0..leaves.len()).find(|&i| leaves[..i].contains(&leaves[i])) {
+            return Err(ZKPError::InvalidInput(format!("Wallet '{}' appears twice in the population", wallets[i])));
+        }
+
+        leaves.resize(leaves.len().next_power_of_two(), F::ZERO);
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1].chunks(2).map(|pair| hash_nodes(pair[0], pair[1])).collect();
+            levels.push(next);
+        }
+        Ok(Self { wallets, levels })
+    }
+
+    pub fn commitment(&self) -> PopulationCommitment {
+        PopulationCommitment {
+            root: self.levels[self.levels.len() - 1][0],
+            size: self.wallets.len() as u64,
+        }
+    }
+
+    fn witness(&self, index: u64) -> InclusionWitness {
+        let siblings = self.levels[..self.levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(level, nodes)| nodes[((index >> level) ^ 1) as usize])
+            .collect();
+        InclusionWitness {
+            leaf_index: index,
+            leaf: self.levels[0][index as usize],
+            siblings,
+        }
+    }
+
+    /// Draw the sample `beacon` selects, with inclusion paths for publication
+    pub fn draw(&self, beacon: [u8; 32], sample_size: usize) -> Result<AuditSample> {
+        let population = self.commitment();
+        let members = sample_indices(&beacon, &population, sample_size)?
+            .into_iter()
+            .map(|index| SampledWallet {
+                wallet: self.wallets[index as usize].clone(),
+                witness: self.witness(index),
+            })
+            .collect();
+        Ok(AuditSample { population, beacon, members })
+    }
+}
+
+/// One sampled wallet and its path into the population
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampledWallet {
+    pub wallet: String,
+    pub witness: InclusionWitness,
+}
+
+/// Published audit sample, in draw order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditSample {
+    pub population: PopulationCommitment,
+    pub beacon: [u8; 32],
+    pub members: Vec<SampledWallet>,
+}
+
+impl AuditSample {
+    /// Check the sample is exactly the one `beacon` draws from `population`
+    ///
+    /// `population` must be the commitment published before the beacon
+    /// value was known; a sample against any other commitment is rejected.
+    pub fn verify(&self, population: &PopulationCommitment, beacon: &[u8; 32]) -> Result<()> {
+        if self.population != *population || self.beacon != *beacon {
+            return Err(ZKPError::VerificationError(
+                "Sample was drawn against a different population or beacon".to_string(),
+            ));
+        }
+
+        let indices = sample_indices(beacon, population, self.members.len())?;
+        for (member, index) in self.members.iter().zip(indices) {
+            let witness = &member.witness;
+            if witness.leaf_index != index || witness.siblings.len() != population.depth() {
+                return Err(ZKPError::VerificationError(format!(
+                    "Wallet '{}' is not at sampled index {}",
+                    member.wallet, index
+                )));
+            }
+            if witness.leaf != wallet_tag(&member.wallet) || !witness.verify(population.root) {
+                return Err(ZKPError::VerificationError(format!(
+                    "Wallet '{}' does not open against the population root",
+                    member.wallet
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_is_fixed_by_beacon_and_commitment() {
+        let wallets: Vec<String> = (0..13).map(|i| format!("0xwallet{}", i)).collect();
+        let population = AuditPopulation::new(wallets.clone()).unwrap();
+        let commitment = population.commitment();
+        let beacon = [5; 32];
+
+        let sample = population.draw(beacon, 4).unwrap();
+        assert_eq!(sample.members.len(), 4);
+        assert!(sample.verify(&commitment, &beacon).is_ok());
+        let published: AuditSample = serde_json::from_str(&sample.to_json().unwrap()).unwrap();
+        assert!(published.verify(&commitment, &beacon).is_ok());
+        assert!(sample.verify(&commitment, &[6; 32]).is_err());
+
+        // Swapping in an unsampled wallet fails even with a valid path
+        let sampled: Vec<&String> = sample.members.iter().map(|m| &m.wallet).collect();
+        let other = (0..13u64).find(|&i| !sampled.contains(&&wallets[i as usize])).unwrap();
+        let mut cherry_picked = sample.clone();
+        cherry_picked.members[0] = population.draw(beacon, 13).unwrap().members
+            .into_iter()
+            .find(|m| m.witness.leaf_index == other)
+            .unwrap();
+        assert!(cherry_picked.verify(&commitment, &beacon).is_err());
+
+        // A population reshaped after the beacon has a different commitment
+        let reshaped = AuditPopulation::new(wallets[1..].to_vec()).unwrap();
+        assert!(reshaped.draw(beacon, 4).unwrap().verify(&commitment, &beacon).is_err());
+        assert!(population.draw(beacon, 14).is_err());
+        assert!(AuditPopulation::new(vec!["0xa".to_string(), "0xa".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! Proving Backends
+//!
+//! A common interface over the proving stacks in this crate so `RepIDZKPSystem`
+//! picks one at construction instead of callers depending on a concrete stack
+//!
+//! The custom STARK is the only implementation. The Plonky3 sources
+//! (`repid_prover`, `repid_verifier`, `repid_air`) are not compiled into this
+//! crate and its Plonky3 dependencies are absent, so no Plonky3 backend
+//! ships and `BackendKind` names only the custom STARK.
+//!
+//! Envelopes record the backend that produced them, and a system only
+//! verifies proofs from its own backend. There is no conversion between proof
+//! formats while a single backend exists.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "prover")]
+use crate::absence::RequestedScores;
+#[cfg(feature = "prover")]
+use crate::custom_stark::CustomStarkProver;
+use crate::custom_stark::{BabyBearField as F, CustomStarkVerifier, StarkProof};
+use crate::limits::ProofLimits;
+#[cfg(feature = "prover")]
+use crate::wallet::WalletCommitment;
+#[cfg(feature = "prover")]
+use crate::RepIDCategory;
+use crate::{decoding, ProofMetadata, RepIDProof, Result, ThresholdVerificationRequest, ZKPError};
+
+/// Available proving stacks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendKind {
+    /// In-crate BabyBear STARK (`custom_stark`)
+    #[default]
+    CustomStark,
+}
+
+impl std::str::FromStr for BackendKind {
+    type Err = ZKPError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "custom_stark" | "custom-stark" => Ok(BackendKind::CustomStark),
+            other => Err(ZKPError::ConfigError(format!("unknown backend '{}'", other))),
+        }
+    }
+}
+
+/// Serialized proof and public inputs produced by a backend
+#[derive(Debug, Clone)]
+pub struct BackendProof {
+    pub proof_data: Vec<u8>,
+    pub public_inputs: Vec<F>,
+}
+
+impl BackendProof {
+    /// Wrap a backend's output in the `RepIDProof` envelope
+    pub fn into_envelope(
+        self,
+        backend: BackendKind,
+        operation_type: &str,
+        wallet_hash: String,
+        timestamp: u64,
+        generation_time_ms: u64,
+    ) -> RepIDProof {
+        RepIDProof {
+            metadata: ProofMetadata {
+                operation_type: operation_type.to_string(),
+                timestamp,
+                wallet_hash,
+                proof_size: self.proof_data.len(),
+                generation_time_ms,
+                backend,
+                prover_signature: None,
+                tee_attestation: None,
+                content_address: None,
+                protocol_version: crate::protocol::PROTOCOL_VERSION,
+                stark_params: None,
+                issuer_signatures: Vec::new(),
+            },
+            proof_data: self.proof_data,
+            public_inputs: self.public_inputs,
+        }
+    }
+
+}
+
+/// Proof generation and verification for the RepID statements
+pub trait ProverBackend: Send {
+    fn kind(&self) -> BackendKind;
+
+    /// Prove the scores meet the request's threshold, with `wallet` as a public input
+    #[cfg(feature = "prover")]
+    fn prove_threshold(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        user_scores: &[(RepIDCategory, u32)],
+        wallet: &WalletCommitment,
+    ) -> Result<BackendProof>;
+
+    /// Prove all four authentication factors passed
+    #[cfg(feature = "prover")]
+    fn prove_biometric(
+        &mut self,
+        webauthn_challenge: [u8; 32],
+        biometric_hash: [u8; 32],
+        factor_proofs: &[bool; 4],
+    ) -> Result<BackendProof>;
+
+    /// Verify a proof envelope, returning the typed failure reason
+    fn verify(&self, proof: &RepIDProof, request: Option<&ThresholdVerificationRequest>) -> Result<()>;
+
+    /// Access the custom STARK stack for features only it supports
+    fn custom_stark(&self) -> Option<&CustomStarkBackend> {
+        None
+    }
+
+    fn custom_stark_mut(&mut self) -> Option<&mut CustomStarkBackend> {
+        None
+    }
+}
+
+/// `ProverBackend` over the in-crate custom STARK
+pub struct CustomStarkBackend {
+    #[cfg(feature = "prover")]
+    pub prover: CustomStarkProver,
+    pub verifier: CustomStarkVerifier,
+    /// Limits applied when decoding untrusted proofs
+    pub limits: ProofLimits,
+}
+
+impl CustomStarkBackend {
+    /// Verify with `verifier` in place of the configured one, e.g. one holding historical caps and scales
+    pub fn verify_with(
+        &self,
+        verifier: &CustomStarkVerifier,
+        proof: &RepIDProof,
+        request: Option<&ThresholdVerificationRequest>,
+    ) -> Result<()> {
+        // Strictly decode the untrusted STARK proof
+        let stark_proof = decoding::decode_stark_proof(&proof.proof_data, &self.limits)?;
+        // Statements are read from the envelope, so it must carry exactly what the proof commits to
+        if proof.public_inputs != stark_proof.public_inputs {
+            return Err(ZKPError::VerificationError("Envelope public inputs differ from the proof's".to_string()));
+        }
+
+        // Check negotiated proofs under the parameters they record, which may
+        // raise the configured ones but never lower them
+        let verifier = match &proof.metadata.stark_params {
+            Some(params) => {
+                params.validate().map_err(|e| ZKPError::MalformedProof(format!("recorded parameters: {}", e)))?;
+                let configured = verifier.params();
+                if !params.meets(&configured) {
+                    return Err(ZKPError::ParameterDowngrade(format!(
+                        "recorded parameters ({} queries, blowup {}) are below the configured ({} queries, blowup {})",
+                        params.num_queries, params.blowup_factor, configured.num_queries, configured.blowup_factor
+                    )));
+                }
+                Cow::Owned(verifier.with_params(params))
+            }
+            None => Cow::Borrowed(verifier),
+        };
+        verifier.check_proof(&stark_proof, &proof.metadata.operation_type)?;
+
+        // The proof must state the request it is verified against, and a
+        // request pinned to an instant only accepts proofs evaluated at it
+        match request {
+            Some(request) => {
+                verifier.check_request(&stark_proof, request)?;
+                if request.as_of != 0 {
+                    verifier.check_as_of(&stark_proof, &proof.metadata.operation_type, request.as_of)?;
+                }
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn new(num_queries: usize, blowup_factor: usize) -> Self {
+        Self {
+            #[cfg(feature = "prover")]
+            prover: CustomStarkProver::new(num_queries, blowup_factor),
+            verifier: CustomStarkVerifier::new(num_queries, blowup_factor),
+            limits: ProofLimits::default(),
+        }
+    }
+
+    /// Run `prove` with `wallet` appended to the public inputs of the proofs it generates
+    #[cfg(feature = "prover")]
+    pub fn with_wallet<T>(
+        &mut self,
+        wallet: &WalletCommitment,
+        prove: impl FnOnce(&mut CustomStarkProver) -> Result<T>,
+    ) -> Result<T> {
+        let outer = self.prover.wallet_commitment.replace(wallet.elements());
+        let result = prove(&mut self.prover);
+        self.prover.wallet_commitment = outer;
+        result
+    }
+
+    /// Serialize a STARK proof into a backend proof
+    pub fn encode(stark_proof: StarkProof) -> Result<BackendProof> {
+        let proof_data = crate::wire::to_bytes(&stark_proof)?;
+        Ok(BackendProof {
+            proof_data,
+            public_inputs: stark_proof.public_inputs,
+        })
+    }
+}
+
+impl ProverBackend for CustomStarkBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::CustomStark
+    }
+
+    #[cfg(feature = "prover")]
+    fn prove_threshold(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        user_scores: &[(RepIDCategory, u32)],
+        wallet: &WalletCommitment,
+    ) -> Result<BackendProof> {
+        let stark_proof = self.with_wallet(wallet, |prover| {
+            prover.prove_requested_threshold_verification(
+                &RequestedScores::new(&request.categories, user_scores),
+                request.threshold,
+                request.time_window,
+                request.as_of,
+                request.decay_params.as_ref(),
+            )
+        })?;
+        Self::encode(stark_proof)
+    }
+
+    #[cfg(feature = "prover")]
+    fn prove_biometric(
+        &mut self,
+        webauthn_challenge: [u8; 32],
+        biometric_hash: [u8; 32],
+        factor_proofs: &[bool; 4],
+    ) -> Result<BackendProof> {
+        let stark_proof = self.prover.prove_biometric_verification(webauthn_challenge, biometric_hash, factor_proofs)?;
+        Self::encode(stark_proof)
+    }
+
+    fn verify(&self, proof: &RepIDProof, request: Option<&ThresholdVerificationRequest>) -> Result<()> {
+        self.verify_with(&self.verifier, proof, request)
+    }
+
+    fn custom_stark(&self) -> Option<&CustomStarkBackend> {
+        Some(self)
+    }
+
+    fn custom_stark_mut(&mut self) -> Option<&mut CustomStarkBackend> {
+        Some(self)
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::config::ProverConfig;
+    use crate::{RepIDZKPSystem, SecurityLevel};
+
+    #[test]
+    fn test_backend_selection() {
+        let system = RepIDZKPSystem::from_config(ProverConfig::new(SecurityLevel::Fast)).unwrap();
+        assert_eq!(system.backend_kind(), BackendKind::CustomStark);
+
+        // No Plonky3 backend ships with this crate
+        assert!(matches!("plonky3".parse::<BackendKind>(), Err(ZKPError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_custom_backend_round_trip() {
+        let mut backend: Box<dyn ProverBackend> = Box::new(CustomStarkBackend::new(4, 4));
+        let proof = backend.prove_biometric([7u8; 32], [9u8; 32], &[true; 4]).unwrap();
+
+        let envelope = proof.into_envelope(BackendKind::CustomStark, "biometric_4fa", String::new(), 0, 0);
+        assert!(backend.verify(&envelope, None).is_ok());
+    }
+
+}
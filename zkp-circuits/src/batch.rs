@@ -0,0 +1,118 @@
+//! Streaming Batch Verification
+//!
+//! Lazily verifies proofs pulled from an iterator, one at a time, so large
+//! audits run in memory bounded by a single proof rather than the whole batch
+
+use crate::policy::VerifyPolicy;
+use crate::{RepIDProof, RepIDZKPSystem, Result, ThresholdVerificationRequest};
+
+/// Result of verifying one proof of a stream
+#[derive(Debug)]
+pub struct VerifyOutcome {
+    /// Position of the proof in the input stream
+    pub index: usize,
+    pub operation_type: String,
+    /// `Ok(false)` for invalid proofs, `Err` for proofs that could not be checked
+    pub result: Result<bool>,
+}
+
+impl VerifyOutcome {
+    pub fn is_valid(&self) -> bool {
+        matches!(self.result, Ok(true))
+    }
+}
+
+/// Verifier for proof streams, borrowing a configured system
+pub struct BatchVerifier<'a> {
+    system: &'a RepIDZKPSystem,
+    policy: VerifyPolicy,
+    max_failures: Option<usize>,
+}
+
+impl<'a> BatchVerifier<'a> {
+    pub fn new(system: &'a RepIDZKPSystem) -> Self {
+        Self {
+            system,
+            policy: VerifyPolicy::default(),
+            max_failures: None,
+        }
+    }
+
+    /// Verify every proof under `policy` instead of the default policy
+    pub fn with_policy(mut self, policy: VerifyPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// End the stream after this many invalid or uncheckable proofs
+    pub fn with_max_failures(mut self, max_failures: usize) -> Self {
+        self.max_failures = Some(max_failures);
+        self
+    }
+
+    /// Verify proofs as the returned iterator is advanced
+    ///
+    /// Each item is a proof with its request; pass the request directly or as
+    /// an `Option` for proofs verified without one. Nothing is read from
+    /// `proofs` ahead of the outcome being asked for.
+    pub fn verify_stream<I, R>(&self, proofs: I) -> impl Iterator<Item = VerifyOutcome> + '_
+    where
+        I: IntoIterator<Item = (RepIDProof, R)>,
+        I::IntoIter: 'a,
+        R: Into<Option<ThresholdVerificationRequest>>,
+    {
+        let mut proofs = proofs.into_iter().enumerate();
+        let mut failures = 0;
+        std::iter::from_fn(move || {
+            if self.max_failures.is_some_and(|max| failures >= max) {
+                return None;
+            }
+            let (index, (proof, request)) = proofs.next()?;
+            let request = request.into();
+            let outcome = VerifyOutcome {
+                index,
+                operation_type: proof.metadata.operation_type.clone(),
+                result: self.system.verify_proof_with_policy(&proof, request.as_ref(), &self.policy),
+            };
+            if !outcome.is_valid() {
+                failures += 1;
+            }
+            Some(outcome)
+        })
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, SecurityLevel};
+
+    #[test]
+    fn test_stream_is_lazy_and_stops_early() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Governance],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let scores = [(RepIDCategory::Governance, 80)];
+        let valid = zkp_system.prove_threshold_verification(&request, &scores, "0xtest").unwrap().proof;
+        let mut stark_proof: crate::custom_stark::StarkProof = crate::wire::from_bytes(&valid.proof_data).unwrap();
+        stark_proof.public_inputs[0] = crate::F::new(10);
+        let mut tampered = valid.clone();
+        tampered.proof_data = crate::wire::to_bytes(&stark_proof).unwrap();
+
+        let mut pulled = 0;
+        let stream = [&valid, &tampered, &valid, &tampered, &valid].into_iter().map(|proof| {
+            pulled += 1;
+            (proof.clone(), request.clone())
+        });
+        let outcomes: Vec<_> = BatchVerifier::new(&zkp_system).with_max_failures(2).verify_stream(stream).collect();
+
+        assert_eq!(outcomes.iter().map(VerifyOutcome::is_valid).collect::<Vec<_>>(), [true, false, true, false]);
+        assert_eq!(outcomes[3].index, 3);
+        assert_eq!(pulled, 4);
+    }
+}
@@ -0,0 +1,152 @@
+//! EIP-4844 Blob Packaging
+//!
+//! Splits an encoded proof envelope into blob-sized chunks with a SHA-256
+//! manifest, and reassembles and checks them on the receiving side
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{RepIDProof, Result, ZKPError};
+
+/// Field elements per blob
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+/// Bytes per blob field element
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// Payload bytes per field element; the top byte stays zero to remain below the BLS12-381 modulus
+pub const USABLE_BYTES_PER_FIELD_ELEMENT: usize = 31;
+/// Raw size of one blob
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+/// Payload carried by one blob
+pub const USABLE_BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * USABLE_BYTES_PER_FIELD_ELEMENT;
+/// Blobs a single Cancun transaction may carry
+pub const MAX_BLOBS_PER_TX: usize = 6;
+
+/// One encoded blob
+pub type Blob = Vec<u8>;
+
+/// Commitments tying a set of blobs to the envelope they carry
+///
+/// The posting client adds the KZG commitments and versioned hashes; the
+/// SHA-256 hashes here let a reader check reassembly without a KZG library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobManifest {
+    /// Length of the encoded envelope in bytes
+    pub payload_len: u64,
+    /// SHA-256 of the encoded envelope
+    pub payload_hash: [u8; 32],
+    /// SHA-256 of each blob, in order
+    pub blob_hashes: Vec<[u8; 32]>,
+}
+
+impl BlobManifest {
+    pub fn blob_count(&self) -> usize {
+        self.blob_hashes.len()
+    }
+
+    /// Transactions needed to post every blob
+    pub fn transactions_needed(&self) -> usize {
+        self.blob_count().div_ceil(MAX_BLOBS_PER_TX)
+    }
+}
+
+/// Blobs plus the manifest describing them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobBundle {
+    pub manifest: BlobManifest,
+    pub blobs: Vec<Blob>,
+}
+
+/// Encode a proof envelope into blobs
+pub fn pack_proof(proof: &RepIDProof) -> Result<BlobBundle> {
+    let payload = crate::protocol::encode_envelope(proof)?;
+    let blobs: Vec<Blob> = payload.chunks(USABLE_BYTES_PER_BLOB).map(encode_blob).collect();
+    let manifest = BlobManifest {
+        payload_len: payload.len() as u64,
+        payload_hash: sha256(&payload),
+        blob_hashes: blobs.iter().map(|blob| sha256(blob)).collect(),
+    };
+    Ok(BlobBundle { manifest, blobs })
+}
+
+/// Reassemble the envelope, checking every blob and the payload against the manifest
+pub fn unpack_proof(manifest: &BlobManifest, blobs: &[Blob]) -> Result<RepIDProof> {
+    if blobs.len() != manifest.blob_count() {
+        return Err(ZKPError::MalformedProof(format!(
+            "manifest lists {} blobs, got {}",
+            manifest.blob_count(),
+            blobs.len()
+        )));
+    }
+    let capacity = (blobs.len() * USABLE_BYTES_PER_BLOB) as u64;
+    if manifest.payload_len > capacity {
+        return Err(ZKPError::MalformedProof(format!(
+            "payload of {} bytes does not fit {} blobs",
+            manifest.payload_len,
+            blobs.len()
+        )));
+    }
+
+    let mut payload = Vec::with_capacity(manifest.payload_len as usize);
+    for (index, (blob, expected)) in blobs.iter().zip(&manifest.blob_hashes).enumerate() {
+        if blob.len() != BYTES_PER_BLOB || sha256(blob) != *expected {
+            return Err(ZKPError::VerificationError(format!("blob {} does not match the manifest", index)));
+        }
+        for element in blob.chunks_exact(BYTES_PER_FIELD_ELEMENT) {
+            if element[0] != 0 {
+                return Err(ZKPError::MalformedProof(format!("blob {} has a non-canonical field element", index)));
+            }
+            payload.extend_from_slice(&element[1..]);
+        }
+    }
+    payload.truncate(manifest.payload_len as usize);
+
+    if sha256(&payload) != manifest.payload_hash {
+        return Err(ZKPError::VerificationError("reassembled payload does not match the manifest".to_string()));
+    }
+    crate::protocol::decode_envelope(&payload)
+}
+
+/// Pad a chunk to a full blob, 31 payload bytes behind a zero byte per element
+fn encode_blob(chunk: &[u8]) -> Blob {
+    let mut blob = vec![0u8; BYTES_PER_BLOB];
+    for (element, data) in blob.chunks_exact_mut(BYTES_PER_FIELD_ELEMENT).zip(chunk.chunks(USABLE_BYTES_PER_FIELD_ELEMENT)) {
+        element[1..=data.len()].copy_from_slice(data);
+    }
+    blob
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_pack_and_unpack_large_proof() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::High);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Technical],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Technical, 75)], "0xtest").unwrap();
+
+        let bundle = pack_proof(&result.proof).unwrap();
+        assert!(bundle.blobs.iter().all(|blob| blob.len() == BYTES_PER_BLOB));
+        assert_eq!(bundle.manifest.transactions_needed(), 1);
+
+        let restored = unpack_proof(&bundle.manifest, &bundle.blobs).unwrap();
+        assert_eq!(restored.proof_data, result.proof.proof_data);
+        assert!(zkp_system.verify_proof(&restored, Some(&request)).unwrap());
+
+        let mut corrupted = bundle.blobs.clone();
+        corrupted[0][1] ^= 1;
+        assert!(matches!(unpack_proof(&bundle.manifest, &corrupted), Err(ZKPError::VerificationError(_))));
+        assert!(unpack_proof(&bundle.manifest, &bundle.blobs[..0]).is_err());
+    }
+}
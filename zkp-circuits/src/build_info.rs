@@ -0,0 +1,105 @@
+//! Build Information
+//!
+//! Reports how the verifier was compiled, so hosts embedding it as wasm can
+//! check they got the size-optimized configuration and that the module fits
+//! the size target
+//!
+//! The binary cannot measure itself before it is linked. Build the module once,
+//! then rebuild with `REPID_VERIFIER_WASM` set to its path and the build script
+//! records its size here. Hosts that load the module can check its bytes
+//! directly with [`check_module_size`].
+//!
+//! Proofs use the crate's own wire encoding rather than bincode, and errors
+//! carry formatted messages; `wasm-small` trims the hash backends and the profile.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, ZKPError};
+
+/// Target size of the `wasm-small` verifier module in bytes
+pub const WASM_SIZE_TARGET: u64 = 200 * 1024;
+
+/// How this copy of the crate was compiled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    pub crate_version: String,
+    pub target_arch: String,
+    /// Proof generation is compiled in
+    pub prover: bool,
+    /// Built with the `wasm-small` feature
+    pub wasm_small: bool,
+    /// Enabled optional features, in manifest order
+    pub features: Vec<String>,
+    /// Size of the verifier module recorded at build time, if any
+    pub verifier_wasm_bytes: Option<u64>,
+    pub size_target_bytes: u64,
+}
+
+impl BuildInfo {
+    /// Whether the recorded module size is within the target; `None` when no size was recorded
+    pub fn within_size_target(&self) -> Option<bool> {
+        self.verifier_wasm_bytes.map(|bytes| bytes <= self.size_target_bytes)
+    }
+}
+
+/// Describe the current build
+pub fn build_info() -> BuildInfo {
+    let features = [
+        ("prover", cfg!(feature = "prover")),
+        ("scoring", cfg!(feature = "scoring")),
+        ("debug-trace", cfg!(feature = "debug-trace")),
+        ("test-utils", cfg!(feature = "test-utils")),
+        ("risc0", cfg!(feature = "risc0")),
+        ("remote-signer", cfg!(feature = "remote-signer")),
+        ("publish", cfg!(feature = "publish")),
+        ("compat", cfg!(feature = "compat")),
+        ("uniffi", cfg!(feature = "uniffi")),
+        ("sled", cfg!(feature = "sled")),
+        ("postgres", cfg!(feature = "postgres")),
+        ("wasm-small", cfg!(feature = "wasm-small")),
+    ];
+
+    BuildInfo {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        target_arch: std::env::consts::ARCH.to_string(),
+        prover: cfg!(feature = "prover"),
+        wasm_small: cfg!(feature = "wasm-small"),
+        features: features
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        verifier_wasm_bytes: option_env!("REPID_VERIFIER_WASM_BYTES").and_then(|bytes| bytes.parse().ok()),
+        size_target_bytes: WASM_SIZE_TARGET,
+    }
+}
+
+/// Check a compiled verifier module against the size target, returning its size
+pub fn check_module_size(module: &[u8]) -> Result<u64> {
+    let actual = module.len() as u64;
+    if actual > WASM_SIZE_TARGET {
+        return Err(ZKPError::LimitExceeded {
+            limit: "verifier_wasm_bytes".to_string(),
+            actual,
+            max: WASM_SIZE_TARGET,
+        });
+    }
+    Ok(actual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_reports_configuration() {
+        let info = build_info();
+        assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.prover, info.features.iter().any(|f| f == "prover"));
+        assert_eq!(info.within_size_target().is_some(), info.verifier_wasm_bytes.is_some());
+
+        assert_eq!(check_module_size(&[0u8; 1024]).unwrap(), 1024);
+        let oversized = vec![0u8; WASM_SIZE_TARGET as usize + 1];
+        assert!(matches!(check_module_size(&oversized), Err(ZKPError::LimitExceeded { .. })));
+    }
+}
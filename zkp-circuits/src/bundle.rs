@@ -0,0 +1,255 @@
+//! Proof Bundles
+//!
+//! Several predicates about one user proven in a single call, for onboarding
+//! flows that ask for three to five at once. `RepIDZKPSystem::prove_bundle`
+//! checks the witness and its attestations once up front, evaluates every
+//! proof at the same instant and proves identical predicates only once. The
+//! bundle digest binds each predicate to the proof answering it; relying
+//! parties check bundles with `check_bundle`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::category_count::{self, CATEGORY_COUNT_OPERATION};
+use crate::freshness::{self, FRESH_THRESHOLD_OPERATION};
+use crate::issuance::{self, IssuancePolicy, COSIGNED_THRESHOLD_OPERATION};
+use crate::limits::ProofLimits;
+use crate::policy::VerifyPolicy;
+use crate::{RepIDProof, RepIDZKPSystem, Result, ThresholdVerificationRequest, ZKPError};
+
+/// Most predicates one bundle may ask for
+pub const MAX_BUNDLE_PREDICATES: usize = 16;
+
+/// One statement to prove about the bundle's user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum PredicateRequest {
+    /// Threshold over plain scores
+    Threshold { request: ThresholdVerificationRequest },
+    /// Threshold over attestations at most `max_age_secs` old
+    Fresh { request: ThresholdVerificationRequest, max_age_secs: u64 },
+    /// Threshold over attestations meeting per-category issuance policies
+    Cosigned { request: ThresholdVerificationRequest, policies: Vec<IssuancePolicy> },
+    /// At least `k` categories each reach `min_per_category`
+    CategoryCount { min_per_category: u32, k: u32 },
+}
+
+impl PredicateRequest {
+    pub fn operation_type(&self) -> &'static str {
+        match self {
+            PredicateRequest::Threshold { .. } => "threshold_verification",
+            PredicateRequest::Fresh { .. } => FRESH_THRESHOLD_OPERATION,
+            PredicateRequest::Cosigned { .. } => COSIGNED_THRESHOLD_OPERATION,
+            PredicateRequest::CategoryCount { .. } => CATEGORY_COUNT_OPERATION,
+        }
+    }
+
+    /// Request the proof answers, for threshold predicates
+    pub fn request(&self) -> Option<&ThresholdVerificationRequest> {
+        match self {
+            PredicateRequest::Threshold { request }
+            | PredicateRequest::Fresh { request, .. }
+            | PredicateRequest::Cosigned { request, .. } => Some(request),
+            PredicateRequest::CategoryCount { .. } => None,
+        }
+    }
+
+    /// Same predicate evaluated at `as_of`, unless it pins its own instant
+    pub(crate) fn pinned(&self, as_of: u64) -> Self {
+        let mut pinned = self.clone();
+        match &mut pinned {
+            PredicateRequest::Threshold { request }
+            | PredicateRequest::Fresh { request, .. }
+            | PredicateRequest::Cosigned { request, .. } if request.as_of == 0 => request.as_of = as_of,
+            _ => {}
+        }
+        pinned
+    }
+
+    /// Canonical encoding, equal for predicates one proof can answer
+    pub(crate) fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+}
+
+/// Proofs answering a list of predicates about one user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofBundle {
+    /// Instant every threshold predicate was evaluated at
+    pub as_of: u64,
+    /// Requested predicates, pinned to `as_of`
+    pub predicates: Vec<PredicateRequest>,
+    /// Index into `proofs` of the proof answering each predicate
+    pub answers: Vec<usize>,
+    pub proofs: Vec<RepIDProof>,
+    /// Blake3 over the predicates, answers and proof envelopes
+    pub digest: [u8; 32],
+}
+
+impl ProofBundle {
+    /// Bundle of `proofs` answering `predicates`, with its digest computed
+    pub fn new(as_of: u64, predicates: Vec<PredicateRequest>, answers: Vec<usize>, proofs: Vec<RepIDProof>) -> Result<Self> {
+        let mut bundle = Self { as_of, predicates, answers, proofs, digest: [0; 32] };
+        bundle.digest = bundle.compute_digest()?;
+        Ok(bundle)
+    }
+
+    pub fn compute_digest(&self) -> Result<[u8; 32]> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"repid/bundle/v1");
+        hasher.update(&self.as_of.to_le_bytes());
+        for predicate in &self.predicates {
+            let bytes = predicate.canonical_bytes()?;
+            hasher.update(&(bytes.len() as u64).to_le_bytes()).update(&bytes);
+        }
+        for answer in &self.answers {
+            hasher.update(&(*answer as u64).to_le_bytes());
+        }
+        for proof in &self.proofs {
+            hasher.update(&crate::registry::ProofRegistry::proof_id(proof));
+        }
+        Ok(*hasher.finalize().as_bytes())
+    }
+
+    /// Proof answering predicate `index`
+    pub fn proof_for(&self, index: usize) -> Option<&RepIDProof> {
+        self.answers.get(index).and_then(|&answer| self.proofs.get(answer))
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+}
+
+/// Reject empty bundles and bundles over `MAX_BUNDLE_PREDICATES`
+pub fn check_predicate_count(count: usize) -> Result<()> {
+    if count == 0 {
+        return Err(ZKPError::InvalidInput("Proof bundle has no predicates".to_string()));
+    }
+    ProofLimits::check("bundle predicates", count as u64, MAX_BUNDLE_PREDICATES as u64)
+}
+
+/// Check a bundle answers `predicates` for a single wallet
+///
+/// Every proof must verify under `policy` as the operation its predicate
+/// calls for; issuance policies, freshness bounds and category counts are
+/// checked against what the proofs commit to.
+pub fn check_bundle(
+    system: &RepIDZKPSystem,
+    bundle: &ProofBundle,
+    predicates: &[PredicateRequest],
+    policy: &VerifyPolicy,
+) -> Result<()> {
+    check_predicate_count(predicates.len())?;
+    if bundle.digest != bundle.compute_digest()? {
+        return Err(ZKPError::VerificationError("bundle digest does not match its contents".to_string()));
+    }
+    let expected = predicates.iter().map(|p| p.pinned(bundle.as_of).canonical_bytes()).collect::<Result<Vec<_>>>()?;
+    let answered = bundle.predicates.iter().map(PredicateRequest::canonical_bytes).collect::<Result<Vec<_>>>()?;
+    if expected != answered || bundle.answers.len() != predicates.len() {
+        return Err(ZKPError::PolicyViolation("bundle answers different predicates".to_string()));
+    }
+    if let Some(first) = bundle.proofs.first() {
+        if bundle.proofs.iter().any(|proof| proof.metadata.wallet_hash != first.metadata.wallet_hash) {
+            return Err(ZKPError::PolicyViolation("bundle mixes proofs of different wallets".to_string()));
+        }
+    }
+
+    for (index, predicate) in bundle.predicates.iter().enumerate() {
+        let proof = bundle
+            .proof_for(index)
+            .ok_or_else(|| ZKPError::MalformedProof(format!("bundle has no proof for predicate {}", index)))?;
+        let verify_policy = policy.clone().with_allowed_operations(&[predicate.operation_type()]);
+        if !system.verify_proof_with_policy(proof, predicate.request(), &verify_policy)? {
+            return Err(ZKPError::VerificationError(format!("{} proof failed verification", predicate.operation_type())));
+        }
+        match predicate {
+            PredicateRequest::Threshold { .. } => {}
+            PredicateRequest::Cosigned { policies, .. } => issuance::check_policies(proof, policies)?,
+            PredicateRequest::Fresh { max_age_secs, .. } => freshness::check_bound(proof, *max_age_secs, bundle.as_of, 0)?,
+            PredicateRequest::CategoryCount { min_per_category, k } => {
+                let statement = category_count::proof_statement(proof)?;
+                if statement.min_per_category < *min_per_category || statement.k < *k {
+                    return Err(ZKPError::PolicyViolation(format!(
+                        "proof shows {} categories at {}, predicate requires {} at {}",
+                        statement.k, statement.min_per_category, k, min_per_category
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::disclosure::DisclosureWitness;
+    use crate::freshness::AttestedScore;
+    use crate::issuance::{CosignedAttestation, IssuerKey};
+    use crate::signer::{InMemorySigner, Signer};
+    use crate::{RepIDCategory, SecurityLevel};
+    use std::sync::Arc;
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_bundle_shares_witness_and_instant() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_clock(Arc::new(FixedClock::new(NOW)));
+        let issuers: Vec<InMemorySigner> = (1..=2).map(|seed| InMemorySigner::from_seed([seed; 32])).collect();
+        let keys: Vec<IssuerKey> = issuers.iter().map(|s| s.public_key().unwrap().try_into().unwrap()).collect();
+        let mut attestation = CosignedAttestation::new(
+            "0xtest",
+            AttestedScore { category: RepIDCategory::Governance, score: 80, issued_at: NOW - 86_400 },
+        );
+        issuers.iter().for_each(|issuer| attestation.cosign(issuer).unwrap());
+        let witness = DisclosureWitness::new("0xtest")
+            .with_scores(&[(RepIDCategory::Technical, 60), (RepIDCategory::Community, 50)])
+            .with_attestations(vec![attestation.clone()]);
+
+        let request = |threshold, category| ThresholdVerificationRequest::builder()
+            .with_threshold(threshold)
+            .with_category(category)
+            .with_time_window(86_400)
+            .build()
+            .unwrap();
+        let technical = PredicateRequest::Threshold { request: request(50, RepIDCategory::Technical) };
+        let predicates = vec![
+            technical.clone(),
+            PredicateRequest::CategoryCount { min_per_category: 40, k: 2 },
+            PredicateRequest::Cosigned {
+                request: request(50, RepIDCategory::Governance),
+                policies: vec![IssuancePolicy::new(RepIDCategory::Governance, 2, keys).unwrap()],
+            },
+            PredicateRequest::Fresh { request: request(50, RepIDCategory::Governance), max_age_secs: 30 * 86_400 },
+            technical,
+        ];
+
+        let bundle = zkp_system.prove_bundle(predicates.clone(), &witness).unwrap();
+        assert_eq!(bundle.as_of, NOW);
+        assert_eq!((bundle.proofs.len(), bundle.answers.clone()), (4, vec![0, 1, 2, 3, 0]));
+        let policy = VerifyPolicy::strict(SecurityLevel::Fast);
+        check_bundle(&zkp_system, &bundle, &predicates, &policy).unwrap();
+        let decoded = ProofBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        check_bundle(&zkp_system, &decoded, &predicates, &policy).unwrap();
+
+        // Answers cannot be swapped, nor the bundle offered for other predicates
+        let mut swapped = bundle.clone();
+        swapped.answers.swap(0, 1);
+        assert!(matches!(check_bundle(&zkp_system, &swapped, &predicates, &policy), Err(ZKPError::VerificationError(_))));
+        swapped.digest = swapped.compute_digest().unwrap();
+        assert!(check_bundle(&zkp_system, &swapped, &predicates, &policy).is_err());
+        assert!(check_bundle(&zkp_system, &bundle, &predicates[..4], &policy).is_err());
+
+        // Attestations are checked once, before anything is proven
+        let mut forged = attestation;
+        forged.attested.score = 95;
+        let witness = witness.with_attestations(vec![forged]);
+        assert!(matches!(zkp_system.prove_bundle(predicates, &witness), Err(ZKPError::InvalidInput(_))));
+        assert!(zkp_system.prove_bundle(Vec::new(), &witness).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+//! Category Count Predicates
+//!
+//! Proofs that at least `k` categories individually reach a per-category
+//! floor, for "well-rounded contributor" gates a summed threshold can't express
+
+use serde::{Deserialize, Serialize};
+
+use crate::{RepIDProof, Result, ZKPError};
+
+/// Operation type of category count proofs
+pub const CATEGORY_COUNT_OPERATION: &str = "category_count";
+
+/// Public statement of a category count proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryCountStatement {
+    /// Score every counted category reaches
+    pub min_per_category: u32,
+    /// Number of categories that reach it, at least
+    pub k: u32,
+}
+
+/// Read the statement a verified category count proof was made for
+pub fn proof_statement(proof: &RepIDProof) -> Result<CategoryCountStatement> {
+    if proof.metadata.operation_type != CATEGORY_COUNT_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no category count statement",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(0..2) {
+        Some([min_per_category, k]) => Ok(CategoryCountStatement {
+            min_per_category: min_per_category.0 as u32,
+            k: k.0 as u32,
+        }),
+        _ => Err(ZKPError::MalformedProof("Category count proof needs floor and k inputs".to_string())),
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel};
+
+    #[test]
+    fn test_count_differs_from_sum() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let well_rounded = [
+            (RepIDCategory::Governance, 40),
+            (RepIDCategory::Community, 45),
+            (RepIDCategory::Technical, 50),
+            (RepIDCategory::DeFi, 5),
+        ];
+
+        let proof = zkp_system.prove_category_count(&well_rounded, 40, 3, "0xtest").unwrap();
+        assert!(zkp_system.verify_proof(&proof, None).unwrap());
+        assert_eq!(proof_statement(&proof).unwrap(), CategoryCountStatement { min_per_category: 40, k: 3 });
+
+        // A larger total concentrated in one category does not qualify
+        let specialist = [(RepIDCategory::DeFi, 500), (RepIDCategory::Community, 39), (RepIDCategory::Technical, 39)];
+        assert!(zkp_system.prove_category_count(&specialist, 40, 3, "0xtest").is_err());
+
+        // Splitting one category into several entries does not count it twice
+        let split = [(RepIDCategory::DeFi, 40), (RepIDCategory::DeFi, 40), (RepIDCategory::Community, 40)];
+        assert!(zkp_system.prove_category_count(&split, 40, 3, "0xtest").is_err());
+    }
+}
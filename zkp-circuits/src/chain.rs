@@ -0,0 +1,148 @@
+//! Proof Chaining
+//!
+//! Links each epoch proof to the previous one through a Poseidon2 chain
+//! commitment, so verifiers can demand continuous reputation history
+
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2;
+use crate::{RepIDProof, RepIDZKPSystem, Result, ZKPError, F};
+
+/// Operation type of chained threshold proofs
+pub const CHAINED_THRESHOLD_OPERATION: &str = "chained_threshold_verification";
+
+/// Previous-proof digest used by the first proof of a chain
+pub const GENESIS: F = F::ZERO;
+
+/// Field digest of a proof, used as the next link's private witness
+pub fn proof_digest(proof: &RepIDProof) -> F {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"RepID_ProofChain");
+    hasher.update(&proof.proof_data);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hasher.finalize().as_bytes()[..8]);
+    F::from_bytes(bytes)
+}
+
+/// Private link to the previous proof in a chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainLink {
+    /// Digest of the previous proof (`GENESIS` for the first epoch)
+    pub previous: F,
+    pub epoch: u64,
+}
+
+impl ChainLink {
+    /// First link of a new chain
+    pub fn genesis(epoch: u64) -> Self {
+        Self { previous: GENESIS, epoch }
+    }
+
+    /// Link following `previous`
+    pub fn after(previous: &RepIDProof, epoch: u64) -> Self {
+        Self {
+            previous: proof_digest(previous),
+            epoch,
+        }
+    }
+
+    /// Hash input: [previous, epoch]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        vec![self.previous, F::new(self.epoch)]
+    }
+
+    /// Public chain commitment
+    pub fn commitment(&self) -> F {
+        poseidon2::hash_elements(&self.to_field_elements())
+    }
+}
+
+/// Public inputs of a chained proof: (chain commitment, epoch)
+fn chain_inputs(proof: &RepIDProof) -> Result<(F, u64)> {
+    if proof.metadata.operation_type != CHAINED_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs cannot be part of a chain",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(2..4) {
+        Some([commitment, epoch]) => Ok((*commitment, epoch.0)),
+        _ => Err(ZKPError::MalformedProof("Chained proof needs a chain commitment and epoch".to_string())),
+    }
+}
+
+/// Verify an unbroken chain of consecutive epoch proofs
+///
+/// With `anchor` the first proof must follow it; otherwise it must start a chain.
+pub fn verify_chain(system: &RepIDZKPSystem, anchor: Option<&RepIDProof>, proofs: &[RepIDProof]) -> Result<()> {
+    let mut previous = anchor;
+
+    for (index, proof) in proofs.iter().enumerate() {
+        if !system.verify_proof(proof, None)? {
+            return Err(ZKPError::VerificationError(format!("Chain proof {} is invalid", index)));
+        }
+
+        let (commitment, epoch) = chain_inputs(proof)?;
+        let expected = match previous {
+            Some(prev) => {
+                let (_, prev_epoch) = chain_inputs(prev)?;
+                if epoch != prev_epoch + 1 {
+                    return Err(ZKPError::VerificationError(format!(
+                        "Chain gap: epoch {} follows epoch {}",
+                        epoch, prev_epoch
+                    )));
+                }
+                ChainLink::after(prev, epoch)
+            }
+            None => ChainLink::genesis(epoch),
+        };
+
+        if expected.commitment() != commitment {
+            return Err(ZKPError::VerificationError(format!("Chain proof {} does not link to its predecessor", index)));
+        }
+        previous = Some(proof);
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_chain_requires_consecutive_linked_proofs() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let scores = [(RepIDCategory::Community, 75)];
+
+        let mut chain: Vec<RepIDProof> = Vec::new();
+        for epoch in 1..=3 {
+            let proof = zkp_system
+                .prove_chained_threshold_verification(&request, &scores, chain.last(), epoch, "0xtest")
+                .unwrap()
+                .proof;
+            chain.push(proof);
+        }
+        assert!(verify_chain(&zkp_system, None, &chain).is_ok());
+        assert!(verify_chain(&zkp_system, Some(&chain[0]), &chain[1..]).is_ok());
+
+        // Dropping the middle epoch breaks the chain
+        let gapped = [chain[0].clone(), chain[2].clone()];
+        assert!(verify_chain(&zkp_system, None, &gapped).is_err());
+
+        // A fresh chain cannot be spliced onto someone else's history
+        let restarted = zkp_system
+            .prove_chained_threshold_verification(&request, &scores, None, 2, "0xtest")
+            .unwrap()
+            .proof;
+        assert!(verify_chain(&zkp_system, Some(&chain[0]), &[restarted]).is_err());
+    }
+}
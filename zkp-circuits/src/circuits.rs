@@ -0,0 +1,1705 @@
+//! Statement Circuits
+//!
+//! The constraint system of every proof is fixed by a `CircuitShape` (which
+//! sections its trace holds and how they link) and the proof's public
+//! inputs; private witness values never enter it. The prover fills a trace
+//! against the system built here and a verifier can rebuild the same system
+//! from the shape alone, so every link between sections is a constraint
+//! rather than a check the prover runs on itself.
+
+use serde::{Deserialize, Serialize};
+
+use crate::air::{
+    AbsenceAir, BiometricAir, CategoryCountAir, ConstraintSystem, CustomAir, Expr, FreshnessAir, NormalizationAir, RangeCheck,
+    SaturationAir, ScoreHistoryAir, ThresholdAir, ThresholdDecay, MAX_AGGREGATE_SCORE,
+};
+use crate::custom_stark::ExecutionTrace;
+use crate::history;
+use crate::issuance::CosignedAttestation;
+use crate::normalization::ScoreScale;
+use crate::oracle::{OracleStatement, CHAINS, CHAIN_LENGTH, DIGIT_BITS, MESSAGE_DIGITS};
+use crate::poseidon2::{self, Digest, Poseidon2Gadget, DIGEST_ELEMENTS, NUM_STEPS, RATE};
+use crate::public_inputs::PublicInputs;
+use crate::revocation::RevocableAttestation;
+use crate::rln::MESSAGE_INDEX_BITS;
+use crate::saturation::CategoryCap;
+use crate::sparse_merkle::{SmtPathGadget, KEY_LIMBS};
+use crate::sustained::{epoch_weights, WEIGHT_SCALE};
+use crate::wallet::SALT_ELEMENTS;
+use crate::wire;
+use crate::{Result, ZKPError, F};
+
+/// Score columns and decay of a threshold section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThresholdShape {
+    /// Category scores, then penalties
+    pub num_scores: usize,
+    pub decay: Option<ThresholdDecay>,
+    /// Tags of the leading category columns (empty when the statement hides them)
+    pub categories: Vec<F>,
+}
+
+impl ThresholdShape {
+    pub fn new(num_scores: usize, decay: Option<ThresholdDecay>) -> Self {
+        Self { num_scores, decay, categories: Vec::new() }
+    }
+
+    /// State the categories of the leading score columns
+    pub fn with_categories(mut self, categories: Vec<F>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// Public inputs stating the section's categories, then its floor and each column's decay factor
+    pub fn statement(&self) -> Vec<F> {
+        let mut statement = self.categories.clone();
+        if let Some(decay) = &self.decay {
+            statement.push(F::from_u32(decay.min_threshold));
+            for factor in &decay.factors {
+                statement.extend([F::from_i64(factor.retention.raw()), F::from_u32(factor.floor)]);
+            }
+        }
+        statement
+    }
+
+    /// Section comparing against `threshold` over the window ending at `timestamp`
+    pub fn air(&self, threshold: F, time_window: F, timestamp: F) -> Result<ThresholdAir> {
+        Ok(ThresholdAir::new(
+            self.num_scores,
+            aggregate_bound(threshold)?,
+            time_window.0,
+            timestamp.0,
+            self.decay.clone(),
+        ))
+    }
+}
+
+/// One snapshot leaf opened against an epoch root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotLeafShape {
+    /// Position of the leaf's epoch among the public (epoch, root) pairs
+    pub epoch: usize,
+    /// Category tag hashed into the leaf
+    pub category: F,
+    /// Siblings from the leaf to the root
+    pub depth: usize,
+}
+
+/// Structure of a proof's constraint system, apart from its public inputs
+///
+/// Carried in every proof; its digest ends the proof's public inputs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitShape {
+    /// Threshold over scores, the first `flagged` of them flagged absent or present
+    Threshold { scores: ThresholdShape, flagged: usize },
+    /// Threshold over normalized and saturated scores
+    AdjustedThreshold {
+        scores: ThresholdShape,
+        /// Score index and cap of every saturated score
+        capped: Vec<(usize, CategoryCap)>,
+        /// Score index and scale of every normalized score
+        scaled: Vec<(usize, ScoreScale)>,
+        flagged: usize,
+    },
+    /// Threshold over scores opened from a wide score commitment
+    CommittedThreshold { scores: ThresholdShape, opening_len: usize, positions: Vec<usize> },
+    /// One score revealed from a wide score commitment
+    ScoreOpening { opening_len: usize, position: usize },
+    /// Threshold over a committed category set
+    HiddenThreshold { scores: ThresholdShape, set_len: usize },
+    /// Threshold linked to the previous epoch's proof
+    ChainedThreshold { scores: ThresholdShape, link_len: usize },
+    /// Threshold over attestations no older than a bound
+    FreshThreshold { scores: ThresholdShape },
+    /// Threshold over co-signed attestations
+    CosignedThreshold { scores: ThresholdShape },
+    /// Threshold over attestations missing from a revocation list
+    UnrevokedThreshold { scores: ThresholdShape, list_len: usize },
+    /// Threshold over an oracle-signed total
+    OracleThreshold { statement_len: usize, levels: usize },
+    /// Threshold met or the designated verifier's secret known
+    DesignatedThreshold { scores: ThresholdShape, witness_len: usize },
+    /// Met threshold with the total encrypted to an escrow key
+    EscrowedThreshold { scores: ThresholdShape, key_len: usize, pad_len: usize },
+    /// Threshold over scores pooled from linked wallets
+    LinkedThreshold { scores: ThresholdShape, wallets: usize },
+    /// Score reaching a band of a committed distribution
+    RankBucket { num_scores: usize, distribution_len: usize, band_position: usize },
+    /// At least `k` categories reaching a floor
+    CategoryCount { num_scores: usize },
+    /// Decayed aggregate over epoch snapshots
+    SustainedThreshold { leaves: Vec<SnapshotLeafShape> },
+    /// Growth between two epoch snapshots; depths of the later leaves, then the earlier
+    ScoreDelta { depths: Vec<usize> },
+    /// Rate-limited signal from a member of an identity set
+    RateLimitedSignal,
+    /// Net of a score event history
+    HistoryThreshold { events: usize },
+    /// Biometric 4FA
+    Biometric,
+    /// Application-defined AIR over `height` witness rows
+    Air { height: usize },
+}
+
+impl CircuitShape {
+    /// Wide Poseidon2 digest of the shape's wire encoding, one element per byte
+    pub fn digest(&self) -> Result<Digest> {
+        let elements: Vec<F> = wire::to_bytes(self)?.into_iter().map(|byte| F::new(byte as u64)).collect();
+        Ok(poseidon2::hash_to_digest(&elements))
+    }
+
+    /// Operation a proof of this shape is verified as
+    pub fn proof_type(&self) -> &'static str {
+        match self {
+            CircuitShape::Threshold { .. } | CircuitShape::AdjustedThreshold { .. } => "threshold_verification",
+            CircuitShape::CommittedThreshold { .. } => "committed_threshold_verification",
+            CircuitShape::ScoreOpening { .. } => "score_opening",
+            CircuitShape::HiddenThreshold { .. } => "hidden_threshold_verification",
+            CircuitShape::ChainedThreshold { .. } => "chained_threshold_verification",
+            CircuitShape::FreshThreshold { .. } => "fresh_threshold_verification",
+            CircuitShape::CosignedThreshold { .. } => "cosigned_threshold_verification",
+            CircuitShape::UnrevokedThreshold { .. } => "unrevoked_threshold_verification",
+            CircuitShape::OracleThreshold { .. } => "oracle_threshold_verification",
+            CircuitShape::DesignatedThreshold { .. } => "designated_threshold_verification",
+            CircuitShape::EscrowedThreshold { .. } => "escrowed_threshold_verification",
+            CircuitShape::LinkedThreshold { .. } => "linked_threshold_verification",
+            CircuitShape::RankBucket { .. } => "rank_bucket",
+            CircuitShape::CategoryCount { .. } => "category_count",
+            CircuitShape::SustainedThreshold { .. } => "sustained_threshold",
+            CircuitShape::ScoreDelta { .. } => "score_delta",
+            CircuitShape::RateLimitedSignal => "rate_limited_signal",
+            CircuitShape::HistoryThreshold { .. } => "history_threshold",
+            CircuitShape::Biometric => "biometric_4fa",
+            CircuitShape::Air { .. } => "air",
+        }
+    }
+
+    /// Threshold section of shapes comparing scores against the public threshold
+    pub fn threshold_shape(&self) -> Option<&ThresholdShape> {
+        match self {
+            CircuitShape::Threshold { scores, .. }
+            | CircuitShape::AdjustedThreshold { scores, .. }
+            | CircuitShape::CommittedThreshold { scores, .. }
+            | CircuitShape::HiddenThreshold { scores, .. }
+            | CircuitShape::ChainedThreshold { scores, .. }
+            | CircuitShape::FreshThreshold { scores }
+            | CircuitShape::CosignedThreshold { scores }
+            | CircuitShape::UnrevokedThreshold { scores, .. }
+            | CircuitShape::DesignatedThreshold { scores, .. }
+            | CircuitShape::EscrowedThreshold { scores, .. }
+            | CircuitShape::LinkedThreshold { scores, .. } => Some(scores),
+            _ => None,
+        }
+    }
+
+    /// Public inputs the shape states, just ahead of its digest
+    pub fn statement(&self) -> Vec<F> {
+        match self {
+            CircuitShape::SustainedThreshold { leaves } => {
+                leaves.iter().flat_map(|leaf| [F::new(leaf.epoch as u64), leaf.category]).collect()
+            }
+            _ => self.threshold_shape().map_or_else(Vec::new, ThresholdShape::statement),
+        }
+    }
+
+    /// Position of the wallet commitment snapshot proofs open their leaves under
+    pub fn wallet_index(&self, public_inputs: &[F]) -> Option<usize> {
+        match self {
+            CircuitShape::SustainedThreshold { .. } => public_inputs.get(3).map(|count| 4 + 2 * count.0 as usize),
+            CircuitShape::ScoreDelta { depths } => Some(7 + depths.len() / 2),
+            _ => None,
+        }
+    }
+
+    fn wallet_commitment(&self, public_inputs: &[F]) -> Result<Digest> {
+        let start = self.wallet_index(public_inputs)
+            .ok_or_else(|| ZKPError::MalformedProof(format!("{} proofs carry no wallet commitment", self.proof_type())))?;
+        let mut commitment = [F::ZERO; DIGEST_ELEMENTS];
+        for (i, element) in commitment.iter_mut().enumerate() {
+            *element = public_input(public_inputs, start + i)?;
+        }
+        Ok(commitment)
+    }
+
+    /// Check `public_inputs` state what the shape's constraints use
+    fn check_statement(&self, public_inputs: &[F]) -> Result<()> {
+        if let Some(scores) = self.threshold_shape() {
+            if scores.categories.len() > scores.num_scores {
+                return Err(ZKPError::MalformedProof(format!(
+                    "Threshold section states {} categories for {} score columns",
+                    scores.categories.len(),
+                    scores.num_scores
+                )));
+            }
+            let decayed = scores.decay.as_ref().map(|decay| decay.factors.len());
+            if decayed.is_some_and(|decayed| decayed > scores.num_scores || !(scores.categories.is_empty() || scores.categories.len() == decayed)) {
+                return Err(ZKPError::MalformedProof("Threshold section decays other columns than its categories".to_string()));
+            }
+        }
+        if let CircuitShape::SustainedThreshold { leaves } = self {
+            if leaves.windows(2).any(|pair| (pair[0].epoch, pair[0].category.0) >= (pair[1].epoch, pair[1].category.0)) {
+                return Err(ZKPError::MalformedProof("Sustained proof opens leaves out of (epoch, category) order".to_string()));
+            }
+        }
+        let statement = self.statement();
+        let stated = public_inputs
+            .len()
+            .checked_sub(DIGEST_ELEMENTS + statement.len())
+            .map(|start| &public_inputs[start..start + statement.len()]);
+        if stated != Some(&statement[..]) {
+            return Err(ZKPError::MalformedProof("Public inputs do not state the shape's categories, decay and openings".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Constraint system of a proof of this shape over `public_inputs`
+    ///
+    /// The statement of a threshold section, or the leaves a sustained proof
+    /// opens, sits just ahead of the shape digest and must match the shape.
+    pub fn build(&self, public_inputs: &[F]) -> Result<Circuit> {
+        self.check_statement(public_inputs)?;
+        let input = |index: usize| public_input(public_inputs, index);
+        let circuit = |air: &dyn CustomAir, rows: usize| Circuit::new(air, rows, public_inputs);
+        match self {
+            CircuitShape::Threshold { scores, flagged } => {
+                let air = FlaggedThresholdAir::new(scores.air(input(0)?, input(1)?, input(2)?)?, *flagged);
+                Ok(circuit(&air, ThresholdAir::ROWS))
+            }
+            CircuitShape::AdjustedThreshold { scores, capped, scaled, flagged } => {
+                let threshold = scores.air(input(0)?, input(1)?, input(3)?)?;
+                let air = AdjustedThresholdAir::new(threshold, capped, scaled, *flagged);
+                air.saturation.check_caps()?;
+                air.normalization.check_scales()?;
+                Ok(circuit(&air, ThresholdAir::ROWS))
+            }
+            CircuitShape::CommittedThreshold { scores, opening_len, positions } => {
+                // Each score column opens its own score slot [.., tag, score, ..] once, under its stated tag
+                let on_score_slot = |&position: &usize| position >= 2 && position % 2 == 0 && position < *opening_len;
+                if !positions.iter().all(on_score_slot) || positions.windows(2).any(|pair| pair[0] >= pair[1]) {
+                    return Err(ZKPError::MalformedProof("Committed scores must open distinct score slots in order".to_string()));
+                }
+                if positions.len() != scores.num_scores || scores.categories.len() != positions.len() {
+                    return Err(ZKPError::MalformedProof(format!(
+                        "Committed threshold opens {} scores for {} columns and {} stated categories",
+                        positions.len(),
+                        scores.num_scores,
+                        scores.categories.len()
+                    )));
+                }
+                let threshold = scores.air(input(0)?, input(1)?, input(2 + DIGEST_ELEMENTS)?)?;
+                let linked = positions.iter().enumerate().map(|(i, &position)| (position, threshold.score_column(i))).collect();
+                let mut air = OpeningAir::new(Some(threshold), *opening_len);
+                air.pinned = positions.iter().zip(&scores.categories).map(|(&position, &tag)| (position - 1, tag)).collect();
+                air.linked = linked;
+                air.digest = (2..2 + DIGEST_ELEMENTS).map(input).collect::<Result<_>>()?;
+                air.meets_threshold = true;
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::ScoreOpening { opening_len, position } => {
+                if *position == 0 {
+                    return Err(ZKPError::MalformedProof("Score position has no tag before it".to_string()));
+                }
+                let mut air = OpeningAir::new(None, *opening_len);
+                air.pinned = vec![(position - 1, input(DIGEST_ELEMENTS)?), (*position, input(DIGEST_ELEMENTS + 1)?)];
+                air.digest = (0..DIGEST_ELEMENTS).map(input).collect::<Result<_>>()?;
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::HiddenThreshold { scores, set_len } => {
+                let mut air = OpeningAir::new(Some(scores.air(input(0)?, input(1)?, input(2 + DIGEST_ELEMENTS)?)?), *set_len);
+                air.digest = (2..2 + DIGEST_ELEMENTS).map(input).collect::<Result<_>>()?;
+                air.meets_threshold = true;
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::ChainedThreshold { scores, link_len } => {
+                let mut air = OpeningAir::new(Some(scores.air(input(0)?, input(1)?, input(4)?)?), *link_len);
+                air.pinned = vec![(1, input(3)?)];
+                air.digest = vec![input(2)?];
+                air.meets_threshold = true;
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::FreshThreshold { scores } => {
+                let threshold = scores.air(input(0)?, input(1)?, input(4)?)?;
+                let air = FreshThresholdAir::new(threshold, input(2)?.0, input(3)?.0);
+                Ok(circuit(&air, ThresholdAir::ROWS))
+            }
+            CircuitShape::CosignedThreshold { scores } => {
+                if input(4)? != F::new(scores.num_scores as u64) {
+                    return Err(ZKPError::MalformedProof(format!(
+                        "Co-signed threshold proof publishes {} attestations for {} scores",
+                        input(4)?.0,
+                        scores.num_scores
+                    )));
+                }
+                let attestations = (0..scores.num_scores)
+                    .map(|i| Ok((input(5 + 2 * i)?, input(6 + 2 * i)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let air = CosignedThresholdAir::new(scores.air(input(0)?, input(1)?, input(3)?)?, attestations);
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::UnrevokedThreshold { scores, list_len } => {
+                let air = UnrevokedThresholdAir::new(scores.air(input(0)?, input(1)?, input(3)?)?, *list_len, input(2)?);
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::OracleThreshold { statement_len, levels } => {
+                let threshold = ThresholdShape::new(1, None).air(input(0)?, input(1)?, input(5)?)?;
+                let air = OracleThresholdAir::new(threshold, *statement_len, *levels, input(2)?, input(3)?, input(4)?);
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::DesignatedThreshold { scores, witness_len } => {
+                let air = DesignatedThresholdAir::new(scores.air(input(0)?, input(1)?, input(3)?)?, *witness_len, input(2)?);
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::EscrowedThreshold { scores, key_len, pad_len } => {
+                let threshold = scores.air(input(0)?, input(1)?, input(2)?)?;
+                let key_commitment = (3..3 + DIGEST_ELEMENTS).map(input).collect::<Result<_>>()?;
+                let ciphertext = (3 + DIGEST_ELEMENTS..3 + 2 * DIGEST_ELEMENTS).map(input).collect::<Result<_>>()?;
+                let air = EscrowedThresholdAir::new(threshold, *key_len, *pad_len, key_commitment, ciphertext);
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::LinkedThreshold { scores, wallets } => {
+                if input(4)?.0 != *wallets as u64 {
+                    return Err(ZKPError::MalformedProof(format!(
+                        "Proof links {} wallets but its public inputs count {}",
+                        wallets,
+                        input(4)?.0
+                    )));
+                }
+                let threshold = scores.air(input(0)?, input(1)?, input(5)?)?;
+                let air = LinkedThresholdAir::new(threshold, *wallets, input(2)?, input(3)?);
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::RankBucket { num_scores, distribution_len, band_position } => {
+                let threshold = ThresholdShape::new(*num_scores, None).air(F::ZERO, F::ONE, input(2)?)?.with_private_threshold();
+                let mut air = OpeningAir::new(Some(threshold), *distribution_len);
+                air.pinned = vec![(*band_position, input(0)?)];
+                air.linked = vec![(band_position + 1, 0)];
+                air.digest = vec![input(1)?];
+                air.meets_threshold = true;
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::CategoryCount { num_scores } => {
+                let air = CategoryCountAir::new(*num_scores, input(0)?.0 as u32, input(1)?.0 as u32, input(2)?.0);
+                Ok(circuit(&air, CategoryCountAir::ROWS))
+            }
+            CircuitShape::SustainedThreshold { leaves } => {
+                let (threshold, decay_rate, count) = (input(0)?, input(1)?, input(3)?.0 as usize);
+                let decay_rate = u16::try_from(decay_rate.0)
+                    .map_err(|_| ZKPError::MalformedProof(format!("Decay rate {} is out of range", decay_rate.0)))?;
+                let weights = epoch_weights(decay_rate, count);
+                let scaled = F::new(threshold.0 * WEIGHT_SCALE as u64);
+                let openings = leaves
+                    .iter()
+                    .map(|leaf| {
+                        let age = count.checked_sub(leaf.epoch + 1).ok_or_else(|| {
+                            ZKPError::MalformedProof(format!("Leaf of epoch {} beyond the {} public epochs", leaf.epoch, count))
+                        })?;
+                        Ok(SnapshotOpening {
+                            coefficient: F::from_u32(weights[age]),
+                            category: leaf.category,
+                            depth: leaf.depth,
+                            root: input(5 + 2 * leaf.epoch)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let threshold = ThresholdShape::new(openings.len(), None).air(scaled, F::ONE, input(2)?)?;
+                let air = SnapshotOpeningsAir::new(threshold, openings, self.wallet_commitment(public_inputs)?);
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::ScoreDelta { depths } => {
+                let categories = input(1)?.0 as usize;
+                if depths.len() != 2 * categories {
+                    return Err(ZKPError::MalformedProof(format!(
+                        "Delta proof opens {} leaves for {} categories",
+                        depths.len(),
+                        categories
+                    )));
+                }
+                let tags = (7..7 + categories).map(input).collect::<Result<Vec<_>>>()?;
+                if tags.iter().enumerate().any(|(i, tag)| tags[..i].contains(tag)) {
+                    return Err(ZKPError::MalformedProof("Delta proof opens a category more than once".to_string()));
+                }
+                let (from_root, to_root) = (input(4)?, input(6)?);
+                let openings = depths
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &depth)| {
+                        let later = i < categories;
+                        Ok(SnapshotOpening {
+                            coefficient: if later { F::ONE } else { F::ZERO - F::ONE },
+                            category: tags[i % categories],
+                            depth,
+                            root: if later { to_root } else { from_root },
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let threshold = ThresholdShape::new(openings.len(), None).air(input(0)?, F::ONE, input(2)?)?;
+                let air = SnapshotOpeningsAir::new(threshold, openings, self.wallet_commitment(public_inputs)?);
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::RateLimitedSignal => {
+                let air = RateLimitedSignalAir {
+                    root: input(0)?,
+                    limit: input(1)?,
+                    external_nullifier: input(4)?,
+                    signal: input(5)?,
+                    share: input(6)?,
+                    nullifier: input(7)?,
+                };
+                Ok(circuit(&air, air.rows()))
+            }
+            CircuitShape::HistoryThreshold { events } => {
+                let air = ScoreHistoryAir::new(aggregate_bound(input(0)?)?, input(1)?.0, input(2)?.0);
+                Ok(circuit(&air, history::trace_height(*events)))
+            }
+            CircuitShape::Biometric => Ok(circuit(&BiometricAir::new(input(0)?), 4)),
+            CircuitShape::Air { .. } => Err(ZKPError::ConfigError(
+                "Application AIR proofs are rebuilt from the AIR, not their shape".to_string(),
+            )),
+        }
+    }
+}
+
+fn public_input(public_inputs: &[F], index: usize) -> Result<F> {
+    public_inputs
+        .get(index)
+        .copied()
+        .ok_or_else(|| ZKPError::MalformedProof(format!("Proof is missing public input {}", index)))
+}
+
+/// A threshold the aggregate comparison is sound for
+fn aggregate_bound(threshold: F) -> Result<u32> {
+    if threshold.0 >= MAX_AGGREGATE_SCORE {
+        return Err(ZKPError::LimitExceeded {
+            limit: "threshold".to_string(),
+            actual: threshold.0,
+            max: MAX_AGGREGATE_SCORE - 1,
+        });
+    }
+    Ok(threshold.0 as u32)
+}
+
+/// Constraint system of a statement, with the section binding its public inputs
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    pub system: ConstraintSystem,
+    /// Section hashing the public inputs, right of the statement's columns
+    pub public_inputs: Poseidon2Gadget,
+}
+
+impl Circuit {
+    /// `air` over at least `rows` rows, padded to a power of two tall enough for the public inputs
+    pub fn new(air: &dyn CustomAir, rows: usize, public_inputs: &[F]) -> Self {
+        let height = Self::height(rows, public_inputs);
+        Self::bind(air.constraint_system(height), public_inputs)
+    }
+
+    /// `system` tiled to hold the public input section
+    pub fn tiled(system: &ConstraintSystem, public_inputs: &[F]) -> Result<Self> {
+        let height = Self::height(system.height(), public_inputs);
+        Ok(Self::bind(system.tiled(height)?, public_inputs))
+    }
+
+    fn height(rows: usize, public_inputs: &[F]) -> usize {
+        rows.max(Poseidon2Gadget::rows_for(public_inputs.len())).next_power_of_two()
+    }
+
+    /// Append a section absorbing the canonical public inputs and reaching their digest
+    fn bind(mut system: ConstraintSystem, public_inputs: &[F]) -> Self {
+        let inputs = PublicInputs::new(public_inputs.to_vec());
+        let values = inputs.canonical_encoding();
+        let gadget = Poseidon2Gadget::new(system.width());
+        gadget.constrain(&mut system, "public_inputs", &[(0, values.len())]);
+        for (position, &value) in values.iter().enumerate() {
+            let (row, column) = gadget.absorb_cell(position);
+            system.constrain_at(row, format!("public_input_{}", position), Expr::cell(column) - value);
+        }
+        let digest_row = Poseidon2Gadget::rows_for(values.len()) - 1;
+        for (i, &element) in inputs.digest().iter().enumerate() {
+            let cell = Expr::cell(gadget.state_column(i));
+            system.constrain_at(digest_row, format!("public_inputs_digest_{}", i), cell - element);
+        }
+        system.reserve(gadget.column_offset + Poseidon2Gadget::COLUMNS);
+        Self { system, public_inputs: gadget }
+    }
+
+    /// Empty trace of the system's dimensions
+    pub fn trace(&self) -> ExecutionTrace {
+        ExecutionTrace::new(self.system.width(), self.system.height())
+    }
+
+    /// Fill the public input section
+    pub fn fill_public_inputs(&self, trace: &mut ExecutionTrace, public_inputs: &[F]) {
+        let values = PublicInputs::new(public_inputs.to_vec()).canonical_encoding();
+        self.public_inputs.generate_trace(trace, 0, &values);
+    }
+}
+
+/// Constrain a threshold section's comparison to hold
+fn require_met(system: &mut ConstraintSystem, threshold: &ThresholdAir) {
+    let meets_threshold = Expr::cell(threshold.meets_threshold_column());
+    system.constrain("threshold_met", meets_threshold - F::ONE);
+}
+
+/// Met threshold section, with flags on its leading score columns when any category is absent
+#[derive(Debug, Clone)]
+pub struct FlaggedThresholdAir {
+    pub threshold: ThresholdAir,
+    pub absence: Option<AbsenceAir>,
+}
+
+impl FlaggedThresholdAir {
+    pub fn new(threshold: ThresholdAir, flagged: usize) -> Self {
+        let absence = (flagged > 0).then(|| {
+            AbsenceAir::new(threshold.width(), (0..flagged).map(|i| threshold.score_column(i)).collect())
+        });
+        Self { threshold, absence }
+    }
+}
+
+impl CustomAir for FlaggedThresholdAir {
+    fn width(&self) -> usize {
+        self.absence.as_ref().map_or(self.threshold.width(), AbsenceAir::width)
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        self.threshold.add_constraints(system);
+        require_met(system, &self.threshold);
+        if let Some(absence) = &self.absence {
+            absence.add_constraints(system);
+        }
+    }
+}
+
+/// Threshold section whose capped scores saturate, and scaled scores normalize, from native scores
+///
+/// A saturation section holds the uncapped score behind every capped score
+/// column; a normalization section then holds the native score behind every
+/// scaled score, feeding the saturation input when the category is also capped.
+#[derive(Debug, Clone)]
+pub struct AdjustedThresholdAir {
+    pub threshold: ThresholdAir,
+    pub saturation: SaturationAir,
+    pub normalization: NormalizationAir,
+    pub absence: Option<AbsenceAir>,
+}
+
+impl AdjustedThresholdAir {
+    pub fn new(threshold: ThresholdAir, capped: &[(usize, CategoryCap)], scaled: &[(usize, ScoreScale)], flagged: usize) -> Self {
+        let saturation = SaturationAir::new(
+            threshold.width(),
+            capped.iter().map(|(index, cap)| (threshold.score_column(*index), cap.clone())).collect(),
+        );
+        let normalization = NormalizationAir::new(
+            saturation.width(),
+            scaled
+                .iter()
+                .map(|(index, scale)| {
+                    let column = capped
+                        .iter()
+                        .position(|(capped_index, _)| capped_index == index)
+                        .map_or(threshold.score_column(*index), |i| saturation.raw_column(i));
+                    (column, scale.clone())
+                })
+                .collect(),
+        );
+        let absence = (flagged > 0).then(|| {
+            AbsenceAir::new(normalization.width(), (0..flagged).map(|i| threshold.score_column(i)).collect())
+        });
+        Self { threshold, saturation, normalization, absence }
+    }
+}
+
+impl CustomAir for AdjustedThresholdAir {
+    fn width(&self) -> usize {
+        self.absence.as_ref().map_or(self.normalization.width(), AbsenceAir::width)
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        self.threshold.add_constraints(system);
+        require_met(system, &self.threshold);
+        self.saturation.add_constraints(system);
+        self.normalization.add_constraints(system);
+        if let Some(absence) = &self.absence {
+            absence.add_constraints(system);
+        }
+    }
+}
+
+/// Threshold section beside a Poseidon2 opening of a committed preimage
+///
+/// The opening starts on row 0 right of the threshold section (or at column
+/// 0 without one). Pinned absorb positions hold public values, linked ones
+/// equal a wired column of the threshold section, and the digest reaches
+/// the public commitment elements.
+#[derive(Debug, Clone)]
+pub struct OpeningAir {
+    pub threshold: Option<ThresholdAir>,
+    pub input_len: usize,
+    /// (absorb position, public value)
+    pub pinned: Vec<(usize, F)>,
+    /// (absorb position, wired column it equals)
+    pub linked: Vec<(usize, usize)>,
+    /// Leading digest elements the opening must reach
+    pub digest: Vec<F>,
+    /// Require the threshold section's comparison to hold
+    pub meets_threshold: bool,
+}
+
+impl OpeningAir {
+    pub fn new(threshold: Option<ThresholdAir>, input_len: usize) -> Self {
+        Self {
+            threshold,
+            input_len,
+            pinned: Vec::new(),
+            linked: Vec::new(),
+            digest: Vec::new(),
+            meets_threshold: false,
+        }
+    }
+
+    pub fn gadget(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.threshold.as_ref().map_or(0, CustomAir::width))
+    }
+
+    pub fn rows(&self) -> usize {
+        let threshold_rows = if self.threshold.is_some() { ThresholdAir::ROWS } else { 1 };
+        threshold_rows.max(Poseidon2Gadget::rows_for(self.input_len))
+    }
+
+    /// Hash the preimage into the opening section
+    pub fn fill(&self, trace: &mut ExecutionTrace, inputs: &[F]) {
+        self.gadget().generate_trace(trace, 0, inputs);
+    }
+}
+
+impl CustomAir for OpeningAir {
+    fn width(&self) -> usize {
+        self.gadget().column_offset + Poseidon2Gadget::COLUMNS
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        if let Some(threshold) = &self.threshold {
+            threshold.add_constraints(system);
+            if self.meets_threshold {
+                require_met(system, threshold);
+            }
+        }
+        let gadget = self.gadget();
+        gadget.constrain(system, "opening", &[(0, self.input_len)]);
+        for &(position, value) in &self.pinned {
+            let (row, column) = gadget.absorb_cell(position);
+            system.constrain_at(row, format!("opening_input_{}", position), Expr::cell(column) - value);
+        }
+        for &(position, linked) in &self.linked {
+            let (row, column) = gadget.absorb_cell(position);
+            system.constrain_at(row, format!("opening_link_{}", position), Expr::cell(column) - Expr::cell(linked));
+        }
+        let digest_row = Poseidon2Gadget::rows_for(self.input_len) - 1;
+        for (i, &element) in self.digest.iter().enumerate() {
+            system.constrain_at(digest_row, format!("opening_digest_{}", i), Expr::cell(gadget.state_column(i)) - element);
+        }
+    }
+}
+
+/// Threshold section over attestations whose issuance times are within a public age bound
+#[derive(Debug, Clone)]
+pub struct FreshThresholdAir {
+    pub threshold: ThresholdAir,
+    pub freshness: FreshnessAir,
+}
+
+impl FreshThresholdAir {
+    pub fn new(threshold: ThresholdAir, now: u64, max_age: u64) -> Self {
+        let freshness = FreshnessAir::new(threshold.width(), threshold.num_scores, now, max_age);
+        Self { threshold, freshness }
+    }
+}
+
+impl CustomAir for FreshThresholdAir {
+    fn width(&self) -> usize {
+        self.freshness.width()
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        self.threshold.add_constraints(system);
+        require_met(system, &self.threshold);
+        self.freshness.add_constraints(system);
+    }
+}
+
+/// Threshold section over co-signed attestations
+///
+/// One gadget hashes each attestation's digest from the score the threshold
+/// section sums, one section per attestation; the category tag it hashes and
+/// the digest it yields are pinned to the public pair issuers' signatures
+/// are checked against.
+#[derive(Debug, Clone)]
+pub struct CosignedThresholdAir {
+    pub threshold: ThresholdAir,
+    /// Public category tag and digest of every attestation, in score order
+    pub attestations: Vec<(F, F)>,
+}
+
+impl CosignedThresholdAir {
+    /// Hashed elements of an attestation digest
+    const DIGEST_LEN: usize = CosignedAttestation::SCORE_POSITION + 2;
+
+    pub fn new(threshold: ThresholdAir, attestations: Vec<(F, F)>) -> Self {
+        Self { threshold, attestations }
+    }
+
+    pub fn gadget(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.threshold.width())
+    }
+
+    fn start(index: usize) -> usize {
+        index * Poseidon2Gadget::rows_for(Self::DIGEST_LEN)
+    }
+
+    pub fn rows(&self) -> usize {
+        ThresholdAir::ROWS.max(Self::start(self.attestations.len()))
+    }
+
+    /// Hash every attestation's digest inputs
+    pub fn fill(&self, trace: &mut ExecutionTrace, attestations: &[Vec<F>]) {
+        for (i, inputs) in attestations.iter().enumerate() {
+            self.gadget().generate_trace(trace, Self::start(i), inputs);
+        }
+    }
+}
+
+impl CustomAir for CosignedThresholdAir {
+    fn width(&self) -> usize {
+        self.threshold.width() + Poseidon2Gadget::COLUMNS
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        self.threshold.add_constraints(system);
+        require_met(system, &self.threshold);
+        let gadget = self.gadget();
+        let digest_rows = Poseidon2Gadget::rows_for(Self::DIGEST_LEN);
+        let sections: Vec<(usize, usize)> = (0..self.attestations.len()).map(|i| (Self::start(i), Self::DIGEST_LEN)).collect();
+        gadget.constrain(system, "attestation_digest", &sections);
+
+        // Each digest hashes the summed score under its public category and yields the public digest
+        let (score_row, score_column) = gadget.absorb_cell(CosignedAttestation::SCORE_POSITION);
+        let (category_row, category_column) = gadget.absorb_cell(CosignedAttestation::CATEGORY_POSITION);
+        for (i, &(category, digest)) in self.attestations.iter().enumerate() {
+            let start = Self::start(i);
+            let hashed = Expr::cell(self.threshold.score_column(i)) - Expr::cell(score_column);
+            system.constrain_at(start + score_row, format!("attestation_{}_score", i), hashed);
+            system.constrain_at(start + category_row, format!("attestation_{}_category", i), Expr::cell(category_column) - category);
+            system.constrain_at(start + digest_rows - 1, format!("attestation_{}_digest", i), Expr::cell(gadget.state_column(0)) - digest);
+        }
+    }
+}
+
+/// Threshold section over attestations absent from a revocation list
+///
+/// One gadget hashes each attestation's id from the score the threshold
+/// section sums, in sections of one permutation each; another opens the
+/// list against the public root. Wired columns carry every id down the
+/// trace, and on the first row of each list block, per attestation and rate
+/// slot, an inverse column proves the id differs from the revoked id
+/// absorbed there.
+#[derive(Debug, Clone)]
+pub struct UnrevokedThresholdAir {
+    pub threshold: ThresholdAir,
+    pub list_len: usize,
+    pub root: F,
+}
+
+impl UnrevokedThresholdAir {
+    /// Hashed elements of an attestation id
+    const ID_LEN: usize = RevocableAttestation::SCORE_POSITION + 2;
+
+    pub fn new(threshold: ThresholdAir, list_len: usize, root: F) -> Self {
+        Self { threshold, list_len, root }
+    }
+
+    pub fn id_gadget(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.threshold.width())
+    }
+
+    pub fn list_gadget(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.threshold.width() + Poseidon2Gadget::COLUMNS)
+    }
+
+    fn id_start(index: usize) -> usize {
+        index * Poseidon2Gadget::rows_for(Self::ID_LEN)
+    }
+
+    /// Id of attestation `index` on every row
+    pub fn id_column(&self, index: usize) -> usize {
+        self.threshold.width() + 2 * Poseidon2Gadget::COLUMNS + index
+    }
+
+    /// Inverse of attestation `index`'s id less the revoked id in rate slot `slot`
+    pub fn inverse_column(&self, index: usize, slot: usize) -> usize {
+        self.id_column(self.threshold.num_scores) + index * RATE + slot
+    }
+
+    pub fn rows(&self) -> usize {
+        ThresholdAir::ROWS
+            .max(Self::id_start(self.threshold.num_scores))
+            .max(Poseidon2Gadget::rows_for(self.list_len))
+    }
+
+    /// Hash every id and the list, and write the id wires and inverses
+    pub fn fill(&self, trace: &mut ExecutionTrace, attestations: &[Vec<F>], list: &[F]) -> Result<()> {
+        let ids: Vec<F> = attestations
+            .iter()
+            .enumerate()
+            .map(|(i, inputs)| self.id_gadget().generate_trace(trace, Self::id_start(i), inputs))
+            .collect();
+        self.list_gadget().generate_trace(trace, 0, list);
+        for row in 0..trace.height {
+            for (i, &id) in ids.iter().enumerate() {
+                trace.set(row, self.id_column(i), id);
+            }
+        }
+        for (position, &revoked) in list.iter().enumerate() {
+            let (row, _) = self.list_gadget().absorb_cell(position);
+            for (i, &id) in ids.iter().enumerate() {
+                let inverse = (id - revoked)
+                    .inverse()
+                    .ok_or_else(|| ZKPError::CircuitError("Attestation id matches a revoked id".to_string()))?;
+                trace.set(row, self.inverse_column(i, position % RATE), inverse);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CustomAir for UnrevokedThresholdAir {
+    fn width(&self) -> usize {
+        self.inverse_column(self.threshold.num_scores, 0)
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        self.threshold.add_constraints(system);
+        require_met(system, &self.threshold);
+        let (ids, list) = (self.id_gadget(), self.list_gadget());
+        let id_rows = Poseidon2Gadget::rows_for(Self::ID_LEN);
+        let sections: Vec<(usize, usize)> = (0..self.threshold.num_scores).map(|i| (Self::id_start(i), Self::ID_LEN)).collect();
+        ids.constrain(system, "attestation_id", &sections);
+        list.constrain(system, "revocation_list", &[(0, self.list_len)]);
+        let root_row = Poseidon2Gadget::rows_for(self.list_len) - 1;
+        system.constrain_at(root_row, "revocation_root", Expr::cell(list.state_column(0)) - self.root);
+
+        // Each id hashes the summed score and is wired to its digest
+        let (score_row, score_column) = ids.absorb_cell(RevocableAttestation::SCORE_POSITION);
+        for i in 0..self.threshold.num_scores {
+            let id = Expr::cell(self.id_column(i));
+            system.wire(format!("attestation_{}_id_constant", i), self.id_column(i));
+            system.constrain_at(Self::id_start(i) + id_rows - 1, format!("attestation_{}_id", i), &id - Expr::cell(ids.state_column(0)));
+            let hashed = Expr::cell(self.threshold.score_column(i)) - Expr::cell(score_column);
+            system.constrain_at(Self::id_start(i) + score_row, format!("attestation_{}_score", i), hashed);
+        }
+
+        // Every listed id differs from every attestation id
+        for slot in 0..RATE {
+            let listed = system.selector((slot..self.list_len).step_by(RATE).map(|position| list.absorb_cell(position).0));
+            let revoked = Expr::cell(list.absorb_cell(slot).1);
+            for i in 0..self.threshold.num_scores {
+                let difference = Expr::cell(self.id_column(i)) - &revoked;
+                let inverse = Expr::cell(self.inverse_column(i, slot));
+                system.constrain(format!("attestation_{}_unrevoked_{}", i, slot), &listed * (difference * inverse - F::ONE));
+            }
+        }
+    }
+}
+
+/// Merkle path section: the leaf section, then one node section per level
+///
+/// Each node absorbs the digest on the row above it on the side its
+/// direction bit selects.
+fn constrain_path(system: &mut ConstraintSystem, name: &str, gadget: Poseidon2Gadget, direction_column: usize, node_rows: &[usize]) {
+    let one = F::ONE;
+    let node = system.selector(node_rows.iter().copied());
+    let direction = Expr::cell(direction_column);
+    let child = Expr::rotated(gadget.state_column(0), -1);
+    let [left, right] = [0, 1].map(|position| Expr::cell(gadget.absorb_cell(position).1));
+    system.constrain(format!("{}_direction_boolean", name), &node * &direction * (&direction - one));
+    system.constrain(format!("{}_left_child", name), &node * (one - &direction) * (left - &child));
+    system.constrain(format!("{}_right_child", name), node * &direction * (right - child));
+}
+
+/// Hash node sections from `start_row` up from `leaf`, returning the root
+fn fill_path(
+    trace: &mut ExecutionTrace,
+    gadget: Poseidon2Gadget,
+    direction_column: usize,
+    start_row: usize,
+    leaf: F,
+    siblings: &[F],
+    leaf_index: u64,
+) -> F {
+    let section_rows = Poseidon2Gadget::rows_for(2);
+    siblings.iter().enumerate().fold(leaf, |node, (level, &sibling)| {
+        let row = start_row + level * section_rows;
+        let bit = (leaf_index >> level) & 1;
+        let pair = if bit == 0 { [node, sibling] } else { [sibling, node] };
+        trace.set(row, direction_column, F::new(bit));
+        gadget.generate_trace(trace, row, &pair)
+    })
+}
+
+/// Threshold section over an oracle-signed total
+///
+/// One gadget section hashes the signed statement. Each signature chain
+/// then takes `CHAIN_LENGTH` step sections that either hash the running
+/// value or pass it through, the output column carrying it to the next
+/// step; its pass-through count is the signed digit. Accumulators over the
+/// steps recompose the message digits into the statement digest and check
+/// the checksum digits. The chain ends, wired, hash to a key leaf opening
+/// against the public oracle root.
+///
+/// Column layout after the gadget: active flag, direction bit, step output,
+/// one wired end per chain, the message and checksum accumulators, and the
+/// wired statement digest.
+#[derive(Debug, Clone)]
+pub struct OracleThresholdAir {
+    pub threshold: ThresholdAir,
+    pub statement_len: usize,
+    pub levels: usize,
+    pub root: F,
+    pub wallet_commitment: F,
+    pub epoch: F,
+}
+
+impl OracleThresholdAir {
+    pub fn new(threshold: ThresholdAir, statement_len: usize, levels: usize, root: F, wallet_commitment: F, epoch: F) -> Self {
+        Self {
+            threshold,
+            statement_len,
+            levels,
+            root,
+            wallet_commitment,
+            epoch,
+        }
+    }
+
+    pub fn gadget(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.threshold.width())
+    }
+
+    pub fn active_column(&self) -> usize {
+        self.threshold.width() + Poseidon2Gadget::COLUMNS
+    }
+
+    pub fn direction_column(&self) -> usize {
+        self.active_column() + 1
+    }
+
+    pub fn output_column(&self) -> usize {
+        self.active_column() + 2
+    }
+
+    pub fn end_column(&self, chain: usize) -> usize {
+        self.active_column() + 3 + chain
+    }
+
+    pub fn message_column(&self) -> usize {
+        self.end_column(CHAINS)
+    }
+
+    pub fn checksum_column(&self) -> usize {
+        self.message_column() + 1
+    }
+
+    pub fn statement_column(&self) -> usize {
+        self.message_column() + 2
+    }
+
+    pub fn step_start(&self, chain: usize, step: usize) -> usize {
+        Poseidon2Gadget::rows_for(self.statement_len) + (chain * CHAIN_LENGTH as usize + step) * Poseidon2Gadget::rows_for(1)
+    }
+
+    pub fn leaf_start(&self) -> usize {
+        self.step_start(CHAINS, 0)
+    }
+
+    pub fn node_start(&self, level: usize) -> usize {
+        self.leaf_start() + Poseidon2Gadget::rows_for(CHAINS) + level * Poseidon2Gadget::rows_for(2)
+    }
+
+    pub fn rows(&self) -> usize {
+        ThresholdAir::ROWS.max(self.node_start(self.levels))
+    }
+
+    fn steps(&self) -> impl Iterator<Item = (usize, usize)> {
+        (0..CHAINS).flat_map(|chain| (0..CHAIN_LENGTH as usize).map(move |step| (chain, step)))
+    }
+
+    /// Hash the statement, walk each chain `digits` steps short of its end and open the key leaf
+    pub fn fill(&self, trace: &mut ExecutionTrace, statement: &[F], chains: &[F], digits: &[u32], siblings: &[F], leaf_index: u64) {
+        let gadget = self.gadget();
+        let digest = gadget.generate_trace(trace, 0, statement);
+        let (mut message, mut checksum) = (F::ZERO, F::ZERO);
+        let mut ends = Vec::with_capacity(CHAINS);
+        for (chain, (&start, &digit)) in chains.iter().zip(digits).enumerate() {
+            let mut value = start;
+            let (message_weight, checksum_weight) = Self::weights(chain);
+            for step in 0..CHAIN_LENGTH as usize {
+                let row = self.step_start(chain, step);
+                let hashed = gadget.generate_trace(trace, row, &[value]);
+                let active = step as u32 >= digit;
+                if active {
+                    value = hashed;
+                } else {
+                    message += message_weight;
+                    checksum += checksum_weight;
+                }
+                trace.set(row, self.active_column(), F::new(active as u64));
+                trace.set(row, self.output_column(), value);
+                trace.set(row, self.message_column(), message);
+                trace.set(row, self.checksum_column(), checksum);
+            }
+            ends.push(value);
+        }
+        for row in 0..trace.height {
+            for (chain, &end) in ends.iter().enumerate() {
+                trace.set(row, self.end_column(chain), end);
+            }
+            trace.set(row, self.statement_column(), digest);
+        }
+        let leaf = gadget.generate_trace(trace, self.leaf_start(), &ends);
+        fill_path(trace, gadget, self.direction_column(), self.node_start(0), leaf, siblings, leaf_index);
+    }
+
+    /// Weight of one pass-through of `chain` in the message and checksum accumulators
+    ///
+    /// Message digits recompose the digest; the checksum accumulator adds each
+    /// message digit once and the checksum digits at their place values, so
+    /// it totals `CHAIN_LENGTH * MESSAGE_DIGITS` exactly when the checksum is
+    /// the signed one.
+    fn weights(chain: usize) -> (F, F) {
+        let radix = F::new(1 << DIGIT_BITS);
+        match chain < MESSAGE_DIGITS {
+            true => (radix.pow(chain as u64), F::ONE),
+            false => (F::ZERO, radix.pow((chain - MESSAGE_DIGITS) as u64)),
+        }
+    }
+}
+
+impl CustomAir for OracleThresholdAir {
+    fn width(&self) -> usize {
+        self.statement_column() + 1
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let one = F::ONE;
+        let gadget = self.gadget();
+        let step_rows = Poseidon2Gadget::rows_for(1) as isize;
+        let absorbed = |position: usize| Expr::cell(gadget.absorb_cell(position).1);
+        self.threshold.add_constraints(system);
+        require_met(system, &self.threshold);
+
+        let mut sections = vec![(0, self.statement_len)];
+        sections.extend(self.steps().map(|(chain, step)| (self.step_start(chain, step), 1)));
+        sections.push((self.leaf_start(), CHAINS));
+        sections.extend((0..self.levels).map(|level| (self.node_start(level), 2)));
+        gadget.constrain(system, "oracle", &sections);
+
+        // The statement carries the public wallet commitment and epoch, and the scored total
+        system.constrain_at(0, "oracle_wallet", absorbed(0) - self.wallet_commitment);
+        system.constrain_at(0, "oracle_epoch", absorbed(2) - self.epoch);
+        let total = Expr::cell(self.threshold.score_column(0)) - absorbed(OracleStatement::SCORE_POSITION);
+        system.constrain_at(0, "oracle_total", total);
+        system.wire("oracle_statement_constant", self.statement_column());
+        let statement_end = Poseidon2Gadget::rows_for(self.statement_len) - 1;
+        let statement = Expr::cell(self.statement_column());
+        system.constrain_at(statement_end, "oracle_statement", &statement - Expr::cell(gadget.state_column(0)));
+
+        // Each step hashes while active and passes through otherwise; its
+        // output feeds the next step
+        let step = system.selector(self.steps().map(|(chain, step)| self.step_start(chain, step)));
+        let later = system.selector(self.steps().filter(|(_, step)| *step > 0).map(|(chain, step)| self.step_start(chain, step)));
+        let active = Expr::cell(self.active_column());
+        let output = Expr::cell(self.output_column());
+        let hashed = Expr::rotated(gadget.state_column(0), NUM_STEPS as isize);
+        system.constrain("oracle_active_boolean", &step * &active * (&active - one));
+        let passed = &active * hashed + (one - &active) * absorbed(0);
+        system.constrain("oracle_step_output", &step * (&output - passed));
+        system.constrain("oracle_step_input", later * (absorbed(0) - Expr::rotated(self.output_column(), -step_rows)));
+
+        // Pass-throughs accumulate into the message and the checksum
+        let keep = system.selector(self.steps().skip(1).map(|(chain, step)| self.step_start(chain, step)));
+        for (name, column, pick) in [
+            ("message", self.message_column(), (|weights: (F, F)| weights.0) as fn((F, F)) -> F),
+            ("checksum", self.checksum_column(), |weights: (F, F)| weights.1),
+        ] {
+            let weight = system.fixed(self.steps().map(|(chain, step)| (self.step_start(chain, step), pick(Self::weights(chain)))));
+            let accumulated = Expr::cell(column) - &keep * Expr::rotated(column, -step_rows) - weight * (one - &active);
+            system.constrain(format!("oracle_{}_accumulator", name), &step * accumulated);
+        }
+        let last_step = self.step_start(CHAINS - 1, CHAIN_LENGTH as usize - 1);
+        system.constrain_at(last_step, "oracle_message_digits", Expr::cell(self.message_column()) - statement);
+        let checksum = F::new(CHAIN_LENGTH as u64 * MESSAGE_DIGITS as u64);
+        system.constrain_at(last_step, "oracle_checksum_digits", Expr::cell(self.checksum_column()) - checksum);
+
+        // Each chain's last output is its end, which the key leaf absorbs
+        for chain in 0..CHAINS {
+            let end = Expr::cell(self.end_column(chain));
+            system.wire(format!("oracle_chain_{}_end_constant", chain), self.end_column(chain));
+            let last = self.step_start(chain, CHAIN_LENGTH as usize - 1);
+            system.constrain_at(last, format!("oracle_chain_{}_end", chain), &output - &end);
+            let (row, column) = gadget.absorb_cell(chain);
+            system.constrain_at(self.leaf_start() + row, format!("oracle_chain_{}_leaf", chain), Expr::cell(column) - end);
+        }
+
+        // The key leaf climbs to the oracle root
+        let node_rows: Vec<usize> = (0..self.levels).map(|level| self.node_start(level)).collect();
+        constrain_path(system, "oracle_key", gadget, self.direction_column(), &node_rows);
+        let root = Expr::cell(gadget.state_column(0)) - self.root;
+        system.constrain_at(self.node_start(self.levels) - 1, "oracle_root", root);
+    }
+}
+
+/// Threshold section that must be met unless a hashed witness opens the designated verifier's key
+///
+/// A wired selector column picks the branch; honest proofs hash random
+/// filler, so both branches fill the same cells.
+#[derive(Debug, Clone)]
+pub struct DesignatedThresholdAir {
+    pub threshold: ThresholdAir,
+    pub witness_len: usize,
+    pub verifier: F,
+}
+
+impl DesignatedThresholdAir {
+    pub fn new(threshold: ThresholdAir, witness_len: usize, verifier: F) -> Self {
+        Self { threshold, witness_len, verifier }
+    }
+
+    pub fn gadget(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.threshold.width())
+    }
+
+    pub fn selector_column(&self) -> usize {
+        self.threshold.width() + Poseidon2Gadget::COLUMNS
+    }
+
+    pub fn rows(&self) -> usize {
+        ThresholdAir::ROWS.max(Poseidon2Gadget::rows_for(self.witness_len))
+    }
+
+    /// Hash the witness and write the branch selector
+    pub fn fill(&self, trace: &mut ExecutionTrace, witness: &[F], selector: bool) {
+        self.gadget().generate_trace(trace, 0, witness);
+        for row in 0..trace.height {
+            trace.set(row, self.selector_column(), F::new(selector as u64));
+        }
+    }
+}
+
+impl CustomAir for DesignatedThresholdAir {
+    fn width(&self) -> usize {
+        self.selector_column() + 1
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let one = F::ONE;
+        let gadget = self.gadget();
+        self.threshold.add_constraints(system);
+        gadget.constrain(system, "designation", &[(0, self.witness_len)]);
+
+        let selector = Expr::cell(self.selector_column());
+        let meets_threshold = Expr::cell(self.threshold.meets_threshold_column());
+        system.wire("designation_selector_constant", self.selector_column());
+        system.constrain("designation_selector_boolean", &selector * (&selector - one));
+        system.constrain("designation_threshold_met", (one - &selector) * (meets_threshold - one));
+        let opened = selector * (Expr::cell(gadget.state_column(0)) - self.verifier);
+        system.constrain_at(Poseidon2Gadget::rows_for(self.witness_len) - 1, "designation_key", opened);
+    }
+}
+
+/// Met threshold section whose total is masked with a pad derived from a committed escrow key
+///
+/// Two gadget sections absorb the same key: one hashes it to the public key
+/// commitment, the other to the pad each ciphertext element adds to the total.
+#[derive(Debug, Clone)]
+pub struct EscrowedThresholdAir {
+    pub threshold: ThresholdAir,
+    pub key_len: usize,
+    pub pad_len: usize,
+    pub key_commitment: Vec<F>,
+    pub ciphertext: Vec<F>,
+}
+
+impl EscrowedThresholdAir {
+    pub fn new(threshold: ThresholdAir, key_len: usize, pad_len: usize, key_commitment: Vec<F>, ciphertext: Vec<F>) -> Self {
+        Self {
+            threshold,
+            key_len,
+            pad_len,
+            key_commitment,
+            ciphertext,
+        }
+    }
+
+    pub fn gadget(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.threshold.width())
+    }
+
+    fn pad_start(&self) -> usize {
+        Poseidon2Gadget::rows_for(self.key_len)
+    }
+
+    pub fn rows(&self) -> usize {
+        ThresholdAir::ROWS.max(self.pad_start() + Poseidon2Gadget::rows_for(self.pad_len))
+    }
+
+    /// Hash the key and the pad inputs
+    pub fn fill(&self, trace: &mut ExecutionTrace, key: &[F], pad_inputs: &[F]) {
+        self.gadget().generate_trace(trace, 0, key);
+        self.gadget().generate_trace(trace, self.pad_start(), pad_inputs);
+    }
+}
+
+impl CustomAir for EscrowedThresholdAir {
+    fn width(&self) -> usize {
+        self.gadget().column_offset + Poseidon2Gadget::COLUMNS
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let gadget = self.gadget();
+        let pad_start = self.pad_start();
+        self.threshold.add_constraints(system);
+        require_met(system, &self.threshold);
+        gadget.constrain(system, "escrow", &[(0, self.key_len), (pad_start, self.pad_len)]);
+
+        // The pad section absorbs the committed key
+        for position in 0..self.key_len {
+            let (row, column) = gadget.absorb_cell(position);
+            let same = Expr::cell(column) - Expr::rotated(column, -(pad_start as isize));
+            system.constrain_at(pad_start + row, format!("escrow_key_{}", position), same);
+        }
+        for (i, &element) in self.key_commitment.iter().enumerate() {
+            let committed = Expr::cell(gadget.state_column(i)) - element;
+            system.constrain_at(pad_start - 1, format!("escrow_key_commitment_{}", i), committed);
+        }
+        let pad_end = pad_start + Poseidon2Gadget::rows_for(self.pad_len) - 1;
+        let total = Expr::cell(self.threshold.final_score_column());
+        for (i, &masked) in self.ciphertext.iter().enumerate() {
+            let encrypted = &total + Expr::cell(gadget.state_column(i)) - masked;
+            system.constrain_at(pad_end, format!("escrow_ciphertext_{}", i), encrypted);
+        }
+    }
+}
+
+/// Threshold section over scores pooled from wallets of one identity
+///
+/// Gadget sections open the identity commitment, one binding per wallet and
+/// the binding-set commitment. A wired column carries the identity secret
+/// into every binding, and one per wallet carries its binding into the set.
+#[derive(Debug, Clone)]
+pub struct LinkedThresholdAir {
+    pub threshold: ThresholdAir,
+    pub wallets: usize,
+    pub identity_commitment: F,
+    pub link_commitment: F,
+}
+
+impl LinkedThresholdAir {
+    pub fn new(threshold: ThresholdAir, wallets: usize, identity_commitment: F, link_commitment: F) -> Self {
+        Self {
+            threshold,
+            wallets,
+            identity_commitment,
+            link_commitment,
+        }
+    }
+
+    pub fn gadget(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.threshold.width())
+    }
+
+    pub fn secret_column(&self) -> usize {
+        self.threshold.width() + Poseidon2Gadget::COLUMNS
+    }
+
+    pub fn binding_column(&self, index: usize) -> usize {
+        self.secret_column() + 1 + index
+    }
+
+    fn binding_start(index: usize) -> usize {
+        Poseidon2Gadget::rows_for(1) + index * Poseidon2Gadget::rows_for(2)
+    }
+
+    fn link_start(&self) -> usize {
+        Self::binding_start(self.wallets)
+    }
+
+    pub fn rows(&self) -> usize {
+        ThresholdAir::ROWS.max(self.link_start() + Poseidon2Gadget::rows_for(self.wallets))
+    }
+
+    /// Hash the identity, each wallet's binding and the binding set
+    pub fn fill(&self, trace: &mut ExecutionTrace, secret: F, wallet_tags: &[F]) {
+        let gadget = self.gadget();
+        gadget.generate_trace(trace, 0, &[secret]);
+        let bindings: Vec<F> = wallet_tags
+            .iter()
+            .enumerate()
+            .map(|(i, &tag)| gadget.generate_trace(trace, Self::binding_start(i), &[secret, tag]))
+            .collect();
+        gadget.generate_trace(trace, self.link_start(), &bindings);
+        for row in 0..trace.height {
+            trace.set(row, self.secret_column(), secret);
+            for (i, &binding) in bindings.iter().enumerate() {
+                trace.set(row, self.binding_column(i), binding);
+            }
+        }
+    }
+}
+
+impl CustomAir for LinkedThresholdAir {
+    fn width(&self) -> usize {
+        self.binding_column(self.wallets)
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let gadget = self.gadget();
+        let digest = Expr::cell(gadget.state_column(0));
+        let (_, secret_column) = gadget.absorb_cell(0);
+        let secret = Expr::cell(self.secret_column());
+        self.threshold.add_constraints(system);
+        require_met(system, &self.threshold);
+
+        let mut sections = vec![(0, 1)];
+        sections.extend((0..self.wallets).map(|i| (Self::binding_start(i), 2)));
+        sections.push((self.link_start(), self.wallets));
+        gadget.constrain(system, "linkage", &sections);
+
+        // Every binding absorbs the identity's secret, and its digest feeds the binding set
+        system.wire("linkage_secret_constant", self.secret_column());
+        system.constrain_at(0, "linkage_identity_secret", Expr::cell(secret_column) - &secret);
+        system.constrain_at(Poseidon2Gadget::rows_for(1) - 1, "linkage_identity", &digest - self.identity_commitment);
+        for i in 0..self.wallets {
+            let start = Self::binding_start(i);
+            let binding = Expr::cell(self.binding_column(i));
+            system.wire(format!("linkage_binding_{}_constant", i), self.binding_column(i));
+            system.constrain_at(start, format!("linkage_binding_{}_secret", i), Expr::cell(secret_column) - &secret);
+            system.constrain_at(start + Poseidon2Gadget::rows_for(2) - 1, format!("linkage_binding_{}", i), &digest - &binding);
+            let (row, column) = gadget.absorb_cell(i);
+            system.constrain_at(self.link_start() + row, format!("linkage_binding_{}_linked", i), Expr::cell(column) - binding);
+        }
+        let link_end = self.link_start() + Poseidon2Gadget::rows_for(self.wallets) - 1;
+        system.constrain_at(link_end, "linkage_set", digest - self.link_commitment);
+    }
+}
+
+/// One snapshot leaf and the score column it stands behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotOpening {
+    /// Score column `i` holds `coefficient` times the score of leaf `i`
+    pub coefficient: F,
+    pub category: F,
+    pub depth: usize,
+    pub root: F,
+}
+
+/// Met threshold section over scores opened from epoch snapshots
+///
+/// Each opening hashes the leaf (wallet, category, score), then one node per
+/// tree level up to its snapshot root. A wired column carries the private
+/// wallet tag into every leaf and into a last section hashing it with the
+/// salt to the public wallet commitment.
+#[derive(Debug, Clone)]
+pub struct SnapshotOpeningsAir {
+    pub threshold: ThresholdAir,
+    pub openings: Vec<SnapshotOpening>,
+    pub wallet_commitment: Digest,
+}
+
+impl SnapshotOpeningsAir {
+    pub fn new(threshold: ThresholdAir, openings: Vec<SnapshotOpening>, wallet_commitment: Digest) -> Self {
+        Self { threshold, openings, wallet_commitment }
+    }
+
+    pub fn gadget(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.threshold.width())
+    }
+
+    pub fn direction_column(&self) -> usize {
+        self.threshold.width() + Poseidon2Gadget::COLUMNS
+    }
+
+    pub fn wallet_column(&self) -> usize {
+        self.direction_column() + 1
+    }
+
+    /// First row of opening `index`'s leaf section
+    fn opening_start(&self, index: usize) -> usize {
+        self.openings[..index].iter().map(|opening| (1 + opening.depth) * Poseidon2Gadget::rows_for(3)).sum()
+    }
+
+    /// First row of the section hashing the wallet tag and salt
+    fn wallet_start(&self) -> usize {
+        self.opening_start(self.openings.len())
+    }
+
+    pub fn rows(&self) -> usize {
+        ThresholdAir::ROWS.max(self.wallet_start() + Poseidon2Gadget::rows_for(1 + SALT_ELEMENTS))
+    }
+
+    /// Hash every leaf and its path, then the wallet commitment
+    ///
+    /// `leaves` holds each opening's score, siblings and leaf index.
+    pub fn fill(&self, trace: &mut ExecutionTrace, wallet: F, salt: &[F; SALT_ELEMENTS], leaves: &[(u32, &[F], u64)]) {
+        let gadget = self.gadget();
+        for (index, (opening, &(score, siblings, leaf_index))) in self.openings.iter().zip(leaves).enumerate() {
+            let start = self.opening_start(index);
+            let leaf = gadget.generate_trace(trace, start, &[wallet, opening.category, F::from_u32(score)]);
+            let node_start = start + Poseidon2Gadget::rows_for(3);
+            fill_path(trace, gadget, self.direction_column(), node_start, leaf, siblings, leaf_index);
+        }
+        let mut committed = vec![wallet];
+        committed.extend(salt);
+        gadget.generate_trace(trace, self.wallet_start(), &committed);
+        for row in 0..trace.height {
+            trace.set(row, self.wallet_column(), wallet);
+        }
+    }
+}
+
+impl CustomAir for SnapshotOpeningsAir {
+    fn width(&self) -> usize {
+        self.wallet_column() + 1
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let gadget = self.gadget();
+        let section_rows = Poseidon2Gadget::rows_for(3);
+        let absorbed = |position: usize| Expr::cell(gadget.absorb_cell(position).1);
+        self.threshold.add_constraints(system);
+        require_met(system, &self.threshold);
+        system.wire("snapshot_wallet_constant", self.wallet_column());
+
+        let mut sections = Vec::new();
+        let mut node_rows = Vec::new();
+        for (index, opening) in self.openings.iter().enumerate() {
+            let start = self.opening_start(index);
+            sections.push((start, 3));
+            for level in 0..opening.depth {
+                node_rows.push(start + (1 + level) * section_rows);
+                sections.push((start + (1 + level) * section_rows, 2));
+            }
+
+            // Same wallet in every leaf, the claimed category, and the scaled score column
+            let score = Expr::cell(self.threshold.score_column(index)) - absorbed(2) * opening.coefficient;
+            system.constrain_at(start, format!("snapshot_{}_wallet", index), absorbed(0) - Expr::cell(self.wallet_column()));
+            system.constrain_at(start, format!("snapshot_{}_category", index), absorbed(1) - opening.category);
+            system.constrain_at(start, format!("snapshot_{}_score", index), score);
+            let root = Expr::cell(gadget.state_column(0)) - opening.root;
+            system.constrain_at(self.opening_start(index + 1) - 1, format!("snapshot_{}_root", index), root);
+        }
+
+        // The same wallet tag, under the private salt, reaches the public commitment
+        let wallet_start = self.wallet_start();
+        sections.push((wallet_start, 1 + SALT_ELEMENTS));
+        system.constrain_at(wallet_start, "snapshot_wallet_tag", absorbed(0) - Expr::cell(self.wallet_column()));
+        let commitment_row = wallet_start + Poseidon2Gadget::rows_for(1 + SALT_ELEMENTS) - 1;
+        for (i, &element) in self.wallet_commitment.iter().enumerate() {
+            system.constrain_at(commitment_row, format!("snapshot_wallet_{}", i), Expr::cell(gadget.state_column(i)) - element);
+        }
+        gadget.constrain(system, "snapshot", &sections);
+        constrain_path(system, "snapshot", gadget, self.direction_column(), &node_rows);
+    }
+}
+
+/// Rate-limited signal from a member of an identity set
+///
+/// Three gadget sections hash the identity commitment, the message secret
+/// and the nullifier, and a sparse Merkle path keyed by the commitment
+/// reaches the public set root. A wired column holds the message index,
+/// range-checked along with its gap below the limit, so the index lies in
+/// `[0, limit)`; the share `y = secret + message_secret * signal` is
+/// constrained where the message secret is hashed.
+#[derive(Debug, Clone)]
+pub struct RateLimitedSignalAir {
+    pub root: F,
+    pub limit: F,
+    pub external_nullifier: F,
+    pub signal: F,
+    pub share: F,
+    pub nullifier: F,
+}
+
+impl RateLimitedSignalAir {
+    pub fn hasher(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(0)
+    }
+
+    pub fn path(&self) -> SmtPathGadget {
+        SmtPathGadget::new(Poseidon2Gadget::COLUMNS)
+    }
+
+    pub fn index_column(&self) -> usize {
+        Poseidon2Gadget::COLUMNS + SmtPathGadget::COLUMNS
+    }
+
+    pub fn index_range(&self) -> RangeCheck {
+        RangeCheck::new(self.index_column() + 1, MESSAGE_INDEX_BITS)
+    }
+
+    pub fn gap_range(&self) -> RangeCheck {
+        RangeCheck::new(self.index_column() + 1 + RangeCheck::COLUMNS, MESSAGE_INDEX_BITS)
+    }
+
+    fn section_start(section: usize) -> usize {
+        section * Poseidon2Gadget::rows_for(3)
+    }
+
+    pub fn rows(&self) -> usize {
+        SmtPathGadget::rows().max(Self::section_start(3))
+    }
+
+    /// Hash the three sections, walk the membership path and write the index checks
+    pub fn fill(&self, trace: &mut ExecutionTrace, secret: F, message_index: u32, membership: &crate::sparse_merkle::SmtWitness) {
+        let hasher = self.hasher();
+        let index = F::from_u32(message_index);
+        hasher.generate_trace(trace, 0, &[secret]);
+        let slope = hasher.generate_trace(trace, Self::section_start(1), &[secret, self.external_nullifier, index]);
+        hasher.generate_trace(trace, Self::section_start(2), &[slope]);
+        self.path().generate_trace(trace, 0, membership, true);
+        for row in 0..trace.height {
+            trace.set(row, self.index_column(), index);
+        }
+        self.index_range().fill(trace, message_index as u64);
+        self.gap_range().fill(trace, (self.limit - F::ONE - index).0);
+    }
+}
+
+impl CustomAir for RateLimitedSignalAir {
+    fn width(&self) -> usize {
+        self.gap_range().accumulator_column() + 1
+    }
+
+    fn add_constraints(&self, system: &mut ConstraintSystem) {
+        let hasher = self.hasher();
+        let path = self.path();
+        let (slope_start, nullifier_start) = (Self::section_start(1), Self::section_start(2));
+        let absorbed = |position: usize| Expr::cell(hasher.absorb_cell(position).1);
+        let digest = Expr::cell(hasher.state_column(0));
+        hasher.constrain(system, "rln", &[(0, 1), (slope_start, 3), (nullifier_start, 1)]);
+
+        // The message secret hashes the committed secret, the public external
+        // nullifier and the message index; the nullifier hashes the message secret
+        let secret = Expr::rotated(hasher.absorb_cell(0).1, -(slope_start as isize));
+        system.constrain_at(slope_start, "rln_secret", absorbed(0) - secret);
+        system.constrain_at(slope_start, "rln_external_nullifier", absorbed(1) - self.external_nullifier);
+        let index = Expr::cell(self.index_column());
+        system.wire("rln_index_constant", self.index_column());
+        system.constrain_at(slope_start, "rln_index", absorbed(2) - &index);
+        system.constrain_at(nullifier_start, "rln_slope", absorbed(0) - Expr::rotated(hasher.state_column(0), -1));
+        system.constrain_at(nullifier_start + NUM_STEPS, "rln_nullifier", &digest - self.nullifier);
+
+        // The share is a point on the line with the secret as intercept
+        let secret = Expr::rotated(hasher.absorb_cell(0).1, -((nullifier_start - 1) as isize));
+        let share = secret + &digest * self.signal - self.share;
+        system.constrain_at(nullifier_start - 1, "rln_share", share);
+
+        // Index and gap below the limit are both MESSAGE_INDEX_BITS wide
+        self.index_range().constrain(system, "rln_index_range", index.clone());
+        self.gap_range().constrain(system, "rln_gap_range", self.limit - F::ONE - index);
+
+        // Membership path keyed by the commitment digest
+        path.constrain(system, "rln_membership", 0, true, self.root);
+        let key = Expr::cell(path.limb_column(0)) + Expr::cell(path.limb_column(1)) * F::new(1 << 16);
+        system.constrain_at(NUM_STEPS, "rln_commitment_key", digest - key);
+        for limb in 2..KEY_LIMBS {
+            system.constrain(format!("rln_commitment_limb_{}", limb), Expr::cell(path.limb_column(limb)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_input_section_binds_the_inputs() {
+        let public_inputs = vec![F::new(7), F::new(11), F::new(13)];
+        let circuit = Circuit::tiled(&ConstraintSystem::new(4), &public_inputs).unwrap();
+        let mut trace = circuit.trace();
+        circuit.fill_public_inputs(&mut trace, &public_inputs);
+        assert!(circuit.system.check(&trace).is_ok());
+
+        // A trace hashing other inputs fails the system rebuilt for these
+        let mut forged = circuit.trace();
+        circuit.fill_public_inputs(&mut forged, &[F::new(7), F::new(11), F::new(14)]);
+        assert!(circuit.system.check(&forged).is_err());
+    }
+
+    #[test]
+    fn test_shape_rejects_missing_public_inputs() {
+        let shape = CircuitShape::Threshold { scores: ThresholdShape::new(2, None), flagged: 0 };
+        let digest = shape.digest().unwrap();
+        assert!(shape.build(&[F::new(10)]).is_err());
+        assert!(shape.build(&[&[F::new(10), F::new(3600), F::new(1_700_000_000)], &digest[..]].concat()).is_ok());
+
+        // A stated category must be the shape's
+        let stated = CircuitShape::Threshold { scores: ThresholdShape::new(2, None).with_categories(vec![F::new(3)]), flagged: 0 };
+        let inputs = |tag: u64| [&[F::new(10), F::new(3600), F::new(1_700_000_000), F::new(tag)], &stated.digest().unwrap()[..]].concat();
+        assert!(stated.build(&inputs(3)).is_ok());
+        assert!(stated.build(&inputs(5)).is_err());
+    }
+
+    #[test]
+    fn test_sustained_shape_opens_each_leaf_once() {
+        let leaf = |category: u64| SnapshotLeafShape { epoch: 0, category: F::new(category), depth: 1 };
+        let build = |leaves: Vec<SnapshotLeafShape>| {
+            let shape = CircuitShape::SustainedThreshold { leaves };
+            let mut inputs = vec![F::new(10), F::new(500), F::new(1_700_000_000), F::ONE, F::ZERO, F::new(99)];
+            inputs.extend([F::new(7); DIGEST_ELEMENTS]);
+            inputs.extend(shape.statement());
+            inputs.extend(shape.digest().unwrap());
+            shape.build(&inputs).map(|_| ())
+        };
+        assert!(build(vec![leaf(1), leaf(3)]).is_ok());
+
+        // Repeating a leaf would count its weighted score twice
+        assert!(matches!(build(vec![leaf(3), leaf(3)]), Err(ZKPError::MalformedProof(_))));
+        assert!(matches!(build(vec![leaf(3), leaf(1)]), Err(ZKPError::MalformedProof(_))));
+    }
+
+    #[test]
+    fn test_delta_shape_counts_each_category_once() {
+        let shape = CircuitShape::ScoreDelta { depths: vec![1; 4] };
+        let build = |tags: [u64; 2]| {
+            let mut inputs = vec![F::new(100), F::new(2), F::new(1_700_000_000), F::ZERO, F::new(98), F::new(2), F::new(99)];
+            inputs.extend(tags.map(F::new));
+            inputs.extend([F::new(7); DIGEST_ELEMENTS]);
+            inputs.extend(shape.digest().unwrap());
+            shape.build(&inputs).map(|_| ())
+        };
+        assert!(build([1, 3]).is_ok());
+
+        // A repeated category would count its delta twice
+        assert!(matches!(build([3, 3]), Err(ZKPError::MalformedProof(_))));
+    }
+
+    #[test]
+    fn test_committed_shape_opens_distinct_score_slots() {
+        let build = |positions: Vec<usize>| {
+            let scores = ThresholdShape::new(positions.len(), None).with_categories(vec![F::new(1); positions.len()]);
+            let shape = CircuitShape::CommittedThreshold { scores, opening_len: 7, positions };
+            let mut inputs = vec![F::new(10), F::new(3600)];
+            inputs.extend([F::new(7); DIGEST_ELEMENTS]);
+            inputs.push(F::new(1_700_000_000));
+            inputs.extend(shape.statement());
+            inputs.extend(shape.digest().unwrap());
+            shape.build(&inputs).map(|_| ())
+        };
+        assert!(build(vec![2, 6]).is_ok());
+
+        // Repeated slots, tags, the blinding or slots past the opening would inflate the sum
+        for positions in [vec![4, 4], vec![6, 2], vec![3], vec![0], vec![8]] {
+            assert!(matches!(build(positions), Err(ZKPError::MalformedProof(_))));
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Time Sources
+//!
+//! The prover reads the evaluation instant through `Clock` instead of the wall
+//! clock, so proofs are reproducible and time-dependent logic is testable
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of the current Unix time in seconds
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> u64;
+}
+
+/// Wall-clock time
+#[cfg(feature = "prover")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "prover")]
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        chrono::Utc::now().timestamp() as u64
+    }
+}
+
+/// Clock that only moves when told to, for reproducible proofs and tests
+#[derive(Debug, Default)]
+pub struct FixedClock {
+    now: AtomicU64,
+}
+
+impl FixedClock {
+    pub fn new(now: u64) -> Self {
+        Self { now: AtomicU64::new(now) }
+    }
+
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, secs: u64) {
+        self.now.fetch_add(secs, Ordering::SeqCst);
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_fixed_clock_makes_proofs_reproducible() {
+        let clock = Arc::new(FixedClock::new(1_700_000_000));
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Technical],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let prove = |clock: Arc<FixedClock>| {
+            RepIDZKPSystem::new(SecurityLevel::Fast)
+                .with_clock(clock)
+                .prove_threshold_verification(&request, &[(RepIDCategory::Technical, 75)], "0xtest")
+                .unwrap()
+                .proof
+        };
+
+        let first = prove(clock.clone());
+        let second = prove(clock.clone());
+        assert_eq!(first.proof_data, second.proof_data);
+        assert_eq!(first.metadata.timestamp, 1_700_000_000);
+        assert_eq!(first.public_inputs.get(2), Some(&crate::F::new(1_700_000_000)));
+
+        clock.advance(60);
+        let later = prove(clock);
+        assert_ne!(later.proof_data, first.proof_data);
+        assert_eq!(later.metadata.timestamp, 1_700_000_060);
+    }
+}
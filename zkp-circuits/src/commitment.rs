@@ -0,0 +1,144 @@
+//! Score Vector Commitments
+//!
+//! Poseidon2 commitments that a custodial scorer publishes once and users
+//! open in-circuit when proving threshold statements about the committed scores,
+//! or when revealing a single committed score to settle a dispute
+
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2::{self, Digest, DIGEST_ELEMENTS};
+use crate::{RepIDCategory, RepIDProof, Result, ZKPError, F};
+
+/// Operation type of proofs revealing one committed score
+pub const SCORE_OPENING_OPERATION: &str = "score_opening";
+
+/// Public commitment to a score vector (eight-element Poseidon2 digest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreCommitment(pub Digest);
+
+/// Private opening of a score commitment, held by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreOpening {
+    /// Committed scores in commitment order
+    pub scores: Vec<(RepIDCategory, u32)>,
+    /// Blinding factor hiding low-entropy score vectors
+    pub blinding: F,
+}
+
+impl ScoreOpening {
+    pub fn new(scores: Vec<(RepIDCategory, u32)>, blinding: F) -> Self {
+        Self { scores, blinding }
+    }
+
+    /// Canonical hash input: [blinding, tag_0, score_0, tag_1, score_1, ...]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        let mut elements = Vec::with_capacity(1 + 2 * self.scores.len());
+        elements.push(self.blinding);
+        for (category, score) in &self.scores {
+            elements.push(category.field_tag());
+            elements.push(F::from_u32(*score));
+        }
+        elements
+    }
+
+    /// Position of a category's score within `to_field_elements`
+    pub fn score_position(&self, category: &RepIDCategory) -> Option<usize> {
+        self.scores
+            .iter()
+            .position(|(c, _)| c == category)
+            .map(|index| 2 + 2 * index)
+    }
+
+    /// Compute the commitment this opening corresponds to
+    pub fn commit(&self) -> ScoreCommitment {
+        ScoreCommitment(poseidon2::hash_to_digest(&self.to_field_elements()))
+    }
+
+    /// Check the opening against a published commitment
+    pub fn opens(&self, commitment: &ScoreCommitment) -> bool {
+        self.commit() == *commitment
+    }
+}
+
+/// Score a verified opening proof reveals for `category` under `commitment`
+///
+/// Fails unless the proof was made against `commitment`, e.g. the one an
+/// earlier committed threshold proof carries, and reveals `category`.
+pub fn disclosed_score(proof: &RepIDProof, commitment: &ScoreCommitment, category: &RepIDCategory) -> Result<u32> {
+    if proof.metadata.operation_type != SCORE_OPENING_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs reveal no committed score",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(..DIGEST_ELEMENTS + 2) {
+        Some([committed @ .., tag, score]) => {
+            if committed != commitment.0 {
+                return Err(ZKPError::VerificationError("Proof opens a different score commitment".to_string()));
+            }
+            if *tag != category.field_tag() {
+                return Err(ZKPError::VerificationError(format!("Proof does not reveal the {:?} score", category)));
+            }
+            Ok(score.0 as u32)
+        }
+        _ => Err(ZKPError::MalformedProof("Score opening proof needs commitment, category and score inputs".to_string())),
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_binds_scores_and_blinding() {
+        let opening = ScoreOpening::new(
+            vec![(RepIDCategory::Technical, 75), (RepIDCategory::Governance, 50)],
+            F::new(12345),
+        );
+        let commitment = opening.commit();
+        assert!(opening.opens(&commitment));
+
+        let mut altered = opening.clone();
+        altered.scores[1].1 = 51;
+        assert!(!altered.opens(&commitment));
+
+        let reblinded = ScoreOpening::new(opening.scores.clone(), F::new(54321));
+        assert!(!reblinded.opens(&commitment));
+    }
+
+    #[test]
+    fn test_opening_proof_matches_threshold_commitment() {
+        use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let opening = ScoreOpening::new(
+            vec![(RepIDCategory::Technical, 75), (RepIDCategory::Governance, 50)],
+            F::new(12345),
+        );
+        let commitment = opening.commit();
+        let request = ThresholdVerificationRequest {
+            threshold: 100,
+            categories: vec![RepIDCategory::Technical, RepIDCategory::Governance],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let threshold = zkp_system
+            .prove_committed_threshold_verification(&request, &opening, &commitment, "0xtest")
+            .unwrap();
+        let earlier = ScoreCommitment(threshold.proof.public_inputs[2..2 + DIGEST_ELEMENTS].try_into().unwrap());
+
+        // Support resolves a Governance dispute against the commitment the threshold proof used
+        let disclosure = zkp_system.prove_score_opening(&opening, &earlier, &RepIDCategory::Governance, "0xtest").unwrap();
+        assert!(zkp_system.verify_proof(&disclosure, None).unwrap());
+        assert_eq!(disclosed_score(&disclosure, &earlier, &RepIDCategory::Governance).unwrap(), 50);
+        assert!(disclosed_score(&disclosure, &earlier, &RepIDCategory::Technical).is_err());
+        assert!(disclosed_score(&disclosure, &ScoreCommitment([F::new(1); DIGEST_ELEMENTS]), &RepIDCategory::Governance).is_err());
+        assert!(disclosed_score(&threshold.proof, &earlier, &RepIDCategory::Governance).is_err());
+
+        assert!(zkp_system.prove_score_opening(&opening, &earlier, &RepIDCategory::DeFi, "0xtest").is_err());
+        let mut altered = opening.clone();
+        altered.scores[1].1 = 90;
+        assert!(zkp_system.prove_score_opening(&altered, &earlier, &RepIDCategory::Governance, "0xtest").is_err());
+    }
+}
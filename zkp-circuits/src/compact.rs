@@ -0,0 +1,86 @@
+//! Compact Proof Strings
+//!
+//! Versioned, deflate-compressed base64url encoding of proof envelopes for
+//! presenting proofs as QR codes in person
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+use crate::limits::ProofLimits;
+use crate::{RepIDProof, Result, ZKPError};
+
+/// Prefix of the current compact encoding
+pub const COMPACT_PREFIX: &str = "repid1:";
+/// Characters a version 40 QR code holds in byte mode at error correction level L
+pub const QR_BYTE_CAPACITY: usize = 2953;
+
+const COMPRESSION_LEVEL: u8 = 10;
+
+impl RepIDProof {
+    /// `repid1:` followed by the base64url (unpadded) deflate of the encoded envelope
+    pub fn to_compact_string(&self) -> Result<String> {
+        let encoded = crate::protocol::encode_envelope(self)?;
+        let compressed = compress_to_vec(&encoded, COMPRESSION_LEVEL);
+        Ok(format!("{}{}", COMPACT_PREFIX, URL_SAFE_NO_PAD.encode(compressed)))
+    }
+
+    /// Decode a compact string
+    ///
+    /// Inflation stops at twice the default proof size limit, leaving room for
+    /// public inputs and metadata while bounding decompression bombs.
+    pub fn from_compact_string(compact: &str) -> Result<Self> {
+        let body = compact.strip_prefix(COMPACT_PREFIX).ok_or_else(|| {
+            let version = compact.split(':').next().unwrap_or_default();
+            ZKPError::MalformedProof(format!("unsupported compact proof version '{}'", version))
+        })?;
+        let compressed = URL_SAFE_NO_PAD
+            .decode(body)
+            .map_err(|e| ZKPError::MalformedProof(format!("invalid base64url: {}", e)))?;
+        let max_bytes = 2 * ProofLimits::default().max_proof_bytes as usize;
+        let encoded = decompress_to_vec_with_limit(&compressed, max_bytes)
+            .map_err(|e| ZKPError::MalformedProof(format!("invalid compressed proof: {:?}", e.status)))?;
+        crate::protocol::decode_envelope(&encoded)
+    }
+
+    /// Whether the compact string fits one QR code
+    ///
+    /// Fast-level proofs compress below their encoded size, but the Merkle
+    /// authentication paths are incompressible; with 40 queries the string is
+    /// around 8 KB and needs a structured-append sequence of three codes.
+    pub fn fits_qr_code(&self) -> Result<bool> {
+        Ok(self.to_compact_string()?.len() <= QR_BYTE_CAPACITY)
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_compact_round_trip() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest").unwrap();
+
+        let compact = result.proof.to_compact_string().unwrap();
+        let envelope_len = crate::wire::to_bytes(&result.proof).unwrap().len();
+        assert!(compact.len() < 3 * envelope_len / 4);
+        assert!(compact.starts_with(COMPACT_PREFIX));
+        assert_eq!(result.proof.fits_qr_code().unwrap(), compact.len() <= QR_BYTE_CAPACITY);
+
+        let decoded = RepIDProof::from_compact_string(&compact).unwrap();
+        assert!(zkp_system.verify_proof(&decoded, Some(&request)).unwrap());
+
+        assert!(RepIDProof::from_compact_string(&compact.replacen("repid1", "repid9", 1)).is_err());
+        assert!(RepIDProof::from_compact_string(&compact[..compact.len() / 2]).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! Verification Concurrency
+//!
+//! Bounds how many verifications run at once on a shared node. Callers queue
+//! per tenant and freed slots go to tenants in round-robin order, so a burst
+//! of large proofs from one tenant waits behind its own queue instead of
+//! starving everyone else. Each tenant's queue is bounded; past it callers
+//! are rejected immediately.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+
+use crate::limits::ProofLimits;
+use crate::Result;
+
+/// Queue key of callers without a tenant
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Queue depths and counters, e.g. for a metrics endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimiterMetrics {
+    pub max_concurrent: usize,
+    pub in_flight: usize,
+    /// Callers waiting, by tenant
+    pub queued: BTreeMap<String, usize>,
+    /// Deepest any single tenant's queue has been
+    pub peak_queue_depth: usize,
+    pub admitted: u64,
+    /// Callers turned away by a full tenant queue
+    pub rejected: u64,
+}
+
+impl LimiterMetrics {
+    pub fn total_queued(&self) -> usize {
+        self.queued.values().sum()
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    in_flight: usize,
+    next_ticket: u64,
+    /// Waiting tickets per tenant, oldest first
+    queues: BTreeMap<String, VecDeque<u64>>,
+    /// Tenants in the order they are next served
+    rotation: VecDeque<String>,
+    /// Tickets given a slot but not yet picked up by their caller
+    granted: BTreeSet<u64>,
+    peak_queue_depth: usize,
+    admitted: u64,
+    rejected: u64,
+}
+
+impl LimiterState {
+    /// Hand free slots to the head of each tenant's queue in turn
+    fn dispatch(&mut self, max_concurrent: usize) {
+        while self.in_flight < max_concurrent {
+            let Some(tenant) = self.rotation.pop_front() else {
+                return;
+            };
+            let queue = self.queues.get_mut(&tenant).expect("rotating tenants have queues");
+            let ticket = queue.pop_front().expect("rotating tenants have waiting tickets");
+            if queue.is_empty() {
+                self.queues.remove(&tenant);
+            } else {
+                self.rotation.push_back(tenant);
+            }
+            self.granted.insert(ticket);
+            self.in_flight += 1;
+            self.admitted += 1;
+        }
+    }
+}
+
+/// Semaphore over verifications with per-tenant fair queueing
+#[derive(Debug)]
+pub struct VerificationLimiter {
+    max_concurrent: usize,
+    max_queued_per_tenant: usize,
+    state: Mutex<LimiterState>,
+    released: Condvar,
+}
+
+impl VerificationLimiter {
+    /// At most `max_concurrent` verifications at once and `max_queued_per_tenant` waiting per tenant
+    pub fn new(max_concurrent: usize, max_queued_per_tenant: usize) -> Self {
+        Self {
+            max_concurrent: max_concurrent.max(1),
+            max_queued_per_tenant,
+            state: Mutex::new(LimiterState::default()),
+            released: Condvar::new(),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait for a verification slot on behalf of `tenant`
+    ///
+    /// Fails with `LimitExceeded` without waiting when the tenant already has
+    /// `max_queued_per_tenant` callers queued.
+    pub fn acquire(self: &Arc<Self>, tenant: &str) -> Result<VerificationPermit> {
+        let mut state = self.state();
+        let depth = state.queues.get(tenant).map_or(0, VecDeque::len);
+        if let Err(e) = ProofLimits::check("queued verifications", depth as u64 + 1, self.max_queued_per_tenant as u64) {
+            state.rejected += 1;
+            return Err(e);
+        }
+
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        let queue = state.queues.entry(tenant.to_string()).or_default();
+        queue.push_back(ticket);
+        let depth = queue.len();
+        if depth == 1 {
+            state.rotation.push_back(tenant.to_string());
+        }
+        state.peak_queue_depth = state.peak_queue_depth.max(depth);
+        state.dispatch(self.max_concurrent);
+        self.released.notify_all();
+
+        while !state.granted.remove(&ticket) {
+            state = self.released.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        Ok(VerificationPermit { limiter: Arc::clone(self) })
+    }
+
+    pub fn metrics(&self) -> LimiterMetrics {
+        let state = self.state();
+        LimiterMetrics {
+            max_concurrent: self.max_concurrent,
+            in_flight: state.in_flight,
+            queued: state.queues.iter().map(|(tenant, queue)| (tenant.clone(), queue.len())).collect(),
+            peak_queue_depth: state.peak_queue_depth,
+            admitted: state.admitted,
+            rejected: state.rejected,
+        }
+    }
+
+    fn release(&self) {
+        let mut state = self.state();
+        state.in_flight -= 1;
+        state.dispatch(self.max_concurrent);
+        self.released.notify_all();
+    }
+}
+
+/// Verification slot, returned to the limiter on drop
+#[derive(Debug)]
+pub struct VerificationPermit {
+    limiter: Arc<VerificationLimiter>,
+}
+
+impl Drop for VerificationPermit {
+    fn drop(&mut self) {
+        self.limiter.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn wait_until(limiter: &VerificationLimiter, done: impl Fn(&LimiterMetrics) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done(&limiter.metrics()) {
+            assert!(Instant::now() < deadline, "limiter never reached the expected state");
+            std::thread::sleep(Duration::from_millis(2));
+        }
+    }
+
+    #[test]
+    fn test_tenants_are_served_in_turn() {
+        let limiter = Arc::new(VerificationLimiter::new(1, 3));
+        let held = limiter.acquire("flood").unwrap();
+
+        // Three queued flood verifications, then one from a quiet tenant
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let spawn = |tenant: &'static str| {
+            let (limiter, order) = (Arc::clone(&limiter), Arc::clone(&order));
+            std::thread::spawn(move || {
+                let _permit = limiter.acquire(tenant).unwrap();
+                order.lock().unwrap().push(tenant);
+            })
+        };
+        let mut threads: Vec<_> = (1..=3)
+            .map(|queued| {
+                let thread = spawn("flood");
+                wait_until(&limiter, |m| m.queued.get("flood") == Some(&queued));
+                thread
+            })
+            .collect();
+        assert!(matches!(limiter.acquire("flood"), Err(crate::ZKPError::LimitExceeded { max: 3, .. })));
+        threads.push(spawn("quiet"));
+        wait_until(&limiter, |m| m.total_queued() == 4);
+
+        drop(held);
+        threads.into_iter().for_each(|thread| thread.join().unwrap());
+        assert_eq!(*order.lock().unwrap(), vec!["flood", "quiet", "flood", "flood"]);
+
+        let metrics = limiter.metrics();
+        assert_eq!((metrics.in_flight, metrics.total_queued()), (0, 0));
+        assert_eq!((metrics.admitted, metrics.rejected, metrics.peak_queue_depth), (5, 1, 3));
+    }
+}
@@ -0,0 +1,238 @@
+//! Prover Configuration
+//!
+//! Deployment settings for the proving service, loaded from a TOML file and/or
+//! `REPID_*` environment variables instead of hardcoded constructor arguments
+
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::BackendKind;
+use crate::custom_stark::{HashBackend, StarkParams};
+use crate::limits::ProofLimits;
+use crate::{Result, SecurityLevel, ZKPError};
+
+/// Environment variable pointing at a TOML config file
+pub const CONFIG_PATH_ENV: &str = "REPID_CONFIG";
+
+/// Verification result cache settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    pub max_entries: usize,
+    pub ttl_secs: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: 1024,
+            ttl_secs: 300,
+        }
+    }
+}
+
+/// Complete prover service configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProverConfig {
+    pub security_level: SecurityLevel,
+    /// Proving stack to construct
+    pub backend: BackendKind,
+    /// Override the security level's query count
+    pub num_queries: Option<usize>,
+    /// Override the security level's blowup factor
+    pub blowup_factor: Option<usize>,
+    pub hash_backend: HashBackend,
+    /// Worker threads for batch work (0 uses every available core)
+    pub parallelism: usize,
+    /// Memory and size bounds
+    pub limits: ProofLimits,
+    pub cache: CacheConfig,
+}
+
+impl ProverConfig {
+    /// Defaults for a security level
+    pub fn new(security_level: SecurityLevel) -> Self {
+        Self {
+            security_level,
+            ..Self::default()
+        }
+    }
+
+    /// Parse a TOML document
+    pub fn from_toml_str(toml: &str) -> Result<Self> {
+        let config: Self = toml::from_str(toml).map_err(|e| ZKPError::ConfigError(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load a TOML config file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ZKPError::ConfigError(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml_str(&contents)
+    }
+
+    /// Load `REPID_CONFIG` (if set) and apply `REPID_*` overrides
+    pub fn from_env() -> Result<Self> {
+        let base = match std::env::var(CONFIG_PATH_ENV) {
+            Ok(path) => Self::from_file(path)?,
+            Err(_) => Self::default(),
+        };
+        base.with_env_overrides(|key| std::env::var(key).ok())
+    }
+
+    /// Apply overrides from a variable lookup (`std::env::var` in production)
+    pub fn with_env_overrides(mut self, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        fn parse<T: FromStr>(key: &str, value: String) -> Result<T> {
+            value.trim().parse().map_err(|_| ZKPError::ConfigError(format!("invalid value for {}: '{}'", key, value)))
+        }
+
+        if let Some(v) = var("REPID_SECURITY_LEVEL") {
+            self.security_level = parse("REPID_SECURITY_LEVEL", v)?;
+        }
+        if let Some(v) = var("REPID_BACKEND") {
+            self.backend = parse("REPID_BACKEND", v)?;
+        }
+        if let Some(v) = var("REPID_NUM_QUERIES") {
+            self.num_queries = Some(parse("REPID_NUM_QUERIES", v)?);
+        }
+        if let Some(v) = var("REPID_BLOWUP_FACTOR") {
+            self.blowup_factor = Some(parse("REPID_BLOWUP_FACTOR", v)?);
+        }
+        if let Some(v) = var("REPID_HASH_BACKEND") {
+            self.hash_backend = parse("REPID_HASH_BACKEND", v)?;
+        }
+        if let Some(v) = var("REPID_PARALLELISM") {
+            self.parallelism = parse("REPID_PARALLELISM", v)?;
+        }
+        if let Some(v) = var("REPID_MAX_TRACE_HEIGHT") {
+            self.limits.max_trace_height = parse("REPID_MAX_TRACE_HEIGHT", v)?;
+        }
+        if let Some(v) = var("REPID_MAX_PROOF_BYTES") {
+            self.limits.max_proof_bytes = parse("REPID_MAX_PROOF_BYTES", v)?;
+        }
+        if let Some(v) = var("REPID_CACHE_ENABLED") {
+            self.cache.enabled = parse("REPID_CACHE_ENABLED", v)?;
+        }
+        if let Some(v) = var("REPID_CACHE_MAX_ENTRIES") {
+            self.cache.max_entries = parse("REPID_CACHE_MAX_ENTRIES", v)?;
+        }
+        if let Some(v) = var("REPID_CACHE_TTL_SECS") {
+            self.cache.ttl_secs = parse("REPID_CACHE_TTL_SECS", v)?;
+        }
+
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Concrete STARK parameters after overrides
+    pub fn stark_params(&self) -> StarkParams {
+        let defaults = self.security_level.params();
+        StarkParams {
+            num_queries: self.num_queries.unwrap_or(defaults.num_queries),
+            blowup_factor: self.blowup_factor.unwrap_or(defaults.blowup_factor),
+            ..defaults
+        }
+    }
+
+    /// Reject parameter combinations the prover cannot run
+    pub fn validate(&self) -> Result<()> {
+        self.stark_params().validate()
+    }
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        Self {
+            security_level: SecurityLevel::Standard,
+            backend: BackendKind::default(),
+            num_queries: None,
+            blowup_factor: None,
+            hash_backend: HashBackend::default(),
+            parallelism: 0,
+            limits: ProofLimits::default(),
+            cache: CacheConfig::default(),
+        }
+    }
+}
+
+impl FromStr for SecurityLevel {
+    type Err = ZKPError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "fast" => Ok(SecurityLevel::Fast),
+            "standard" => Ok(SecurityLevel::Standard),
+            "high" => Ok(SecurityLevel::High),
+            other => Err(ZKPError::ConfigError(format!("unknown security level '{}'", other))),
+        }
+    }
+}
+
+impl FromStr for HashBackend {
+    type Err = ZKPError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "blake3" => Ok(HashBackend::Blake3),
+            "sha256" => Ok(HashBackend::Sha256),
+            other => Err(ZKPError::ConfigError(format!("unknown hash backend '{}'", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toml_with_partial_sections() {
+        let config = ProverConfig::from_toml_str(
+            r#"
+            security_level = "high"
+            num_queries = 100
+            hash_backend = "sha256"
+
+            [limits]
+            max_trace_height = 1024
+
+            [cache]
+            enabled = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.security_level, SecurityLevel::High);
+        assert_eq!(config.stark_params().num_queries, 100);
+        assert_eq!(config.stark_params().blowup_factor, 16);
+        assert_eq!(config.hash_backend, HashBackend::Sha256);
+        assert_eq!(config.limits.max_trace_height, 1024);
+        assert_eq!(config.limits.max_categories, ProofLimits::default().max_categories);
+        assert!(config.cache.enabled);
+
+        assert!(ProverConfig::from_toml_str("blowup_factor = 3").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let vars = [("REPID_SECURITY_LEVEL", "Fast"), ("REPID_PARALLELISM", "4"), ("REPID_CACHE_TTL_SECS", "60")];
+        let lookup = |key: &str| vars.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string());
+
+        let config = ProverConfig::default().with_env_overrides(lookup).unwrap();
+        assert_eq!(config.security_level, SecurityLevel::Fast);
+        assert_eq!(config.parallelism, 4);
+        assert_eq!(config.cache.ttl_secs, 60);
+
+        let bad = |key: &str| (key == "REPID_NUM_QUERIES").then(|| "many".to_string());
+        assert!(matches!(
+            ProverConfig::default().with_env_overrides(bad),
+            Err(ZKPError::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,294 @@
+//! Proving Cost Accounting
+//!
+//! Credit costs per proof request derived from security parameters and trace
+//! size, plus admission hooks so deployments can bill, reject or defer work
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::custom_stark::StarkParams;
+use crate::poseidon2::Poseidon2Gadget;
+use crate::{Result, ZKPError};
+
+/// Estimated dimensions of the trace a request will produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceShape {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl TraceShape {
+    /// Shape of a threshold proof over `num_scores` categories
+    pub fn threshold(num_scores: usize) -> Self {
+        Self::with_gadget(6 + 2 * num_scores, 8, 3)
+    }
+
+    /// Shape of a committed threshold proof opening `num_scores` categories
+    pub fn committed_threshold(num_scores: usize) -> Self {
+        let opening_rows = Poseidon2Gadget::rows_for(2 + 2 * num_scores);
+        Self::with_gadget(6 + 2 * num_scores + Poseidon2Gadget::COLUMNS, opening_rows, 3)
+    }
+
+    /// Shape of a proof revealing one score of a commitment to `num_scores`
+    pub fn score_opening(num_scores: usize) -> Self {
+        Self::with_gadget(Poseidon2Gadget::COLUMNS, Poseidon2Gadget::rows_for(1 + 2 * num_scores), 3)
+    }
+
+    /// Shape of a hidden-category threshold proof over a set of `num_categories`
+    pub fn hidden_threshold(num_categories: usize) -> Self {
+        let opening_rows = Poseidon2Gadget::rows_for(1 + num_categories);
+        Self::with_gadget(6 + 2 * num_categories + Poseidon2Gadget::COLUMNS, opening_rows, 3)
+    }
+
+    /// Shape of a chained threshold proof over `num_scores` categories
+    pub fn chained_threshold(num_scores: usize) -> Self {
+        Self::with_gadget(6 + 2 * num_scores + Poseidon2Gadget::COLUMNS, Poseidon2Gadget::rows_for(2), 4)
+    }
+
+    /// Shape of a freshness-bound threshold proof over `num_attestations` scores
+    pub fn fresh_threshold(num_attestations: usize) -> Self {
+        Self::with_gadget(8 + 4 * num_attestations, 8, 4)
+    }
+
+    /// Shape of a co-signed threshold proof hashing `num_attestations` attestation digests
+    pub fn cosigned_threshold(num_attestations: usize) -> Self {
+        let rows = (num_attestations * Poseidon2Gadget::rows_for(4)).max(8);
+        Self::with_gadget(6 + 2 * num_attestations + Poseidon2Gadget::COLUMNS, rows, 5 + 2 * num_attestations)
+    }
+
+    /// Shape of an unrevoked threshold proof over `num_attestations` against `num_revoked` revocations
+    pub fn unrevoked_threshold(num_attestations: usize, num_revoked: usize) -> Self {
+        let rows = (num_attestations * Poseidon2Gadget::rows_for(4))
+            .max(Poseidon2Gadget::rows_for(num_revoked))
+            .max(num_revoked);
+        Self::with_gadget(8 + 3 * num_attestations + 2 * Poseidon2Gadget::COLUMNS, rows, 4)
+    }
+
+    /// Shape of a linked-wallet threshold proof over `num_scores` pooled from `num_wallets`
+    pub fn linked_threshold(num_scores: usize, num_wallets: usize) -> Self {
+        let linkage_rows = Poseidon2Gadget::rows_for(1)
+            + num_wallets * Poseidon2Gadget::rows_for(2)
+            + Poseidon2Gadget::rows_for(num_wallets);
+        Self::with_gadget(6 + 2 * num_scores + Poseidon2Gadget::COLUMNS, linkage_rows, 5)
+    }
+
+    /// Shape of a designated-verifier threshold proof over `num_scores` categories
+    pub fn designated_threshold(num_scores: usize) -> Self {
+        let witness_rows = Poseidon2Gadget::rows_for(crate::designated::SECRET_ELEMENTS);
+        Self::with_gadget(6 + 2 * num_scores + Poseidon2Gadget::COLUMNS + 1, witness_rows, 4)
+    }
+
+    /// Shape of an escrowed threshold proof over `num_scores` categories
+    pub fn escrowed_threshold(num_scores: usize) -> Self {
+        let key_rows = Poseidon2Gadget::rows_for(crate::escrow::ESCROW_KEY_ELEMENTS + 1);
+        Self::with_gadget(6 + 2 * num_scores + Poseidon2Gadget::COLUMNS, 2 * key_rows, 5)
+    }
+
+    /// Shape of an oracle threshold proof with a key tree of `key_height` levels
+    pub fn oracle_threshold(key_height: usize) -> Self {
+        let signature_rows = Poseidon2Gadget::rows_for(3)
+            + crate::oracle::CHAINS * crate::oracle::CHAIN_LENGTH as usize * Poseidon2Gadget::rows_for(1)
+            + Poseidon2Gadget::rows_for(crate::oracle::CHAINS)
+            + key_height * Poseidon2Gadget::rows_for(2);
+        Self::with_gadget(8 + Poseidon2Gadget::COLUMNS + 2, signature_rows, 6)
+    }
+
+    /// Shape of a rank-bucket proof over `num_scores` against `num_bands` cutoffs
+    pub fn rank_bucket(num_scores: usize, num_bands: usize) -> Self {
+        Self::with_gadget(6 + 2 * num_scores + Poseidon2Gadget::COLUMNS, Poseidon2Gadget::rows_for(2 * num_bands), 2)
+    }
+
+    /// Shape of a category count proof over `num_scores` categories
+    pub fn category_count(num_scores: usize) -> Self {
+        Self::with_gadget(5 + 2 * num_scores, 8, 3)
+    }
+
+    /// Shape of a sustained threshold proof opening `num_leaves` scores at `depth` across `num_epochs`
+    pub fn sustained_threshold(num_leaves: usize, depth: usize, num_epochs: usize) -> Self {
+        let inclusion_rows = num_leaves * (1 + depth) * Poseidon2Gadget::rows_for(3);
+        Self::with_gadget(7 + 2 * num_leaves + Poseidon2Gadget::COLUMNS, inclusion_rows, 4 + 2 * num_epochs)
+    }
+
+    /// Shape of a score delta proof over `num_categories` opened in two epochs at `depth`
+    pub fn score_delta(num_categories: usize, depth: usize) -> Self {
+        let inclusion_rows = 2 * num_categories * (1 + depth) * Poseidon2Gadget::rows_for(3);
+        Self::with_gadget(7 + 4 * num_categories + Poseidon2Gadget::COLUMNS, inclusion_rows, 7 + num_categories)
+    }
+
+    /// Shape of a rate-limited signal proof with its full sparse Merkle path
+    pub fn rate_limited_signal() -> Self {
+        let rows = crate::sparse_merkle::SmtPathGadget::rows();
+        Self::with_gadget(2 + crate::sparse_merkle::SmtPathGadget::COLUMNS, rows, 8)
+    }
+
+    /// Shape of a score history proof with one row per event
+    pub fn history_threshold(num_events: usize) -> Self {
+        Self::with_gadget(8, crate::history::trace_height(num_events), 3)
+    }
+
+    /// Shape of a biometric 4FA proof
+    pub fn biometric() -> Self {
+        Self::with_gadget(8, 4, 2)
+    }
+
+    // The prover always appends a public-input hashing gadget and pads to a power of two
+    fn with_gadget(width: usize, height: usize, num_public_inputs: usize) -> Self {
+        Self {
+            width: width + Poseidon2Gadget::COLUMNS,
+            height: height.max(Poseidon2Gadget::rows_for(num_public_inputs)).next_power_of_two(),
+        }
+    }
+
+    /// Number of cells in the low-degree extension
+    pub fn lde_cells(&self, blowup_factor: usize) -> u64 {
+        (self.width as u64)
+            .saturating_mul(self.height as u64)
+            .saturating_mul(blowup_factor as u64)
+    }
+}
+
+/// Credit prices for proving work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostModel {
+    /// Flat charge per proof
+    pub base_credits: u64,
+    /// Charge per FRI query
+    pub credits_per_query: u64,
+    /// Charge per 1024 low-degree extension cells
+    pub credits_per_kilo_cell: u64,
+}
+
+impl CostModel {
+    /// Credits charged for proving a trace of `shape` under `params`
+    pub fn cost(&self, params: &StarkParams, shape: &TraceShape) -> u64 {
+        let kilo_cells = shape.lde_cells(params.blowup_factor).div_ceil(1024);
+        self.base_credits
+            .saturating_add(self.credits_per_query.saturating_mul(params.num_queries as u64))
+            .saturating_add(self.credits_per_kilo_cell.saturating_mul(kilo_cells))
+    }
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self {
+            base_credits: 10,
+            credits_per_query: 1,
+            credits_per_kilo_cell: 2,
+        }
+    }
+}
+
+/// A priced proving request presented to a `BudgetHook`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofCharge {
+    pub operation_type: String,
+    pub shape: TraceShape,
+    pub credits: u64,
+}
+
+/// Decision returned by a `BudgetHook` before proving starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Admission {
+    /// Run the request now
+    Admit,
+    /// Refuse the request outright
+    Reject(String),
+    /// Ask the caller to queue the request and retry later
+    Defer { retry_after: Duration },
+}
+
+impl Admission {
+    /// Convert a non-admitting decision into the matching error
+    pub fn into_result(self) -> Result<()> {
+        match self {
+            Admission::Admit => Ok(()),
+            Admission::Reject(reason) => Err(ZKPError::BudgetExceeded(reason)),
+            Admission::Defer { retry_after } => Err(ZKPError::RateLimited {
+                retry_after_ms: retry_after.as_millis() as u64,
+            }),
+        }
+    }
+}
+
+/// Admission and billing callbacks around every proof
+pub trait BudgetHook: Send + Sync {
+    /// Decide whether a priced request may be proven
+    fn admit(&self, charge: &ProofCharge) -> Admission;
+
+    /// Called after the proof was generated successfully
+    fn record(&self, _charge: &ProofCharge) {}
+}
+
+/// Fixed pool of prepaid credits, debited as proofs complete
+#[derive(Debug)]
+pub struct CreditBudget {
+    remaining: Mutex<u64>,
+}
+
+impl CreditBudget {
+    pub fn new(credits: u64) -> Self {
+        Self { remaining: Mutex::new(credits) }
+    }
+
+    /// Credits still available
+    pub fn remaining(&self) -> u64 {
+        *self.remaining.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl BudgetHook for CreditBudget {
+    fn admit(&self, charge: &ProofCharge) -> Admission {
+        let remaining = self.remaining();
+        if charge.credits > remaining {
+            return Admission::Reject(format!(
+                "{} costs {} credits, {} remaining",
+                charge.operation_type, charge.credits, remaining
+            ));
+        }
+        Admission::Admit
+    }
+
+    fn record(&self, charge: &ProofCharge) {
+        let mut remaining = self.remaining.lock().unwrap_or_else(|e| e.into_inner());
+        *remaining = remaining.saturating_sub(charge.credits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecurityLevel;
+
+    #[test]
+    fn test_cost_grows_with_security_and_trace_size() {
+        let model = CostModel::default();
+        let shape = TraceShape::threshold(2);
+
+        let fast = model.cost(&SecurityLevel::Fast.params(), &shape);
+        let high = model.cost(&SecurityLevel::High.params(), &shape);
+        assert!(high > fast);
+
+        let wide = model.cost(&SecurityLevel::Fast.params(), &TraceShape::committed_threshold(32));
+        assert!(wide > fast);
+    }
+
+    #[test]
+    fn test_credit_budget_rejects_over_budget_requests() {
+        let budget = CreditBudget::new(100);
+        let charge = ProofCharge {
+            operation_type: "threshold_verification".to_string(),
+            shape: TraceShape::threshold(1),
+            credits: 60,
+        };
+
+        assert_eq!(budget.admit(&charge), Admission::Admit);
+        budget.record(&charge);
+        assert_eq!(budget.remaining(), 40);
+        assert!(matches!(
+            budget.admit(&charge).into_result(),
+            Err(ZKPError::BudgetExceeded(_))
+        ));
+    }
+}
@@ -0,0 +1,345 @@
+//! Verifiable Credential Exchange
+//!
+//! Score attestations as W3C Verifiable Credentials, in JSON-LD and VC-JWT
+//! form, so issuers in the DID ecosystem feed the pipeline without custom
+//! adapters. Issuers are `did:key` Ed25519 identifiers. Each issuer signature
+//! travels as one entry of the credential's proof set and signs the same
+//! digest as `CosignedAttestation`, so imported credentials satisfy issuance
+//! policies unchanged. The JWT form wraps the credential in an EdDSA JWT
+//! signed by its issuer.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::freshness::AttestedScore;
+use crate::issuance::{CosignedAttestation, IssuerKey, IssuerSignature};
+use crate::signer::{SignatureScheme, Signer};
+use crate::{RepIDCategory, Result, ZKPError};
+
+/// Base context of every W3C credential
+pub const CREDENTIALS_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+/// Context defining the RepID score terms
+pub const REPID_CONTEXT: &str = "https://repid.hyperdag.io/credentials/v1";
+/// Credential type of score attestations
+pub const SCORE_CREDENTIAL_TYPE: &str = "RepIDScoreCredential";
+/// Proof type: Ed25519 over `CosignedAttestation::signing_digest`
+pub const PROOF_TYPE: &str = "RepIDAttestationSignature2024";
+
+/// Multicodec prefix of an Ed25519 public key
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+/// Attested score of one subject
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreSubject {
+    /// Subject wallet, as passed to `CosignedAttestation::new`
+    pub id: String,
+    pub category: RepIDCategory,
+    pub score: u32,
+}
+
+/// One issuer's entry in the credential's proof set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialProof {
+    #[serde(rename = "type")]
+    pub proof_type: String,
+    pub proof_purpose: String,
+    /// `did:key` URL of the signing key
+    pub verification_method: String,
+    /// Multibase (base58btc) signature
+    pub proof_value: String,
+}
+
+/// Score attestation as a W3C Verifiable Credential
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreCredential {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    #[serde(rename = "type")]
+    pub types: Vec<String>,
+    /// `did:key` of the first signer
+    pub issuer: String,
+    /// RFC 3339 issuance time
+    pub issuance_date: String,
+    pub credential_subject: ScoreSubject,
+    #[serde(default)]
+    pub proof: Vec<CredentialProof>,
+}
+
+impl ScoreCredential {
+    /// Credential carrying every signature of `attestation`
+    pub fn from_attestation(attestation: &CosignedAttestation) -> Result<Self> {
+        let first = attestation
+            .signatures
+            .first()
+            .ok_or_else(|| ZKPError::InvalidInput("Unsigned attestations have no credential issuer".to_string()))?;
+        let issued = chrono::DateTime::from_timestamp(attestation.attested.issued_at as i64, 0)
+            .ok_or_else(|| ZKPError::InvalidInput(format!("issued_at {} is out of range", attestation.attested.issued_at)))?;
+        Ok(Self {
+            context: vec![CREDENTIALS_CONTEXT.to_string(), REPID_CONTEXT.to_string()],
+            types: vec!["VerifiableCredential".to_string(), SCORE_CREDENTIAL_TYPE.to_string()],
+            issuer: did_key(&first.public_key),
+            issuance_date: issued.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            credential_subject: ScoreSubject {
+                id: attestation.subject.clone(),
+                category: attestation.attested.category.clone(),
+                score: attestation.attested.score,
+            },
+            proof: attestation
+                .signatures
+                .iter()
+                .map(|signed| CredentialProof {
+                    proof_type: PROOF_TYPE.to_string(),
+                    proof_purpose: "assertionMethod".to_string(),
+                    verification_method: verification_method(&signed.public_key),
+                    proof_value: format!("z{}", base58_encode(&signed.signature)),
+                })
+                .collect(),
+        })
+    }
+
+    /// Internal attestation, after checking every proof in the set
+    ///
+    /// The issuer must be one of the signers; which signers count is left to
+    /// the issuance policy the attestation is later checked against.
+    pub fn to_attestation(&self) -> Result<CosignedAttestation> {
+        if !self.types.iter().any(|t| t == SCORE_CREDENTIAL_TYPE) {
+            return Err(ZKPError::InvalidInput(format!("credential is not a {}", SCORE_CREDENTIAL_TYPE)));
+        }
+        let issued_at = chrono::DateTime::parse_from_rfc3339(&self.issuance_date)
+            .map_err(|e| ZKPError::SerializationError(format!("issuanceDate: {}", e)))?
+            .timestamp();
+        let subject = &self.credential_subject;
+        let mut attestation = CosignedAttestation::new(
+            &subject.id,
+            AttestedScore {
+                category: subject.category.clone(),
+                score: subject.score,
+                issued_at: u64::try_from(issued_at)
+                    .map_err(|_| ZKPError::InvalidInput(format!("issuanceDate {} predates the epoch", self.issuance_date)))?,
+            },
+        );
+
+        let digest = attestation.signing_digest();
+        for proof in &self.proof {
+            if proof.proof_type != PROOF_TYPE {
+                return Err(ZKPError::InvalidInput(format!("unsupported proof type '{}'", proof.proof_type)));
+            }
+            let did = proof.verification_method.split('#').next().unwrap_or_default();
+            let signed = IssuerSignature {
+                public_key: parse_did_key(did)?,
+                signature: proof
+                    .proof_value
+                    .strip_prefix('z')
+                    .and_then(base58_decode)
+                    .ok_or_else(|| ZKPError::SerializationError("proofValue is not base58btc multibase".to_string()))?,
+            };
+            if !signed.verifies(&digest) {
+                return Err(ZKPError::VerificationError(format!("credential proof by {} does not verify", did)));
+            }
+            attestation.signatures.push(signed);
+        }
+
+        let issuer = parse_did_key(&self.issuer)?;
+        if !attestation.signatures.iter().any(|signed| signed.public_key == issuer) {
+            return Err(ZKPError::VerificationError(format!("credential carries no proof by its issuer {}", self.issuer)));
+        }
+        Ok(attestation)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    /// VC-JWT of the credential, signed by its issuer
+    pub fn to_jwt(&self, issuer: &dyn Signer) -> Result<String> {
+        if issuer.scheme() != SignatureScheme::Ed25519 {
+            return Err(ZKPError::SigningError(format!("VC-JWTs are EdDSA, signer uses {:?}", issuer.scheme())));
+        }
+        let key = issuer.public_key()?;
+        if key.as_slice().try_into().map(|key: IssuerKey| did_key(&key)).ok().as_deref() != Some(self.issuer.as_str()) {
+            return Err(ZKPError::SigningError(format!("signer is not the credential issuer {}", self.issuer)));
+        }
+
+        let header = serde_json::json!({ "alg": "EdDSA", "typ": "JWT" });
+        let claims = serde_json::json!({
+            "iss": self.issuer,
+            "sub": self.credential_subject.id,
+            "nbf": chrono::DateTime::parse_from_rfc3339(&self.issuance_date)
+                .map_err(|e| ZKPError::SerializationError(format!("issuanceDate: {}", e)))?
+                .timestamp(),
+            "vc": self,
+        });
+        let signing_input = format!("{}.{}", encode_segment(&header)?, encode_segment(&claims)?);
+        let signature = issuer.sign(signing_input.as_bytes())?;
+        Ok(format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature)))
+    }
+
+    /// Credential from a VC-JWT, after checking the JWT signature and its claims
+    pub fn from_jwt(token: &str) -> Result<Self> {
+        let malformed = |what: &str| ZKPError::SerializationError(format!("VC-JWT {}", what));
+        let mut segments = token.split('.');
+        let (Some(header), Some(claims), Some(signature), None) =
+            (segments.next(), segments.next(), segments.next(), segments.next())
+        else {
+            return Err(malformed("must have three segments"));
+        };
+
+        let header: serde_json::Value = decode_segment(header)?;
+        if header["alg"] != "EdDSA" {
+            return Err(ZKPError::InvalidInput(format!("unsupported VC-JWT algorithm {}", header["alg"])));
+        }
+        let payload: serde_json::Value = decode_segment(claims)?;
+        let issuer = payload["iss"].as_str().ok_or_else(|| malformed("has no iss claim"))?;
+        let key = VerifyingKey::from_bytes(&parse_did_key(issuer)?)
+            .map_err(|e| ZKPError::InvalidInput(format!("{}: {}", issuer, e)))?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or_else(|| malformed("signature is not a base64url Ed25519 signature"))?;
+        let signing_input = &token[..token.len() - token.rsplit('.').next().unwrap_or_default().len() - 1];
+        key.verify(signing_input.as_bytes(), &signature)
+            .map_err(|_| ZKPError::VerificationError(format!("VC-JWT signature by {} does not verify", issuer)))?;
+
+        let credential: Self = serde_json::from_value(payload["vc"].clone())
+            .map_err(|e| ZKPError::SerializationError(format!("vc claim: {}", e)))?;
+        if credential.issuer != issuer || payload["sub"] != credential.credential_subject.id.as_str() {
+            return Err(ZKPError::VerificationError("VC-JWT claims disagree with the credential".to_string()));
+        }
+        Ok(credential)
+    }
+}
+
+/// Attestation from a credential in either form (JSON-LD object or VC-JWT)
+pub fn import_attestation(credential: &str) -> Result<CosignedAttestation> {
+    let credential = credential.trim();
+    match credential.starts_with('{') {
+        true => ScoreCredential::from_json(credential)?.to_attestation(),
+        false => ScoreCredential::from_jwt(credential)?.to_attestation(),
+    }
+}
+
+/// `did:key` identifier of an Ed25519 issuer key
+pub fn did_key(key: &IssuerKey) -> String {
+    let mut bytes = ED25519_MULTICODEC.to_vec();
+    bytes.extend_from_slice(key);
+    format!("did:key:z{}", base58_encode(&bytes))
+}
+
+/// Issuer key named by a `did:key` identifier
+pub fn parse_did_key(did: &str) -> Result<IssuerKey> {
+    let unsupported = || ZKPError::InvalidInput(format!("'{}' is not an Ed25519 did:key", did));
+    let bytes = did
+        .strip_prefix("did:key:z")
+        .and_then(base58_decode)
+        .ok_or_else(unsupported)?;
+    match bytes.strip_prefix(&ED25519_MULTICODEC[..]) {
+        Some(key) => key.try_into().map_err(|_| unsupported()),
+        None => Err(unsupported()),
+    }
+}
+
+fn verification_method(key: &IssuerKey) -> String {
+    let did = did_key(key);
+    let fragment = did.trim_start_matches("did:key:");
+    format!("{}#{}", did, fragment)
+}
+
+fn encode_segment(value: &serde_json::Value) -> Result<String> {
+    let json = serde_json::to_vec(value).map_err(|e| ZKPError::SerializationError(e.to_string()))?;
+    Ok(URL_SAFE_NO_PAD.encode(json))
+}
+
+fn decode_segment(segment: &str) -> Result<serde_json::Value> {
+    let json = URL_SAFE_NO_PAD
+        .decode(segment)
+        .map_err(|e| ZKPError::SerializationError(format!("VC-JWT segment: {}", e)))?;
+    serde_json::from_slice(&json).map_err(|e| ZKPError::SerializationError(format!("VC-JWT segment: {}", e)))
+}
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+fn base58_encode(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    // Base-58 digits, least significant first
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let leading = std::iter::repeat_n('1', zeros);
+    leading.chain(digits.iter().rev().map(|&d| BASE58_ALPHABET[d as usize] as char)).collect()
+}
+
+fn base58_decode(encoded: &str) -> Option<Vec<u8>> {
+    let zeros = encoded.bytes().take_while(|&c| c == b'1').count();
+    // Bytes, least significant first
+    let mut bytes: Vec<u8> = Vec::with_capacity(encoded.len());
+    for c in encoded.bytes().skip(zeros) {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut decoded = vec![0u8; zeros];
+    decoded.extend(bytes.iter().rev());
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuance::IssuancePolicy;
+    use crate::signer::InMemorySigner;
+
+    #[test]
+    fn test_credentials_round_trip_both_forms() {
+        let issuers: Vec<InMemorySigner> = (1..=2).map(|seed| InMemorySigner::from_seed([seed; 32])).collect();
+        let keys: Vec<IssuerKey> = issuers.iter().map(|s| s.public_key().unwrap().try_into().unwrap()).collect();
+        let attested = AttestedScore { category: RepIDCategory::Governance, score: 80, issued_at: 1_700_000_000 };
+        let mut attestation = CosignedAttestation::new("0xtest", attested);
+        issuers.iter().for_each(|issuer| attestation.cosign(issuer).unwrap());
+
+        let credential = ScoreCredential::from_attestation(&attestation).unwrap();
+        assert_eq!(credential.issuance_date, "2023-11-14T22:13:20Z");
+        assert_eq!(parse_did_key(&credential.issuer).unwrap(), keys[0]);
+        let json = credential.to_json().unwrap();
+        assert!(json.contains("\"@context\"") && json.contains("\"credentialSubject\""));
+        let imported = import_attestation(&json).unwrap();
+        assert_eq!(imported, attestation);
+        assert!(IssuancePolicy::new(RepIDCategory::Governance, 2, keys).unwrap().check(&imported).is_ok());
+
+        let jwt = credential.to_jwt(&issuers[0]).unwrap();
+        assert_eq!(import_attestation(&jwt).unwrap(), attestation);
+        assert!(credential.to_jwt(&issuers[1]).is_err());
+
+        // Inflating the score breaks every proof in the set, and the JWT signature
+        let inflated = json.replace("\"score\": 80", "\"score\": 95");
+        assert!(matches!(import_attestation(&inflated), Err(ZKPError::VerificationError(_))));
+        let (signing_input, _) = jwt.rsplit_once('.').unwrap();
+        let forged = format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode([0u8; 64]));
+        assert!(matches!(import_attestation(&forged), Err(ZKPError::VerificationError(_))));
+    }
+}
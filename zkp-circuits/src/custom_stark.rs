@@ -3,12 +3,67 @@
 //! Implements a lightweight zk-STARK system optimized for RepID verification
 //! Uses BabyBear field arithmetic and FRI-based polynomial commitment
 
+#[cfg(feature = "prover")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "prover")]
+use std::sync::Arc;
+
 use blake3::Hasher;
-use rand::{RngCore, SeedableRng};
-use rand_chacha::ChaCha20Rng;
+#[cfg(feature = "prover")]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::{RepIDCategory, DecayParameters, Result, ZKPError};
+use crate::air::{ConstraintSystem, CustomAir, TraceLayout};
+use crate::circuits::{Circuit, CircuitShape};
+use crate::domain::{TwoAdicSubgroup, MULTIPLICATIVE_GENERATOR};
+use crate::polynomial::Evaluations;
+use crate::poseidon2::DIGEST_ELEMENTS;
+use crate::public_inputs::PublicInputs;
+use crate::normalization::{adjustments_digest, ScoreScale};
+use crate::saturation::CategoryCap;
+use crate::transcript::Transcript;
+pub use crate::transcript::{TranscriptEntry, TranscriptLog};
+use crate::{Result, ThresholdVerificationRequest, ZKPError};
+
+// Witness generation and proving only
+#[cfg(feature = "prover")]
+use crate::{
+    air::{CategoryCountAir, ScoreHistoryAir, ThresholdAir, ThresholdDecay, MAX_AGGREGATE_SCORE, MAX_DECAYED_SCORE},
+    absence::RequestedScores,
+    chain::ChainLink,
+    circuits::{
+        AdjustedThresholdAir, CosignedThresholdAir, DesignatedThresholdAir, EscrowedThresholdAir, FlaggedThresholdAir,
+        FreshThresholdAir, LinkedThresholdAir, OpeningAir, OracleThresholdAir, RateLimitedSignalAir, SnapshotLeafShape,
+        SnapshotOpening, SnapshotOpeningsAir, ThresholdShape, UnrevokedThresholdAir,
+    },
+    clock::{Clock, SystemClock},
+    commitment::{ScoreCommitment, ScoreOpening},
+    decay::decay_span,
+    designated::{seed_elements, Designation},
+    entropy::{self, RngProvider},
+    escrow::EscrowKey,
+    epoch::{EpochScores, SnapshotLeaf},
+    freshness::{AttestedScore, FreshnessBound},
+    hidden::CategorySetOpening,
+    history::ScoreEvent,
+    issuance::{check_attestations, policies_digest, CosignedAttestation, IssuancePolicy},
+    ledger::wallet_tag,
+    limits::ProofLimits,
+    linkage::{check_wallets, IdentitySecret, LinkedWallet},
+    normalization::normalize_scores,
+    oracle::{signed_digits, OraclePublicKey, SignedScore},
+    poseidon2,
+    progress::{ProgressObserver, ProgressReporter, ProvingStage},
+    rank::{DistributionCommitment, ScoreDistribution},
+    revocation::{RevocableAttestation, RevocationList},
+    rln::RateLimit,
+    saturation::apply_caps,
+    slashing::PenaltyEvent,
+    sparse_merkle::{key_from_field, leaf_hash, SmtWitness, DEPTH},
+    sustained::{epoch_weights, WEIGHT_SCALE},
+    wallet::{WalletCommitment, WalletSalt},
+    DecayParameters, RepIDCategory,
+};
 
 /// BabyBear field implementation (p = 2^31 - 2^27 + 1)
 const BABY_BEAR_MODULUS: u64 = 0x78000001; // 2013265921
@@ -29,6 +84,34 @@ impl BabyBearField {
         Self::new(value as u64)
     }
 
+    /// Whether the raw value is the reduced representative in [0, p)
+    pub fn is_canonical(&self) -> bool {
+        self.0 < Self::MODULUS
+    }
+
+    /// Reduced representative of the same residue
+    ///
+    /// Arithmetic results are always canonical; only values built directly
+    /// through the public field can be out of range.
+    pub fn canonicalize(&self) -> Self {
+        Self::new(self.0)
+    }
+
+    /// Encode a signed value, negatives as p - |value|
+    pub fn from_i64(value: i64) -> Self {
+        let magnitude = Self::new(value.unsigned_abs());
+        if value < 0 { Self::ZERO - magnitude } else { magnitude }
+    }
+
+    /// Decode a signed value, reading elements above (p - 1) / 2 as negative
+    pub fn to_i64(&self) -> i64 {
+        if self.0 > Self::MODULUS / 2 {
+            self.0 as i64 - Self::MODULUS as i64
+        } else {
+            self.0 as i64
+        }
+    }
+
     pub fn to_bytes(&self) -> [u8; 8] {
         self.0.to_le_bytes()
     }
@@ -44,7 +127,7 @@ impl BabyBearField {
 
         while e > 0 {
             if e & 1 == 1 {
-                result = result * base;
+                result *= base;
             }
             base = base * base;
             e >>= 1;
@@ -54,25 +137,70 @@ impl BabyBearField {
     }
 
     pub fn inverse(&self) -> Option<Self> {
-        if self.0 == 0 {
+        if self.canonicalize() == Self::ZERO {
             return None;
         }
         // Fermat's little theorem: a^(p-2) ≡ a^(-1) (mod p)
         Some(self.pow(Self::MODULUS - 2))
     }
+
+    pub fn double(&self) -> Self {
+        *self + *self
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// `self^(2^power_log)` by repeated squaring
+    pub fn exp_power_of_2(&self, power_log: usize) -> Self {
+        (0..power_log).fold(*self, |acc, _| acc.square())
+    }
+
+    pub fn sum(values: &[Self]) -> Self {
+        values.iter().fold(Self::ZERO, |acc, &v| acc + v)
+    }
+
+    pub fn product(values: &[Self]) -> Self {
+        values.iter().fold(Self::ONE, |acc, &v| acc * v)
+    }
+
+    /// Invert every element with a single field inversion (Montgomery's trick)
+    ///
+    /// Returns `None` if any element is zero.
+    pub fn batch_inverse(values: &[Self]) -> Option<Vec<Self>> {
+        // prefix[i] = values[0] * ... * values[i - 1]
+        let mut prefix = Vec::with_capacity(values.len());
+        let mut acc = Self::ONE;
+        for &value in values {
+            prefix.push(acc);
+            acc *= value;
+        }
+
+        let mut inverse_acc = acc.inverse()?;
+        let mut inverses = vec![Self::ZERO; values.len()];
+        for i in (0..values.len()).rev() {
+            inverses[i] = inverse_acc * prefix[i];
+            inverse_acc *= values[i];
+        }
+        Some(inverses)
+    }
 }
 
 impl std::ops::Add for BabyBearField {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
-        Self::new(self.0.wrapping_add(rhs.0))
+        // Both operands are below 2^31, so the sum cannot overflow
+        let sum = self.canonicalize().0 + rhs.canonicalize().0;
+        Self(if sum >= Self::MODULUS { sum - Self::MODULUS } else { sum })
     }
 }
 
 impl std::ops::Sub for BabyBearField {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
-        Self::new(self.0.wrapping_sub(rhs.0).wrapping_add(Self::MODULUS))
+        let (lhs, rhs) = (self.canonicalize().0, rhs.canonicalize().0);
+        Self(if lhs >= rhs { lhs - rhs } else { lhs + Self::MODULUS - rhs })
     }
 }
 
@@ -87,16 +215,100 @@ impl std::ops::Mul for BabyBearField {
 impl std::ops::Neg for BabyBearField {
     type Output = Self;
     fn neg(self) -> Self::Output {
-        if self.0 == 0 {
-            self
+        let value = self.canonicalize();
+        if value == Self::ZERO {
+            value
         } else {
-            Self(Self::MODULUS - self.0)
+            Self(Self::MODULUS - value.0)
+        }
+    }
+}
+
+impl std::ops::AddAssign for BabyBearField {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::SubAssign for BabyBearField {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::MulAssign for BabyBearField {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl From<u32> for BabyBearField {
+    fn from(value: u32) -> Self {
+        Self::from_u32(value)
+    }
+}
+
+impl From<u64> for BabyBearField {
+    fn from(value: u64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl std::iter::Sum for BabyBearField {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, v| acc + v)
+    }
+}
+
+impl<'a> std::iter::Sum<&'a BabyBearField> for BabyBearField {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl std::iter::Product for BabyBearField {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, v| acc * v)
+    }
+}
+
+impl<'a> std::iter::Product<&'a BabyBearField> for BabyBearField {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
+/// Uniform sampling by rejection over 31-bit values
+#[cfg(feature = "prover")]
+impl rand::distributions::Distribution<BabyBearField> for rand::distributions::Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> BabyBearField {
+        loop {
+            let candidate = (rng.next_u32() & 0x7fff_ffff) as u64;
+            if candidate < BabyBearField::MODULUS {
+                return BabyBearField(candidate);
+            }
         }
     }
 }
 
+impl num_traits::Zero for BabyBearField {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        self.canonicalize() == Self::ZERO
+    }
+}
+
+impl num_traits::One for BabyBearField {
+    fn one() -> Self {
+        Self::ONE
+    }
+}
+
 /// Execution trace for STARK proof generation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionTrace {
     pub width: usize,
     pub height: usize,
@@ -125,29 +337,88 @@ impl ExecutionTrace {
             BabyBearField::ZERO
         }
     }
+
+    /// CSV dump with a `row,c0,c1,...` header and canonical values
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("row");
+        for col in 0..self.width {
+            csv.push_str(&format!(",c{}", col));
+        }
+        csv.push('\n');
+
+        for (row, values) in self.data.iter().enumerate() {
+            csv.push_str(&row.to_string());
+            for value in values {
+                csv.push_str(&format!(",{}", value.0));
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// JSON dump of the trace dimensions and rows
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self)
+            .map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    /// Place `other`'s columns to the right of this trace, zero-padding the shorter one
+    pub fn append_columns(&self, other: &ExecutionTrace) -> ExecutionTrace {
+        let height = self.height.max(other.height);
+        let mut combined = ExecutionTrace::new(self.width + other.width, height);
+
+        for row in 0..height {
+            for col in 0..self.width {
+                combined.set(row, col, self.get(row, col));
+            }
+            for col in 0..other.width {
+                combined.set(row, self.width + col, other.get(row, col));
+            }
+        }
+
+        combined
+    }
+
+    /// This trace repeated down `height` rows and zero-padded to `width` columns
+    pub fn tiled(&self, height: usize, width: usize) -> ExecutionTrace {
+        let mut tiled = ExecutionTrace::new(width.max(self.width), height);
+        for row in 0..height {
+            for col in 0..self.width {
+                tiled.set(row, col, self.get(row % self.height, col));
+            }
+        }
+        tiled
+    }
 }
 
 /// STARK proof structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StarkProof {
-    /// Merkle root of the execution trace
+    /// Statement structure the verifier rebuilds the constraint system from
+    pub shape: CircuitShape,
+    /// Digest of the execution trace
     pub trace_root: [u8; 32],
-    /// Low-degree extension root  
+    /// Merkle root over the rows of the trace's low-degree extension
     pub lde_root: [u8; 32],
+    /// Merkle root over the quotient's low-degree extension
+    pub quotient_root: [u8; 32],
     /// FRI proof components
     pub fri_proof: FriProof,
     /// Query responses
     pub queries: Vec<QueryResponse>,
-    /// Public inputs
+    /// Public inputs, ending with the digest of `shape`
     pub public_inputs: Vec<BabyBearField>,
+    /// Wide Poseidon2 digest of the public inputs, constrained in-circuit
+    pub public_inputs_digest: crate::poseidon2::Digest,
 }
 
 /// FRI (Fast Reed-Solomon Interactive Oracle) proof
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FriProof {
-    /// Commitment layers
+    /// Merkle root of each folded layer, the first being the batched quotient and trace
     pub commitments: Vec<[u8; 32]>,
-    /// Final polynomial coefficients
+    /// Evaluations of the last layer, sent in the clear
     pub final_poly: Vec<BabyBearField>,
     /// Proof of work nonce
     pub pow_nonce: u64,
@@ -156,443 +427,2297 @@ pub struct FriProof {
 /// Query response for STARK verification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryResponse {
-    /// Queried position
+    /// Queried LDE position
     pub position: usize,
-    /// Value at position
-    pub value: BabyBearField,
-    /// Merkle authentication path
+    /// Trace LDE rows `position + rotation * blowup`, one per rotation the constraints read, ascending
+    pub rows: Vec<MerkleOpening>,
+    /// Quotient evaluation at `position`
+    pub quotient: MerkleOpening,
+    /// Coset of each FRI layer folded into the query's value on the next
+    pub fri_layers: Vec<MerkleOpening>,
+}
+
+/// Values committed in one Merkle leaf, with the siblings from the leaf to the root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleOpening {
+    pub values: Vec<BabyBearField>,
     pub auth_path: Vec<[u8; 32]>,
 }
 
-/// Custom STARK prover based on Plonky3 principles
-pub struct CustomStarkProver {
-    /// Security parameter (number of queries)
-    pub num_queries: usize,
-    /// Blowup factor for LDE
-    pub blowup_factor: usize,
-    /// Random number generator
-    pub rng: ChaCha20Rng,
+#[cfg(feature = "prover")]
+pub fn running_sums(contributions: &[i64]) -> Result<Vec<i64>> {
+    let bound = MAX_AGGREGATE_SCORE as i64;
+    let mut total = 0i64;
+    contributions
+        .iter()
+        .map(|&contribution| {
+            total = total.saturating_add(contribution);
+            if !(-bound..bound).contains(&total) {
+                return Err(ZKPError::LimitExceeded {
+                    limit: "aggregate_score".to_string(),
+                    actual: total.unsigned_abs(),
+                    max: MAX_AGGREGATE_SCORE,
+                });
+            }
+            Ok(total)
+        })
+        .collect()
 }
 
-impl CustomStarkProver {
-    pub fn new(num_queries: usize, blowup_factor: usize) -> Self {
-        Self {
-            num_queries,
-            blowup_factor,
-            rng: {
-                let mut rng = ChaCha20Rng::from_seed([42u8; 32]);
-                rng
-            },
+/// Witness of a threshold section, filled into whichever circuit holds it
+#[cfg(feature = "prover")]
+#[derive(Debug, Clone)]
+struct ThresholdWitness {
+    threshold: u32,
+    time_window: u64,
+    timestamp: u64,
+    decay: Option<ThresholdDecay>,
+    private_threshold: bool,
+    /// Tags of the category columns
+    categories: Vec<BabyBearField>,
+    /// Category scores, then slashing penalties as negative amounts
+    contributions: Vec<i64>,
+    running_sums: Vec<i64>,
+    final_score: i64,
+    /// Whether the decayed category total reaches the floor
+    kept: bool,
+    meets_threshold: bool,
+}
+
+#[cfg(feature = "prover")]
+impl ThresholdWitness {
+    /// Refuse a total below the threshold, which no met-threshold circuit accepts
+    fn require_met(self) -> Result<Self> {
+        if !self.meets_threshold {
+            return Err(ZKPError::InvalidInput(format!(
+                "Total score {} does not meet the threshold of {}",
+                self.final_score, self.threshold
+            )));
         }
+        Ok(self)
     }
 
-    /// Generate STARK proof for RepID threshold verification
-    pub fn prove_threshold_verification(
-        &mut self,
-        user_scores: &[(RepIDCategory, u32)],
-        threshold: u32,
-        time_window: u64,
-        decay_params: Option<&DecayParameters>,
-    ) -> Result<StarkProof> {
-        // Create execution trace
-        let trace = self.create_threshold_trace(user_scores, threshold, time_window, decay_params)?;
-        
-        // Generate polynomial constraints
-        let constraints = self.generate_threshold_constraints(&trace, threshold, time_window)?;
-        
-        // Commit to execution trace
-        let trace_commitment = self.commit_to_trace(&trace)?;
-        
-        // Generate low-degree extension
-        let lde = self.compute_lde(&trace)?;
-        let lde_commitment = self.commit_to_lde(&lde)?;
-        
-        // Generate FRI proof
-        let fri_proof = self.generate_fri_proof(&lde, &constraints)?;
-        
-        // Generate query responses
-        let queries = self.generate_queries(&trace, &lde, &fri_proof)?;
-        
-        // Prepare public inputs (only threshold and time_window are public)
-        let public_inputs = vec![
-            BabyBearField::from_u32(threshold),
-            BabyBearField::new(time_window),
-        ];
-        
-        Ok(StarkProof {
-            trace_root: trace_commitment,
-            lde_root: lde_commitment,
-            fri_proof,
-            queries,
-            public_inputs,
-        })
+    /// Leave the threshold a witness, as `ThresholdAir::with_private_threshold`
+    fn with_private_threshold(mut self) -> Self {
+        self.private_threshold = true;
+        self
     }
 
-    /// Generate STARK proof for biometric 4FA verification
-    pub fn prove_biometric_verification(
-        &mut self,
-        webauthn_challenge: [u8; 32],
-        biometric_hash: [u8; 32],
-        factor_proofs: &[bool; 4],
-    ) -> Result<StarkProof> {
-        // Create biometric verification trace
-        let trace = self.create_biometric_trace(webauthn_challenge, biometric_hash, factor_proofs)?;
-        
-        // Generate constraints for 4FA verification
-        let constraints = self.generate_biometric_constraints(&trace, webauthn_challenge)?;
-        
-        // Standard STARK proof generation
-        let trace_commitment = self.commit_to_trace(&trace)?;
-        let lde = self.compute_lde(&trace)?;
-        let lde_commitment = self.commit_to_lde(&lde)?;
-        let fri_proof = self.generate_fri_proof(&lde, &constraints)?;
-        let queries = self.generate_queries(&trace, &lde, &fri_proof)?;
-        
-        // Public input: WebAuthn challenge
-        let challenge_field = BabyBearField::new(
-            u64::from_le_bytes([
-                webauthn_challenge[0], webauthn_challenge[1], webauthn_challenge[2], webauthn_challenge[3],
-                webauthn_challenge[4], webauthn_challenge[5], webauthn_challenge[6], webauthn_challenge[7],
-            ])
-        );
-        
-        let public_inputs = vec![challenge_field];
-        
-        Ok(StarkProof {
-            trace_root: trace_commitment,
-            lde_root: lde_commitment,
-            fri_proof,
-            queries,
-            public_inputs,
-        })
+    fn shape(&self) -> ThresholdShape {
+        ThresholdShape::new(self.contributions.len(), self.decay.clone()).with_categories(self.categories.clone())
     }
 
-    fn create_threshold_trace(
-        &self,
-        user_scores: &[(RepIDCategory, u32)],
-        threshold: u32,
-        time_window: u64,
-        decay_params: Option<&DecayParameters>,
-    ) -> Result<ExecutionTrace> {
-        let trace_length = 8; // Power of 2 for efficient FFT
-        let width = 6 + user_scores.len(); // Basic columns + score columns
-
-        let mut trace = ExecutionTrace::new(width, trace_length);
+    fn air(&self) -> ThresholdAir {
+        let air = ThresholdAir::new(self.contributions.len(), self.threshold, self.time_window, self.timestamp, self.decay.clone());
+        if self.private_threshold {
+            air.with_private_threshold()
+        } else {
+            air
+        }
+    }
 
-        let current_timestamp = chrono::Utc::now().timestamp() as u64;
-        
-        for row in 0..trace_length {
-            let mut col = 0;
-            
-            // Column 0: threshold (public)
-            trace.set(row, col, BabyBearField::from_u32(threshold));
-            col += 1;
-            
-            // Column 1: time_window (public)
-            trace.set(row, col, BabyBearField::new(time_window));
-            col += 1;
-            
-            // Column 2: current_timestamp (private)
-            trace.set(row, col, BabyBearField::new(current_timestamp));
-            col += 1;
-            
-            // Columns 3-N: individual category scores (private)
-            let mut total_score = 0u32;
-            for (_, score) in user_scores {
-                trace.set(row, col, BabyBearField::from_u32(*score));
-                total_score += *score;
-                col += 1;
+    /// Write the section on every row of `trace`, then its gadgets
+    fn fill(&self, trace: &mut ExecutionTrace) {
+        let air = self.air();
+        for row in 0..trace.height {
+            // Threshold, time window and evaluation instant
+            trace.set(row, 0, BabyBearField::from_u32(self.threshold));
+            trace.set(row, 1, BabyBearField::new(self.time_window));
+            trace.set(row, 2, BabyBearField::new(self.timestamp));
+
+            // Category scores, then slashing penalties as negative field elements
+            for (i, &contribution) in self.contributions.iter().enumerate() {
+                trace.set(row, air.score_column(i), BabyBearField::from_i64(contribution));
+                trace.set(row, air.running_sum_column(i), BabyBearField::from_i64(self.running_sums[i]));
             }
-            
-            // Apply decay if configured
-            let mut final_score = total_score;
-            if let Some(decay) = decay_params {
-                if current_timestamp > time_window {
-                    let time_diff = current_timestamp - time_window;
-                    let decay_rate = decay.base_decay_rate as f32 / 10000.0;
-                    let decay_amount = (total_score as f32 * decay_rate * (time_diff as f32 / 86400.0)) as u32;
-                    final_score = final_score.saturating_sub(decay_amount);
-                    
-                    if final_score < decay.min_threshold {
-                        final_score = decay.min_threshold;
-                    }
-                }
+            trace.set(row, air.final_score_column(), BabyBearField::from_i64(self.final_score));
+            trace.set(row, air.meets_threshold_column(), BabyBearField::new(self.meets_threshold as u64));
+            trace.set(row, air.validity_column(), BabyBearField::ONE);
+
+            if air.decay.is_some() {
+                trace.set(row, air.floor_flag_column(), BabyBearField::new(self.kept as u64));
             }
-            
-            // Column N+1: final_score (private)
-            trace.set(row, col, BabyBearField::from_u32(final_score));
-            col += 1;
-            
-            // Column N+2: meets_threshold (private result)
-            let meets_threshold = if final_score >= threshold { 1 } else { 0 };
-            trace.set(row, col, BabyBearField::from_u32(meets_threshold));
-            col += 1;
-            
-            // Column N+3: proof_validity_flag
-            trace.set(row, col, BabyBearField::ONE);
         }
-        
-        Ok(trace)
+        air.fill_gadgets(trace);
     }
+}
 
-    fn create_biometric_trace(
-        &self,
-        webauthn_challenge: [u8; 32],
-        biometric_hash: [u8; 32],
-        factor_proofs: &[bool; 4],
-    ) -> Result<ExecutionTrace> {
-        let trace_length = 4; // Minimal trace for biometric verification
-        let width = 8; // challenge + hash + 4 factors + all_verified + validity
-
-        let mut trace = ExecutionTrace::new(width, trace_length);
+/// Domain separator for the Fiat–Shamir transcript
+pub const TRANSCRIPT_DOMAIN: &str = "RepID_STARK_v1";
+
+/// Fiat–Shamir challenges of a proof, in the order the prover draws them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofChallenges {
+    /// Combines the constraint values into the composition polynomial
+    pub composition: BabyBearField,
+    /// Combines the quotient and the trace columns into the first FRI layer
+    pub batching: BabyBearField,
+    /// One per committed FRI layer
+    pub folding: Vec<BabyBearField>,
+    /// Seed the proof of work is ground against, drawn after every commitment
+    pub pow_seed: u64,
+    /// Queried LDE positions
+    pub positions: Vec<usize>,
+}
 
-        let challenge_field = BabyBearField::new(
-            u64::from_le_bytes([
-                webauthn_challenge[0], webauthn_challenge[1], webauthn_challenge[2], webauthn_challenge[3],
-                webauthn_challenge[4], webauthn_challenge[5], webauthn_challenge[6], webauthn_challenge[7],
-            ])
-        );
+/// Replay the Fiat–Shamir transcript of a proof and derive its challenges
+///
+/// Shared by prover and verifier so challenge derivation cannot diverge; the
+/// proof's own query responses are not absorbed.
+pub fn derive_challenges(
+    transcript: &mut Transcript,
+    tenant_tag: Option<BabyBearField>,
+    proof: &StarkProof,
+    params: &StarkParams,
+    lde_height: usize,
+) -> ProofChallenges {
+    let composition = absorb_trace_commitment(transcript, tenant_tag, proof, params, lde_height);
+    let batching = absorb_quotient_commitment(transcript, proof);
+    let folding = proof.fri_proof.commitments.iter().map(|commitment| absorb_fri_commitment(transcript, commitment)).collect();
+    let pow_seed = absorb_fri_final_layer(transcript, &proof.fri_proof);
+    let positions = absorb_pow_nonce(transcript, proof.fri_proof.pow_nonce, params.num_queries, lde_height);
+    ProofChallenges { composition, batching, folding, pow_seed, positions }
+}
 
-        let hash_field = BabyBearField::new(
-            u64::from_le_bytes([
-                biometric_hash[0], biometric_hash[1], biometric_hash[2], biometric_hash[3],
-                biometric_hash[4], biometric_hash[5], biometric_hash[6], biometric_hash[7],
-            ])
-        );
+/// Absorb the statement and the trace commitments, drawing the composition challenge
+///
+/// Every field of `params` is absorbed, so a proof only verifies under the
+/// parameters it was generated with.
+fn absorb_trace_commitment(
+    transcript: &mut Transcript,
+    tenant_tag: Option<BabyBearField>,
+    proof: &StarkProof,
+    params: &StarkParams,
+    lde_height: usize,
+) -> BabyBearField {
+    let values = [
+        params.num_queries as u64,
+        params.blowup_factor as u64,
+        params.grinding_bits as u64,
+        params.challenge_extension_degree as u64,
+        params.fri_folding_arity as u64,
+        lde_height as u64,
+    ];
+    let mut encoded = [0u8; 48];
+    for (chunk, value) in encoded.chunks_exact_mut(8).zip(values) {
+        chunk.copy_from_slice(&value.to_le_bytes());
+    }
+    if let Some(tag) = tenant_tag {
+        transcript.absorb_field_elements("tenant", &[tag]);
+    }
+    transcript.absorb("params", &encoded);
+    transcript.absorb_field_elements("public_inputs", &proof.public_inputs);
+    transcript.absorb_field_elements("public_inputs_digest", &proof.public_inputs_digest);
+    transcript.absorb("trace_root", &proof.trace_root);
+    transcript.absorb("lde_root", &proof.lde_root);
+    transcript.challenge_field("composition_challenge")
+}
 
-        for row in 0..trace_length {
-            let mut col = 0;
+/// Absorb the quotient commitment, drawing the challenge batching it with the trace
+fn absorb_quotient_commitment(transcript: &mut Transcript, proof: &StarkProof) -> BabyBearField {
+    transcript.absorb("quotient_root", &proof.quotient_root);
+    transcript.challenge_field("batching_challenge")
+}
 
-            // Column 0: WebAuthn challenge (public)
-            trace.set(row, col, challenge_field);
-            col += 1;
+/// Absorb one FRI layer commitment, drawing the challenge the layer is folded with
+fn absorb_fri_commitment(transcript: &mut Transcript, commitment: &[u8; 32]) -> BabyBearField {
+    transcript.absorb("fri_commitment", commitment);
+    transcript.challenge_field("fri_folding_challenge")
+}
 
-            // Column 1: Biometric hash (private)
-            trace.set(row, col, hash_field);
-            col += 1;
+/// Absorb the final FRI layer, drawing the seed the proof of work is ground against
+fn absorb_fri_final_layer(transcript: &mut Transcript, fri_proof: &FriProof) -> u64 {
+    transcript.absorb_field_elements("fri_final_poly", &fri_proof.final_poly);
+    transcript.challenge_u64("pow_seed")
+}
 
-            // Columns 2-5: Factor verification results (private)
-            let mut all_verified = true;
-            for &factor in factor_proofs {
-                let factor_field = if factor { BabyBearField::ONE } else { BabyBearField::ZERO };
-                trace.set(row, col, factor_field);
-                if !factor {
-                    all_verified = false;
-                }
-                col += 1;
-            }
+/// Absorb the proof-of-work nonce, drawing the query positions
+fn absorb_pow_nonce(transcript: &mut Transcript, nonce: u64, num_queries: usize, lde_height: usize) -> Vec<usize> {
+    transcript.absorb("pow_nonce", &nonce.to_le_bytes());
+    transcript.challenge_indices("query_index", num_queries, lde_height)
+}
 
-            // Column 6: All factors verified (private result)
-            let all_verified_field = if all_verified { BabyBearField::ONE } else { BabyBearField::ZERO };
-            trace.set(row, col, all_verified_field);
-            col += 1;
+/// Whether `nonce` hashes with `seed` to `bits` leading zero bits
+pub fn pow_nonce_is_valid(seed: u64, nonce: u64, bits: u32) -> bool {
+    let mut hasher = Hasher::new();
+    hasher.update(b"RepID_PoW");
+    hasher.update(&seed.to_le_bytes());
+    hasher.update(&nonce.to_le_bytes());
+    let hash = hasher.finalize();
+    let mut leading = [0u8; 8];
+    leading.copy_from_slice(&hash.as_bytes()[..8]);
+    u64::from_be_bytes(leading).leading_zeros() >= bits
+}
 
-            // Column 7: Proof validity
-            trace.set(row, col, BabyBearField::ONE);
-        }
+/// Nonces tried before proof-of-work search gives up
+#[cfg(feature = "prover")]
+const MAX_POW_ATTEMPTS: u64 = 1 << 24;
+
+/// Smallest nonce grinding `seed` to `bits` leading zero bits
+///
+/// The seed is drawn after every commitment, so the work is redone per proof.
+#[cfg(feature = "prover")]
+pub fn grind_pow_nonce(seed: u64, bits: u32) -> Result<u64> {
+    (0..MAX_POW_ATTEMPTS)
+        .into_par_iter()
+        .find_first(|&nonce| pow_nonce_is_valid(seed, nonce, bits))
+        .ok_or_else(|| ZKPError::ProofGenerationError("PoW timeout".to_string()))
+}
 
-        Ok(trace)
-    }
+/// Hash function used for trace and LDE commitments
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashBackend {
+    #[default]
+    Blake3,
+    Sha256,
+}
 
-    fn generate_threshold_constraints(
-        &self,
-        trace: &ExecutionTrace,
-        threshold: u32,
-        time_window: u64,
-    ) -> Result<Vec<Vec<BabyBearField>>> {
-        let mut constraints = Vec::new();
-        
-        for row in 0..trace.height {
-            let mut row_constraints = Vec::new();
-            
-            // Constraint: threshold consistency
-            let threshold_val = trace.get(row, 0);
-            let expected_threshold = BabyBearField::from_u32(threshold);
-            row_constraints.push(threshold_val - expected_threshold);
-            
-            // Constraint: time_window consistency
-            let time_val = trace.get(row, 1);
-            let expected_time = BabyBearField::new(time_window);
-            row_constraints.push(time_val - expected_time);
-            
-            // Constraint: meets_threshold correctness
-            let final_score = trace.get(row, trace.width - 2);
-            let meets_threshold = trace.get(row, trace.width - 1);
-            
-            // meets_threshold should be 1 if final_score >= threshold, 0 otherwise
-            let threshold_check = if final_score.0 >= threshold as u64 {
-                BabyBearField::ONE
-            } else {
-                BabyBearField::ZERO
-            };
-            row_constraints.push(meets_threshold - threshold_check);
-            
-            constraints.push(row_constraints);
+impl HashBackend {
+    /// Digest every cell of a trace, row by row
+    pub fn commit(&self, trace: &ExecutionTrace) -> [u8; 32] {
+        let cells = trace.data.iter().flatten().map(|cell| cell.to_bytes());
+        match self {
+            HashBackend::Blake3 => {
+                let mut hasher = Hasher::new();
+                cells.for_each(|bytes| {
+                    hasher.update(&bytes);
+                });
+                *hasher.finalize().as_bytes()
+            }
+            HashBackend::Sha256 => {
+                use sha2::{Digest, Sha256};
+                let mut hasher = Sha256::new();
+                cells.for_each(|bytes| hasher.update(bytes));
+                hasher.finalize().into()
+            }
         }
-        
-        Ok(constraints)
     }
+}
 
-    fn generate_biometric_constraints(
-        &self,
-        trace: &ExecutionTrace,
-        webauthn_challenge: [u8; 32],
-    ) -> Result<Vec<Vec<BabyBearField>>> {
-        let mut constraints = Vec::new();
-        
-        let expected_challenge = BabyBearField::new(
-            u64::from_le_bytes([
-                webauthn_challenge[0], webauthn_challenge[1], webauthn_challenge[2], webauthn_challenge[3],
-                webauthn_challenge[4], webauthn_challenge[5], webauthn_challenge[6], webauthn_challenge[7],
-            ])
-        );
-        
-        for row in 0..trace.height {
-            let mut row_constraints = Vec::new();
-            
-            // Constraint: WebAuthn challenge consistency
-            let challenge_val = trace.get(row, 0);
-            row_constraints.push(challenge_val - expected_challenge);
-            
-            // Constraint: All factors verified correctness
-            let factor1 = trace.get(row, 2);
-            let factor2 = trace.get(row, 3);
-            let factor3 = trace.get(row, 4);
-            let factor4 = trace.get(row, 5);
-            let all_verified = trace.get(row, 6);
-            
-            // all_verified should be 1 only if all factors are 1
-            let expected_all_verified = factor1 * factor2 * factor3 * factor4;
-            row_constraints.push(all_verified - expected_all_verified);
-            
-            constraints.push(row_constraints);
-        }
-        
-        Ok(constraints)
-    }
+/// Proof-of-work difficulty enforced by the prover and verifier
+pub const GRINDING_BITS: u32 = 16;
 
-    fn commit_to_trace(&self, trace: &ExecutionTrace) -> Result<[u8; 32]> {
-        let mut hasher = Hasher::new();
-        
-        for row in &trace.data {
-            for &cell in row {
-                hasher.update(&cell.to_bytes());
-            }
-        }
-        
-        let hash = hasher.finalize();
-        Ok(*hash.as_bytes())
-    }
+/// Degree of the BabyBear extension challenges are drawn from
+pub const CHALLENGE_EXTENSION_DEGREE: u32 = 4;
 
-    fn compute_lde(&self, trace: &ExecutionTrace) -> Result<ExecutionTrace> {
-        // Low-degree extension (simplified for MVP)
-        let extended_height = trace.height * self.blowup_factor;
-        let mut lde = ExecutionTrace::new(trace.width, extended_height);
-        
-        // Copy original trace
-        for row in 0..trace.height {
-            for col in 0..trace.width {
-                lde.set(row, col, trace.get(row, col));
-            }
-        }
-        
-        // Fill extended rows with interpolated values (simplified)
-        for row in trace.height..extended_height {
-            for col in 0..trace.width {
-                let base_row = row % trace.height;
-                let interpolation_factor = BabyBearField::new((row as u64) + 1);
-                let base_value = trace.get(base_row, col);
-                lde.set(row, col, base_value * interpolation_factor);
-            }
-        }
-        
-        Ok(lde)
-    }
+/// FRI folding arities the prover and verifier support
+pub const FRI_FOLDING_ARITIES: [usize; 3] = [2, 4, 8];
 
-    fn commit_to_lde(&self, lde: &ExecutionTrace) -> Result<[u8; 32]> {
-        self.commit_to_trace(lde)
-    }
-
-    fn generate_fri_proof(&mut self, lde: &ExecutionTrace, _constraints: &[Vec<BabyBearField>]) -> Result<FriProof> {
-        let mut commitments = Vec::new();
-        let mut current_poly_size = lde.height;
-        
-        // FRI folding rounds (simplified)
-        while current_poly_size > 16 {
-            let mut hasher = Hasher::new();
-            hasher.update(&current_poly_size.to_le_bytes());
-            let commitment = *hasher.finalize().as_bytes();
-            commitments.push(commitment);
-            
-            current_poly_size /= 2;
-        }
-        
-        // Final polynomial (constant for MVP)
-        let final_poly = vec![BabyBearField::ONE; current_poly_size.min(8)];
-        
-        // Proof of work
-        let mut pow_nonce = 0u64;
-        loop {
-            let mut hasher = Hasher::new();
-            hasher.update(b"RepID_PoW");
-            hasher.update(&pow_nonce.to_le_bytes());
-            let hash = hasher.finalize();
-            
-            // Check if first 16 bits are zero (simplified PoW)
-            if hash.as_bytes()[0] == 0 && hash.as_bytes()[1] == 0 {
-                break;
-            }
-            pow_nonce += 1;
-            
-            if pow_nonce > 1_000_000 {
-                return Err(ZKPError::ProofGenerationError("PoW timeout".to_string()));
-            }
-        }
-        
-        Ok(FriProof {
-            commitments,
-            final_poly,
-            pow_nonce,
-        })
-    }
+/// FRI stops folding once a layer has at most this many evaluations
+const FRI_FINAL_LAYER_SIZE: usize = 16;
 
-    fn generate_queries(&mut self, trace: &ExecutionTrace, lde: &ExecutionTrace, _fri_proof: &FriProof) -> Result<Vec<QueryResponse>> {
-        let mut queries = Vec::new();
-        
-        for _ in 0..self.num_queries {
-            let position = (RngCore::next_u64(&mut self.rng) as usize) % lde.height;
-            let value = lde.get(position, 0); // Query first column for simplicity
-            
-            // Generate authentication path (simplified Merkle proof)
-            let mut auth_path = Vec::new();
-            let mut current_pos = position;
-            let mut current_size = lde.height;
-            
-            while current_size > 1 {
-                let sibling_pos = current_pos ^ 1;
-                let mut hasher = Hasher::new();
-                hasher.update(&(sibling_pos as u64).to_le_bytes());
-                auth_path.push(*hasher.finalize().as_bytes());
-                
-                current_pos /= 2;
-                current_size /= 2;
-            }
-            
-            queries.push(QueryResponse {
-                position,
-                value,
-                auth_path,
-            });
-        }
-        
-        Ok(queries)
+/// Merkle leaf committing to values opened together
+fn opening_leaf(values: &[BabyBearField]) -> [u8; 32] {
+    let mut hasher = Hasher::new();
+    hasher.update(b"RepID_LDE_leaf");
+    for value in values {
+        hasher.update(&value.to_bytes());
     }
+    *hasher.finalize().as_bytes()
 }
 
-/// Custom STARK verifier
-pub struct CustomStarkVerifier {
-    pub num_queries: usize,
-    pub blowup_factor: usize,
+fn lde_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Hasher::new();
+    hasher.update(b"RepID_LDE_node");
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+/// Root an opening of leaf `index` authenticates to; equals the committed root for a genuine opening
+pub fn opening_root(opening: &MerkleOpening, index: usize) -> [u8; 32] {
+    let leaf = opening_leaf(&opening.values);
+    let (root, _) = opening.auth_path.iter().fold((leaf, index), |(node, position), sibling| {
+        let parent = if position & 1 == 0 { lde_node(&node, sibling) } else { lde_node(sibling, &node) };
+        (parent, position >> 1)
+    });
+    root
+}
+
+/// Merkle tree over committed leaves, leaves first
+#[cfg(feature = "prover")]
+struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(feature = "prover")]
+impl MerkleTree {
+    /// Tree whose leaf `i` commits to `leaves[i]`
+    fn new(leaves: Vec<Vec<BabyBearField>>) -> Self {
+        let mut levels = vec![leaves.par_iter().map(|values| opening_leaf(values)).collect::<Vec<_>>()];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let parents = level.par_chunks(2).map(|pair| lde_node(&pair[0], &pair[1])).collect();
+            levels.push(parents);
+        }
+        Self { levels }
+    }
+
+    fn root(&self) -> [u8; 32] {
+        self.levels.last().map(|level| level[0]).unwrap_or_default()
+    }
+
+    /// Sibling of each node on the path from `position` to the root
+    fn auth_path(&self, position: usize) -> Vec<[u8; 32]> {
+        let depth = self.levels.len() - 1;
+        (0..depth).map(|level| self.levels[level][(position >> level) ^ 1]).collect()
+    }
+}
+
+/// Committed evaluations: the quotient one per leaf, a FRI layer one folding coset per leaf
+#[cfg(feature = "prover")]
+struct CommittedLayer {
+    values: Vec<BabyBearField>,
+    tree: MerkleTree,
+}
+
+#[cfg(feature = "prover")]
+impl CommittedLayer {
+    /// Leaf `i` holds the `arity` evaluations `i + j * len / arity` folded together
+    fn new(values: Vec<BabyBearField>, arity: usize) -> Self {
+        let stride = values.len() / arity;
+        let cosets = (0..stride).map(|i| (0..arity).map(|j| values[i + j * stride]).collect()).collect();
+        Self { tree: MerkleTree::new(cosets), values }
+    }
+
+    fn open(&self, index: usize, arity: usize) -> MerkleOpening {
+        let stride = self.values.len() / arity;
+        MerkleOpening {
+            values: (0..arity).map(|j| self.values[index + j * stride]).collect(),
+            auth_path: self.tree.auth_path(index),
+        }
+    }
+}
+
+/// Domain of the layer folding `domain` by `arity`: every point raised to the `arity`
+fn folded_domain(domain: &TwoAdicSubgroup, arity: usize) -> Result<TwoAdicSubgroup> {
+    let log_size = domain.log_size() - arity.trailing_zeros();
+    Ok(TwoAdicSubgroup::new(log_size)?.coset(domain.shift().pow(arity as u64)))
+}
+
+/// Fold one coset of a FRI layer with challenge `beta`
+///
+/// `values[j]` is the layer at `x * zeta^j` for a primitive `arity`-th root
+/// `zeta`, and `inverses[j]` the inverse of that point. Writing the layer as
+/// `f(y) = sum_k y^k f_k(y^arity)`, the result is `sum_k beta^k f_k(x^arity)`,
+/// a polynomial of `1 / arity` the degree on the folded domain.
+fn fold_coset(values: &[BabyBearField], inverses: &[BabyBearField], beta: BabyBearField) -> BabyBearField {
+    let arity_inverse = BabyBearField::new(values.len() as u64).inverse().expect("arity is invertible");
+    let folded: BabyBearField = values
+        .iter()
+        .zip(inverses)
+        .map(|(&value, &inverse)| {
+            let ratio = beta * inverse;
+            let (weight, _) = (1..values.len()).fold((BabyBearField::ONE, BabyBearField::ONE), |(sum, power), _| {
+                let power = power * ratio;
+                (sum + power, power)
+            });
+            value * weight
+        })
+        .sum();
+    folded * arity_inverse
+}
+
+/// Composition of every constraint at one LDE point, combined by powers of `alpha`
+///
+/// `rows[i]` is the trace LDE row `rotations[i]` trace rows after the point
+/// and `fixed` the fixed columns' values at it.
+fn composition_at(
+    system: &ConstraintSystem,
+    rotations: &[isize],
+    rows: &[&[BabyBearField]],
+    fixed: &[BabyBearField],
+    alpha: BabyBearField,
+    values: &mut Vec<BabyBearField>,
+) -> BabyBearField {
+    let program = system.program();
+    program.run(
+        |index| {
+            let (column, rotation) = program.cells()[index];
+            let row = rotations.binary_search(&rotation).expect("rotation of a program cell");
+            rows[row][column]
+        },
+        |index| fixed[index],
+        values,
+    );
+    program.outputs(values).fold(BabyBearField::ZERO, |acc, value| acc * alpha + value)
+}
+
+/// First FRI layer at one point: the quotient plus the trace row combined by powers of `gamma`
+fn batched_at(quotient: BabyBearField, row: &[BabyBearField], gamma: BabyBearField) -> BabyBearField {
+    row.iter().rev().fold(BabyBearField::ZERO, |acc, &value| (acc + value) * gamma) + quotient
+}
+
+/// Position `rotation` trace rows after `position` in an LDE of `lde_height` rows
+fn rotated_position(position: usize, rotation: isize, blowup_factor: usize, lde_height: usize) -> usize {
+    (position as i64 + rotation as i64 * blowup_factor as i64).rem_euclid(lde_height as i64) as usize
+}
+
+/// Coset of `trace_height * blowup_factor` points the trace is extended over
+fn lde_domain(trace_height: usize, blowup_factor: usize) -> Result<TwoAdicSubgroup> {
+    Ok(TwoAdicSubgroup::new((trace_height * blowup_factor).trailing_zeros())?.coset(MULTIPLICATIVE_GENERATOR))
+}
+
+/// Number of committed FRI layers for an LDE of `lde_height` rows
+pub fn fri_layer_count(lde_height: usize, arity: usize) -> usize {
+    let mut size = lde_height;
+    let mut layers = 0;
+    while size > FRI_FINAL_LAYER_SIZE && arity > 1 {
+        size /= arity;
+        layers += 1;
+    }
+    layers
+}
+
+/// Concrete STARK parameter set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StarkParams {
+    /// Number of FRI queries
+    pub num_queries: usize,
+    /// LDE blowup factor (power of two)
+    pub blowup_factor: usize,
+    /// Proof-of-work bits ground before query sampling
+    pub grinding_bits: u32,
+    /// Degree of the BabyBear extension challenges are drawn from
+    pub challenge_extension_degree: u32,
+    /// Evaluations folded into one per FRI round (2, 4 or 8)
+    #[serde(default = "default_fri_folding_arity")]
+    pub fri_folding_arity: usize,
+}
+
+fn default_fri_folding_arity() -> usize {
+    2
+}
+
+impl StarkParams {
+    pub fn new(num_queries: usize, blowup_factor: usize) -> Self {
+        Self {
+            num_queries,
+            blowup_factor,
+            grinding_bits: GRINDING_BITS,
+            challenge_extension_degree: CHALLENGE_EXTENSION_DEGREE,
+            fri_folding_arity: default_fri_folding_arity(),
+        }
+    }
+
+    /// Fold `arity` evaluations per FRI round, trading wider openings for fewer layers
+    pub fn with_fri_folding_arity(mut self, arity: usize) -> Self {
+        self.fri_folding_arity = arity;
+        self
+    }
+
+    /// Reject parameter combinations the prover and verifier cannot run
+    pub fn validate(&self) -> Result<()> {
+        if self.num_queries == 0 {
+            return Err(ZKPError::ConfigError("num_queries must be positive".to_string()));
+        }
+        if self.blowup_factor < 2 || !self.blowup_factor.is_power_of_two() {
+            return Err(ZKPError::ConfigError(format!(
+                "blowup_factor must be a power of two >= 2, got {}",
+                self.blowup_factor
+            )));
+        }
+        if !FRI_FOLDING_ARITIES.contains(&self.fri_folding_arity) {
+            return Err(ZKPError::ConfigError(format!(
+                "fri_folding_arity must be one of {:?}, got {}",
+                FRI_FOLDING_ARITIES, self.fri_folding_arity
+            )));
+        }
+        Ok(())
+    }
+
+    /// Same parameters with the blowup raised to carry constraints of `max_degree`
+    pub fn for_degree(mut self, max_degree: usize) -> Self {
+        self.blowup_factor = self.blowup_factor.max(crate::air::min_blowup_for_degree(max_degree));
+        self
+    }
+
+    /// At least as many queries and as large a blowup as `minimum`
+    pub fn meets(&self, minimum: &StarkParams) -> bool {
+        self.num_queries >= minimum.num_queries && self.blowup_factor >= minimum.blowup_factor
+    }
+
+    /// Conjectured FRI query soundness: queries * log2(blowup), plus grinding
+    pub fn query_security_bits(&self) -> f64 {
+        self.num_queries as f64 * (self.blowup_factor.max(1) as f64).log2() + self.grinding_bits as f64
+    }
+
+    /// Bound from the size of the challenge field
+    pub fn field_security_bits(&self) -> f64 {
+        self.challenge_extension_degree as f64 * (BabyBearField::MODULUS as f64).log2()
+    }
+
+    /// Estimated security level: the weaker of query and field soundness
+    pub fn estimated_security_bits(&self) -> f64 {
+        self.query_security_bits().min(self.field_security_bits())
+    }
+}
+
+/// Custom STARK prover based on Plonky3 principles
+#[cfg(feature = "prover")]
+pub struct CustomStarkProver {
+    /// Security parameter (number of queries)
+    pub num_queries: usize,
+    /// Blowup factor for LDE
+    pub blowup_factor: usize,
+    /// Source of prover randomness
+    pub rng: Arc<dyn RngProvider>,
+    /// Record the Fiat–Shamir transcript of each proof for audit export
+    pub record_transcript: bool,
+    /// Transcript of the most recent proof (when recording is enabled)
+    pub last_transcript: Option<TranscriptLog>,
+    /// Resource limits enforced while proving
+    pub limits: ProofLimits,
+    /// Wallet commitment appended to public inputs, ahead of any tenant tag
+    pub wallet_commitment: Option<crate::poseidon2::Digest>,
+    /// Tenant binding appended to public inputs and absorbed into the transcript
+    pub tenant_tag: Option<BabyBearField>,
+    /// Verifier challenge absorbed into the transcript of session-bound proofs
+    pub session_challenge: Option<[u8; 32]>,
+    /// Hash function for trace commitments
+    pub hash_backend: HashBackend,
+    /// Per-category caps applied to threshold proofs
+    pub category_caps: Vec<CategoryCap>,
+    /// Issuer scales normalized onto the canonical scale in threshold proofs
+    pub score_scales: Vec<ScoreScale>,
+    /// Evaluations folded into one per FRI round
+    pub fri_folding_arity: usize,
+    /// Source of the evaluation instant written into threshold traces
+    pub clock: Arc<dyn Clock>,
+    /// Receiver of per-stage progress reports
+    pub progress: Option<Arc<dyn ProgressObserver>>,
+}
+
+#[cfg(feature = "prover")]
+impl CustomStarkProver {
+    pub fn new(num_queries: usize, blowup_factor: usize) -> Self {
+        Self {
+            num_queries,
+            blowup_factor,
+            rng: entropy::os_entropy(),
+            record_transcript: false,
+            last_transcript: None,
+            limits: ProofLimits::default(),
+            wallet_commitment: None,
+            tenant_tag: None,
+            session_challenge: None,
+            hash_backend: HashBackend::default(),
+            category_caps: Vec::new(),
+            score_scales: Vec::new(),
+            fri_folding_arity: default_fri_folding_arity(),
+            clock: Arc::new(SystemClock),
+            progress: None,
+        }
+    }
+
+    /// Generate STARK proof for RepID threshold verification
+    pub fn prove_threshold_verification(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        self.prove_penalized_threshold_verification(user_scores, &[], threshold, time_window, as_of, decay_params)
+    }
+
+    /// Generate STARK proof for a threshold over every requested category
+    ///
+    /// Absent categories are explicit zero score columns, each flagged in an
+    /// appended `AbsenceAir` section.
+    pub fn prove_requested_threshold_verification(
+        &mut self,
+        requested: &RequestedScores,
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        let (scores, absent) = (&requested.scores, &requested.absent);
+        self.prove_flagged_threshold_verification(scores, absent, &[], threshold, time_window, as_of, decay_params)
+    }
+
+    /// Generate STARK proof for a threshold over scores net of slashing penalties
+    ///
+    /// Penalties occupy extra score columns holding field-encoded negatives.
+    pub fn prove_penalized_threshold_verification(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        penalties: &[PenaltyEvent],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        self.prove_flagged_threshold_verification(
+            user_scores,
+            &[],
+            penalties,
+            threshold,
+            time_window,
+            as_of,
+            decay_params,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn prove_flagged_threshold_verification(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        absent: &[bool],
+        penalties: &[PenaltyEvent],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        if !self.category_caps.is_empty() || !self.score_scales.is_empty() {
+            return self.prove_adjusted_threshold_verification(
+                user_scores,
+                absent,
+                penalties,
+                threshold,
+                time_window,
+                as_of,
+                decay_params,
+            );
+        }
+
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.penalized_threshold_section(user_scores, penalties, threshold, time_window, timestamp, decay_params)?.require_met()?;
+        let flagged = Self::flagged_columns(absent);
+
+        // Prepare public inputs (threshold, time_window and the evaluation timestamp)
+        let public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::Threshold { scores: witness.shape(), flagged };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let air = FlaggedThresholdAir::new(witness.air(), flagged);
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        if let Some(absence) = &air.absence {
+            absence.fill(&mut trace, absent);
+        }
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Score columns flagged in an `AbsenceAir` section: all of them when any category is absent
+    fn flagged_columns(absent: &[bool]) -> usize {
+        if absent.contains(&true) {
+            absent.len()
+        } else {
+            0
+        }
+    }
+
+    /// Threshold proof over normalized and saturated scores, with the adjustments digest public
+    #[allow(clippy::too_many_arguments)]
+    fn prove_adjusted_threshold_verification(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        absent: &[bool],
+        penalties: &[PenaltyEvent],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        let normalized = normalize_scores(&self.score_scales, user_scores);
+        let saturated = apply_caps(&self.category_caps, &normalized);
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.penalized_threshold_section(&saturated, penalties, threshold, time_window, timestamp, decay_params)?.require_met()?;
+
+        // Saturated scores with their caps, then normalized scores with their scales
+        let capped: Vec<(usize, CategoryCap)> = user_scores.iter()
+            .enumerate()
+            .filter_map(|(index, (category, _))| {
+                let cap = self.category_caps.iter().find(|cap| cap.category == *category)?;
+                Some((index, cap.clone()))
+            })
+            .collect();
+        let scaled: Vec<(usize, ScoreScale)> = user_scores.iter()
+            .enumerate()
+            .filter_map(|(index, (category, _))| {
+                let scale = self.score_scales.iter().find(|scale| scale.category == *category)?;
+                Some((index, scale.clone()))
+            })
+            .collect();
+        let flagged = Self::flagged_columns(absent);
+
+        // Public inputs: threshold, time_window, the adjustments digest and the timestamp
+        let public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            adjustments_digest(&self.score_scales, &self.category_caps),
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::AdjustedThreshold {
+            scores: witness.shape(),
+            capped: capped.clone(),
+            scaled: scaled.clone(),
+            flagged,
+        };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let air = AdjustedThresholdAir::new(witness.air(), &capped, &scaled, flagged);
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        let raw: Vec<u32> = capped.iter().map(|(index, _)| normalized[*index].1).collect();
+        air.saturation.fill(&mut trace, &raw);
+        let native: Vec<u32> = scaled.iter().map(|(index, _)| user_scores[*index].1).collect();
+        air.normalization.fill(&mut trace, &native);
+        if let Some(absence) = &air.absence {
+            absence.fill(&mut trace, absent);
+        }
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for a threshold over externally committed scores
+    ///
+    /// The circuit opens `commitment` with the Poseidon2 gadget and links the
+    /// selected category scores to the committed values.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_committed_threshold_verification(
+        &mut self,
+        opening: &ScoreOpening,
+        commitment: &ScoreCommitment,
+        categories: &[RepIDCategory],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        if !opening.opens(commitment) {
+            return Err(ZKPError::InvalidInput("Score opening does not match commitment".to_string()));
+        }
+
+        let selected: Vec<(RepIDCategory, u32)> = opening.scores.iter()
+            .filter(|(cat, _)| categories.contains(cat))
+            .cloned()
+            .collect();
+        let positions = selected.iter()
+            .map(|(category, _)| {
+                opening.score_position(category)
+                    .ok_or_else(|| ZKPError::CircuitError("Selected category missing from opening".to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Threshold section over the selected scores, each linked to its committed value
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(&selected, threshold, time_window, timestamp, decay_params)?.require_met()?;
+        let inputs = opening.to_field_elements();
+
+        // Public inputs: threshold, time_window, the score commitment digest and the timestamp
+        let mut public_inputs = vec![BabyBearField::from_u32(threshold), BabyBearField::new(time_window)];
+        public_inputs.extend(commitment.0);
+        public_inputs.push(BabyBearField::new(timestamp));
+        let shape = CircuitShape::CommittedThreshold { scores: witness.shape(), opening_len: inputs.len(), positions };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        OpeningAir::new(Some(witness.air()), inputs.len()).fill(&mut trace, &inputs);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof revealing one category score of a committed score vector
+    ///
+    /// The whole opening is hashed in-circuit against `commitment`; only the
+    /// category's tag and score are constrained to public inputs.
+    pub fn prove_score_opening(
+        &mut self,
+        opening: &ScoreOpening,
+        commitment: &ScoreCommitment,
+        category: &RepIDCategory,
+    ) -> Result<StarkProof> {
+        if !opening.opens(commitment) {
+            return Err(ZKPError::InvalidInput("Score opening does not match commitment".to_string()));
+        }
+        let position = opening.score_position(category)
+            .ok_or_else(|| ZKPError::InvalidInput(format!("Category {:?} is not in the committed scores", category)))?;
+        let score = opening.scores[(position - 2) / 2].1;
+        let inputs = opening.to_field_elements();
+
+        // Public inputs: the score commitment digest, the category tag and the revealed score
+        let mut public_inputs = commitment.0.to_vec();
+        public_inputs.extend([category.field_tag(), BabyBearField::from_u32(score)]);
+        let shape = CircuitShape::ScoreOpening { opening_len: inputs.len(), position };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let mut trace = circuit.trace();
+        OpeningAir::new(None, inputs.len()).fill(&mut trace, &inputs);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for a threshold over a committed, undisclosed category set
+    ///
+    /// Score column `i` holds the score of the set's `i`-th category; the circuit
+    /// opens the set commitment with the Poseidon2 gadget so only its digest is public.
+    pub fn prove_hidden_threshold_verification(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        category_set: &CategorySetOpening,
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        category_set.check()?;
+        let selected = category_set.select(user_scores);
+
+        // Threshold section over one score per committed category
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(&selected, threshold, time_window, timestamp, decay_params)?.require_met()?;
+        let inputs = category_set.to_field_elements();
+
+        // Public inputs: threshold, time_window, the category set commitment digest and the timestamp
+        let mut public_inputs = vec![BabyBearField::from_u32(threshold), BabyBearField::new(time_window)];
+        public_inputs.extend(category_set.commit().0);
+        public_inputs.push(BabyBearField::new(timestamp));
+        // The category set stays committed, so the section states no categories
+        let scores = witness.shape().with_categories(Vec::new());
+        let shape = CircuitShape::HiddenThreshold { scores, set_len: inputs.len() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        OpeningAir::new(Some(witness.air()), inputs.len()).fill(&mut trace, &inputs);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for a threshold linked to the previous epoch's proof
+    ///
+    /// The circuit opens the public chain commitment with the Poseidon2 gadget,
+    /// keeping the previous proof digest a private witness.
+    pub fn prove_chained_threshold_verification(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+        link: &ChainLink,
+    ) -> Result<StarkProof> {
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(user_scores, threshold, time_window, timestamp, decay_params)?.require_met()?;
+        let inputs = link.to_field_elements();
+
+        // Public inputs: threshold, time_window, chain commitment, epoch and timestamp
+        let public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            link.commitment(),
+            BabyBearField::new(link.epoch),
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::ChainedThreshold { scores: witness.shape(), link_len: inputs.len() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        OpeningAir::new(Some(witness.air()), inputs.len()).fill(&mut trace, &inputs);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for a threshold over attestations no older than a bound
+    ///
+    /// Attestation timestamps stay private; `now` and the maximum age are public.
+    pub fn prove_fresh_threshold_verification(
+        &mut self,
+        attested: &[AttestedScore],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+        bound: &FreshnessBound,
+    ) -> Result<StarkProof> {
+        let user_scores: Vec<(RepIDCategory, u32)> = attested.iter()
+            .map(|a| (a.category.clone(), a.score))
+            .collect();
+
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(&user_scores, threshold, time_window, timestamp, decay_params)?.require_met()?;
+
+        // Public inputs: threshold, time_window, now, the maximum age and the timestamp
+        let public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            BabyBearField::new(bound.now),
+            BabyBearField::new(bound.max_age_secs),
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::FreshThreshold { scores: witness.shape() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        // Freshness section: issuance times and ages of every attestation
+        let air = FreshThresholdAir::new(witness.air(), bound.now, bound.max_age_secs);
+        let issued_at: Vec<u64> = attested.iter().map(|a| a.issued_at).collect();
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        air.freshness.fill(&mut trace, &issued_at);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for a threshold over attestations meeting per-category issuance policies
+    ///
+    /// Issuer signatures are checked here and again by verifiers, against the
+    /// attestation digests the circuit hashes from the summed scores and
+    /// publishes with their categories; the digest of the policy set is a
+    /// public input so verifiers know which rules applied.
+    pub fn prove_cosigned_threshold_verification(
+        &mut self,
+        attestations: &[CosignedAttestation],
+        policies: &[IssuancePolicy],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        check_attestations(policies, attestations)?;
+
+        let user_scores: Vec<(RepIDCategory, u32)> = attestations.iter()
+            .map(|a| (a.attested.category.clone(), a.attested.score))
+            .collect();
+
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(&user_scores, threshold, time_window, timestamp, decay_params)?.require_met()?;
+
+        // Public inputs: threshold, time_window, the policy set digest, the timestamp,
+        // the attestation count and each attestation's category tag and digest
+        let mut public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            policies_digest(policies),
+            BabyBearField::new(timestamp),
+            BabyBearField::new(attestations.len() as u64),
+        ];
+        let published: Vec<(BabyBearField, BabyBearField)> = attestations.iter().map(|a| (a.attested.category.field_tag(), a.digest())).collect();
+        public_inputs.extend(published.iter().flat_map(|&(category, digest)| [category, digest]));
+        let shape = CircuitShape::CosignedThreshold { scores: witness.shape() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let digests: Vec<Vec<BabyBearField>> = attestations.iter().map(CosignedAttestation::to_field_elements).collect();
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        CosignedThresholdAir::new(witness.air(), published).fill(&mut trace, &digests);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for a threshold over attestations absent from an issuer's revocation list
+    ///
+    /// One gadget section per attestation hashes its id from the scored
+    /// value, and another opens the list against the public revocation root.
+    /// Each list block then carries, per attestation, the inverse of the id's
+    /// difference from every revoked id, which exists only when they differ.
+    pub fn prove_unrevoked_threshold_verification(
+        &mut self,
+        attestations: &[RevocableAttestation],
+        revocations: &RevocationList,
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        if let Some(revoked) = attestations.iter().find(|a| revocations.is_revoked(a)) {
+            return Err(ZKPError::PolicyViolation(format!(
+                "attestation {} was revoked by {}",
+                revoked.serial, revocations.issuer
+            )));
+        }
+
+        let user_scores: Vec<(RepIDCategory, u32)> = attestations.iter()
+            .map(|a| (a.attested.category.clone(), a.attested.score))
+            .collect();
+
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(&user_scores, threshold, time_window, timestamp, decay_params)?.require_met()?;
+
+        // Public inputs: threshold, time_window, the revocation root and the timestamp
+        let root = revocations.root();
+        let public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            root,
+            BabyBearField::new(timestamp),
+        ];
+        let list = &revocations.revoked;
+        let shape = CircuitShape::UnrevokedThreshold { scores: witness.shape(), list_len: list.len() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let ids: Vec<Vec<BabyBearField>> = attestations.iter().map(RevocableAttestation::to_field_elements).collect();
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        UnrevokedThresholdAir::new(witness.air(), list.len(), root).fill(&mut trace, &ids, list)?;
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for a threshold over an oracle-signed total
+    ///
+    /// One gadget section hashes the signed statement. Each signature chain
+    /// then takes `CHAIN_LENGTH` step sections that either hash the running
+    /// value or pass it through, and its pass-through count is the signed
+    /// digit. The chain ends hash to a key leaf opening against the public
+    /// oracle root; only the signed total enters the threshold section.
+    pub fn prove_oracle_threshold_verification(
+        &mut self,
+        signed: &SignedScore,
+        oracle: &OraclePublicKey,
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+    ) -> Result<StarkProof> {
+        let statement = &signed.statement;
+        let signature = &signed.signature;
+        if !oracle.verify(statement, signature) {
+            return Err(ZKPError::InvalidInput("Oracle signature does not verify under the oracle key".to_string()));
+        }
+
+        // The total is already final, so it is the only score column
+        let user_scores = [(RepIDCategory::Custom("oracle_total".to_string()), statement.total_score)];
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(&user_scores, threshold, time_window, timestamp, None)?.require_met()?;
+        let statement_inputs = statement.to_field_elements();
+
+        // Public inputs: threshold, time_window, oracle root, wallet commitment, epoch and the timestamp
+        let epoch = BabyBearField::new(statement.epoch);
+        let public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            oracle.root,
+            statement.wallet_commitment,
+            epoch,
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::OracleThreshold {
+            statement_len: statement_inputs.len(),
+            levels: signature.siblings.len(),
+        };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let air = OracleThresholdAir::new(
+            witness.air(),
+            statement_inputs.len(),
+            signature.siblings.len(),
+            oracle.root,
+            statement.wallet_commitment,
+            epoch,
+        );
+        let digits = signed_digits(statement.digest());
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        air.fill(&mut trace, &statement_inputs, &signature.chains, &digits, &signature.siblings, signature.leaf_index);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof that a threshold is met or the designated verifier's secret is known
+    ///
+    /// A gadget section hashes a private witness next to a branch selector:
+    /// with the selector clear the threshold must be met, with it set the
+    /// witness must hash to the verifier's public key. Honest proofs hash
+    /// random filler, so both branches fill the same cells.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_designated_threshold_verification(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        designation: &Designation,
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(user_scores, threshold, time_window, timestamp, decay_params)?;
+
+        let (selector, designated) = match designation.trapdoor() {
+            Some(key) => (true, key.to_field_elements()),
+            None if witness.meets_threshold => (false, seed_elements(&entropy::seed(self.rng.as_ref())?).to_vec()),
+            None => {
+                return Err(ZKPError::InvalidInput(
+                    "Scores do not meet the threshold a designated proof asserts".to_string(),
+                ))
+            }
+        };
+
+        // Public inputs: threshold, time_window, the verifier's public key and the timestamp
+        let verifier = designation.public_key();
+        let public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            verifier.0,
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::DesignatedThreshold { scores: witness.shape(), witness_len: designated.len() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        DesignatedThresholdAir::new(witness.air(), designated.len(), verifier.0).fill(&mut trace, &designated, selector);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for a threshold that also encrypts the total to an escrow key
+    ///
+    /// Two gadget sections absorb the same escrow key: one hashes it to the
+    /// public key commitment, the other to the pad the total is masked with.
+    /// The total must meet the threshold, so the proof attests nothing else.
+    pub fn prove_escrowed_threshold_verification(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        escrow_key: &EscrowKey,
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(user_scores, threshold, time_window, timestamp, decay_params)?;
+        if !witness.meets_threshold {
+            return Err(ZKPError::InvalidInput("Escrowed proofs require a total meeting the threshold".to_string()));
+        }
+
+        // Escrow sections: the key commitment, then the pad over the same key
+        let key = escrow_key.to_field_elements();
+        let pad_inputs = escrow_key.pad_inputs();
+        let total = BabyBearField::from_i64(witness.final_score);
+        let key_commitment = escrow_key.commitment();
+        let ciphertext = escrow_key.pad().map(|pad| total + pad);
+
+        // Public inputs: threshold, time_window, timestamp, key commitment and the encrypted total
+        let mut public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            BabyBearField::new(timestamp),
+        ];
+        public_inputs.extend(key_commitment);
+        public_inputs.extend(ciphertext);
+        let shape = CircuitShape::EscrowedThreshold { scores: witness.shape(), key_len: key.len(), pad_len: pad_inputs.len() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let air = EscrowedThresholdAir::new(witness.air(), key.len(), pad_inputs.len(), key_commitment.to_vec(), ciphertext.to_vec());
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        air.fill(&mut trace, &key, &pad_inputs);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for a threshold over scores pooled from linked wallets
+    ///
+    /// Gadget sections open the identity commitment, one binding per wallet and
+    /// the binding-set commitment; wallet identities stay private witnesses.
+    pub fn prove_linked_threshold_verification(
+        &mut self,
+        identity: &IdentitySecret,
+        wallets: &[LinkedWallet],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<StarkProof> {
+        check_wallets(wallets)?;
+        let user_scores: Vec<(RepIDCategory, u32)> = wallets.iter()
+            .flat_map(|w| w.scores.iter().cloned())
+            .collect();
+
+        let timestamp = self.evaluation_instant(as_of);
+        let witness = self.threshold_section(&user_scores, threshold, time_window, timestamp, decay_params)?.require_met()?;
+        let bindings: Vec<BabyBearField> = wallets.iter().map(|w| identity.binding(&w.wallet_hash)).collect();
+        let identity_commitment = identity.commitment();
+        let link_commitment = poseidon2::hash_elements(&bindings);
+
+        // Public inputs: threshold, time_window, identity and link commitments, wallet count, timestamp
+        let public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            identity_commitment,
+            link_commitment,
+            BabyBearField::new(wallets.len() as u64),
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::LinkedThreshold { scores: witness.shape(), wallets: wallets.len() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let air = LinkedThresholdAir::new(witness.air(), wallets.len(), identity_commitment, link_commitment);
+        let tags: Vec<BabyBearField> = wallets.iter().map(|w| wallet_tag(&w.wallet_hash)).collect();
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        air.fill(&mut trace, identity.0, &tags);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof that a score reaches a band of a committed distribution
+    ///
+    /// The band cutoff is opened from the commitment with the Poseidon2 gadget
+    /// and used as the (private) threshold; only the band is public.
+    pub fn prove_rank_bucket(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        distribution: &ScoreDistribution,
+        commitment: &DistributionCommitment,
+        top_percent: u8,
+    ) -> Result<StarkProof> {
+        if distribution.commit() != *commitment {
+            return Err(ZKPError::InvalidInput("Distribution does not match commitment".to_string()));
+        }
+        let (band_position, cutoff) = distribution.band_position(top_percent)
+            .zip(distribution.cutoff(top_percent))
+            .ok_or_else(|| ZKPError::InvalidInput(format!("Distribution has no 'top {}%' band", top_percent)))?;
+
+        let timestamp = self.clock.now();
+        let witness = self.threshold_section(user_scores, cutoff, 1, timestamp, None)?.with_private_threshold();
+        let inputs = distribution.to_field_elements();
+
+        // Public inputs: band, distribution commitment and timestamp
+        let public_inputs = vec![
+            BabyBearField::new(top_percent as u64),
+            commitment.0,
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::RankBucket {
+            num_scores: user_scores.len(),
+            distribution_len: inputs.len(),
+            band_position,
+        };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        OpeningAir::new(Some(witness.air()), inputs.len()).fill(&mut trace, &inputs);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof that at least `k` categories each reach `min_per_category`
+    ///
+    /// Scores and which categories qualify stay private; the floor and `k` are public.
+    pub fn prove_category_count(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        min_per_category: u32,
+        k: u32,
+    ) -> Result<StarkProof> {
+        if k == 0 {
+            return Err(ZKPError::InvalidInput("Category count needs k of at least 1".to_string()));
+        }
+        for (i, (category, _)) in user_scores.iter().enumerate() {
+            if user_scores[..i].iter().any(|(c, _)| c == category) {
+                return Err(ZKPError::InvalidInput(format!("Category {:?} is scored twice", category)));
+            }
+        }
+
+        let timestamp = self.clock.now();
+        let reached: Vec<bool> = user_scores.iter().map(|(_, score)| *score >= min_per_category).collect();
+        let count = reached.iter().filter(|&&r| r).count() as u64;
+
+        // Public inputs: floor, k and timestamp
+        let public_inputs = vec![
+            BabyBearField::from_u32(min_per_category),
+            BabyBearField::from_u32(k),
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::CategoryCount { num_scores: user_scores.len() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let air = CategoryCountAir::new(user_scores.len(), min_per_category, k, timestamp);
+        let mut trace = circuit.trace();
+        for row in 0..trace.height {
+            trace.set(row, 0, BabyBearField::from_u32(min_per_category));
+            trace.set(row, 1, BabyBearField::from_u32(k));
+            trace.set(row, 2, BabyBearField::new(timestamp));
+            for (i, ((_, score), &reached)) in user_scores.iter().zip(&reached).enumerate() {
+                trace.set(row, air.score_column(i), BabyBearField::from_u32(*score));
+                trace.set(row, air.reached_column(i), BabyBearField::new(reached as u64));
+            }
+            trace.set(row, air.count_column(), BabyBearField::new(count));
+            trace.set(row, air.validity_column(), BabyBearField::ONE);
+        }
+        air.fill_gadgets(&mut trace);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof that the decayed aggregate over consecutive epoch snapshots clears `threshold`
+    ///
+    /// Each score column holds a snapshot score times its epoch weight and is
+    /// linked to a leaf the circuit hashes and walks up to that epoch's public
+    /// snapshot root. The threshold is compared in the same basis-point scale,
+    /// and the leaves' wallet tag is committed under `salt`.
+    pub fn prove_sustained_threshold(
+        &mut self,
+        wallet_hash: &str,
+        salt: &WalletSalt,
+        history: &[EpochScores],
+        threshold: u32,
+        decay_rate_bps: u16,
+    ) -> Result<StarkProof> {
+        if history.is_empty() {
+            return Err(ZKPError::InvalidInput("Sustained proof needs at least one epoch".to_string()));
+        }
+        if history.windows(2).any(|pair| pair[1].snapshot.epoch != pair[0].snapshot.epoch + 1) {
+            return Err(ZKPError::InvalidInput("Epoch snapshots must be consecutive, oldest first".to_string()));
+        }
+        let scaled_threshold = threshold.checked_mul(WEIGHT_SCALE)
+            .ok_or_else(|| ZKPError::InvalidInput(format!("Threshold {} is too large to scale", threshold)))?;
+
+        // One score column per (epoch, category) leaf in increasing order, newest epoch weighted highest
+        let weights = epoch_weights(decay_rate_bps, history.len());
+        let mut leaves = Vec::new();
+        for (age, epoch) in history.iter().rev().enumerate() {
+            epoch.check_openings(wallet_hash)?;
+            let position = history.len() - 1 - age;
+            leaves.extend(epoch.leaves.iter().map(|leaf| (position, weights[age], leaf, epoch.snapshot.root)));
+        }
+        leaves.sort_by_key(|(position, _, leaf, _)| (*position, leaf.category.field_tag().0));
+        if leaves.windows(2).any(|pair| (pair[0].0, &pair[0].2.category) == (pair[1].0, &pair[1].2.category)) {
+            return Err(ZKPError::InvalidInput("Epoch snapshots must open each category once".to_string()));
+        }
+        let weighted = leaves.iter()
+            .map(|(_, weight, leaf, _)| {
+                weight.checked_mul(leaf.score)
+                    .map(|score| (leaf.category.clone(), score))
+                    .ok_or_else(|| ZKPError::InvalidInput(format!("{:?} score {} is too large to weight", leaf.category, leaf.score)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Threshold section over the weighted scores; the aggregate must clear it
+        let timestamp = self.clock.now();
+        let witness = self.threshold_section(&weighted, scaled_threshold, 1, timestamp, None)?;
+
+        // Public inputs: threshold, decay rate, timestamp, epoch count, (epoch, root) oldest first, then the wallet commitment
+        let mut public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(decay_rate_bps as u64),
+            BabyBearField::new(timestamp),
+            BabyBearField::new(history.len() as u64),
+        ];
+        for epoch in history {
+            public_inputs.extend(epoch.snapshot.public_inputs());
+        }
+        public_inputs.extend(WalletCommitment::of_tag(wallet_tag(wallet_hash), salt).elements());
+        let shape = CircuitShape::SustainedThreshold {
+            leaves: leaves.iter()
+                .map(|(position, _, leaf, _)| SnapshotLeafShape {
+                    epoch: *position,
+                    category: leaf.category.field_tag(),
+                    depth: leaf.witness.siblings.len(),
+                })
+                .collect(),
+        };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let openings = leaves.iter()
+            .map(|(_, weight, leaf, root)| (BabyBearField::from_u32(*weight), *leaf, *root))
+            .collect::<Vec<_>>();
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        Self::fill_snapshot_openings(&mut trace, witness.air(), wallet_hash, salt, &openings);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof that a wallet's total over the opened categories grew by at least `min_delta` between two epochs
+    ///
+    /// The later epoch's scores enter the threshold section as contributions
+    /// and the earlier epoch's as penalties, each linked to a leaf opened
+    /// against its public snapshot root. Only the difference is compared, so
+    /// neither total is revealed.
+    pub fn prove_score_delta(
+        &mut self,
+        wallet_hash: &str,
+        salt: &WalletSalt,
+        from: &EpochScores,
+        to: &EpochScores,
+        min_delta: u32,
+    ) -> Result<StarkProof> {
+        if to.snapshot.epoch <= from.snapshot.epoch {
+            return Err(ZKPError::InvalidInput(format!(
+                "Epoch {} does not follow epoch {}",
+                to.snapshot.epoch, from.snapshot.epoch
+            )));
+        }
+        if to.leaves.is_empty() {
+            return Err(ZKPError::InvalidInput("Score delta proof needs at least one category".to_string()));
+        }
+        if to.leaves.iter().enumerate().any(|(i, leaf)| to.leaves[..i].iter().any(|other| other.category == leaf.category)) {
+            return Err(ZKPError::InvalidInput("Score delta proof opens a category more than once".to_string()));
+        }
+        from.check_openings(wallet_hash)?;
+        to.check_openings(wallet_hash)?;
+
+        // Earlier leaves in the order of the later ones; both epochs open the same categories
+        let earlier: Vec<&SnapshotLeaf> = to.leaves.iter()
+            .filter_map(|leaf| from.leaves.iter().find(|other| other.category == leaf.category))
+            .collect();
+        if earlier.len() != to.leaves.len() || from.leaves.len() != to.leaves.len() {
+            return Err(ZKPError::InvalidInput("Both epochs must open the same categories".to_string()));
+        }
+
+        let later_scores: Vec<(RepIDCategory, u32)> = to.leaves.iter().map(|leaf| (leaf.category.clone(), leaf.score)).collect();
+        let reason = format!("epoch {}", from.snapshot.epoch);
+        let earlier_scores: Vec<PenaltyEvent> = earlier.iter()
+            .map(|leaf| PenaltyEvent::new(leaf.category.clone(), leaf.score, &reason, from.snapshot.frozen_at))
+            .collect();
+
+        // Threshold section over the signed difference; it must clear the minimum delta
+        let timestamp = self.clock.now();
+        let witness = self.penalized_threshold_section(&later_scores, &earlier_scores, min_delta, 1, timestamp, None)?;
+        let openings: Vec<_> = to.leaves.iter()
+            .map(|leaf| (BabyBearField::ONE, leaf, to.snapshot.root))
+            .chain(earlier.iter().map(|leaf| (BabyBearField::ZERO - BabyBearField::ONE, *leaf, from.snapshot.root)))
+            .collect();
+
+        // Public inputs: minimum delta, category count, timestamp, both (epoch, root), category tags, then the wallet commitment
+        let mut public_inputs = vec![
+            BabyBearField::from_u32(min_delta),
+            BabyBearField::new(to.leaves.len() as u64),
+            BabyBearField::new(timestamp),
+        ];
+        public_inputs.extend(from.snapshot.public_inputs());
+        public_inputs.extend(to.snapshot.public_inputs());
+        public_inputs.extend(to.leaves.iter().map(|leaf| leaf.category.field_tag()));
+        public_inputs.extend(WalletCommitment::of_tag(wallet_tag(wallet_hash), salt).elements());
+        let shape = CircuitShape::ScoreDelta {
+            depths: openings.iter().map(|(_, leaf, _)| leaf.witness.siblings.len()).collect(),
+        };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        Self::fill_snapshot_openings(&mut trace, witness.air(), wallet_hash, salt, &openings);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof of a rate-limited signal from a member of an identity set
+    ///
+    /// Three gadget sections hash the identity commitment, the message secret
+    /// and the nullifier, and a sparse Merkle path keyed by the commitment
+    /// reaches the public set root. The message index and its gap below the
+    /// limit are range-checked, so the index lies in `[0, limit)`; the share
+    /// `y = secret + message_secret * x` is constrained where the message
+    /// secret is hashed.
+    pub fn prove_rate_limited_signal(
+        &mut self,
+        identity: &IdentitySecret,
+        membership: &SmtWitness,
+        limit: &RateLimit,
+        message_index: u32,
+        signal_hash: BabyBearField,
+    ) -> Result<StarkProof> {
+        if message_index >= limit.message_limit {
+            return Err(ZKPError::InvalidInput(format!(
+                "Message index {} is not below the limit of {}",
+                message_index, limit.message_limit
+            )));
+        }
+        if membership.key != key_from_field(identity.commitment()) || membership.siblings.len() != DEPTH {
+            return Err(ZKPError::InvalidInput("Membership witness is not a full path for this identity".to_string()));
+        }
+
+        // Message secret, nullifier and share on the line through the secret
+        let external_nullifier = limit.external_nullifier();
+        let index = BabyBearField::from_u32(message_index);
+        let slope = poseidon2::hash_elements(&[identity.0, external_nullifier, index]);
+        let nullifier = poseidon2::hash_elements(&[slope]);
+        let y = identity.0 + slope * signal_hash;
+        let members_root = membership.compute_root(leaf_hash(&membership.key));
+
+        // Public inputs: set root, limit, timestamp, epoch, external nullifier, then the share
+        let public_inputs = vec![
+            members_root,
+            BabyBearField::from_u32(limit.message_limit),
+            BabyBearField::new(self.clock.now()),
+            BabyBearField::new(limit.epoch),
+            external_nullifier,
+            signal_hash,
+            y,
+            nullifier,
+        ];
+        let shape = CircuitShape::RateLimitedSignal;
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        let air = RateLimitedSignalAir {
+            root: members_root,
+            limit: BabyBearField::from_u32(limit.message_limit),
+            external_nullifier,
+            signal: signal_hash,
+            share: y,
+            nullifier,
+        };
+        let mut trace = circuit.trace();
+        air.fill(&mut trace, identity.0, message_index, membership);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof that the net of a wallet's score events clears `threshold`
+    ///
+    /// Each event occupies one row, oldest first, and the trace is as tall as
+    /// the history (padded to a power of two) rather than a fixed height; a
+    /// running total accumulates the deltas row to row.
+    pub fn prove_history_threshold(
+        &mut self,
+        events: &[ScoreEvent],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+    ) -> Result<StarkProof> {
+        if events.is_empty() {
+            return Err(ZKPError::InvalidInput("Score history has no events".to_string()));
+        }
+
+        let timestamp = self.evaluation_instant(as_of);
+        let deltas: Vec<i64> = events.iter().map(|event| event.delta).collect();
+        let totals = running_sums(&deltas)?;
+        let total = totals[totals.len() - 1];
+        let last_time = events[events.len() - 1].timestamp;
+
+        // Public inputs: threshold, time window and the evaluation timestamp
+        let public_inputs = vec![
+            BabyBearField::from_u32(threshold),
+            BabyBearField::new(time_window),
+            BabyBearField::new(timestamp),
+        ];
+        let shape = CircuitShape::HistoryThreshold { events: events.len() };
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        // Event rows, then padding rows repeating the last time and total
+        let rows = events
+            .iter()
+            .zip(&totals)
+            .map(|(event, &running_total)| (event.timestamp, event.delta, running_total))
+            .chain(std::iter::repeat((last_time, 0, total)));
+
+        let air = ScoreHistoryAir::new(threshold, time_window, timestamp);
+        let mut trace = circuit.trace();
+        for (row, (event_time, delta, running_total)) in rows.take(trace.height).enumerate() {
+            trace.set(row, 0, BabyBearField::from_u32(threshold));
+            trace.set(row, 1, BabyBearField::new(time_window));
+            trace.set(row, 2, BabyBearField::new(timestamp));
+            trace.set(row, ScoreHistoryAir::EVENT_TIME_COLUMN, BabyBearField::new(event_time));
+            trace.set(row, ScoreHistoryAir::SCORE_COLUMN, BabyBearField::from_i64(delta));
+            trace.set(row, ScoreHistoryAir::ACTIVE_COLUMN, BabyBearField::new((row < events.len()) as u64));
+            trace.set(row, ScoreHistoryAir::TOTAL_COLUMN, BabyBearField::from_i64(running_total));
+            trace.set(row, ScoreHistoryAir::MEETS_THRESHOLD_COLUMN, BabyBearField::new((total >= threshold as i64) as u64));
+        }
+        air.fill_gadgets(&mut trace);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for biometric 4FA verification
+    pub fn prove_biometric_verification(
+        &mut self,
+        webauthn_challenge: [u8; 32],
+        biometric_hash: [u8; 32],
+        factor_proofs: &[bool; 4],
+    ) -> Result<StarkProof> {
+        // Public input: WebAuthn challenge
+        let challenge_field = BabyBearField::new(
+            u64::from_le_bytes([
+                webauthn_challenge[0], webauthn_challenge[1], webauthn_challenge[2], webauthn_challenge[3],
+                webauthn_challenge[4], webauthn_challenge[5], webauthn_challenge[6], webauthn_challenge[7],
+            ])
+        );
+        let public_inputs = vec![challenge_field];
+        let shape = CircuitShape::Biometric;
+        let (circuit, public_inputs) = self.circuit(&shape, public_inputs)?;
+
+        // Create biometric verification trace
+        let mut trace = circuit.trace();
+        Self::fill_biometric_trace(&mut trace, challenge_field, biometric_hash, factor_proofs);
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Generate STARK proof for an application-defined AIR
+    ///
+    /// `witness` is the filled trace, zero-padded to a power of two; it is
+    /// checked against `air` before any commitment work, and `public_inputs`
+    /// are bound into the proof as for the built-in operations. When the
+    /// public input section needs more rows, the witness repeats down the
+    /// trace. Verify with `CustomStarkVerifier::check_air_proof`. Constraints
+    /// of a higher degree than the blowup supports are rejected; size the
+    /// prover and verifier with `StarkParams::for_degree`.
+    pub fn prove_air(
+        &mut self,
+        air: &dyn CustomAir,
+        witness: ExecutionTrace,
+        public_inputs: Vec<BabyBearField>,
+    ) -> Result<StarkProof> {
+        if witness.height == 0 {
+            return Err(ZKPError::InvalidInput("Witness trace has no rows".to_string()));
+        }
+        let degree = air.constraint_system(witness.height.next_power_of_two()).max_degree();
+        let layout = TraceLayout::select(witness.height, degree, self.blowup_factor)?;
+        self.limits.check_trace_height(layout.trace_length)?;
+        let system = air.constraint_system(layout.trace_length);
+        system.check_degrees(self.blowup_factor)?;
+        let witness = witness.append_columns(&ExecutionTrace::new(0, layout.trace_length));
+        system.check(&witness)?;
+
+        let shape = CircuitShape::Air { height: witness.height };
+        let public_inputs = self.bound_public_inputs(public_inputs, &shape)?;
+        let circuit = Circuit::tiled(&system, &public_inputs)?;
+        let trace = witness.tiled(circuit.system.height(), circuit.system.width());
+
+        self.finalize_proof(trace, &circuit, shape, public_inputs)
+    }
+
+    /// Public inputs followed by the wallet commitment and tenant tag the prover binds, then the shape's statement and digest
+    fn bound_public_inputs(&self, mut public_inputs: Vec<BabyBearField>, shape: &CircuitShape) -> Result<Vec<BabyBearField>> {
+        public_inputs.extend(self.wallet_commitment.into_iter().flatten());
+        public_inputs.extend(self.tenant_tag);
+        public_inputs.extend(shape.statement());
+        public_inputs.extend(shape.digest()?);
+        Ok(public_inputs)
+    }
+
+    /// Constraint system of a statement of `shape` over the bound public inputs
+    fn circuit(&self, shape: &CircuitShape, public_inputs: Vec<BabyBearField>) -> Result<(Circuit, Vec<BabyBearField>)> {
+        let public_inputs = self.bound_public_inputs(public_inputs, shape)?;
+        let circuit = shape.build(&public_inputs)?;
+        self.limits.check_trace_height(circuit.system.height())?;
+        Ok((circuit, public_inputs))
+    }
+
+    /// Bind the public inputs into the trace and run the commitment/FRI pipeline
+    ///
+    /// Fills the circuit's section hashing the public inputs, then checks the
+    /// whole trace against the circuit's constraints, and their degrees
+    /// against the blowup, before any commitment work. The trace is extended
+    /// and committed row by row, the constraints composed over the extension
+    /// and divided by the trace's vanishing polynomial, and FRI run on the
+    /// quotient batched with the trace columns.
+    fn finalize_proof(
+        &mut self,
+        mut trace: ExecutionTrace,
+        circuit: &Circuit,
+        shape: CircuitShape,
+        public_inputs: Vec<BabyBearField>,
+    ) -> Result<StarkProof> {
+        circuit.fill_public_inputs(&mut trace, &public_inputs);
+        let digest_inputs = PublicInputs::new(public_inputs);
+        let system = &circuit.system;
+        self.limits.check_trace_height(trace.height)?;
+        self.limits.check_auth_path_depth((trace.height * self.blowup_factor).trailing_zeros() as usize)?;
+
+        #[cfg(feature = "debug-trace")]
+        crate::trace_debug::TraceDebugReport::new(&trace, &system.evaluate(&trace)).dump();
+
+        system.check_degrees(self.blowup_factor)?;
+        system.check(&trace)?;
+        let progress = ProgressReporter::new(self.progress.clone());
+        progress.finish(ProvingStage::TraceBuild);
+
+        self.commit_trace(trace, system, shape, digest_inputs, &progress)
+    }
+
+    /// Commit to a trace as the proof for `shape` without checking it against the circuit
+    ///
+    /// Adversarial testing only: every opening of the result authenticates,
+    /// so a verifier must reject it on the constraints and FRI alone when the
+    /// trace is invalid.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn prove_unchecked(
+        &mut self,
+        mut trace: ExecutionTrace,
+        circuit: &Circuit,
+        shape: CircuitShape,
+        public_inputs: Vec<BabyBearField>,
+    ) -> Result<StarkProof> {
+        circuit.fill_public_inputs(&mut trace, &public_inputs);
+        let progress = ProgressReporter::new(self.progress.clone());
+        self.commit_trace(trace, &circuit.system, shape, PublicInputs::new(public_inputs), &progress)
+    }
+
+    /// Parameters bound into the transcript of the proofs this prover generates
+    fn params(&self) -> StarkParams {
+        StarkParams::new(self.num_queries, self.blowup_factor).with_fri_folding_arity(self.fri_folding_arity)
+    }
+
+    /// Extend, commit and fold a filled trace into a proof
+    fn commit_trace(
+        &mut self,
+        trace: ExecutionTrace,
+        system: &ConstraintSystem,
+        shape: CircuitShape,
+        public_inputs: PublicInputs,
+        progress: &ProgressReporter,
+    ) -> Result<StarkProof> {
+        // Generate low-degree extension
+        let lde = self.compute_lde(&trace, progress)?;
+
+        // Commit to execution trace and its extension
+        let trace_commitment = self.commit_to_trace(&trace)?;
+        progress.report(ProvingStage::Commitment, 1, 3);
+        let lde_tree = MerkleTree::new(lde.data.clone());
+
+        let mut proof = StarkProof {
+            shape,
+            trace_root: trace_commitment,
+            lde_root: lde_tree.root(),
+            quotient_root: [0u8; 32],
+            fri_proof: FriProof { commitments: Vec::new(), final_poly: Vec::new(), pow_nonce: 0 },
+            queries: Vec::new(),
+            public_inputs_digest: public_inputs.digest(),
+            public_inputs: public_inputs.values,
+        };
+        let mut transcript = Transcript::new(TRANSCRIPT_DOMAIN, self.record_transcript);
+        if let Some(challenge) = &self.session_challenge {
+            transcript.absorb("session_challenge", challenge);
+        }
+        let alpha = absorb_trace_commitment(&mut transcript, self.tenant_tag, &proof, &self.params(), lde.height);
+
+        // Commit to the quotient of the composed constraints
+        let quotient = self.compute_quotient(system, &lde, alpha)?;
+        let quotient = CommittedLayer::new(quotient, 1);
+        proof.quotient_root = quotient.tree.root();
+        progress.finish(ProvingStage::Commitment);
+        let gamma = absorb_quotient_commitment(&mut transcript, &proof);
+
+        // Generate FRI proof over the quotient batched with the trace
+        let batched = (0..lde.height).into_par_iter().map(|row| batched_at(quotient.values[row], &lde.data[row], gamma)).collect();
+        let layers = self.generate_fri_proof(batched, &mut transcript, &mut proof.fri_proof, progress)?;
+
+        // Derive query positions via Fiat–Shamir and open them
+        let positions = absorb_pow_nonce(&mut transcript, proof.fri_proof.pow_nonce, self.num_queries, lde.height);
+        proof.queries = self.generate_queries(system, &lde, &lde_tree, &quotient, &layers, &positions);
+        self.last_transcript = transcript.into_log();
+        progress.finish(ProvingStage::Queries);
+
+        Ok(proof)
+    }
+
+    /// `as_of`, or the clock's current time when the request leaves it unset
+    fn evaluation_instant(&self, as_of: u64) -> u64 {
+        if as_of == 0 {
+            self.clock.now()
+        } else {
+            as_of
+        }
+    }
+
+    /// Threshold section witness over `user_scores`
+    fn threshold_section(
+        &self,
+        user_scores: &[(RepIDCategory, u32)],
+        threshold: u32,
+        time_window: u64,
+        timestamp: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<ThresholdWitness> {
+        self.penalized_threshold_section(user_scores, &[], threshold, time_window, timestamp, decay_params)
+    }
+
+    /// Threshold section witness over `user_scores` less `penalties`
+    fn penalized_threshold_section(
+        &self,
+        user_scores: &[(RepIDCategory, u32)],
+        penalties: &[PenaltyEvent],
+        threshold: u32,
+        time_window: u64,
+        as_of: u64,
+        decay_params: Option<&DecayParameters>,
+    ) -> Result<ThresholdWitness> {
+        // Thresholds share the aggregate bounds the comparison is sound within,
+        // and decayed scores the bound the decay gadget divides within
+        let floor = decay_params.map_or(0, |decay| decay.min_threshold);
+        for (limit, value) in [("threshold", threshold), ("min_threshold", floor)] {
+            if value as u64 >= MAX_AGGREGATE_SCORE {
+                return Err(ZKPError::LimitExceeded {
+                    limit: limit.to_string(),
+                    actual: value as u64,
+                    max: MAX_AGGREGATE_SCORE - 1,
+                });
+            }
+        }
+        // A floor at the threshold would meet it whatever the scores
+        if decay_params.is_some() && floor >= threshold {
+            return Err(ZKPError::InvalidInput(format!(
+                "Decay floor {} must be below the threshold {}",
+                floor, threshold
+            )));
+        }
+        if decay_params.is_some() {
+            if let Some(&(_, score)) = user_scores.iter().find(|(_, score)| *score as u64 >= MAX_DECAYED_SCORE) {
+                return Err(ZKPError::LimitExceeded {
+                    limit: "decayed_score".to_string(),
+                    actual: score as u64,
+                    max: MAX_DECAYED_SCORE - 1,
+                });
+            }
+        }
+
+        // Aggregate in 64 bits and refuse totals the field cannot carry without wrapping
+        let contributions: Vec<i64> = user_scores.iter()
+            .map(|(_, score)| *score as i64)
+            .chain(penalties.iter().map(|penalty| -(penalty.amount as i64)))
+            .collect();
+        let running_sums = running_sums(&contributions)?;
+        let total_penalty: i64 = penalties.iter().map(|penalty| penalty.amount as i64).sum();
+
+        // Scores cover [as_of - time_window, as_of]; decay runs over that span
+        // and the decayed total is raised to the floor
+        let factors = decay_params.map(|decay| decay.decay_factors(user_scores, decay_span(as_of, time_window)));
+        let category_total: i64 = match &factors {
+            Some(factors) => user_scores.iter().zip(factors).map(|((_, score), factor)| factor.apply(*score) as i64).sum(),
+            None => user_scores.iter().map(|(_, score)| *score as i64).sum(),
+        };
+        // Penalties are not decayed and may push the score below zero
+        let final_score = category_total.max(floor as i64) - total_penalty;
+
+        Ok(ThresholdWitness {
+            threshold,
+            time_window,
+            timestamp: as_of,
+            decay: factors.map(|factors| ThresholdDecay { factors, min_threshold: floor }),
+            private_threshold: false,
+            categories: user_scores.iter().map(|(category, _)| category.field_tag()).collect(),
+            contributions,
+            running_sums,
+            final_score,
+            kept: category_total >= floor as i64,
+            meets_threshold: final_score >= threshold as i64,
+        })
+    }
+
+    /// Fill one inclusion section per snapshot leaf right of the threshold section
+    ///
+    /// Score column `3 + index` of the threshold section holds `coefficient`
+    /// times the score hashed into leaf `index`; the wallet tag is committed under `salt`.
+    fn fill_snapshot_openings(
+        trace: &mut ExecutionTrace,
+        threshold: ThresholdAir,
+        wallet_hash: &str,
+        salt: &WalletSalt,
+        openings: &[(BabyBearField, &SnapshotLeaf, BabyBearField)],
+    ) {
+        let air = SnapshotOpeningsAir::new(
+            threshold,
+            openings.iter()
+                .map(|(coefficient, leaf, root)| SnapshotOpening {
+                    coefficient: *coefficient,
+                    category: leaf.category.field_tag(),
+                    depth: leaf.witness.siblings.len(),
+                    root: *root,
+                })
+                .collect(),
+            WalletCommitment::of_tag(wallet_tag(wallet_hash), salt).elements(),
+        );
+        let leaves: Vec<(u32, &[BabyBearField], u64)> = openings.iter()
+            .map(|(_, leaf, _)| (leaf.score, leaf.witness.siblings.as_slice(), leaf.witness.leaf_index))
+            .collect();
+        air.fill(trace, wallet_tag(wallet_hash), &salt.elements(), &leaves);
+    }
+
+    fn fill_biometric_trace(
+        trace: &mut ExecutionTrace,
+        challenge_field: BabyBearField,
+        biometric_hash: [u8; 32],
+        factor_proofs: &[bool; 4],
+    ) {
+        // Columns: challenge + hash + 4 factors + all_verified + validity
+        let hash_field = BabyBearField::new(
+            u64::from_le_bytes([
+                biometric_hash[0], biometric_hash[1], biometric_hash[2], biometric_hash[3],
+                biometric_hash[4], biometric_hash[5], biometric_hash[6], biometric_hash[7],
+            ])
+        );
+
+        for row in 0..trace.height {
+            let mut col = 0;
+
+            // Column 0: WebAuthn challenge (public)
+            trace.set(row, col, challenge_field);
+            col += 1;
+
+            // Column 1: Biometric hash (private)
+            trace.set(row, col, hash_field);
+            col += 1;
+
+            // Columns 2-5: Factor verification results (private)
+            let mut all_verified = true;
+            for &factor in factor_proofs {
+                let factor_field = if factor { BabyBearField::ONE } else { BabyBearField::ZERO };
+                trace.set(row, col, factor_field);
+                if !factor {
+                    all_verified = false;
+                }
+                col += 1;
+            }
+
+            // Column 6: All factors verified (private result)
+            let all_verified_field = if all_verified { BabyBearField::ONE } else { BabyBearField::ZERO };
+            trace.set(row, col, all_verified_field);
+            col += 1;
+
+            // Column 7: Proof validity
+            trace.set(row, col, BabyBearField::ONE);
+        }
+    }
+
+    fn commit_to_trace(&self, trace: &ExecutionTrace) -> Result<[u8; 32]> {
+        Ok(self.hash_backend.commit(trace))
+    }
+
+    fn compute_lde(&self, trace: &ExecutionTrace, progress: &ProgressReporter) -> Result<ExecutionTrace> {
+        // Interpolate each column over the trace subgroup and evaluate it on a
+        // disjoint coset blowup_factor times larger
+        let trace_domain = TwoAdicSubgroup::new(trace.height.trailing_zeros())?;
+        let lde_domain = lde_domain(trace.height, self.blowup_factor)?;
+
+        let extended = AtomicUsize::new(0);
+        let columns = (0..trace.width)
+            .into_par_iter()
+            .map(|col| {
+                let column = (0..trace.height).map(|row| trace.get(row, col)).collect();
+                let evaluations = Evaluations::new(trace_domain, column)?.interpolate()?.evaluate_over(&lde_domain)?;
+                progress.report(ProvingStage::Lde, extended.fetch_add(1, Ordering::SeqCst) + 1, trace.width);
+                Ok(evaluations.into_values())
+            })
+            .collect::<Result<Vec<Vec<BabyBearField>>>>()?;
+
+        let mut lde = ExecutionTrace::new(trace.width, lde_domain.size());
+        for (col, values) in columns.into_iter().enumerate() {
+            for (row, value) in values.into_iter().enumerate() {
+                lde.set(row, col, value);
+            }
+        }
+        Ok(lde)
+    }
+
+    /// Composed constraints divided by the trace's vanishing polynomial, on every LDE point
+    fn compute_quotient(&self, system: &ConstraintSystem, lde: &ExecutionTrace, alpha: BabyBearField) -> Result<Vec<BabyBearField>> {
+        let lde_domain = lde_domain(system.height(), self.blowup_factor)?;
+        let fixed = system
+            .fixed_polynomials()?
+            .iter()
+            .map(|poly| Ok(poly.evaluate_over(&lde_domain)?.into_values()))
+            .collect::<Result<Vec<Vec<BabyBearField>>>>()?;
+        let rotations = system.rotations();
+
+        // x^n - 1 repeats every blowup_factor points of the coset
+        let trace_domain = TwoAdicSubgroup::new(system.height().trailing_zeros())?;
+        let vanishing: Vec<BabyBearField> =
+            (0..self.blowup_factor).map(|position| trace_domain.vanishing_at(lde_domain.element(position))).collect();
+        let vanishing_inverses = BabyBearField::batch_inverse(&vanishing)
+            .ok_or_else(|| ZKPError::ProofGenerationError("LDE coset meets the trace domain".to_string()))?;
+
+        Ok((0..lde.height)
+            .into_par_iter()
+            .map_init(Vec::new, |values, position| {
+                let rows: Vec<&[BabyBearField]> = rotations
+                    .iter()
+                    .map(|&rotation| lde.data[rotated_position(position, rotation, self.blowup_factor, lde.height)].as_slice())
+                    .collect();
+                let fixed_row: Vec<BabyBearField> = fixed.iter().map(|column| column[position]).collect();
+                composition_at(system, &rotations, &rows, &fixed_row, alpha, values) * vanishing_inverses[position % self.blowup_factor]
+            })
+            .collect())
+    }
+
+    /// Commit to and fold each FRI layer, filling `fri_proof` and returning the committed layers
+    fn generate_fri_proof(
+        &mut self,
+        batched: Vec<BabyBearField>,
+        transcript: &mut Transcript,
+        fri_proof: &mut FriProof,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<CommittedLayer>> {
+        let arity = self.fri_folding_arity;
+        if !FRI_FOLDING_ARITIES.contains(&arity) {
+            return Err(ZKPError::InvalidInput(format!("Unsupported FRI folding arity {}", arity)));
+        }
+
+        let mut layers = Vec::new();
+        let mut domain = lde_domain(batched.len() / self.blowup_factor, self.blowup_factor)?;
+        let mut layer = batched;
+        // Rounds, then proof of work as one more unit
+        let rounds = fri_layer_count(layer.len(), arity) + 1;
+
+        // Each round commits to the layer's folding cosets and folds them with
+        // a challenge drawn after the commitment
+        while layer.len() > FRI_FINAL_LAYER_SIZE {
+            let committed = CommittedLayer::new(layer, arity);
+            fri_proof.commitments.push(committed.tree.root());
+            let beta = absorb_fri_commitment(transcript, &committed.tree.root());
+
+            let inverses = BabyBearField::batch_inverse(&domain.elements())
+                .ok_or_else(|| ZKPError::ProofGenerationError("FRI domain contains zero".to_string()))?;
+            let stride = committed.values.len() / arity;
+            layer = (0..stride)
+                .into_par_iter()
+                .map(|i| {
+                    let values: Vec<BabyBearField> = (0..arity).map(|j| committed.values[i + j * stride]).collect();
+                    let points: Vec<BabyBearField> = (0..arity).map(|j| inverses[i + j * stride]).collect();
+                    fold_coset(&values, &points, beta)
+                })
+                .collect();
+            domain = folded_domain(&domain, arity)?;
+            layers.push(committed);
+            progress.report(ProvingStage::Fri, layers.len(), rounds);
+        }
+
+        // Remaining evaluations are sent in the clear
+        fri_proof.final_poly = layer;
+
+        // Proof of work, ground against a seed drawn after the final layer
+        let seed = absorb_fri_final_layer(transcript, fri_proof);
+        fri_proof.pow_nonce = grind_pow_nonce(seed, self.params().grinding_bits)?;
+        progress.finish(ProvingStage::Fri);
+
+        Ok(layers)
+    }
+
+    /// Open the trace rows, quotient and FRI cosets each queried position reads
+    fn generate_queries(
+        &self,
+        system: &ConstraintSystem,
+        lde: &ExecutionTrace,
+        lde_tree: &MerkleTree,
+        quotient: &CommittedLayer,
+        layers: &[CommittedLayer],
+        positions: &[usize],
+    ) -> Vec<QueryResponse> {
+        let rotations = system.rotations();
+        let arity = self.fri_folding_arity;
+        positions
+            .iter()
+            .map(|&position| {
+                let rows = rotations
+                    .iter()
+                    .map(|&rotation| {
+                        let row = rotated_position(position, rotation, self.blowup_factor, lde.height);
+                        MerkleOpening { values: lde.data[row].clone(), auth_path: lde_tree.auth_path(row) }
+                    })
+                    .collect();
+                let mut index = position;
+                let fri_layers = layers
+                    .iter()
+                    .map(|layer| {
+                        index %= layer.values.len() / arity;
+                        layer.open(index, arity)
+                    })
+                    .collect();
+                QueryResponse { position, rows, quotient: quotient.open(position, 1), fri_layers }
+            })
+            .collect()
+    }
+}
+
+/// Verify a proof's commitments, constraints and FRI layers against `system`
+///
+/// Covers everything but the statement's own public inputs: the proof of
+/// work, the public input and shape digests, the query positions, every
+/// opening against its root, the composed constraints against the quotient at
+/// each queried point, and each FRI fold down to the final layer, whose
+/// degree is checked in the clear.
+pub fn check_stark_proof(
+    proof: &StarkProof,
+    system: &ConstraintSystem,
+    params: &StarkParams,
+    tenant_tag: Option<BabyBearField>,
+    session_challenge: Option<&[u8; 32]>,
+) -> Result<()> {
+    // Basic structural validation
+    if proof.queries.len() < params.num_queries {
+        return Err(ZKPError::ParameterDowngrade(format!(
+            "proof has {} queries, verifier requires {}",
+            proof.queries.len(),
+            params.num_queries
+        )));
+    }
+    if proof.queries.len() > params.num_queries {
+        return Err(ZKPError::MalformedProof(format!(
+            "proof has {} queries, verifier expects {}",
+            proof.queries.len(),
+            params.num_queries
+        )));
+    }
+
+    // Verify public inputs are in field
+    if !proof.public_inputs.iter().all(BabyBearField::is_canonical) {
+        return Err(ZKPError::MalformedProof("Public input is not a canonical field element".to_string()));
+    }
+
+    // Verify the public input digest matches the inputs it commits to
+    if PublicInputs::new(proof.public_inputs.clone()).digest() != proof.public_inputs_digest {
+        return Err(ZKPError::VerificationError("Public input digest mismatch".to_string()));
+    }
+    if !proof.public_inputs.ends_with(&proof.shape.digest()?) {
+        return Err(ZKPError::VerificationError("Public inputs do not end with the shape digest".to_string()));
+    }
+
+    // FRI layer count and final layer size are fixed by the LDE height and folding arity
+    let arity = params.fri_folding_arity;
+    if !FRI_FOLDING_ARITIES.contains(&arity) {
+        return Err(ZKPError::ConfigError(format!("Unsupported FRI folding arity {}", arity)));
+    }
+    system.check_degrees(params.blowup_factor)?;
+    let lde_height = system.height() * params.blowup_factor;
+    let trace_domain = TwoAdicSubgroup::new(system.height().trailing_zeros())?;
+    let lde = lde_domain(system.height(), params.blowup_factor)?;
+    let expected_layers = fri_layer_count(lde_height, arity);
+    if proof.fri_proof.commitments.len() != expected_layers {
+        return Err(ZKPError::MalformedProof(format!(
+            "FRI proof has {} layers, expected {} at folding arity {}",
+            proof.fri_proof.commitments.len(),
+            expected_layers,
+            arity
+        )));
+    }
+    let mut final_domain = lde;
+    for _ in 0..expected_layers {
+        final_domain = folded_domain(&final_domain, arity)?;
+    }
+    if proof.fri_proof.final_poly.len() != final_domain.size() {
+        return Err(ZKPError::MalformedProof(format!(
+            "FRI final layer has {} evaluations, expected {}",
+            proof.fri_proof.final_poly.len(),
+            final_domain.size()
+        )));
+    }
+
+    // The final layer is the batched polynomial folded down: its degree bound
+    // shrinks by the arity every layer
+    let folding = arity.pow(expected_layers as u32);
+    let final_bound = system.quotient_degree_bound().div_ceil(folding);
+    let final_poly = Evaluations::new(final_domain, proof.fri_proof.final_poly.clone())?.interpolate()?;
+    if final_poly.degree().is_some_and(|degree| degree >= final_bound) {
+        return Err(ZKPError::VerificationError("FRI final layer exceeds its degree bound".to_string()));
+    }
+
+    // Verify the proof of work and query positions against the transcript
+    let mut transcript = Transcript::new(TRANSCRIPT_DOMAIN, false);
+    if let Some(challenge) = session_challenge {
+        transcript.absorb("session_challenge", challenge);
+    }
+    let challenges = derive_challenges(&mut transcript, tenant_tag, proof, params, lde_height);
+    if !pow_nonce_is_valid(challenges.pow_seed, proof.fri_proof.pow_nonce, params.grinding_bits) {
+        return Err(ZKPError::VerificationError("Proof of work is invalid".to_string()));
+    }
+    if proof.queries.iter().map(|q| q.position).ne(challenges.positions.iter().copied()) {
+        return Err(ZKPError::VerificationError("Query positions do not match the transcript".to_string()));
+    }
+
+    let rotations = system.rotations();
+    let current_row = rotations.binary_search(&0).expect("rotations include zero");
+    let fixed = system.fixed_polynomials()?;
+    let depth = lde_height.trailing_zeros() as usize;
+    let mut values = Vec::new();
+    for query in &proof.queries {
+        // Every opening must authenticate to the committed LDE and quotient
+        if query.rows.len() != rotations.len() {
+            return Err(ZKPError::MalformedProof(format!(
+                "Query opens {} rows, the constraints read {}",
+                query.rows.len(),
+                rotations.len()
+            )));
+        }
+        for (opening, &rotation) in query.rows.iter().zip(&rotations) {
+            if opening.values.len() != system.width() || opening.auth_path.len() != depth {
+                return Err(ZKPError::MalformedProof("Trace opening has the wrong width or depth".to_string()));
+            }
+            let row = rotated_position(query.position, rotation, params.blowup_factor, lde_height);
+            if opening_root(opening, row) != proof.lde_root {
+                return Err(ZKPError::VerificationError("Query opening does not match the LDE root".to_string()));
+            }
+        }
+        if query.quotient.values.len() != 1 || query.quotient.auth_path.len() != depth {
+            return Err(ZKPError::MalformedProof("Quotient opening has the wrong width or depth".to_string()));
+        }
+        if opening_root(&query.quotient, query.position) != proof.quotient_root {
+            return Err(ZKPError::VerificationError("Quotient opening does not match the quotient root".to_string()));
+        }
+
+        // Composed constraints at the point must equal the quotient times the vanishing polynomial
+        let x = lde.element(query.position);
+        let fixed_row: Vec<BabyBearField> = fixed.iter().map(|poly| poly.evaluate(x)).collect();
+        let rows: Vec<&[BabyBearField]> = query.rows.iter().map(|opening| opening.values.as_slice()).collect();
+        let quotient = query.quotient.values[0];
+        if composition_at(system, &rotations, &rows, &fixed_row, challenges.composition, &mut values)
+            != quotient * trace_domain.vanishing_at(x)
+        {
+            return Err(ZKPError::VerificationError("Constraints do not match the quotient at a queried point".to_string()));
+        }
+
+        // Each FRI layer's coset must hold the previous layer's value and fold into the next
+        if query.fri_layers.len() != expected_layers {
+            return Err(ZKPError::MalformedProof(format!(
+                "Query opens {} FRI layers, expected {}",
+                query.fri_layers.len(),
+                expected_layers
+            )));
+        }
+        let mut current = batched_at(quotient, rows[current_row], challenges.batching);
+        let mut domain = lde;
+        let mut position = query.position;
+        for ((opening, commitment), &beta) in query.fri_layers.iter().zip(&proof.fri_proof.commitments).zip(&challenges.folding) {
+            let stride = domain.size() / arity;
+            let index = position % stride;
+            if opening.values.len() != arity || opening.auth_path.len() != stride.trailing_zeros() as usize {
+                return Err(ZKPError::MalformedProof("FRI opening has the wrong width or depth".to_string()));
+            }
+            if opening_root(opening, index) != *commitment {
+                return Err(ZKPError::VerificationError("FRI opening does not match its layer commitment".to_string()));
+            }
+            if opening.values[position / stride] != current {
+                return Err(ZKPError::VerificationError("FRI layer does not fold from the previous one".to_string()));
+            }
+            let points: Vec<BabyBearField> = (0..arity).map(|j| domain.element(index + j * stride)).collect();
+            let inverses = BabyBearField::batch_inverse(&points)
+                .ok_or_else(|| ZKPError::MalformedProof("FRI domain contains zero".to_string()))?;
+            current = fold_coset(&opening.values, &inverses, beta);
+            domain = folded_domain(&domain, arity)?;
+            position = index;
+        }
+        if proof.fri_proof.final_poly[position] != current {
+            return Err(ZKPError::VerificationError("FRI final layer does not match the folded query".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Custom STARK verifier
+#[derive(Clone)]
+pub struct CustomStarkVerifier {
+    pub num_queries: usize,
+    pub blowup_factor: usize,
+    /// Only accept proofs committing to this wallet
+    pub wallet_commitment: Option<crate::poseidon2::Digest>,
+    /// Only accept proofs bound to this tenant
+    pub tenant_tag: Option<BabyBearField>,
+    /// Only accept proofs bound to this session challenge
+    pub session_challenge: Option<[u8; 32]>,
+    /// Only accept threshold proofs enforcing exactly these caps
+    pub category_caps: Vec<CategoryCap>,
+    /// Only accept threshold proofs normalizing exactly these scales
+    pub score_scales: Vec<ScoreScale>,
+    /// FRI folding arity the proof must have been generated with
+    pub fri_folding_arity: usize,
+    /// Proof-of-work bits the proof must have been generated with
+    pub grinding_bits: u32,
+    /// Challenge extension degree the proof must have been generated with
+    pub challenge_extension_degree: u32,
 }
 
 impl CustomStarkVerifier {
@@ -600,80 +2725,788 @@ impl CustomStarkVerifier {
         Self {
             num_queries,
             blowup_factor,
+            wallet_commitment: None,
+            tenant_tag: None,
+            session_challenge: None,
+            category_caps: Vec::new(),
+            score_scales: Vec::new(),
+            fri_folding_arity: default_fri_folding_arity(),
+            grinding_bits: GRINDING_BITS,
+            challenge_extension_degree: CHALLENGE_EXTENSION_DEGREE,
+        }
+    }
+
+    /// Same verifier requiring `caps` and `scales` in place of its own
+    pub fn with_adjustments(&self, caps: Vec<CategoryCap>, scales: Vec<ScoreScale>) -> Self {
+        Self {
+            category_caps: caps,
+            score_scales: scales,
+            ..self.clone()
+        }
+    }
+
+    /// Same verifier only accepting proofs committing to `wallet`
+    pub fn with_wallet_commitment(&self, wallet: crate::poseidon2::Digest) -> Self {
+        Self {
+            wallet_commitment: Some(wallet),
+            ..self.clone()
+        }
+    }
+
+    /// Same verifier checking proofs generated under `params`
+    pub fn with_params(&self, params: &StarkParams) -> Self {
+        Self {
+            num_queries: params.num_queries,
+            blowup_factor: params.blowup_factor,
+            fri_folding_arity: params.fri_folding_arity,
+            grinding_bits: params.grinding_bits,
+            challenge_extension_degree: params.challenge_extension_degree,
+            ..self.clone()
         }
     }
 
     /// Verify a STARK proof
+    ///
+    /// Lenient form: any verification failure yields `Ok(false)`; use
+    /// `check_proof` for the typed reason.
     pub fn verify_proof(&self, proof: &StarkProof, proof_type: &str) -> Result<bool> {
-        // Basic structural validation
-        if proof.queries.len() != self.num_queries {
-            return Ok(false);
+        Ok(self.check_proof(proof, proof_type).is_ok())
+    }
+
+    /// Verify a STARK proof, failing closed with a typed error
+    pub fn check_proof(&self, proof: &StarkProof, proof_type: &str) -> Result<()> {
+        // Type-specific verification
+        let check: fn(&Self, &StarkProof) -> Result<()> = match proof_type {
+            "threshold_verification" => Self::check_capped_threshold_proof,
+            "committed_threshold_verification" => Self::check_committed_threshold_proof,
+            "hidden_threshold_verification" => Self::check_hidden_threshold_proof,
+            "chained_threshold_verification" => Self::check_chained_threshold_proof,
+            "fresh_threshold_verification" => Self::check_fresh_threshold_proof,
+            "unrevoked_threshold_verification" => Self::check_unrevoked_threshold_proof,
+            "cosigned_threshold_verification" => Self::check_cosigned_threshold_proof,
+            "linked_threshold_verification" => Self::check_linked_threshold_proof,
+            "designated_threshold_verification" => Self::check_designated_threshold_proof,
+            "escrowed_threshold_verification" => Self::check_escrowed_threshold_proof,
+            "oracle_threshold_verification" => Self::check_oracle_threshold_proof,
+            "score_opening" => Self::check_score_opening_proof,
+            "rank_bucket" => Self::check_rank_bucket_proof,
+            "category_count" => Self::check_category_count_proof,
+            "sustained_threshold" => Self::check_sustained_threshold_proof,
+            "score_delta" => Self::check_score_delta_proof,
+            "rate_limited_signal" => Self::check_rate_limited_signal_proof,
+            "history_threshold" => Self::check_threshold_proof,
+            "biometric_4fa" => Self::check_biometric_proof,
+            other => return Err(ZKPError::UnknownOperation(other.to_string())),
+        };
+        if proof.shape.proof_type() != proof_type {
+            return Err(ZKPError::VerificationError(format!(
+                "Proof is a {} proof, not {}",
+                proof.shape.proof_type(),
+                proof_type
+            )));
+        }
+        self.check_structure(proof)?;
+        check(self, proof)
+    }
+
+    /// Verify the checks shared by every operation type
+    ///
+    /// Rebuilds the constraint system from the proof's shape and public
+    /// inputs and checks the proof against it; callers check the public
+    /// inputs against their own statement. Proofs from
+    /// `CustomStarkProver::prove_air` are checked with `check_air_proof`.
+    pub fn check_structure(&self, proof: &StarkProof) -> Result<()> {
+        if let CircuitShape::Air { .. } = proof.shape {
+            return Err(ZKPError::VerificationError("AIR proofs are checked against their AIR".to_string()));
         }
+        self.check_bindings(proof)?;
+        let circuit = proof.shape.build(&proof.public_inputs)?;
+        check_stark_proof(proof, &circuit.system, &self.params(), self.tenant_tag, self.session_challenge.as_ref())
+    }
 
-        // Verify proof of work
-        if !self.verify_proof_of_work(&proof.fri_proof)? {
-            return Ok(false);
+    /// Verify a proof from `CustomStarkProver::prove_air` against `air`
+    ///
+    /// The whole of verification for application-defined AIRs; callers check
+    /// the public inputs against their own statement.
+    pub fn check_air_proof(&self, proof: &StarkProof, air: &dyn CustomAir) -> Result<()> {
+        let CircuitShape::Air { height } = proof.shape else {
+            return Err(ZKPError::VerificationError(format!("Proof is a {} proof, not an AIR proof", proof.shape.proof_type())));
+        };
+        if !height.is_power_of_two() {
+            return Err(ZKPError::MalformedProof(format!("AIR trace height {} is not a power of two", height)));
         }
+        self.check_bindings(proof)?;
+        let degree = air.constraint_system(height).max_degree();
+        let layout = TraceLayout::select(height, degree, self.blowup_factor)?;
+        let system = air.constraint_system(layout.trace_length);
+        system.check_degrees(self.blowup_factor)?;
+        let circuit = Circuit::tiled(&system, &proof.public_inputs)?;
+        check_stark_proof(proof, &circuit.system, &self.params(), self.tenant_tag, self.session_challenge.as_ref())
+    }
 
-        // Verify FRI proof structure
-        if proof.fri_proof.commitments.is_empty() {
-            return Ok(false);
+    /// Parameters proofs are checked under
+    pub fn params(&self) -> StarkParams {
+        StarkParams {
+            num_queries: self.num_queries,
+            blowup_factor: self.blowup_factor,
+            grinding_bits: self.grinding_bits,
+            challenge_extension_degree: self.challenge_extension_degree,
+            fri_folding_arity: self.fri_folding_arity,
         }
+    }
 
-        // Verify public inputs are in field
-        for &input in &proof.public_inputs {
-            if input.0 >= BabyBearField::MODULUS {
-                return Ok(false);
+    /// Check the tenant tag and wallet commitment the verifier requires, just ahead of the shape's statement
+    fn check_bindings(&self, proof: &StarkProof) -> Result<()> {
+        let bound = proof.public_inputs.len().checked_sub(DIGEST_ELEMENTS + proof.shape.statement().len())
+            .ok_or_else(|| ZKPError::MalformedProof("Public inputs end before the shape's statement".to_string()))?;
+        let bound = &proof.public_inputs[..bound];
+
+        // Tenant-bound verifiers only accept proofs carrying their tag
+        if let Some(tag) = self.tenant_tag {
+            if bound.last() != Some(&tag) {
+                return Err(ZKPError::VerificationError("Proof is bound to a different tenant".to_string()));
             }
         }
 
-        // Type-specific verification
-        match proof_type {
-            "threshold_verification" => self.verify_threshold_proof(proof),
-            "biometric_4fa" => self.verify_biometric_proof(proof),
-            _ => Ok(true), // Generic verification passed
+        // Wallet-bound verifiers check the commitment snapshot leaves open under,
+        // or else the one just ahead of any tenant tag
+        if let Some(wallet) = self.wallet_commitment {
+            let end = bound.len().saturating_sub(self.tenant_tag.is_some() as usize);
+            let committed = match proof.shape.wallet_index(&proof.public_inputs) {
+                Some(start) => bound.get(start..start + DIGEST_ELEMENTS) == Some(&wallet[..]),
+                None => bound[..end].ends_with(&wallet),
+            };
+            if !committed {
+                return Err(ZKPError::VerificationError("Proof commits to a different wallet".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Position of the evaluation instant among the public inputs of a proof of `shape`
+    fn as_of_index(shape: &CircuitShape) -> Option<usize> {
+        match shape {
+            CircuitShape::Threshold { .. } => Some(2),
+            CircuitShape::AdjustedThreshold { .. } => Some(3),
+            CircuitShape::CommittedThreshold { .. } | CircuitShape::HiddenThreshold { .. } => Some(2 + DIGEST_ELEMENTS),
+            CircuitShape::UnrevokedThreshold { .. } | CircuitShape::CosignedThreshold { .. } => Some(3),
+            CircuitShape::DesignatedThreshold { .. } => Some(3),
+            CircuitShape::ChainedThreshold { .. } | CircuitShape::FreshThreshold { .. } => Some(4),
+            CircuitShape::LinkedThreshold { .. } | CircuitShape::OracleThreshold { .. } => Some(5),
+            CircuitShape::RankBucket { .. } | CircuitShape::CategoryCount { .. } | CircuitShape::SustainedThreshold { .. } => Some(2),
+            CircuitShape::ScoreDelta { .. } | CircuitShape::HistoryThreshold { .. } => Some(2),
+            CircuitShape::RateLimitedSignal | CircuitShape::EscrowedThreshold { .. } => Some(2),
+            CircuitShape::ScoreOpening { .. } | CircuitShape::Biometric | CircuitShape::Air { .. } => None,
+        }
+    }
+
+    /// Check a threshold-family proof was evaluated at `as_of`
+    pub fn check_as_of(&self, proof: &StarkProof, proof_type: &str, as_of: u64) -> Result<()> {
+        let index = Self::as_of_index(&proof.shape)
+            .ok_or_else(|| ZKPError::InvalidInput(format!("{} proofs carry no evaluation instant", proof_type)))?;
+        match proof.public_inputs.get(index) {
+            Some(value) if value.0 == as_of => Ok(()),
+            Some(value) => Err(ZKPError::VerificationError(format!(
+                "proof was evaluated at {}, request is as of {}",
+                value.0, as_of
+            ))),
+            None => Err(ZKPError::MalformedProof("Proof has no evaluation instant".to_string())),
+        }
+    }
+
+    /// Check a threshold-family proof states `request`'s threshold, time window and categories
+    ///
+    /// Every category a threshold section sums must be requested; sections
+    /// over a committed category set state none.
+    pub fn check_request(&self, proof: &StarkProof, request: &ThresholdVerificationRequest) -> Result<()> {
+        let compares = proof.shape.threshold_shape().is_some()
+            || matches!(proof.shape, CircuitShape::OracleThreshold { .. } | CircuitShape::HistoryThreshold { .. });
+        if !compares {
+            return Ok(());
+        }
+        let stated = |index: usize, name: &str| {
+            proof.public_inputs.get(index).map(|value| value.0)
+                .ok_or_else(|| ZKPError::MalformedProof(format!("Proof has no {}", name)))
+        };
+
+        let threshold = stated(0, "threshold")?;
+        if threshold != request.threshold as u64 {
+            return Err(ZKPError::VerificationError(format!(
+                "proof is for threshold {}, request is for {}",
+                threshold, request.threshold
+            )));
+        }
+        let time_window = stated(1, "time window")?;
+        if time_window != request.time_window {
+            return Err(ZKPError::VerificationError(format!(
+                "proof covers a window of {}s, request covers {}s",
+                time_window, request.time_window
+            )));
+        }
+
+        if let Some(scores) = proof.shape.threshold_shape() {
+            let requested: Vec<BabyBearField> = request.categories.iter().map(|category| category.field_tag()).collect();
+            if let Some(tag) = scores.categories.iter().find(|tag| !requested.contains(tag)) {
+                return Err(ZKPError::VerificationError(format!("proof sums category {} the request does not cover", tag.0)));
+            }
+            let timestamp = Self::as_of_index(&proof.shape).map_or(Ok(0), |index| stated(index, "evaluation instant"))?;
+            Self::check_decay(scores, request, decay_span(timestamp, time_window))?;
+        }
+        Ok(())
+    }
+
+    /// Check a threshold section states the floor and decay factors of `request` over `span`
+    ///
+    /// Factors of stated categories must be exactly theirs; a section over a
+    /// committed category set may use the factor of any requested category.
+    fn check_decay(scores: &ThresholdShape, request: &ThresholdVerificationRequest, span: u64) -> Result<()> {
+        let (decay, params) = match (&scores.decay, &request.decay_params) {
+            (None, None) => return Ok(()),
+            (Some(decay), Some(params)) => (decay, params),
+            (Some(_), None) => return Err(ZKPError::VerificationError("proof decays scores the request does not".to_string())),
+            (None, Some(_)) => return Err(ZKPError::VerificationError("proof does not apply the requested decay".to_string())),
+        };
+        if decay.min_threshold != params.min_threshold {
+            return Err(ZKPError::VerificationError(format!(
+                "proof raises scores to a floor of {}, request sets {}",
+                decay.min_threshold, params.min_threshold
+            )));
+        }
+
+        let factor_of = |category: &RepIDCategory| params.decay_for(category).factor(span);
+        let applies_requested = decay.factors.iter().enumerate().all(|(i, factor)| match scores.categories.get(i) {
+            Some(tag) => request.categories.iter().any(|category| category.field_tag() == *tag && factor_of(category) == *factor),
+            None => request.categories.iter().any(|category| factor_of(category) == *factor),
+        });
+        if !applies_requested {
+            return Err(ZKPError::VerificationError("proof decays scores with factors other than the request's".to_string()));
         }
+        Ok(())
     }
 
-    fn verify_proof_of_work(&self, fri_proof: &FriProof) -> Result<bool> {
-        let mut hasher = Hasher::new();
-        hasher.update(b"RepID_PoW");
-        hasher.update(&fri_proof.pow_nonce.to_le_bytes());
-        let hash = hasher.finalize();
-        
-        // Verify first 16 bits are zero
-        Ok(hash.as_bytes()[0] == 0 && hash.as_bytes()[1] == 0)
+    /// Replay the Fiat–Shamir transcript of a proof for audit comparison
+    pub fn replay_transcript(&self, proof: &StarkProof) -> TranscriptLog {
+        let lde_height = proof.queries.first()
+            .and_then(|query| query.rows.first())
+            .map(|row| 1usize << row.auth_path.len().min(usize::BITS as usize - 1))
+            .unwrap_or(1);
+        let mut transcript = Transcript::new(TRANSCRIPT_DOMAIN, true);
+        if let Some(challenge) = &self.session_challenge {
+            transcript.absorb("session_challenge", challenge);
+        }
+        derive_challenges(&mut transcript, self.tenant_tag, proof, &self.params(), lde_height);
+        transcript.into_log().unwrap_or_else(|| TranscriptLog {
+            domain: TRANSCRIPT_DOMAIN.to_string(),
+            entries: Vec::new(),
+        })
     }
 
-    fn verify_threshold_proof(&self, proof: &StarkProof) -> Result<bool> {
+    fn check_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
         if proof.public_inputs.len() < 2 {
-            return Ok(false);
+            return Err(ZKPError::MalformedProof("Threshold proof needs 2 public inputs".to_string()));
         }
 
-        let threshold = proof.public_inputs[0].0 as u32;
+        let threshold = proof.public_inputs[0].0;
         let time_window = proof.public_inputs[1].0;
 
         // Validate threshold range
         if threshold == 0 || threshold > 1000 {
-            return Ok(false);
+            return Err(ZKPError::VerificationError(format!("Threshold {} out of range", threshold)));
+        }
+
+        // A floor at the threshold would meet it whatever the scores
+        if let Some(decay) = proof.shape.threshold_shape().and_then(|scores| scores.decay.as_ref()) {
+            if decay.min_threshold as u64 >= threshold {
+                return Err(ZKPError::VerificationError(format!(
+                    "Decay floor {} is not below the threshold {}",
+                    decay.min_threshold, threshold
+                )));
+            }
         }
 
         // Validate time window
         if time_window == 0 {
-            return Ok(false);
+            return Err(ZKPError::VerificationError("Time window is zero".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn check_capped_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        // The circuit saturates and normalizes with the caps and scales in its
+        // shape, so those must be this verifier's, even when it configures none
+        let enforced = match &proof.shape {
+            CircuitShape::AdjustedThreshold { capped, scaled, .. } => {
+                capped.iter().all(|(_, cap)| self.category_caps.contains(cap))
+                    && scaled.iter().all(|(_, scale)| self.score_scales.contains(scale))
+            }
+            _ => self.category_caps.is_empty() && self.score_scales.is_empty(),
+        };
+        if !enforced {
+            return Err(ZKPError::VerificationError("Proof circuit does not apply the configured caps and scales".to_string()));
+        }
+        if !self.category_caps.is_empty() || !self.score_scales.is_empty() {
+            let digest = adjustments_digest(&self.score_scales, &self.category_caps);
+            if proof.public_inputs.get(2) != Some(&digest) {
+                return Err(ZKPError::VerificationError("Proof does not enforce the configured caps and scales".to_string()));
+            }
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_committed_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 3 + DIGEST_ELEMENTS {
+            return Err(ZKPError::MalformedProof(format!(
+                "Committed threshold proof needs {} public inputs",
+                3 + DIGEST_ELEMENTS
+            )));
+        }
+
+        // Commitment must be a non-trivial digest
+        if proof.public_inputs[2..2 + DIGEST_ELEMENTS].iter().all(|&element| element == BabyBearField::ZERO) {
+            return Err(ZKPError::VerificationError("Score commitment is zero".to_string()));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_hidden_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 3 + DIGEST_ELEMENTS {
+            return Err(ZKPError::MalformedProof(format!(
+                "Hidden-category threshold proof needs {} public inputs",
+                3 + DIGEST_ELEMENTS
+            )));
+        }
+
+        // Commitment must be a non-trivial digest
+        if proof.public_inputs[2..2 + DIGEST_ELEMENTS].iter().all(|&element| element == BabyBearField::ZERO) {
+            return Err(ZKPError::VerificationError("Category set commitment is zero".to_string()));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_chained_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 4 {
+            return Err(ZKPError::MalformedProof("Chained threshold proof needs 4 public inputs".to_string()));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_fresh_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 4 {
+            return Err(ZKPError::MalformedProof("Freshness threshold proof needs 4 public inputs".to_string()));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_unrevoked_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 4 {
+            return Err(ZKPError::MalformedProof("Unrevoked threshold proof needs 4 public inputs".to_string()));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_cosigned_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 5 {
+            return Err(ZKPError::MalformedProof("Co-signed threshold proof needs 5 public inputs".to_string()));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_linked_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 5 {
+            return Err(ZKPError::MalformedProof("Linked threshold proof needs 5 public inputs".to_string()));
+        }
+
+        // At least one wallet must be linked
+        if proof.public_inputs[4] == BabyBearField::ZERO {
+            return Err(ZKPError::VerificationError("Linked proof covers no wallets".to_string()));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_designated_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 4 {
+            return Err(ZKPError::MalformedProof("Designated threshold proof needs 4 public inputs".to_string()));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_escrowed_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 3 + 2 * DIGEST_ELEMENTS {
+            return Err(ZKPError::MalformedProof(format!(
+                "Escrowed threshold proof needs {} public inputs",
+                3 + 2 * DIGEST_ELEMENTS
+            )));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_oracle_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 6 {
+            return Err(ZKPError::MalformedProof("Oracle threshold proof needs 6 public inputs".to_string()));
+        }
+
+        self.check_threshold_proof(proof)
+    }
+
+    fn check_rank_bucket_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 2 {
+            return Err(ZKPError::MalformedProof("Rank bucket proof needs 2 public inputs".to_string()));
+        }
+
+        let top_percent = proof.public_inputs[0].0;
+        if top_percent == 0 || top_percent > 100 {
+            return Err(ZKPError::VerificationError(format!("Band 'top {}%' out of range", top_percent)));
+        }
+
+        // Commitment must be a non-trivial digest
+        if proof.public_inputs[1] == BabyBearField::ZERO {
+            return Err(ZKPError::VerificationError("Distribution commitment is zero".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn check_score_opening_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < DIGEST_ELEMENTS + 2 {
+            return Err(ZKPError::MalformedProof(format!(
+                "Score opening proof needs {} public inputs",
+                DIGEST_ELEMENTS + 2
+            )));
+        }
+
+        // Commitment must be a non-trivial digest
+        if proof.public_inputs[..DIGEST_ELEMENTS].iter().all(|&element| element == BabyBearField::ZERO) {
+            return Err(ZKPError::VerificationError("Score commitment is zero".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn check_category_count_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 2 {
+            return Err(ZKPError::MalformedProof("Category count proof needs 2 public inputs".to_string()));
+        }
+
+        // A count of zero categories proves nothing
+        if proof.public_inputs[1] == BabyBearField::ZERO {
+            return Err(ZKPError::VerificationError("Category count k is zero".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn check_sustained_threshold_proof(&self, proof: &StarkProof) -> Result<()> {
+        let num_epochs = match proof.public_inputs.get(3) {
+            Some(count) if count.0 > 0 => count.0 as usize,
+            _ => return Err(ZKPError::MalformedProof("Sustained proof needs a positive epoch count".to_string())),
+        };
+        let snapshots = proof.public_inputs.get(4..4 + 2 * num_epochs)
+            .ok_or_else(|| ZKPError::MalformedProof(format!("Sustained proof needs {} snapshot inputs", 2 * num_epochs)))?;
+        if proof.public_inputs[0] == BabyBearField::ZERO {
+            return Err(ZKPError::VerificationError("Sustained threshold is zero".to_string()));
+        }
+        if snapshots.chunks(2).zip(snapshots.chunks(2).skip(1)).any(|(a, b)| b[0].0 != a[0].0 + 1) {
+            return Err(ZKPError::VerificationError("Snapshot epochs are not consecutive".to_string()));
         }
+        Ok(())
+    }
+
+    fn check_score_delta_proof(&self, proof: &StarkProof) -> Result<()> {
+        let num_categories = match proof.public_inputs.get(1) {
+            Some(count) if count.0 > 0 => count.0 as usize,
+            _ => return Err(ZKPError::MalformedProof("Score delta proof needs a positive category count".to_string())),
+        };
+        if proof.public_inputs.len() < 7 + num_categories {
+            return Err(ZKPError::MalformedProof(format!("Score delta proof needs {} public inputs", 7 + num_categories)));
+        }
+        if proof.public_inputs[0] == BabyBearField::ZERO {
+            return Err(ZKPError::VerificationError("Minimum score delta is zero".to_string()));
+        }
+        if proof.public_inputs[5].0 <= proof.public_inputs[3].0 {
+            return Err(ZKPError::VerificationError("Score delta epochs are not in order".to_string()));
+        }
+        let tags = &proof.public_inputs[7..7 + num_categories];
+        if tags.iter().enumerate().any(|(i, tag)| tags[..i].contains(tag)) {
+            return Err(ZKPError::VerificationError("Score delta counts a category more than once".to_string()));
+        }
+        Ok(())
+    }
 
-        Ok(true)
+    fn check_rate_limited_signal_proof(&self, proof: &StarkProof) -> Result<()> {
+        if proof.public_inputs.len() < 8 {
+            return Err(ZKPError::MalformedProof("Rate-limited signal proof needs 8 public inputs".to_string()));
+        }
+        let message_limit = proof.public_inputs[1].0;
+        if message_limit == 0 || message_limit > crate::rln::MAX_MESSAGE_LIMIT as u64 {
+            return Err(ZKPError::VerificationError(format!("Message limit {} is out of range", message_limit)));
+        }
+        if proof.public_inputs[0] == BabyBearField::ZERO || proof.public_inputs[7] == BabyBearField::ZERO {
+            return Err(ZKPError::VerificationError("Identity set root or nullifier is zero".to_string()));
+        }
+        Ok(())
     }
 
-    fn verify_biometric_proof(&self, proof: &StarkProof) -> Result<bool> {
+    fn check_biometric_proof(&self, proof: &StarkProof) -> Result<()> {
         if proof.public_inputs.is_empty() {
-            return Ok(false);
+            return Err(ZKPError::MalformedProof("Biometric proof needs a public challenge".to_string()));
         }
 
-        let webauthn_challenge = proof.public_inputs[0].0;
-        
         // Validate challenge is non-zero
-        Ok(webauthn_challenge > 0)
+        if proof.public_inputs[0].0 == 0 {
+            return Err(ZKPError::VerificationError("WebAuthn challenge is zero".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::field_element_strategy;
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_field_utilities_match_elementwise() {
+        let values: Vec<BabyBearField> = [3u64, 7, BabyBearField::MODULUS - 1, 123_456_789].map(BabyBearField::new).to_vec();
+        let inverses = BabyBearField::batch_inverse(&values).unwrap();
+        for (value, inverse) in values.iter().zip(&inverses) {
+            assert_eq!(Some(*inverse), value.inverse());
+        }
+        assert_eq!(BabyBearField::batch_inverse(&[BabyBearField::ONE, BabyBearField::ZERO]), None);
+        assert_eq!(BabyBearField::batch_inverse(&[]), Some(Vec::new()));
+
+        let x = BabyBearField::new(5);
+        assert_eq!(x.double(), BabyBearField::new(10));
+        assert_eq!(x.square(), BabyBearField::new(25));
+        assert_eq!(x.exp_power_of_2(3), x.pow(8));
+        assert_eq!(BabyBearField::sum(&values), values[0] + values[1] + values[2] + values[3]);
+        assert_eq!(BabyBearField::product(&values[..2]), BabyBearField::new(21));
+    }
+    #[test]
+    fn test_numeric_traits_for_generic_code() {
+        fn horner<T: Copy + num_traits::Zero + std::ops::MulAssign + std::ops::AddAssign>(coeffs: &[T], x: T) -> T {
+            let mut acc = T::zero();
+            for &c in coeffs.iter().rev() {
+                acc *= x;
+                acc += c;
+            }
+            acc
+        }
+
+        let coeffs: Vec<BabyBearField> = [1u32, 2, 3].into_iter().map(BabyBearField::from).collect();
+        assert_eq!(horner(&coeffs, BabyBearField::from(2u64)), BabyBearField::new(17));
+        assert_eq!(coeffs.iter().sum::<BabyBearField>(), BabyBearField::new(6));
+        assert_eq!(coeffs.iter().copied().product::<BabyBearField>(), BabyBearField::new(6));
+        assert!(<BabyBearField as num_traits::One>::one() == BabyBearField::ONE);
+
+        let mut rng = ChaCha20Rng::from_seed([7u8; 32]);
+        let samples: Vec<BabyBearField> = (0..256).map(|_| rand::Rng::gen(&mut rng)).collect();
+        assert!(samples.iter().all(BabyBearField::is_canonical));
+        assert!(samples.windows(2).any(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn test_running_sums_reject_overflow() {
+        assert_eq!(running_sums(&[40, 35, -20]).unwrap(), vec![40, 75, 55]);
+
+        let max = u32::MAX as i64;
+        assert!(matches!(running_sums(&[max, max]), Err(ZKPError::LimitExceeded { .. })));
+        // An intermediate overflow is rejected even when later penalties bring the total back down
+        assert!(running_sums(&[max, max, -max]).is_err());
+
+        let mut zkp_system = crate::RepIDZKPSystem::new(crate::SecurityLevel::Fast);
+        let request = crate::ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![crate::RepIDCategory::Technical, crate::RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let scores = [(crate::RepIDCategory::Technical, u32::MAX), (crate::RepIDCategory::Community, u32::MAX)];
+        assert!(zkp_system.prove_threshold_verification(&request, &scores, "0xtest").is_err());
+    }
+
+    /// Application AIR: column 1 doubles column 0 and column 0 counts up from `start`
+    struct DoublingAir {
+        start: u64,
+    }
+
+    impl crate::air::CustomAir for DoublingAir {
+        fn width(&self) -> usize {
+            2
+        }
+
+        fn add_constraints(&self, system: &mut crate::air::ConstraintSystem) {
+            use crate::air::Expr;
+
+            let first = system.first_row();
+            let x = Expr::cell(0);
+            let step = (BabyBearField::ONE - &first) * (&x - Expr::rotated(0, -1) - BabyBearField::ONE);
+            system.constrain("count", step + first * (&x - BabyBearField::new(self.start)));
+            system.constrain("double", Expr::cell(1) - x * BabyBearField::new(2));
+        }
+    }
+
+    #[test]
+    fn test_prove_air_drives_application_constraints() {
+        let air = DoublingAir { start: 5 };
+        let mut witness = ExecutionTrace::new(2, 16);
+        for row in 0..16 {
+            witness.set(row, 0, BabyBearField::new(5 + row as u64));
+            witness.set(row, 1, BabyBearField::new(10 + 2 * row as u64));
+        }
+
+        let mut prover = CustomStarkProver::new(8, 4);
+        let proof = prover.prove_air(&air, witness.clone(), vec![BabyBearField::new(5)]).unwrap();
+        let verifier = CustomStarkVerifier::new(8, 4);
+        assert!(verifier.check_air_proof(&proof, &air).is_ok());
+        assert_eq!(proof.public_inputs[..1], [BabyBearField::new(5)]);
+        assert_eq!(proof.public_inputs[1..], proof.shape.digest().unwrap());
+        assert!(matches!(verifier.check_proof(&proof, "doubling"), Err(ZKPError::UnknownOperation(_))));
+
+        // Checked against another AIR, the openings no longer satisfy the constraints
+        assert!(verifier.check_air_proof(&proof, &DoublingAir { start: 6 }).is_err());
+        assert!(verifier.check_structure(&proof).is_err());
+
+        witness.set(9, 1, BabyBearField::new(0));
+        let err = prover.prove_air(&air, witness, vec![BabyBearField::new(5)]).unwrap_err();
+        assert!(err.to_string().contains("row 9"));
+    }
+
+    /// Application AIR with one degree-7 constraint: column 1 is column 0 to the seventh
+    struct SeventhPowerAir;
+
+    impl crate::air::CustomAir for SeventhPowerAir {
+        fn width(&self) -> usize {
+            2
+        }
+
+        fn add_constraints(&self, system: &mut crate::air::ConstraintSystem) {
+            use crate::air::Expr;
+
+            let x = Expr::cell(0);
+            let seventh = (0..6).fold(x.clone(), |power, _| power * &x);
+            system.constrain("seventh_power", Expr::cell(1) - seventh);
+        }
+    }
+
+    #[test]
+    fn test_prove_air_selects_blowup_for_constraint_degree() {
+        use crate::air::{min_blowup_for_degree, TraceLayout};
+
+        assert_eq!([2, 3, 4, 5, 7, 9].map(min_blowup_for_degree), [2, 4, 4, 8, 8, 16]);
+        let layout = TraceLayout::select(12, 7, 4).unwrap();
+        assert_eq!((layout.trace_length, layout.blowup_factor, layout.lde_height()), (16, 8, 128));
+        assert!(matches!(TraceLayout::select(1 << 26, 7, 2), Err(ZKPError::ConfigError(_))));
+
+        let mut witness = ExecutionTrace::new(2, 12);
+        for row in 0..12 {
+            let x = BabyBearField::new(row as u64 + 2);
+            witness.set(row, 0, x);
+            witness.set(row, 1, x.pow(7));
+        }
+        let air = SeventhPowerAir;
+        let err = CustomStarkProver::new(8, 4).prove_air(&air, witness.clone(), Vec::new()).unwrap_err();
+        assert!(matches!(&err, ZKPError::ConfigError(message) if message.contains("'seventh_power' has degree 7")));
+
+        let params = StarkParams::new(8, 4).for_degree(air.constraint_system(16).max_degree());
+        assert_eq!(params.blowup_factor, 8);
+        let proof = CustomStarkProver::new(params.num_queries, params.blowup_factor).prove_air(&air, witness, Vec::new()).unwrap();
+        let verifier = CustomStarkVerifier::new(8, 4).with_params(&params);
+        assert!(verifier.check_air_proof(&proof, &air).is_ok());
+    }
+
+    #[test]
+    fn test_scorer_and_trace_decay_over_the_same_span() {
+        use crate::fixed_point::Q16;
+        use crate::hierarchical_scoring::HierarchicalScorer;
+
+        let decay = DecayParameters {
+            base_decay_rate: 500,
+            multiplicative_factor: Q16::ZERO,
+            min_threshold: 0,
+            category_decay: Vec::new(),
+        };
+        let scores = [(RepIDCategory::Governance, 400), (RepIDCategory::Technical, 700)];
+        // Unit weights and no synergies reduce the scorer to the trace's decayed sum
+        let mut scorer = HierarchicalScorer::new().with_decay(decay.clone());
+        scorer.synergy_matrix.clear();
+        for (category, _) in &scores {
+            scorer.set_category_weight(category.clone(), Q16::ONE);
+        }
+
+        let prover = CustomStarkProver::new(8, 4);
+        for (as_of, time_window) in [(30 * 86400, 10 * 86400), (3 * 86400, 10 * 86400)] {
+            let expected = scorer.calculate_score(&scores, as_of, time_window, None);
+            let witness = prover.threshold_section(&scores, 1, time_window, as_of, Some(&decay)).unwrap();
+            assert_eq!(witness.final_score, expected.net_score);
+        }
+    }
+
+    #[test]
+    fn test_threshold_circuits_require_a_met_threshold() {
+        let mut prover = CustomStarkProver::new(8, 4);
+        let scores = [(RepIDCategory::Governance, 40), (RepIDCategory::Technical, 30)];
+        assert!(matches!(prover.prove_threshold_verification(&scores, 100, 3600, 0, None), Err(ZKPError::InvalidInput(_))));
+
+        // A trace honestly recording the miss still fails the rebuilt constraint system
+        let timestamp = prover.evaluation_instant(0);
+        let witness = prover.threshold_section(&scores, 100, 3600, timestamp, None).unwrap();
+        assert!(!witness.meets_threshold);
+        let shape = CircuitShape::Threshold { scores: witness.shape(), flagged: 0 };
+        let public_inputs = prover
+            .bound_public_inputs(vec![BabyBearField::new(100), BabyBearField::new(3600), BabyBearField::new(timestamp)], &shape)
+            .unwrap();
+        let circuit = shape.build(&public_inputs).unwrap();
+        let mut trace = circuit.trace();
+        witness.fill(&mut trace);
+        circuit.fill_public_inputs(&mut trace, &public_inputs);
+        assert!(circuit.system.check(&trace).is_err());
+    }
+
+    /// Raw values including non-canonical representatives
+    fn raw_strategy() -> impl Strategy<Value = BabyBearField> {
+        any::<u64>().prop_map(BabyBearField)
     }
-}
\ No newline at end of file
+
+    proptest! {
+        #[test]
+        fn test_ring_laws(a in field_element_strategy(), b in field_element_strategy(), c in field_element_strategy()) {
+            prop_assert_eq!((a + b) + c, a + (b + c));
+            prop_assert_eq!((a * b) * c, a * (b * c));
+            prop_assert_eq!(a + b, b + a);
+            prop_assert_eq!(a * b, b * a);
+            prop_assert_eq!(a * (b + c), a * b + a * c);
+            prop_assert_eq!(a + (-a), BabyBearField::ZERO);
+            prop_assert_eq!(a - b, a + (-b));
+            if a != BabyBearField::ZERO {
+                prop_assert_eq!(a * a.inverse().unwrap(), BabyBearField::ONE);
+            }
+        }
+
+        #[test]
+        fn test_results_are_canonical(a in raw_strategy(), b in raw_strategy()) {
+            for result in [a + b, a - b, a * b, -a, a.canonicalize()] {
+                prop_assert!(result.is_canonical());
+            }
+            prop_assert_eq!(a - b, a.canonicalize() - b.canonicalize());
+            prop_assert_eq!(a + b, a.canonicalize() + b.canonicalize());
+            prop_assert_eq!(-a, -a.canonicalize());
+            prop_assert_eq!(a.inverse().is_none(), a.canonicalize() == BabyBearField::ZERO);
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! Per-Category Decay
+//!
+//! Category-specific decay rates, half-lives and floors layered over the base
+//! `DecayParameters`, shared by the scorer and the proving traces
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fixed_point::Q16;
+use crate::{DecayParameters, RepIDCategory, Result, ZKPError};
+
+const SECONDS_PER_DAY: u64 = 86400;
+
+/// Bits of a half-life resolved below one whole halving
+const HALVING_FRACTION_BITS: usize = 20;
+/// `2^(-2^-i)` for i in 1..=HALVING_FRACTION_BITS, with 32 fractional bits
+const HALVING_FRACTIONS: [u64; HALVING_FRACTION_BITS] = [
+    0xb504_f334, 0xd744_fccb, 0xeac0_c6e8, 0xf525_7d15, 0xfa83_b2db, 0xfd3e_0c0d, 0xfe9e_115c, 0xff4e_cb59,
+    0xffa7_5652, 0xffd3_a752, 0xffe9_d2b3, 0xfff4_e91c, 0xfffa_747f, 0xfffd_3a3b, 0xfffe_9d1d, 0xffff_4e8e,
+    0xffff_a747, 0xffff_d3a3, 0xffff_e9d2, 0xffff_f4e9,
+];
+
+/// `value * 0.5^(elapsed_secs / half_life_secs)`, rounded down
+///
+/// Whole half-lives are shifts and the remainder multiplies in one table
+/// entry per set bit of its binary fraction, so every platform computes the
+/// same result without floating point.
+fn halve_over(value: u64, elapsed_secs: u64, half_life_secs: u64) -> u64 {
+    let halvings = elapsed_secs / half_life_secs;
+    if halvings >= u64::BITS as u64 {
+        return 0;
+    }
+    let fraction = (((elapsed_secs % half_life_secs) as u128) << HALVING_FRACTION_BITS) / half_life_secs as u128;
+    let mut factor: u128 = 1 << 32;
+    for (i, step) in HALVING_FRACTIONS.iter().enumerate() {
+        if (fraction >> (HALVING_FRACTION_BITS - 1 - i)) & 1 == 1 {
+            factor = (factor * *step as u128) >> 32;
+        }
+    }
+    ((value as u128 * factor) >> 32 >> halvings) as u64
+}
+
+/// Seconds of decay for scores covering `[as_of - time_window, as_of]`
+///
+/// The window is clamped at the epoch, so the scorer and the proving traces
+/// decay over the same span.
+pub fn decay_span(as_of: u64, time_window: u64) -> u64 {
+    time_window.min(as_of)
+}
+
+/// Decay schedule for a single category
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryDecay {
+    pub category: RepIDCategory,
+    /// Linear decay rate in basis points per day (used without a half-life)
+    pub decay_rate: u16,
+    /// Exponential half-life in seconds, replacing the linear rate when set
+    #[serde(default)]
+    pub half_life_secs: Option<u64>,
+    /// Score below which the category stops decaying
+    #[serde(default)]
+    pub floor: u32,
+}
+
+impl CategoryDecay {
+    /// Linear decay at `decay_rate` basis points per day
+    pub fn linear(category: RepIDCategory, decay_rate: u16) -> Self {
+        Self {
+            category,
+            decay_rate,
+            half_life_secs: None,
+            floor: 0,
+        }
+    }
+
+    /// Exponential decay halving every `half_life_secs`
+    pub fn half_life(category: RepIDCategory, half_life_secs: u64) -> Self {
+        Self {
+            category,
+            decay_rate: 0,
+            half_life_secs: Some(half_life_secs),
+            floor: 0,
+        }
+    }
+
+    pub fn with_floor(mut self, floor: u32) -> Self {
+        self.floor = floor;
+        self
+    }
+
+    /// Retained fraction and floor after `elapsed_secs` of decay
+    ///
+    /// The retention is a `Q16` fraction so the traces can prove it with
+    /// products that stay below the field modulus; a linear loss is rounded
+    /// down, so whole-percentage rates decay whole scores exactly.
+    pub fn factor(&self, elapsed_secs: u64) -> DecayFactor {
+        let one = Q16::ONE.raw() as u64;
+        let retention = match self.half_life_secs {
+            Some(half_life) if half_life > 0 => halve_over(one, elapsed_secs, half_life),
+            _ => {
+                let lost = one as u128 * self.decay_rate as u128 * elapsed_secs as u128 / (10_000 * SECONDS_PER_DAY as u128);
+                one - lost.min(one as u128) as u64
+            }
+        };
+        DecayFactor {
+            retention: Q16(retention as i64),
+            floor: self.floor,
+        }
+    }
+
+    /// Score remaining after `elapsed_secs` of decay
+    pub fn apply(&self, score: u32, elapsed_secs: u64) -> u32 {
+        self.factor(elapsed_secs).apply(score)
+    }
+}
+
+/// One category's decay over a fixed span: a retained fraction and a floor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecayFactor {
+    /// Fraction of the score kept, between zero and one
+    pub retention: Q16,
+    /// Score below which the category stops decaying
+    pub floor: u32,
+}
+
+impl DecayFactor {
+    /// `score * retention` rounded down, raised to the floor but never above `score`
+    pub fn apply(&self, score: u32) -> u32 {
+        let decayed = (score as u64 * self.retention.raw() as u64) >> Q16::FRAC_BITS;
+        (decayed as u32).max(self.floor.min(score))
+    }
+}
+
+impl DecayParameters {
+    /// Override the decay schedule of one category
+    pub fn with_category_decay(mut self, decay: CategoryDecay) -> Self {
+        self.category_decay.retain(|d| d.category != decay.category);
+        self.category_decay.push(decay);
+        self
+    }
+
+    /// Schedule for `category`, falling back to the base linear rate
+    pub fn decay_for(&self, category: &RepIDCategory) -> CategoryDecay {
+        self.category_decay
+            .iter()
+            .find(|d| d.category == *category)
+            .cloned()
+            .unwrap_or_else(|| CategoryDecay::linear(category.clone(), self.base_decay_rate))
+    }
+
+    /// Decay factor of each scored category over `elapsed_secs`
+    pub fn decay_factors(&self, scores: &[(RepIDCategory, u32)], elapsed_secs: u64) -> Vec<DecayFactor> {
+        scores.iter().map(|(category, _)| self.decay_for(category).factor(elapsed_secs)).collect()
+    }
+
+    /// Sum of the decayed scores raised to `min_threshold`, as threshold circuits aggregate them
+    pub fn decayed_total(&self, scores: &[(RepIDCategory, u32)], elapsed_secs: u64) -> u64 {
+        let total: u64 = self
+            .decay_factors(scores, elapsed_secs)
+            .iter()
+            .zip(scores)
+            .map(|(factor, (_, score))| factor.apply(*score) as u64)
+            .sum();
+        total.max(self.min_threshold as u64)
+    }
+
+    /// Apply each category's schedule to its score
+    pub fn decay_scores(&self, scores: &[(RepIDCategory, u32)], elapsed_secs: u64) -> Vec<(RepIDCategory, u32)> {
+        scores
+            .iter()
+            .map(|(category, score)| (category.clone(), self.decay_for(category).apply(*score, elapsed_secs)))
+            .collect()
+    }
+}
+
+/// A decay curve over fixed-point scores
+///
+/// Custom models must declare the provable shape the circuit evaluates in their place.
+pub trait DecayModel: Debug + Send + Sync {
+    fn apply(&self, score: Q16, elapsed_secs: u64) -> Q16;
+
+    /// Whitelisted shape this model compiles to
+    fn shape(&self) -> DecayShape;
+}
+
+/// Decay shapes the proving traces can evaluate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum DecayShape {
+    Constant,
+    /// Linear decay in basis points per day
+    Linear { decay_rate: u16 },
+    /// Exponential decay halving every `half_life_secs`
+    HalfLife { half_life_secs: u64 },
+}
+
+impl DecayShape {
+    /// Per-category schedule the traces prove
+    pub fn to_category_decay(self, category: RepIDCategory) -> CategoryDecay {
+        match self {
+            DecayShape::Constant => CategoryDecay::linear(category, 0),
+            DecayShape::Linear { decay_rate } => CategoryDecay::linear(category, decay_rate),
+            DecayShape::HalfLife { half_life_secs } => CategoryDecay::half_life(category, half_life_secs),
+        }
+    }
+}
+
+impl DecayModel for DecayShape {
+    fn apply(&self, score: Q16, elapsed_secs: u64) -> Q16 {
+        match *self {
+            DecayShape::Constant => score,
+            DecayShape::Linear { decay_rate } => {
+                let decay_amount = score.raw() as i128 * decay_rate as i128 * elapsed_secs as i128
+                    / (10_000 * SECONDS_PER_DAY as i128);
+                Q16((score.raw() as i128 - decay_amount).max(0) as i64)
+            }
+            DecayShape::HalfLife { half_life_secs } if half_life_secs > 0 => {
+                let magnitude = halve_over(score.raw().unsigned_abs(), elapsed_secs, half_life_secs) as i64;
+                Q16(score.raw().signum() * magnitude)
+            }
+            DecayShape::HalfLife { .. } => score,
+        }
+    }
+
+    fn shape(&self) -> DecayShape {
+        *self
+    }
+}
+
+/// Named decay models, admitted only when they agree with their declared shape
+#[derive(Debug, Clone, Default)]
+pub struct DecayRegistry {
+    models: Vec<(String, Arc<dyn DecayModel>)>,
+}
+
+impl DecayRegistry {
+    /// Scores and elapsed times on which a model must track its shape
+    const CONFORMANCE_SCORES: [i64; 5] = [0, 1, 100, 1_000, 100_000];
+    const CONFORMANCE_ELAPSED: [u64; 5] = [0, 3_600, SECONDS_PER_DAY, 30 * SECONDS_PER_DAY, 365 * SECONDS_PER_DAY];
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `model` under `name`, rejecting models the circuit could not reproduce
+    pub fn register(&mut self, name: &str, model: Arc<dyn DecayModel>) -> Result<()> {
+        let shape = model.shape();
+        for score in Self::CONFORMANCE_SCORES.map(Q16::from_int) {
+            for elapsed in Self::CONFORMANCE_ELAPSED {
+                let (custom, provable) = (model.apply(score, elapsed), shape.apply(score, elapsed));
+                if (custom - provable).raw().abs() > Q16::ONE.raw() {
+                    return Err(ZKPError::InvalidInput(format!(
+                        "Decay model '{}' diverges from its {:?} shape at score {} after {}s",
+                        name,
+                        shape,
+                        score.to_int(),
+                        elapsed
+                    )));
+                }
+            }
+        }
+        self.models.retain(|(n, _)| n != name);
+        self.models.push((name.to_string(), model));
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn DecayModel>> {
+        self.models.iter().find(|(n, _)| n == name).map(|(_, model)| model.clone())
+    }
+
+    /// Compile a registered model into the schedule proven for `category`
+    pub fn compile(&self, name: &str, category: RepIDCategory) -> Result<CategoryDecay> {
+        self.get(name)
+            .map(|model| model.shape().to_category_decay(category))
+            .ok_or_else(|| ZKPError::ConfigError(format!("Unknown decay model '{}'", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories_decay_on_their_own_schedule() {
+        let decay = DecayParameters {
+            base_decay_rate: 100,
+            multiplicative_factor: Q16::ONE,
+            min_threshold: 0,
+            category_decay: Vec::new(),
+        }
+        .with_category_decay(CategoryDecay::linear(RepIDCategory::Governance, 10).with_floor(60))
+        .with_category_decay(CategoryDecay::half_life(RepIDCategory::DeFi, 10 * 86400));
+
+        let scores = [
+            (RepIDCategory::Governance, 100),
+            (RepIDCategory::DeFi, 100),
+            (RepIDCategory::Community, 100),
+        ];
+        let ten_days = decay.decay_scores(&scores, 10 * 86400);
+        assert_eq!(
+            ten_days,
+            vec![(RepIDCategory::Governance, 99), (RepIDCategory::DeFi, 50), (RepIDCategory::Community, 90)]
+        );
+
+        // Governance stops at its floor; a floor never raises a lower score
+        let long = decay.decay_scores(&scores, 10_000 * 86400);
+        assert_eq!(long[0].1, 60);
+        assert_eq!(decay.decay_for(&RepIDCategory::Governance).apply(40, 10_000 * 86400), 40);
+
+        // Half-lives are exact integer arithmetic: whole halvings plus the fraction table
+        let half_life = CategoryDecay::half_life(RepIDCategory::DeFi, 4);
+        assert_eq!([0, 2, 4, 12, 1_000].map(|t| half_life.apply(1_000_000, t)), [1_000_000, 707_092, 500_000, 125_000, 0]);
+        assert_eq!(half_life.factor(2).retention, Q16(46_340));
+        let shape = DecayShape::HalfLife { half_life_secs: 4 };
+        assert_eq!(shape.apply(Q16::from_int(-8), 8), Q16::from_int(-2));
+    }
+
+    #[derive(Debug)]
+    struct Quarterly;
+
+    impl DecayModel for Quarterly {
+        /// Loses a quarter of its value every 30 days, expressed as a half-life
+        fn apply(&self, score: Q16, elapsed_secs: u64) -> Q16 {
+            Q16((score.raw() as f64 * 0.75f64.powf(elapsed_secs as f64 / (30.0 * 86400.0))) as i64)
+        }
+
+        fn shape(&self) -> DecayShape {
+            DecayShape::HalfLife { half_life_secs: (30.0 * 86400.0 * 0.5f64.ln() / 0.75f64.ln()) as u64 }
+        }
+    }
+
+    #[derive(Debug)]
+    struct Cliff;
+
+    impl DecayModel for Cliff {
+        fn apply(&self, score: Q16, elapsed_secs: u64) -> Q16 {
+            if elapsed_secs > 86400 {
+                Q16::ZERO
+            } else {
+                score
+            }
+        }
+
+        fn shape(&self) -> DecayShape {
+            DecayShape::Constant
+        }
+    }
+
+    #[test]
+    fn test_custom_decay_models_compile_to_provable_shapes() {
+        let linear = DecayShape::Linear { decay_rate: 100 };
+        assert_eq!(linear.apply(Q16::from_int(100), 10 * 86400), Q16::from_int(90));
+
+        let mut registry = DecayRegistry::new();
+        registry.register("quarterly", Arc::new(Quarterly)).unwrap();
+        assert!(registry.register("cliff", Arc::new(Cliff)).is_err());
+        assert!(registry.compile("cliff", RepIDCategory::DeFi).is_err());
+
+        let schedule = registry.compile("quarterly", RepIDCategory::DeFi).unwrap();
+        assert!((749..=750).contains(&schedule.apply(1_000, 30 * 86400)));
+    }
+}
@@ -0,0 +1,138 @@
+//! Hardened Proof Decoding
+//!
+//! Strict, length-limited decoders for untrusted proof bytes, so a malicious
+//! blob cannot force large allocations or smuggle non-canonical values
+
+use serde::de::DeserializeOwned;
+
+use crate::custom_stark::{BabyBearField, FriProof, MerkleOpening, QueryResponse, StarkProof};
+use crate::limits::ProofLimits;
+use crate::wire;
+use crate::{Result, ZKPError};
+
+/// Wire decoding under a hard byte limit
+fn decode_bounded<T: DeserializeOwned>(bytes: &[u8], limits: &ProofLimits) -> Result<T> {
+    limits.check_proof_bytes(bytes.len())?;
+
+    wire::from_bytes(bytes)
+}
+
+fn check_bound(limit: &str, actual: usize, max: usize) -> Result<()> {
+    ProofLimits::check(limit, actual as u64, max as u64)
+}
+
+fn check_canonical(what: &str, elements: &[BabyBearField]) -> Result<()> {
+    if !elements.iter().all(BabyBearField::is_canonical) {
+        return Err(ZKPError::SerializationError(format!("Non-canonical field element in {}", what)));
+    }
+    Ok(())
+}
+
+/// Validate a decoded FRI proof against the limits
+pub fn validate_fri_proof(fri_proof: &FriProof, limits: &ProofLimits) -> Result<()> {
+    check_bound("fri_commitments", fri_proof.commitments.len(), limits.max_commitments)?;
+    check_bound("final_poly_len", fri_proof.final_poly.len(), limits.max_final_poly_len)?;
+    check_canonical("final polynomial", &fri_proof.final_poly)
+}
+
+/// Validate a decoded Merkle opening against the limits
+pub fn validate_opening(opening: &MerkleOpening, limits: &ProofLimits) -> Result<()> {
+    limits.check_auth_path_depth(opening.auth_path.len())?;
+    check_canonical("opened values", &opening.values)
+}
+
+/// Validate a decoded query response against the limits
+pub fn validate_query_response(query: &QueryResponse, limits: &ProofLimits) -> Result<()> {
+    check_bound("fri_commitments", query.fri_layers.len(), limits.max_commitments)?;
+    for opening in query.rows.iter().chain([&query.quotient]).chain(&query.fri_layers) {
+        validate_opening(opening, limits)?;
+    }
+
+    let depth = query.quotient.auth_path.len();
+    if depth < usize::BITS as usize && query.position >> depth != 0 {
+        return Err(ZKPError::SerializationError(format!(
+            "Query position {} outside domain of depth {}",
+            query.position, depth
+        )));
+    }
+    Ok(())
+}
+
+/// Validate a decoded STARK proof against the limits
+pub fn validate_stark_proof(proof: &StarkProof, limits: &ProofLimits) -> Result<()> {
+    check_bound("queries", proof.queries.len(), limits.max_queries)?;
+    check_bound("public_inputs", proof.public_inputs.len(), limits.max_public_inputs)?;
+    check_canonical("public inputs", &proof.public_inputs)?;
+    check_canonical("public input digest", &proof.public_inputs_digest)?;
+    validate_fri_proof(&proof.fri_proof, limits)?;
+
+    for query in &proof.queries {
+        validate_query_response(query, limits)?;
+    }
+    Ok(())
+}
+
+/// Strictly decode an untrusted `StarkProof`
+pub fn decode_stark_proof(bytes: &[u8], limits: &ProofLimits) -> Result<StarkProof> {
+    let proof: StarkProof = decode_bounded(bytes, limits)?;
+    validate_stark_proof(&proof, limits)?;
+    Ok(proof)
+}
+
+/// Strictly decode an untrusted `FriProof`
+pub fn decode_fri_proof(bytes: &[u8], limits: &ProofLimits) -> Result<FriProof> {
+    let fri_proof: FriProof = decode_bounded(bytes, limits)?;
+    validate_fri_proof(&fri_proof, limits)?;
+    Ok(fri_proof)
+}
+
+/// Strictly decode an untrusted `QueryResponse`
+pub fn decode_query_response(bytes: &[u8], limits: &ProofLimits) -> Result<QueryResponse> {
+    let query: QueryResponse = decode_bounded(bytes, limits)?;
+    validate_query_response(&query, limits)?;
+    Ok(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_huge_length_prefix_without_allocating() {
+        // A FriProof claiming 2^60 commitments in a 16-byte blob
+        let mut blob = (1u64 << 60).to_le_bytes().to_vec();
+        blob.extend_from_slice(&[0u8; 8]);
+        assert!(decode_fri_proof(&blob, &ProofLimits::default()).is_err());
+    }
+
+    #[test]
+    fn test_enforces_structural_limits() {
+        let fri_proof = FriProof {
+            commitments: vec![[0u8; 32]; 4],
+            final_poly: vec![BabyBearField::ONE; 4],
+            pow_nonce: 7,
+        };
+        let bytes = crate::wire::to_bytes(&fri_proof).unwrap();
+        assert!(decode_fri_proof(&bytes, &ProofLimits::default()).is_ok());
+
+        let tight = ProofLimits { max_commitments: 3, ..ProofLimits::default() };
+        assert!(matches!(decode_fri_proof(&bytes, &tight), Err(ZKPError::LimitExceeded { .. })));
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(decode_fri_proof(&trailing, &ProofLimits::default()).is_err());
+
+        let opening = |values: Vec<BabyBearField>| MerkleOpening { values, auth_path: vec![[0u8; 32]; 3] };
+        let query = QueryResponse {
+            position: 5,
+            rows: vec![opening(vec![BabyBearField::ONE; 2])],
+            quotient: opening(vec![BabyBearField::ONE]),
+            fri_layers: Vec::new(),
+        };
+        assert!(decode_query_response(&crate::wire::to_bytes(&query).unwrap(), &ProofLimits::default()).is_ok());
+        let outside = QueryResponse { position: 9, ..query.clone() };
+        assert!(decode_query_response(&crate::wire::to_bytes(&outside).unwrap(), &ProofLimits::default()).is_err());
+        let non_canonical = QueryResponse { quotient: opening(vec![BabyBearField(BabyBearField::MODULUS)]), ..query };
+        assert!(decode_query_response(&crate::wire::to_bytes(&non_canonical).unwrap(), &ProofLimits::default()).is_err());
+    }
+}
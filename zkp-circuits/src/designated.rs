@@ -0,0 +1,153 @@
+//! Designated-Verifier Proofs
+//!
+//! Threshold proofs that convince only one relying party. The circuit proves
+//! "the scores clear the threshold, or the prover knows the secret behind the
+//! designated verifier's public key". An honest prover takes the first
+//! branch; the verifier, holding the secret, can produce an indistinguishable
+//! proof for any wallet and any scores through the second. A proof passed on
+//! by the verifier therefore shows a third party nothing, so received proofs
+//! cannot be resold.
+//!
+//! Keys are Poseidon2 preimages so the circuit only needs the hash gadget.
+//! Public keys are single field elements as elsewhere in the crate, which caps
+//! the work of finding some preimage, and with it a forgery, at about 2^31
+//! hash evaluations.
+
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2;
+use crate::{RepIDProof, Result, ZKPError, F};
+
+/// Operation type of designated-verifier threshold proofs
+pub const DESIGNATED_THRESHOLD_OPERATION: &str = "designated_threshold_verification";
+
+/// Field elements in a verifier secret
+pub const SECRET_ELEMENTS: usize = 4;
+
+/// Public key a designated proof is addressed to: Poseidon2 of the verifier secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DesignatedVerifierPublicKey(pub F);
+
+/// Verification secret of a designated verifier
+#[derive(Clone)]
+pub struct DesignatedVerifierKey {
+    secret: [F; SECRET_ELEMENTS],
+}
+
+impl std::fmt::Debug for DesignatedVerifierKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DesignatedVerifierKey")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DesignatedVerifierKey {
+    /// Key from 32 bytes of caller-held key material
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self { secret: seed_elements(&seed) }
+    }
+
+    /// Fresh key from `rng`
+    #[cfg(feature = "scoring")]
+    pub fn generate(rng: &dyn crate::entropy::RngProvider) -> Result<Self> {
+        crate::entropy::seed(rng).map(Self::from_seed)
+    }
+
+    pub fn public_key(&self) -> DesignatedVerifierPublicKey {
+        DesignatedVerifierPublicKey(poseidon2::hash_elements(&self.secret))
+    }
+
+    /// Circuit witness of the verifier branch
+    pub fn to_field_elements(&self) -> Vec<F> {
+        self.secret.to_vec()
+    }
+}
+
+/// Field elements from 32 bytes, for secrets and the honest prover's filler
+pub fn seed_elements(seed: &[u8; 32]) -> [F; SECRET_ELEMENTS] {
+    std::array::from_fn(|i| F::from_bytes(seed[8 * i..8 * (i + 1)].try_into().expect("8-byte chunk")))
+}
+
+/// Which branch of the designated statement a proof takes
+#[derive(Debug, Clone)]
+pub enum Designation {
+    /// Prove the threshold, addressed to this verifier
+    To(DesignatedVerifierPublicKey),
+    /// Simulate with the verifier's own secret; the scores need not clear the threshold
+    Simulated(DesignatedVerifierKey),
+}
+
+impl Designation {
+    pub fn public_key(&self) -> DesignatedVerifierPublicKey {
+        match self {
+            Designation::To(public_key) => *public_key,
+            Designation::Simulated(key) => key.public_key(),
+        }
+    }
+
+    pub fn trapdoor(&self) -> Option<&DesignatedVerifierKey> {
+        match self {
+            Designation::To(_) => None,
+            Designation::Simulated(key) => Some(key),
+        }
+    }
+}
+
+/// Check a designated proof is addressed to `verifier`
+///
+/// Any other verifier must reject it: it cannot tell a real proof from one
+/// the addressee simulated.
+pub fn check_designation(proof: &RepIDProof, verifier: &DesignatedVerifierPublicKey) -> Result<()> {
+    if proof.metadata.operation_type != DESIGNATED_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs are not designated to a verifier",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(2) {
+        Some(public_key) if *public_key == verifier.0 => Ok(()),
+        Some(_) => Err(ZKPError::VerificationError("Proof is designated to a different verifier".to_string())),
+        None => Err(ZKPError::MalformedProof("Designated proof needs 3 public inputs".to_string())),
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_verifier_can_simulate_its_own_proofs() {
+        let request = ThresholdVerificationRequest {
+            threshold: 100,
+            categories: vec![RepIDCategory::Community, RepIDCategory::DeFi],
+            time_window: 86400,
+            as_of: 1_700_000_000,
+            decay_params: None,
+        };
+        let verifier = DesignatedVerifierKey::from_seed([3; 32]);
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+
+        let designation = Designation::To(verifier.public_key());
+        let scores = [(RepIDCategory::Community, 70), (RepIDCategory::DeFi, 60)];
+        let real = zkp_system.prove_designated_threshold_verification(&request, &scores, &designation, "0xtest").unwrap();
+        assert!(real.meets_threshold);
+        assert!(zkp_system.verify_proof(&real.proof, Some(&request)).unwrap());
+        assert!(check_designation(&real.proof, &verifier.public_key()).is_ok());
+        let other = DesignatedVerifierKey::from_seed([4; 32]).public_key();
+        assert!(check_designation(&real.proof, &other).is_err());
+
+        // Without the secret, scores below the threshold cannot be proven
+        let low = [(RepIDCategory::Community, 10), (RepIDCategory::DeFi, 5)];
+        assert!(zkp_system.prove_designated_threshold_verification(&request, &low, &designation, "0xtest").is_err());
+
+        // With it, the verifier makes a proof that verifies just the same
+        let simulated = zkp_system
+            .prove_designated_threshold_verification(&request, &low, &Designation::Simulated(verifier.clone()), "0xother")
+            .unwrap();
+        assert!(!simulated.meets_threshold);
+        assert!(zkp_system.verify_proof(&simulated.proof, Some(&request)).unwrap());
+        assert_eq!(simulated.proof.public_inputs, real.proof.public_inputs);
+    }
+}
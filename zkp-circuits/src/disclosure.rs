@@ -0,0 +1,323 @@
+//! Disclosure Policies
+//!
+//! Machine-readable statements of what a relying party needs proven: required
+//! predicates, attestation freshness, minimum security and accepted issuers.
+//! Provers turn a policy into the proofs that satisfy it with
+//! `RepIDZKPSystem::prove_for_policy`; relying parties check the resulting
+//! presentation with `check_presentation`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::category_count::{self, CATEGORY_COUNT_OPERATION};
+use crate::freshness::{self, FRESH_THRESHOLD_OPERATION};
+use crate::issuance::{self, CosignedAttestation, IssuancePolicy, IssuerKey, COSIGNED_THRESHOLD_OPERATION};
+use crate::policy::VerifyPolicy;
+use crate::{RepIDCategory, RepIDProof, RepIDZKPSystem, Result, SecurityLevel, ThresholdVerificationRequest, ZKPError};
+
+/// Statement a relying party requires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum DisclosurePredicate {
+    /// Summed score over `categories` within `time_window` reaches `threshold`
+    Threshold {
+        threshold: u32,
+        categories: Vec<RepIDCategory>,
+        time_window: u64,
+    },
+    /// At least `k` categories each reach `min_per_category`
+    CategoryCount { min_per_category: u32, k: u32 },
+}
+
+/// Every backing attestation is at most `max_age_secs` old, in a proof made within `max_skew_secs`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FreshnessRequirement {
+    pub max_age_secs: u64,
+    pub max_skew_secs: u64,
+}
+
+/// Backing attestations carry signatures from `required` of `issuers`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssuerRequirement {
+    pub issuers: Vec<IssuerKey>,
+    pub required: u32,
+}
+
+/// Published requirements of one relying party
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisclosurePolicy {
+    pub name: String,
+    pub predicates: Vec<DisclosurePredicate>,
+    pub min_security: SecurityLevel,
+    #[serde(default)]
+    pub freshness: Option<FreshnessRequirement>,
+    #[serde(default)]
+    pub issuers: Option<IssuerRequirement>,
+}
+
+impl DisclosurePolicy {
+    pub fn new(name: &str, min_security: SecurityLevel) -> Self {
+        Self {
+            name: name.to_string(),
+            predicates: Vec::new(),
+            min_security,
+            freshness: None,
+            issuers: None,
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: u32, categories: &[RepIDCategory], time_window: u64) -> Self {
+        self.predicates.push(DisclosurePredicate::Threshold {
+            threshold,
+            categories: categories.to_vec(),
+            time_window,
+        });
+        self
+    }
+
+    pub fn with_category_count(mut self, min_per_category: u32, k: u32) -> Self {
+        self.predicates.push(DisclosurePredicate::CategoryCount { min_per_category, k });
+        self
+    }
+
+    pub fn with_freshness(mut self, max_age_secs: u64, max_skew_secs: u64) -> Self {
+        self.freshness = Some(FreshnessRequirement { max_age_secs, max_skew_secs });
+        self
+    }
+
+    pub fn with_issuers(mut self, issuers: Vec<IssuerKey>, required: u32) -> Self {
+        self.issuers = Some(IssuerRequirement { issuers, required });
+        self
+    }
+
+    /// Blake3 hash of the canonical JSON encoding, carried by presentations
+    pub fn hash(&self) -> [u8; 32] {
+        let canonical = serde_json::to_vec(self).expect("disclosure policy serializes");
+        *blake3::hash(&canonical).as_bytes()
+    }
+
+    /// Issuance policies the issuer requirement implies for `categories`
+    pub fn issuance_policies(&self, categories: &[RepIDCategory]) -> Result<Vec<IssuancePolicy>> {
+        let Some(requirement) = &self.issuers else {
+            return Ok(Vec::new());
+        };
+        categories
+            .iter()
+            .map(|category| IssuancePolicy::new(category.clone(), requirement.required, requirement.issuers.clone()))
+            .collect()
+    }
+
+    /// Proofs satisfying the policy, in presentation order
+    ///
+    /// Threshold predicates are proven over co-signed attestations when issuers
+    /// are restricted and over fresh attestations when freshness is required,
+    /// one proof each; otherwise over plain scores. Category counts cannot be
+    /// backed by attestations, so they are rejected under either requirement.
+    pub fn plan(&self) -> Result<Vec<PlannedProof>> {
+        if self.predicates.is_empty() {
+            return Err(ZKPError::ConfigError(format!("disclosure policy '{}' has no predicates", self.name)));
+        }
+        let attested = self.issuers.is_some() || self.freshness.is_some();
+        let mut planned = Vec::new();
+        for predicate in &self.predicates {
+            match predicate {
+                DisclosurePredicate::Threshold { threshold, categories, time_window } => {
+                    let request = ThresholdVerificationRequest::builder()
+                        .with_threshold(*threshold)
+                        .with_categories(categories.iter().cloned())
+                        .with_time_window(*time_window)
+                        .build()?;
+                    if self.issuers.is_some() {
+                        planned.push(PlannedProof::Cosigned(request.clone()));
+                    }
+                    if self.freshness.is_some() {
+                        planned.push(PlannedProof::Fresh(request.clone()));
+                    }
+                    if !attested {
+                        planned.push(PlannedProof::Threshold(request));
+                    }
+                }
+                DisclosurePredicate::CategoryCount { .. } if attested => {
+                    return Err(ZKPError::ConfigError(
+                        "category count predicates cannot be backed by attestations".to_string(),
+                    ));
+                }
+                DisclosurePredicate::CategoryCount { min_per_category, k } => {
+                    planned.push(PlannedProof::CategoryCount { min_per_category: *min_per_category, k: *k });
+                }
+            }
+        }
+        Ok(planned)
+    }
+}
+
+/// One proof a policy calls for
+#[derive(Debug, Clone)]
+pub enum PlannedProof {
+    Threshold(ThresholdVerificationRequest),
+    Fresh(ThresholdVerificationRequest),
+    Cosigned(ThresholdVerificationRequest),
+    CategoryCount { min_per_category: u32, k: u32 },
+}
+
+impl PlannedProof {
+    pub fn operation_type(&self) -> &'static str {
+        match self {
+            PlannedProof::Threshold(_) => "threshold_verification",
+            PlannedProof::Fresh(_) => FRESH_THRESHOLD_OPERATION,
+            PlannedProof::Cosigned(_) => COSIGNED_THRESHOLD_OPERATION,
+            PlannedProof::CategoryCount { .. } => CATEGORY_COUNT_OPERATION,
+        }
+    }
+
+    /// Request the proof answers, for threshold proofs
+    pub fn request(&self) -> Option<&ThresholdVerificationRequest> {
+        match self {
+            PlannedProof::Threshold(request) | PlannedProof::Fresh(request) | PlannedProof::Cosigned(request) => Some(request),
+            PlannedProof::CategoryCount { .. } => None,
+        }
+    }
+}
+
+/// Everything a prover may draw on to satisfy a policy
+#[derive(Debug, Clone, Default)]
+pub struct DisclosureWitness {
+    pub wallet_address: String,
+    /// Category scores, for plain threshold and category count proofs
+    pub scores: Vec<(RepIDCategory, u32)>,
+    /// Signed, timestamped attestations, for issuer and freshness requirements
+    pub attestations: Vec<CosignedAttestation>,
+}
+
+impl DisclosureWitness {
+    pub fn new(wallet_address: &str) -> Self {
+        Self {
+            wallet_address: wallet_address.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_scores(mut self, scores: &[(RepIDCategory, u32)]) -> Self {
+        self.scores = scores.to_vec();
+        self
+    }
+
+    pub fn with_attestations(mut self, attestations: Vec<CosignedAttestation>) -> Self {
+        self.attestations = attestations;
+        self
+    }
+}
+
+/// Proofs answering a policy, in `DisclosurePolicy::plan` order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisclosurePresentation {
+    pub policy_hash: [u8; 32],
+    pub proofs: Vec<RepIDProof>,
+}
+
+/// Check a presentation satisfies `policy` at `now`
+///
+/// Every proof must verify under the policy's security level, answer its
+/// planned statement and belong to the same wallet.
+pub fn check_presentation(
+    system: &RepIDZKPSystem,
+    policy: &DisclosurePolicy,
+    presentation: &DisclosurePresentation,
+    now: u64,
+) -> Result<()> {
+    if presentation.policy_hash != policy.hash() {
+        return Err(ZKPError::PolicyViolation(format!("presentation answers a different policy than '{}'", policy.name)));
+    }
+    let planned = policy.plan()?;
+    if presentation.proofs.len() != planned.len() {
+        return Err(ZKPError::PolicyViolation(format!(
+            "policy '{}' needs {} proofs, presentation has {}",
+            policy.name,
+            planned.len(),
+            presentation.proofs.len()
+        )));
+    }
+    if let Some(first) = presentation.proofs.first() {
+        if presentation.proofs.iter().any(|proof| proof.metadata.wallet_hash != first.metadata.wallet_hash) {
+            return Err(ZKPError::PolicyViolation("presentation mixes proofs of different wallets".to_string()));
+        }
+    }
+
+    for (plan, proof) in planned.iter().zip(&presentation.proofs) {
+        let verify_policy = VerifyPolicy::strict(policy.min_security).with_allowed_operations(&[plan.operation_type()]);
+        if !system.verify_proof_with_policy(proof, plan.request(), &verify_policy)? {
+            return Err(ZKPError::VerificationError(format!("{} proof failed verification", plan.operation_type())));
+        }
+        match plan {
+            PlannedProof::Threshold(_) => {}
+            PlannedProof::Cosigned(request) => {
+                issuance::check_policies(proof, &policy.issuance_policies(&request.categories)?)?;
+            }
+            PlannedProof::Fresh(_) => {
+                let freshness = policy.freshness.expect("fresh proofs are only planned under a freshness requirement");
+                freshness::check_bound(proof, freshness.max_age_secs, now, freshness.max_skew_secs)?;
+            }
+            PlannedProof::CategoryCount { min_per_category, k } => {
+                let statement = category_count::proof_statement(proof)?;
+                if statement.min_per_category < *min_per_category || statement.k < *k {
+                    return Err(ZKPError::PolicyViolation(format!(
+                        "proof shows {} categories at {}, policy requires {} at {}",
+                        statement.k, statement.min_per_category, k, min_per_category
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::freshness::AttestedScore;
+    use crate::signer::{InMemorySigner, Signer};
+    use std::sync::Arc;
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_prove_for_policy_selects_circuits() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_clock(Arc::new(FixedClock::new(NOW)));
+
+        // Plain scores answer a policy without attestation requirements
+        let open = DisclosurePolicy::new("forum", SecurityLevel::Fast)
+            .with_threshold(100, &[RepIDCategory::Technical, RepIDCategory::Community], 86_400)
+            .with_category_count(40, 2);
+        let witness = DisclosureWitness::new("0xtest")
+            .with_scores(&[(RepIDCategory::Technical, 60), (RepIDCategory::Community, 50)]);
+        let presentation = zkp_system.prove_for_policy(&open, &witness).unwrap();
+        let operations: Vec<&str> = presentation.proofs.iter().map(|p| p.metadata.operation_type.as_str()).collect();
+        assert_eq!(operations, vec!["threshold_verification", CATEGORY_COUNT_OPERATION]);
+        assert!(check_presentation(&zkp_system, &open, &presentation, NOW).is_ok());
+        assert!(check_presentation(&zkp_system, &open.clone().with_category_count(40, 3), &presentation, NOW).is_err());
+        let stricter = DisclosurePolicy { min_security: SecurityLevel::Standard, ..open.clone() };
+        assert!(matches!(zkp_system.prove_for_policy(&stricter, &witness), Err(ZKPError::ParameterDowngrade(_))));
+
+        // Issuer and freshness requirements switch to attestation-backed circuits
+        let issuers: Vec<InMemorySigner> = (1..=2).map(|seed| InMemorySigner::from_seed([seed; 32])).collect();
+        let keys: Vec<IssuerKey> = issuers.iter().map(|s| s.public_key().unwrap().try_into().unwrap()).collect();
+        let dao = DisclosurePolicy::new("dao", SecurityLevel::Fast)
+            .with_threshold(50, &[RepIDCategory::Governance], 86_400)
+            .with_freshness(30 * 86_400, 600)
+            .with_issuers(keys, 2);
+        let mut attestation = CosignedAttestation::new(
+            "0xtest",
+            AttestedScore { category: RepIDCategory::Governance, score: 80, issued_at: NOW - 86_400 },
+        );
+        attestation.cosign(&issuers[0]).unwrap();
+        assert!(zkp_system.prove_for_policy(&dao, &witness.clone().with_attestations(vec![attestation.clone()])).is_err());
+
+        attestation.cosign(&issuers[1]).unwrap();
+        let presentation = zkp_system.prove_for_policy(&dao, &witness.with_attestations(vec![attestation])).unwrap();
+        let operations: Vec<&str> = presentation.proofs.iter().map(|p| p.metadata.operation_type.as_str()).collect();
+        assert_eq!(operations, vec![COSIGNED_THRESHOLD_OPERATION, FRESH_THRESHOLD_OPERATION]);
+        assert!(check_presentation(&zkp_system, &dao, &presentation, NOW + 60).is_ok());
+        assert!(check_presentation(&zkp_system, &dao, &presentation, NOW + 3_600).is_err());
+        assert!(check_presentation(&zkp_system, &open, &presentation, NOW).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! Evaluation Domains
+//!
+//! Two-adic subgroups and their cosets, plus roots of unity, twiddle factors and
+//! bit-reversal tables computed once per size and shared by every proof in the process
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::custom_stark::BabyBearField;
+use crate::{Result, ZKPError};
+
+/// Largest `k` with 2^k dividing p - 1 (p - 1 = 15 * 2^27)
+pub const TWO_ADICITY: u32 = 27;
+/// Generator of the multiplicative group of BabyBear
+pub const MULTIPLICATIVE_GENERATOR: BabyBearField = BabyBearField(31);
+
+/// Generator of the multiplicative subgroup of order 2^log_size
+pub fn two_adic_generator(log_size: u32) -> Result<BabyBearField> {
+    if log_size > TWO_ADICITY {
+        return Err(ZKPError::InvalidInput(format!(
+            "Domain of size 2^{} exceeds the BabyBear two-adicity 2^{}",
+            log_size, TWO_ADICITY
+        )));
+    }
+    Ok(MULTIPLICATIVE_GENERATOR.pow((BabyBearField::MODULUS - 1) >> log_size))
+}
+
+/// Subgroup of order 2^log_size, or a coset `shift * H` of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwoAdicSubgroup {
+    log_size: u32,
+    generator: BabyBearField,
+    shift: BabyBearField,
+}
+
+impl TwoAdicSubgroup {
+    pub fn new(log_size: u32) -> Result<Self> {
+        Ok(Self {
+            log_size,
+            generator: two_adic_generator(log_size)?,
+            shift: BabyBearField::ONE,
+        })
+    }
+
+    /// Coset `shift * H`; LDEs use `MULTIPLICATIVE_GENERATOR` so it is disjoint from H
+    pub fn coset(&self, shift: BabyBearField) -> Self {
+        Self { shift, ..*self }
+    }
+
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    pub fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    pub fn generator(&self) -> BabyBearField {
+        self.generator
+    }
+
+    pub fn shift(&self) -> BabyBearField {
+        self.shift
+    }
+
+    /// `shift * generator^index`
+    pub fn element(&self, index: usize) -> BabyBearField {
+        self.shift * self.generator.pow((index % self.size()) as u64)
+    }
+
+    /// All elements in natural order
+    pub fn elements(&self) -> Vec<BabyBearField> {
+        let mut elements = Vec::with_capacity(self.size());
+        let mut current = self.shift;
+        for _ in 0..self.size() {
+            elements.push(current);
+            current *= self.generator;
+        }
+        elements
+    }
+
+    /// Vanishing polynomial `x^n - shift^n` evaluated at `x`
+    pub fn vanishing_at(&self, x: BabyBearField) -> BabyBearField {
+        x.exp_power_of_2(self.log_size as usize) - self.shift.exp_power_of_2(self.log_size as usize)
+    }
+
+    pub fn contains(&self, x: BabyBearField) -> bool {
+        self.vanishing_at(x) == BabyBearField::ZERO
+    }
+}
+
+/// Multiplicative subgroup of order 2^log_size with its precomputed tables
+#[derive(Debug, Clone)]
+pub struct EvaluationDomain {
+    log_size: u32,
+    generator: BabyBearField,
+    roots: Vec<BabyBearField>,
+    twiddles: Vec<BabyBearField>,
+    bit_reversal: Vec<u32>,
+}
+
+impl EvaluationDomain {
+    pub fn new(log_size: u32) -> Result<Self> {
+        let subgroup = TwoAdicSubgroup::new(log_size)?;
+        let size = subgroup.size();
+        let generator = subgroup.generator();
+        let roots = subgroup.elements();
+
+        let bit_reversal: Vec<u32> = (0..size as u32)
+            .map(|i| if log_size == 0 { 0 } else { i.reverse_bits() >> (u32::BITS - log_size) })
+            .collect();
+        // Butterfly twiddles for a bit-reversed NTT: first half of the roots, permuted
+        let twiddles = (0..size / 2)
+            .map(|i| roots[(bit_reversal[i] >> 1) as usize])
+            .collect();
+
+        Ok(Self {
+            log_size,
+            generator,
+            roots,
+            twiddles,
+            bit_reversal,
+        })
+    }
+
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    pub fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    /// The underlying subgroup, for building cosets of the same size
+    pub fn subgroup(&self) -> TwoAdicSubgroup {
+        TwoAdicSubgroup {
+            log_size: self.log_size,
+            generator: self.generator,
+            shift: BabyBearField::ONE,
+        }
+    }
+
+    /// Primitive root of unity generating the domain
+    pub fn generator(&self) -> BabyBearField {
+        self.generator
+    }
+
+    /// `generator^index`, wrapping around the domain
+    pub fn element(&self, index: usize) -> BabyBearField {
+        self.roots[index % self.roots.len()]
+    }
+
+    /// All domain elements in natural order
+    pub fn roots(&self) -> &[BabyBearField] {
+        &self.roots
+    }
+
+    /// Butterfly twiddle factors in bit-reversed order
+    pub fn twiddles(&self) -> &[BabyBearField] {
+        &self.twiddles
+    }
+
+    /// Bit-reversed index of every position
+    pub fn bit_reversal(&self) -> &[u32] {
+        &self.bit_reversal
+    }
+
+    /// Reorder `values` into bit-reversed order in place
+    pub fn bit_reverse_permute<T>(&self, values: &mut [T]) {
+        debug_assert_eq!(values.len(), self.size());
+        for (i, &j) in self.bit_reversal.iter().enumerate() {
+            let j = j as usize;
+            if i < j {
+                values.swap(i, j);
+            }
+        }
+    }
+}
+
+/// Process-wide cache of evaluation domains keyed by size
+#[derive(Debug, Default)]
+pub struct DomainCache {
+    domains: Mutex<HashMap<u32, Arc<EvaluationDomain>>>,
+}
+
+impl DomainCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cache shared by every prover in the process
+    pub fn global() -> &'static Self {
+        static CACHE: OnceLock<DomainCache> = OnceLock::new();
+        CACHE.get_or_init(DomainCache::new)
+    }
+
+    /// Domain of `size` elements (a power of two), computed on first use
+    pub fn get(&self, size: usize) -> Result<Arc<EvaluationDomain>> {
+        if !size.is_power_of_two() {
+            return Err(ZKPError::InvalidInput(format!("Domain size {} is not a power of two", size)));
+        }
+        let log_size = size.trailing_zeros();
+        let mut domains = self.domains.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(domain) = domains.get(&log_size) {
+            return Ok(domain.clone());
+        }
+        let domain = Arc::new(EvaluationDomain::new(log_size)?);
+        domains.insert(log_size, domain.clone());
+        Ok(domain)
+    }
+
+    pub fn len(&self) -> usize {
+        self.domains.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.domains.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_tables_and_sharing() {
+        let cache = DomainCache::new();
+        let domain = cache.get(8).unwrap();
+        assert_eq!(domain.generator().pow(8), BabyBearField::ONE);
+        assert_eq!(domain.generator().pow(4), -BabyBearField::ONE);
+        assert_eq!(domain.bit_reversal(), &[0, 4, 2, 6, 1, 5, 3, 7]);
+        assert_eq!(domain.twiddles(), &[domain.element(0), domain.element(2), domain.element(1), domain.element(3)]);
+
+        let mut values: Vec<usize> = (0..8).collect();
+        domain.bit_reverse_permute(&mut values);
+        assert_eq!(values, vec![0, 4, 2, 6, 1, 5, 3, 7]);
+
+        assert!(Arc::ptr_eq(&domain, &cache.get(8).unwrap()));
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(12).is_err());
+        assert!(cache.get(1 << 28).is_err());
+    }
+
+    #[test]
+    fn test_subgroup_and_coset() {
+        let subgroup = TwoAdicSubgroup::new(4).unwrap();
+        let elements = subgroup.elements();
+        assert_eq!(elements.len(), 16);
+        assert!(elements.iter().all(|&x| subgroup.contains(x)));
+        assert_eq!(subgroup.element(17), subgroup.generator());
+
+        let coset = subgroup.coset(MULTIPLICATIVE_GENERATOR);
+        assert_eq!(coset.element(0), MULTIPLICATIVE_GENERATOR);
+        assert!(coset.elements().iter().all(|&x| coset.contains(x) && !subgroup.contains(x)));
+        assert_eq!(DomainCache::new().get(16).unwrap().subgroup(), subgroup);
+        assert!(TwoAdicSubgroup::new(TWO_ADICITY + 1).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Randomness Sources
+//!
+//! Components that need randomness draw it through `RngProvider` instead of
+//! reaching for the OS or a fixed seed, so deployments can route it through a
+//! certified DRBG (e.g. an HSM) and tests can pin it
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::{Result, ZKPError};
+
+/// Source of random bytes
+///
+/// Implementations backed by an HSM or external DRBG report exhaustion or
+/// health-test failures as errors rather than returning weak output.
+pub trait RngProvider: Debug + Send + Sync {
+    fn fill_bytes(&self, dest: &mut [u8]) -> Result<()>;
+}
+
+/// Operating system entropy (`getrandom`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsEntropy;
+
+impl RngProvider for OsEntropy {
+    fn fill_bytes(&self, dest: &mut [u8]) -> Result<()> {
+        rand::rngs::OsRng
+            .try_fill_bytes(dest)
+            .map_err(|e| ZKPError::ConfigError(format!("OS entropy unavailable: {}", e)))
+    }
+}
+
+/// Seeded ChaCha20 stream, for reproducible proofs and tests only
+#[derive(Debug)]
+pub struct DeterministicRng {
+    rng: Mutex<ChaCha20Rng>,
+}
+
+impl DeterministicRng {
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self { rng: Mutex::new(ChaCha20Rng::from_seed(seed)) }
+    }
+}
+
+impl RngProvider for DeterministicRng {
+    fn fill_bytes(&self, dest: &mut [u8]) -> Result<()> {
+        self.rng.lock().unwrap_or_else(|e| e.into_inner()).fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Default provider for components constructed without one
+pub fn os_entropy() -> Arc<dyn RngProvider> {
+    Arc::new(OsEntropy)
+}
+
+/// `RngCore` view of a provider, for APIs that take a `rand` generator
+///
+/// The infallible `RngCore` methods panic if the provider fails; use
+/// `try_fill_bytes` where a failure must be handled.
+pub struct ProviderRng<'a>(pub &'a dyn RngProvider);
+
+impl RngCore for ProviderRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest).expect("randomness provider failed")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand::Error> {
+        self.0.fill_bytes(dest).map_err(rand::Error::new)
+    }
+}
+
+/// 32-byte seed drawn from `provider`
+pub fn seed(provider: &dyn RngProvider) -> Result<[u8; 32]> {
+    let mut seed = [0u8; 32];
+    provider.fill_bytes(&mut seed)?;
+    Ok(seed)
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::keys::{KeyPurpose, KeyRing};
+
+    #[test]
+    fn test_deterministic_provider_pins_key_generation() {
+        let rotate = |provider: Arc<dyn RngProvider>| {
+            let mut ring = KeyRing::new().with_rng_provider(provider);
+            ring.rotate(KeyPurpose::ProverIdentity, 1_000).unwrap().id.clone()
+        };
+
+        let first = rotate(Arc::new(DeterministicRng::new([9u8; 32])));
+        assert_eq!(first, rotate(Arc::new(DeterministicRng::new([9u8; 32]))));
+        assert_ne!(first, rotate(Arc::new(DeterministicRng::new([10u8; 32]))));
+        assert_ne!(rotate(os_entropy()), rotate(os_entropy()));
+    }
+}
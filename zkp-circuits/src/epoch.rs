@@ -0,0 +1,397 @@
+//! Epoch Snapshots
+//!
+//! Freezes live score state at fixed intervals into Merkle snapshot roots, so
+//! "score as of epoch E" is a well-defined statement for time-bound proofs
+//!
+//! Disputed entries are frozen as dispute markers instead of their scores
+//! until the dispute is resolved. The marker is a leaf like any other, so
+//! the snapshot root itself shows the score was excluded.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::{hash_nodes, wallet_tag, InclusionWitness};
+use crate::poseidon2;
+use crate::{RepIDCategory, Result, ZKPError, F};
+
+/// Published commitment to the frozen state of one epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotRoot {
+    pub epoch: u64,
+    pub root: F,
+    /// Timestamp at which the epoch ended
+    pub frozen_at: u64,
+    pub num_entries: usize,
+}
+
+impl SnapshotRoot {
+    /// Public inputs binding a proof to this snapshot: [epoch, root]
+    pub fn public_inputs(&self) -> Vec<F> {
+        vec![F::new(self.epoch), self.root]
+    }
+}
+
+type StateKey = (String, RepIDCategory);
+
+/// Open dispute over one live score entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dispute {
+    pub reason: String,
+    pub opened_at: u64,
+}
+
+/// Outcome of a dispute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisputeResolution {
+    /// The score stands as recorded
+    Dismissed,
+    /// The score is replaced by the corrected value
+    Corrected(u32),
+    /// The entry is deleted
+    Removed,
+}
+
+/// One of a wallet's scores as frozen in a snapshot, with its path to the root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotLeaf {
+    pub category: RepIDCategory,
+    pub score: u32,
+    pub witness: InclusionWitness,
+}
+
+/// A wallet's frozen scores in one epoch, provable against its snapshot root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochScores {
+    pub snapshot: SnapshotRoot,
+    pub leaves: Vec<SnapshotLeaf>,
+}
+
+impl EpochScores {
+    /// Check each category is opened once and every leaf opens against the snapshot root
+    pub fn check_openings(&self, wallet_hash: &str) -> Result<()> {
+        for (i, leaf) in self.leaves.iter().enumerate() {
+            if self.leaves[..i].iter().any(|other| other.category == leaf.category) {
+                return Err(ZKPError::InvalidInput(format!("Category {:?} is opened twice in epoch {}", leaf.category, self.snapshot.epoch)));
+            }
+            if leaf.witness.leaf != state_leaf(wallet_hash, &leaf.category, leaf.score) || !leaf.witness.verify(self.snapshot.root) {
+                return Err(ZKPError::InvalidInput(format!("{:?} score does not open against the epoch {} snapshot", leaf.category, self.snapshot.epoch)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Live score state with periodic frozen snapshots
+#[derive(Debug, Clone)]
+pub struct EpochManager {
+    genesis: u64,
+    epoch_length_secs: u64,
+    current_epoch: u64,
+    state: BTreeMap<StateKey, u32>,
+    disputes: BTreeMap<StateKey, Dispute>,
+    snapshots: BTreeMap<u64, Frozen>,
+}
+
+/// State of a frozen epoch: its root, scores and the keys frozen as disputed
+#[derive(Debug, Clone)]
+struct Frozen {
+    snapshot: SnapshotRoot,
+    state: BTreeMap<StateKey, u32>,
+    disputed: BTreeSet<StateKey>,
+}
+
+impl EpochManager {
+    /// Epochs of `epoch_length_secs` starting at `genesis`
+    pub fn new(genesis: u64, epoch_length_secs: u64) -> Result<Self> {
+        if epoch_length_secs == 0 {
+            return Err(ZKPError::InvalidInput("epoch length must be positive".to_string()));
+        }
+        Ok(Self {
+            genesis,
+            epoch_length_secs,
+            current_epoch: 0,
+            state: BTreeMap::new(),
+            disputes: BTreeMap::new(),
+            snapshots: BTreeMap::new(),
+        })
+    }
+
+    /// Epoch containing `timestamp`
+    pub fn epoch_at(&self, timestamp: u64) -> u64 {
+        timestamp.saturating_sub(self.genesis) / self.epoch_length_secs
+    }
+
+    /// Epoch currently accepting updates
+    pub fn current_epoch(&self) -> u64 {
+        self.current_epoch
+    }
+
+    /// Record a score, freezing any epochs that ended before `timestamp`
+    pub fn update_score(&mut self, wallet_hash: &str, category: RepIDCategory, score: u32, timestamp: u64) -> Result<()> {
+        let epoch = self.epoch_at(timestamp);
+        if epoch < self.current_epoch {
+            return Err(ZKPError::InvalidInput(format!(
+                "epoch {} is already frozen (current epoch {})",
+                epoch, self.current_epoch
+            )));
+        }
+        self.advance_to(timestamp);
+        self.state.insert((wallet_hash.to_string(), category), score);
+        Ok(())
+    }
+
+    /// Flag a live score as disputed, excluding it from snapshots until resolved
+    pub fn flag_dispute(&mut self, wallet_hash: &str, category: RepIDCategory, reason: &str, timestamp: u64) -> Result<()> {
+        self.advance_to(timestamp);
+        let key = (wallet_hash.to_string(), category);
+        if !self.state.contains_key(&key) {
+            return Err(ZKPError::InvalidInput(format!("{} has no {:?} score to dispute", key.0, key.1)));
+        }
+        if self.disputes.contains_key(&key) {
+            return Err(ZKPError::InvalidInput(format!("{:?} score of {} is already disputed", key.1, key.0)));
+        }
+        self.disputes.insert(key, Dispute { reason: reason.to_string(), opened_at: timestamp });
+        Ok(())
+    }
+
+    /// Close a dispute; snapshots frozen after `timestamp` reflect the outcome
+    pub fn resolve_dispute(
+        &mut self,
+        wallet_hash: &str,
+        category: RepIDCategory,
+        resolution: DisputeResolution,
+        timestamp: u64,
+    ) -> Result<Dispute> {
+        self.advance_to(timestamp);
+        let key = (wallet_hash.to_string(), category);
+        let dispute = self.disputes.remove(&key)
+            .ok_or_else(|| ZKPError::InvalidInput(format!("{:?} score of {} is not disputed", key.1, key.0)))?;
+        match resolution {
+            DisputeResolution::Dismissed => {}
+            DisputeResolution::Corrected(score) => {
+                self.state.insert(key, score);
+            }
+            DisputeResolution::Removed => {
+                self.state.remove(&key);
+            }
+        }
+        Ok(dispute)
+    }
+
+    /// Open dispute over a wallet's score, if any
+    pub fn dispute(&self, wallet_hash: &str, category: &RepIDCategory) -> Option<&Dispute> {
+        self.disputes.get(&(wallet_hash.to_string(), category.clone()))
+    }
+
+    /// Freeze every epoch that ended at or before `now`
+    pub fn advance_to(&mut self, now: u64) {
+        while self.epoch_at(now) > self.current_epoch {
+            self.freeze_current();
+        }
+    }
+
+    fn freeze_current(&mut self) {
+        let epoch = self.current_epoch;
+        let disputed: BTreeSet<StateKey> = self.disputes.keys().cloned().collect();
+        let snapshot = SnapshotRoot {
+            epoch,
+            root: state_root(&self.state, &disputed),
+            frozen_at: self.genesis + (epoch + 1) * self.epoch_length_secs,
+            num_entries: self.state.len(),
+        };
+        self.snapshots.insert(epoch, Frozen { snapshot, state: self.state.clone(), disputed });
+        self.current_epoch += 1;
+    }
+
+    /// Published root of a frozen epoch
+    pub fn get_snapshot(&self, epoch: u64) -> Option<SnapshotRoot> {
+        self.snapshots.get(&epoch).map(|frozen| frozen.snapshot)
+    }
+
+    /// A wallet's undisputed scores as frozen in `epoch`
+    pub fn scores_at(&self, epoch: u64, wallet_hash: &str) -> Option<Vec<(RepIDCategory, u32)>> {
+        self.snapshots.get(&epoch).map(|frozen| {
+            frozen
+                .state
+                .iter()
+                .filter(|(key, _)| key.0 == wallet_hash && !frozen.disputed.contains(key))
+                .map(|((_, category), score)| (category.clone(), *score))
+                .collect()
+        })
+    }
+
+    /// A wallet's scores in `categories` as frozen in `epoch`, with inclusion witnesses
+    ///
+    /// Categories the wallet had no score in, or whose score was frozen as
+    /// disputed, are left out.
+    pub fn epoch_scores(&self, epoch: u64, wallet_hash: &str, categories: &[RepIDCategory]) -> Result<EpochScores> {
+        let frozen = self.frozen(epoch)?;
+        let leaves = categories
+            .iter()
+            .filter_map(|category| {
+                let key = (wallet_hash.to_string(), category.clone());
+                if frozen.disputed.contains(&key) {
+                    return None;
+                }
+                let leaf_index = frozen.state.keys().position(|k| *k == key)?;
+                Some(SnapshotLeaf {
+                    category: category.clone(),
+                    score: frozen.state[&key],
+                    witness: state_witness(&frozen.state, &frozen.disputed, leaf_index),
+                })
+            })
+            .collect();
+        Ok(EpochScores { snapshot: frozen.snapshot, leaves })
+    }
+
+    /// Path to the dispute marker a score was frozen as in `epoch`
+    ///
+    /// Shows against the published root that the score was excluded; check it
+    /// with `verify_exclusion`.
+    pub fn exclusion_witness(&self, epoch: u64, wallet_hash: &str, category: &RepIDCategory) -> Result<InclusionWitness> {
+        let frozen = self.frozen(epoch)?;
+        let key = (wallet_hash.to_string(), category.clone());
+        if !frozen.disputed.contains(&key) {
+            return Err(ZKPError::InvalidInput(format!("{:?} score of {} was not disputed in epoch {}", category, wallet_hash, epoch)));
+        }
+        let leaf_index = frozen.state.keys().position(|k| *k == key)
+            .ok_or_else(|| ZKPError::InvalidInput(format!("{} has no {:?} entry in epoch {}", wallet_hash, category, epoch)))?;
+        Ok(state_witness(&frozen.state, &frozen.disputed, leaf_index))
+    }
+
+    fn frozen(&self, epoch: u64) -> Result<&Frozen> {
+        self.snapshots
+            .get(&epoch)
+            .ok_or_else(|| ZKPError::InvalidInput(format!("epoch {} is not frozen", epoch)))
+    }
+
+    /// The last `n` frozen epochs up to and including `last_epoch`, oldest first
+    pub fn history(&self, wallet_hash: &str, categories: &[RepIDCategory], last_epoch: u64, n: usize) -> Result<Vec<EpochScores>> {
+        let first_epoch = (last_epoch + 1)
+            .checked_sub(n as u64)
+            .ok_or_else(|| ZKPError::InvalidInput(format!("only {} epochs precede epoch {}", last_epoch + 1, last_epoch)))?;
+        (first_epoch..=last_epoch).map(|epoch| self.epoch_scores(epoch, wallet_hash, categories)).collect()
+    }
+}
+
+/// Snapshot leaf of a wallet's score in one category
+pub fn state_leaf(wallet_hash: &str, category: &RepIDCategory, score: u32) -> F {
+    poseidon2::hash_elements(&[wallet_tag(wallet_hash), category.field_tag(), F::from_u32(score)])
+}
+
+/// Snapshot leaf standing in for a wallet's disputed score in one category
+///
+/// Hashes four elements, so it cannot collide with any three-element score leaf.
+pub fn disputed_leaf(wallet_hash: &str, category: &RepIDCategory) -> F {
+    poseidon2::hash_elements(&[wallet_tag(wallet_hash), category.field_tag(), F::ZERO, F::ONE])
+}
+
+/// Check `witness` shows the score was frozen as disputed under `snapshot`
+pub fn verify_exclusion(snapshot: &SnapshotRoot, wallet_hash: &str, category: &RepIDCategory, witness: &InclusionWitness) -> bool {
+    witness.leaf == disputed_leaf(wallet_hash, category) && witness.verify(snapshot.root)
+}
+
+/// Leaves of a state in key order, padded with zeros to a power of two
+fn state_leaves(state: &BTreeMap<StateKey, u32>, disputed: &BTreeSet<StateKey>) -> Vec<F> {
+    let mut leaves: Vec<F> = state
+        .iter()
+        .map(|(key, score)| match disputed.contains(key) {
+            true => disputed_leaf(&key.0, &key.1),
+            false => state_leaf(&key.0, &key.1, *score),
+        })
+        .collect();
+    leaves.resize(leaves.len().next_power_of_two().max(1), F::ZERO);
+    leaves
+}
+
+/// Merkle root over state entries in key order, padded with zero leaves
+fn state_root(state: &BTreeMap<StateKey, u32>, disputed: &BTreeSet<StateKey>) -> F {
+    let mut level = state_leaves(state, disputed);
+    while level.len() > 1 {
+        level = level.chunks(2).map(|pair| hash_nodes(pair[0], pair[1])).collect();
+    }
+    level[0]
+}
+
+/// Authentication path of the `leaf_index`-th state entry
+fn state_witness(state: &BTreeMap<StateKey, u32>, disputed: &BTreeSet<StateKey>, leaf_index: usize) -> InclusionWitness {
+    let mut level = state_leaves(state, disputed);
+    let leaf = level[leaf_index];
+    let mut siblings = Vec::new();
+    let mut position = leaf_index;
+    while level.len() > 1 {
+        siblings.push(level[position ^ 1]);
+        level = level.chunks(2).map(|pair| hash_nodes(pair[0], pair[1])).collect();
+        position /= 2;
+    }
+    InclusionWitness {
+        leaf_index: leaf_index as u64,
+        leaf,
+        siblings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshots_freeze_state_per_epoch() {
+        let mut manager = EpochManager::new(1_000, 100).unwrap();
+        manager.update_score("0xabc", RepIDCategory::Community, 10, 1_010).unwrap();
+        manager.update_score("0xabc", RepIDCategory::DeFi, 5, 1_050).unwrap();
+        assert!(manager.get_snapshot(0).is_none());
+
+        // Crossing into epoch 2 freezes epochs 0 and 1
+        manager.update_score("0xabc", RepIDCategory::Community, 40, 1_250).unwrap();
+        let epoch0 = manager.get_snapshot(0).unwrap();
+        let epoch1 = manager.get_snapshot(1).unwrap();
+        assert_eq!(epoch0.root, epoch1.root);
+        assert_eq!((epoch0.frozen_at, epoch0.num_entries), (1_100, 2));
+        assert_eq!(epoch0.public_inputs(), vec![F::new(0), epoch0.root]);
+
+        manager.advance_to(1_300);
+        let epoch2 = manager.get_snapshot(2).unwrap();
+        assert_ne!(epoch2.root, epoch1.root);
+        assert_eq!(
+            manager.scores_at(1, "0xabc").unwrap(),
+            vec![(RepIDCategory::Community, 10), (RepIDCategory::DeFi, 5)]
+        );
+
+        // Frozen epochs can no longer change
+        assert!(manager.update_score("0xabc", RepIDCategory::DeFi, 99, 1_150).is_err());
+    }
+
+    #[test]
+    fn test_disputed_scores_are_frozen_out_until_resolved() {
+        let categories = [RepIDCategory::Governance, RepIDCategory::DeFi];
+        let mut manager = EpochManager::new(0, 100).unwrap();
+        manager.update_score("0xabc", RepIDCategory::Governance, 900, 10).unwrap();
+        manager.update_score("0xabc", RepIDCategory::DeFi, 20, 20).unwrap();
+        assert!(manager.flag_dispute("0xabc", RepIDCategory::Community, "no entry", 30).is_err());
+        manager.flag_dispute("0xabc", RepIDCategory::Governance, "sybil votes", 30).unwrap();
+        assert!(manager.flag_dispute("0xabc", RepIDCategory::Governance, "again", 40).is_err());
+
+        // Epoch 0 freezes the dispute marker in place of the score
+        manager.advance_to(100);
+        let snapshot = manager.get_snapshot(0).unwrap();
+        assert_eq!(manager.scores_at(0, "0xabc").unwrap(), vec![(RepIDCategory::DeFi, 20)]);
+        let frozen = manager.epoch_scores(0, "0xabc", &categories).unwrap();
+        assert_eq!(frozen.leaves.len(), 1);
+        assert!(frozen.leaves[0].witness.verify(snapshot.root));
+        let exclusion = manager.exclusion_witness(0, "0xabc", &RepIDCategory::Governance).unwrap();
+        assert!(verify_exclusion(&snapshot, "0xabc", &RepIDCategory::Governance, &exclusion));
+        assert!(!verify_exclusion(&snapshot, "0xabc", &RepIDCategory::DeFi, &frozen.leaves[0].witness));
+
+        // A corrected score is frozen again from the next epoch
+        manager.resolve_dispute("0xabc", RepIDCategory::Governance, DisputeResolution::Corrected(90), 150).unwrap();
+        assert!(manager.dispute("0xabc", &RepIDCategory::Governance).is_none());
+        manager.advance_to(200);
+        assert_eq!(
+            manager.scores_at(1, "0xabc").unwrap(),
+            vec![(RepIDCategory::Governance, 90), (RepIDCategory::DeFi, 20)]
+        );
+        assert!(manager.exclusion_witness(1, "0xabc", &RepIDCategory::Governance).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! Escrowed Scores
+//!
+//! Threshold proofs that also carry the exact total, encrypted for an escrow
+//! agent such as a regulator. Only totals meeting the threshold are provable,
+//! so day-to-day verifiers learn nothing beyond that; under subpoena the agent
+//! decrypts the total the circuit compared against it.
+//!
+//! Encryption is split in two. In the circuit, a fresh escrow key `k` of
+//! four field elements is committed to as the eight-element digest
+//! Poseidon2(k), and the total is masked element-wise with the eight-element
+//! pad Poseidon2(k, tag); the proof shows every public ciphertext element
+//! masks the very total of the threshold check. Outside it, `k` is sealed to
+//! the agent's X25519 key in an `EscrowCapsule` stored next to the proof.
+//! The circuit cannot see into the capsule, so a prover could seal a wrong
+//! key; the agent detects that on opening, when the key no longer hashes to
+//! the commitment in the proof.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2::{self, Digest, DIGEST_ELEMENTS};
+use crate::{RepIDProof, Result, ZKPError, F};
+
+/// Operation type of escrowed threshold proofs
+pub const ESCROWED_THRESHOLD_OPERATION: &str = "escrowed_threshold_verification";
+/// Field elements in an escrow key
+pub const ESCROW_KEY_ELEMENTS: usize = 4;
+/// Public inputs of an escrowed threshold proof: threshold, time window,
+/// timestamp, key commitment and ciphertext
+pub const STATEMENT_INPUTS: usize = 3 + 2 * DIGEST_ELEMENTS;
+
+const KDF_CONTEXT: &str = "repid/score-escrow/v1";
+/// Last pad input, so the pad never equals the key commitment
+const PAD_TAG: u64 = 1;
+
+/// X25519 public key of an escrow agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EscrowPublicKey(pub [u8; 32]);
+
+/// X25519 secret key of an escrow agent
+#[derive(Clone)]
+pub struct EscrowSecretKey([u8; 32]);
+
+impl std::fmt::Debug for EscrowSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EscrowSecretKey")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+impl EscrowSecretKey {
+    /// Key from 32 bytes of caller-held key material (clamped on use)
+    pub fn from_bytes(secret: [u8; 32]) -> Self {
+        Self(secret)
+    }
+
+    /// Fresh key from `rng`
+    #[cfg(feature = "scoring")]
+    pub fn generate(rng: &dyn crate::entropy::RngProvider) -> Result<Self> {
+        crate::entropy::seed(rng).map(Self)
+    }
+
+    pub fn public_key(&self) -> EscrowPublicKey {
+        EscrowPublicKey(MontgomeryPoint::mul_base_clamped(self.0).to_bytes())
+    }
+
+    /// Recover the escrow key sealed in `capsule`, checked against its commitment
+    pub fn open(&self, capsule: &EscrowCapsule) -> Result<EscrowKey> {
+        if capsule.recipient != self.public_key() {
+            return Err(ZKPError::VerificationError("Capsule is sealed to a different escrow agent".to_string()));
+        }
+        let shared = MontgomeryPoint(capsule.ephemeral).mul_clamped(self.0);
+        let (key, nonce) = derive_key(&shared, &capsule.ephemeral, &capsule.recipient)?;
+        let plaintext = ChaCha20Poly1305::new(&key)
+            .decrypt(&nonce, Payload { msg: &capsule.ciphertext, aad: &capsule.associated_data() })
+            .map_err(|_| ZKPError::VerificationError("Escrow capsule could not be decrypted".to_string()))?;
+        let escrow_key = EscrowKey::from_bytes(&plaintext)?;
+        if escrow_key.commitment() != capsule.key_commitment {
+            return Err(ZKPError::VerificationError("Sealed escrow key does not match its commitment".to_string()));
+        }
+        Ok(escrow_key)
+    }
+
+    /// Decrypt the total an escrowed proof was checked against
+    pub fn decrypt_score(&self, proof: &RepIDProof, capsule: &EscrowCapsule) -> Result<i64> {
+        check_capsule(proof, capsule)?;
+        let statement = proof_statement(proof)?;
+        let pad = self.open(capsule)?.pad();
+        let total = statement.ciphertext[0] - pad[0];
+        if statement.ciphertext.iter().zip(&pad).any(|(&element, &pad)| element - pad != total) {
+            return Err(ZKPError::VerificationError("Escrowed ciphertext elements decrypt to different totals".to_string()));
+        }
+        Ok(total.to_i64())
+    }
+}
+
+/// One-time key the total of one proof is masked with
+#[derive(Clone, PartialEq, Eq)]
+pub struct EscrowKey {
+    elements: [F; ESCROW_KEY_ELEMENTS],
+}
+
+impl std::fmt::Debug for EscrowKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EscrowKey")
+            .field("commitment", &self.commitment())
+            .finish_non_exhaustive()
+    }
+}
+
+impl EscrowKey {
+    /// Key from 32 bytes of key material
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self { elements: crate::designated::seed_elements(&seed) }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 8 * ESCROW_KEY_ELEMENTS {
+            return Err(ZKPError::MalformedProof("Escrow key must be 32 bytes".to_string()));
+        }
+        let elements: Vec<F> = bytes.chunks_exact(8).map(|chunk| F::from_bytes(chunk.try_into().expect("8-byte chunk"))).collect();
+        Ok(Self { elements: elements.try_into().expect("four elements") })
+    }
+
+    #[cfg(feature = "scoring")]
+    fn to_bytes(&self) -> Vec<u8> {
+        self.elements.iter().flat_map(|element| element.to_bytes()).collect()
+    }
+
+    /// Poseidon2(k), public in the proof
+    pub fn commitment(&self) -> Digest {
+        poseidon2::hash_to_digest(&self.elements)
+    }
+
+    /// Poseidon2(k, tag), each element added to the total
+    pub fn pad(&self) -> Digest {
+        poseidon2::hash_to_digest(&self.pad_inputs())
+    }
+
+    /// Circuit witness of the commitment section
+    pub fn to_field_elements(&self) -> Vec<F> {
+        self.elements.to_vec()
+    }
+
+    /// Circuit witness of the pad section
+    pub fn pad_inputs(&self) -> Vec<F> {
+        let mut inputs = self.elements.to_vec();
+        inputs.push(F::new(PAD_TAG));
+        inputs
+    }
+}
+
+/// Escrow key sealed to the agent, stored alongside the proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowCapsule {
+    pub recipient: EscrowPublicKey,
+    /// Sender's ephemeral X25519 public key
+    pub ephemeral: [u8; 32],
+    /// Commitment of the sealed key, as it appears in the proof
+    pub key_commitment: Digest,
+    pub ciphertext: Vec<u8>,
+}
+
+impl EscrowCapsule {
+    fn associated_data(&self) -> Vec<u8> {
+        let mut data = [self.ephemeral.as_slice(), &self.recipient.0].concat();
+        data.extend(self.key_commitment.iter().flat_map(|element| element.to_bytes()));
+        data
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        crate::wire::to_bytes(self)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        crate::wire::from_bytes(bytes)
+    }
+}
+
+/// Fresh escrow key for one proof and its capsule
+#[derive(Debug, Clone)]
+pub struct ScoreEscrow {
+    key: EscrowKey,
+    capsule: EscrowCapsule,
+}
+
+impl ScoreEscrow {
+    /// Draw a key from `rng` and seal it to `agent`
+    #[cfg(feature = "scoring")]
+    pub fn new(agent: &EscrowPublicKey, rng: &dyn crate::entropy::RngProvider) -> Result<Self> {
+        let key = EscrowKey::from_seed(crate::entropy::seed(rng)?);
+        let ephemeral_secret = crate::entropy::seed(rng)?;
+        let ephemeral = MontgomeryPoint::mul_base_clamped(ephemeral_secret).to_bytes();
+        let shared = MontgomeryPoint(agent.0).mul_clamped(ephemeral_secret);
+        let (aead_key, nonce) = derive_key(&shared, &ephemeral, agent)?;
+
+        let mut capsule = EscrowCapsule {
+            recipient: *agent,
+            ephemeral,
+            key_commitment: key.commitment(),
+            ciphertext: Vec::new(),
+        };
+        capsule.ciphertext = ChaCha20Poly1305::new(&aead_key)
+            .encrypt(&nonce, Payload { msg: &key.to_bytes(), aad: &capsule.associated_data() })
+            .map_err(|_| ZKPError::SerializationError("Escrow key encryption failed".to_string()))?;
+        Ok(Self { key, capsule })
+    }
+
+    pub fn key(&self) -> &EscrowKey {
+        &self.key
+    }
+
+    pub fn capsule(&self) -> &EscrowCapsule {
+        &self.capsule
+    }
+}
+
+/// Public statement of an escrowed threshold proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowStatement {
+    pub threshold: u32,
+    pub key_commitment: Digest,
+    /// Total plus each element of the escrow pad
+    pub ciphertext: Digest,
+}
+
+/// Read the statement a verified escrowed threshold proof was made for
+pub fn proof_statement(proof: &RepIDProof) -> Result<EscrowStatement> {
+    if proof.metadata.operation_type != ESCROWED_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no escrowed score",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(..STATEMENT_INPUTS) {
+        Some([threshold, _time_window, _timestamp, digests @ ..]) => {
+            let (key_commitment, ciphertext) = digests.split_at(DIGEST_ELEMENTS);
+            Ok(EscrowStatement {
+                threshold: threshold.0 as u32,
+                key_commitment: key_commitment.try_into().expect("digest-sized split"),
+                ciphertext: ciphertext.try_into().expect("digest-sized split"),
+            })
+        }
+        _ => Err(ZKPError::MalformedProof(format!("Escrowed threshold proof needs {} public inputs", STATEMENT_INPUTS))),
+    }
+}
+
+/// Check `capsule` is the one sealed for `proof`, before archiving them together
+pub fn check_capsule(proof: &RepIDProof, capsule: &EscrowCapsule) -> Result<()> {
+    if proof_statement(proof)?.key_commitment != capsule.key_commitment {
+        return Err(ZKPError::VerificationError("Capsule seals the key of a different proof".to_string()));
+    }
+    Ok(())
+}
+
+/// AEAD key and nonce from the X25519 shared secret
+///
+/// Every ephemeral key seals one escrow key, so a derived nonce is never
+/// reused under its key. Low-order points give an all-zero shared secret and
+/// are rejected.
+fn derive_key(shared: &MontgomeryPoint, ephemeral: &[u8; 32], recipient: &EscrowPublicKey) -> Result<(Key, Nonce)> {
+    if shared.to_bytes() == [0u8; 32] {
+        return Err(ZKPError::InvalidInput("X25519 key agreement produced a low-order point".to_string()));
+    }
+    let mut hasher = blake3::Hasher::new_derive_key(KDF_CONTEXT);
+    hasher.update(shared.as_bytes());
+    hasher.update(ephemeral);
+    hasher.update(&recipient.0);
+    let mut output = [0u8; 44];
+    hasher.finalize_xof().fill(&mut output);
+    Ok((*Key::from_slice(&output[..32]), *Nonce::from_slice(&output[32..])))
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::entropy::DeterministicRng;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_only_the_escrow_agent_learns_the_score() {
+        let request = ThresholdVerificationRequest {
+            threshold: 100,
+            categories: vec![RepIDCategory::Community, RepIDCategory::DeFi],
+            time_window: 86400,
+            as_of: 1_700_000_000,
+            decay_params: None,
+        };
+        let rng = DeterministicRng::new([5; 32]);
+        let regulator = EscrowSecretKey::generate(&rng).unwrap();
+        let escrow = ScoreEscrow::new(&regulator.public_key(), &rng).unwrap();
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+
+        let scores = [(RepIDCategory::Community, 70), (RepIDCategory::DeFi, 63)];
+        let result = zkp_system.prove_escrowed_threshold_verification(&request, &scores, &escrow, "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+        assert!(!result.proof.public_inputs.iter().any(|input| input.0 == 133));
+
+        // The capsule travels with the proof; only the regulator opens it
+        let capsule = EscrowCapsule::from_bytes(&escrow.capsule().to_bytes().unwrap()).unwrap();
+        assert_eq!(regulator.decrypt_score(&result.proof, &capsule).unwrap(), 133);
+        let bystander = EscrowSecretKey::from_bytes([9; 32]);
+        assert!(bystander.decrypt_score(&result.proof, &capsule).is_err());
+        let mut tampered = capsule.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert!(regulator.open(&tampered).is_err());
+
+        // A capsule for another proof's key is rejected before archiving
+        let other = ScoreEscrow::new(&regulator.public_key(), &rng).unwrap();
+        assert!(check_capsule(&result.proof, other.capsule()).is_err());
+
+        // Totals below the threshold are not provable, so an escrowed proof always attests a met threshold
+        let low = [(RepIDCategory::Community, 10), (RepIDCategory::DeFi, 5)];
+        assert!(matches!(
+            zkp_system.prove_escrowed_threshold_verification(&request, &low, &other, "0xtest"),
+            Err(ZKPError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,449 @@
+//! EVM Artifacts and Gas Estimates
+//!
+//! Writes the generated verifier contract, its ABI and the test vectors into a
+//! Foundry or Hardhat project layout, and models on-chain verification cost
+//! and how proof size grows with the security level
+
+#[cfg(feature = "prover")]
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::custom_stark::{BabyBearField, StarkParams, StarkProof};
+#[cfg(feature = "prover")]
+use crate::fixtures::{generate_solidity_vectors, VECTOR_SECURITY_LEVEL};
+use crate::{SecurityLevel, SolidityVerificationData};
+#[cfg(feature = "prover")]
+use crate::{RepIDCategory, RepIDZKPSystem, Result, ThresholdVerificationRequest, ZKPError};
+
+/// Name of the generated verifier contract
+pub const VERIFIER_CONTRACT: &str = "RepIDStarkVerifier";
+
+/// Directory layout of the target project
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvmLayout {
+    /// `src/`, `test/`
+    #[default]
+    Foundry,
+    /// `contracts/`, `test/`
+    Hardhat,
+}
+
+impl EvmLayout {
+    #[cfg(feature = "prover")]
+    fn contracts_dir(self) -> &'static str {
+        match self {
+            EvmLayout::Foundry => "src",
+            EvmLayout::Hardhat => "contracts",
+        }
+    }
+
+    #[cfg(feature = "prover")]
+    fn vectors_dir(self) -> &'static str {
+        match self {
+            EvmLayout::Foundry => "test",
+            EvmLayout::Hardhat => "contracts/test",
+        }
+    }
+}
+
+/// Gas schedule of the chain a verifier is deployed to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainProfile {
+    pub name: String,
+    /// Fixed cost of every transaction
+    pub tx_base: u64,
+    /// Gas per non-zero calldata byte (proof bytes are costed as non-zero)
+    pub calldata_byte: u64,
+    /// Keccak-256 base and per-word cost
+    pub keccak_base: u64,
+    pub keccak_word: u64,
+    /// Writing a fresh storage slot, including the cold access
+    pub sstore_new: u64,
+    /// `ecrecover` precompile
+    pub ecrecover: u64,
+    /// Arithmetic per public input (range check and digest absorption)
+    pub per_input: u64,
+}
+
+impl ChainProfile {
+    /// Ethereum mainnet (post-Istanbul calldata, EIP-2929 storage)
+    pub fn ethereum() -> Self {
+        Self {
+            name: "ethereum".to_string(),
+            tx_base: 21_000,
+            calldata_byte: 16,
+            keccak_base: 30,
+            keccak_word: 6,
+            sstore_new: 22_100,
+            ecrecover: 3_000,
+            per_input: 200,
+        }
+    }
+
+    /// Optimistic rollup with calldata compressed roughly 4x before posting to L1
+    pub fn rollup() -> Self {
+        Self {
+            name: "rollup".to_string(),
+            calldata_byte: 4,
+            ..Self::ethereum()
+        }
+    }
+}
+
+/// Gas split for one verification path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasBreakdown {
+    pub calldata: u64,
+    pub hashing: u64,
+    pub storage: u64,
+    pub execution: u64,
+}
+
+impl GasBreakdown {
+    pub fn total(&self) -> u64 {
+        self.calldata + self.hashing + self.storage + self.execution
+    }
+}
+
+/// Estimated cost of both verification paths on one chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasEstimate {
+    pub chain: String,
+    /// Full STARK verification of the proof bytes on-chain
+    pub full_verify: GasBreakdown,
+    /// Checking a prover signature over the verified result
+    pub signed_result: GasBreakdown,
+}
+
+/// Bytes of an encoded STARK proof, by section
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofSizeBreakdown {
+    /// Trace, LDE and quotient roots plus the query openings and their Merkle paths
+    pub trace_openings: usize,
+    /// FRI layer commitments
+    pub fri_layers: usize,
+    /// Evaluations of the final FRI layer
+    pub final_poly: usize,
+    /// Proof-of-work nonce
+    pub proof_of_work: usize,
+    /// Public inputs and their digest
+    pub public_inputs: usize,
+    /// Circuit shape the verifier rebuilds the constraints from
+    pub shape: usize,
+}
+
+impl ProofSizeBreakdown {
+    /// Split the wire encoding of `proof` into its sections
+    pub fn of(proof: &StarkProof) -> Self {
+        Self {
+            trace_openings: encoded_len(&proof.trace_root)
+                + encoded_len(&proof.lde_root)
+                + encoded_len(&proof.quotient_root)
+                + encoded_len(&proof.queries),
+            fri_layers: encoded_len(&proof.fri_proof.commitments),
+            final_poly: encoded_len(&proof.fri_proof.final_poly),
+            proof_of_work: encoded_len(&proof.fri_proof.pow_nonce),
+            public_inputs: encoded_len(&proof.public_inputs) + encoded_len(&proof.public_inputs_digest),
+            shape: encoded_len(&proof.shape),
+        }
+    }
+
+    /// Size of the whole encoded proof
+    pub fn total(&self) -> usize {
+        self.trace_openings + self.fri_layers + self.final_poly + self.proof_of_work + self.public_inputs + self.shape
+    }
+}
+
+fn encoded_len<T: Serialize + ?Sized>(value: &T) -> usize {
+    crate::wire::encoded_len(value).expect("proof sections serialize")
+}
+
+/// Selector, two ABI offsets and two length words of a `verify` call
+const ABI_OVERHEAD: usize = 4 + 4 * 32;
+
+impl SolidityVerificationData {
+    /// Calldata of a full `verify(proof, publicInputs)` call
+    pub fn calldata_bytes(&self) -> usize {
+        ABI_OVERHEAD + self.proof_size + 32 * self.public_inputs.len()
+    }
+
+    /// Model calldata, hashing and storage cost of verifying this proof on `chain_profile`
+    pub fn estimate_gas(&self, chain_profile: &ChainProfile) -> GasEstimate {
+        let p = chain_profile;
+        let keccak = |bytes: u64| p.keccak_base + p.keccak_word * bytes.div_ceil(32);
+        let inputs = self.public_inputs.len() as u64;
+
+        // Every proof word is hashed once as part of a 64-byte Merkle or transcript node
+        let proof_size = self.proof_size as u64;
+        let full_verify = GasBreakdown {
+            calldata: self.calldata_bytes() as u64 * p.calldata_byte,
+            hashing: proof_size.div_ceil(64) * keccak(64),
+            storage: p.sstore_new,
+            execution: p.tx_base + inputs * p.per_input,
+        };
+
+        // Digest, result flag and a 65-byte signature
+        let signed_result = GasBreakdown {
+            calldata: (4 + 32 + 32 + 65) * p.calldata_byte,
+            hashing: keccak(64),
+            storage: p.sstore_new,
+            execution: p.tx_base + p.ecrecover,
+        };
+
+        GasEstimate {
+            chain: p.name.clone(),
+            full_verify,
+            signed_result,
+        }
+    }
+}
+
+/// Proof size of the reference proof at one security level
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizeBenchmark {
+    pub security_level: SecurityLevel,
+    pub estimated_security_bits: f64,
+    pub breakdown: ProofSizeBreakdown,
+    /// Calldata of verifying the proof on-chain
+    pub calldata_bytes: usize,
+}
+
+/// Measure a two-category threshold proof at every security level, weakest first
+///
+/// Openings dominate and grow with the query count and the Merkle depth, so
+/// other threshold-style proofs track these numbers closely.
+#[cfg(feature = "prover")]
+pub fn benchmark_proof_sizes() -> Result<Vec<SizeBenchmark>> {
+    let request = ThresholdVerificationRequest {
+        threshold: 50,
+        categories: vec![RepIDCategory::Governance, RepIDCategory::Technical],
+        time_window: 86400,
+        as_of: 0,
+        decay_params: None,
+    };
+    let scores = [(RepIDCategory::Governance, 40), (RepIDCategory::Technical, 35)];
+
+    [SecurityLevel::Fast, SecurityLevel::Standard, SecurityLevel::High]
+        .into_iter()
+        .map(|level| {
+            let mut zkp_system = RepIDZKPSystem::new(level);
+            let proof = zkp_system.prove_threshold_verification(&request, &scores, "0xbenchmark")?.proof;
+            let data = zkp_system.extract_solidity_verification_data(&proof);
+            Ok(SizeBenchmark {
+                security_level: level,
+                estimated_security_bits: zkp_system.estimated_security_bits(),
+                breakdown: data.size_breakdown.unwrap_or_default(),
+                calldata_bytes: data.calldata_bytes(),
+            })
+        })
+        .collect()
+}
+
+/// Strongest security level whose reference proof fits in `max_calldata_bytes`
+///
+/// `None` when even `Fast` proofs exceed the budget.
+#[cfg(feature = "prover")]
+pub fn recommend_security_level(max_calldata_bytes: usize) -> Result<Option<SecurityLevel>> {
+    Ok(benchmark_proof_sizes()?
+        .into_iter()
+        .rev()
+        .find(|benchmark| benchmark.calldata_bytes <= max_calldata_bytes)
+        .map(|benchmark| benchmark.security_level))
+}
+
+/// Verifier contract pinned to `params`
+///
+/// Rejects non-canonical public inputs and forwards the rest to the
+/// transpiled `verify_stark` backend deployed alongside it.
+pub fn verifier_contract(params: &StarkParams) -> String {
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+// Generated by repid-zkp-circuits {version}; do not edit
+pragma solidity ^0.8.24;
+
+interface IRepIDStarkBackend {{
+    function verifyStark(bytes calldata proof, uint256[] calldata publicInputs) external view returns (bool);
+}}
+
+contract {name} {{
+    uint256 public constant FIELD_MODULUS = {modulus};
+    uint256 public constant NUM_QUERIES = {num_queries};
+    uint256 public constant BLOWUP_FACTOR = {blowup_factor};
+    uint256 public constant FRI_FOLDING_ARITY = {arity};
+    uint256 public constant GRINDING_BITS = {grinding_bits};
+
+    IRepIDStarkBackend public immutable backend;
+
+    constructor(IRepIDStarkBackend backend_) {{
+        backend = backend_;
+    }}
+
+    function verify(bytes calldata proof, uint256[] calldata publicInputs) external view returns (bool) {{
+        for (uint256 i = 0; i < publicInputs.length; i++) {{
+            if (publicInputs[i] >= FIELD_MODULUS) {{
+                return false;
+            }}
+        }}
+        return backend.verifyStark(proof, publicInputs);
+    }}
+}}
+"#,
+        version = env!("CARGO_PKG_VERSION"),
+        name = VERIFIER_CONTRACT,
+        modulus = BabyBearField::MODULUS,
+        num_queries = params.num_queries,
+        blowup_factor = params.blowup_factor,
+        arity = params.fri_folding_arity,
+        grinding_bits = params.grinding_bits,
+    )
+}
+
+/// ABI of the generated verifier contract
+pub fn verifier_abi() -> serde_json::Value {
+    let constant = |name: &str| {
+        json!({
+            "type": "function",
+            "name": name,
+            "inputs": [],
+            "outputs": [{ "name": "", "type": "uint256", "internalType": "uint256" }],
+            "stateMutability": "view"
+        })
+    };
+    json!([
+        {
+            "type": "constructor",
+            "inputs": [{ "name": "backend_", "type": "address", "internalType": "contract IRepIDStarkBackend" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "function",
+            "name": "verify",
+            "inputs": [
+                { "name": "proof", "type": "bytes", "internalType": "bytes" },
+                { "name": "publicInputs", "type": "uint256[]", "internalType": "uint256[]" }
+            ],
+            "outputs": [{ "name": "", "type": "bool", "internalType": "bool" }],
+            "stateMutability": "view"
+        },
+        {
+            "type": "function",
+            "name": "backend",
+            "inputs": [],
+            "outputs": [{ "name": "", "type": "address", "internalType": "contract IRepIDStarkBackend" }],
+            "stateMutability": "view"
+        },
+        constant("FIELD_MODULUS"),
+        constant("NUM_QUERIES"),
+        constant("BLOWUP_FACTOR"),
+        constant("FRI_FOLDING_ARITY"),
+        constant("GRINDING_BITS")
+    ])
+}
+
+/// Write all artifacts under `root` in the Foundry layout
+#[cfg(feature = "prover")]
+pub fn export_evm_artifacts(root: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    export_evm_artifacts_with_layout(root, EvmLayout::Foundry)
+}
+
+/// Write the verifier contract, ABI JSON and test vectors, returning the files written
+#[cfg(feature = "prover")]
+pub fn export_evm_artifacts_with_layout(root: impl AsRef<Path>, layout: EvmLayout) -> Result<Vec<PathBuf>> {
+    let root = root.as_ref();
+    let vectors = generate_solidity_vectors()?;
+    let abi = serde_json::to_string_pretty(&verifier_abi()).map_err(|e| ZKPError::SerializationError(e.to_string()))?;
+
+    let files = [
+        (
+            root.join(layout.contracts_dir()).join(format!("{}.sol", VERIFIER_CONTRACT)),
+            verifier_contract(&VECTOR_SECURITY_LEVEL.params()),
+        ),
+        (root.join("abi").join(format!("{}.json", VERIFIER_CONTRACT)), abi),
+        (root.join(layout.vectors_dir()).join("RepIDTestVectors.sol"), vectors.to_solidity()),
+        (root.join("test").join("fixtures").join("repid_vectors.json"), vectors.to_json()?),
+    ];
+
+    let mut written = Vec::with_capacity(files.len());
+    for (path, contents) in files {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| ZKPError::ConfigError(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        std::fs::write(&path, contents)
+            .map_err(|e| ZKPError::ConfigError(format!("Failed to write {}: {}", path.display(), e)))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_export_layouts() {
+        let root = std::env::temp_dir().join(format!("repid_evm_{}", std::process::id()));
+        let foundry = export_evm_artifacts(root.join("foundry")).unwrap();
+        let hardhat = export_evm_artifacts_with_layout(root.join("hardhat"), EvmLayout::Hardhat).unwrap();
+
+        assert!(foundry[0].ends_with("foundry/src/RepIDStarkVerifier.sol"));
+        assert!(hardhat[0].ends_with("hardhat/contracts/RepIDStarkVerifier.sol"));
+        assert!(foundry.iter().chain(&hardhat).all(|path| path.is_file()));
+
+        let contract = std::fs::read_to_string(&foundry[0]).unwrap();
+        assert!(contract.contains(&format!("NUM_QUERIES = {}", VECTOR_SECURITY_LEVEL.params().num_queries)));
+        let abi: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&foundry[1]).unwrap()).unwrap();
+        assert!(abi.as_array().unwrap().iter().any(|entry| entry["name"] == "verify"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_gas_estimate_tracks_proof_size() {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let estimate = |level: SecurityLevel, chain: &ChainProfile| {
+            let mut zkp_system = RepIDZKPSystem::new(level);
+            let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest").unwrap();
+            zkp_system.extract_solidity_verification_data(&result.proof).estimate_gas(chain)
+        };
+
+        let fast = estimate(SecurityLevel::Fast, &ChainProfile::ethereum());
+        let standard = estimate(SecurityLevel::Standard, &ChainProfile::ethereum());
+        assert!(standard.full_verify.total() > fast.full_verify.total());
+        assert!(fast.signed_result.total() < fast.full_verify.total());
+        assert_eq!(fast.signed_result, standard.signed_result);
+
+        let rollup = estimate(SecurityLevel::Fast, &ChainProfile::rollup());
+        assert!(rollup.full_verify.calldata < fast.full_verify.calldata);
+    }
+
+    #[test]
+    fn test_size_benchmarks_and_recommendation() {
+        let benchmarks = benchmark_proof_sizes().unwrap();
+        assert_eq!(benchmarks.len(), 3);
+        for benchmark in &benchmarks {
+            let breakdown = benchmark.breakdown;
+            assert!(breakdown.trace_openings > breakdown.fri_layers);
+            assert_eq!(breakdown.proof_of_work, 8);
+            // Length prefix and eight-element digest around 8-byte inputs, each a full word in calldata
+            let inputs = (breakdown.public_inputs - 8 - 64) / 8;
+            assert_eq!(benchmark.calldata_bytes, ABI_OVERHEAD + breakdown.total() + 32 * inputs);
+        }
+        assert!(benchmarks.windows(2).all(|pair| pair[0].calldata_bytes < pair[1].calldata_bytes));
+
+        let [fast, standard, high] = [0, 1, 2].map(|i| benchmarks[i].calldata_bytes);
+        assert_eq!(recommend_security_level(high).unwrap(), Some(SecurityLevel::High));
+        assert_eq!(recommend_security_level(high - 1).unwrap(), Some(SecurityLevel::Standard));
+        assert_eq!(recommend_security_level(standard - 1).unwrap(), Some(SecurityLevel::Fast));
+        assert_eq!(recommend_security_level(fast - 1).unwrap(), None);
+    }
+}
@@ -0,0 +1,199 @@
+//! External Evidence
+//!
+//! Verifies zkVM proofs of off-chain computation (e.g. GitHub contribution
+//! analysis) and maps their journals into category scores for the threshold prover
+
+use serde::{Deserialize, Serialize};
+
+use crate::{RepIDCategory, Result, ZKPError};
+
+/// Proof system backing a piece of evidence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceKind {
+    /// Serialized `risc0_zkvm::Receipt`
+    Risc0Receipt,
+}
+
+/// Off-chain computation proof submitted as reputation evidence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalEvidence {
+    pub kind: EvidenceKind,
+    /// Identifier of the guest program (RISC Zero image ID)
+    pub program_id: [u8; 32],
+    pub proof: Vec<u8>,
+}
+
+/// Public output committed by an evidence program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvidenceJournal {
+    /// Wallet hash the computation was run for
+    pub subject: String,
+    /// Data source analysed (e.g. "github")
+    pub source: String,
+    /// Named measurements produced by the program
+    pub metrics: Vec<(String, u64)>,
+    pub issued_at: u64,
+}
+
+/// Checks evidence proofs and returns their verified journals
+pub trait EvidenceVerifier {
+    fn kind(&self) -> EvidenceKind;
+
+    fn verify(&self, evidence: &ExternalEvidence) -> Result<EvidenceJournal>;
+}
+
+/// How one journal metric contributes to a category score
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricRule {
+    pub metric: String,
+    pub category: RepIDCategory,
+    pub points_per_unit: u32,
+    /// Cap on the points this metric can contribute
+    pub max_points: u32,
+}
+
+/// Mapping from journal metrics to category scores
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreMapping {
+    pub rules: Vec<MetricRule>,
+}
+
+impl ScoreMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rule(mut self, metric: &str, category: RepIDCategory, points_per_unit: u32, max_points: u32) -> Self {
+        self.rules.push(MetricRule {
+            metric: metric.to_string(),
+            category,
+            points_per_unit,
+            max_points,
+        });
+        self
+    }
+
+    /// Category scores implied by a journal (unmapped metrics are ignored)
+    pub fn apply(&self, journal: &EvidenceJournal) -> Vec<(RepIDCategory, u32)> {
+        let mut scores: Vec<(RepIDCategory, u32)> = Vec::new();
+
+        for rule in &self.rules {
+            let units: u64 = journal.metrics.iter()
+                .filter(|(name, _)| *name == rule.metric)
+                .map(|(_, value)| *value)
+                .fold(0u64, u64::saturating_add);
+            let points = units
+                .saturating_mul(rule.points_per_unit as u64)
+                .min(rule.max_points as u64) as u32;
+
+            match scores.iter_mut().find(|(category, _)| *category == rule.category) {
+                Some((_, score)) => *score = score.saturating_add(points),
+                None => scores.push((rule.category.clone(), points)),
+            }
+        }
+
+        scores
+    }
+}
+
+/// Verify evidence for `subject` and convert it into category scores
+pub fn import_scores(
+    verifier: &dyn EvidenceVerifier,
+    evidence: &ExternalEvidence,
+    mapping: &ScoreMapping,
+    subject: &str,
+) -> Result<Vec<(RepIDCategory, u32)>> {
+    if evidence.kind != verifier.kind() {
+        return Err(ZKPError::InvalidInput(format!(
+            "{:?} evidence cannot be checked by a {:?} verifier",
+            evidence.kind,
+            verifier.kind()
+        )));
+    }
+
+    let journal = verifier.verify(evidence)?;
+    if journal.subject != subject {
+        return Err(ZKPError::VerificationError(format!(
+            "evidence was produced for '{}', not '{}'",
+            journal.subject, subject
+        )));
+    }
+
+    Ok(mapping.apply(&journal))
+}
+
+/// RISC Zero receipt verifier pinned to one guest image
+#[cfg(feature = "risc0")]
+#[derive(Debug, Clone)]
+pub struct Risc0ReceiptVerifier {
+    pub image_id: [u8; 32],
+}
+
+#[cfg(feature = "risc0")]
+impl EvidenceVerifier for Risc0ReceiptVerifier {
+    fn kind(&self) -> EvidenceKind {
+        EvidenceKind::Risc0Receipt
+    }
+
+    fn verify(&self, evidence: &ExternalEvidence) -> Result<EvidenceJournal> {
+        if evidence.program_id != self.image_id {
+            return Err(ZKPError::VerificationError("Receipt is for an unexpected guest image".to_string()));
+        }
+
+        let receipt: risc0_zkvm::Receipt = crate::wire::from_bytes(&evidence.proof)
+            .map_err(|e| ZKPError::SerializationError(format!("Failed to deserialize receipt: {}", e)))?;
+        receipt
+            .verify(self.image_id)
+            .map_err(|e| ZKPError::VerificationError(format!("Receipt verification failed: {}", e)))?;
+        receipt
+            .journal
+            .decode()
+            .map_err(|e| ZKPError::SerializationError(format!("Failed to decode journal: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Accepts evidence whose proof bytes are a JSON journal
+    struct TrustedJournal;
+
+    impl EvidenceVerifier for TrustedJournal {
+        fn kind(&self) -> EvidenceKind {
+            EvidenceKind::Risc0Receipt
+        }
+
+        fn verify(&self, evidence: &ExternalEvidence) -> Result<EvidenceJournal> {
+            serde_json::from_slice(&evidence.proof).map_err(|e| ZKPError::SerializationError(e.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_journal_metrics_map_to_capped_scores() {
+        let journal = EvidenceJournal {
+            subject: "0xabc".to_string(),
+            source: "github".to_string(),
+            metrics: vec![("merged_prs".to_string(), 12), ("reviews".to_string(), 40), ("stars".to_string(), 9)],
+            issued_at: 1_700_000_000,
+        };
+        let evidence = ExternalEvidence {
+            kind: EvidenceKind::Risc0Receipt,
+            program_id: [0u8; 32],
+            proof: serde_json::to_vec(&journal).unwrap(),
+        };
+        let mapping = ScoreMapping::new()
+            .with_rule("merged_prs", RepIDCategory::Technical, 5, 100)
+            .with_rule("reviews", RepIDCategory::Technical, 2, 50)
+            .with_rule("reviews", RepIDCategory::Community, 1, 20);
+
+        let scores = import_scores(&TrustedJournal, &evidence, &mapping, "0xabc").unwrap();
+        assert_eq!(scores, vec![(RepIDCategory::Technical, 110), (RepIDCategory::Community, 20)]);
+
+        assert!(matches!(
+            import_scores(&TrustedJournal, &evidence, &mapping, "0xother"),
+            Err(ZKPError::VerificationError(_))
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+//! Mobile Verifier Bindings
+//!
+//! UniFFI exports of the verifier-only path, so iOS and Android wallets check
+//! received proofs locally. Build with `cargo rustc --features uniffi --crate-type cdylib`
+//! and run `uniffi-bindgen generate --library` on the result for Swift or Kotlin.
+
+use crate::{RepIDProof, RepIDZKPSystem, SecurityLevel, ZKPError};
+
+/// Envelope fields a wallet shows before or after verifying
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct ProofSummary {
+    pub operation_type: String,
+    pub timestamp: u64,
+    pub proof_size: u64,
+    pub public_inputs: Vec<u64>,
+    pub signed: bool,
+}
+
+impl From<&RepIDProof> for ProofSummary {
+    fn from(proof: &RepIDProof) -> Self {
+        Self {
+            operation_type: proof.metadata.operation_type.clone(),
+            timestamp: proof.metadata.timestamp,
+            proof_size: proof.metadata.proof_size as u64,
+            public_inputs: proof.public_inputs.iter().map(|input| input.0).collect(),
+            signed: proof.metadata.prover_signature.is_some(),
+        }
+    }
+}
+
+/// Verify a wire-encoded proof envelope
+#[uniffi::export]
+pub fn verify_proof_bytes(envelope: Vec<u8>, security_level: SecurityLevel) -> Result<bool, ZKPError> {
+    let proof = crate::protocol::decode_envelope(&envelope)?;
+    RepIDZKPSystem::new(security_level).verify_proof(&proof, None)
+}
+
+/// Verify a proof scanned as a compact string
+#[uniffi::export]
+pub fn verify_compact_proof(compact: String, security_level: SecurityLevel) -> Result<bool, ZKPError> {
+    let proof = RepIDProof::from_compact_string(&compact)?;
+    RepIDZKPSystem::new(security_level).verify_proof(&proof, None)
+}
+
+/// Decode a compact string without verifying it
+#[uniffi::export]
+pub fn inspect_compact_proof(compact: String) -> Result<ProofSummary, ZKPError> {
+    Ok(ProofSummary::from(&RepIDProof::from_compact_string(&compact)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_exported_verifier_path() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest").unwrap();
+        let compact = result.proof.to_compact_string().unwrap();
+
+        assert!(verify_compact_proof(compact.clone(), SecurityLevel::Fast).unwrap());
+        assert!(verify_proof_bytes(crate::wire::to_bytes(&result.proof).unwrap(), SecurityLevel::Fast).unwrap());
+        assert!(verify_proof_bytes(vec![0; 4], SecurityLevel::Fast).is_err());
+
+        let summary = inspect_compact_proof(compact).unwrap();
+        assert_eq!(summary.operation_type, "threshold_verification");
+        assert_eq!(summary.public_inputs.len(), result.proof.public_inputs.len());
+    }
+}
@@ -0,0 +1,110 @@
+//! Fixed-Point Arithmetic
+//!
+//! Q16.16 numbers shared by the scorer and the AIR encodings, so reported and
+//! proven scores come from the same integer arithmetic on every platform
+
+use std::ops::{Add, Mul, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use crate::F;
+
+/// Signed Q16.16 fixed-point value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Q16(pub i64);
+
+impl Q16 {
+    pub const FRAC_BITS: u32 = 16;
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << Self::FRAC_BITS);
+
+    pub fn from_int(value: i64) -> Self {
+        Self(value << Self::FRAC_BITS)
+    }
+
+    /// Nearest Q16.16 value to a configured weight
+    pub fn from_f32(value: f32) -> Self {
+        Self((value as f64 * Self::ONE.0 as f64).round() as i64)
+    }
+
+    /// Nearest Q16.16 value to `numerator / denominator` (denominator must be positive)
+    pub const fn from_ratio(numerator: i64, denominator: i64) -> Self {
+        let scaled = numerator * Self::ONE.0;
+        Self((scaled + scaled.signum() * denominator / 2) / denominator)
+    }
+
+    /// Fraction expressed in basis points (100 = 1%)
+    pub fn from_basis_points(basis_points: u16) -> Self {
+        Self((basis_points as i64 * Self::ONE.0) / 10_000)
+    }
+
+    pub fn raw(self) -> i64 {
+        self.0
+    }
+
+    /// Exact product with an integer
+    pub fn mul_int(self, value: i64) -> Self {
+        Self(self.0 * value)
+    }
+
+    /// Integer part, rounded towards negative infinity
+    pub fn to_int(self) -> i64 {
+        self.0 >> Self::FRAC_BITS
+    }
+
+    /// Fractional remainder in [0, ONE)
+    pub fn frac(self) -> i64 {
+        self.0 & (Self::ONE.0 - 1)
+    }
+
+    /// Integer part clamped to the `u32` score range
+    pub fn to_u32(self) -> u32 {
+        self.to_int().clamp(0, u32::MAX as i64) as u32
+    }
+
+    /// Field encoding of the raw value (negatives as p - |raw|)
+    pub fn to_field(self) -> F {
+        F::from_i64(self.0)
+    }
+}
+
+impl Add for Q16 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+/// Product rounded towards negative infinity
+impl Mul for Q16 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self(((self.0 as i128 * other.0 as i128) >> Self::FRAC_BITS) as i64)
+    }
+}
+
+impl Sub for Q16 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_q16_rounding_and_encoding() {
+        assert_eq!(Q16::from_f32(1.2).raw(), 78643);
+        assert_eq!(Q16::from_f32(1.2).mul_int(85).to_int(), 101);
+        assert_eq!(Q16::from_basis_points(500), Q16(3276));
+        assert_eq!([Q16::from_ratio(11, 10), Q16::from_ratio(-11, 10)], [Q16(72090), Q16(-72090)]);
+        assert_eq!(Q16(-1).to_int(), -1);
+        assert_eq!((Q16::from_int(3) - Q16::ONE * Q16::from_f32(0.5)).to_int(), 2);
+        assert_eq!(Q16::from_int(-2).to_field().to_i64(), -2 << 16);
+    }
+}
@@ -0,0 +1,300 @@
+//! Solidity Test Vectors and Golden Fixtures
+//!
+//! Deterministic proofs, public inputs and expected outcomes from this crate
+//! version, emitted as JSON and as a Solidity library for Foundry tests, plus
+//! golden proof hashes for catching unintended proof-format changes
+
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::FixedClock;
+use crate::fixed_point::Q16;
+use crate::hidden::CategorySetOpening;
+use crate::public_inputs::PublicInputs;
+use crate::stateless::verify_stark;
+use crate::{
+    DecayParameters, RepIDCategory, RepIDProof, RepIDZKPSystem, Result, SecurityLevel, ThresholdVerificationRequest,
+    ZKPError, F,
+};
+
+/// Security level every vector is generated at
+pub const VECTOR_SECURITY_LEVEL: SecurityLevel = SecurityLevel::Fast;
+/// Evaluation instant every vector is proven at
+pub const VECTOR_TIMESTAMP: u64 = 1_700_000_000;
+
+/// One proof with the outcome `verify_stark` gives it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolidityVector {
+    pub name: String,
+    pub operation_type: String,
+    /// 0x-prefixed proof bytes
+    pub proof: String,
+    /// Public inputs as 0x-prefixed 256-bit words
+    pub public_inputs: Vec<String>,
+    pub public_inputs_digest: String,
+    pub expected_valid: bool,
+}
+
+/// Full vector set tagged with the crate version that produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolidityVectors {
+    pub crate_version: String,
+    pub security_level: SecurityLevel,
+    pub vectors: Vec<SolidityVector>,
+}
+
+impl SolidityVectors {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    /// Solidity library exposing the vectors as an array of structs
+    pub fn to_solidity(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "// SPDX-License-Identifier: MIT");
+        let _ = writeln!(out, "// Generated by repid-zkp-circuits {}; do not edit", self.crate_version);
+        let _ = writeln!(out, "pragma solidity ^0.8.24;\n");
+        let _ = writeln!(out, "library RepIDTestVectors {{");
+        let _ = writeln!(out, "    struct Vector {{");
+        let _ = writeln!(out, "        string name;");
+        let _ = writeln!(out, "        string operationType;");
+        let _ = writeln!(out, "        bytes proof;");
+        let _ = writeln!(out, "        uint256[] publicInputs;");
+        let _ = writeln!(out, "        bytes32 publicInputsDigest;");
+        let _ = writeln!(out, "        bool expectedValid;");
+        let _ = writeln!(out, "    }}\n");
+        let _ = writeln!(out, "    function vectors() internal pure returns (Vector[] memory v) {{");
+        let _ = writeln!(out, "        v = new Vector[]({});", self.vectors.len());
+        for (i, vector) in self.vectors.iter().enumerate() {
+            let _ = writeln!(out, "        uint256[] memory inputs{} = new uint256[]({});", i, vector.public_inputs.len());
+            for (j, input) in vector.public_inputs.iter().enumerate() {
+                let _ = writeln!(out, "        inputs{}[{}] = {};", i, j, input);
+            }
+            let _ = writeln!(out, "        v[{}] = Vector({{", i);
+            let _ = writeln!(out, "            name: \"{}\",", vector.name);
+            let _ = writeln!(out, "            operationType: \"{}\",", vector.operation_type);
+            let _ = writeln!(out, "            proof: hex\"{}\",", vector.proof.trim_start_matches("0x"));
+            let _ = writeln!(out, "            publicInputs: inputs{},", i);
+            let _ = writeln!(out, "            publicInputsDigest: {},", vector.public_inputs_digest);
+            let _ = writeln!(out, "            expectedValid: {}", vector.expected_valid);
+            let _ = writeln!(out, "        }});");
+        }
+        let _ = writeln!(out, "    }}");
+        let _ = writeln!(out, "}}");
+        out
+    }
+}
+
+/// Generate the deterministic vector set: valid proofs plus tampered variants
+pub fn generate_solidity_vectors() -> Result<SolidityVectors> {
+    let request = ThresholdVerificationRequest {
+        threshold: 50,
+        categories: vec![RepIDCategory::Governance, RepIDCategory::Technical],
+        time_window: 86400,
+        as_of: VECTOR_TIMESTAMP,
+        decay_params: None,
+    };
+    let scores = [(RepIDCategory::Governance, 40), (RepIDCategory::Technical, 35)];
+
+    // A fresh system per proof keeps every vector independent of generation order
+    let system = || RepIDZKPSystem::new(VECTOR_SECURITY_LEVEL).with_clock(Arc::new(FixedClock::new(VECTOR_TIMESTAMP)));
+    let threshold = system().prove_threshold_verification(&request, &scores, "0xvector")?.proof;
+    let biometric = system().prove_biometric_4fa([1u8; 32], [2u8; 32], &[true; 4])?;
+
+    let mut tampered_inputs = threshold.public_inputs.clone();
+    tampered_inputs[0] += F::ONE;
+    let mut truncated = threshold.proof_data.clone();
+    truncated.truncate(truncated.len() / 2);
+
+    let vectors = vec![
+        vector("threshold_valid", &threshold, &threshold.proof_data, &threshold.public_inputs),
+        vector("biometric_valid", &biometric, &biometric.proof_data, &biometric.public_inputs),
+        vector("threshold_tampered_input", &threshold, &threshold.proof_data, &tampered_inputs),
+        vector("threshold_truncated_proof", &threshold, &truncated, &threshold.public_inputs),
+    ];
+
+    Ok(SolidityVectors {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        security_level: VECTOR_SECURITY_LEVEL,
+        vectors,
+    })
+}
+
+fn vector(name: &str, proof: &RepIDProof, proof_data: &[u8], public_inputs: &[F]) -> SolidityVector {
+    let params = VECTOR_SECURITY_LEVEL.params();
+    SolidityVector {
+        name: name.to_string(),
+        operation_type: proof.metadata.operation_type.clone(),
+        proof: format!("0x{}", hex::encode(proof_data)),
+        public_inputs: public_inputs.iter().map(|input| format!("0x{:064x}", input.0)).collect(),
+        public_inputs_digest: format!("0x{}", hex::encode(PublicInputs::new(public_inputs.to_vec()).digest_bytes())),
+        expected_valid: verify_stark(proof_data, public_inputs, &params).unwrap_or(false),
+    }
+}
+
+/// Security levels golden fixtures are generated at
+pub const GOLDEN_SECURITY_LEVELS: [SecurityLevel; 2] = [SecurityLevel::Fast, SecurityLevel::Standard];
+
+/// Hashes pinning the encoding of one deterministic proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldenFixture {
+    pub name: String,
+    pub operation_type: String,
+    pub security_level: SecurityLevel,
+    pub proof_size: usize,
+    /// Blake3 of the proof bytes
+    pub proof_hash: String,
+    /// Blake3 of the canonical public input encoding
+    pub public_inputs_hash: String,
+}
+
+/// Golden fixtures tagged with the crate version that produced them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldenSet {
+    pub crate_version: String,
+    pub fixtures: Vec<GoldenFixture>,
+}
+
+impl GoldenSet {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    /// Names of fixtures that changed, appeared or disappeared relative to `expected`
+    pub fn diff(&self, expected: &GoldenSet) -> Vec<String> {
+        let mut changed: Vec<String> = self
+            .fixtures
+            .iter()
+            .filter(|fixture| !expected.fixtures.contains(fixture))
+            .map(|fixture| fixture.name.clone())
+            .collect();
+        changed.extend(
+            expected
+                .fixtures
+                .iter()
+                .filter(|fixture| !self.fixtures.iter().any(|f| f.name == fixture.name))
+                .map(|fixture| fixture.name.clone()),
+        );
+        changed
+    }
+}
+
+/// Generate the golden set: every fixture operation at every golden security level
+///
+/// Envelope metadata such as generation time varies between runs, so only
+/// the proof bytes and public inputs are hashed.
+pub fn generate_golden() -> Result<GoldenSet> {
+    let request = ThresholdVerificationRequest {
+        threshold: 50,
+        categories: vec![RepIDCategory::Governance, RepIDCategory::Technical],
+        time_window: 86400,
+        as_of: VECTOR_TIMESTAMP,
+        decay_params: None,
+    };
+    let decayed = ThresholdVerificationRequest {
+        decay_params: Some(DecayParameters {
+            base_decay_rate: 100,
+            multiplicative_factor: Q16::ONE,
+            min_threshold: 10,
+            category_decay: Vec::new(),
+        }),
+        ..request.clone()
+    };
+    let scores = [(RepIDCategory::Governance, 40), (RepIDCategory::Technical, 35)];
+    let category_set = CategorySetOpening::new(vec![RepIDCategory::Governance, RepIDCategory::Technical], F::new(1));
+
+    let mut fixtures = Vec::new();
+    for level in GOLDEN_SECURITY_LEVELS {
+        let system = || RepIDZKPSystem::new(level).with_clock(Arc::new(FixedClock::new(VECTOR_TIMESTAMP)));
+        let proofs = [
+            ("threshold", system().prove_threshold_verification(&request, &scores, "0xgolden")?.proof),
+            ("threshold_decayed", system().prove_threshold_verification(&decayed, &scores, "0xgolden")?.proof),
+            ("biometric", system().prove_biometric_4fa([1u8; 32], [2u8; 32], &[true; 4])?),
+            ("category_count", system().prove_category_count(&scores, 30, 2, "0xgolden")?),
+            (
+                "hidden_threshold",
+                system().prove_hidden_threshold_verification(&request, &scores, &category_set, "0xgolden")?.proof,
+            ),
+        ];
+        fixtures.extend(proofs.into_iter().map(|(name, proof)| golden_fixture(name, level, &proof)));
+    }
+
+    Ok(GoldenSet {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        fixtures,
+    })
+}
+
+fn golden_fixture(name: &str, level: SecurityLevel, proof: &RepIDProof) -> GoldenFixture {
+    GoldenFixture {
+        name: format!("{}_{:?}", name, level).to_lowercase(),
+        operation_type: proof.metadata.operation_type.clone(),
+        security_level: level,
+        proof_size: proof.proof_data.len(),
+        proof_hash: blake3::hash(&proof.proof_data).to_hex().to_string(),
+        public_inputs_hash: hex::encode(blake3::hash(&PublicInputs::new(proof.public_inputs.clone()).digest_bytes()).as_bytes()),
+    }
+}
+
+/// Write a freshly generated golden set to `path` as JSON
+pub fn write_golden(path: impl AsRef<Path>) -> Result<GoldenSet> {
+    let path = path.as_ref();
+    let golden = generate_golden()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| ZKPError::ConfigError(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    std::fs::write(path, golden.to_json()?)
+        .map_err(|e| ZKPError::ConfigError(format!("Failed to write {}: {}", path.display(), e)))?;
+    Ok(golden)
+}
+
+/// Regenerate the golden set and compare it with the one stored at `path`
+///
+/// Returns the names of fixtures whose encoding changed; empty means this
+/// build produces byte-identical proofs.
+pub fn check_golden(path: impl AsRef<Path>) -> Result<Vec<String>> {
+    let path = path.as_ref();
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| ZKPError::ConfigError(format!("Failed to read {}: {}", path.display(), e)))?;
+    Ok(generate_golden()?.diff(&GoldenSet::from_json(&json)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors_are_deterministic() {
+        let vectors = generate_solidity_vectors().unwrap();
+        assert_eq!(vectors, generate_solidity_vectors().unwrap());
+        let outcomes: Vec<bool> = vectors.vectors.iter().map(|v| v.expected_valid).collect();
+        assert_eq!(outcomes, vec![true, true, false, false]);
+
+        let solidity = vectors.to_solidity();
+        assert!(solidity.contains("library RepIDTestVectors"));
+        assert!(solidity.contains("name: \"threshold_tampered_input\""));
+        assert!(vectors.to_json().unwrap().contains("\"expected_valid\": false"));
+    }
+
+    #[test]
+    fn test_golden_set_detects_format_changes() {
+        let path = std::env::temp_dir().join(format!("repid-golden-{}.json", std::process::id()));
+        let golden = write_golden(&path).unwrap();
+        assert_eq!(golden.fixtures.len(), 5 * GOLDEN_SECURITY_LEVELS.len());
+        assert!(check_golden(&path).unwrap().is_empty());
+
+        let mut stale = golden.clone();
+        stale.fixtures[0].proof_hash = "00".repeat(32);
+        stale.fixtures.pop();
+        assert_eq!(golden.diff(&stale), vec!["threshold_fast".to_string(), "hidden_threshold_standard".to_string()]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,117 @@
+//! Attestation Freshness
+//!
+//! Scores backed by timestamped attestations, and the relying-party check that
+//! a freshness proof was made recently against a tight enough age bound
+
+use serde::{Deserialize, Serialize};
+
+use crate::{RepIDCategory, RepIDProof, Result, ZKPError};
+
+/// Operation type of freshness-bound threshold proofs
+pub const FRESH_THRESHOLD_OPERATION: &str = "fresh_threshold_verification";
+
+/// Category score backed by an attestation issued at a known time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttestedScore {
+    pub category: RepIDCategory,
+    pub score: u32,
+    /// Issuance time of the underlying attestation (Unix seconds)
+    pub issued_at: u64,
+}
+
+/// Public freshness statement: every attestation is at most `max_age_secs` old at `now`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FreshnessBound {
+    pub now: u64,
+    pub max_age_secs: u64,
+}
+
+impl FreshnessBound {
+    pub fn new(now: u64, max_age_secs: u64) -> Self {
+        Self { now, max_age_secs }
+    }
+
+    /// Attestations older than the bound (or dated in the future)
+    pub fn stale<'a>(&self, attested: &'a [AttestedScore]) -> Vec<&'a AttestedScore> {
+        attested
+            .iter()
+            .filter(|a| a.issued_at > self.now || self.now - a.issued_at > self.max_age_secs)
+            .collect()
+    }
+}
+
+/// Read the freshness bound a verified proof was made against
+pub fn proof_bound(proof: &RepIDProof) -> Result<FreshnessBound> {
+    if proof.metadata.operation_type != FRESH_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no freshness bound",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(2..4) {
+        Some([now, max_age]) => Ok(FreshnessBound::new(now.0, max_age.0)),
+        _ => Err(ZKPError::MalformedProof("Freshness proof needs now and max_age inputs".to_string())),
+    }
+}
+
+/// Relying-party policy: bound no looser than `max_age_secs`, proof made within `max_skew_secs` of `now`
+pub fn check_bound(proof: &RepIDProof, max_age_secs: u64, now: u64, max_skew_secs: u64) -> Result<()> {
+    let bound = proof_bound(proof)?;
+    if bound.max_age_secs > max_age_secs {
+        return Err(ZKPError::PolicyViolation(format!(
+            "proof allows attestations up to {}s old, policy requires {}s",
+            bound.max_age_secs, max_age_secs
+        )));
+    }
+    if bound.now.abs_diff(now) > max_skew_secs {
+        return Err(ZKPError::PolicyViolation(format!(
+            "proof was made at {}, {}s away from {}",
+            bound.now,
+            bound.now.abs_diff(now),
+            now
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    fn attested(issued_at: u64) -> Vec<AttestedScore> {
+        vec![
+            AttestedScore { category: RepIDCategory::Community, score: 40, issued_at: 1_000 },
+            AttestedScore { category: RepIDCategory::DeFi, score: 30, issued_at },
+        ]
+    }
+
+    #[test]
+    fn test_freshness_proofs() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community, RepIDCategory::DeFi],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let bound = FreshnessBound::new(1_500, 3_600);
+
+        let proof = zkp_system
+            .prove_fresh_threshold_verification(&request, &attested(1_200), bound, "0xtest")
+            .unwrap()
+            .proof;
+        assert!(zkp_system.verify_proof(&proof, None).unwrap());
+        assert_eq!(proof_bound(&proof).unwrap(), bound);
+        assert!(check_bound(&proof, 3_600, 1_560, 120).is_ok());
+        assert!(check_bound(&proof, 600, 1_560, 120).is_err());
+        assert!(check_bound(&proof, 3_600, 9_000, 120).is_err());
+
+        // One stale (and one future-dated) attestation cannot be proven fresh
+        let stale = FreshnessBound::new(10_000, 3_600);
+        assert_eq!(stale.stale(&attested(9_000)).len(), 1);
+        assert!(zkp_system.prove_fresh_threshold_verification(&request, &attested(9_000), stale, "0xtest").is_err());
+        assert!(zkp_system.prove_fresh_threshold_verification(&request, &attested(2_000), bound, "0xtest").is_err());
+    }
+}
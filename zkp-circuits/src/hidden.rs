@@ -0,0 +1,129 @@
+//! Hidden Category Sets
+//!
+//! Threshold proofs where the set of contributing categories is committed
+//! rather than listed, so verifiers learn the threshold but not which
+//! categories the score was drawn from
+
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2::{self, Digest, DIGEST_ELEMENTS};
+use crate::{RepIDCategory, RepIDProof, Result, ZKPError, F};
+
+/// Operation type of hidden-category threshold proofs
+pub const HIDDEN_THRESHOLD_OPERATION: &str = "hidden_threshold_verification";
+
+/// Public commitment to a category set (eight-element Poseidon2 digest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategorySetCommitment(pub Digest);
+
+/// Private opening of a category set commitment, held by the prover
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategorySetOpening {
+    /// Committed categories in commitment order
+    pub categories: Vec<RepIDCategory>,
+    /// Blinding factor; without it the few built-in categories are trivially enumerable
+    pub blinding: F,
+}
+
+impl CategorySetOpening {
+    pub fn new(categories: Vec<RepIDCategory>, blinding: F) -> Self {
+        Self { categories, blinding }
+    }
+
+    /// Canonical hash input: [blinding, tag_0, tag_1, ...]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        std::iter::once(self.blinding)
+            .chain(self.categories.iter().map(RepIDCategory::field_tag))
+            .collect()
+    }
+
+    /// Compute the commitment this opening corresponds to
+    pub fn commit(&self) -> CategorySetCommitment {
+        CategorySetCommitment(poseidon2::hash_to_digest(&self.to_field_elements()))
+    }
+
+    /// One score per committed category, in commitment order
+    ///
+    /// Categories the user has no score in contribute zero, so the trace shape
+    /// depends only on the size of the set.
+    pub fn select(&self, user_scores: &[(RepIDCategory, u32)]) -> Vec<(RepIDCategory, u32)> {
+        self.categories
+            .iter()
+            .map(|category| {
+                let score = user_scores
+                    .iter()
+                    .filter(|(c, _)| c == category)
+                    .fold(0u32, |total, (_, score)| total.saturating_add(*score));
+                (category.clone(), score)
+            })
+            .collect()
+    }
+
+    /// Reject empty sets, which would prove nothing, and categories listed twice
+    pub fn check(&self) -> Result<()> {
+        if self.categories.is_empty() {
+            return Err(ZKPError::InvalidInput("Hidden category set is empty".to_string()));
+        }
+        for (i, category) in self.categories.iter().enumerate() {
+            if self.categories[..i].contains(category) {
+                return Err(ZKPError::InvalidInput(format!("Category {:?} is committed twice", category)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read the category set commitment a verified proof was made for
+pub fn proof_category_commitment(proof: &RepIDProof) -> Result<CategorySetCommitment> {
+    if proof.metadata.operation_type != HIDDEN_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no category set commitment",
+            proof.metadata.operation_type
+        )));
+    }
+    proof
+        .public_inputs
+        .get(2..2 + DIGEST_ELEMENTS)
+        .and_then(|commitment| commitment.try_into().ok())
+        .map(CategorySetCommitment)
+        .ok_or_else(|| ZKPError::MalformedProof("Hidden-category proof needs a category commitment input".to_string()))
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_category_set_stays_hidden() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 100,
+            categories: Vec::new(),
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let category_set = CategorySetOpening::new(vec![RepIDCategory::DeFi, RepIDCategory::Governance], F::new(987_654));
+        let scores = [(RepIDCategory::DeFi, 90), (RepIDCategory::Governance, 30), (RepIDCategory::Community, 500)];
+
+        let result = zkp_system.prove_hidden_threshold_verification(&request, &scores, &category_set, "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert!(result.metadata.categories_verified.is_empty());
+        assert_eq!(result.metadata.categories_commitment, Some(category_set.commit()));
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+        assert_eq!(proof_category_commitment(&result.proof).unwrap(), category_set.commit());
+        assert_eq!(result.proof.public_inputs[2..2 + DIGEST_ELEMENTS], category_set.commit().0);
+
+        // No category tag appears among the public inputs
+        let tags: Vec<F> = [RepIDCategory::DeFi, RepIDCategory::Governance].iter().map(RepIDCategory::field_tag).collect();
+        assert!(result.proof.public_inputs.iter().all(|input| !tags.contains(input)));
+
+        // The commitment binds the blinding as well as the categories
+        let reblinded = CategorySetOpening::new(category_set.categories.clone(), F::new(1));
+        assert_ne!(reblinded.commit(), category_set.commit());
+
+        let doubled = CategorySetOpening::new(vec![RepIDCategory::DeFi, RepIDCategory::DeFi], F::new(5));
+        assert!(zkp_system.prove_hidden_threshold_verification(&request, &scores, &doubled, "0xtest").is_err());
+    }
+}
@@ -2,44 +2,77 @@
 //! 
 //! Implements ANFIS-inspired scoring with decay mechanics and multiplicative factors
 
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-
-use crate::{RepIDCategory, DecayParameters, F};
+use std::path::Path;
+
+use crate::air::WeightedScoreAir;
+use crate::custom_stark::ExecutionTrace;
+use crate::decay::decay_span;
+use crate::fixed_point::Q16;
+use crate::normalization::{normalize_scores, ScoreScale};
+use crate::privacy::{NoisyScoreComponents, PrivacyBudget};
+use crate::saturation::{apply_caps, CategoryCap};
+use crate::slashing::{total_penalties, PenaltyEvent};
+use crate::synergy::{evaluation_order, GroupSynergy, SynergyStacking};
+use crate::taxonomy::CategoryTaxonomy;
+use crate::{RepIDCategory, DecayParameters, Result, ZKPError, F};
+
+/// Current scoring profile format version
+///
+/// Version 2 stores weights and synergy multipliers as Q16.16 integers.
+pub const SCORING_PROFILE_VERSION: u32 = 2;
 
 /// Hierarchical scoring engine for RepID calculations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "ScoringProfile", try_from = "ScoringProfile")]
 pub struct HierarchicalScorer {
     /// Base scoring weights for each category
-    pub category_weights: HashMap<RepIDCategory, f32>,
+    pub category_weights: HashMap<RepIDCategory, Q16>,
     /// Time-based decay configuration
     pub decay_config: Option<DecayParameters>,
     /// Multiplicative factors for cross-category synergies
-    pub synergy_matrix: HashMap<(RepIDCategory, RepIDCategory), f32>,
+    pub synergy_matrix: HashMap<(RepIDCategory, RepIDCategory), Q16>,
+    /// ANFIS-style fuzzy rule set
+    pub fuzzy_rules: Vec<FuzzyRule>,
+    /// Maximum contribution and saturation curve per category
+    pub category_caps: Vec<CategoryCap>,
+    /// Synergies across three or more categories, evaluated before pairs
+    pub group_synergies: Vec<GroupSynergy>,
+    /// Parent links folding app-defined categories into top-level ones
+    pub taxonomy: CategoryTaxonomy,
+    /// Issuer scales mapped onto the canonical 0–1000 scale before scoring
+    pub score_scales: Vec<ScoreScale>,
 }
 
 impl HierarchicalScorer {
     /// Create a new hierarchical scorer with default weights
     pub fn new() -> Self {
         let mut category_weights = HashMap::new();
-        category_weights.insert(RepIDCategory::Governance, 1.0);
-        category_weights.insert(RepIDCategory::Community, 0.8);
-        category_weights.insert(RepIDCategory::Technical, 1.2);
-        category_weights.insert(RepIDCategory::FaithTech, 0.9);
-        category_weights.insert(RepIDCategory::DeFi, 1.1);
+        category_weights.insert(RepIDCategory::Governance, Q16::ONE);
+        category_weights.insert(RepIDCategory::Community, Q16::from_ratio(4, 5));
+        category_weights.insert(RepIDCategory::Technical, Q16::from_ratio(6, 5));
+        category_weights.insert(RepIDCategory::FaithTech, Q16::from_ratio(9, 10));
+        category_weights.insert(RepIDCategory::DeFi, Q16::from_ratio(11, 10));
 
         let mut synergy_matrix = HashMap::new();
         // Governance + Technical = leadership bonus
-        synergy_matrix.insert((RepIDCategory::Governance, RepIDCategory::Technical), 1.3);
+        synergy_matrix.insert((RepIDCategory::Governance, RepIDCategory::Technical), Q16::from_ratio(13, 10));
         // Community + FaithTech = purpose alignment bonus
-        synergy_matrix.insert((RepIDCategory::Community, RepIDCategory::FaithTech), 1.25);
+        synergy_matrix.insert((RepIDCategory::Community, RepIDCategory::FaithTech), Q16::from_ratio(5, 4));
         // Technical + DeFi = innovation bonus
-        synergy_matrix.insert((RepIDCategory::Technical, RepIDCategory::DeFi), 1.2);
+        synergy_matrix.insert((RepIDCategory::Technical, RepIDCategory::DeFi), Q16::from_ratio(6, 5));
 
         Self {
             category_weights,
             decay_config: None,
             synergy_matrix,
+            fuzzy_rules: default_fuzzy_rules(),
+            category_caps: Vec::new(),
+            group_synergies: Vec::new(),
+            taxonomy: CategoryTaxonomy::default(),
+            score_scales: Vec::new(),
         }
     }
 
@@ -50,95 +83,239 @@ impl HierarchicalScorer {
     }
 
     /// Add custom category weight
-    pub fn set_category_weight(&mut self, category: RepIDCategory, weight: f32) {
+    pub fn set_category_weight(&mut self, category: RepIDCategory, weight: Q16) {
         self.category_weights.insert(category, weight);
     }
 
+    /// Cap a category's contribution, replacing any existing cap
+    pub fn set_category_cap(&mut self, cap: CategoryCap) {
+        self.category_caps.retain(|c| c.category != cap.category);
+        self.category_caps.push(cap);
+    }
+
+    /// Normalize a category's issuer scale, replacing any existing scale
+    pub fn set_score_scale(&mut self, scale: ScoreScale) {
+        self.score_scales.retain(|s| s.category != scale.category);
+        self.score_scales.push(scale);
+    }
+
+    /// Configured weight of a category in Q16.16 (1.0 when unset)
+    pub fn weight_q16(&self, category: &RepIDCategory) -> Q16 {
+        self.category_weights.get(category).copied().unwrap_or(Q16::ONE)
+    }
+
+    /// Weighted base-score section: witness trace and the AIR checking it
+    pub fn weighted_base_trace(&self, user_scores: &[(RepIDCategory, u32)]) -> (ExecutionTrace, WeightedScoreAir) {
+        let weights: Vec<Q16> = user_scores.iter().map(|(category, _)| self.weight_q16(category)).collect();
+        let air = WeightedScoreAir::new(0, weights);
+        let scores: Vec<u32> = user_scores.iter().map(|(_, score)| *score).collect();
+        (air.generate_trace(&scores), air)
+    }
+
     /// Add synergy between two categories
-    pub fn set_synergy(&mut self, cat1: RepIDCategory, cat2: RepIDCategory, multiplier: f32) {
+    pub fn set_synergy(&mut self, cat1: RepIDCategory, cat2: RepIDCategory, multiplier: Q16) {
         self.synergy_matrix.insert((cat1.clone(), cat2.clone()), multiplier);
         self.synergy_matrix.insert((cat2, cat1), multiplier); // Symmetric
     }
 
+    /// Add a group synergy, replacing any existing group over the same categories
+    pub fn add_group_synergy(&mut self, group: GroupSynergy) {
+        self.group_synergies.retain(|g| g.categories != group.categories);
+        self.group_synergies.push(group);
+    }
+
     /// Calculate hierarchical score with decay and synergies
+    ///
+    /// With a `privacy` budget the result also carries a noised analytics view;
+    /// the exact components remain the circuit witness.
     pub fn calculate_score(
         &self,
         user_scores: &[(RepIDCategory, u32)],
         timestamp: u64,
         time_window: u64,
+        privacy: Option<&mut PrivacyBudget>,
     ) -> ScoreResult {
-        let mut base_score = 0.0;
+        self.calculate_score_with_penalties(user_scores, &[], timestamp, time_window, privacy)
+    }
+
+    /// Score many users in parallel, e.g. for nightly recomputation
+    ///
+    /// Privacy budgets are per-release and stateful, so batch results carry no analytics.
+    pub fn calculate_scores_batch(&self, users: &[UserScores]) -> Vec<ScoreResult> {
+        let mut results = Vec::new();
+        self.calculate_scores_batch_into(users, &mut results);
+        results
+    }
+
+    /// Batch scoring into a caller-owned buffer, reusing its allocation across runs
+    pub fn calculate_scores_batch_into(&self, users: &[UserScores], results: &mut Vec<ScoreResult>) {
+        users
+            .par_iter()
+            .map(|user| {
+                self.calculate_score_with_penalties(&user.scores, &user.penalties, user.timestamp, user.time_window, None)
+            })
+            .collect_into_vec(results);
+    }
+
+    /// Calculate the score net of slashing penalties
+    ///
+    /// Penalties are subtracted last and are not decayed; `net_score` may be negative.
+    pub fn calculate_score_with_penalties(
+        &self,
+        user_scores: &[(RepIDCategory, u32)],
+        penalties: &[PenaltyEvent],
+        timestamp: u64,
+        time_window: u64,
+        privacy: Option<&mut PrivacyBudget>,
+    ) -> ScoreResult {
+        // App-defined categories count towards their top-level parents, on the canonical scale
+        let rolled_up = normalize_scores(&self.score_scales, &self.taxonomy.roll_up(user_scores));
+        let user_scores = &rolled_up[..];
+
+        // Decay each category on its own schedule before weighting
+        let decayed_scores;
+        let decay_applied = match &self.decay_config {
+            Some(decay_params) => {
+                decayed_scores = decay_params.decay_scores(user_scores, decay_span(timestamp, time_window));
+                true
+            }
+            _ => {
+                decayed_scores = user_scores.to_vec();
+                false
+            }
+        };
+        // Saturate each category so none can dominate the aggregate
+        let capped_scores = apply_caps(&self.category_caps, &decayed_scores);
+        let raw_scores = user_scores;
+        let user_scores = &capped_scores[..];
+        let mut breakdown = ScoreBreakdown {
+            decay_amount: points_removed(raw_scores, &decayed_scores),
+            saturation_amount: points_removed(&decayed_scores, &capped_scores),
+            penalties: penalties.to_vec(),
+            ..ScoreBreakdown::default()
+        };
+
+        let mut base_score = Q16::ZERO;
         let mut active_categories = Vec::new();
 
-        // Calculate base weighted scores
-        for (category, raw_score) in user_scores {
-            if *raw_score > 0 {
+        // Calculate base weighted scores (exact: integer score times Q16 weight)
+        for ((category, raw_score), (_, effective_score)) in raw_scores.iter().zip(user_scores) {
+            if *effective_score > 0 {
                 active_categories.push(category.clone());
-                
-                let weight = self.category_weights.get(category).unwrap_or(&1.0);
-                base_score += (*raw_score as f32) * weight;
+
+                let weight = self.weight_q16(category);
+                let contribution = weight.mul_int(*effective_score as i64);
+                base_score = base_score + contribution;
+                breakdown.categories.push(CategoryContribution {
+                    category: category.clone(),
+                    raw_score: *raw_score,
+                    effective_score: *effective_score,
+                    weight,
+                    contribution,
+                });
+            }
+        }
+
+        // Apply synergy multipliers: groups (largest first), then pairs
+        let score_of = |category: &RepIDCategory| {
+            user_scores.iter().find(|(c, _)| c == category).map_or(0, |(_, s)| *s as i64)
+        };
+        let mut synergy_bonus = Q16::ZERO;
+        let mut exclusive: Vec<&GroupSynergy> = Vec::new();
+        for group in evaluation_order(&self.group_synergies) {
+            if !group.is_active(&active_categories) || exclusive.iter().any(|g| g.covers(&group.categories)) {
+                continue;
+            }
+            let multiplier = group.multiplier;
+            let bonus = (multiplier - Q16::ONE).mul_int(group.categories.iter().map(score_of).sum());
+            synergy_bonus = synergy_bonus + bonus;
+            breakdown.synergies.push(SynergyContribution {
+                categories: group.categories.clone(),
+                multiplier,
+                bonus,
+            });
+            if group.stacking == SynergyStacking::Exclusive {
+                exclusive.push(group);
             }
         }
 
-        // Apply synergy multipliers
-        let mut synergy_bonus = 0.0;
         for i in 0..active_categories.len() {
             for j in (i + 1)..active_categories.len() {
                 let cat1 = &active_categories[i];
                 let cat2 = &active_categories[j];
-                
+                if exclusive.iter().any(|g| g.covers(&[cat1.clone(), cat2.clone()])) {
+                    continue;
+                }
+
                 if let Some(&multiplier) = self.synergy_matrix.get(&(cat1.clone(), cat2.clone())) {
-                    let score1 = user_scores.iter()
-                        .find(|(c, _)| c == cat1)
-                        .map(|(_, s)| *s as f32)
-                        .unwrap_or(0.0);
-                    let score2 = user_scores.iter()
-                        .find(|(c, _)| c == cat2)
-                        .map(|(_, s)| *s as f32)
-                        .unwrap_or(0.0);
-                        
-                    synergy_bonus += (score1 + score2) * (multiplier - 1.0);
+                    let bonus = (multiplier - Q16::ONE).mul_int(score_of(cat1) + score_of(cat2));
+                    synergy_bonus = synergy_bonus + bonus;
+                    breakdown.synergies.push(SynergyContribution {
+                        categories: vec![cat1.clone(), cat2.clone()],
+                        multiplier,
+                        bonus,
+                    });
                 }
             }
         }
 
         let mut final_score = base_score + synergy_bonus;
 
-        // Apply time-based decay if configured
-        let mut decay_applied = false;
-        if let Some(decay_params) = &self.decay_config {
-            if timestamp > time_window {
-                let time_diff = timestamp - time_window;
-                let decay_rate = decay_params.base_decay_rate as f32 / 10000.0; // Basis points to fraction
-                let decay_amount = final_score * decay_rate * (time_diff as f32 / 86400.0); // Daily decay
-                
-                final_score -= decay_amount;
-                decay_applied = true;
-
-                // Apply minimum threshold
-                if final_score < decay_params.min_threshold as f32 {
-                    final_score = decay_params.min_threshold as f32;
-                }
-            }
+        // Decay stops at the configured minimum
+        if let Some(decay_params) = self.decay_config.as_ref().filter(|_| decay_applied) {
+            let floored = final_score.max(Q16::from_int(decay_params.min_threshold as i64));
+            breakdown.floor_adjustment = floored - final_score;
+            final_score = floored;
         }
 
         // Apply multiplicative factor for sustained activity
-        let multiplicative_bonus = if let Some(decay_params) = &self.decay_config {
-            active_categories.len() as f32 * decay_params.multiplicative_factor
-        } else {
-            0.0
+        let multiplicative_bonus = match &self.decay_config {
+            Some(decay_params) => decay_params.multiplicative_factor.mul_int(active_categories.len() as i64),
+            None => Q16::ZERO,
         };
 
-        final_score += multiplicative_bonus;
+        final_score = final_score + multiplicative_bonus;
+        breakdown.multiplicative_bonus = multiplicative_bonus;
+        breakdown.matched_rules = self.fuzzy_rules.iter()
+            .filter(|rule| rule.matches(user_scores))
+            .map(|rule| RuleMatch {
+                description: rule.description.clone(),
+                output_multiplier: rule.output_multiplier,
+            })
+            .collect();
+
+        let penalty_total = total_penalties(penalties);
+        let net_score = final_score.to_int() - penalty_total as i64;
+
+        let components = [
+            base_score.to_u32(),
+            synergy_bonus.to_u32(),
+            multiplicative_bonus.to_u32(),
+            net_score.clamp(0, u32::MAX as i64) as u32,
+        ];
+        let analytics = privacy.and_then(|budget| {
+            let epsilon = budget.epsilon_per_release();
+            budget.release(&components).map(|noised| NoisyScoreComponents {
+                base_score: noised[0],
+                synergy_bonus: noised[1],
+                multiplicative_bonus: noised[2],
+                final_score: noised[3],
+                epsilon,
+            })
+        });
 
         ScoreResult {
-            base_score: base_score as u32,
-            synergy_bonus: synergy_bonus as u32,
-            multiplicative_bonus: multiplicative_bonus as u32,
-            final_score: final_score as u32,
+            base_score: components[0],
+            synergy_bonus: components[1],
+            multiplicative_bonus: components[2],
+            final_score: components[3],
+            penalties: penalty_total,
+            net_score,
             active_categories,
             decay_applied,
             timestamp,
+            analytics,
+            breakdown,
         }
     }
 
@@ -152,47 +329,172 @@ impl HierarchicalScorer {
         elements.push(F::from_u32(score_result.final_score));
         elements.push(F::new(score_result.timestamp));
         elements.push(F::from_u32(if score_result.decay_applied { 1 } else { 0 }));
+        elements.push(F::from_u32(score_result.penalties));
+        elements.push(F::from_i64(score_result.net_score));
         
         elements
     }
 
-    /// Generate ANFIS-style fuzzy rules for dynamic scoring
+    /// Fuzzy rules for dynamic scoring
     pub fn generate_fuzzy_rules(&self) -> Vec<FuzzyRule> {
-        let mut rules = Vec::new();
-
-        // Rule 1: High governance + High technical = Leadership tier
-        rules.push(FuzzyRule {
-            conditions: vec![
-                (RepIDCategory::Governance, ScoreRange::High),
-                (RepIDCategory::Technical, ScoreRange::High),
-            ],
-            output_multiplier: 1.5,
-            description: "Leadership tier - Strong governance and technical skills".to_string(),
-        });
+        self.fuzzy_rules.clone()
+    }
 
-        // Rule 2: High community + High faith-tech = Purpose-driven tier
-        rules.push(FuzzyRule {
-            conditions: vec![
-                (RepIDCategory::Community, ScoreRange::High),
-                (RepIDCategory::FaithTech, ScoreRange::High),
-            ],
-            output_multiplier: 1.3,
-            description: "Purpose-driven tier - Strong community and faith-tech alignment".to_string(),
-        });
+    /// Snapshot of the scoring rules as a versioned profile
+    pub fn profile(&self) -> ScoringProfile {
+        self.clone().into()
+    }
+}
 
-        // Rule 3: Multiple medium scores = Well-rounded bonus
-        rules.push(FuzzyRule {
-            conditions: vec![
-                (RepIDCategory::Governance, ScoreRange::Medium),
-                (RepIDCategory::Community, ScoreRange::Medium),
-                (RepIDCategory::Technical, ScoreRange::Medium),
-            ],
-            output_multiplier: 1.2,
-            description: "Well-rounded contributor - Balanced across categories".to_string(),
-        });
+/// Default ANFIS-style fuzzy rules
+#[allow(clippy::vec_init_then_push)]
+fn default_fuzzy_rules() -> Vec<FuzzyRule> {
+    let mut rules = Vec::new();
+
+    // Rule 1: High governance + High technical = Leadership tier
+    rules.push(FuzzyRule {
+        conditions: vec![
+            (RepIDCategory::Governance, ScoreRange::High),
+            (RepIDCategory::Technical, ScoreRange::High),
+        ],
+        output_multiplier: 1.5,
+        description: "Leadership tier - Strong governance and technical skills".to_string(),
+    });
+
+    // Rule 2: High community + High faith-tech = Purpose-driven tier
+    rules.push(FuzzyRule {
+        conditions: vec![
+            (RepIDCategory::Community, ScoreRange::High),
+            (RepIDCategory::FaithTech, ScoreRange::High),
+        ],
+        output_multiplier: 1.3,
+        description: "Purpose-driven tier - Strong community and faith-tech alignment".to_string(),
+    });
+
+    // Rule 3: Multiple medium scores = Well-rounded bonus
+    rules.push(FuzzyRule {
+        conditions: vec![
+            (RepIDCategory::Governance, ScoreRange::Medium),
+            (RepIDCategory::Community, ScoreRange::Medium),
+            (RepIDCategory::Technical, ScoreRange::Medium),
+        ],
+        output_multiplier: 1.2,
+        description: "Well-rounded contributor - Balanced across categories".to_string(),
+    });
+
+    rules
+}
+
+/// Serialized scoring rules, stamped into proofs by hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoringProfile {
+    pub version: u32,
+    /// Category weights in category order
+    pub category_weights: Vec<(RepIDCategory, Q16)>,
+    /// Synergy multipliers in category-pair order
+    pub synergies: Vec<(RepIDCategory, RepIDCategory, Q16)>,
+    pub decay_config: Option<DecayParameters>,
+    pub fuzzy_rules: Vec<FuzzyRule>,
+    #[serde(default)]
+    pub category_caps: Vec<CategoryCap>,
+    #[serde(default)]
+    pub group_synergies: Vec<GroupSynergy>,
+    #[serde(default)]
+    pub taxonomy: CategoryTaxonomy,
+    #[serde(default)]
+    pub score_scales: Vec<ScoreScale>,
+}
+
+impl ScoringProfile {
+    /// Read a JSON profile, rejecting versions this build does not understand
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ZKPError::ConfigError(format!("Failed to read {}: {}", path.display(), e)))?;
+        let profile: Self = serde_json::from_str(&contents)
+            .map_err(|e| ZKPError::ConfigError(format!("Invalid scoring profile {}: {}", path.display(), e)))?;
+        profile.check_version()?;
+        Ok(profile)
+    }
+
+    /// Write the profile as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| ZKPError::SerializationError(e.to_string()))?;
+        std::fs::write(path, contents)
+            .map_err(|e| ZKPError::ConfigError(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    pub(crate) fn check_version(&self) -> Result<()> {
+        if self.version == 0 || self.version > SCORING_PROFILE_VERSION {
+            return Err(ZKPError::ConfigError(format!(
+                "Unsupported scoring profile version {} (this build reads up to {})",
+                self.version, SCORING_PROFILE_VERSION
+            )));
+        }
+        Ok(())
+    }
 
-        rules
+    /// Blake3 hash of the canonical JSON encoding
+    pub fn hash(&self) -> [u8; 32] {
+        let canonical = serde_json::to_vec(self).expect("scoring profile serializes");
+        *blake3::hash(&canonical).as_bytes()
     }
+
+    /// Hex profile hash, as recorded in `VerificationMetadata`
+    pub fn hash_hex(&self) -> String {
+        hex::encode(self.hash())
+    }
+}
+
+impl From<HierarchicalScorer> for ScoringProfile {
+    fn from(scorer: HierarchicalScorer) -> Self {
+        let mut category_weights: Vec<_> = scorer.category_weights.into_iter().collect();
+        category_weights.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut synergies: Vec<_> = scorer.synergy_matrix.into_iter().map(|((a, b), m)| (a, b, m)).collect();
+        synergies.sort_by(|x, y| (&x.0, &x.1).cmp(&(&y.0, &y.1)));
+
+        Self {
+            version: SCORING_PROFILE_VERSION,
+            category_weights,
+            synergies,
+            decay_config: scorer.decay_config,
+            fuzzy_rules: scorer.fuzzy_rules,
+            category_caps: scorer.category_caps,
+            group_synergies: scorer.group_synergies,
+            taxonomy: scorer.taxonomy,
+            score_scales: scorer.score_scales,
+        }
+    }
+}
+
+impl TryFrom<ScoringProfile> for HierarchicalScorer {
+    type Error = ZKPError;
+
+    fn try_from(profile: ScoringProfile) -> Result<Self> {
+        profile.check_version()?;
+        Ok(Self {
+            category_weights: profile.category_weights.into_iter().collect(),
+            decay_config: profile.decay_config,
+            synergy_matrix: profile.synergies.into_iter().map(|(a, b, m)| ((a, b), m)).collect(),
+            fuzzy_rules: profile.fuzzy_rules,
+            category_caps: profile.category_caps,
+            group_synergies: profile.group_synergies,
+            taxonomy: profile.taxonomy,
+            score_scales: profile.score_scales,
+        })
+    }
+}
+
+/// One user's inputs to a batch scoring run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserScores {
+    pub scores: Vec<(RepIDCategory, u32)>,
+    #[serde(default)]
+    pub penalties: Vec<PenaltyEvent>,
+    pub timestamp: u64,
+    pub time_window: u64,
 }
 
 /// Result of hierarchical scoring calculation
@@ -204,18 +506,87 @@ pub struct ScoreResult {
     pub synergy_bonus: u32,
     /// Bonus for sustained activity
     pub multiplicative_bonus: u32,
-    /// Final calculated score
+    /// Final calculated score (net of penalties, floored at zero)
     pub final_score: u32,
+    /// Points removed by slashing penalties
+    #[serde(default)]
+    pub penalties: u32,
+    /// Signed score after penalties
+    #[serde(default)]
+    pub net_score: i64,
     /// Categories with non-zero scores
     pub active_categories: Vec<RepIDCategory>,
     /// Whether time-based decay was applied
     pub decay_applied: bool,
     /// Timestamp used for calculation
     pub timestamp: u64,
+    /// Noised components for analytics export (exact fields stay witness-only)
+    #[serde(default)]
+    pub analytics: Option<NoisyScoreComponents>,
+    /// Per-component derivation of the score
+    #[serde(default)]
+    pub breakdown: ScoreBreakdown,
+}
+
+impl ScoreResult {
+    /// Structured "why is my score X" breakdown
+    pub fn explain(&self) -> &ScoreBreakdown {
+        &self.breakdown
+    }
+}
+
+/// Per-component derivation of a score, in the order the scorer applies them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    /// Weighted contribution of each active category
+    pub categories: Vec<CategoryContribution>,
+    /// Bonus from each group and pairwise synergy, in evaluation order
+    pub synergies: Vec<SynergyContribution>,
+    /// Points removed by time decay, over all categories
+    pub decay_amount: u32,
+    /// Points removed by category caps
+    pub saturation_amount: u32,
+    /// Lift applied when decay fell below the minimum threshold
+    pub floor_adjustment: Q16,
+    pub multiplicative_bonus: Q16,
+    pub penalties: Vec<PenaltyEvent>,
+    /// Fuzzy rules whose conditions the effective scores meet
+    pub matched_rules: Vec<RuleMatch>,
+}
+
+/// One category's path from raw score to weighted contribution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryContribution {
+    pub category: RepIDCategory,
+    pub raw_score: u32,
+    /// Score after decay and caps
+    pub effective_score: u32,
+    pub weight: Q16,
+    pub contribution: Q16,
+}
+
+/// Bonus earned by an active synergy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SynergyContribution {
+    pub categories: Vec<RepIDCategory>,
+    pub multiplier: Q16,
+    pub bonus: Q16,
+}
+
+/// Fuzzy rule that fired for a score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleMatch {
+    pub description: String,
+    pub output_multiplier: f32,
+}
+
+/// Points lost between two stages of the same score vector
+fn points_removed(before: &[(RepIDCategory, u32)], after: &[(RepIDCategory, u32)]) -> u32 {
+    before.iter().zip(after).map(|((_, b), (_, a))| b.saturating_sub(*a)).sum()
 }
 
 /// Fuzzy rule for ANFIS-style scoring
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FuzzyRule {
     /// Conditions that must be met
     pub conditions: Vec<(RepIDCategory, ScoreRange)>,
@@ -225,8 +596,18 @@ pub struct FuzzyRule {
     pub description: String,
 }
 
+impl FuzzyRule {
+    /// Whether every condition holds for `scores` (missing categories score 0)
+    pub fn matches(&self, scores: &[(RepIDCategory, u32)]) -> bool {
+        self.conditions.iter().all(|(category, range)| {
+            let score = scores.iter().find(|(c, _)| c == category).map_or(0, |(_, s)| *s);
+            ScoreRange::from_score(score) == *range
+        })
+    }
+}
+
 /// Score ranges for fuzzy logic
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ScoreRange {
     Low,      // 0-33
     Medium,   // 34-66
@@ -265,7 +646,7 @@ mod tests {
             (RepIDCategory::Community, 50),
         ];
 
-        let result = scorer.calculate_score(&user_scores, 1000000000, 999999999);
+        let result = scorer.calculate_score(&user_scores, 1000000000, 999999999, None);
         
         assert!(result.final_score > 0);
         assert_eq!(result.active_categories.len(), 3);
@@ -276,8 +657,9 @@ mod tests {
     fn test_decay_application() {
         let decay_params = DecayParameters {
             base_decay_rate: 500, // 5%
-            multiplicative_factor: 1.2,
+            multiplicative_factor: Q16::from_ratio(6, 5),
             min_threshold: 10,
+            category_decay: Vec::new(),
         };
         
         let scorer = HierarchicalScorer::new().with_decay(decay_params);
@@ -287,7 +669,132 @@ mod tests {
         ];
 
         // Test with old timestamp (should trigger decay)
-        let result = scorer.calculate_score(&user_scores, 2000000000, 1000000000);
+        let result = scorer.calculate_score(&user_scores, 2000000000, 1000000000, None);
         assert!(result.decay_applied);
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[cfg(feature = "prover")]
+    fn test_scoring_profile_round_trip_and_stamp() {
+        let mut scorer = HierarchicalScorer::new().with_decay(DecayParameters {
+            base_decay_rate: 250,
+            multiplicative_factor: Q16::from_ratio(11, 10),
+            min_threshold: 5,
+            category_decay: Vec::new(),
+        });
+        scorer.set_synergy(RepIDCategory::DeFi, RepIDCategory::Governance, Q16::from_ratio(23, 20));
+        let profile = scorer.profile();
+        assert_eq!(profile.version, SCORING_PROFILE_VERSION);
+
+        let path = std::env::temp_dir().join(format!("repid_profile_{}.json", std::process::id()));
+        profile.save(&path).unwrap();
+        let loaded = ScoringProfile::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.hash(), profile.hash());
+
+        // The scorer itself round-trips through the profile format
+        let json = serde_json::to_string(&scorer).unwrap();
+        let restored: HierarchicalScorer = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.profile().hash(), profile.hash());
+        let scores = [(RepIDCategory::DeFi, 40), (RepIDCategory::Governance, 60)];
+        assert_eq!(
+            restored.calculate_score(&scores, 10, 20, None).final_score,
+            scorer.calculate_score(&scores, 10, 20, None).final_score
+        );
+
+        let mut future = profile.clone();
+        future.version = SCORING_PROFILE_VERSION + 1;
+        assert!(serde_json::from_str::<HierarchicalScorer>(&serde_json::to_string(&future).unwrap()).is_err());
+
+        let mut zkp_system = crate::RepIDZKPSystem::new(crate::SecurityLevel::Fast).with_scoring_profile(&profile);
+        let request = crate::ThresholdVerificationRequest {
+            threshold: 30,
+            categories: vec![RepIDCategory::DeFi],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = zkp_system.prove_threshold_verification(&request, &scores, "0xtest").unwrap();
+        assert_eq!(result.metadata.scoring_profile, Some(profile.hash_hex()));
+    }
+
+    #[test]
+    fn test_fixed_point_scorer_matches_circuit_encoding() {
+        let scorer = HierarchicalScorer::new();
+        let categories = [
+            RepIDCategory::Governance,
+            RepIDCategory::Community,
+            RepIDCategory::Technical,
+            RepIDCategory::FaithTech,
+            RepIDCategory::DeFi,
+        ];
+
+        // Every category at every score: the reported base score is the proven integer part
+        for category in &categories {
+            for score in 0..=1000u32 {
+                let scores = [(category.clone(), score)];
+                let result = scorer.calculate_score(&scores, 0, 0, None);
+                let (trace, air) = scorer.weighted_base_trace(&scores);
+                assert!(crate::air::check_witness(&trace, &air).is_ok());
+                assert_eq!(trace.get(0, air.integer_column()).to_i64(), result.base_score as i64);
+            }
+        }
+
+        // Pinned values guard against platform-dependent arithmetic creeping back in
+        let result = scorer.calculate_score(
+            &[(RepIDCategory::Governance, 75), (RepIDCategory::Technical, 85), (RepIDCategory::Community, 50)],
+            0,
+            0,
+            None,
+        );
+        assert_eq!((result.base_score, result.synergy_bonus, result.final_score), (216, 48, 265));
+    }
+
+    #[test]
+    fn test_explain_accounts_for_every_component() {
+        let mut scorer = HierarchicalScorer::new();
+        scorer.set_category_cap(CategoryCap::new(
+            RepIDCategory::Technical,
+            80,
+            crate::saturation::SaturationCurve::HardCap,
+        ));
+        let scores = [(RepIDCategory::Governance, 75), (RepIDCategory::Technical, 95)];
+        let result = scorer.calculate_score(&scores, 0, 0, None);
+        let explanation = result.explain();
+
+        assert_eq!(explanation.categories.len(), 2);
+        assert_eq!(explanation.categories[1].effective_score, 80);
+        assert_eq!(explanation.saturation_amount, 15);
+        assert_eq!(explanation.synergies[0].categories, [RepIDCategory::Governance, RepIDCategory::Technical]);
+        assert_eq!(explanation.matched_rules[0].output_multiplier, 1.5);
+
+        let base = explanation.categories.iter().fold(Q16::ZERO, |acc, c| acc + c.contribution);
+        let synergy = explanation.synergies.iter().fold(Q16::ZERO, |acc, s| acc + s.bonus);
+        assert_eq!(base.to_u32(), result.base_score);
+        assert_eq!((base + synergy).to_u32(), result.final_score);
+    }
+
+    #[test]
+    fn test_batch_scoring_matches_sequential() {
+        let scorer = HierarchicalScorer::new();
+        let users: Vec<UserScores> = (0..64u32)
+            .map(|i| UserScores {
+                scores: vec![(RepIDCategory::Governance, i * 3), (RepIDCategory::Technical, 200 - i)],
+                penalties: vec![PenaltyEvent::new(RepIDCategory::Technical, i % 5, "batch", 0)],
+                timestamp: 0,
+                time_window: 0,
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(users.len());
+        scorer.calculate_scores_batch_into(&users, &mut results);
+        let capacity = results.capacity();
+        scorer.calculate_scores_batch_into(&users, &mut results);
+        assert_eq!(results.capacity(), capacity);
+
+        for (user, batched) in users.iter().zip(scorer.calculate_scores_batch(&users)) {
+            let single = scorer.calculate_score_with_penalties(&user.scores, &user.penalties, 0, 0, None);
+            assert_eq!((batched.final_score, batched.net_score), (single.final_score, single.net_score));
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Score Histories
+//!
+//! Threshold proofs over a wallet's individual score events rather than
+//! pre-summed category totals: each event occupies one trace row, and the
+//! circuit accumulates them in time order within the request's window
+
+use serde::{Deserialize, Serialize};
+
+use crate::{RepIDCategory, RepIDProof, Result, ZKPError};
+
+/// Operation type of score history threshold proofs
+pub const HISTORY_THRESHOLD_OPERATION: &str = "history_threshold";
+
+/// One change to a wallet's score
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreEvent {
+    pub category: RepIDCategory,
+    /// Points gained, or lost when negative
+    pub delta: i64,
+    pub timestamp: u64,
+}
+
+impl ScoreEvent {
+    pub fn new(category: RepIDCategory, delta: i64, timestamp: u64) -> Self {
+        Self {
+            category,
+            delta,
+            timestamp,
+        }
+    }
+}
+
+/// Events in `categories` that fall within `time_window` before `as_of`, oldest first
+pub fn events_in_window(
+    events: &[ScoreEvent],
+    categories: &[RepIDCategory],
+    time_window: u64,
+    as_of: u64,
+) -> Vec<ScoreEvent> {
+    let window_start = as_of.saturating_sub(time_window);
+    let mut selected: Vec<ScoreEvent> = events
+        .iter()
+        .filter(|event| categories.contains(&event.category))
+        .filter(|event| (window_start..=as_of).contains(&event.timestamp))
+        .cloned()
+        .collect();
+    selected.sort_by_key(|event| event.timestamp);
+    selected
+}
+
+/// Trace height for `num_events` events: one row each, padded to a power of two
+pub fn trace_height(num_events: usize) -> usize {
+    num_events.next_power_of_two().max(8)
+}
+
+/// Public statement of a score history threshold proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryStatement {
+    pub threshold: u32,
+    pub time_window: u64,
+    /// Evaluation instant closing the window
+    pub as_of: u64,
+}
+
+/// Read the statement a verified score history proof was made for
+pub fn proof_statement(proof: &RepIDProof) -> Result<HistoryStatement> {
+    if proof.metadata.operation_type != HISTORY_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no score history statement",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(0..3) {
+        Some([threshold, time_window, as_of]) => Ok(HistoryStatement {
+            threshold: threshold.0 as u32,
+            time_window: time_window.0,
+            as_of: as_of.0,
+        }),
+        _ => Err(ZKPError::MalformedProof("Score history proof needs threshold, window and timestamp inputs".to_string())),
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn test_history_threshold_over_event_rows() {
+        let as_of = 400 * DAY;
+        let mut events: Vec<ScoreEvent> = (0..20)
+            .map(|day| ScoreEvent::new(RepIDCategory::Governance, 5, as_of - day * DAY))
+            .collect();
+        // Outside the window, and outside the requested categories
+        events.push(ScoreEvent::new(RepIDCategory::Governance, 500, as_of - 200 * DAY));
+        events.push(ScoreEvent::new(RepIDCategory::DeFi, 500, as_of - DAY));
+        events.push(ScoreEvent::new(RepIDCategory::Governance, -30, as_of - 3 * DAY));
+
+        let request = ThresholdVerificationRequest {
+            threshold: 60,
+            categories: vec![RepIDCategory::Governance],
+            time_window: 30 * DAY,
+            as_of,
+            decay_params: None,
+        };
+        assert_eq!(events_in_window(&events, &request.categories, request.time_window, as_of).len(), 21);
+        assert_eq!(trace_height(21), 32);
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let result = zkp_system.prove_history_threshold_verification(&request, &events, "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+        assert_eq!(
+            proof_statement(&result.proof).unwrap(),
+            HistoryStatement { threshold: 60, time_window: 30 * DAY, as_of }
+        );
+
+        // The 100 points in the window net of the 30 point penalty fall short of 80
+        let stricter = ThresholdVerificationRequest { threshold: 80, ..request.clone() };
+        let result = zkp_system.prove_history_threshold_verification(&stricter, &events, "0xtest").unwrap();
+        assert!(!result.meets_threshold);
+
+        // A longer history needs a taller trace than a short one
+        let short = zkp_system.prove_history_threshold_verification(&request, &events[..2], "0xtest").unwrap();
+        let depth = |proof: &RepIDProof| proof.info().unwrap().stark.unwrap().auth_path_depth;
+        let long_events: Vec<ScoreEvent> = (0..100)
+            .map(|i| ScoreEvent::new(RepIDCategory::Governance, 1, as_of - i * 60))
+            .collect();
+        let long = zkp_system.prove_history_threshold_verification(&request, &long_events, "0xtest").unwrap();
+        assert!(depth(&long.proof) > depth(&short.proof));
+    }
+}
@@ -0,0 +1,142 @@
+//! Prover Identity
+//!
+//! Ed25519 signatures over proof envelopes, letting relying parties check
+//! which proving service produced a proof before trusting it
+
+use std::sync::Arc;
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::signer::{InMemorySigner, SignatureScheme, Signer};
+use crate::{RepIDProof, Result, ZKPError};
+
+/// Ed25519 public key identifying a prover
+pub type ProverKey = [u8; 32];
+
+/// Prover signature carried in `ProofMetadata`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProverSignature {
+    pub public_key: ProverKey,
+    /// 64-byte Ed25519 signature over `signing_digest`
+    pub signature: Vec<u8>,
+    /// Key ring ID of the signing key, when managed by `keys::KeyRing`
+    #[serde(default)]
+    pub key_id: Option<String>,
+}
+
+/// Signing key of a proving service
+#[derive(Debug, Clone)]
+pub struct ProverIdentity {
+    signer: Arc<dyn Signer>,
+    public_key: ProverKey,
+}
+
+impl ProverIdentity {
+    /// Identity with an in-memory key (development and tests)
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self::from_signer(Arc::new(InMemorySigner::from_seed(seed))).expect("in-memory signers are Ed25519")
+    }
+
+    /// Identity backed by any Ed25519 `Signer`
+    pub fn from_signer(signer: Arc<dyn Signer>) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Ed25519 {
+            return Err(ZKPError::SigningError(format!("Prover identity needs Ed25519, signer uses {:?}", signer.scheme())));
+        }
+        let public_key = signer.public_key()?.try_into().map_err(|key: Vec<u8>| {
+            ZKPError::SigningError(format!("Expected a 32-byte Ed25519 public key, got {} bytes", key.len()))
+        })?;
+        Ok(Self { signer, public_key })
+    }
+
+    pub fn public_key(&self) -> ProverKey {
+        self.public_key
+    }
+
+    /// Sign the whole envelope, replacing any existing prover signature
+    pub fn sign(&self, proof: &mut RepIDProof) -> Result<()> {
+        let key_id = self.signer.key_id();
+        let digest = signing_digest(proof, key_id.as_deref());
+        proof.metadata.prover_signature = Some(ProverSignature {
+            public_key: self.public_key,
+            signature: self.signer.sign(&digest)?,
+            key_id,
+        });
+        Ok(())
+    }
+}
+
+/// Blake3 digest of the envelope with the signature and content address slots
+/// cleared, bound to the key ID, so a signed envelope can still be published
+pub fn signing_digest(proof: &RepIDProof, key_id: Option<&str>) -> [u8; 32] {
+    let mut unsigned = proof.clone();
+    unsigned.metadata.prover_signature = None;
+    unsigned.metadata.content_address = None;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"repid/prover-signature/v1");
+    hasher.update(&crate::protocol::encode_envelope(&unsigned).expect("proof serializes"));
+    hasher.update(key_id.unwrap_or_default().as_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+/// Key of the prover that signed `proof`, `None` when unsigned
+///
+/// A signature that does not verify is an error rather than `None`.
+pub fn verify_prover_signature(proof: &RepIDProof) -> Result<Option<ProverKey>> {
+    let Some(signed) = &proof.metadata.prover_signature else {
+        return Ok(None);
+    };
+    let key = VerifyingKey::from_bytes(&signed.public_key)
+        .map_err(|e| ZKPError::VerificationError(format!("Invalid prover key: {}", e)))?;
+    let signature = Signature::from_slice(&signed.signature)
+        .map_err(|e| ZKPError::VerificationError(format!("Malformed prover signature: {}", e)))?;
+    key.verify(&signing_digest(proof, signed.key_id.as_deref()), &signature)
+        .map_err(|_| ZKPError::VerificationError("Prover signature does not match the envelope".to_string()))?;
+    Ok(Some(signed.public_key))
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::policy::VerifyPolicy;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_signed_envelopes_and_trusted_provers() {
+        let trusted = ProverIdentity::from_seed([1; 32]);
+        let other = ProverIdentity::from_seed([2; 32]);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let scores = [(RepIDCategory::Community, 75)];
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_prover_identity(trusted.clone());
+        let proof = zkp_system.prove_threshold_verification(&request, &scores, "0xtest").unwrap().proof;
+        assert_eq!(verify_prover_signature(&proof).unwrap(), Some(trusted.public_key()));
+
+        let policy = VerifyPolicy::strict(SecurityLevel::Fast).with_trusted_provers(&[trusted.public_key()]);
+        assert!(zkp_system.verify_proof_with_policy(&proof, None, &policy).unwrap());
+
+        // Metadata edits invalidate the signature
+        let mut edited = proof.clone();
+        edited.metadata.wallet_hash = "someone-else".to_string();
+        assert!(verify_prover_signature(&edited).is_err());
+        assert!(zkp_system.verify_proof_with_policy(&edited, None, &policy).is_err());
+
+        let mut untrusted = proof.clone();
+        other.sign(&mut untrusted).unwrap();
+        assert!(matches!(
+            zkp_system.verify_proof_with_policy(&untrusted, None, &policy),
+            Err(ZKPError::PolicyViolation(_))
+        ));
+
+        let mut unsigned = proof;
+        unsigned.metadata.prover_signature = None;
+        assert!(zkp_system.verify_proof_with_policy(&unsigned, None, &VerifyPolicy::default()).unwrap());
+        assert!(zkp_system.verify_proof_with_policy(&unsigned, None, &policy).is_err());
+    }
+}
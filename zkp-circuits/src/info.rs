@@ -0,0 +1,142 @@
+//! Proof Introspection
+//!
+//! Structural details of a proof envelope, read without verifying it, for
+//! dashboards and support tooling
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::BackendKind;
+use crate::custom_stark::StarkProof;
+use crate::decoding::decode_stark_proof;
+use crate::limits::ProofLimits;
+use crate::{RepIDProof, Result, SecurityLevel};
+
+/// Structural summary of a proof envelope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofInfo {
+    pub operation_type: String,
+    pub backend: BackendKind,
+    /// Security level whose query count the proof matches, if any
+    pub security_level: Option<SecurityLevel>,
+    /// Estimated security of that level's parameters
+    pub estimated_security_bits: Option<f64>,
+    /// Encoded size of the whole envelope
+    pub envelope_bytes: u64,
+    /// STARK structure; `None` for backends whose proofs this crate doesn't decode
+    pub stark: Option<StarkProofInfo>,
+}
+
+/// Shape of a custom STARK proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StarkProofInfo {
+    pub fri_layers: usize,
+    pub query_count: usize,
+    /// Longest Merkle authentication path among the queries
+    pub auth_path_depth: usize,
+    pub final_poly_len: usize,
+    pub public_input_count: usize,
+    pub sizes: SectionSizes,
+}
+
+/// Encoded size of each section of a STARK proof in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionSizes {
+    /// Trace, LDE and quotient Merkle roots
+    pub roots: u64,
+    pub fri_commitments: u64,
+    pub final_poly: u64,
+    pub queries: u64,
+    /// Public inputs and their digest
+    pub public_inputs: u64,
+    /// Circuit shape
+    pub shape: u64,
+    /// Whole `proof_data`
+    pub total: u64,
+}
+
+impl RepIDProof {
+    /// Describe the proof's structure without verifying it
+    ///
+    /// Custom STARK payloads are decoded under the default limits, so a
+    /// malformed or oversized proof is an error here as in verification.
+    pub fn info(&self) -> Result<ProofInfo> {
+        let envelope_bytes = encoded_size(self)?;
+        let stark = match self.metadata.backend {
+            BackendKind::CustomStark => {
+                let proof = decode_stark_proof(&self.proof_data, &ProofLimits::default())?;
+                Some(stark_info(&proof, self.proof_data.len() as u64)?)
+            }
+        };
+
+        let security_level = stark.as_ref().and_then(|stark| {
+            [SecurityLevel::Fast, SecurityLevel::Standard, SecurityLevel::High]
+                .into_iter()
+                .find(|level| level.params().num_queries == stark.query_count)
+        });
+
+        Ok(ProofInfo {
+            operation_type: self.metadata.operation_type.clone(),
+            backend: self.metadata.backend,
+            security_level,
+            estimated_security_bits: security_level.map(|level| level.params().estimated_security_bits()),
+            envelope_bytes,
+            stark,
+        })
+    }
+}
+
+fn stark_info(proof: &StarkProof, total: u64) -> Result<StarkProofInfo> {
+    Ok(StarkProofInfo {
+        fri_layers: proof.fri_proof.commitments.len(),
+        query_count: proof.queries.len(),
+        auth_path_depth: proof.queries.iter().map(|query| query.quotient.auth_path.len()).max().unwrap_or(0),
+        final_poly_len: proof.fri_proof.final_poly.len(),
+        public_input_count: proof.public_inputs.len(),
+        sizes: SectionSizes {
+            roots: encoded_size(&(proof.trace_root, proof.lde_root, proof.quotient_root))?,
+            fri_commitments: encoded_size(&proof.fri_proof.commitments)?,
+            final_poly: encoded_size(&proof.fri_proof.final_poly)?,
+            queries: encoded_size(&proof.queries)?,
+            public_inputs: encoded_size(&(&proof.public_inputs, proof.public_inputs_digest))?,
+            shape: encoded_size(&proof.shape)?,
+            total,
+        },
+    })
+}
+
+fn encoded_size<T: Serialize>(value: &T) -> Result<u64> {
+    crate::wire::encoded_len(value).map(|len| len as u64)
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_info_describes_structure() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Standard);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Technical],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let proof = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Technical, 70)], "0xtest").unwrap().proof;
+
+        let info = proof.info().unwrap();
+        assert_eq!(info.backend, BackendKind::CustomStark);
+        assert_eq!(info.security_level, Some(SecurityLevel::Standard));
+        assert_eq!(info.estimated_security_bits, Some(zkp_system.estimated_security_bits()));
+
+        let stark = info.stark.unwrap();
+        assert_eq!(stark.query_count, SecurityLevel::Standard.params().num_queries);
+        assert!(stark.fri_layers > 0 && stark.auth_path_depth > 0);
+        assert_eq!(stark.sizes.total, proof.proof_data.len() as u64);
+        // Sections account for every byte of the payload but the PoW nonce
+        let sections = stark.sizes.roots + stark.sizes.fri_commitments + stark.sizes.final_poly
+            + stark.sizes.queries + stark.sizes.public_inputs + stark.sizes.shape;
+        assert_eq!(sections + 8, stark.sizes.total);
+    }
+}
@@ -0,0 +1,303 @@
+//! Issuance Policies
+//!
+//! Per-category rules for who may attest scores. High-value categories can
+//! require attestations co-signed by m of n issuer keys. Issuers sign a
+//! Poseidon2 digest of the attestation; co-signed threshold proofs hash that
+//! digest from each scored value in-circuit and publish it with its category,
+//! and the envelope ships the signatures, so verifiers check them against the
+//! policy set committed in the same public inputs.
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::freshness::AttestedScore;
+use crate::poseidon2;
+use crate::signer::{SignatureScheme, Signer};
+use crate::{RepIDCategory, RepIDProof, Result, ZKPError, F};
+
+/// Operation type of threshold proofs over co-signed attestations
+pub const COSIGNED_THRESHOLD_OPERATION: &str = "cosigned_threshold_verification";
+
+/// Fewest co-signers a Governance policy may require
+pub const MIN_GOVERNANCE_COSIGNERS: u32 = 2;
+
+/// Ed25519 public key of an attestation issuer
+pub type IssuerKey = [u8; 32];
+
+/// Field encoding of an issuer key
+pub fn issuer_tag(key: &IssuerKey) -> F {
+    blake3_tag(key)
+}
+
+/// Field encoding of an attestation subject
+pub fn subject_tag(subject: &str) -> F {
+    blake3_tag(subject.as_bytes())
+}
+
+fn blake3_tag(data: &[u8]) -> F {
+    let hash = blake3::hash(data);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash.as_bytes()[..8]);
+    F::from_bytes(bytes)
+}
+
+/// Message issuers sign for the attestation with Poseidon2 `digest`
+pub fn signing_message(digest: F) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"repid/attestation/v2");
+    hasher.update(&digest.to_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+/// Attestations in `category` need signatures from `required` of `issuers`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssuancePolicy {
+    pub category: RepIDCategory,
+    pub required: u32,
+    pub issuers: Vec<IssuerKey>,
+}
+
+impl IssuancePolicy {
+    pub fn new(category: RepIDCategory, required: u32, issuers: Vec<IssuerKey>) -> Result<Self> {
+        if required == 0 || required as usize > issuers.len() {
+            return Err(ZKPError::ConfigError(format!(
+                "{:?} policy requires {} of {} issuers",
+                category,
+                required,
+                issuers.len()
+            )));
+        }
+        if issuers.iter().enumerate().any(|(i, key)| issuers[..i].contains(key)) {
+            return Err(ZKPError::ConfigError(format!("{:?} policy lists an issuer twice", category)));
+        }
+        if category == RepIDCategory::Governance && required < MIN_GOVERNANCE_COSIGNERS {
+            return Err(ZKPError::ConfigError(format!(
+                "Governance attestations need at least {} co-signers",
+                MIN_GOVERNANCE_COSIGNERS
+            )));
+        }
+        Ok(Self { category, required, issuers })
+    }
+
+    /// Hash input: [category tag, required, issuer count, issuer tags...]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        let mut elements = vec![
+            self.category.field_tag(),
+            F::from_u32(self.required),
+            F::new(self.issuers.len() as u64),
+        ];
+        elements.extend(self.issuers.iter().map(issuer_tag));
+        elements
+    }
+
+    /// Check `attestation` carries valid signatures from enough distinct policy issuers
+    pub fn check(&self, attestation: &CosignedAttestation) -> Result<()> {
+        self.check_signatures(attestation.digest(), &attestation.signatures)
+    }
+
+    /// Check `signatures` over the attestation with `digest` come from enough distinct policy issuers
+    pub fn check_signatures(&self, digest: F, signatures: &[IssuerSignature]) -> Result<()> {
+        let message = signing_message(digest);
+        let mut signers: Vec<&IssuerKey> = signatures
+            .iter()
+            .filter(|signed| self.issuers.contains(&signed.public_key) && signed.verifies(&message))
+            .map(|signed| &signed.public_key)
+            .collect();
+        signers.sort();
+        signers.dedup();
+
+        if (signers.len() as u32) < self.required {
+            return Err(ZKPError::PolicyViolation(format!(
+                "{:?} attestation has {} of {} required issuer signatures",
+                self.category,
+                signers.len(),
+                self.required
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Poseidon2 digest of a policy set, committed into co-signed threshold proofs
+pub fn policies_digest(policies: &[IssuancePolicy]) -> F {
+    let elements: Vec<F> = policies.iter().flat_map(|policy| policy.to_field_elements()).collect();
+    poseidon2::hash_elements(&elements)
+}
+
+/// Check every attestation against the policy for its category
+///
+/// Attestations in categories without a policy are accepted unsigned.
+pub fn check_attestations(policies: &[IssuancePolicy], attestations: &[CosignedAttestation]) -> Result<()> {
+    for attestation in attestations {
+        if let Some(policy) = policies.iter().find(|p| p.category == attestation.attested.category) {
+            policy.check(attestation)?;
+        }
+    }
+    Ok(())
+}
+
+/// One issuer's signature over an attestation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssuerSignature {
+    pub public_key: IssuerKey,
+    /// 64-byte Ed25519 signature over `signing_message` of the attestation digest
+    pub signature: Vec<u8>,
+}
+
+impl IssuerSignature {
+    pub(crate) fn verifies(&self, digest: &[u8; 32]) -> bool {
+        let Ok(key) = VerifyingKey::from_bytes(&self.public_key) else {
+            return false;
+        };
+        Signature::from_slice(&self.signature).is_ok_and(|signature| key.verify(digest, &signature).is_ok())
+    }
+}
+
+/// Attested score for a subject wallet with any number of issuer signatures
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CosignedAttestation {
+    /// Wallet the score was attested for
+    pub subject: String,
+    pub attested: AttestedScore,
+    pub signatures: Vec<IssuerSignature>,
+}
+
+impl CosignedAttestation {
+    /// Position of the category tag within `to_field_elements`
+    pub const CATEGORY_POSITION: usize = 1;
+    /// Position of the score within `to_field_elements`
+    pub const SCORE_POSITION: usize = 2;
+
+    pub fn new(subject: &str, attested: AttestedScore) -> Self {
+        Self {
+            subject: subject.to_string(),
+            attested,
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Hash input of the attestation digest: [subject tag, category tag, score, issued_at]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        vec![
+            subject_tag(&self.subject),
+            self.attested.category.field_tag(),
+            F::from_u32(self.attested.score),
+            F::new(self.attested.issued_at),
+        ]
+    }
+
+    /// Poseidon2 digest binding the score to its subject, published by co-signed threshold proofs
+    pub fn digest(&self) -> F {
+        poseidon2::hash_elements(&self.to_field_elements())
+    }
+
+    /// Blake3 message issuers sign
+    pub fn signing_digest(&self) -> [u8; 32] {
+        signing_message(self.digest())
+    }
+
+    /// Add `signer`'s signature
+    pub fn cosign(&mut self, signer: &dyn Signer) -> Result<()> {
+        if signer.scheme() != SignatureScheme::Ed25519 {
+            return Err(ZKPError::SigningError(format!("Issuer keys are Ed25519, signer uses {:?}", signer.scheme())));
+        }
+        let public_key = signer.public_key()?.try_into().map_err(|key: Vec<u8>| {
+            ZKPError::SigningError(format!("Expected a 32-byte Ed25519 public key, got {} bytes", key.len()))
+        })?;
+        let signature = signer.sign(&self.signing_digest())?;
+        self.signatures.push(IssuerSignature { public_key, signature });
+        Ok(())
+    }
+}
+
+/// Check a co-signed threshold proof was made under exactly these policies
+///
+/// Every attestation the proof publishes must carry, in the envelope, valid
+/// signatures from as many of its category's policy issuers as required.
+pub fn check_policies(proof: &RepIDProof, policies: &[IssuancePolicy]) -> Result<()> {
+    if proof.metadata.operation_type != COSIGNED_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no issuance policies",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(2) {
+        Some(digest) if *digest == policies_digest(policies) => {}
+        Some(_) => return Err(ZKPError::VerificationError("Proof was made under different issuance policies".to_string())),
+        None => return Err(ZKPError::MalformedProof("Co-signed threshold proof needs a policy digest input".to_string())),
+    }
+
+    // Public inputs: ..., attestation count, then a (category tag, digest) pair per attestation
+    let signatures = &proof.metadata.issuer_signatures;
+    let count = proof.public_inputs.get(4).map(|count| count.0 as usize);
+    if count != Some(signatures.len()) {
+        return Err(ZKPError::MalformedProof(format!(
+            "Envelope carries signatures for {} attestations, proof publishes {:?}",
+            signatures.len(),
+            count
+        )));
+    }
+    let attestations = proof.public_inputs.get(5..5 + 2 * signatures.len())
+        .ok_or_else(|| ZKPError::MalformedProof("Co-signed threshold proof ends before its attestations".to_string()))?;
+    for (pair, signatures) in attestations.chunks_exact(2).zip(signatures) {
+        if let Some(policy) = policies.iter().find(|p| p.category.field_tag() == pair[0]) {
+            policy.check_signatures(pair[1], signatures)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::signer::InMemorySigner;
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_governance_needs_two_of_three_issuers() {
+        let issuers: Vec<InMemorySigner> = (1..=3).map(|seed| InMemorySigner::from_seed([seed; 32])).collect();
+        let keys: Vec<IssuerKey> = issuers.iter().map(|s| s.public_key().unwrap().try_into().unwrap()).collect();
+        assert!(IssuancePolicy::new(RepIDCategory::Governance, 1, keys.clone()).is_err());
+        let policies = vec![IssuancePolicy::new(RepIDCategory::Governance, 2, keys.clone()).unwrap()];
+
+        let attested = AttestedScore { category: RepIDCategory::Governance, score: 80, issued_at: 1_000 };
+        let mut attestation = CosignedAttestation::new("0xtest", attested);
+        attestation.cosign(&issuers[0]).unwrap();
+        // The same key twice, or a key outside the policy, does not count
+        attestation.cosign(&issuers[0]).unwrap();
+        attestation.cosign(&InMemorySigner::from_seed([9; 32])).unwrap();
+
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Governance],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let single = zkp_system.prove_cosigned_threshold_verification(&request, &[attestation.clone()], &policies, "0xtest");
+        assert!(matches!(single, Err(ZKPError::PolicyViolation(_))));
+
+        attestation.cosign(&issuers[2]).unwrap();
+        let result = zkp_system.prove_cosigned_threshold_verification(&request, &[attestation.clone()], &policies, "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+        assert!(check_policies(&result.proof, &policies).is_ok());
+        assert_eq!(result.proof.public_inputs[5..7], [RepIDCategory::Governance.field_tag(), attestation.digest()]);
+
+        // The envelope's signatures are checked against the published digest
+        let mut stripped = result.proof.clone();
+        stripped.metadata.issuer_signatures[0].retain(|signed| signed.public_key != keys[2]);
+        assert!(matches!(check_policies(&stripped, &policies), Err(ZKPError::PolicyViolation(_))));
+        let mut other = CosignedAttestation::new("0xtest", AttestedScore { score: 90, ..attestation.attested.clone() });
+        issuers.iter().for_each(|issuer| other.cosign(issuer).unwrap());
+        let mut swapped = result.proof.clone();
+        swapped.metadata.issuer_signatures[0] = other.signatures;
+        assert!(check_policies(&swapped, &policies).is_err());
+        let weaker = vec![IssuancePolicy::new(RepIDCategory::Governance, 2, keys[..2].to_vec()).unwrap()];
+        assert!(check_policies(&result.proof, &weaker).is_err());
+
+        // Signatures bind the subject wallet
+        assert!(zkp_system.prove_cosigned_threshold_verification(&request, &[attestation], &policies, "0xother").is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! Known-Answer Tests
+//!
+//! Reference values for every primitive a verifier reimplements, exported as
+//! one JSON document so the TypeScript and Solidity verifiers can be checked
+//! against this crate programmatically.
+//!
+//! Schema (version `KAT_SCHEMA_VERSION`):
+//! - field elements are JSON integers in `[0, modulus)`;
+//! - byte strings are lowercase hex without a `0x` prefix;
+//! - `field`: `{op, a, b, result}` for `add`, `sub`, `mul`, `neg`, `inv`
+//!   and `pow` (`b` is the exponent for `pow` and absent for unary ops);
+//! - `hashing`: `{function, input, output}` where `function` is
+//!   `poseidon2_hash` (sponge over `input`, output one element),
+//!   `poseidon2_permutation` (16 elements in, 16 out) or
+//!   `public_inputs_digest` (output is the 32-byte big-endian EVM word);
+//! - `merkle`: leaves of a Poseidon2 tree, its root and one path per leaf,
+//!   siblings from the leaf level up and left/right taken from the index bits;
+//! - `transcript`: a domain and the recorded absorb/challenge sequence;
+//!   replaying the absorbs must reproduce every challenge value. The second
+//!   log is a child forked under `label` (domain `parent/label`) from the
+//!   parent's final state;
+//! - `proofs`: the end-to-end Solidity vectors, proof bytes with the outcome
+//!   verification must give.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::custom_stark::TRANSCRIPT_DOMAIN;
+use crate::fixtures::{generate_solidity_vectors, SolidityVector};
+use crate::ledger::hash_nodes;
+use crate::poseidon2::{self, WIDTH};
+use crate::public_inputs::PublicInputs;
+use crate::transcript::{Transcript, TranscriptLog};
+use crate::{Result, SecurityLevel, ZKPError, F};
+
+/// Version of the JSON layout described in the module docs
+pub const KAT_SCHEMA_VERSION: u32 = 1;
+
+/// One field operation and its result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldKat {
+    pub op: String,
+    pub a: F,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub b: Option<u64>,
+    pub result: F,
+}
+
+/// One hash evaluation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashKat {
+    pub function: String,
+    pub input: Vec<F>,
+    /// Field elements, or hex for `public_inputs_digest`
+    pub output: serde_json::Value,
+}
+
+/// Authentication path of one leaf
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerklePathKat {
+    pub leaf_index: u64,
+    pub siblings: Vec<F>,
+}
+
+/// Poseidon2 Merkle tree with every path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleKat {
+    pub leaves: Vec<F>,
+    pub root: F,
+    pub paths: Vec<MerklePathKat>,
+}
+
+/// Full known-answer set tagged with the crate version that produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KatSet {
+    pub schema_version: u32,
+    pub crate_version: String,
+    pub modulus: u64,
+    pub field: Vec<FieldKat>,
+    pub hashing: Vec<HashKat>,
+    pub merkle: Vec<MerkleKat>,
+    pub transcript: Vec<TranscriptLog>,
+    /// Security level the proof vectors are generated at
+    pub proof_security_level: SecurityLevel,
+    pub proofs: Vec<SolidityVector>,
+}
+
+impl KatSet {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+}
+
+/// Operands covering zero, one, the top of the field and reduction edges
+fn field_operands() -> Vec<F> {
+    let p = F::MODULUS;
+    [0, 1, 2, 7, 1 << 16, (1 << 31) - 1, p - 2, p - 1].into_iter().map(F::new).collect()
+}
+
+fn field_kats() -> Vec<FieldKat> {
+    let operands = field_operands();
+    let mut kats = Vec::new();
+    for &a in &operands {
+        for &b in &operands {
+            kats.push(FieldKat { op: "add".to_string(), a, b: Some(b.0), result: a + b });
+            kats.push(FieldKat { op: "sub".to_string(), a, b: Some(b.0), result: a - b });
+            kats.push(FieldKat { op: "mul".to_string(), a, b: Some(b.0), result: a * b });
+        }
+        kats.push(FieldKat { op: "neg".to_string(), a, b: None, result: -a });
+        if let Some(inverse) = a.inverse() {
+            kats.push(FieldKat { op: "inv".to_string(), a, b: None, result: inverse });
+        }
+        for exponent in [0, 1, 7, F::MODULUS - 2] {
+            kats.push(FieldKat { op: "pow".to_string(), a, b: Some(exponent), result: a.pow(exponent) });
+        }
+    }
+    kats
+}
+
+fn hash_kats() -> Vec<HashKat> {
+    let elements = |len: u64| (1..=len).map(F::new).collect::<Vec<_>>();
+    // Empty, single, exactly one rate block and one past it
+    let mut kats: Vec<HashKat> = [0, 1, poseidon2::RATE as u64, poseidon2::RATE as u64 + 1]
+        .into_iter()
+        .map(|len| {
+            let input = elements(len);
+            HashKat {
+                function: "poseidon2_hash".to_string(),
+                output: serde_json::json!([poseidon2::hash_elements(&input)]),
+                input,
+            }
+        })
+        .collect();
+
+    let mut state = [F::ZERO; WIDTH];
+    state.iter_mut().enumerate().for_each(|(i, element)| *element = F::new(i as u64));
+    let input = state.to_vec();
+    poseidon2::permute(&mut state);
+    kats.push(HashKat {
+        function: "poseidon2_permutation".to_string(),
+        input,
+        output: serde_json::json!(state.to_vec()),
+    });
+
+    let input = vec![F::new(50), F::new(86400), F::new(1_700_000_000)];
+    kats.push(HashKat {
+        function: "public_inputs_digest".to_string(),
+        output: serde_json::json!(hex::encode(PublicInputs::new(input.clone()).digest_bytes())),
+        input,
+    });
+    kats
+}
+
+/// Full binary tree over `leaves` (a power of two), with every path
+fn merkle_kat(leaves: Vec<F>) -> MerkleKat {
+    // Every level below the root, leaves first
+    let mut levels = Vec::new();
+    let mut level = leaves.clone();
+    while level.len() > 1 {
+        let next = level.chunks(2).map(|pair| hash_nodes(pair[0], pair[1])).collect();
+        levels.push(std::mem::replace(&mut level, next));
+    }
+    let root = level[0];
+    let paths = (0..leaves.len())
+        .map(|index| MerklePathKat {
+            leaf_index: index as u64,
+            siblings: levels.iter().enumerate().map(|(depth, level)| level[(index >> depth) ^ 1]).collect(),
+        })
+        .collect();
+    MerkleKat { leaves, root, paths }
+}
+
+fn transcript_kats() -> Vec<TranscriptLog> {
+    let mut transcript = Transcript::new(TRANSCRIPT_DOMAIN, true);
+    transcript.absorb("bytes", b"repid");
+    transcript.absorb_field_elements("elements", &[F::new(1), F::new(F::MODULUS - 1)]);
+    transcript.challenge_u64("alpha");
+    transcript.challenge_field("beta");
+    transcript.absorb("empty", &[]);
+    transcript.challenge_indices("query_index", 4, 64);
+
+    let mut child = transcript.fork("child");
+    child.challenge_u64("gamma");
+    [transcript, child].into_iter().filter_map(Transcript::into_log).collect()
+}
+
+/// Generate the full known-answer set
+pub fn generate() -> Result<KatSet> {
+    let vectors = generate_solidity_vectors()?;
+    Ok(KatSet {
+        schema_version: KAT_SCHEMA_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        modulus: F::MODULUS,
+        field: field_kats(),
+        hashing: hash_kats(),
+        merkle: vec![merkle_kat((1..=2).map(F::new).collect()), merkle_kat((1..=8).map(F::new).collect())],
+        transcript: transcript_kats(),
+        proof_security_level: vectors.security_level,
+        proofs: vectors.vectors,
+    })
+}
+
+/// Write a freshly generated known-answer set to `path` as JSON
+pub fn export(path: impl AsRef<Path>) -> Result<KatSet> {
+    let path = path.as_ref();
+    let kats = generate()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| ZKPError::ConfigError(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    std::fs::write(path, kats.to_json()?)
+        .map_err(|e| ZKPError::ConfigError(format!("Failed to write {}: {}", path.display(), e)))?;
+    Ok(kats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::InclusionWitness;
+    use crate::transcript::TranscriptEntry;
+
+    #[test]
+    fn test_exported_kats_are_self_consistent() {
+        let path = std::env::temp_dir().join(format!("repid-kats-{}.json", std::process::id()));
+        let kats = export(&path).unwrap();
+        assert_eq!(KatSet::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap(), kats);
+        assert_eq!(kats, generate().unwrap());
+
+        assert!(kats.field.iter().all(|kat| kat.result.is_canonical()));
+        assert!(kats.field.iter().filter(|kat| kat.op == "inv").all(|kat| kat.a * kat.result == F::ONE));
+
+        for tree in &kats.merkle {
+            for (path, &leaf) in tree.paths.iter().zip(&tree.leaves) {
+                let witness = InclusionWitness { leaf_index: path.leaf_index, leaf, siblings: path.siblings.clone() };
+                assert!(witness.verify(tree.root));
+            }
+        }
+
+        let challenges = kats.transcript[0].entries.iter().filter(|entry| matches!(entry, TranscriptEntry::Challenge { .. }));
+        assert_eq!(challenges.count(), 6);
+        assert_eq!(kats.transcript[1].domain, format!("{}/child", TRANSCRIPT_DOMAIN));
+
+        assert!(kats.proofs.iter().any(|vector| vector.expected_valid));
+        assert!(kats.to_json().unwrap().contains("\"function\": \"poseidon2_permutation\""));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,287 @@
+//! Key Management
+//!
+//! Prover identity, attestation issuer and EIP-712 signing keys with stable
+//! key IDs, rotation schedules and encrypted-at-rest storage
+
+use std::path::Path;
+use std::sync::Arc;
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::entropy::{self, RngProvider};
+use crate::identity::ProverIdentity;
+use crate::signer::InMemorySigner;
+use crate::{Result, ZKPError};
+
+const NONCE_LEN: usize = 12;
+
+/// What a managed key is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyPurpose {
+    ProverIdentity,
+    AttestationIssuer,
+    Eip712,
+}
+
+/// How often keys of one purpose are replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationSchedule {
+    /// Age after which the active key is due for rotation
+    pub max_age_secs: u64,
+    /// How long a retired key still verifies after rotation
+    pub grace_secs: u64,
+}
+
+/// One generation of a managed key
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KeyRecord {
+    /// Stable identifier embedded in signatures and artifacts
+    pub id: String,
+    pub purpose: KeyPurpose,
+    secret: [u8; 32],
+    pub created_at: u64,
+    pub retired_at: Option<u64>,
+}
+
+impl std::fmt::Debug for KeyRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyRecord")
+            .field("id", &self.id)
+            .field("purpose", &self.purpose)
+            .field("created_at", &self.created_at)
+            .field("retired_at", &self.retired_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyRecord {
+    fn new(purpose: KeyPurpose, secret: [u8; 32], created_at: u64) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"repid/key-id/v1");
+        hasher.update(&secret);
+        let id = hex::encode(&hasher.finalize().as_bytes()[..8]);
+        Self {
+            id,
+            purpose,
+            secret,
+            created_at,
+            retired_at: None,
+        }
+    }
+
+    /// Raw key material, for signers outside this crate (e.g. EIP-712)
+    pub fn secret(&self) -> &[u8; 32] {
+        &self.secret
+    }
+
+    /// In-memory Ed25519 signer for this key, tagged with its ID
+    pub fn signer(&self) -> InMemorySigner {
+        InMemorySigner::from_seed(self.secret).with_key_id(&self.id)
+    }
+
+    /// Whether the key may still verify signatures at `now`
+    fn usable_at(&self, now: u64, grace_secs: u64) -> bool {
+        self.retired_at.is_none_or(|retired| now < retired.saturating_add(grace_secs))
+    }
+}
+
+/// Managed keys of every purpose, with their rotation schedules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyRing {
+    records: Vec<KeyRecord>,
+    schedules: Vec<(KeyPurpose, RotationSchedule)>,
+    /// Source of key material and sealing nonces
+    #[serde(skip, default = "entropy::os_entropy")]
+    rng: Arc<dyn RngProvider>,
+}
+
+impl Default for KeyRing {
+    fn default() -> Self {
+        Self {
+            records: Vec::new(),
+            schedules: Vec::new(),
+            rng: entropy::os_entropy(),
+        }
+    }
+}
+
+impl KeyRing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_schedule(mut self, purpose: KeyPurpose, schedule: RotationSchedule) -> Self {
+        self.schedules.retain(|(p, _)| *p != purpose);
+        self.schedules.push((purpose, schedule));
+        self
+    }
+
+    /// Draw key material and nonces from `provider` instead of OS entropy
+    pub fn with_rng_provider(mut self, provider: Arc<dyn RngProvider>) -> Self {
+        self.rng = provider;
+        self
+    }
+
+    fn schedule(&self, purpose: KeyPurpose) -> Option<RotationSchedule> {
+        self.schedules.iter().find(|(p, _)| *p == purpose).map(|(_, s)| *s)
+    }
+
+    /// Replace the active key of `purpose` with a fresh random one
+    pub fn rotate(&mut self, purpose: KeyPurpose, now: u64) -> Result<&KeyRecord> {
+        let secret = entropy::seed(self.rng.as_ref())?;
+        Ok(self.install(purpose, secret, now))
+    }
+
+    pub fn rotate_with_rng(&mut self, purpose: KeyPurpose, now: u64, rng: &mut impl RngCore) -> &KeyRecord {
+        let mut secret = [0u8; 32];
+        rng.fill_bytes(&mut secret);
+        self.install(purpose, secret, now)
+    }
+
+    fn install(&mut self, purpose: KeyPurpose, secret: [u8; 32], now: u64) -> &KeyRecord {
+        for record in self.records.iter_mut().filter(|r| r.purpose == purpose && r.retired_at.is_none()) {
+            record.retired_at = Some(now);
+        }
+        self.records.push(KeyRecord::new(purpose, secret, now));
+        self.records.last().expect("just pushed")
+    }
+
+    /// Current signing key of `purpose`
+    pub fn active(&self, purpose: KeyPurpose) -> Option<&KeyRecord> {
+        self.records.iter().rev().find(|r| r.purpose == purpose && r.retired_at.is_none())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&KeyRecord> {
+        self.records.iter().find(|r| r.id == id)
+    }
+
+    /// Whether the active key of `purpose` is missing or older than its schedule allows
+    pub fn needs_rotation(&self, purpose: KeyPurpose, now: u64) -> bool {
+        match (self.active(purpose), self.schedule(purpose)) {
+            (None, _) => true,
+            (Some(record), Some(schedule)) => now.saturating_sub(record.created_at) >= schedule.max_age_secs,
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Rotate every scheduled purpose that is due, returning the new key IDs
+    pub fn rotate_due(&mut self, now: u64) -> Result<Vec<String>> {
+        let due: Vec<KeyPurpose> =
+            self.schedules.iter().map(|(p, _)| *p).filter(|p| self.needs_rotation(*p, now)).collect();
+        due.into_iter().map(|purpose| Ok(self.rotate(purpose, now)?.id.clone())).collect()
+    }
+
+    /// Keys of `purpose` that still verify at `now`: the active key and retired keys within grace
+    pub fn verifying_keys(&self, purpose: KeyPurpose, now: u64) -> Vec<&KeyRecord> {
+        let grace = self.schedule(purpose).map_or(0, |s| s.grace_secs);
+        self.records.iter().filter(|r| r.purpose == purpose && r.usable_at(now, grace)).collect()
+    }
+
+    /// Drop retired keys whose grace period has ended
+    pub fn prune(&mut self, now: u64) {
+        let schedules = self.schedules.clone();
+        self.records.retain(|r| {
+            let grace = schedules.iter().find(|(p, _)| *p == r.purpose).map_or(0, |(_, s)| s.grace_secs);
+            r.usable_at(now, grace)
+        });
+    }
+
+    /// Prover identity for the active prover key, tagged with its key ID
+    pub fn prover_identity(&self) -> Option<ProverIdentity> {
+        self.active(KeyPurpose::ProverIdentity).map(|record| {
+            ProverIdentity::from_signer(std::sync::Arc::new(record.signer())).expect("in-memory signers are Ed25519")
+        })
+    }
+
+    /// ChaCha20-Poly1305 encryption of the ring under `key` (nonce prepended)
+    pub fn seal(&self, key: &[u8; 32]) -> Result<Vec<u8>> {
+        let plaintext = crate::wire::to_bytes(self)?;
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng.fill_bytes(&mut nonce)?;
+        let ciphertext = ChaCha20Poly1305::new(key.into())
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| ZKPError::SerializationError("Key ring encryption failed".to_string()))?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    /// Decrypt a sealed ring, failing on a wrong key or tampered data
+    pub fn open(sealed: &[u8], key: &[u8; 32]) -> Result<Self> {
+        if sealed.len() < NONCE_LEN {
+            return Err(ZKPError::ConfigError("Sealed key ring is truncated".to_string()));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = ChaCha20Poly1305::new(key.into())
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| ZKPError::ConfigError("Key ring could not be decrypted".to_string()))?;
+        crate::wire::from_bytes(&plaintext)
+    }
+
+    /// Write the sealed ring to `path`
+    pub fn save(&self, path: impl AsRef<Path>, key: &[u8; 32]) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.seal(key)?)
+            .map_err(|e| ZKPError::ConfigError(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    pub fn load(path: impl AsRef<Path>, key: &[u8; 32]) -> Result<Self> {
+        let path = path.as_ref();
+        let sealed =
+            std::fs::read(path).map_err(|e| ZKPError::ConfigError(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::open(&sealed, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_rotation_grace_and_sealed_storage() {
+        let schedule = RotationSchedule { max_age_secs: 1_000, grace_secs: 100 };
+        let mut ring = KeyRing::new().with_schedule(KeyPurpose::ProverIdentity, schedule);
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(7);
+
+        assert_eq!(ring.rotate_due(0).unwrap().len(), 1);
+        let first = ring.active(KeyPurpose::ProverIdentity).unwrap().id.clone();
+        assert!(!ring.needs_rotation(KeyPurpose::ProverIdentity, 999));
+
+        let second = ring.rotate_with_rng(KeyPurpose::ProverIdentity, 1_000, &mut rng).id.clone();
+        assert_ne!(first, second);
+        assert_eq!(ring.verifying_keys(KeyPurpose::ProverIdentity, 1_050).len(), 2);
+        assert_eq!(ring.verifying_keys(KeyPurpose::ProverIdentity, 1_100).len(), 1);
+        ring.prune(1_100);
+        assert!(ring.get(&first).is_none());
+
+        let mut proof = crate::RepIDProof {
+            proof_data: vec![1, 2, 3],
+            public_inputs: Vec::new(),
+            metadata: crate::ProofMetadata {
+                operation_type: "threshold_verification".to_string(),
+                timestamp: 1_100,
+                wallet_hash: String::new(),
+                proof_size: 3,
+                generation_time_ms: 0,
+                backend: crate::backend::BackendKind::CustomStark,
+                prover_signature: None,
+                tee_attestation: None,
+                content_address: None,
+                protocol_version: crate::protocol::PROTOCOL_VERSION,
+                stark_params: None,
+                issuer_signatures: Vec::new(),
+            },
+        };
+        ring.prover_identity().unwrap().sign(&mut proof).unwrap();
+        assert_eq!(proof.metadata.prover_signature.unwrap().key_id.as_deref(), Some(second.as_str()));
+
+        ring.rotate_with_rng(KeyPurpose::Eip712, 1_100, &mut rng);
+        let sealed = ring.seal(&[9; 32]).unwrap();
+        assert!(KeyRing::open(&sealed, &[8; 32]).is_err());
+        let opened = KeyRing::open(&sealed, &[9; 32]).unwrap();
+        assert_eq!(opened.active(KeyPurpose::Eip712).unwrap().secret(), ring.active(KeyPurpose::Eip712).unwrap().secret());
+    }
+}
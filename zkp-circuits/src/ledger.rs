@@ -0,0 +1,306 @@
+//! Reputation Ledger
+//!
+//! Append-only Poseidon2 Merkle log of score updates with a root per epoch and
+//! inclusion witnesses for proving statements about attested scores
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2;
+use crate::storage::{self, Storage};
+use crate::{RepIDCategory, Result, ZKPError, F};
+
+/// Storage namespace of appended records, keyed by big-endian leaf index
+const STORAGE_NAMESPACE: &str = "ledger/records";
+
+/// Default tree depth (about a million records)
+pub const DEFAULT_DEPTH: usize = 20;
+
+/// A single attested score change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreUpdate {
+    pub wallet_hash: String,
+    pub category: RepIDCategory,
+    /// Score after the update
+    pub score: u32,
+    pub timestamp: u64,
+    pub epoch: u64,
+}
+
+impl ScoreUpdate {
+    /// Field element identifying the wallet
+    pub fn wallet_tag(&self) -> F {
+        wallet_tag(&self.wallet_hash)
+    }
+
+    /// Leaf hash input: [wallet_tag, category_tag, score, timestamp, epoch]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        vec![
+            self.wallet_tag(),
+            self.category.field_tag(),
+            F::from_u32(self.score),
+            F::new(self.timestamp),
+            F::new(self.epoch),
+        ]
+    }
+
+    pub fn leaf(&self) -> F {
+        poseidon2::hash_elements(&self.to_field_elements())
+    }
+}
+
+/// Field element identifying a wallet hash
+pub fn wallet_tag(wallet_hash: &str) -> F {
+    let hash = blake3::hash(wallet_hash.as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash.as_bytes()[..8]);
+    F::from_bytes(bytes)
+}
+
+/// Poseidon2 compression of two tree nodes
+pub fn hash_nodes(left: F, right: F) -> F {
+    poseidon2::hash_elements(&[left, right])
+}
+
+/// Merkle authentication path for one ledger record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionWitness {
+    pub leaf_index: u64,
+    pub leaf: F,
+    /// Sibling hashes from the leaf level upwards
+    pub siblings: Vec<F>,
+}
+
+impl InclusionWitness {
+    /// Recompute the root implied by the path
+    pub fn compute_root(&self) -> F {
+        self.siblings.iter().enumerate().fold(self.leaf, |node, (level, &sibling)| {
+            if (self.leaf_index >> level) & 1 == 0 {
+                hash_nodes(node, sibling)
+            } else {
+                hash_nodes(sibling, node)
+            }
+        })
+    }
+
+    pub fn verify(&self, root: F) -> bool {
+        self.compute_root() == root
+    }
+
+    /// Circuit witness layout: [leaf, (direction_bit, sibling) per level]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        let mut elements = Vec::with_capacity(1 + 2 * self.siblings.len());
+        elements.push(self.leaf);
+        for (level, &sibling) in self.siblings.iter().enumerate() {
+            elements.push(F::new((self.leaf_index >> level) & 1));
+            elements.push(sibling);
+        }
+        elements
+    }
+}
+
+/// Append-only Merkle ledger of score updates
+#[derive(Debug, Clone)]
+pub struct MerkleLedger {
+    depth: usize,
+    records: Vec<ScoreUpdate>,
+    /// Leaf hashes in append order
+    leaves: Vec<F>,
+    /// Rightmost filled node per level, for O(depth) root updates
+    frontier: Vec<F>,
+    /// Roots of empty subtrees per level
+    empty: Vec<F>,
+    root: F,
+    epoch_roots: BTreeMap<u64, F>,
+    storage: Option<Arc<dyn Storage>>,
+}
+
+impl MerkleLedger {
+    pub fn new(depth: usize) -> Self {
+        let mut empty = vec![F::ZERO];
+        for level in 0..depth {
+            empty.push(hash_nodes(empty[level], empty[level]));
+        }
+
+        Self {
+            depth,
+            records: Vec::new(),
+            leaves: Vec::new(),
+            frontier: vec![F::ZERO; depth],
+            root: empty[depth],
+            empty,
+            epoch_roots: BTreeMap::new(),
+            storage: None,
+        }
+    }
+
+    /// Ledger persisted in `storage`, replaying the records appended there
+    pub fn open(depth: usize, storage: Arc<dyn Storage>) -> Result<Self> {
+        let mut ledger = Self::new(depth);
+        for (_, value) in storage.iterate(STORAGE_NAMESPACE)? {
+            ledger.append(storage::decode(&value)?)?;
+        }
+        ledger.storage = Some(storage);
+        Ok(ledger)
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn records(&self) -> &[ScoreUpdate] {
+        &self.records
+    }
+
+    /// Current root over every appended record
+    pub fn root(&self) -> F {
+        self.root
+    }
+
+    /// Root after the last record of `epoch`
+    pub fn epoch_root(&self, epoch: u64) -> Option<F> {
+        self.epoch_roots.get(&epoch).copied()
+    }
+
+    /// Append a record, returning its leaf index
+    ///
+    /// Records must arrive in non-decreasing epoch order.
+    pub fn append(&mut self, update: ScoreUpdate) -> Result<u64> {
+        let index = self.records.len() as u64;
+        if self.depth < u64::BITS as usize && index >> self.depth != 0 {
+            return Err(ZKPError::LimitExceeded {
+                limit: "ledger_records".to_string(),
+                actual: index + 1,
+                max: 1u64 << self.depth,
+            });
+        }
+        if let Some(&last_epoch) = self.epoch_roots.keys().next_back() {
+            if update.epoch < last_epoch {
+                return Err(ZKPError::InvalidInput(format!(
+                    "ledger is at epoch {}, cannot append epoch {}",
+                    last_epoch, update.epoch
+                )));
+            }
+        }
+
+        if let Some(storage) = &self.storage {
+            storage.put(STORAGE_NAMESPACE, &index.to_be_bytes(), &storage::encode(&update)?)?;
+        }
+
+        let leaf = update.leaf();
+        let mut node = leaf;
+        for level in 0..self.depth {
+            if (index >> level) & 1 == 0 {
+                self.frontier[level] = node;
+                node = hash_nodes(node, self.empty[level]);
+            } else {
+                node = hash_nodes(self.frontier[level], node);
+            }
+        }
+
+        self.root = node;
+        self.epoch_roots.insert(update.epoch, node);
+        self.leaves.push(leaf);
+        self.records.push(update);
+        Ok(index)
+    }
+
+    /// Inclusion witness against the current root
+    pub fn witness(&self, leaf_index: u64) -> Result<InclusionWitness> {
+        self.witness_at(leaf_index, self.leaves.len())
+    }
+
+    /// Inclusion witness against `epoch_root(epoch)`
+    pub fn witness_for_epoch(&self, leaf_index: u64, epoch: u64) -> Result<InclusionWitness> {
+        let size = self.records.iter().take_while(|record| record.epoch <= epoch).count();
+        self.witness_at(leaf_index, size)
+    }
+
+    /// Path for `leaf_index` in the tree formed by the first `size` leaves
+    fn witness_at(&self, leaf_index: u64, size: usize) -> Result<InclusionWitness> {
+        if leaf_index >= size as u64 {
+            return Err(ZKPError::InvalidInput(format!("no ledger record {} in a tree of {}", leaf_index, size)));
+        }
+
+        let mut level_nodes: Vec<F> = self.leaves[..size].to_vec();
+        let mut siblings = Vec::with_capacity(self.depth);
+        let mut position = leaf_index as usize;
+
+        for level in 0..self.depth {
+            let sibling = level_nodes.get(position ^ 1).copied().unwrap_or(self.empty[level]);
+            siblings.push(sibling);
+
+            level_nodes = level_nodes
+                .chunks(2)
+                .map(|pair| hash_nodes(pair[0], pair.get(1).copied().unwrap_or(self.empty[level])))
+                .collect();
+            position /= 2;
+        }
+
+        Ok(InclusionWitness {
+            leaf_index,
+            leaf: self.leaves[leaf_index as usize],
+            siblings,
+        })
+    }
+}
+
+impl Default for MerkleLedger {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(score: u32, epoch: u64) -> ScoreUpdate {
+        ScoreUpdate {
+            wallet_hash: "0xabc".to_string(),
+            category: RepIDCategory::Community,
+            score,
+            timestamp: 1_700_000_000 + score as u64,
+            epoch,
+        }
+    }
+
+    #[test]
+    fn test_witnesses_verify_against_epoch_roots() {
+        let mut ledger = MerkleLedger::new(8);
+        for (i, epoch) in [1, 1, 1, 2, 2].into_iter().enumerate() {
+            ledger.append(update(10 * i as u32, epoch)).unwrap();
+        }
+
+        let epoch_one = ledger.epoch_root(1).unwrap();
+        assert_ne!(epoch_one, ledger.root());
+        assert_eq!(ledger.epoch_root(2), Some(ledger.root()));
+
+        for index in 0..5 {
+            assert!(ledger.witness(index).unwrap().verify(ledger.root()));
+        }
+        let historical = ledger.witness_for_epoch(2, 1).unwrap();
+        assert!(historical.verify(epoch_one));
+        assert!(!historical.verify(ledger.root()));
+        assert!(ledger.witness_for_epoch(3, 1).is_err());
+    }
+
+    #[test]
+    fn test_append_only_ordering_and_capacity() {
+        let mut ledger = MerkleLedger::new(1);
+        ledger.append(update(1, 5)).unwrap();
+        assert!(ledger.append(update(2, 4)).is_err());
+        ledger.append(update(3, 5)).unwrap();
+        assert!(matches!(ledger.append(update(4, 6)), Err(ZKPError::LimitExceeded { .. })));
+    }
+}
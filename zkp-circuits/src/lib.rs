@@ -4,9 +4,114 @@
 //! Based on Plonky3 principles with BabyBear field arithmetic
 
 pub mod custom_stark;
+pub mod absence;
+pub mod air;
+pub mod audit;
+pub mod backend;
+pub mod batch;
+pub mod blob;
+pub mod build_info;
+pub mod bundle;
+pub mod category_count;
+pub mod chain;
+pub mod circuits;
+pub mod clock;
+pub mod commitment;
+pub mod compact;
+pub mod concurrency;
+pub mod config;
+pub mod cost;
+#[cfg(feature = "prover")]
+pub mod credential;
+pub mod decay;
+pub mod decoding;
+pub mod designated;
+pub mod disclosure;
+pub mod domain;
+#[cfg(feature = "scoring")]
+pub mod entropy;
+pub mod epoch;
+pub mod escrow;
+pub mod evm;
+pub mod external_evidence;
+#[cfg(feature = "uniffi")]
+pub mod ffi;
+pub mod fixed_point;
+#[cfg(feature = "prover")]
+pub mod fixtures;
+pub mod freshness;
+pub mod hidden;
+#[cfg(feature = "scoring")]
 pub mod hierarchical_scoring;
+pub mod history;
+pub mod identity;
+pub mod info;
+pub mod issuance;
+#[cfg(feature = "prover")]
+pub mod kats;
+#[cfg(feature = "prover")]
+pub mod keys;
+pub mod ledger;
+pub mod limits;
+pub mod linkage;
+pub mod multichain;
+pub mod normalization;
+pub mod oracle;
+pub mod policy;
+pub mod polynomial;
+pub mod poseidon2;
+pub mod protocol;
+#[cfg(feature = "scoring")]
+pub mod privacy;
+#[cfg(feature = "scoring")]
+pub mod profile_archive;
+pub mod progress;
+pub mod public_inputs;
+pub mod publish;
+pub mod rank;
+pub mod registry;
+pub mod reproving;
+pub mod replay;
+pub mod request;
+pub mod revocation;
+pub mod rln;
+pub mod saturation;
+pub mod score_delta;
+#[cfg(feature = "scoring")]
+pub mod scoring;
+pub mod sealed;
+pub mod session;
+pub mod signer;
+pub mod slashing;
+pub mod solana;
+pub mod sparse_merkle;
+pub mod stateless;
+pub mod storage;
+pub mod submission;
+pub mod sustained;
+pub mod synergy;
+pub mod taxonomy;
+pub mod tee;
+pub mod telemetry;
+pub mod tenant;
+pub mod time_predicate;
+#[cfg(feature = "prover")]
+pub mod trace_debug;
+pub mod transcript;
+pub mod verify_cache;
+pub mod wallet;
+pub mod wire;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
 
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
 
 /// Field element type (BabyBear field)
 pub use custom_stark::BabyBearField as F;
@@ -35,10 +140,31 @@ pub struct ProofMetadata {
     pub proof_size: usize,
     /// Generation time in milliseconds
     pub generation_time_ms: u64,
+    /// Proving stack that produced the proof (legacy proofs are custom STARK)
+    #[serde(default)]
+    pub backend: backend::BackendKind,
+    /// Signature of the proving service over the whole envelope
+    #[serde(default)]
+    pub prover_signature: Option<identity::ProverSignature>,
+    /// Enclave quote over the envelope, prover binary and configuration
+    #[serde(default)]
+    pub tee_attestation: Option<tee::TeeAttestation>,
+    /// Where the full envelope is published, for contracts that store only the address
+    #[serde(default)]
+    pub content_address: Option<publish::ContentAddress>,
+    /// Protocol the envelope was produced under (envelopes without one are version 1)
+    #[serde(default = "protocol::legacy_version")]
+    pub protocol_version: u32,
+    /// Parameters the proof was generated under (older envelopes use the verifier's own)
+    #[serde(default)]
+    pub stark_params: Option<custom_stark::StarkParams>,
+    /// Issuer signatures over each attestation a co-signed threshold proof publishes, in order
+    #[serde(default)]
+    pub issuer_signatures: Vec<Vec<issuance::IssuerSignature>>,
 }
 
 /// RepID scoring categories for hierarchical verification
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum RepIDCategory {
     /// Governance participation and voting
     Governance,
@@ -54,6 +180,25 @@ pub enum RepIDCategory {
     Custom(String),
 }
 
+impl RepIDCategory {
+    /// Stable field encoding of the category used in commitments
+    pub fn field_tag(&self) -> F {
+        match self {
+            RepIDCategory::Governance => F::new(1),
+            RepIDCategory::Community => F::new(2),
+            RepIDCategory::Technical => F::new(3),
+            RepIDCategory::FaithTech => F::new(4),
+            RepIDCategory::DeFi => F::new(5),
+            RepIDCategory::Custom(name) => {
+                let hash = blake3::hash(name.as_bytes());
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&hash.as_bytes()[..8]);
+                F::from_bytes(bytes)
+            }
+        }
+    }
+}
+
 /// RepID threshold verification request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThresholdVerificationRequest {
@@ -61,8 +206,11 @@ pub struct ThresholdVerificationRequest {
     pub threshold: u32,
     /// Categories to include in verification
     pub categories: Vec<RepIDCategory>,
-    /// Time window for score calculation (in seconds)
+    /// Time window for score calculation (in seconds), ending at `as_of`
     pub time_window: u64,
+    /// Evaluation instant (Unix seconds), public in the proof; 0 takes the prover's clock
+    #[serde(default)]
+    pub as_of: u64,
     /// Optional decay parameters
     pub decay_params: Option<DecayParameters>,
 }
@@ -72,10 +220,13 @@ pub struct ThresholdVerificationRequest {
 pub struct DecayParameters {
     /// Base decay rate in basis points (100 = 1%)
     pub base_decay_rate: u16,
-    /// Multiplicative factor for sustained activity
-    pub multiplicative_factor: f32,
+    /// Multiplicative factor for sustained activity (Q16.16, per active category)
+    pub multiplicative_factor: fixed_point::Q16,
     /// Minimum score threshold before decay stops
     pub min_threshold: u32,
+    /// Per-category overrides of the base decay rate
+    #[serde(default)]
+    pub category_decay: Vec<decay::CategoryDecay>,
 }
 
 /// Result of threshold verification
@@ -100,10 +251,17 @@ pub struct VerificationMetadata {
     pub time_window_applied: u64,
     /// Whether decay was applied
     pub decay_applied: bool,
+    /// Hash of the scoring profile the scores were computed under
+    #[serde(default)]
+    pub scoring_profile: Option<String>,
+    /// Commitment standing in for `categories_verified` in hidden-category proofs
+    #[serde(default)]
+    pub categories_commitment: Option<hidden::CategorySetCommitment>,
 }
 
 /// Error types for ZKP operations
 #[derive(Debug, thiserror::Error)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error), uniffi(flat_error))]
 pub enum ZKPError {
     #[error("Circuit execution failed: {0}")]
     CircuitError(String),
@@ -115,71 +273,601 @@ pub enum ZKPError {
     InvalidInput(String),
     #[error("Serialization error: {0}")]
     SerializationError(String),
+    #[error("Malformed proof: {0}")]
+    MalformedProof(String),
+    #[error("Unknown operation type: {0}")]
+    UnknownOperation(String),
+    #[error("Security parameter downgrade: {0}")]
+    ParameterDowngrade(String),
+    #[error("Unsupported protocol version {version}, this verifier accepts {oldest} to {current}")]
+    UnsupportedVersion { version: u32, oldest: u32, current: u32 },
+    #[error("Verification policy violation: {0}")]
+    PolicyViolation(String),
+    #[error("Limit exceeded: {limit} is {actual}, maximum is {max}")]
+    LimitExceeded { limit: String, actual: u64, max: u64 },
+    #[error("Proving budget exceeded: {0}")]
+    BudgetExceeded(String),
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
+    #[error("Signing failed: {0}")]
+    SigningError(String),
+    #[error("Storage backend failed: {0}")]
+    StorageError(String),
+    #[error("Rate limited, retry after {retry_after_ms} ms")]
+    RateLimited { retry_after_ms: u64 },
+    #[error("[{correlation_id}] {source}")]
+    Correlated { correlation_id: String, source: Box<ZKPError> },
+}
+
+impl ZKPError {
+    /// Tag the error with the request it was raised for, keeping an existing tag
+    pub fn with_correlation_id(self, correlation_id: &str) -> Self {
+        match self {
+            ZKPError::Correlated { .. } => self,
+            source => ZKPError::Correlated {
+                correlation_id: correlation_id.to_string(),
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// Correlation ID of the originating request, if the error was raised under one
+    pub fn correlation_id(&self) -> Option<&str> {
+        match self {
+            ZKPError::Correlated { correlation_id, .. } => Some(correlation_id),
+            _ => None,
+        }
+    }
+
+    /// The error without its correlation tag
+    pub fn untagged(&self) -> &ZKPError {
+        match self {
+            ZKPError::Correlated { source, .. } => source,
+            _ => self,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, ZKPError>;
 
 /// Main interface for RepID ZKP operations
 pub struct RepIDZKPSystem {
-    prover: custom_stark::CustomStarkProver,
-    verifier: custom_stark::CustomStarkVerifier,
+    params: custom_stark::StarkParams,
+    backend: Box<dyn backend::ProverBackend>,
+    limits: limits::ProofLimits,
+    cost_model: cost::CostModel,
+    budget_hook: Option<std::sync::Arc<dyn cost::BudgetHook>>,
+    tenant: Option<tenant::TenantConfig>,
+    config: config::ProverConfig,
+    #[cfg(feature = "prover")]
+    scoring_profile: Option<String>,
+    #[cfg(feature = "prover")]
+    security_tiers: Vec<policy::SecurityTier>,
+    category_caps: Vec<saturation::CategoryCap>,
+    score_scales: Vec<normalization::ScoreScale>,
+    taxonomy: taxonomy::CategoryTaxonomy,
+    verification_cache: Option<verify_cache::VerificationCache>,
+    verification_limiter: Option<std::sync::Arc<concurrency::VerificationLimiter>>,
+    prover_identity: Option<identity::ProverIdentity>,
+    enclave: Option<tee::EnclaveContext>,
+    quote_verifier: Option<std::sync::Arc<dyn tee::QuoteVerifier>>,
+    telemetry: Option<std::sync::Arc<telemetry::TelemetryCollector>>,
+    #[cfg(feature = "prover")]
+    clock: std::sync::Arc<dyn clock::Clock>,
+    #[cfg(feature = "prover")]
+    wallet_salt: wallet::WalletSalt,
+    /// ID of the request the current `correlated` call serves
+    correlation_id: Option<String>,
+    /// Challenge of the session the current `challenged` call serves
+    session_challenge: Option<[u8; 32]>,
 }
 
 impl RepIDZKPSystem {
     /// Create a new RepID ZKP system with security parameters
     pub fn new(security_level: SecurityLevel) -> Self {
-        let (num_queries, blowup_factor) = match security_level {
-            SecurityLevel::Fast => (40, 4),      // ~80-bit security
-            SecurityLevel::Standard => (80, 8),   // ~128-bit security  
-            SecurityLevel::High => (120, 16),    // ~192-bit security
-        };
+        Self::with_custom_stark(config::ProverConfig::new(security_level))
+    }
+
+    /// Create a system from a loaded prover configuration
+    pub fn from_config(config: config::ProverConfig) -> Result<Self> {
+        match config.backend {
+            backend::BackendKind::CustomStark => Ok(Self::with_custom_stark(config)),
+        }
+    }
+
+    fn with_custom_stark(config: config::ProverConfig) -> Self {
+        let params = config.stark_params();
+        tracing::info!(
+            "RepID ZKP system: {:?} security, {} queries, blowup {}, ~{:.1} estimated security bits",
+            config.security_level,
+            params.num_queries,
+            params.blowup_factor,
+            params.estimated_security_bits()
+        );
+
+        let mut custom = backend::CustomStarkBackend::new(params.num_queries, params.blowup_factor);
+        #[cfg(feature = "prover")]
+        {
+            custom.prover.hash_backend = config.hash_backend;
+            custom.prover.fri_folding_arity = params.fri_folding_arity;
+            custom.prover.limits = config.limits;
+        }
+        custom.verifier.fri_folding_arity = params.fri_folding_arity;
+        custom.limits = config.limits;
 
         Self {
-            prover: custom_stark::CustomStarkProver::new(num_queries, blowup_factor),
-            verifier: custom_stark::CustomStarkVerifier::new(num_queries, blowup_factor),
+            params,
+            backend: Box::new(custom),
+            limits: config.limits,
+            cost_model: cost::CostModel::default(),
+            budget_hook: None,
+            tenant: None,
+            config,
+            #[cfg(feature = "prover")]
+            scoring_profile: None,
+            #[cfg(feature = "prover")]
+            security_tiers: Vec::new(),
+            category_caps: Vec::new(),
+            score_scales: Vec::new(),
+            taxonomy: taxonomy::CategoryTaxonomy::default(),
+            verification_cache: None,
+            verification_limiter: None,
+            prover_identity: None,
+            enclave: None,
+            quote_verifier: None,
+            telemetry: None,
+            #[cfg(feature = "prover")]
+            clock: std::sync::Arc::new(clock::SystemClock),
+            #[cfg(feature = "prover")]
+            wallet_salt: wallet::WalletSalt::default(),
+            correlation_id: None,
+            session_challenge: None,
+        }
+    }
+
+    /// Replace the proving backend
+    pub fn with_backend(mut self, backend: Box<dyn backend::ProverBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Backend able to verify proofs produced by `kind`
+    fn verifier_backend(&self, kind: backend::BackendKind) -> Result<&dyn backend::ProverBackend> {
+        if self.backend.kind() == kind {
+            Ok(self.backend.as_ref())
+        } else {
+            Err(ZKPError::ConfigError(format!("no verifier configured for {:?} proofs", kind)))
+        }
+    }
+
+    /// Proving stack in use
+    pub fn backend_kind(&self) -> backend::BackendKind {
+        self.backend.kind()
+    }
+
+    #[cfg(feature = "prover")]
+    fn custom_stark_mut(&mut self) -> Result<&mut backend::CustomStarkBackend> {
+        let kind = self.backend.kind();
+        self.backend.custom_stark_mut().ok_or_else(|| {
+            ZKPError::ConfigError(format!("operation requires the custom STARK backend, not {:?}", kind))
+        })
+    }
+
+    /// Configuration the system was built from
+    pub fn config(&self) -> &config::ProverConfig {
+        &self.config
+    }
+
+    /// Create a system serving a single tenant
+    ///
+    /// Proofs carry the tenant's tag and only verify under the same tenant.
+    pub fn for_tenant(config: tenant::TenantConfig) -> Self {
+        let mut system = Self::new(config.security_level);
+        let tag = config.id.field_tag();
+        if let Some(custom) = system.backend.custom_stark_mut() {
+            #[cfg(feature = "prover")]
+            {
+                custom.prover.tenant_tag = Some(tag);
+            }
+            custom.verifier.tenant_tag = Some(tag);
+        }
+        system.tenant = Some(config);
+        system
+    }
+
+    /// Tenant profile in use, if any
+    pub fn tenant(&self) -> Option<&tenant::TenantConfig> {
+        self.tenant.as_ref()
+    }
+
+    /// Concrete STARK parameters in use
+    pub fn params(&self) -> &custom_stark::StarkParams {
+        &self.params
+    }
+
+    /// Switch to the cheapest parameters `verifier_policy` accepts
+    ///
+    /// Takes the verifier's advertised minimum query count and blowup, keeping
+    /// this prover's folding arity and grinding, so a prover configured above
+    /// the minimum steps down and one below it steps up. Proofs record the
+    /// negotiated set and are checked under it rather than under whatever the
+    /// verifier is configured with.
+    #[cfg(feature = "prover")]
+    pub fn negotiate_params(&mut self, verifier_policy: &policy::VerifyPolicy) -> Result<custom_stark::StarkParams> {
+        let minimum = verifier_policy.min_params();
+        let params = custom_stark::StarkParams {
+            num_queries: minimum.num_queries,
+            blowup_factor: minimum.blowup_factor,
+            ..self.params
+        };
+        params.validate()?;
+        limits::ProofLimits::check("queries", params.num_queries as u64, self.limits.max_queries as u64)?;
+
+        let custom = self.custom_stark_mut()?;
+        custom.prover.num_queries = params.num_queries;
+        custom.prover.blowup_factor = params.blowup_factor;
+        custom.verifier = custom.verifier.with_params(&params);
+        self.config.num_queries = Some(params.num_queries);
+        self.config.blowup_factor = Some(params.blowup_factor);
+        self.params = params;
+        tracing::info!(
+            "Negotiated {} queries, blowup {}, ~{:.1} estimated security bits",
+            params.num_queries,
+            params.blowup_factor,
+            params.estimated_security_bits()
+        );
+        Ok(params)
+    }
+
+    /// Estimated security of the configured parameters, in bits
+    pub fn estimated_security_bits(&self) -> f64 {
+        self.params.estimated_security_bits()
+    }
+
+    /// Override the resource limits enforced when proving and verifying
+    pub fn with_limits(mut self, limits: limits::ProofLimits) -> Self {
+        self.config.limits = limits;
+        self.limits = limits;
+        if let Some(custom) = self.backend.custom_stark_mut() {
+            #[cfg(feature = "prover")]
+            {
+                custom.prover.limits = limits;
+            }
+            custom.limits = limits;
+        }
+        self
+    }
+
+    /// Record the scoring rules behind proven scores in every result's metadata
+    #[cfg(feature = "prover")]
+    pub fn with_scoring_profile(mut self, profile: &hierarchical_scoring::ScoringProfile) -> Self {
+        self.set_scoring_profile(profile);
+        self
+    }
+
+    /// Cap category contributions in threshold proofs and require the same caps when verifying
+    pub fn with_category_caps(mut self, caps: Vec<saturation::CategoryCap>) -> Self {
+        if let Some(custom) = self.backend.custom_stark_mut() {
+            #[cfg(feature = "prover")]
+            {
+                custom.prover.category_caps = caps.clone();
+            }
+            custom.verifier.category_caps = caps.clone();
+        }
+        self.category_caps = caps;
+        self
+    }
+
+    /// Normalize issuer scales onto the canonical 0–1000 scale in threshold proofs and require the same scales when verifying
+    pub fn with_score_scales(mut self, scales: Vec<normalization::ScoreScale>) -> Self {
+        if let Some(custom) = self.backend.custom_stark_mut() {
+            #[cfg(feature = "prover")]
+            {
+                custom.prover.score_scales = scales.clone();
+            }
+            custom.verifier.score_scales = scales.clone();
+        }
+        self.score_scales = scales;
+        self
+    }
+
+    /// Read proof timestamps from `clock` instead of the wall clock
+    #[cfg(feature = "prover")]
+    pub fn with_clock(mut self, clock: std::sync::Arc<dyn clock::Clock>) -> Self {
+        if let Some(custom) = self.backend.custom_stark_mut() {
+            custom.prover.clock = clock.clone();
+        }
+        self.clock = clock;
+        self
+    }
+
+    /// Commit proofs to wallets under `salt` instead of the empty salt
+    ///
+    /// Every proof labels its envelope with the wallet commitment, and
+    /// threshold proofs carry it as their public identity input.
+    #[cfg(feature = "prover")]
+    pub fn with_wallet_salt(mut self, salt: wallet::WalletSalt) -> Self {
+        self.wallet_salt = salt;
+        self
+    }
+
+    /// Commitment proofs made by this system carry for `wallet_address`
+    #[cfg(feature = "prover")]
+    pub fn commit_wallet(&self, wallet_address: &str) -> Result<wallet::WalletCommitment> {
+        wallet::WalletCommitment::new(wallet_address, &self.wallet_salt)
+    }
+
+    /// Commitment sustained and score delta proofs made by this system carry for `wallet_address`
+    ///
+    /// Taken over the wallet tag the epoch snapshot leaves hash, so the circuit
+    /// can recompute it from the leaves it opens.
+    #[cfg(feature = "prover")]
+    pub fn commit_snapshot_wallet(&self, wallet_address: &str) -> Result<wallet::WalletCommitment> {
+        wallet::normalize_address(wallet_address)?;
+        Ok(wallet::WalletCommitment::of_tag(ledger::wallet_tag(wallet_address), &self.wallet_salt))
+    }
+
+    /// Draw prover randomness from `provider` instead of OS entropy
+    #[cfg(feature = "prover")]
+    pub fn with_rng_provider(mut self, provider: std::sync::Arc<dyn entropy::RngProvider>) -> Self {
+        if let Some(custom) = self.backend.custom_stark_mut() {
+            custom.prover.rng = provider;
+        }
+        self
+    }
+
+    /// Report proving stages and overall percentage to `observer`, e.g. from `progress::channel`
+    #[cfg(feature = "prover")]
+    pub fn with_progress(mut self, observer: std::sync::Arc<dyn progress::ProgressObserver>) -> Self {
+        if let Some(custom) = self.backend.custom_stark_mut() {
+            custom.prover.progress = Some(observer);
+        }
+        self
+    }
+
+    /// Roll app-defined categories up into their top-level parents before threshold proofs
+    pub fn with_taxonomy(mut self, taxonomy: taxonomy::CategoryTaxonomy) -> Self {
+        self.taxonomy = taxonomy;
+        self
+    }
+
+    /// Memoize verification outcomes by proof hash for `ttl`, keeping at most `max_entries`
+    pub fn with_verification_cache(mut self, ttl: std::time::Duration, max_entries: usize) -> Self {
+        self.verification_cache = Some(verify_cache::VerificationCache::new(ttl, max_entries));
+        self
+    }
+
+    /// Verification cache, when enabled
+    pub fn verification_cache(&self) -> Option<&verify_cache::VerificationCache> {
+        self.verification_cache.as_ref()
+    }
+
+    /// Run verifications through `limiter`, queued under this system's tenant
+    ///
+    /// Share one limiter between the systems of every tenant on a node so
+    /// they take turns for its verification slots.
+    pub fn with_verification_limiter(mut self, limiter: std::sync::Arc<concurrency::VerificationLimiter>) -> Self {
+        self.verification_limiter = Some(limiter);
+        self
+    }
+
+    /// Wait for a verification slot when a limiter is configured
+    fn verification_slot(&self) -> Result<Option<concurrency::VerificationPermit>> {
+        let Some(limiter) = &self.verification_limiter else {
+            return Ok(None);
+        };
+        let tenant = self.tenant.as_ref().map_or(concurrency::DEFAULT_TENANT, |tenant| tenant.id.as_str());
+        limiter.acquire(tenant).map(Some)
+    }
+
+    /// Sign every proof envelope produced by this system
+    pub fn with_prover_identity(mut self, identity: identity::ProverIdentity) -> Self {
+        self.prover_identity = Some(identity);
+        self
+    }
+
+    /// Attest every proof envelope from inside an enclave running `binary_hash` with `config_hash`
+    pub fn with_enclave(
+        mut self,
+        provider: std::sync::Arc<dyn tee::QuoteProvider>,
+        binary_hash: [u8; 32],
+        config_hash: [u8; 32],
+    ) -> Self {
+        self.enclave = Some(tee::EnclaveContext { provider, binary_hash, config_hash });
+        self
+    }
+
+    /// Platform quote verifier used for policies that require enclave attestation
+    pub fn with_quote_verifier(mut self, verifier: std::sync::Arc<dyn tee::QuoteVerifier>) -> Self {
+        self.quote_verifier = Some(verifier);
+        self
+    }
+
+    /// Report anonymous proving statistics to `collector`
+    pub fn with_telemetry(mut self, collector: std::sync::Arc<telemetry::TelemetryCollector>) -> Self {
+        self.telemetry = Some(collector);
+        self
+    }
+
+    /// Record a failure at `stage` and pass the error through
+    #[cfg(feature = "prover")]
+    fn fail(&self, stage: telemetry::TelemetryStage, error: ZKPError) -> ZKPError {
+        if let Some(collector) = &self.telemetry {
+            collector.record_failure(stage);
+        }
+        error
+    }
+
+    #[cfg(feature = "prover")]
+    fn check_proof_size(&self, proof_bytes: usize) -> Result<()> {
+        self.limits
+            .check_proof_bytes(proof_bytes)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::ProofSize, e))
+    }
+
+    /// Attach the enclave quote and the prover signature, then record the finished proof
+    #[cfg(feature = "prover")]
+    fn finish_envelope(&self, mut proof: RepIDProof) -> Result<RepIDProof> {
+        if proof.metadata.backend == backend::BackendKind::CustomStark {
+            proof.metadata.stark_params = Some(self.params);
+        }
+        let attested = match &self.enclave {
+            Some(enclave) => enclave.attest(&mut proof),
+            None => Ok(()),
+        };
+        let signed = attested.and_then(|_| match &self.prover_identity {
+            Some(identity) => identity.sign(&mut proof),
+            None => Ok(()),
+        });
+        signed.map_err(|e| self.fail(telemetry::TelemetryStage::Signing, e))?;
+
+        if let Some(collector) = &self.telemetry {
+            collector.record_success(self.config.security_level, proof.metadata.generation_time_ms, proof.proof_data.len());
+        }
+        Ok(proof)
+    }
+
+    /// Override the credit prices used for cost accounting
+    pub fn with_cost_model(mut self, cost_model: cost::CostModel) -> Self {
+        self.cost_model = cost_model;
+        self
+    }
+
+    /// Consult `hook` before every proof and report completed charges to it
+    pub fn with_budget_hook(mut self, hook: std::sync::Arc<dyn cost::BudgetHook>) -> Self {
+        self.budget_hook = Some(hook);
+        self
+    }
+
+    /// Price a proof of the given operation and trace shape
+    pub fn estimate_cost(&self, operation_type: &str, shape: cost::TraceShape) -> cost::ProofCharge {
+        cost::ProofCharge {
+            operation_type: operation_type.to_string(),
+            shape,
+            credits: self.cost_model.cost(&self.params, &shape),
+        }
+    }
+
+    /// Apply the tenant profile and validate the result before any trace is built
+    #[cfg(feature = "prover")]
+    fn tenant_request(&self, request: &ThresholdVerificationRequest) -> Result<ThresholdVerificationRequest> {
+        let request = match &self.tenant {
+            Some(tenant) => tenant.apply(request)?,
+            None => request.clone(),
+        };
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Refuse to prove predicates a security tier covers below its parameters
+    ///
+    /// Typically the tiers of the relying party's `VerifyPolicy`, so proofs it
+    /// would reject as downgraded are never generated.
+    #[cfg(feature = "prover")]
+    pub fn with_security_tiers(mut self, tiers: &[policy::SecurityTier]) -> Self {
+        self.security_tiers = tiers.to_vec();
+        self
+    }
+
+    #[cfg(feature = "prover")]
+    fn check_security_tiers(&self, operation: &str, request: Option<&ThresholdVerificationRequest>) -> Result<()> {
+        let predicate = policy::Predicate::of_request(operation, request);
+        policy::check_security_tiers(&self.security_tiers, &predicate, &self.params)
+    }
+
+    #[cfg(feature = "prover")]
+    fn admit(&self, charge: &cost::ProofCharge) -> Result<()> {
+        if let Some(collector) = &self.telemetry {
+            collector.record_attempt();
+        }
+        match &self.budget_hook {
+            Some(hook) => hook
+                .admit(charge)
+                .into_result()
+                .map_err(|e| self.fail(telemetry::TelemetryStage::Admission, e)),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "prover")]
+    fn record_charge(&self, charge: &cost::ProofCharge) {
+        if let Some(hook) = &self.budget_hook {
+            hook.record(charge);
+        }
+    }
+
+    /// Scores as threshold proofs count them: normalized, then capped
+    #[cfg(feature = "prover")]
+    fn adjusted_scores(&self, user_scores: &[(RepIDCategory, u32)]) -> Vec<(RepIDCategory, u32)> {
+        let normalized = normalization::normalize_scores(&self.score_scales, user_scores);
+        saturation::apply_caps(&self.category_caps, &normalized)
+    }
+
+    /// Total a threshold proof over `scores` compares, decayed over the request's window
+    #[cfg(feature = "prover")]
+    fn decayed_total(&self, request: &ThresholdVerificationRequest, scores: &[(RepIDCategory, u32)]) -> u64 {
+        match &request.decay_params {
+            Some(decay_params) => {
+                let as_of = if request.as_of == 0 { self.clock.now() } else { request.as_of };
+                decay_params.decayed_total(scores, decay::decay_span(as_of, request.time_window))
+            }
+            None => scores.iter().map(|(_, score)| *score as u64).sum(),
         }
     }
 
     /// Generate threshold verification proof
+    #[cfg(feature = "prover")]
     pub fn prove_threshold_verification(
         &mut self,
         request: &ThresholdVerificationRequest,
         user_scores: &[(RepIDCategory, u32)],
         wallet_address: &str,
     ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        let rolled_up = self.taxonomy.roll_up(user_scores);
+        let user_scores = &rolled_up[..];
+        self.limits.check_categories(request.categories.len())?;
+        self.limits.check_scores(user_scores)?;
+
+        let charge = self.estimate_cost("threshold_verification", cost::TraceShape::threshold(user_scores.len()));
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
         let start_time = std::time::Instant::now();
 
-        // Generate STARK proof
-        let stark_proof = self.prover.prove_threshold_verification(
-            user_scores,
-            request.threshold,
-            request.time_window,
-            request.decay_params.as_ref(),
-        )?;
+        // Generate and serialize proof
+        let backend::BackendProof { proof_data, public_inputs } = self.backend.prove_threshold(request, user_scores, &wallet)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
 
         let generation_time = start_time.elapsed().as_millis() as u64;
-
-        // Serialize proof
-        let proof_data = bincode::serialize(&stark_proof)
-            .map_err(|e| ZKPError::SerializationError(e.to_string()))?;
+        self.check_proof_size(proof_data.len())?;
+        self.record_charge(&charge);
 
         // Calculate if threshold is met (privately)
-        let total_score: u32 = user_scores.iter()
+        let requested: Vec<_> = self.adjusted_scores(user_scores).into_iter()
             .filter(|(cat, _)| request.categories.contains(cat))
-            .map(|(_, score)| *score)
-            .sum();
+            .collect();
+        let total_score = self.decayed_total(request, &requested);
 
-        let meets_threshold = total_score >= request.threshold;
+        let meets_threshold = total_score >= request.threshold as u64;
 
         let repid_proof = RepIDProof {
             proof_data: proof_data.clone(),
-            public_inputs: stark_proof.public_inputs,
+            public_inputs,
             metadata: ProofMetadata {
                 operation_type: "threshold_verification".to_string(),
-                timestamp: chrono::Utc::now().timestamp() as u64,
-                wallet_hash: format!("{:x}", md5::compute(wallet_address.as_bytes())),
+                timestamp: self.clock.now(),
+                wallet_hash: wallet.to_hex(),
                 proof_size: proof_data.len(),
                 generation_time_ms: generation_time,
+                backend: self.backend.kind(),
+                prover_signature: None,
+                tee_attestation: None,
+                content_address: None,
+                protocol_version: protocol::PROTOCOL_VERSION,
+                stark_params: None,
+                issuer_signatures: Vec::new(),
             },
         };
 
@@ -188,146 +876,1732 @@ impl RepIDZKPSystem {
             threshold_used: request.threshold,
             time_window_applied: request.time_window,
             decay_applied: request.decay_params.is_some(),
+            scoring_profile: self.scoring_profile.clone(),
+            categories_commitment: None,
         };
 
         Ok(ThresholdVerificationResult {
             meets_threshold,
-            proof: repid_proof,
+            proof: self.finish_envelope(repid_proof)?,
             metadata: verification_metadata,
         })
     }
 
-    /// Generate biometric 4FA verification proof
-    pub fn prove_biometric_4fa(
+    /// Generate a threshold proof over scores net of slashing penalties
+    #[cfg(feature = "prover")]
+    pub fn prove_penalized_threshold_verification(
         &mut self,
-        webauthn_challenge: [u8; 32],
-        biometric_hash: [u8; 32],
-        factor_proofs: &[bool; 4],
-    ) -> Result<RepIDProof> {
+        request: &ThresholdVerificationRequest,
+        user_scores: &[(RepIDCategory, u32)],
+        penalties: &[slashing::PenaltyEvent],
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        let rolled_up = self.taxonomy.roll_up(user_scores);
+        let user_scores = &rolled_up[..];
+        let penalties = &self.taxonomy.roll_up_penalties(penalties)[..];
+        self.limits.check_categories(request.categories.len())?;
+        self.limits.check_scores(user_scores)?;
+        self.limits.check_categories(user_scores.len() + penalties.len())?;
+
+        let charge = self.estimate_cost(
+            "threshold_verification",
+            cost::TraceShape::threshold(user_scores.len() + penalties.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
         let start_time = std::time::Instant::now();
 
-        // Generate STARK proof
-        let stark_proof = self.prover.prove_biometric_verification(
-            webauthn_challenge,
-            biometric_hash,
-            factor_proofs,
-        )?;
+        let stark_proof = self.custom_stark_mut()?.with_wallet(&wallet, |prover| {
+            prover.prove_penalized_threshold_verification(
+                user_scores,
+                penalties,
+                request.threshold,
+                request.time_window,
+                request.as_of,
+                request.decay_params.as_ref(),
+            )
+        })
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
 
         let generation_time = start_time.elapsed().as_millis() as u64;
 
-        // Serialize proof
-        let proof_data = bincode::serialize(&stark_proof)
-            .map_err(|e| ZKPError::SerializationError(e.to_string()))?;
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                "threshold_verification",
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
 
-        Ok(RepIDProof {
-            proof_data: proof_data.clone(),
-            public_inputs: stark_proof.public_inputs,
-            metadata: ProofMetadata {
-                operation_type: "biometric_4fa".to_string(),
-                timestamp: chrono::Utc::now().timestamp() as u64,
-                wallet_hash: "biometric_verification".to_string(),
-                proof_size: proof_data.len(),
-                generation_time_ms: generation_time,
+        let requested: Vec<_> = self.adjusted_scores(user_scores).into_iter()
+            .filter(|(cat, _)| request.categories.contains(cat))
+            .collect();
+        let total_score = self.decayed_total(request, &requested) as i64
+            + penalties.iter()
+                .filter(|p| request.categories.contains(&p.category))
+                .map(|p| p.signed_amount())
+                .sum::<i64>();
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as i64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
             },
         })
     }
 
-    /// Verify any RepID proof
-    pub fn verify_proof(&self, proof: &RepIDProof, _request: Option<&ThresholdVerificationRequest>) -> Result<bool> {
-        // Deserialize STARK proof
-        let stark_proof: custom_stark::StarkProof = bincode::deserialize(&proof.proof_data)
-            .map_err(|e| ZKPError::SerializationError(format!("Failed to deserialize proof: {}", e)))?;
+    /// Generate threshold proof against an externally published score commitment
+    #[cfg(feature = "prover")]
+    pub fn prove_committed_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        opening: &commitment::ScoreOpening,
+        score_commitment: &commitment::ScoreCommitment,
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        self.limits.check_categories(request.categories.len())?;
+        self.limits.check_scores(&opening.scores)?;
 
-        // Verify the proof
-        self.verifier.verify_proof(&stark_proof, &proof.metadata.operation_type)
-    }
+        let charge = self.estimate_cost(
+            "committed_threshold_verification",
+            cost::TraceShape::committed_threshold(opening.scores.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
 
-    /// Extract verification data for Solidity contracts
-    pub fn extract_solidity_verification_data(&self, proof: &RepIDProof) -> SolidityVerificationData {
-        SolidityVerificationData {
-            proof_hash: format!("0x{:x}", md5::compute(&proof.proof_data)),
-            public_inputs: proof.public_inputs
-                .iter()
-                .map(|input| format!("0x{:016x}", input.0))
-                .collect(),
-            proof_type: proof.metadata.operation_type.clone(),
-            timestamp: proof.metadata.timestamp,
-            proof_size: proof.metadata.proof_size,
-        }
-    }
-}
+        let start_time = std::time::Instant::now();
 
-/// Security level for proof generation
-#[derive(Debug, Clone, Copy)]
-pub enum SecurityLevel {
-    Fast,      // ~80-bit security, faster proving
-    Standard,  // ~128-bit security, balanced
-    High,      // ~192-bit security, maximum security
-}
+        let stark_proof = self.custom_stark_mut()?.prover.prove_committed_threshold_verification(
+            opening,
+            score_commitment,
+            &request.categories,
+            request.threshold,
+            request.time_window,
+            request.as_of,
+            request.decay_params.as_ref(),
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
 
-/// Data for Solidity contract verification
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SolidityVerificationData {
-    pub proof_hash: String,
-    pub public_inputs: Vec<String>,
-    pub proof_type: String,
-    pub timestamp: u64,
-    pub proof_size: usize,
-}
+        let generation_time = start_time.elapsed().as_millis() as u64;
 
-impl Default for RepIDZKPSystem {
-    fn default() -> Self {
-        Self::new(SecurityLevel::Standard)
-    }
-}
+        let proof_data = wire::to_bytes(&stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, ZKPError::SerializationError(e.to_string())))?;
+        self.check_proof_size(proof_data.len())?;
+        self.record_charge(&charge);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let requested: Vec<_> = opening.scores.iter()
+            .filter(|(cat, _)| request.categories.contains(cat))
+            .cloned()
+            .collect();
+        let total_score = self.decayed_total(request, &requested);
 
-    #[test]
-    fn test_threshold_verification() {
-        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
-        
-        let request = ThresholdVerificationRequest {
-            threshold: 100,
-            categories: vec![RepIDCategory::Technical, RepIDCategory::Governance],
-            time_window: 86400, // 1 day
-            decay_params: None,
-        };
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as u64,
+            proof: self.finish_envelope(RepIDProof {
+                proof_data: proof_data.clone(),
+                public_inputs: stark_proof.public_inputs,
+                metadata: ProofMetadata {
+                    operation_type: "committed_threshold_verification".to_string(),
+                    timestamp: self.clock.now(),
+                    wallet_hash: wallet.to_hex(),
+                    proof_size: proof_data.len(),
+                    generation_time_ms: generation_time,
+                    backend: backend::BackendKind::CustomStark,
+                    prover_signature: None,
+                    tee_attestation: None,
+                    content_address: None,
+                    protocol_version: protocol::PROTOCOL_VERSION,
+                    stark_params: None,
+                    issuer_signatures: Vec::new(),
+                },
+            })?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
 
-        let user_scores = vec![
-            (RepIDCategory::Technical, 75),
-            (RepIDCategory::Governance, 50),
-            (RepIDCategory::Community, 25),
-        ];
+    /// Reveal one committed category score with a proof it matches `score_commitment`
+    ///
+    /// For dispute resolution: support checks the revealed score against the
+    /// commitment an earlier committed threshold proof carries, using
+    /// `commitment::disclosed_score`, without re-running that proof.
+    #[cfg(feature = "prover")]
+    pub fn prove_score_opening(
+        &mut self,
+        opening: &commitment::ScoreOpening,
+        score_commitment: &commitment::ScoreCommitment,
+        category: &RepIDCategory,
+        wallet_address: &str,
+    ) -> Result<RepIDProof> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        self.limits.check_scores(&opening.scores)?;
 
-        let result = zkp_system.prove_threshold_verification(
-            &request,
-            &user_scores,
-            "0x1234567890abcdef",
+        let charge = self.estimate_cost(
+            commitment::SCORE_OPENING_OPERATION,
+            cost::TraceShape::score_opening(opening.scores.len()),
         );
+        self.check_security_tiers(&charge.operation_type, None)?;
+        self.admit(&charge)?;
 
-        assert!(result.is_ok());
-        let proof_result = result.unwrap();
-        assert!(proof_result.meets_threshold); // 75 + 50 = 125 >= 100
-    }
+        let start_time = std::time::Instant::now();
 
-    #[test]
-    fn test_biometric_verification() {
-        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
-        
-        let webauthn_challenge = [1u8; 32];
-        let biometric_hash = [2u8; 32];
-        let factor_proofs = [true, true, true, true];
+        let stark_proof = self.custom_stark_mut()?.prover.prove_score_opening(opening, score_commitment, category)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
 
-        let result = zkp_system.prove_biometric_4fa(
-            webauthn_challenge,
-            biometric_hash,
-            &factor_proofs,
-        );
+        let generation_time = start_time.elapsed().as_millis() as u64;
 
-        assert!(result.is_ok());
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                commitment::SCORE_OPENING_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        self.finish_envelope(proof)
+    }
+
+    /// Generate a threshold proof whose contributing categories stay private
+    ///
+    /// The categories come from `category_set` rather than the request; the
+    /// verification metadata lists none and carries the set commitment instead.
+    #[cfg(feature = "prover")]
+    pub fn prove_hidden_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        user_scores: &[(RepIDCategory, u32)],
+        category_set: &hidden::CategorySetOpening,
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        // Tenant allow-lists still apply to the committed categories
+        let request = &self.tenant_request(&ThresholdVerificationRequest {
+            categories: category_set.categories.clone(),
+            ..request.clone()
+        })?;
+        let rolled_up = self.taxonomy.roll_up(user_scores);
+        let user_scores = &rolled_up[..];
+        self.limits.check_categories(request.categories.len())?;
+        self.limits.check_scores(user_scores)?;
+
+        let charge = self.estimate_cost(
+            hidden::HIDDEN_THRESHOLD_OPERATION,
+            cost::TraceShape::hidden_threshold(category_set.categories.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_hidden_threshold_verification(
+            user_scores,
+            category_set,
+            request.threshold,
+            request.time_window,
+            request.as_of,
+            request.decay_params.as_ref(),
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                hidden::HIDDEN_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        let total_score = self.decayed_total(request, &category_set.select(user_scores));
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as u64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: Vec::new(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: Some(category_set.commit()),
+            },
+        })
+    }
+
+    /// Generate a threshold proof chained to the user's previous epoch proof
+    ///
+    /// `previous` is `None` for the first epoch of a chain.
+    #[cfg(feature = "prover")]
+    pub fn prove_chained_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        user_scores: &[(RepIDCategory, u32)],
+        previous: Option<&RepIDProof>,
+        epoch: u64,
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        self.limits.check_categories(request.categories.len())?;
+        self.limits.check_scores(user_scores)?;
+
+        let charge = self.estimate_cost(
+            chain::CHAINED_THRESHOLD_OPERATION,
+            cost::TraceShape::chained_threshold(user_scores.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let link = match previous {
+            Some(previous) => chain::ChainLink::after(previous, epoch),
+            None => chain::ChainLink::genesis(epoch),
+        };
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_chained_threshold_verification(
+            user_scores,
+            request.threshold,
+            request.time_window,
+            request.as_of,
+            request.decay_params.as_ref(),
+            &link,
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                chain::CHAINED_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        let requested: Vec<_> = user_scores.iter()
+            .filter(|(cat, _)| request.categories.contains(cat))
+            .cloned()
+            .collect();
+        let total_score = self.decayed_total(request, &requested);
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as u64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
+
+    /// Generate a threshold proof whose attestations all satisfy a freshness bound
+    #[cfg(feature = "prover")]
+    pub fn prove_fresh_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        attested: &[freshness::AttestedScore],
+        bound: freshness::FreshnessBound,
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        self.limits.check_categories(request.categories.len())?;
+        self.limits.check_categories(attested.len())?;
+
+        let charge = self.estimate_cost(
+            freshness::FRESH_THRESHOLD_OPERATION,
+            cost::TraceShape::fresh_threshold(attested.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_fresh_threshold_verification(
+            attested,
+            request.threshold,
+            request.time_window,
+            request.as_of,
+            request.decay_params.as_ref(),
+            &bound,
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                freshness::FRESH_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        let requested: Vec<_> = attested.iter()
+            .filter(|a| request.categories.contains(&a.category))
+            .map(|a| (a.category.clone(), a.score))
+            .collect();
+        let total_score = self.decayed_total(request, &requested);
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as u64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
+
+    /// Generate a threshold proof over attestations co-signed as the issuance policies require
+    ///
+    /// Every attestation must be for `wallet_address`; those outside the
+    /// request's categories are left out before the policies are checked.
+    /// The envelope carries each attestation's issuer signatures for
+    /// `issuance::check_policies`.
+    #[cfg(feature = "prover")]
+    pub fn prove_cosigned_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        attestations: &[issuance::CosignedAttestation],
+        policies: &[issuance::IssuancePolicy],
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        if let Some(foreign) = attestations.iter().find(|a| a.subject != wallet_address) {
+            return Err(ZKPError::PolicyViolation(format!("attestation is for {}, not the proving wallet", foreign.subject)));
+        }
+        let attestations: Vec<_> = attestations.iter()
+            .filter(|a| request.categories.contains(&a.attested.category))
+            .cloned()
+            .collect();
+        self.limits.check_categories(request.categories.len())?;
+        self.limits.check_categories(attestations.len())?;
+
+        let charge = self.estimate_cost(
+            issuance::COSIGNED_THRESHOLD_OPERATION,
+            cost::TraceShape::cosigned_threshold(attestations.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_cosigned_threshold_verification(
+            &attestations,
+            policies,
+            request.threshold,
+            request.time_window,
+            request.as_of,
+            request.decay_params.as_ref(),
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let mut proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                issuance::COSIGNED_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        proof.metadata.issuer_signatures = attestations.iter().map(|a| a.signatures.clone()).collect();
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        let attested: Vec<_> = attestations.iter().map(|a| (a.attested.category.clone(), a.attested.score)).collect();
+        let total_score = self.decayed_total(request, &attested);
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as u64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
+
+    /// Generate a threshold proof over attestations the issuer has not revoked
+    ///
+    /// Only attestations in the request's categories back the score; the
+    /// issuer's revocation root is public so relying parties can check it is current.
+    #[cfg(feature = "prover")]
+    pub fn prove_unrevoked_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        attestations: &[revocation::RevocableAttestation],
+        revocations: &revocation::RevocationList,
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        let attestations: Vec<_> = attestations.iter()
+            .filter(|a| request.categories.contains(&a.attested.category))
+            .cloned()
+            .collect();
+        self.limits.check_categories(request.categories.len())?;
+        self.limits.check_categories(attestations.len())?;
+
+        let charge = self.estimate_cost(
+            revocation::UNREVOKED_THRESHOLD_OPERATION,
+            cost::TraceShape::unrevoked_threshold(attestations.len(), revocations.revoked.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_unrevoked_threshold_verification(
+            &attestations,
+            revocations,
+            request.threshold,
+            request.time_window,
+            request.as_of,
+            request.decay_params.as_ref(),
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                revocation::UNREVOKED_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        let attested: Vec<_> = attestations.iter().map(|a| (a.attested.category.clone(), a.attested.score)).collect();
+        let total_score = self.decayed_total(request, &attested);
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as u64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
+
+    /// Generate a threshold proof over a total signed by a trusted scoring oracle
+    ///
+    /// The oracle's signature is checked in-circuit; the oracle root, wallet
+    /// commitment and epoch are public so relying parties can match them with
+    /// `oracle::check_oracle_statement`. Oracle totals are final, so requests
+    /// with decay are rejected.
+    #[cfg(feature = "prover")]
+    pub fn prove_oracle_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        signed: &oracle::SignedScore,
+        oracle_key: &oracle::OraclePublicKey,
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        if request.decay_params.is_some() {
+            return Err(ZKPError::InvalidInput("Oracle totals are already final and cannot be decayed".to_string()));
+        }
+        self.limits.check_categories(request.categories.len())?;
+
+        let charge = self.estimate_cost(
+            oracle::ORACLE_THRESHOLD_OPERATION,
+            cost::TraceShape::oracle_threshold(oracle_key.height as usize),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_oracle_threshold_verification(
+            signed,
+            oracle_key,
+            request.threshold,
+            request.time_window,
+            request.as_of,
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                oracle::ORACLE_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: signed.statement.total_score >= request.threshold,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: false,
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
+
+    /// Generate a threshold proof only the designated verifier is convinced by
+    ///
+    /// With `Designation::To` the scores must meet the threshold; with
+    /// `Designation::Simulated` the verifier produces a proof of the same form
+    /// from its own secret, whatever the scores. Relying parties check the
+    /// addressee with `designated::check_designation`.
+    #[cfg(feature = "prover")]
+    pub fn prove_designated_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        user_scores: &[(RepIDCategory, u32)],
+        designation: &designated::Designation,
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        self.limits.check_categories(request.categories.len())?;
+
+        let charge = self.estimate_cost(
+            designated::DESIGNATED_THRESHOLD_OPERATION,
+            cost::TraceShape::designated_threshold(user_scores.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_designated_threshold_verification(
+            user_scores,
+            designation,
+            request.threshold,
+            request.time_window,
+            request.as_of,
+            request.decay_params.as_ref(),
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                designated::DESIGNATED_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        let total_score = self.decayed_total(request, user_scores);
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as u64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
+
+    /// Generate a threshold proof carrying the exact total encrypted to an escrow agent
+    ///
+    /// Fails unless the total meets the threshold, so verifiers learn only that
+    /// it did. Store `escrow.capsule()` with the proof; the agent recovers the
+    /// total with `escrow::EscrowSecretKey::decrypt_score`.
+    #[cfg(feature = "prover")]
+    pub fn prove_escrowed_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        user_scores: &[(RepIDCategory, u32)],
+        escrow: &escrow::ScoreEscrow,
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        self.limits.check_categories(request.categories.len())?;
+
+        let charge = self.estimate_cost(
+            escrow::ESCROWED_THRESHOLD_OPERATION,
+            cost::TraceShape::escrowed_threshold(user_scores.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_escrowed_threshold_verification(
+            user_scores,
+            escrow.key(),
+            request.threshold,
+            request.time_window,
+            request.as_of,
+            request.decay_params.as_ref(),
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                escrow::ESCROWED_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        let total_score = self.decayed_total(request, user_scores);
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as u64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
+
+    /// Generate a threshold proof over scores pooled from wallets sharing one identity
+    ///
+    /// The envelope is labelled with the identity commitment rather than any wallet.
+    #[cfg(feature = "prover")]
+    pub fn prove_linked_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        identity: &linkage::IdentitySecret,
+        wallets: &[linkage::LinkedWallet],
+    ) -> Result<ThresholdVerificationResult> {
+        let request = &self.tenant_request(request)?;
+        self.limits.check_categories(request.categories.len())?;
+        let num_scores: usize = wallets.iter().map(|w| w.scores.len()).sum();
+        self.limits.check_categories(num_scores)?;
+        for wallet in wallets {
+            self.limits.check_scores(&wallet.scores)?;
+        }
+
+        let charge = self.estimate_cost(
+            linkage::LINKED_THRESHOLD_OPERATION,
+            cost::TraceShape::linked_threshold(num_scores, wallets.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_linked_threshold_verification(
+            identity,
+            wallets,
+            request.threshold,
+            request.time_window,
+            request.as_of,
+            request.decay_params.as_ref(),
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                linkage::LINKED_THRESHOLD_OPERATION,
+                hex::encode(Keccak256::digest(identity.commitment().to_bytes())),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        let requested: Vec<_> = wallets.iter()
+            .flat_map(|w| w.scores.iter())
+            .filter(|(cat, _)| request.categories.contains(cat))
+            .cloned()
+            .collect();
+        let total_score = self.decayed_total(request, &requested);
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as u64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: request.decay_params.is_some(),
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
+
+    /// Generate a proof that the user's score falls in a band of a committed distribution
+    #[cfg(feature = "prover")]
+    pub fn prove_rank_bucket(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        distribution: &rank::ScoreDistribution,
+        distribution_commitment: &rank::DistributionCommitment,
+        top_percent: u8,
+        wallet_address: &str,
+    ) -> Result<RepIDProof> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        self.limits.check_scores(user_scores)?;
+
+        let charge = self.estimate_cost(
+            rank::RANK_BUCKET_OPERATION,
+            cost::TraceShape::rank_bucket(user_scores.len(), distribution.cutoffs.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, None)?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_rank_bucket(
+            user_scores,
+            distribution,
+            distribution_commitment,
+            top_percent,
+        )
+        .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                rank::RANK_BUCKET_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        self.finish_envelope(proof)
+    }
+
+    /// Generate a proof that at least `k` categories each reach `min_per_category`
+    #[cfg(feature = "prover")]
+    pub fn prove_category_count(
+        &mut self,
+        user_scores: &[(RepIDCategory, u32)],
+        min_per_category: u32,
+        k: u32,
+        wallet_address: &str,
+    ) -> Result<RepIDProof> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        // Rolling up merges duplicate entries, so no category is counted twice
+        let rolled_up = self.taxonomy.roll_up(user_scores);
+        let user_scores = &rolled_up[..];
+        self.limits.check_scores(user_scores)?;
+
+        let charge = self.estimate_cost(
+            category_count::CATEGORY_COUNT_OPERATION,
+            cost::TraceShape::category_count(user_scores.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, None)?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover.prove_category_count(user_scores, min_per_category, k)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                category_count::CATEGORY_COUNT_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        self.finish_envelope(proof)
+    }
+
+    /// Prove the decayed aggregate of a wallet's scores over consecutive epoch snapshots clears `threshold`
+    ///
+    /// `history` comes from `EpochManager::history`, oldest epoch first; the
+    /// snapshot roots are public so verifiers can match them to published ones.
+    #[cfg(feature = "prover")]
+    pub fn prove_sustained_threshold(
+        &mut self,
+        history: &[epoch::EpochScores],
+        threshold: u32,
+        decay_rate_bps: u16,
+        wallet_address: &str,
+    ) -> Result<RepIDProof> {
+        let wallet = self.commit_snapshot_wallet(wallet_address)?;
+        let salt = self.wallet_salt;
+        let num_leaves: usize = history.iter().map(|epoch| epoch.leaves.len()).sum();
+        self.limits.check_categories(num_leaves)?;
+        let depth = history.iter()
+            .flat_map(|epoch| epoch.leaves.iter().map(|leaf| leaf.witness.siblings.len()))
+            .max()
+            .unwrap_or(0);
+
+        let charge = self.estimate_cost(
+            sustained::SUSTAINED_THRESHOLD_OPERATION,
+            cost::TraceShape::sustained_threshold(num_leaves, depth, history.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, None)?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover
+            .prove_sustained_threshold(wallet_address, &salt, history, threshold, decay_rate_bps)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                sustained::SUSTAINED_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        self.finish_envelope(proof)
+    }
+
+    /// Prove a wallet's total over the opened categories grew by at least `min_delta` from one epoch to a later one
+    ///
+    /// `from` and `to` come from `EpochManager::epoch_scores` over the same
+    /// categories; both snapshot roots are public, neither total is.
+    #[cfg(feature = "prover")]
+    pub fn prove_score_delta(
+        &mut self,
+        from: &epoch::EpochScores,
+        to: &epoch::EpochScores,
+        min_delta: u32,
+        wallet_address: &str,
+    ) -> Result<RepIDProof> {
+        let wallet = self.commit_snapshot_wallet(wallet_address)?;
+        let salt = self.wallet_salt;
+        self.limits.check_categories(from.leaves.len() + to.leaves.len())?;
+        let depth = from.leaves.iter()
+            .chain(&to.leaves)
+            .map(|leaf| leaf.witness.siblings.len())
+            .max()
+            .unwrap_or(0);
+
+        let charge = self.estimate_cost(
+            score_delta::SCORE_DELTA_OPERATION,
+            cost::TraceShape::score_delta(to.leaves.len(), depth),
+        );
+        self.check_security_tiers(&charge.operation_type, None)?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover
+            .prove_score_delta(wallet_address, &salt, from, to, min_delta)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                score_delta::SCORE_DELTA_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        self.finish_envelope(proof)
+    }
+
+    /// Prove a rate-limited signal from a member of the identity set `membership` opens into
+    ///
+    /// The envelope is labelled with the signal's nullifier rather than a
+    /// wallet, so signals stay unlinkable to their sender. Verifiers check the
+    /// proof with `rln::check_signal` and record the share it returns.
+    #[cfg(feature = "prover")]
+    pub fn prove_rate_limited_signal(
+        &mut self,
+        identity: &linkage::IdentitySecret,
+        membership: &sparse_merkle::SmtWitness,
+        limit: &rln::RateLimit,
+        message_index: u32,
+        signal: &[u8],
+    ) -> Result<RepIDProof> {
+        let charge = self.estimate_cost(rln::RATE_LIMITED_OPERATION, cost::TraceShape::rate_limited_signal());
+        self.check_security_tiers(&charge.operation_type, None)?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover
+            .prove_rate_limited_signal(identity, membership, limit, message_index, rln::signal_hash(signal))
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+        let nullifier = stark_proof.public_inputs[7];
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                rln::RATE_LIMITED_OPERATION,
+                hex::encode(nullifier.to_bytes()),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        self.finish_envelope(proof)
+    }
+
+    /// Generate a threshold proof over individual score events instead of category totals
+    ///
+    /// Events outside the request's categories or time window are left out;
+    /// the rest are proven one per trace row, so the trace grows with the history.
+    #[cfg(feature = "prover")]
+    pub fn prove_history_threshold_verification(
+        &mut self,
+        request: &ThresholdVerificationRequest,
+        events: &[history::ScoreEvent],
+        wallet_address: &str,
+    ) -> Result<ThresholdVerificationResult> {
+        let wallet = self.commit_wallet(wallet_address)?;
+        let request = &self.tenant_request(request)?;
+        self.limits.check_categories(request.categories.len())?;
+        let as_of = if request.as_of == 0 { self.clock.now() } else { request.as_of };
+        let events = history::events_in_window(events, &request.categories, request.time_window, as_of);
+
+        let charge = self.estimate_cost(
+            history::HISTORY_THRESHOLD_OPERATION,
+            cost::TraceShape::history_threshold(events.len()),
+        );
+        self.check_security_tiers(&charge.operation_type, Some(request))?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        let stark_proof = self.custom_stark_mut()?.prover
+            .prove_history_threshold(&events, request.threshold, request.time_window, as_of)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+
+        let proof = backend::CustomStarkBackend::encode(stark_proof)
+            .map_err(|e| self.fail(telemetry::TelemetryStage::Encoding, e))?
+            .into_envelope(
+                backend::BackendKind::CustomStark,
+                history::HISTORY_THRESHOLD_OPERATION,
+                wallet.to_hex(),
+                self.clock.now(),
+                generation_time,
+            );
+        self.check_proof_size(proof.proof_data.len())?;
+        self.record_charge(&charge);
+
+        let total_score: i64 = events.iter().map(|event| event.delta).sum();
+
+        Ok(ThresholdVerificationResult {
+            meets_threshold: total_score >= request.threshold as i64,
+            proof: self.finish_envelope(proof)?,
+            metadata: VerificationMetadata {
+                categories_verified: request.categories.clone(),
+                threshold_used: request.threshold,
+                time_window_applied: request.time_window,
+                decay_applied: false,
+                scoring_profile: self.scoring_profile.clone(),
+                categories_commitment: None,
+            },
+        })
+    }
+
+    /// Generate the proofs a relying party's disclosure policy calls for
+    ///
+    /// Circuits and public inputs follow `DisclosurePolicy::plan`; the witness
+    /// must meet every threshold predicate.
+    #[cfg(feature = "prover")]
+    pub fn prove_for_policy(
+        &mut self,
+        policy: &disclosure::DisclosurePolicy,
+        witness: &disclosure::DisclosureWitness,
+    ) -> Result<disclosure::DisclosurePresentation> {
+        let required = policy.min_security.params();
+        if self.params.num_queries < required.num_queries || self.params.blowup_factor < required.blowup_factor {
+            return Err(ZKPError::ParameterDowngrade(format!(
+                "policy '{}' requires {:?} security",
+                policy.name, policy.min_security
+            )));
+        }
+
+        let wallet_address = witness.wallet_address.as_str();
+        let attested: Vec<freshness::AttestedScore> = witness.attestations.iter().map(|a| a.attested.clone()).collect();
+        let mut proofs = Vec::new();
+        for planned in policy.plan()? {
+            let (request, result) = match &planned {
+                disclosure::PlannedProof::Threshold(request) => {
+                    (request, self.prove_threshold_verification(request, &witness.scores, wallet_address)?)
+                }
+                disclosure::PlannedProof::Cosigned(request) => {
+                    let policies = policy.issuance_policies(&request.categories)?;
+                    let result = self.prove_cosigned_threshold_verification(request, &witness.attestations, &policies, wallet_address)?;
+                    (request, result)
+                }
+                disclosure::PlannedProof::Fresh(request) => {
+                    let freshness = policy.freshness.expect("fresh proofs are only planned under a freshness requirement");
+                    let bound = freshness::FreshnessBound::new(self.clock.now(), freshness.max_age_secs);
+                    (request, self.prove_fresh_threshold_verification(request, &attested, bound, wallet_address)?)
+                }
+                disclosure::PlannedProof::CategoryCount { min_per_category, k } => {
+                    proofs.push(self.prove_category_count(&witness.scores, *min_per_category, *k, wallet_address)?);
+                    continue;
+                }
+            };
+            if !result.meets_threshold {
+                return Err(ZKPError::PolicyViolation(format!(
+                    "witness does not reach the threshold of {} required by policy '{}'",
+                    request.threshold, policy.name
+                )));
+            }
+            proofs.push(result.proof);
+        }
+
+        Ok(disclosure::DisclosurePresentation {
+            policy_hash: policy.hash(),
+            proofs,
+        })
+    }
+
+    /// Prove several predicates about one user in one call
+    ///
+    /// The witness's attestations are checked once before anything is
+    /// proven, every threshold predicate is evaluated at the same instant and
+    /// identical predicates share one proof. The witness must meet every
+    /// threshold predicate.
+    #[cfg(feature = "prover")]
+    pub fn prove_bundle(
+        &mut self,
+        predicates: Vec<bundle::PredicateRequest>,
+        witness: &disclosure::DisclosureWitness,
+    ) -> Result<bundle::ProofBundle> {
+        bundle::check_predicate_count(predicates.len())?;
+        let wallet_address = witness.wallet_address.as_str();
+        for attestation in &witness.attestations {
+            let digest = attestation.signing_digest();
+            if attestation.subject != wallet_address || !attestation.signatures.iter().all(|signed| signed.verifies(&digest)) {
+                return Err(ZKPError::InvalidInput(format!(
+                    "{:?} attestation for {} does not verify",
+                    attestation.attested.category, attestation.subject
+                )));
+            }
+        }
+        let attested: Vec<freshness::AttestedScore> = witness.attestations.iter().map(|a| a.attested.clone()).collect();
+
+        let as_of = self.clock.now();
+        let predicates: Vec<bundle::PredicateRequest> = predicates.iter().map(|p| p.pinned(as_of)).collect();
+        let mut proven: Vec<Vec<u8>> = Vec::new();
+        let mut proofs = Vec::new();
+        let mut answers = Vec::with_capacity(predicates.len());
+        for predicate in &predicates {
+            let key = predicate.canonical_bytes()?;
+            if let Some(answer) = proven.iter().position(|earlier| *earlier == key) {
+                answers.push(answer);
+                continue;
+            }
+            let result = match predicate {
+                bundle::PredicateRequest::Threshold { request } => {
+                    self.prove_threshold_verification(request, &witness.scores, wallet_address)?
+                }
+                bundle::PredicateRequest::Fresh { request, max_age_secs } => {
+                    let bound = freshness::FreshnessBound::new(as_of, *max_age_secs);
+                    self.prove_fresh_threshold_verification(request, &attested, bound, wallet_address)?
+                }
+                bundle::PredicateRequest::Cosigned { request, policies } => {
+                    self.prove_cosigned_threshold_verification(request, &witness.attestations, policies, wallet_address)?
+                }
+                bundle::PredicateRequest::CategoryCount { min_per_category, k } => {
+                    answers.push(proofs.len());
+                    proofs.push(self.prove_category_count(&witness.scores, *min_per_category, *k, wallet_address)?);
+                    proven.push(key);
+                    continue;
+                }
+            };
+            if !result.meets_threshold {
+                return Err(ZKPError::PolicyViolation(format!(
+                    "witness does not reach the threshold of {} in a {} predicate",
+                    result.metadata.threshold_used,
+                    predicate.operation_type()
+                )));
+            }
+            answers.push(proofs.len());
+            proofs.push(result.proof);
+            proven.push(key);
+        }
+
+        bundle::ProofBundle::new(as_of, predicates, answers, proofs)
+    }
+
+    /// Generate biometric 4FA verification proof
+    #[cfg(feature = "prover")]
+    pub fn prove_biometric_4fa(
+        &mut self,
+        webauthn_challenge: [u8; 32],
+        biometric_hash: [u8; 32],
+        factor_proofs: &[bool; 4],
+    ) -> Result<RepIDProof> {
+        let charge = self.estimate_cost("biometric_4fa", cost::TraceShape::biometric());
+        self.check_security_tiers(&charge.operation_type, None)?;
+        self.admit(&charge)?;
+
+        let start_time = std::time::Instant::now();
+
+        // Generate and serialize proof
+        let backend::BackendProof { proof_data, public_inputs } =
+            self.backend.prove_biometric(webauthn_challenge, biometric_hash, factor_proofs)
+                .map_err(|e| self.fail(telemetry::TelemetryStage::Proving, e))?;
+
+        let generation_time = start_time.elapsed().as_millis() as u64;
+        self.check_proof_size(proof_data.len())?;
+        self.record_charge(&charge);
+
+        self.finish_envelope(RepIDProof {
+            proof_data: proof_data.clone(),
+            public_inputs,
+            metadata: ProofMetadata {
+                operation_type: "biometric_4fa".to_string(),
+                timestamp: self.clock.now(),
+                wallet_hash: "biometric_verification".to_string(),
+                proof_size: proof_data.len(),
+                generation_time_ms: generation_time,
+                backend: self.backend.kind(),
+                prover_signature: None,
+                tee_attestation: None,
+                content_address: None,
+                protocol_version: protocol::PROTOCOL_VERSION,
+                stark_params: None,
+                issuer_signatures: Vec::new(),
+            },
+        })
+    }
+
+    /// Stamp subsequent proofs with `profile`, e.g. after promoting a `ProfileRollout`
+    #[cfg(feature = "prover")]
+    pub fn set_scoring_profile(&mut self, profile: &hierarchical_scoring::ScoringProfile) {
+        self.scoring_profile = Some(profile.hash_hex());
+    }
+
+    /// Precompute the process-wide tables proving uses, so the first proof does not pay for them
+    ///
+    /// Derives the Poseidon2 constants, starts the rayon pool and fills the
+    /// domain cache for every threshold proof shape within the configured
+    /// limits. Cheap to repeat; returns the time spent.
+    #[cfg(feature = "prover")]
+    pub fn warm_up(&self) -> Result<std::time::Duration> {
+        let start = std::time::Instant::now();
+        poseidon2::Poseidon2Params::get();
+        rayon::current_num_threads();
+
+        let heights: std::collections::BTreeSet<usize> = (1..=self.limits.max_categories)
+            .flat_map(|n| {
+                [
+                    cost::TraceShape::threshold(n),
+                    cost::TraceShape::committed_threshold(n),
+                    cost::TraceShape::hidden_threshold(n),
+                ]
+            })
+            .map(|shape| shape.height)
+            .filter(|&height| height <= self.limits.max_trace_height)
+            .collect();
+        let cache = domain::DomainCache::global();
+        for height in heights {
+            cache.get(height)?;
+            cache.get(height * self.params.blowup_factor)?;
+        }
+
+        let elapsed = start.elapsed();
+        tracing::debug!(elapsed_ms = elapsed.as_millis() as u64, domains = cache.len(), "warmed up prover");
+        Ok(elapsed)
+    }
+
+    /// Record the Fiat–Shamir transcript of subsequent proofs for audit export
+    #[cfg(feature = "prover")]
+    pub fn set_transcript_export(&mut self, enabled: bool) {
+        if let Some(custom) = self.backend.custom_stark_mut() {
+            custom.prover.record_transcript = enabled;
+            custom.prover.last_transcript = None;
+        }
+    }
+
+    /// Bundle a proof with the transcript recorded while generating it
+    ///
+    /// Returns `None` unless transcript export was enabled before proving.
+    #[cfg(feature = "prover")]
+    pub fn take_audit_artifact(&mut self, proof: &RepIDProof) -> Option<ProofAuditArtifact> {
+        let correlation_id = self.correlation_id.clone();
+        self.backend.custom_stark_mut()?.prover.last_transcript.take().map(|transcript| ProofAuditArtifact {
+            proof: proof.clone(),
+            transcript,
+            correlation_id,
+        })
+    }
+
+    /// Run prove or verify calls on behalf of an upstream request
+    ///
+    /// Everything logged inside runs in a span carrying `correlation_id`,
+    /// errors come back tagged with it, and audit artifacts taken inside
+    /// record it.
+    pub fn correlated<T>(&mut self, correlation_id: &str, call: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let span = tracing::info_span!("repid_request", correlation_id = %correlation_id);
+        let _entered = span.enter();
+        let outer = self.correlation_id.replace(correlation_id.to_string());
+        let result = call(self);
+        self.correlation_id = outer;
+        result.map_err(|e| e.with_correlation_id(correlation_id))
+    }
+
+    /// Run prove or verify calls bound to a verifier's session challenge
+    ///
+    /// Proofs generated inside absorb `challenge` into their Fiat–Shamir
+    /// transcript, so they only verify inside a call bound to the same
+    /// challenge. Verification results are not cached while bound.
+    pub fn challenged<T>(&mut self, challenge: &[u8; 32], call: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let outer = self.set_session_challenge(Some(*challenge))?;
+        let result = call(self);
+        self.set_session_challenge(outer)?;
+        result
+    }
+
+    fn set_session_challenge(&mut self, challenge: Option<[u8; 32]>) -> Result<Option<[u8; 32]>> {
+        let kind = self.backend.kind();
+        let custom = self.backend.custom_stark_mut().ok_or_else(|| {
+            ZKPError::ConfigError(format!("session binding requires the custom STARK backend, not {:?}", kind))
+        })?;
+        #[cfg(feature = "prover")]
+        {
+            custom.prover.session_challenge = challenge;
+        }
+        custom.verifier.session_challenge = challenge;
+        Ok(std::mem::replace(&mut self.session_challenge, challenge))
+    }
+
+    /// Verify any RepID proof
+    pub fn verify_proof(&self, proof: &RepIDProof, request: Option<&ThresholdVerificationRequest>) -> Result<bool> {
+        self.verify_proof_with_policy(proof, request, &policy::VerifyPolicy::default())
+    }
+
+    /// Verify a RepID proof under an explicit policy
+    ///
+    /// In strict mode structural problems, unknown or disallowed operation types
+    /// and parameter downgrades are returned as typed errors instead of `Ok(false)`.
+    pub fn verify_proof_with_policy(
+        &self,
+        proof: &RepIDProof,
+        request: Option<&ThresholdVerificationRequest>,
+        policy: &policy::VerifyPolicy,
+    ) -> Result<bool> {
+        self.verify_with_disclosure(proof, request, None, policy)
+    }
+
+    /// Verify a hidden-category proof whose category set the prover disclosed to this verifier
+    ///
+    /// The policy's category filter is checked against `category_set` after it
+    /// is matched to the commitment the proof was made for.
+    pub fn verify_proof_with_category_set(
+        &self,
+        proof: &RepIDProof,
+        request: Option<&ThresholdVerificationRequest>,
+        category_set: &hidden::CategorySetOpening,
+        policy: &policy::VerifyPolicy,
+    ) -> Result<bool> {
+        self.verify_with_disclosure(proof, request, Some(category_set), policy)
+    }
+
+    fn verify_with_disclosure(
+        &self,
+        proof: &RepIDProof,
+        request: Option<&ThresholdVerificationRequest>,
+        disclosed: Option<&hidden::CategorySetOpening>,
+        policy: &policy::VerifyPolicy,
+    ) -> Result<bool> {
+        // Reject oversized inputs before any decoding or hashing
+        self.limits.check_proof_bytes(proof.proof_data.len())?;
+        if let Some(request) = request {
+            self.limits.check_categories(request.categories.len())?;
+            if let Some(tenant) = &self.tenant {
+                tenant.check_request(request)?;
+            }
+        }
+
+        let cache_key = self.verification_cache
+            .as_ref()
+            .filter(|_| self.session_challenge.is_none() && disclosed.is_none())
+            .map(|_| verify_cache::VerificationCache::key(proof, request, policy));
+        if let (Some(cache), Some(key)) = (&self.verification_cache, &cache_key) {
+            if let Some(valid) = cache.get(key) {
+                return Ok(valid);
+            }
+        }
+
+        let _slot = self.verification_slot()?;
+        let proof_params = proof.metadata.stark_params.as_ref().unwrap_or(&self.params);
+        let outcome = protocol::check_version(proof)
+            .and_then(|_| policy.check_envelope(proof, proof_params))
+            .and_then(|_| policy.check_security_tiers(proof, request, proof_params))
+            .and_then(|_| policy.check_categories(proof, disclosed))
+            .and_then(|_| self.check_enclave(proof, policy))
+            .and_then(|_| self.verifier_backend(proof.metadata.backend))
+            .and_then(|backend| backend.verify(proof, request));
+
+        let valid = Self::settle(outcome, policy)?;
+        if let (Some(cache), Some(key)) = (&self.verification_cache, cache_key) {
+            cache.insert(key, valid);
+        }
+        Ok(valid)
+    }
+
+    /// Verify a proof under the archived scoring profile it was stamped with
+    ///
+    /// `profile_hash` is the `scoring_profile` of the proof's verification
+    /// metadata. Caps and scales come from that profile instead of this
+    /// system's configuration; since adjusted proofs commit to their
+    /// adjustments digest, a proof only verifies under the profile it was
+    /// actually produced with.
+    #[cfg(feature = "scoring")]
+    pub fn verify_proof_under_profile(
+        &self,
+        proof: &RepIDProof,
+        request: Option<&ThresholdVerificationRequest>,
+        profile_hash: &str,
+        archive: &profile_archive::ProfileArchive,
+        policy: &policy::VerifyPolicy,
+    ) -> Result<bool> {
+        self.limits.check_proof_bytes(proof.proof_data.len())?;
+        let profile = archive.resolve(profile_hash)?;
+        let custom = self.backend.custom_stark().ok_or_else(|| {
+            ZKPError::ConfigError(format!("historical verification requires the custom STARK backend, not {:?}", self.backend.kind()))
+        })?;
+        let historical = custom.verifier.with_adjustments(profile.category_caps, profile.score_scales);
+        self.verify_with_custom(custom, &historical, proof, request, policy)
+    }
+
+    /// Verify a threshold proof and check it commits to `wallet`
+    ///
+    /// For relying parties shown the wallet's address and salt: the proof
+    /// must carry the commitment to them as its public identity input.
+    pub fn verify_proof_for_wallet(
+        &self,
+        proof: &RepIDProof,
+        request: Option<&ThresholdVerificationRequest>,
+        wallet: &wallet::WalletCommitment,
+        policy: &policy::VerifyPolicy,
+    ) -> Result<bool> {
+        self.limits.check_proof_bytes(proof.proof_data.len())?;
+        let custom = self.backend.custom_stark().ok_or_else(|| {
+            ZKPError::ConfigError(format!("wallet-bound verification requires the custom STARK backend, not {:?}", self.backend.kind()))
+        })?;
+        let bound = custom.verifier.with_wallet_commitment(wallet.elements());
+        self.verify_with_custom(custom, &bound, proof, request, policy)
+    }
+
+    /// Verify under `verifier` in place of the configured custom STARK verifier
+    fn verify_with_custom(
+        &self,
+        custom: &backend::CustomStarkBackend,
+        verifier: &custom_stark::CustomStarkVerifier,
+        proof: &RepIDProof,
+        request: Option<&ThresholdVerificationRequest>,
+        policy: &policy::VerifyPolicy,
+    ) -> Result<bool> {
+        let _slot = self.verification_slot()?;
+        let proof_params = proof.metadata.stark_params.as_ref().unwrap_or(&self.params);
+        let outcome = protocol::check_version(proof)
+            .and_then(|_| policy.check_envelope(proof, proof_params))
+            .and_then(|_| policy.check_security_tiers(proof, request, proof_params))
+            .and_then(|_| policy.check_categories(proof, None))
+            .and_then(|_| self.check_enclave(proof, policy))
+            .and_then(|_| custom.verify_with(verifier, proof, request));
+        Self::settle(outcome, policy)
+    }
+
+    /// Verification result under `policy`: typed errors when strict, `Ok(false)` otherwise
+    fn settle(outcome: Result<()>, policy: &policy::VerifyPolicy) -> Result<bool> {
+        match outcome {
+            Ok(()) => Ok(true),
+            Err(e) if policy.strict => Err(e),
+            Err(e @ (ZKPError::SerializationError(_) | ZKPError::LimitExceeded { .. })) => Err(e),
+            Err(e) => {
+                tracing::warn!("Proof verification failed: {}", e);
+                Ok(false)
+            }
+        }
+    }
+
+    fn check_enclave(&self, proof: &RepIDProof, policy: &policy::VerifyPolicy) -> Result<()> {
+        let Some(requirement) = &policy.tee else {
+            return Ok(());
+        };
+        let verifier = self.quote_verifier.as_ref().ok_or_else(|| {
+            ZKPError::ConfigError("policy requires enclave attestation but no quote verifier is configured".to_string())
+        })?;
+        requirement.check(proof, verifier.as_ref())
+    }
+
+    /// Extract verification data for Solidity contracts
+    pub fn extract_solidity_verification_data(&self, proof: &RepIDProof) -> SolidityVerificationData {
+        SolidityVerificationData {
+            proof_hash: format!("0x{}", hex::encode(Keccak256::digest(&proof.proof_data))),
+            public_inputs: proof.public_inputs
+                .iter()
+                .map(|input| format!("0x{:016x}", input.0))
+                .collect(),
+            public_inputs_digest: format!(
+                "0x{}",
+                hex::encode(public_inputs::PublicInputs::new(proof.public_inputs.clone()).digest_bytes())
+            ),
+            proof_type: proof.metadata.operation_type.clone(),
+            timestamp: proof.metadata.timestamp,
+            proof_size: proof.metadata.proof_size,
+            size_breakdown: decoding::decode_stark_proof(&proof.proof_data, &self.limits)
+                .ok()
+                .map(|stark_proof| evm::ProofSizeBreakdown::of(&stark_proof)),
+        }
+    }
+}
+
+impl SecurityLevel {
+    /// Concrete STARK parameters for this level
+    pub fn params(&self) -> custom_stark::StarkParams {
+        match self {
+            SecurityLevel::Fast => custom_stark::StarkParams::new(40, 4),       // ~96-bit estimate, query-bound
+            SecurityLevel::Standard => custom_stark::StarkParams::new(80, 8),   // ~124-bit estimate, field-bound
+            SecurityLevel::High => custom_stark::StarkParams::new(120, 16).with_fri_folding_arity(8), // ~124-bit estimate, field-bound
+        }
+    }
+}
+
+/// Proof bundled with its Fiat–Shamir transcript for external auditors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofAuditArtifact {
+    pub proof: RepIDProof,
+    pub transcript: custom_stark::TranscriptLog,
+    /// Request the proof was generated for, when taken inside a `correlated` call
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+}
+
+impl ProofAuditArtifact {
+    /// Structured JSON form of the artifact
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+}
+
+/// Security level for proof generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "lowercase")]
+pub enum SecurityLevel {
+    Fast,      // ~96-bit estimated security, faster proving
+    Standard,  // ~124-bit estimated security, balanced
+    High,      // ~124-bit estimated security, widest query margin
+}
+
+/// Data for Solidity contract verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolidityVerificationData {
+    pub proof_hash: String,
+    pub public_inputs: Vec<String>,
+    /// Single 32-byte word committing to all public inputs
+    pub public_inputs_digest: String,
+    pub proof_type: String,
+    pub timestamp: u64,
+    pub proof_size: usize,
+    /// Per-section sizes of custom STARK proofs
+    #[serde(default)]
+    pub size_breakdown: Option<evm::ProofSizeBreakdown>,
+}
+
+impl Default for RepIDZKPSystem {
+    fn default() -> Self {
+        Self::new(SecurityLevel::Standard)
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_verification() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        
+        let request = ThresholdVerificationRequest {
+            threshold: 100,
+            categories: vec![RepIDCategory::Technical, RepIDCategory::Governance],
+            time_window: 86400, // 1 day
+            as_of: 0,
+            decay_params: None,
+        };
+
+        let user_scores = vec![
+            (RepIDCategory::Technical, 75),
+            (RepIDCategory::Governance, 50),
+            (RepIDCategory::Community, 25),
+        ];
+
+        let result = zkp_system.prove_threshold_verification(
+            &request,
+            &user_scores,
+            "0x1234567890abcdef",
+        );
+
+        assert!(result.is_ok());
+        let proof_result = result.unwrap();
+        assert!(proof_result.meets_threshold); // 75 + 50 = 125 >= 100
+    }
+
+    #[test]
+    fn test_decay_can_flip_the_threshold_result() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 1000,
+            categories: vec![RepIDCategory::Technical, RepIDCategory::Community],
+            time_window: 10 * 86400,
+            as_of: 1_700_000_000,
+            decay_params: None,
+        };
+        let user_scores = [(RepIDCategory::Technical, 600), (RepIDCategory::Community, 500)];
+        assert!(zkp_system.prove_threshold_verification(&request, &user_scores, "0xtest").unwrap().meets_threshold);
+
+        // The raw total clears the threshold but the Q16-decayed total the circuit compares does not
+        let decayed = ThresholdVerificationRequest {
+            decay_params: Some(DecayParameters {
+                base_decay_rate: 500,
+                multiplicative_factor: fixed_point::Q16::ZERO,
+                min_threshold: 0,
+                category_decay: Vec::new(),
+            }),
+            ..request
+        };
+        assert!(zkp_system.decayed_total(&decayed, &user_scores) < 1000);
+        assert!(zkp_system.prove_threshold_verification(&decayed, &user_scores, "0xtest").is_err());
+    }
+
+    #[test]
+    fn test_biometric_verification() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        
+        let webauthn_challenge = [1u8; 32];
+        let biometric_hash = [2u8; 32];
+        let factor_proofs = [true, true, true, true];
+
+        let result = zkp_system.prove_biometric_4fa(
+            webauthn_challenge,
+            biometric_hash,
+            &factor_proofs,
+        );
+
+        assert!(result.is_ok());
         let proof = result.unwrap();
         assert_eq!(proof.metadata.operation_type, "biometric_4fa");
     }
@@ -340,6 +2614,7 @@ mod tests {
             threshold: 50,
             categories: vec![RepIDCategory::Community],
             time_window: 86400,
+            as_of: 0,
             decay_params: None,
         };
 
@@ -355,4 +2630,371 @@ mod tests {
         assert!(verification.is_ok());
         assert!(verification.unwrap());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_security_bits_estimate() {
+        let fast = RepIDZKPSystem::new(SecurityLevel::Fast).estimated_security_bits();
+        let standard = RepIDZKPSystem::new(SecurityLevel::Standard).estimated_security_bits();
+        let high = SecurityLevel::High.params();
+
+        // The estimates the level comments quote
+        assert_eq!((fast.round(), standard.round(), high.estimated_security_bits().round()), (96.0, 124.0, 124.0));
+        // High is bounded by the degree-4 challenge field rather than the query count
+        assert_eq!(high.estimated_security_bits(), high.field_security_bits());
+    }
+
+    #[test]
+    fn test_higher_arity_fri_folding() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::High);
+        assert_eq!(zkp_system.params().fri_folding_arity, 8);
+
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Governance],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Governance, 75)], "0xtest").unwrap();
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+
+        let stark_proof: custom_stark::StarkProof = wire::from_bytes(&result.proof.proof_data).unwrap();
+        let lde_height = 1usize << stark_proof.queries[0].quotient.auth_path.len();
+        let layers = stark_proof.fri_proof.commitments.len();
+        assert_eq!(layers, custom_stark::fri_layer_count(lde_height, 8));
+        assert!(layers < custom_stark::fri_layer_count(lde_height, 2));
+
+        // A verifier expecting binary folding rejects the 8-ary layer structure
+        let params = SecurityLevel::High.params();
+        let binary = custom_stark::CustomStarkVerifier::new(params.num_queries, params.blowup_factor);
+        assert!(matches!(
+            binary.check_proof(&stark_proof, "threshold_verification"),
+            Err(ZKPError::MalformedProof(_))
+        ));
+    }
+
+    #[test]
+    fn test_limits_enforced_on_prove_and_verify() {
+        let tight = limits::ProofLimits { max_categories: 1, ..limits::ProofLimits::default() };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_limits(tight);
+
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community, RepIDCategory::DeFi],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest");
+        assert!(matches!(result, Err(ZKPError::LimitExceeded { ref limit, .. }) if limit == "categories"));
+
+        // A 10 MB blob is rejected before it is deserialized
+        let proof = RepIDProof {
+            proof_data: vec![0u8; 10 * 1024 * 1024],
+            public_inputs: Vec::new(),
+            metadata: ProofMetadata {
+                operation_type: "threshold_verification".to_string(),
+                timestamp: 0,
+                wallet_hash: String::new(),
+                proof_size: 10 * 1024 * 1024,
+                generation_time_ms: 0,
+                backend: backend::BackendKind::CustomStark,
+                prover_signature: None,
+                tee_attestation: None,
+                content_address: None,
+                protocol_version: protocol::PROTOCOL_VERSION,
+                stark_params: None,
+                issuer_signatures: Vec::new(),
+            },
+        };
+        assert!(matches!(
+            zkp_system.verify_proof(&proof, None),
+            Err(ZKPError::LimitExceeded { ref limit, .. }) if limit == "proof_bytes"
+        ));
+    }
+
+    #[test]
+    fn test_transcript_audit_artifact() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        zkp_system.set_transcript_export(true);
+
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+
+        let proof_result = zkp_system.prove_threshold_verification(
+            &request,
+            &[(RepIDCategory::Community, 75)],
+            "0xtest",
+        ).unwrap();
+
+        let artifact = zkp_system.take_audit_artifact(&proof_result.proof).unwrap();
+        assert!(artifact.to_json().unwrap().contains("query_index"));
+
+        // Auditors replaying the transcript from the proof alone get the same log
+        let stark_proof: custom_stark::StarkProof = wire::from_bytes(&proof_result.proof.proof_data).unwrap();
+        assert_eq!(zkp_system.backend.custom_stark().unwrap().verifier.replay_transcript(&stark_proof), artifact.transcript);
+        assert!(zkp_system.take_audit_artifact(&proof_result.proof).is_none());
+    }
+
+    #[test]
+    fn test_correlation_id_tags_errors_and_artifacts() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        zkp_system.set_transcript_export(true);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+
+        let artifact = zkp_system.correlated("req-42", |system| {
+            let result = system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest")?;
+            Ok(system.take_audit_artifact(&result.proof))
+        }).unwrap().unwrap();
+        assert_eq!(artifact.correlation_id.as_deref(), Some("req-42"));
+
+        let err = zkp_system.correlated("req-43", |system| {
+            system.prove_category_count(&[(RepIDCategory::Community, 75)], 50, 0, "0xtest")
+        }).unwrap_err();
+        assert_eq!(err.correlation_id(), Some("req-43"));
+        assert!(err.to_string().starts_with("[req-43] "));
+        assert!(!matches!(err.untagged(), ZKPError::Correlated { .. }));
+
+        // The tag does not leak into later uncorrelated calls
+        let err = zkp_system.prove_category_count(&[(RepIDCategory::Community, 75)], 50, 0, "0xtest").unwrap_err();
+        assert!(err.correlation_id().is_none());
+    }
+
+    #[test]
+    fn test_public_inputs_digest_binding() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+
+        let proof_result = zkp_system.prove_threshold_verification(
+            &request,
+            &[(RepIDCategory::Community, 75)],
+            "0xtest",
+        ).unwrap();
+
+        let solidity_data = zkp_system.extract_solidity_verification_data(&proof_result.proof);
+        let expected = public_inputs::PublicInputs::new(proof_result.proof.public_inputs.clone());
+        assert_eq!(solidity_data.public_inputs_digest, format!("0x{}", hex::encode(expected.digest_bytes())));
+        // Contracts recompute the proof hash with the EVM's native Keccak-256
+        let proof_hash = Keccak256::digest(&proof_result.proof.proof_data);
+        assert_eq!(solidity_data.proof_hash, format!("0x{}", hex::encode(proof_hash)));
+
+        // Tampering with a public input inside the proof breaks the digest binding
+        let mut stark_proof: custom_stark::StarkProof = wire::from_bytes(&proof_result.proof.proof_data).unwrap();
+        stark_proof.public_inputs[0] = F::new(51);
+        let mut tampered = proof_result.proof.clone();
+        tampered.proof_data = wire::to_bytes(&stark_proof).unwrap();
+        assert!(!zkp_system.verify_proof(&tampered, Some(&request)).unwrap());
+    }
+
+    #[test]
+    fn test_as_of_is_public_and_bound() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest::builder()
+            .with_threshold(50)
+            .with_category(RepIDCategory::Community)
+            .with_time_window(86400)
+            .with_as_of(1_700_000_000)
+            .build()
+            .unwrap();
+
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest").unwrap();
+        assert_eq!(result.proof.public_inputs[2], F::new(1_700_000_000));
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+        assert!(zkp_system.verify_proof(&result.proof, None).unwrap());
+
+        let later = ThresholdVerificationRequest { as_of: 1_700_000_060, ..request.clone() };
+        assert!(!zkp_system.verify_proof(&result.proof, Some(&later)).unwrap());
+        assert_ne!(later.canonical_hash(), request.canonical_hash());
+        assert!(ThresholdVerificationRequest { as_of: request::MAX_AS_OF + 1, ..request }.validate().is_err());
+    }
+
+    #[test]
+    fn test_proofs_state_the_request_they_answer() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest::builder()
+            .with_threshold(10)
+            .with_category(RepIDCategory::Technical)
+            .with_time_window(86400)
+            .build()
+            .unwrap();
+        let scores = [(RepIDCategory::Technical, 75), (RepIDCategory::DeFi, 900)];
+        let result = zkp_system.prove_threshold_verification(&request, &scores, "0xtest").unwrap();
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+
+        // Only the requested category is summed and stated
+        let strict = policy::VerifyPolicy::strict(SecurityLevel::Fast);
+        let higher = ThresholdVerificationRequest { threshold: 900, ..request.clone() };
+        let wider = ThresholdVerificationRequest { time_window: 7 * 86400, ..request.clone() };
+        let other = ThresholdVerificationRequest { categories: vec![RepIDCategory::Governance], ..request.clone() };
+        for mismatched in [higher, wider, other] {
+            assert!(matches!(
+                zkp_system.verify_proof_with_policy(&result.proof, Some(&mismatched), &strict),
+                Err(ZKPError::VerificationError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_proofs_state_the_decay_they_apply() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let decay = DecayParameters {
+            base_decay_rate: 500,
+            multiplicative_factor: fixed_point::Q16::ZERO,
+            min_threshold: 5,
+            category_decay: Vec::new(),
+        };
+        let request = ThresholdVerificationRequest {
+            threshold: 30,
+            categories: vec![RepIDCategory::Technical],
+            time_window: 10 * 86400,
+            as_of: 1_700_000_000,
+            decay_params: Some(decay.clone()),
+        };
+        let scores = [(RepIDCategory::Technical, 75)];
+        let result = zkp_system.prove_threshold_verification(&request, &scores, "0xtest").unwrap();
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+
+        // The verifier recomputes the floor and factors from its own request
+        let strict = policy::VerifyPolicy::strict(SecurityLevel::Fast);
+        let undecayed = ThresholdVerificationRequest { decay_params: None, ..request.clone() };
+        let raised = ThresholdVerificationRequest {
+            decay_params: Some(DecayParameters { min_threshold: 20, ..decay.clone() }),
+            ..request.clone()
+        };
+        let slower = ThresholdVerificationRequest {
+            decay_params: Some(DecayParameters { base_decay_rate: 100, ..decay.clone() }),
+            ..request.clone()
+        };
+        for mismatched in [undecayed, raised, slower] {
+            assert!(matches!(
+                zkp_system.verify_proof_with_policy(&result.proof, Some(&mismatched), &strict),
+                Err(ZKPError::VerificationError(_))
+            ));
+        }
+
+        // A floor at the threshold would meet it for any scores
+        let floored = ThresholdVerificationRequest {
+            decay_params: Some(DecayParameters { min_threshold: 900, ..decay }),
+            threshold: 900,
+            ..request
+        };
+        assert!(matches!(
+            zkp_system.prove_threshold_verification(&floored, &[(RepIDCategory::Technical, 5)], "0xtest"),
+            Err(ZKPError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_inputs_rejected_before_proving() {
+        let collector = std::sync::Arc::new(telemetry::TelemetryCollector::new(16));
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast)
+            .with_limits(limits::ProofLimits { max_score: 1000, ..limits::ProofLimits::default() })
+            .with_telemetry(collector.clone());
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Technical, RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let scores = [(RepIDCategory::Technical, 40), (RepIDCategory::Community, 30)];
+        let rejection = |result: Result<ThresholdVerificationResult>| match result {
+            Err(ZKPError::InvalidInput(reason)) => reason,
+            other => panic!("expected an input rejection, got {:?}", other.map(|r| r.meets_threshold)),
+        };
+
+        let mut prove = |request: ThresholdVerificationRequest, scores: &[(RepIDCategory, u32)]| {
+            rejection(zkp_system.prove_threshold_verification(&request, scores, "0xtest"))
+        };
+        assert!(prove(ThresholdVerificationRequest { categories: Vec::new(), ..request.clone() }, &scores).contains("category"));
+        assert!(prove(ThresholdVerificationRequest { threshold: 0, ..request.clone() }, &scores).contains("threshold"));
+        assert!(prove(ThresholdVerificationRequest { time_window: u64::MAX, ..request.clone() }, &scores).contains("time window"));
+        let duplicated = ThresholdVerificationRequest { categories: vec![RepIDCategory::Technical; 2], ..request.clone() };
+        assert!(prove(duplicated, &scores).contains("more than once"));
+        assert!(prove(request.clone(), &[(RepIDCategory::Technical, 1001)]).contains("above the configured maximum"));
+        assert_eq!(collector.snapshot().attempts, 0);
+
+        assert!(zkp_system.prove_threshold_verification(&request, &scores, "0xtest").unwrap().meets_threshold);
+        assert_eq!(collector.snapshot().attempts, 1);
+    }
+
+    #[test]
+    fn test_committed_threshold_verification() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+
+        let request = ThresholdVerificationRequest {
+            threshold: 100,
+            categories: vec![RepIDCategory::Technical, RepIDCategory::Governance],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+
+        // Custodial scorer publishes the commitment once
+        let opening = commitment::ScoreOpening::new(
+            vec![
+                (RepIDCategory::Governance, 50),
+                (RepIDCategory::Community, 25),
+                (RepIDCategory::Technical, 75),
+            ],
+            F::new(987654321),
+        );
+        let score_commitment = opening.commit();
+
+        let result = zkp_system.prove_committed_threshold_verification(
+            &request,
+            &opening,
+            &score_commitment,
+            "0xtest",
+        ).unwrap();
+
+        assert!(result.meets_threshold);
+        assert_eq!(result.proof.public_inputs[2..2 + poseidon2::DIGEST_ELEMENTS], score_commitment.0);
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+
+        // An opening that doesn't match the published commitment is rejected
+        let forged = commitment::ScoreOpening::new(vec![(RepIDCategory::Technical, 500)], F::new(987654321));
+        assert!(zkp_system.prove_committed_threshold_verification(&request, &forged, &score_commitment, "0xtest").is_err());
+    }
+
+    #[test]
+    fn test_warm_up_precomputes_shared_tables() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_limits(limits::ProofLimits {
+            max_categories: 4,
+            ..limits::ProofLimits::default()
+        });
+        zkp_system.warm_up().unwrap();
+        let lde_size = cost::TraceShape::threshold(4).height * zkp_system.params.blowup_factor;
+        let warmed = domain::DomainCache::global().get(lde_size).unwrap();
+
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let proof = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest").unwrap().proof;
+        assert!(zkp_system.verify_proof(&proof, Some(&request)).unwrap());
+        assert!(std::sync::Arc::ptr_eq(&warmed, &domain::DomainCache::global().get(lde_size).unwrap()));
+        zkp_system.warm_up().unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+//! Resource Limits
+//!
+//! Configurable bounds enforced on both the prove and verify paths so that
+//! oversized requests or crafted proofs are rejected before expensive work
+
+use serde::{Deserialize, Serialize};
+
+use crate::request::RequestValidationError;
+use crate::{RepIDCategory, Result, ZKPError};
+
+/// Size bounds for requests, traces and proofs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProofLimits {
+    /// Maximum number of categories in a request or score set
+    pub max_categories: usize,
+    /// Maximum execution trace height (rows)
+    pub max_trace_height: usize,
+    /// Maximum encoded proof size in bytes
+    pub max_proof_bytes: u64,
+    /// Maximum Merkle authentication path depth
+    pub max_auth_path_depth: usize,
+    /// Maximum number of FRI layer commitments
+    pub max_commitments: usize,
+    /// Maximum final FRI polynomial length
+    pub max_final_poly_len: usize,
+    /// Maximum number of query responses
+    pub max_queries: usize,
+    /// Maximum number of public inputs
+    pub max_public_inputs: usize,
+    /// Highest score a prover accepts for any one category
+    pub max_score: u32,
+}
+
+impl ProofLimits {
+    /// Fail with `LimitExceeded` when `actual` is above `max`
+    pub fn check(limit: &str, actual: u64, max: u64) -> Result<()> {
+        if actual > max {
+            return Err(ZKPError::LimitExceeded {
+                limit: limit.to_string(),
+                actual,
+                max,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn check_categories(&self, count: usize) -> Result<()> {
+        Self::check("categories", count as u64, self.max_categories as u64)
+    }
+
+    /// Check a score set's size and reject any score above `max_score`
+    pub fn check_scores(&self, scores: &[(RepIDCategory, u32)]) -> Result<()> {
+        self.check_categories(scores.len())?;
+        match scores.iter().find(|(_, score)| *score > self.max_score) {
+            Some((category, score)) => Err(RequestValidationError::ScoreAboveCap {
+                category: category.clone(),
+                score: *score,
+                max: self.max_score,
+            }
+            .into()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn check_trace_height(&self, height: usize) -> Result<()> {
+        Self::check("trace_height", height as u64, self.max_trace_height as u64)
+    }
+
+    pub fn check_proof_bytes(&self, len: usize) -> Result<()> {
+        Self::check("proof_bytes", len as u64, self.max_proof_bytes)
+    }
+
+    pub fn check_auth_path_depth(&self, depth: usize) -> Result<()> {
+        Self::check("auth_path_depth", depth as u64, self.max_auth_path_depth as u64)
+    }
+}
+
+impl Default for ProofLimits {
+    fn default() -> Self {
+        Self {
+            max_categories: 64,
+            max_trace_height: 1 << 16,
+            max_proof_bytes: 1 << 20, // 1 MiB
+            max_auth_path_depth: 32,
+            max_commitments: 32,
+            max_final_poly_len: 256,
+            max_queries: 256,
+            max_public_inputs: 64,
+            max_score: 1_000_000,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Cross-Wallet Linkage
+//!
+//! Binds several wallets to one private identity secret so their scores can be
+//! aggregated in a single proof without revealing which wallets are linked
+
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::wallet_tag;
+use crate::poseidon2;
+use crate::{RepIDCategory, RepIDProof, Result, ZKPError, F};
+
+/// Operation type of linked-wallet threshold proofs
+pub const LINKED_THRESHOLD_OPERATION: &str = "linked_threshold_verification";
+
+/// Private secret shared by every wallet of one identity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentitySecret(pub F);
+
+impl IdentitySecret {
+    /// Derive a secret from caller-held key material
+    pub fn derive(seed: &[u8]) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"RepID_Identity");
+        hasher.update(seed);
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hasher.finalize().as_bytes()[..8]);
+        Self(F::from_bytes(bytes))
+    }
+
+    /// Public identity commitment: Poseidon2(secret)
+    pub fn commitment(&self) -> F {
+        poseidon2::hash_elements(&[self.0])
+    }
+
+    /// Per-wallet binding commitment: Poseidon2(secret, wallet_tag)
+    pub fn binding(&self, wallet_hash: &str) -> F {
+        poseidon2::hash_elements(&[self.0, wallet_tag(wallet_hash)])
+    }
+}
+
+/// One wallet contributing scores to a linked proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedWallet {
+    pub wallet_hash: String,
+    pub scores: Vec<(RepIDCategory, u32)>,
+}
+
+/// Commitment to the set of wallet bindings: Poseidon2(binding_1, ..., binding_k)
+pub fn link_commitment(identity: &IdentitySecret, wallets: &[LinkedWallet]) -> F {
+    let bindings: Vec<F> = wallets.iter().map(|w| identity.binding(&w.wallet_hash)).collect();
+    poseidon2::hash_elements(&bindings)
+}
+
+/// Reject empty wallet sets and wallets listed twice
+pub fn check_wallets(wallets: &[LinkedWallet]) -> Result<()> {
+    if wallets.is_empty() {
+        return Err(ZKPError::InvalidInput("Linked proof needs at least one wallet".to_string()));
+    }
+    for (i, wallet) in wallets.iter().enumerate() {
+        if wallets[..i].iter().any(|w| wallet_tag(&w.wallet_hash) == wallet_tag(&wallet.wallet_hash)) {
+            return Err(ZKPError::InvalidInput(format!("Wallet '{}' is linked twice", wallet.wallet_hash)));
+        }
+    }
+    Ok(())
+}
+
+/// Public linkage statement of a linked proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkageStatement {
+    pub identity_commitment: F,
+    pub link_commitment: F,
+    pub num_wallets: usize,
+}
+
+/// Read the linkage statement a verified proof was made for
+pub fn proof_linkage(proof: &RepIDProof) -> Result<LinkageStatement> {
+    if proof.metadata.operation_type != LINKED_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no wallet linkage",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(2..5) {
+        Some([identity, link, count]) => Ok(LinkageStatement {
+            identity_commitment: *identity,
+            link_commitment: *link,
+            num_wallets: count.0 as usize,
+        }),
+        _ => Err(ZKPError::MalformedProof("Linked proof needs identity, link and wallet count inputs".to_string())),
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    fn wallet(wallet_hash: &str, score: u32) -> LinkedWallet {
+        LinkedWallet {
+            wallet_hash: wallet_hash.to_string(),
+            scores: vec![(RepIDCategory::DeFi, score)],
+        }
+    }
+
+    #[test]
+    fn test_linked_wallets_aggregate_privately() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 60,
+            categories: vec![RepIDCategory::DeFi],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let identity = IdentitySecret::derive(b"power user");
+        let wallets = [wallet("0xaaa", 25), wallet("0xbbb", 20), wallet("0xccc", 30)];
+
+        // No single wallet clears the threshold, the linked set does
+        let result = zkp_system.prove_linked_threshold_verification(&request, &identity, &wallets).unwrap();
+        assert!(result.meets_threshold);
+        assert!(zkp_system.verify_proof(&result.proof, None).unwrap());
+
+        let statement = proof_linkage(&result.proof).unwrap();
+        assert_eq!(statement.identity_commitment, identity.commitment());
+        assert_eq!(statement.link_commitment, link_commitment(&identity, &wallets));
+        assert_eq!(statement.num_wallets, 3);
+        assert!(!result.proof.metadata.wallet_hash.contains("0xaaa"));
+
+        // Duplicated wallets cannot inflate the aggregate
+        let doubled = [wallet("0xccc", 30), wallet("0xccc", 30)];
+        assert!(zkp_system.prove_linked_threshold_verification(&request, &identity, &doubled).is_err());
+    }
+}
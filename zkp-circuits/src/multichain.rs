@@ -0,0 +1,281 @@
+//! Multi-chain Verification Artifacts
+//!
+//! One threshold result exported for every chain family a deployment posts
+//! to. `export` builds the same `VerificationRecord` for each target and
+//! encodes it the way that chain's verifier decodes and hashes natively: ABI
+//! words under Keccak-256 on EVM chains, a JSON execute message under SHA-256
+//! on CosmWasm, SCALE under Blake2b-256 on Substrate and Anchor instruction
+//! data (Borsh, see `solana`) under SHA-256 on Solana.
+
+use std::fmt;
+use std::str::FromStr;
+
+use blake2::digest::consts::U32;
+use blake2::Blake2b;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+use crate::public_inputs::PublicInputs;
+use crate::{Result, ThresholdVerificationResult, ZKPError};
+
+/// Chain family a verification artifact is produced for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainTarget {
+    Evm,
+    CosmWasm,
+    Substrate,
+    Solana,
+}
+
+impl ChainTarget {
+    pub const ALL: [ChainTarget; 4] = [ChainTarget::Evm, ChainTarget::CosmWasm, ChainTarget::Substrate, ChainTarget::Solana];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChainTarget::Evm => "evm",
+            ChainTarget::CosmWasm => "cosm_wasm",
+            ChainTarget::Substrate => "substrate",
+            ChainTarget::Solana => "solana",
+        }
+    }
+
+    /// Hash the chain's runtime offers natively
+    pub fn hash(&self, bytes: &[u8]) -> [u8; 32] {
+        match self {
+            ChainTarget::Evm => Keccak256::digest(bytes).into(),
+            ChainTarget::CosmWasm | ChainTarget::Solana => Sha256::digest(bytes).into(),
+            ChainTarget::Substrate => Blake2b::<U32>::digest(bytes).into(),
+        }
+    }
+}
+
+impl fmt::Display for ChainTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ChainTarget {
+    type Err = ZKPError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "evm" | "ethereum" => Ok(ChainTarget::Evm),
+            "cosm_wasm" | "cosmwasm" | "cosmos" => Ok(ChainTarget::CosmWasm),
+            "substrate" | "polkadot" => Ok(ChainTarget::Substrate),
+            "solana" => Ok(ChainTarget::Solana),
+            other => Err(ZKPError::ConfigError(format!("unknown chain target '{}'", other))),
+        }
+    }
+}
+
+/// Fields every chain's verifier receives, in encoding order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct VerificationRecord {
+    /// Envelope wallet label, the hex wallet commitment for threshold proofs
+    pub subject: String,
+    pub proof_type: String,
+    pub threshold: u32,
+    pub meets_threshold: bool,
+    pub timestamp: u64,
+    pub public_inputs: Vec<u64>,
+    /// Single 32-byte word committing to all public inputs
+    pub public_inputs_digest: [u8; 32],
+    /// Proof bytes under the target chain's hash
+    pub proof_hash: [u8; 32],
+}
+
+impl VerificationRecord {
+    pub fn new(result: &ThresholdVerificationResult, target: ChainTarget) -> Self {
+        let proof = &result.proof;
+        Self {
+            subject: proof.metadata.wallet_hash.clone(),
+            proof_type: proof.metadata.operation_type.clone(),
+            threshold: result.metadata.threshold_used,
+            meets_threshold: result.meets_threshold,
+            timestamp: proof.metadata.timestamp,
+            public_inputs: proof.public_inputs.iter().map(|input| input.0).collect(),
+            public_inputs_digest: PublicInputs::new(proof.public_inputs.clone()).digest_bytes(),
+            proof_hash: target.hash(&proof.proof_data),
+        }
+    }
+
+    /// `abi.encode(bytes32 subject, bytes32 proofType, uint32, bool, uint64, uint64[], bytes32, bytes32)`
+    ///
+    /// Strings are passed as their Keccak-256 so the head stays static; the
+    /// public inputs array is the only dynamic member.
+    fn encode_abi(&self) -> Vec<u8> {
+        let head = [
+            ChainTarget::Evm.hash(self.subject.as_bytes()),
+            ChainTarget::Evm.hash(self.proof_type.as_bytes()),
+            abi_word(self.threshold as u64),
+            abi_word(self.meets_threshold as u64),
+            abi_word(self.timestamp),
+            abi_word(8 * 32),
+            self.public_inputs_digest,
+            self.proof_hash,
+        ];
+        let mut bytes: Vec<u8> = head.concat();
+        bytes.extend(abi_word(self.public_inputs.len() as u64));
+        self.public_inputs.iter().for_each(|input| bytes.extend(abi_word(*input)));
+        bytes
+    }
+
+    /// `{"verify_rep_id": {..}}` execute message, 64-bit integers as strings
+    fn encode_cosmwasm(&self) -> Result<Vec<u8>> {
+        let message = json!({
+            "verify_rep_id": {
+                "subject": self.subject,
+                "proof_type": self.proof_type,
+                "threshold": self.threshold,
+                "meets_threshold": self.meets_threshold,
+                "timestamp": self.timestamp.to_string(),
+                "public_inputs": self.public_inputs.iter().map(u64::to_string).collect::<Vec<_>>(),
+                "public_inputs_digest": hex::encode(self.public_inputs_digest),
+                "proof_hash": hex::encode(self.proof_hash),
+            }
+        });
+        serde_json::to_vec(&message).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    /// SCALE: little-endian integers, compact length prefixes
+    fn encode_scale(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for text in [&self.subject, &self.proof_type] {
+            bytes.extend(scale_compact(text.len() as u64));
+            bytes.extend(text.as_bytes());
+        }
+        bytes.extend(self.threshold.to_le_bytes());
+        bytes.push(self.meets_threshold as u8);
+        bytes.extend(self.timestamp.to_le_bytes());
+        bytes.extend(scale_compact(self.public_inputs.len() as u64));
+        self.public_inputs.iter().for_each(|input| bytes.extend(input.to_le_bytes()));
+        bytes.extend(self.public_inputs_digest);
+        bytes.extend(self.proof_hash);
+        bytes
+    }
+}
+
+/// Encoded verification message for one chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationArtifact {
+    pub target: ChainTarget,
+    pub record: VerificationRecord,
+    /// Bytes submitted to the chain's verifier
+    pub payload: Vec<u8>,
+    /// `payload` under the chain's hash, as its verifier recomputes it
+    pub payload_hash: [u8; 32],
+}
+
+impl VerificationArtifact {
+    pub fn payload_hex(&self) -> String {
+        format!("0x{}", hex::encode(&self.payload))
+    }
+}
+
+/// Verification artifact of `result` for `target`
+pub fn export(result: &ThresholdVerificationResult, target: ChainTarget) -> Result<VerificationArtifact> {
+    let record = VerificationRecord::new(result, target);
+    let payload = match target {
+        ChainTarget::Evm => record.encode_abi(),
+        ChainTarget::CosmWasm => record.encode_cosmwasm()?,
+        ChainTarget::Substrate => record.encode_scale(),
+        ChainTarget::Solana => crate::solana::instruction_data(&record)?,
+    };
+    let payload_hash = target.hash(&payload);
+    Ok(VerificationArtifact { target, record, payload, payload_hash })
+}
+
+/// Artifacts of `result` for each of `targets`, in order
+pub fn export_all(result: &ThresholdVerificationResult, targets: &[ChainTarget]) -> Result<Vec<VerificationArtifact>> {
+    targets.iter().map(|target| export(result, *target)).collect()
+}
+
+fn abi_word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// SCALE compact integer
+fn scale_compact(value: u64) -> Vec<u8> {
+    match value {
+        0..=0x3f => vec![(value as u8) << 2],
+        0x40..=0x3fff => (((value as u16) << 2) | 0b01).to_le_bytes().to_vec(),
+        0x4000..=0x3fff_ffff => (((value as u32) << 2) | 0b10).to_le_bytes().to_vec(),
+        _ => {
+            let len = 8 - value.leading_zeros() as usize / 8;
+            let mut bytes = vec![(((len - 4) as u8) << 2) | 0b11];
+            bytes.extend(&value.to_le_bytes()[..len]);
+            bytes
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_and_hash_vectors() {
+        assert_eq!(scale_compact(1), vec![0x04]);
+        assert_eq!(scale_compact(64), vec![0x01, 0x01]);
+        assert_eq!(scale_compact(1 << 14), vec![0x02, 0x00, 0x01, 0x00]);
+        assert_eq!(scale_compact(1 << 30), vec![0x03, 0x00, 0x00, 0x00, 0x40]);
+        assert_eq!(
+            hex::encode(ChainTarget::Evm.hash(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            hex::encode(ChainTarget::Substrate.hash(b"")),
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+        );
+        for target in ChainTarget::ALL {
+            assert_eq!(target.name().parse::<ChainTarget>().unwrap(), target);
+        }
+        assert!("tron".parse::<ChainTarget>().is_err());
+    }
+
+    #[cfg(feature = "prover")]
+    #[test]
+    fn test_one_result_exports_to_every_chain() {
+        use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest::builder()
+            .with_threshold(50)
+            .with_category(RepIDCategory::Technical)
+            .with_time_window(86_400)
+            .build()
+            .unwrap();
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Technical, 75)], "0xtest").unwrap();
+        let artifacts = export_all(&result, &ChainTarget::ALL).unwrap();
+        let inputs = result.proof.public_inputs.len();
+
+        for artifact in &artifacts {
+            assert_eq!(artifact.payload_hash, artifact.target.hash(&artifact.payload));
+            assert_eq!(artifact.record.subject, result.proof.metadata.wallet_hash);
+            assert!(artifact.record.meets_threshold);
+        }
+        let [evm, cosmwasm, substrate, solana] = &artifacts[..] else { panic!("one artifact per target") };
+        assert_eq!(evm.payload.len(), (9 + inputs) * 32);
+        assert_eq!(&evm.payload[5 * 32..6 * 32], &abi_word(256));
+        assert_eq!(evm.record.public_inputs_digest, cosmwasm.record.public_inputs_digest);
+        assert_eq!(cosmwasm.record.proof_hash, solana.record.proof_hash);
+        assert_ne!(evm.record.proof_hash, substrate.record.proof_hash);
+
+        let message: serde_json::Value = serde_json::from_slice(&cosmwasm.payload).unwrap();
+        assert_eq!(message["verify_rep_id"]["threshold"], 50);
+        assert_eq!(message["verify_rep_id"]["public_inputs"].as_array().unwrap().len(), inputs);
+
+        // SCALE and Borsh differ only in their length prefixes, past the instruction discriminator
+        let subject_len = result.proof.metadata.wallet_hash.len();
+        assert_eq!(substrate.payload[..2], scale_compact(subject_len as u64)[..]);
+        assert_eq!(&solana.payload[8..12], &(subject_len as u32).to_le_bytes());
+        assert_eq!(solana.payload.len() - substrate.payload.len(), 8 + 2 + 2 * 3);
+    }
+}
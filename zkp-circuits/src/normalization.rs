@@ -0,0 +1,209 @@
+//! Score Normalization
+//!
+//! Maps issuer-specific score scales onto the canonical 0–1000 scale before
+//! scoring, so thresholds mean the same thing whichever issuer a score came
+//! from. Division is integer with an explicit rounding policy, and the
+//! scorer and the circuit share it, so a normalized score never differs
+//! between the reported and the proven value.
+
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2;
+use crate::saturation::{caps_digest, CategoryCap};
+use crate::{RepIDCategory, Result, ZKPError, F};
+
+/// Top of the canonical score scale
+pub const CANONICAL_MAX: u32 = 1000;
+
+/// How a normalized score that falls between integers is rounded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingPolicy {
+    /// Towards zero
+    #[default]
+    Floor,
+    /// To nearest, ties to even
+    Bankers,
+}
+
+impl RoundingPolicy {
+    fn id(&self) -> u64 {
+        match self {
+            RoundingPolicy::Floor => 0,
+            RoundingPolicy::Bankers => 1,
+        }
+    }
+
+    /// `numerator / denominator` rounded under this policy
+    pub fn divide(&self, numerator: u64, denominator: u64) -> u64 {
+        let (quotient, remainder) = (numerator / denominator, numerator % denominator);
+        match self {
+            RoundingPolicy::Floor => quotient,
+            RoundingPolicy::Bankers if 2 * remainder > denominator => quotient + 1,
+            RoundingPolicy::Bankers if 2 * remainder == denominator => quotient + (quotient & 1),
+            RoundingPolicy::Bankers => quotient,
+        }
+    }
+
+    /// Whether `numerator - quotient * denominator = remainder` is this policy's rounding
+    pub fn accepts(&self, quotient: u64, remainder: i64, denominator: u64) -> bool {
+        let denominator = denominator as i64;
+        match self {
+            RoundingPolicy::Floor => (0..denominator).contains(&remainder),
+            RoundingPolicy::Bankers => {
+                let twice = 2 * remainder.abs();
+                twice < denominator || (twice == denominator && quotient.is_multiple_of(2))
+            }
+        }
+    }
+}
+
+/// Native score scale of one category's issuer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreScale {
+    pub category: RepIDCategory,
+    pub min: u32,
+    pub max: u32,
+    #[serde(default)]
+    pub rounding: RoundingPolicy,
+}
+
+impl ScoreScale {
+    pub fn new(category: RepIDCategory, min: u32, max: u32, rounding: RoundingPolicy) -> Result<Self> {
+        if max <= min {
+            return Err(ZKPError::ConfigError(format!(
+                "{:?} scale [{}, {}] is empty",
+                category, min, max
+            )));
+        }
+        Ok(Self { category, min, max, rounding })
+    }
+
+    /// The canonical scale itself, for converting out of it
+    pub fn canonical(category: RepIDCategory) -> Self {
+        Self {
+            category,
+            min: 0,
+            max: CANONICAL_MAX,
+            rounding: RoundingPolicy::Floor,
+        }
+    }
+
+    pub fn span(&self) -> u32 {
+        self.max - self.min
+    }
+
+    /// Clamp a score into the scale
+    pub fn clamp(&self, score: u32) -> u32 {
+        score.clamp(self.min, self.max)
+    }
+
+    /// Canonical score of a native one; scores outside the scale are clamped first
+    pub fn normalize(&self, score: u32) -> u32 {
+        let offset = (self.clamp(score) - self.min) as u64;
+        self.rounding.divide(offset * CANONICAL_MAX as u64, self.span() as u64) as u32
+    }
+
+    /// Signed remainder of `normalize`, the circuit witness of its rounding
+    pub fn remainder(&self, score: u32) -> i64 {
+        let offset = (self.clamp(score) - self.min) as i64;
+        offset * CANONICAL_MAX as i64 - self.normalize(score) as i64 * self.span() as i64
+    }
+
+    /// Native score of a canonical one, rounded under this scale's policy
+    pub fn denormalize(&self, canonical: u32) -> u32 {
+        let canonical = canonical.min(CANONICAL_MAX) as u64;
+        self.min + self.rounding.divide(canonical * self.span() as u64, CANONICAL_MAX as u64) as u32
+    }
+
+    /// Commitment input: [category_tag, min, max, rounding_id]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        vec![
+            self.category.field_tag(),
+            F::from_u32(self.min),
+            F::from_u32(self.max),
+            F::new(self.rounding.id()),
+        ]
+    }
+}
+
+/// Convert a score between two issuers' scales through the canonical one
+pub fn convert(score: u32, from: &ScoreScale, to: &ScoreScale) -> u32 {
+    to.denormalize(from.normalize(score))
+}
+
+/// Normalize each score with its category's scale; categories without one are left as is
+pub fn normalize_scores(scales: &[ScoreScale], scores: &[(RepIDCategory, u32)]) -> Vec<(RepIDCategory, u32)> {
+    scores
+        .iter()
+        .map(|(category, score)| {
+            let normalized = scales.iter().find(|scale| scale.category == *category).map_or(*score, |scale| scale.normalize(*score));
+            (category.clone(), normalized)
+        })
+        .collect()
+}
+
+/// Poseidon2 digest of a scale set
+pub fn scales_digest(scales: &[ScoreScale]) -> F {
+    let elements: Vec<F> = scales.iter().flat_map(|scale| scale.to_field_elements()).collect();
+    poseidon2::hash_elements(&elements)
+}
+
+/// Digest of the score adjustments a threshold proof enforced
+///
+/// Without scales this is the cap set digest, as in proofs made before
+/// normalization existed; with scales it also covers the scale set.
+pub fn adjustments_digest(scales: &[ScoreScale], caps: &[CategoryCap]) -> F {
+    if scales.is_empty() {
+        caps_digest(caps)
+    } else {
+        poseidon2::hash_elements(&[scales_digest(scales), caps_digest(caps)])
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_issuer_scales_meet_on_the_canonical_scale() {
+        let percent = ScoreScale::new(RepIDCategory::Technical, 0, 100, RoundingPolicy::Floor).unwrap();
+        let stars = ScoreScale::new(RepIDCategory::Community, 1, 5, RoundingPolicy::Bankers).unwrap();
+        let odd = ScoreScale::new(RepIDCategory::DeFi, 0, 16, RoundingPolicy::Bankers).unwrap();
+        assert!(ScoreScale::new(RepIDCategory::DeFi, 5, 5, RoundingPolicy::Floor).is_err());
+
+        assert_eq!((percent.normalize(72), percent.normalize(250)), (720, 1000));
+        assert_eq!((stars.normalize(1), stars.normalize(3), stars.normalize(0)), (0, 500, 0));
+        // 1000 / 16 = 62.5 rounds to the even 62, 3000 / 16 = 187.5 to 188
+        assert_eq!((odd.normalize(1), odd.normalize(3)), (62, 188));
+        assert_eq!(RoundingPolicy::Floor.divide(3000, 16), 187);
+        assert_eq!(convert(4, &stars, &percent), 75);
+        assert_eq!(convert(75, &percent, &ScoreScale::canonical(RepIDCategory::Technical)), 750);
+
+        let scales = vec![percent, odd];
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_score_scales(scales.clone());
+        let request = ThresholdVerificationRequest {
+            threshold: 900,
+            categories: vec![RepIDCategory::Technical, RepIDCategory::DeFi],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        // 72% and 3/16 normalize to 720 + 188, clearing a 900 threshold raw scores never could
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Technical, 72), (RepIDCategory::DeFi, 3)], "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert_eq!(result.proof.public_inputs[2], adjustments_digest(&scales, &[]));
+        assert!(zkp_system.verify_proof(&result.proof, None).unwrap());
+
+        // Proofs over raw scores are rejected by a verifier expecting the scales
+        let mut unscaled = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let raw = unscaled.prove_threshold_verification(&request, &[(RepIDCategory::Technical, 720), (RepIDCategory::DeFi, 188)], "0xtest").unwrap();
+        assert!(!zkp_system.verify_proof(&raw.proof, None).unwrap());
+
+        // and scaled proofs by verifiers that configured no scales, or others
+        assert!(!unscaled.verify_proof(&result.proof, None).unwrap());
+        let stars_only = RepIDZKPSystem::new(SecurityLevel::Fast).with_score_scales(vec![stars]);
+        assert!(!stars_only.verify_proof(&result.proof, None).unwrap());
+    }
+}
@@ -0,0 +1,307 @@
+//! Oracle-Signed Scores
+//!
+//! Operating mode for deployments that trust a scoring oracle: the oracle
+//! signs `(wallet_commitment, total_score, epoch)` and the prover shows in
+//! circuit that the signature verifies under the oracle's public key and that
+//! the signed total clears the threshold. Per-category scores never enter the
+//! trace, so its size no longer grows with the categories behind the total.
+//!
+//! Signatures are hash-based so the circuit only needs the Poseidon2 gadget: a
+//! base-16 Winternitz one-time signature over the statement digest, with the
+//! one-time keys as leaves of a Poseidon2 Merkle tree whose root is the
+//! oracle's public key. Each leaf signs once, so a key of height `h` signs at
+//! most `2^h` statements. Digests are single field elements as elsewhere in
+//! the crate, which caps forgery resistance at about 2^31 hash evaluations.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::{hash_nodes, wallet_tag};
+use crate::poseidon2;
+use crate::{RepIDProof, Result, ZKPError, F};
+
+/// Operation type of threshold proofs over oracle-signed totals
+pub const ORACLE_THRESHOLD_OPERATION: &str = "oracle_threshold_verification";
+
+/// Bits signed per Winternitz chain
+pub const DIGIT_BITS: u32 = 4;
+/// Hashes from a chain's secret to its public end
+pub const CHAIN_LENGTH: u32 = (1 << DIGIT_BITS) - 1;
+/// Digits of the 31-bit statement digest
+pub const MESSAGE_DIGITS: usize = 8;
+/// Digits of the checksum over the message digits (at most 8 * 15)
+pub const CHECKSUM_DIGITS: usize = 2;
+/// Chains per one-time signature
+pub const CHAINS: usize = MESSAGE_DIGITS + CHECKSUM_DIGITS;
+/// Tallest key tree an oracle may generate
+pub const MAX_KEY_HEIGHT: u32 = 16;
+
+/// Statement an oracle signs for one wallet and epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OracleStatement {
+    pub wallet_commitment: F,
+    pub total_score: u32,
+    pub epoch: u64,
+}
+
+impl OracleStatement {
+    /// Position of the total within `to_field_elements`
+    pub const SCORE_POSITION: usize = 1;
+
+    pub fn new(wallet_commitment: F, total_score: u32, epoch: u64) -> Self {
+        Self { wallet_commitment, total_score, epoch }
+    }
+
+    /// Hash input of the signed digest: [wallet commitment, total score, epoch]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        vec![self.wallet_commitment, F::from_u32(self.total_score), F::new(self.epoch)]
+    }
+
+    /// Poseidon2 digest the oracle signs
+    pub fn digest(&self) -> F {
+        poseidon2::hash_elements(&self.to_field_elements())
+    }
+}
+
+/// Commitment to a wallet the oracle scores: Poseidon2(wallet_tag, blinding)
+///
+/// The blinding keeps the public input from being matched against a list of
+/// known wallets; the oracle and the wallet owner share it.
+pub fn wallet_commitment(wallet_address: &str, blinding: F) -> F {
+    poseidon2::hash_elements(&[wallet_tag(wallet_address), blinding])
+}
+
+/// Base-16 digits of `digest`, least significant first, followed by the checksum digits
+pub fn signed_digits(digest: F) -> [u32; CHAINS] {
+    let mut digits = [0u32; CHAINS];
+    let mask = (1u64 << DIGIT_BITS) - 1;
+    for (i, digit) in digits[..MESSAGE_DIGITS].iter_mut().enumerate() {
+        *digit = ((digest.0 >> (DIGIT_BITS as usize * i)) & mask) as u32;
+    }
+    let checksum: u32 = digits[..MESSAGE_DIGITS].iter().map(|d| CHAIN_LENGTH - d).sum();
+    for (i, digit) in digits[MESSAGE_DIGITS..].iter_mut().enumerate() {
+        *digit = (checksum >> (DIGIT_BITS as usize * i)) & mask as u32;
+    }
+    digits
+}
+
+/// One Winternitz chain step
+pub fn chain_step(value: F) -> F {
+    poseidon2::hash_elements(&[value])
+}
+
+fn chain(mut value: F, steps: u32) -> F {
+    for _ in 0..steps {
+        value = chain_step(value);
+    }
+    value
+}
+
+/// Key-tree leaf of a one-time key: Poseidon2 over its chain ends
+pub fn key_leaf(chain_ends: &[F]) -> F {
+    poseidon2::hash_elements(chain_ends)
+}
+
+/// Oracle public key: root of the one-time key tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OraclePublicKey {
+    pub root: F,
+    pub height: u32,
+}
+
+impl OraclePublicKey {
+    /// Chain ends a signature reaches, `CHAIN_LENGTH - digit` steps past each signed value
+    pub fn chain_ends(statement: &OracleStatement, signature: &OracleSignature) -> Option<Vec<F>> {
+        if signature.chains.len() != CHAINS {
+            return None;
+        }
+        let digits = signed_digits(statement.digest());
+        Some(signature.chains.iter().zip(digits).map(|(&value, digit)| chain(value, CHAIN_LENGTH - digit)).collect())
+    }
+
+    /// Check `signature` over `statement` outside the circuit
+    pub fn verify(&self, statement: &OracleStatement, signature: &OracleSignature) -> bool {
+        if signature.siblings.len() != self.height as usize || signature.leaf_index >> self.height != 0 {
+            return false;
+        }
+        let Some(ends) = Self::chain_ends(statement, signature) else {
+            return false;
+        };
+        let root = signature.siblings.iter().enumerate().fold(key_leaf(&ends), |node, (level, &sibling)| {
+            match (signature.leaf_index >> level) & 1 {
+                0 => hash_nodes(node, sibling),
+                _ => hash_nodes(sibling, node),
+            }
+        });
+        root == self.root
+    }
+}
+
+/// One-time signature with the authentication path of its key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OracleSignature {
+    pub leaf_index: u64,
+    /// Chain values, `digit` steps from each chain's secret
+    pub chains: Vec<F>,
+    /// Sibling hashes from the leaf level upwards
+    pub siblings: Vec<F>,
+}
+
+/// Oracle statement with its signature, held by the prover
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedScore {
+    pub statement: OracleStatement,
+    pub signature: OracleSignature,
+}
+
+/// Oracle signing key
+///
+/// Stateful: every signature consumes a one-time key. Signing two statements
+/// with the same leaf lets anyone forge signatures for that leaf, so the
+/// signing state must never be rolled back, e.g. by restoring a backup.
+pub struct OracleKey {
+    seed: [u8; 32],
+    /// Tree levels from the leaves upwards
+    levels: Vec<Vec<F>>,
+    next_leaf: u64,
+}
+
+impl std::fmt::Debug for OracleKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OracleKey")
+            .field("public_key", &self.public_key())
+            .field("next_leaf", &self.next_leaf)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OracleKey {
+    /// Derive a key of `2^height` one-time keys from caller-held key material
+    pub fn generate(seed: [u8; 32], height: u32) -> Result<Self> {
+        if height > MAX_KEY_HEIGHT {
+            return Err(ZKPError::ConfigError(format!(
+                "Oracle key height {} exceeds the maximum of {}",
+                height, MAX_KEY_HEIGHT
+            )));
+        }
+        let leaves: Vec<F> = (0..1u64 << height)
+            .map(|leaf| {
+                let ends: Vec<F> = (0..CHAINS).map(|i| chain(Self::chain_secret(&seed, leaf, i), CHAIN_LENGTH)).collect();
+                key_leaf(&ends)
+            })
+            .collect();
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1].chunks(2).map(|pair| hash_nodes(pair[0], pair[1])).collect();
+            levels.push(next);
+        }
+        Ok(Self { seed, levels, next_leaf: 0 })
+    }
+
+    fn chain_secret(seed: &[u8; 32], leaf: u64, chain: usize) -> F {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"RepID_OracleChain");
+        hasher.update(seed);
+        hasher.update(&leaf.to_le_bytes());
+        hasher.update(&(chain as u64).to_le_bytes());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hasher.finalize().as_bytes()[..8]);
+        F::from_bytes(bytes)
+    }
+
+    pub fn public_key(&self) -> OraclePublicKey {
+        OraclePublicKey {
+            root: self.levels[self.levels.len() - 1][0],
+            height: (self.levels.len() - 1) as u32,
+        }
+    }
+
+    /// One-time keys left to sign with
+    pub fn remaining(&self) -> u64 {
+        self.levels[0].len() as u64 - self.next_leaf
+    }
+
+    /// Sign `statement` with the next unused one-time key
+    pub fn sign(&mut self, statement: &OracleStatement) -> Result<SignedScore> {
+        if self.remaining() == 0 {
+            return Err(ZKPError::SigningError("Oracle key has no one-time keys left".to_string()));
+        }
+        let leaf = self.next_leaf;
+        self.next_leaf += 1;
+
+        let digits = signed_digits(statement.digest());
+        let chains = digits.iter()
+            .enumerate()
+            .map(|(i, &digit)| chain(Self::chain_secret(&self.seed, leaf, i), digit))
+            .collect();
+        let siblings = self.levels[..self.levels.len() - 1].iter()
+            .enumerate()
+            .map(|(level, nodes)| nodes[((leaf >> level) ^ 1) as usize])
+            .collect();
+        Ok(SignedScore {
+            statement: *statement,
+            signature: OracleSignature { leaf_index: leaf, chains, siblings },
+        })
+    }
+}
+
+/// Check an oracle threshold proof was made for this oracle, wallet and epoch
+pub fn check_oracle_statement(proof: &RepIDProof, oracle: &OraclePublicKey, wallet_commitment: F, epoch: u64) -> Result<()> {
+    if proof.metadata.operation_type != ORACLE_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no oracle statement",
+            proof.metadata.operation_type
+        )));
+    }
+    let inputs = proof.public_inputs.get(2..5)
+        .ok_or_else(|| ZKPError::MalformedProof("Oracle threshold proof needs 5 public inputs".to_string()))?;
+    if inputs[0] != oracle.root {
+        return Err(ZKPError::VerificationError("Proof was not signed by this oracle".to_string()));
+    }
+    if inputs[1] != wallet_commitment || inputs[2] != F::new(epoch) {
+        return Err(ZKPError::VerificationError(format!(
+            "Proof is not for this wallet commitment in epoch {}",
+            epoch
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_oracle_signed_total_backs_threshold_proof() {
+        let mut oracle = OracleKey::generate([3; 32], 2).unwrap();
+        let public_key = oracle.public_key();
+        let commitment = wallet_commitment("0xtest", F::new(42));
+        let signed = oracle.sign(&OracleStatement::new(commitment, 120, 7)).unwrap();
+        assert!(public_key.verify(&signed.statement, &signed.signature));
+        assert_eq!(oracle.remaining(), 3);
+
+        let request = ThresholdVerificationRequest {
+            threshold: 100,
+            categories: vec![RepIDCategory::Technical],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let result = zkp_system.prove_oracle_threshold_verification(&request, &signed, &public_key, "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+        assert!(check_oracle_statement(&result.proof, &public_key, commitment, 7).is_ok());
+        assert!(check_oracle_statement(&result.proof, &public_key, commitment, 8).is_err());
+        let other = OracleKey::generate([4; 32], 2).unwrap().public_key();
+        assert!(check_oracle_statement(&result.proof, &other, commitment, 7).is_err());
+
+        // A raised total no longer matches the signature, in or out of the circuit
+        let forged = SignedScore {
+            statement: OracleStatement { total_score: 900, ..signed.statement },
+            signature: signed.signature.clone(),
+        };
+        assert!(!public_key.verify(&forged.statement, &forged.signature));
+        assert!(zkp_system.prove_oracle_threshold_verification(&request, &forged, &public_key, "0xtest").is_err());
+    }
+}
@@ -0,0 +1,545 @@
+//! Verification Policies
+//!
+//! Relying-party rules applied around proof verification, choosing between
+//! lenient `Ok(false)` results and fail-closed typed errors
+
+use serde::{Deserialize, Serialize};
+
+use crate::custom_stark::{StarkParams, GRINDING_BITS};
+use crate::hidden::{self, CategorySetOpening};
+use crate::identity::{self, ProverKey};
+use crate::tee::TeeRequirement;
+use crate::{RepIDCategory, RepIDProof, Result, SecurityLevel, ThresholdVerificationRequest, ZKPError};
+
+/// Operation types the custom STARK verifier understands
+pub const KNOWN_OPERATIONS: &[&str] = &[
+    "threshold_verification",
+    "committed_threshold_verification",
+    "hidden_threshold_verification",
+    "chained_threshold_verification",
+    "fresh_threshold_verification",
+    "unrevoked_threshold_verification",
+    "cosigned_threshold_verification",
+    "linked_threshold_verification",
+    "designated_threshold_verification",
+    "escrowed_threshold_verification",
+    "oracle_threshold_verification",
+    "score_opening",
+    "rank_bucket",
+    "category_count",
+    "sustained_threshold",
+    "score_delta",
+    "rate_limited_signal",
+    "history_threshold",
+    "biometric_4fa",
+];
+
+/// Categories a verifier accepts scores from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryFilter {
+    /// Accepted categories (`None` accepts every category not denied)
+    pub allowed: Option<Vec<RepIDCategory>>,
+    /// Rejected categories, taking precedence over `allowed`
+    pub denied: Vec<RepIDCategory>,
+}
+
+impl CategoryFilter {
+    pub fn permits(&self, category: &RepIDCategory) -> bool {
+        !self.denied.contains(category) && self.allowed.as_ref().is_none_or(|allowed| allowed.contains(category))
+    }
+
+    /// Reject a category set containing any category the filter does not permit
+    pub fn check(&self, categories: &[RepIDCategory]) -> Result<()> {
+        match categories.iter().find(|category| !self.permits(category)) {
+            Some(category) => Err(ZKPError::PolicyViolation(format!("category {:?} is not allowed", category))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What a proof asserts, as far as security tiers are concerned
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Predicate {
+    pub operation: String,
+    /// Score threshold, for threshold operations
+    pub threshold: Option<u32>,
+    /// Categories the decision rests on
+    pub categories: Vec<RepIDCategory>,
+}
+
+impl Predicate {
+    /// Predicate of a proof about to be generated
+    pub fn of_request(operation: &str, request: Option<&ThresholdVerificationRequest>) -> Self {
+        Self {
+            operation: operation.to_string(),
+            threshold: request.map(|request| request.threshold),
+            categories: request.map(|request| request.categories.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// Predicate of a received proof
+    ///
+    /// Threshold operations carry their threshold as the first public input;
+    /// verification holds it to the request's, so it only stands in when
+    /// there is no request.
+    pub fn of_proof(proof: &RepIDProof, request: Option<&ThresholdVerificationRequest>) -> Self {
+        let mut predicate = Self::of_request(&proof.metadata.operation_type, request);
+        if request.is_none() && proof.metadata.operation_type.contains("threshold") {
+            if let Some(threshold) = proof.public_inputs.first() {
+                predicate.threshold = Some(u32::try_from(threshold.0).unwrap_or(u32::MAX));
+            }
+        }
+        predicate
+    }
+}
+
+/// Minimum parameters for high-value predicates
+///
+/// A tier applies when every condition it sets matches: the threshold is at
+/// least `min_threshold`, the predicate involves one of `categories`, and the
+/// operation is one of `operations`. Provers refuse to generate, and
+/// verifiers refuse to accept, a matching proof below the tier's parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityTier {
+    pub name: String,
+    pub min_security: SecurityLevel,
+    /// Proof-of-work bits required on top of the security level
+    #[serde(default)]
+    pub min_grinding_bits: u32,
+    /// Applies to thresholds at or above this value (`None`: any predicate)
+    #[serde(default)]
+    pub min_threshold: Option<u32>,
+    /// Applies when the predicate covers any of these categories (empty: any)
+    #[serde(default)]
+    pub categories: Vec<RepIDCategory>,
+    /// Applies to these operation types (`None`: every operation)
+    #[serde(default)]
+    pub operations: Option<Vec<String>>,
+}
+
+impl SecurityTier {
+    pub fn new(name: &str, min_security: SecurityLevel) -> Self {
+        Self {
+            name: name.to_string(),
+            min_security,
+            min_grinding_bits: 0,
+            min_threshold: None,
+            categories: Vec::new(),
+            operations: None,
+        }
+    }
+
+    /// Apply to thresholds of at least `threshold`
+    pub fn above_threshold(mut self, threshold: u32) -> Self {
+        self.min_threshold = Some(threshold);
+        self
+    }
+
+    /// Apply to predicates over any of `categories`, e.g. DeFi collateral gating
+    pub fn for_categories(mut self, categories: &[RepIDCategory]) -> Self {
+        self.categories = categories.to_vec();
+        self
+    }
+
+    /// Apply to these operation types only
+    pub fn for_operations(mut self, operations: &[&str]) -> Self {
+        self.operations = Some(operations.iter().map(|op| op.to_string()).collect());
+        self
+    }
+
+    pub fn with_min_grinding_bits(mut self, bits: u32) -> Self {
+        self.min_grinding_bits = bits;
+        self
+    }
+
+    pub fn applies_to(&self, predicate: &Predicate) -> bool {
+        self.min_threshold.is_none_or(|min| predicate.threshold.is_some_and(|threshold| threshold >= min))
+            && (self.categories.is_empty() || predicate.categories.iter().any(|c| self.categories.contains(c)))
+            && self.operations.as_ref().is_none_or(|operations| operations.contains(&predicate.operation))
+    }
+
+    /// Reject `params` below this tier
+    ///
+    /// Provers grind `GRINDING_BITS` against the transcript, so grinding
+    /// claimed beyond it does not count.
+    pub fn check(&self, params: &StarkParams) -> Result<()> {
+        let required = self.min_security.params();
+        let grinding = params.grinding_bits.min(GRINDING_BITS);
+        if !params.meets(&required) || grinding < self.min_grinding_bits {
+            return Err(ZKPError::ParameterDowngrade(format!(
+                "security tier '{}' requires {:?} parameters ({} queries, blowup {}) and {} grinding bits, \
+                 got {} queries, blowup {}, {} grinding bits",
+                self.name,
+                self.min_security,
+                required.num_queries,
+                required.blowup_factor,
+                self.min_grinding_bits,
+                params.num_queries,
+                params.blowup_factor,
+                grinding
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Check `params` against every tier applying to `predicate`
+pub fn check_security_tiers(tiers: &[SecurityTier], predicate: &Predicate, params: &StarkParams) -> Result<()> {
+    tiers.iter().filter(|tier| tier.applies_to(predicate)).try_for_each(|tier| tier.check(params))
+}
+
+/// How a verifier reacts to malformed, unexpected or downgraded proofs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyPolicy {
+    /// Return typed errors instead of `Ok(false)`
+    pub strict: bool,
+    /// Weakest parameter set the verifier will accept
+    pub min_security: SecurityLevel,
+    /// Advertised minimum parameters, overriding `min_security`'s
+    #[serde(default)]
+    pub min_params: Option<StarkParams>,
+    /// Accepted operation types (`None` accepts every known type)
+    pub allowed_operations: Option<Vec<String>>,
+    /// Provers whose signed proofs are accepted (`None` accepts unsigned proofs)
+    #[serde(default)]
+    pub trusted_provers: Option<Vec<ProverKey>>,
+    /// Enclave attestation the proving environment must present
+    #[serde(default)]
+    pub tee: Option<TeeRequirement>,
+    /// Categories the proof's committed category set may contain
+    #[serde(default)]
+    pub categories: Option<CategoryFilter>,
+    /// Escalated minimum parameters for high-value predicates
+    #[serde(default)]
+    pub security_tiers: Vec<SecurityTier>,
+}
+
+impl VerifyPolicy {
+    /// Fail-closed policy with the given minimum security
+    pub fn strict(min_security: SecurityLevel) -> Self {
+        Self {
+            strict: true,
+            min_security,
+            min_params: None,
+            allowed_operations: None,
+            trusted_provers: None,
+            tee: None,
+            categories: None,
+            security_tiers: Vec::new(),
+        }
+    }
+
+    /// Advertise explicit minimum parameters instead of a security level's
+    pub fn with_min_params(mut self, params: StarkParams) -> Self {
+        self.min_params = Some(params);
+        self
+    }
+
+    /// Weakest parameters the verifier accepts, as advertised to provers
+    pub fn min_params(&self) -> StarkParams {
+        self.min_params.unwrap_or_else(|| self.min_security.params())
+    }
+
+    /// Restrict the accepted operation types
+    pub fn with_allowed_operations(mut self, operations: &[&str]) -> Self {
+        self.allowed_operations = Some(operations.iter().map(|op| op.to_string()).collect());
+        self
+    }
+
+    /// Require a valid signature from one of `provers`
+    pub fn with_trusted_provers(mut self, provers: &[ProverKey]) -> Self {
+        self.trusted_provers = Some(provers.to_vec());
+        self
+    }
+
+    /// Require proofs generated inside an attested enclave
+    pub fn with_tee_requirement(mut self, requirement: TeeRequirement) -> Self {
+        self.tee = Some(requirement);
+        self
+    }
+
+    /// Accept only proofs whose committed categories are all in `categories`
+    pub fn with_allowed_categories(mut self, categories: &[RepIDCategory]) -> Self {
+        self.categories.get_or_insert_with(CategoryFilter::default).allowed = Some(categories.to_vec());
+        self
+    }
+
+    /// Reject proofs whose committed category set contains any of `categories`
+    pub fn with_denied_categories(mut self, categories: &[RepIDCategory]) -> Self {
+        self.categories.get_or_insert_with(CategoryFilter::default).denied.extend_from_slice(categories);
+        self
+    }
+
+    /// Require at least `tier`'s parameters for the predicates it covers
+    pub fn with_security_tier(mut self, tier: SecurityTier) -> Self {
+        self.security_tiers.push(tier);
+        self
+    }
+
+    /// Check the parameters a proof was proved under against the tiers its predicate falls in
+    pub fn check_security_tiers(
+        &self,
+        proof: &RepIDProof,
+        request: Option<&ThresholdVerificationRequest>,
+        proof_params: &StarkParams,
+    ) -> Result<()> {
+        check_security_tiers(&self.security_tiers, &Predicate::of_proof(proof, request), proof_params)
+    }
+
+    /// Check the category filter against the category set the proof commits to
+    ///
+    /// Only hidden-category proofs bind their category set, so a filter rejects
+    /// every other operation type rather than trusting the listed categories.
+    /// `disclosed` is the prover's opening of the commitment, shown to this
+    /// verifier only.
+    pub fn check_categories(&self, proof: &RepIDProof, disclosed: Option<&CategorySetOpening>) -> Result<()> {
+        let Some(filter) = &self.categories else {
+            return Ok(());
+        };
+        let commitment = hidden::proof_category_commitment(proof)?;
+        let opening = disclosed
+            .ok_or_else(|| ZKPError::PolicyViolation("category policy needs the proof's category set disclosed".to_string()))?;
+        if opening.commit() != commitment {
+            return Err(ZKPError::PolicyViolation("disclosed category set does not open the proof's commitment".to_string()));
+        }
+        filter.check(&opening.categories)
+    }
+
+    /// Check the envelope's operation type, prover and the parameters it was proved under
+    pub fn check_envelope(&self, proof: &RepIDProof, proof_params: &StarkParams) -> Result<()> {
+        let operation = proof.metadata.operation_type.as_str();
+        if !KNOWN_OPERATIONS.contains(&operation) {
+            return Err(ZKPError::UnknownOperation(operation.to_string()));
+        }
+
+        if let Some(allowed) = &self.allowed_operations {
+            if !allowed.iter().any(|op| op == operation) {
+                return Err(ZKPError::PolicyViolation(format!("operation '{}' is not allowed", operation)));
+            }
+        }
+
+        if let Some(trusted) = &self.trusted_provers {
+            match identity::verify_prover_signature(proof)? {
+                Some(key) if trusted.contains(&key) => {}
+                Some(key) => {
+                    return Err(ZKPError::PolicyViolation(format!("prover {} is not trusted", hex::encode(key))));
+                }
+                None => return Err(ZKPError::PolicyViolation("proof is not signed by a prover".to_string())),
+            }
+        }
+
+        let required = self.min_params();
+        if !proof_params.meets(&required) {
+            return Err(ZKPError::ParameterDowngrade(format!(
+                "parameters ({} queries, blowup {}) are below the minimum ({} queries, blowup {})",
+                proof_params.num_queries,
+                proof_params.blowup_factor,
+                required.num_queries,
+                required.blowup_factor
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for VerifyPolicy {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            min_security: SecurityLevel::Fast,
+            min_params: None,
+            allowed_operations: None,
+            trusted_provers: None,
+            tee: None,
+            categories: None,
+            security_tiers: Vec::new(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDZKPSystem, ThresholdVerificationRequest, F};
+
+    fn threshold_proof(zkp_system: &mut RepIDZKPSystem) -> RepIDProof {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        zkp_system
+            .prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest")
+            .unwrap()
+            .proof
+    }
+
+    #[test]
+    fn test_strict_policy_returns_typed_errors() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let proof = threshold_proof(&mut zkp_system);
+
+        let strict = VerifyPolicy::strict(SecurityLevel::Fast);
+        assert!(zkp_system.verify_proof_with_policy(&proof, None, &strict).unwrap());
+
+        let mut unknown = proof.clone();
+        unknown.metadata.operation_type = "made_up".to_string();
+        assert!(matches!(
+            zkp_system.verify_proof_with_policy(&unknown, None, &strict),
+            Err(ZKPError::UnknownOperation(_))
+        ));
+        // The lenient default no longer accepts unknown operation types
+        assert!(!zkp_system.verify_proof(&unknown, None).unwrap());
+
+        let downgrade = VerifyPolicy::strict(SecurityLevel::High);
+        assert!(matches!(
+            zkp_system.verify_proof_with_policy(&proof, None, &downgrade),
+            Err(ZKPError::ParameterDowngrade(_))
+        ));
+
+        let biometric_only = strict.with_allowed_operations(&["biometric_4fa"]);
+        assert!(matches!(
+            zkp_system.verify_proof_with_policy(&proof, None, &biometric_only),
+            Err(ZKPError::PolicyViolation(_))
+        ));
+    }
+
+    #[test]
+    fn test_negotiated_parameters_are_recorded_and_enforced() {
+        // Both sides configured at Standard; the verifier only asks for Fast
+        let advertised = VerifyPolicy::strict(SecurityLevel::Fast);
+        let mut prover = RepIDZKPSystem::new(SecurityLevel::Standard);
+        let negotiated = prover.negotiate_params(&advertised).unwrap();
+        assert_eq!((negotiated.num_queries, negotiated.blowup_factor), (40, 4));
+
+        let proof = threshold_proof(&mut prover);
+        assert_eq!(proof.metadata.stark_params, Some(negotiated));
+        let verifier = RepIDZKPSystem::new(SecurityLevel::Fast);
+        assert!(verifier.verify_proof_with_policy(&proof, None, &advertised).unwrap());
+
+        // A verifier configured above the recorded parameters refuses them
+        let high = RepIDZKPSystem::new(SecurityLevel::High);
+        assert!(matches!(
+            high.verify_proof_with_policy(&proof, None, &advertised),
+            Err(ZKPError::ParameterDowngrade(_))
+        ));
+        assert!(matches!(
+            verifier.verify_proof_with_policy(&proof, None, &VerifyPolicy::strict(SecurityLevel::Standard)),
+            Err(ZKPError::ParameterDowngrade(_))
+        ));
+
+        // Recorded parameters are bound into the transcript
+        let mut relabeled = proof.clone();
+        relabeled.metadata.stark_params = Some(StarkParams::new(40, 8));
+        assert!(verifier.verify_proof_with_policy(&relabeled, None, &advertised).is_err());
+        let mut ground = negotiated;
+        ground.grinding_bits += 8;
+        relabeled.metadata.stark_params = Some(ground);
+        assert!(!verifier.verify_proof_with_policy(&relabeled, None, &advertised).unwrap_or(false));
+
+        // Explicit minimums above every security level are honored as advertised
+        let demanding = VerifyPolicy::strict(SecurityLevel::Fast).with_min_params(StarkParams::new(160, 16));
+        assert_eq!(prover.negotiate_params(&demanding).unwrap().num_queries, 160);
+        let proof = threshold_proof(&mut prover);
+        assert!(verifier.verify_proof_with_policy(&proof, None, &demanding).unwrap());
+    }
+
+    #[test]
+    fn test_security_tiers_escalate_high_value_predicates() {
+        let high_value = SecurityTier::new("high-value", SecurityLevel::Standard).above_threshold(500);
+        let collateral = SecurityTier::new("defi-collateral", SecurityLevel::High)
+            .for_categories(&[RepIDCategory::DeFi])
+            .with_min_grinding_bits(GRINDING_BITS + 4);
+        let policy = VerifyPolicy::strict(SecurityLevel::Fast)
+            .with_security_tier(high_value.clone())
+            .with_security_tier(collateral.clone());
+        let request = |threshold, category| ThresholdVerificationRequest {
+            threshold,
+            categories: vec![category],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+
+        // Low stakes still accept Fast proofs
+        let mut fast = RepIDZKPSystem::new(SecurityLevel::Fast).with_security_tiers(&policy.security_tiers);
+        let low = request(50, RepIDCategory::Community);
+        let proof = fast.prove_threshold_verification(&low, &[(RepIDCategory::Community, 75)], "0xtest").unwrap().proof;
+        assert!(fast.verify_proof_with_policy(&proof, Some(&low), &policy).unwrap());
+
+        // The prover refuses a Fast proof for a high threshold, and the verifier refuses one made without tiers
+        let high = request(600, RepIDCategory::Community);
+        let scores = [(RepIDCategory::Community, 700)];
+        assert!(matches!(
+            fast.prove_threshold_verification(&high, &scores, "0xtest"),
+            Err(ZKPError::ParameterDowngrade(_))
+        ));
+        let mut untiered = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let downgraded = untiered.prove_threshold_verification(&high, &scores, "0xtest").unwrap().proof;
+        assert!(matches!(
+            untiered.verify_proof_with_policy(&downgraded, Some(&high), &policy),
+            Err(ZKPError::ParameterDowngrade(_))
+        ));
+        // Understating the threshold in the request does not escape the tier
+        assert!(untiered.verify_proof_with_policy(&downgraded, Some(&low), &policy).is_err());
+        assert!(untiered.verify_proof_with_policy(&downgraded, None, &policy).is_err());
+
+        let mut standard = RepIDZKPSystem::new(SecurityLevel::Standard).with_security_tiers(&policy.security_tiers);
+        let proof = standard.prove_threshold_verification(&high, &scores, "0xtest").unwrap().proof;
+        assert!(standard.verify_proof_with_policy(&proof, Some(&high), &policy).unwrap());
+
+        // Grinding beyond what provers perform cannot be claimed
+        let defi = Predicate::of_request("threshold_verification", Some(&request(10, RepIDCategory::DeFi)));
+        let mut claimed = SecurityLevel::High.params();
+        claimed.grinding_bits = 64;
+        assert!(collateral.applies_to(&defi) && !high_value.applies_to(&defi));
+        assert!(matches!(
+            check_security_tiers(&policy.security_tiers, &defi, &claimed),
+            Err(ZKPError::ParameterDowngrade(_))
+        ));
+        let relaxed = [collateral.with_min_grinding_bits(GRINDING_BITS)];
+        assert!(check_security_tiers(&relaxed, &defi, &claimed).is_ok());
+        assert!(check_security_tiers(&relaxed, &defi, &SecurityLevel::Standard.params()).is_err());
+    }
+
+    #[test]
+    fn test_category_filter_checks_committed_set() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let category_set = CategorySetOpening::new(vec![RepIDCategory::DeFi, RepIDCategory::Governance], F::new(4242));
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: Vec::new(),
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let scores = [(RepIDCategory::DeFi, 40), (RepIDCategory::Governance, 30), (RepIDCategory::FaithTech, 90)];
+        let proof = zkp_system.prove_hidden_threshold_verification(&request, &scores, &category_set, "0xtest").unwrap().proof;
+
+        let no_faith_tech = VerifyPolicy::strict(SecurityLevel::Fast).with_denied_categories(&[RepIDCategory::FaithTech]);
+        assert!(zkp_system.verify_proof_with_category_set(&proof, None, &category_set, &no_faith_tech).unwrap());
+        let no_defi = VerifyPolicy::strict(SecurityLevel::Fast).with_denied_categories(&[RepIDCategory::DeFi]);
+        assert!(matches!(
+            zkp_system.verify_proof_with_category_set(&proof, None, &category_set, &no_defi),
+            Err(ZKPError::PolicyViolation(_))
+        ));
+        let governance_only = VerifyPolicy::strict(SecurityLevel::Fast).with_allowed_categories(&[RepIDCategory::Governance]);
+        assert!(zkp_system.verify_proof_with_category_set(&proof, None, &category_set, &governance_only).is_err());
+
+        // The disclosure must open the proof's commitment, and is required at all
+        let understated = CategorySetOpening::new(vec![RepIDCategory::Governance], F::new(4242));
+        assert!(zkp_system.verify_proof_with_category_set(&proof, None, &understated, &no_defi).is_err());
+        assert!(zkp_system.verify_proof_with_policy(&proof, None, &no_faith_tech).is_err());
+
+        // Proofs that list rather than commit their categories are not trusted
+        let listed = threshold_proof(&mut zkp_system);
+        assert!(matches!(
+            zkp_system.verify_proof_with_policy(&listed, None, &no_faith_tech),
+            Err(ZKPError::PolicyViolation(_))
+        ));
+        assert!(zkp_system.verify_proof_with_policy(&listed, None, &VerifyPolicy::strict(SecurityLevel::Fast)).unwrap());
+    }
+}
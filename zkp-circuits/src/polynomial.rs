@@ -0,0 +1,275 @@
+//! Polynomials over BabyBear
+//!
+//! Coefficient and subgroup-evaluation forms with NTT conversion, Lagrange
+//! interpolation and exact division by vanishing polynomials
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::domain::{DomainCache, EvaluationDomain, TwoAdicSubgroup};
+use crate::{Result, ZKPError, F};
+
+/// Polynomial in coefficient form, lowest degree first, without trailing zeros
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Polynomial {
+    coeffs: Vec<F>,
+}
+
+impl Polynomial {
+    pub fn new(coeffs: Vec<F>) -> Self {
+        let mut poly = Self { coeffs };
+        poly.trim();
+        poly
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn constant(value: F) -> Self {
+        Self::new(vec![value])
+    }
+
+    pub fn coefficients(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    /// Degree, or `None` for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// Value at `x` by Horner's rule
+    pub fn evaluate(&self, x: F) -> F {
+        self.coeffs.iter().rev().fold(F::ZERO, |acc, &c| acc * x + c)
+    }
+
+    pub fn scale(&self, factor: F) -> Self {
+        Self::new(self.coeffs.iter().map(|&c| c * factor).collect())
+    }
+
+    /// Lagrange interpolation through arbitrary points with distinct x
+    pub fn interpolate(points: &[(F, F)]) -> Result<Self> {
+        let xs: Vec<F> = points.iter().map(|&(x, _)| x).collect();
+        let mut result = Self::zero();
+        for (i, &(xi, yi)) in points.iter().enumerate() {
+            let mut basis = Self::constant(F::ONE);
+            let mut denominator = F::ONE;
+            for (j, &xj) in xs.iter().enumerate() {
+                if i != j {
+                    basis = &basis * &Self::new(vec![-xj, F::ONE]);
+                    denominator *= xi - xj;
+                }
+            }
+            let inverse = denominator
+                .inverse()
+                .ok_or_else(|| ZKPError::InvalidInput("Interpolation points must have distinct x".to_string()))?;
+            result = &result + &basis.scale(yi * inverse);
+        }
+        Ok(result)
+    }
+
+    /// Evaluate on every element of `subgroup` (or coset) with an NTT
+    pub fn evaluate_over(&self, subgroup: &TwoAdicSubgroup) -> Result<Evaluations> {
+        let size = subgroup.size();
+        if self.coeffs.len() > size {
+            return Err(ZKPError::InvalidInput(format!(
+                "Polynomial of degree {} does not fit a domain of {} points",
+                self.coeffs.len() - 1,
+                size
+            )));
+        }
+        let mut values = self.coeffs.clone();
+        values.resize(size, F::ZERO);
+        let mut power = F::ONE;
+        for value in values.iter_mut() {
+            *value *= power;
+            power *= subgroup.shift();
+        }
+        ntt(&mut values, &*DomainCache::global().get(size)?);
+        Ok(Evaluations { subgroup: *subgroup, values })
+    }
+
+    /// Exact quotient by `x^n - shift^n`, failing when the polynomial does not vanish on the subgroup
+    pub fn divide_by_vanishing(&self, subgroup: &TwoAdicSubgroup) -> Result<Self> {
+        let n = subgroup.size();
+        let constant = subgroup.shift().exp_power_of_2(subgroup.log_size() as usize);
+        let mut remainder = self.coeffs.clone();
+        let mut quotient = vec![F::ZERO; remainder.len().saturating_sub(n)];
+        for i in (n..remainder.len()).rev() {
+            let lead = remainder[i];
+            quotient[i - n] = lead;
+            remainder[i] = F::ZERO;
+            remainder[i - n] += lead * constant;
+        }
+        if remainder.iter().any(|&c| c != F::ZERO) {
+            return Err(ZKPError::CircuitError("Polynomial does not vanish on the subgroup".to_string()));
+        }
+        Ok(Self::new(quotient))
+    }
+
+    fn trim(&mut self) {
+        while self.coeffs.last() == Some(&F::ZERO) {
+            self.coeffs.pop();
+        }
+    }
+}
+
+impl Add for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, rhs: Self) -> Polynomial {
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        let coeff = |p: &Polynomial, i: usize| p.coeffs.get(i).copied().unwrap_or(F::ZERO);
+        Polynomial::new((0..len).map(|i| coeff(self, i) + coeff(rhs, i)).collect())
+    }
+}
+
+impl Sub for &Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, rhs: Self) -> Polynomial {
+        self + &(-rhs)
+    }
+}
+
+impl Neg for &Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Polynomial {
+        Polynomial::new(self.coeffs.iter().map(|&c| -c).collect())
+    }
+}
+
+/// Schoolbook product
+impl Mul for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: Self) -> Polynomial {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::zero();
+        }
+        let mut coeffs = vec![F::ZERO; self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] += a * b;
+            }
+        }
+        Polynomial::new(coeffs)
+    }
+}
+
+/// Values of a polynomial on every point of a subgroup or coset, in natural order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluations {
+    subgroup: TwoAdicSubgroup,
+    values: Vec<F>,
+}
+
+impl Evaluations {
+    pub fn new(subgroup: TwoAdicSubgroup, values: Vec<F>) -> Result<Self> {
+        if values.len() != subgroup.size() {
+            return Err(ZKPError::InvalidInput(format!(
+                "{} evaluations for a domain of {} points",
+                values.len(),
+                subgroup.size()
+            )));
+        }
+        Ok(Self { subgroup, values })
+    }
+
+    pub fn subgroup(&self) -> &TwoAdicSubgroup {
+        &self.subgroup
+    }
+
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<F> {
+        self.values
+    }
+
+    /// Unique polynomial of degree below the domain size through these values
+    pub fn interpolate(&self) -> Result<Polynomial> {
+        let size = self.subgroup.size();
+        let mut coeffs = self.values.clone();
+        ntt(&mut coeffs, &*DomainCache::global().get(size)?);
+        // Inverse NTT: forward transform, reverse all but the first entry, divide by n
+        coeffs[1..].reverse();
+        let size_inverse = F::new(size as u64).inverse().expect("domain size is invertible");
+        let shift_inverse = self
+            .subgroup
+            .shift()
+            .inverse()
+            .ok_or_else(|| ZKPError::InvalidInput("Coset shift must be non-zero".to_string()))?;
+        let mut factor = size_inverse;
+        for coeff in coeffs.iter_mut() {
+            *coeff *= factor;
+            factor *= shift_inverse;
+        }
+        Ok(Polynomial::new(coeffs))
+    }
+}
+
+/// In-place radix-2 NTT evaluating coefficients on the domain, natural order in and out
+fn ntt(values: &mut [F], domain: &EvaluationDomain) {
+    let n = values.len();
+    domain.bit_reverse_permute(values);
+    let roots = domain.roots();
+    let mut len = 2;
+    while len <= n {
+        let step = n / len;
+        for start in (0..n).step_by(len) {
+            for j in 0..len / 2 {
+                let u = values[start + j];
+                let v = values[start + j + len / 2] * roots[j * step];
+                values[start + j] = u + v;
+                values[start + j + len / 2] = u - v;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MULTIPLICATIVE_GENERATOR;
+
+    fn poly(coeffs: &[u64]) -> Polynomial {
+        Polynomial::new(coeffs.iter().map(|&c| F::new(c)).collect())
+    }
+
+    #[test]
+    fn test_ntt_round_trip_and_interpolation() {
+        let p = poly(&[3, 1, 4, 1, 5]);
+        for shift in [F::ONE, MULTIPLICATIVE_GENERATOR] {
+            let coset = TwoAdicSubgroup::new(3).unwrap().coset(shift);
+            let evaluations = p.evaluate_over(&coset).unwrap();
+            for (i, &value) in evaluations.values().iter().enumerate() {
+                assert_eq!(value, p.evaluate(coset.element(i)));
+            }
+            assert_eq!(evaluations.interpolate().unwrap(), p);
+        }
+        assert!(p.evaluate_over(&TwoAdicSubgroup::new(2).unwrap()).is_err());
+
+        let points: Vec<(F, F)> = (1..=5).map(|x| (F::new(x), p.evaluate(F::new(x)))).collect();
+        assert_eq!(Polynomial::interpolate(&points).unwrap(), p);
+        assert!(Polynomial::interpolate(&[(F::ONE, F::ONE), (F::ONE, F::ZERO)]).is_err());
+    }
+
+    #[test]
+    fn test_divide_by_vanishing() {
+        let subgroup = TwoAdicSubgroup::new(2).unwrap().coset(MULTIPLICATIVE_GENERATOR);
+        let vanishing = &poly(&[0, 0, 0, 0, 1]) - &Polynomial::constant(MULTIPLICATIVE_GENERATOR.pow(4));
+        let quotient = poly(&[7, 0, 2]);
+        let product = &vanishing * &quotient;
+        assert_eq!(product.degree(), Some(6));
+        assert_eq!(product.divide_by_vanishing(&subgroup).unwrap(), quotient);
+        assert!((&product + &Polynomial::constant(F::ONE)).divide_by_vanishing(&subgroup).is_err());
+    }
+}
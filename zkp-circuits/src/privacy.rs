@@ -0,0 +1,136 @@
+//! Differential Privacy
+//!
+//! Epsilon budget and discrete Laplace noise for score components released to
+//! analytics; proving always uses the exact values
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::entropy::{self, OsEntropy, RngProvider};
+use crate::{Result, ZKPError};
+
+/// Noised score components for analytics export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoisyScoreComponents {
+    pub base_score: u32,
+    pub synergy_bonus: u32,
+    pub multiplicative_bonus: u32,
+    pub final_score: u32,
+    /// Epsilon spent on this release
+    pub epsilon: f64,
+}
+
+/// Epsilon budget spent by noised releases
+#[derive(Debug, Clone)]
+pub struct PrivacyBudget {
+    epsilon_per_release: f64,
+    remaining: f64,
+    /// Largest change one user's activity can make to a component
+    sensitivity: u32,
+    rng: ChaCha20Rng,
+}
+
+impl PrivacyBudget {
+    /// Budget of `total_epsilon`, charging `epsilon_per_release` per noised result
+    pub fn new(total_epsilon: f64, epsilon_per_release: f64) -> Result<Self> {
+        if !(epsilon_per_release > 0.0 && total_epsilon >= epsilon_per_release) {
+            return Err(ZKPError::InvalidInput(format!(
+                "privacy budget {} cannot fund releases of {}",
+                total_epsilon, epsilon_per_release
+            )));
+        }
+        Ok(Self {
+            epsilon_per_release,
+            remaining: total_epsilon,
+            sensitivity: 100,
+            rng: ChaCha20Rng::from_seed(entropy::seed(&OsEntropy)?),
+        })
+    }
+
+    pub fn with_sensitivity(mut self, sensitivity: u32) -> Self {
+        self.sensitivity = sensitivity.max(1);
+        self
+    }
+
+    /// Reseed the noise generator from `provider`
+    pub fn with_rng_provider(mut self, provider: &dyn RngProvider) -> Result<Self> {
+        self.rng = ChaCha20Rng::from_seed(entropy::seed(provider)?);
+        Ok(self)
+    }
+
+    /// Deterministic noise, for reproducible exports and tests
+    pub fn with_seed(mut self, seed: [u8; 32]) -> Self {
+        self.rng = ChaCha20Rng::from_seed(seed);
+        self
+    }
+
+    pub fn remaining(&self) -> f64 {
+        self.remaining
+    }
+
+    pub fn epsilon_per_release(&self) -> f64 {
+        self.epsilon_per_release
+    }
+
+    /// Noise `values` under one release, or `None` once the budget is spent
+    ///
+    /// The release epsilon is split evenly across the values (sequential composition).
+    pub fn release(&mut self, values: &[u32]) -> Option<Vec<u32>> {
+        if values.is_empty() || self.remaining + 1e-9 < self.epsilon_per_release {
+            return None;
+        }
+        self.remaining -= self.epsilon_per_release;
+
+        let epsilon = self.epsilon_per_release / values.len() as f64;
+        let alpha = (-epsilon / self.sensitivity as f64).exp();
+        Some(
+            values
+                .iter()
+                .map(|&value| {
+                    let noised = value as i64 + self.discrete_laplace(alpha);
+                    noised.clamp(0, u32::MAX as i64) as u32
+                })
+                .collect(),
+        )
+    }
+
+    /// Two-sided geometric sample with P(k) proportional to alpha^|k|
+    fn discrete_laplace(&mut self, alpha: f64) -> i64 {
+        self.geometric(alpha) - self.geometric(alpha)
+    }
+
+    fn geometric(&mut self, alpha: f64) -> i64 {
+        let u: f64 = self.rng.gen_range(f64::MIN_POSITIVE..1.0);
+        (u.ln() / alpha.ln()).floor() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hierarchical_scoring::HierarchicalScorer;
+    use crate::RepIDCategory;
+
+    #[test]
+    fn test_noise_only_touches_analytics_view() {
+        let scorer = HierarchicalScorer::new();
+        let scores = [(RepIDCategory::Governance, 75), (RepIDCategory::Technical, 85)];
+        let exact = scorer.calculate_score(&scores, 1_000, 900, None);
+        assert!(exact.analytics.is_none());
+
+        let mut budget = PrivacyBudget::new(1.0, 0.5).unwrap().with_seed([7u8; 32]);
+        let first = scorer.calculate_score(&scores, 1_000, 900, Some(&mut budget));
+        let second = scorer.calculate_score(&scores, 1_000, 900, Some(&mut budget));
+        let third = scorer.calculate_score(&scores, 1_000, 900, Some(&mut budget));
+
+        // Witness values are exact; noise is fresh per release until the budget runs out
+        assert_eq!(first.final_score, exact.final_score);
+        assert_eq!(scorer.to_field_elements(&first), scorer.to_field_elements(&exact));
+        let (first, second) = (first.analytics.unwrap(), second.analytics.unwrap());
+        assert_eq!(first.epsilon, 0.5);
+        assert_ne!(first, second);
+        assert!(third.analytics.is_none());
+        assert!(budget.remaining() < 1e-9);
+    }
+}
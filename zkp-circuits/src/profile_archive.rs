@@ -0,0 +1,133 @@
+//! Scoring Profile Archive
+//!
+//! Every scoring profile proofs were stamped with, by hash, so verifiers can
+//! check old proofs under the rules they were produced with after weights,
+//! synergies, caps or scales change. Archives opened on a `Storage` write
+//! profiles through and restore them on open.
+
+use std::collections::btree_map::{BTreeMap, Entry};
+use std::sync::{Arc, Mutex};
+
+use crate::hierarchical_scoring::ScoringProfile;
+use crate::storage::Storage;
+use crate::{Result, ZKPError};
+
+/// Storage namespace of archived profiles: canonical JSON keyed by its hash
+const PROFILES_NAMESPACE: &str = "profiles/archive";
+
+/// Scoring profiles by hash, append-only
+#[derive(Debug, Default)]
+pub struct ProfileArchive {
+    profiles: Mutex<BTreeMap<[u8; 32], ScoringProfile>>,
+    storage: Option<Arc<dyn Storage>>,
+}
+
+impl ProfileArchive {
+    /// Archive held in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Archive persisted in `storage`, restoring the profiles held there
+    ///
+    /// Each restored profile must still hash to the key it was stored under.
+    pub fn open(storage: Arc<dyn Storage>) -> Result<Self> {
+        let mut profiles = BTreeMap::new();
+        for (key, value) in storage.iterate(PROFILES_NAMESPACE)? {
+            let profile: ScoringProfile = serde_json::from_slice(&value)
+                .map_err(|e| ZKPError::SerializationError(e.to_string()))?;
+            if profile.hash().as_slice() != key.as_slice() {
+                return Err(ZKPError::StorageError(format!(
+                    "Archived profile {} does not match its hash",
+                    hex::encode(&key)
+                )));
+            }
+            profiles.insert(profile.hash(), profile);
+        }
+        Ok(Self { profiles: Mutex::new(profiles), storage: Some(storage) })
+    }
+
+    /// Archive `profile`, returning the hex hash proofs record for it
+    ///
+    /// Profiles of versions this build cannot score under are rejected.
+    pub fn archive(&self, profile: &ScoringProfile) -> Result<String> {
+        profile.check_version()?;
+        let hash = profile.hash();
+        let mut profiles = self.profiles.lock().unwrap_or_else(|e| e.into_inner());
+        if let Entry::Vacant(slot) = profiles.entry(hash) {
+            if let Some(storage) = &self.storage {
+                let canonical = serde_json::to_vec(profile).map_err(|e| ZKPError::SerializationError(e.to_string()))?;
+                storage.put(PROFILES_NAMESPACE, &hash, &canonical)?;
+            }
+            slot.insert(profile.clone());
+        }
+        Ok(hex::encode(hash))
+    }
+
+    pub fn get(&self, hash_hex: &str) -> Option<ScoringProfile> {
+        let hash: [u8; 32] = hex::decode(hash_hex).ok()?.try_into().ok()?;
+        self.profiles.lock().unwrap_or_else(|e| e.into_inner()).get(&hash).cloned()
+    }
+
+    /// Archived profile with hash `hash_hex`, as recorded in `VerificationMetadata`
+    pub fn resolve(&self, hash_hex: &str) -> Result<ScoringProfile> {
+        self.get(hash_hex)
+            .ok_or_else(|| ZKPError::ConfigError(format!("Scoring profile {} is not archived", hash_hex)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.profiles.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::hierarchical_scoring::HierarchicalScorer;
+    use crate::policy::VerifyPolicy;
+    use crate::saturation::{CategoryCap, SaturationCurve};
+    use crate::storage::MemoryStorage;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_old_proofs_verify_under_archived_profile() {
+        let profile_with_cap = |max_contribution| ScoringProfile {
+            category_caps: vec![CategoryCap::new(RepIDCategory::DeFi, max_contribution, SaturationCurve::HardCap)],
+            ..HierarchicalScorer::new().profile()
+        };
+        let (original, revised) = (profile_with_cap(60), profile_with_cap(40));
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let archive = ProfileArchive::open(storage.clone()).unwrap();
+        let original_hash = archive.archive(&original).unwrap();
+        let revised_hash = archive.archive(&revised).unwrap();
+
+        let mut prover = RepIDZKPSystem::new(SecurityLevel::Fast)
+            .with_category_caps(original.category_caps.clone())
+            .with_scoring_profile(&original);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::DeFi],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = prover.prove_threshold_verification(&request, &[(RepIDCategory::DeFi, 80)], "0xtest").unwrap();
+        assert_eq!(result.metadata.scoring_profile.as_deref(), Some(original_hash.as_str()));
+
+        // The verifier has since moved to the revised caps
+        let verifier = RepIDZKPSystem::new(SecurityLevel::Fast).with_category_caps(revised.category_caps.clone());
+        let policy = VerifyPolicy::default();
+        assert!(!verifier.verify_proof(&result.proof, None).unwrap());
+        assert!(verifier.verify_proof_under_profile(&result.proof, None, &original_hash, &archive, &policy).unwrap());
+        assert!(!verifier.verify_proof_under_profile(&result.proof, None, &revised_hash, &archive, &policy).unwrap());
+        assert!(verifier.verify_proof_under_profile(&result.proof, None, &"00".repeat(32), &archive, &policy).is_err());
+
+        let reopened = ProfileArchive::open(storage).unwrap();
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.resolve(&original_hash).unwrap().hash(), original.hash());
+    }
+}
@@ -0,0 +1,145 @@
+//! Proving Progress
+//!
+//! Stage and percentage callbacks from the prover, so UIs can show a real
+//! progress bar for multi-second proofs. Percentages cover the whole proof:
+//! each stage owns a fixed share sized by its typical cost, and reports are
+//! strictly increasing within a proof even when a stage runs in parallel.
+
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+#[cfg(feature = "prover")]
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// Pipeline stage a progress report comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProvingStage {
+    /// Witness sections and the public-input binding
+    TraceBuild,
+    /// Low-degree extension of every trace column
+    Lde,
+    /// Trace and LDE commitments
+    Commitment,
+    /// FRI folding rounds and proof of work
+    Fri,
+    /// Fiat–Shamir query derivation and openings
+    Queries,
+}
+
+impl ProvingStage {
+    /// Overall percentage at the start and end of the stage
+    pub fn span(&self) -> (u8, u8) {
+        match self {
+            ProvingStage::TraceBuild => (0, 10),
+            ProvingStage::Lde => (10, 45),
+            ProvingStage::Commitment => (45, 55),
+            ProvingStage::Fri => (55, 90),
+            ProvingStage::Queries => (90, 100),
+        }
+    }
+}
+
+/// One progress report, as delivered over a channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    pub stage: ProvingStage,
+    /// Overall completion of the proof, 0 to 100
+    pub percent: u8,
+}
+
+/// Receiver of progress reports
+///
+/// Called on the proving thread or a rayon worker; implementations should
+/// hand the report off rather than block.
+pub trait ProgressObserver: Send + Sync {
+    fn on_progress(&self, stage: ProvingStage, percent: u8);
+}
+
+impl<C: Fn(ProvingStage, u8) + Send + Sync> ProgressObserver for C {
+    fn on_progress(&self, stage: ProvingStage, percent: u8) {
+        self(stage, percent)
+    }
+}
+
+/// Stream reports over a channel; a dropped receiver just stops the stream
+impl ProgressObserver for Sender<ProgressEvent> {
+    fn on_progress(&self, stage: ProvingStage, percent: u8) {
+        let _ = self.send(ProgressEvent { stage, percent });
+    }
+}
+
+/// Observer streaming into a new channel, with the receiving end
+pub fn channel() -> (Arc<dyn ProgressObserver>, std::sync::mpsc::Receiver<ProgressEvent>) {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    (Arc::new(sender), receiver)
+}
+
+/// Per-proof reporter mapping stage fractions onto overall percentages
+#[cfg(feature = "prover")]
+pub(crate) struct ProgressReporter {
+    observer: Option<Arc<dyn ProgressObserver>>,
+    /// Last percentage reported, held while reporting so reports arrive in order
+    reported: Mutex<u8>,
+}
+
+#[cfg(feature = "prover")]
+impl ProgressReporter {
+    pub(crate) fn new(observer: Option<Arc<dyn ProgressObserver>>) -> Self {
+        Self {
+            observer,
+            reported: Mutex::new(0),
+        }
+    }
+
+    /// Report `done` of `total` units of `stage` finished
+    pub(crate) fn report(&self, stage: ProvingStage, done: usize, total: usize) {
+        let Some(observer) = &self.observer else {
+            return;
+        };
+        let (start, end) = stage.span();
+        let fraction = done.min(total) as f64 / total.max(1) as f64;
+        let percent = start + ((end - start) as f64 * fraction) as u8;
+        let mut reported = self.reported.lock().unwrap_or_else(|e| e.into_inner());
+        if percent > *reported {
+            *reported = percent;
+            observer.on_progress(stage, percent);
+        }
+    }
+
+    /// Report `stage` complete
+    pub(crate) fn finish(&self, stage: ProvingStage) {
+        self.report(stage, 1, 1);
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_progress_covers_every_stage_in_order() {
+        let (observer, events) = channel();
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_progress(observer);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest").unwrap();
+
+        let events: Vec<ProgressEvent> = events.try_iter().collect();
+        assert!(events.windows(2).all(|pair| pair[0].percent < pair[1].percent));
+        assert_eq!(events.last().map(|e| (e.stage, e.percent)), Some((ProvingStage::Queries, 100)));
+        let stages = [ProvingStage::TraceBuild, ProvingStage::Lde, ProvingStage::Commitment, ProvingStage::Fri, ProvingStage::Queries];
+        for stage in stages {
+            assert!(events.iter().any(|e| e.stage == stage), "no report from {:?}", stage);
+        }
+        let first_of = |stage| events.iter().position(|e| e.stage == stage).unwrap();
+        assert!(stages.windows(2).all(|pair| first_of(pair[0]) < first_of(pair[1])));
+    }
+}
@@ -0,0 +1,340 @@
+//! Protocol Versions
+//!
+//! Every envelope records the protocol version it was produced under. The
+//! verifier accepts the current version and, with the `compat` feature, the
+//! previous `COMPAT_WINDOW` versions, so proofs referenced long-term on-chain
+//! stay checkable after upgrades. Logic that differs between versions lives
+//! here, behind the envelope encoding the rest of the crate uses.
+//!
+//! Version 1 envelopes predate the version field: their wire encoding ends
+//! at `content_address`, and prover signatures and content addresses cover
+//! that shorter encoding. Version 2 envelopes end at `protocol_version` and
+//! carry no STARK parameters; they verify under the verifier's own. Version 3
+//! envelopes end at `stark_params` and carry no issuer signatures.
+
+use crate::{RepIDProof, Result, ZKPError};
+
+/// Version new envelopes are produced under
+pub const PROTOCOL_VERSION: u32 = 4;
+/// Version of envelopes without a version field
+pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+/// Versions before the current one the `compat` feature keeps verifying
+pub const COMPAT_WINDOW: u32 = 3;
+
+/// Serde default of `ProofMetadata::protocol_version`
+pub(crate) fn legacy_version() -> u32 {
+    LEGACY_PROTOCOL_VERSION
+}
+
+/// Oldest version this build verifies
+pub fn oldest_supported() -> u32 {
+    if cfg!(feature = "compat") {
+        PROTOCOL_VERSION - COMPAT_WINDOW
+    } else {
+        PROTOCOL_VERSION
+    }
+}
+
+pub fn is_supported(version: u32) -> bool {
+    (oldest_supported()..=PROTOCOL_VERSION).contains(&version)
+}
+
+/// Reject envelopes from versions this build does not verify
+pub fn check_version(proof: &RepIDProof) -> Result<()> {
+    let version = proof.metadata.protocol_version;
+    if !is_supported(version) {
+        return Err(ZKPError::UnsupportedVersion {
+            version,
+            oldest: oldest_supported(),
+            current: PROTOCOL_VERSION,
+        });
+    }
+    Ok(())
+}
+
+/// Wire encoding of an envelope in the layout of its own version
+///
+/// Signatures and content addresses are computed over this encoding.
+/// Versions outside the supported range are encoded in the current layout.
+pub fn encode_envelope(proof: &RepIDProof) -> Result<Vec<u8>> {
+    match proof.metadata.protocol_version {
+        #[cfg(feature = "compat")]
+        LEGACY_PROTOCOL_VERSION => v1::encode(proof),
+        #[cfg(feature = "compat")]
+        2 => v2::encode(proof),
+        #[cfg(feature = "compat")]
+        3 => v3::encode(proof),
+        _ => crate::wire::to_bytes(proof),
+    }
+}
+
+/// Decode a wire-encoded envelope, falling back to older layouts under `compat`
+pub fn decode_envelope(bytes: &[u8]) -> Result<RepIDProof> {
+    let current = crate::wire::from_bytes(bytes);
+    #[cfg(feature = "compat")]
+    // Older layouts are prefixes of newer ones, so try the longest first
+    let current = current
+        .or_else(|e| v3::decode(bytes).map_err(|_| e))
+        .or_else(|e| v2::decode(bytes).map_err(|_| e))
+        .or_else(|e| v1::decode(bytes).map_err(|_| e));
+    current
+}
+
+#[cfg(feature = "compat")]
+mod v1 {
+    use serde::{Deserialize, Serialize};
+
+    use crate::backend::BackendKind;
+    use crate::identity::ProverSignature;
+    use crate::publish::ContentAddress;
+    use crate::tee::TeeAttestation;
+    use crate::{ProofMetadata, RepIDProof, Result, F};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct MetadataV1 {
+        operation_type: String,
+        timestamp: u64,
+        wallet_hash: String,
+        proof_size: usize,
+        generation_time_ms: u64,
+        backend: BackendKind,
+        prover_signature: Option<ProverSignature>,
+        tee_attestation: Option<TeeAttestation>,
+        content_address: Option<ContentAddress>,
+    }
+
+    impl MetadataV1 {
+        pub(super) fn new(metadata: ProofMetadata) -> Self {
+            Self {
+                operation_type: metadata.operation_type,
+                timestamp: metadata.timestamp,
+                wallet_hash: metadata.wallet_hash,
+                proof_size: metadata.proof_size,
+                generation_time_ms: metadata.generation_time_ms,
+                backend: metadata.backend,
+                prover_signature: metadata.prover_signature,
+                tee_attestation: metadata.tee_attestation,
+                content_address: metadata.content_address,
+            }
+        }
+
+        pub(super) fn into_metadata(self, protocol_version: u32) -> ProofMetadata {
+            ProofMetadata {
+                operation_type: self.operation_type,
+                timestamp: self.timestamp,
+                wallet_hash: self.wallet_hash,
+                proof_size: self.proof_size,
+                generation_time_ms: self.generation_time_ms,
+                backend: self.backend,
+                prover_signature: self.prover_signature,
+                tee_attestation: self.tee_attestation,
+                content_address: self.content_address,
+                protocol_version,
+                stark_params: None,
+                issuer_signatures: Vec::new(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct EnvelopeV1 {
+        proof_data: Vec<u8>,
+        public_inputs: Vec<F>,
+        metadata: MetadataV1,
+    }
+
+    pub(super) fn encode(proof: &RepIDProof) -> Result<Vec<u8>> {
+        let envelope = EnvelopeV1 {
+            proof_data: proof.proof_data.clone(),
+            public_inputs: proof.public_inputs.clone(),
+            metadata: MetadataV1::new(proof.metadata.clone()),
+        };
+        crate::wire::to_bytes(&envelope)
+    }
+
+    pub(super) fn decode(bytes: &[u8]) -> Result<RepIDProof> {
+        let envelope: EnvelopeV1 = crate::wire::from_bytes(bytes)?;
+        Ok(RepIDProof {
+            proof_data: envelope.proof_data,
+            public_inputs: envelope.public_inputs,
+            metadata: envelope.metadata.into_metadata(super::LEGACY_PROTOCOL_VERSION),
+        })
+    }
+}
+
+#[cfg(feature = "compat")]
+mod v2 {
+    use serde::{Deserialize, Serialize};
+
+    use super::v1::MetadataV1;
+    use crate::{ProofMetadata, RepIDProof, Result, ZKPError, F};
+
+    /// Version 1 metadata followed by the version field
+    ///
+    /// The wire encoding lays nested structs out inline, so this is the flat version 2 layout.
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct MetadataV2 {
+        base: MetadataV1,
+        pub(super) protocol_version: u32,
+    }
+
+    impl MetadataV2 {
+        pub(super) fn new(metadata: ProofMetadata) -> Self {
+            let protocol_version = metadata.protocol_version;
+            Self { base: MetadataV1::new(metadata), protocol_version }
+        }
+
+        pub(super) fn into_metadata(self) -> ProofMetadata {
+            self.base.into_metadata(self.protocol_version)
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct EnvelopeV2 {
+        proof_data: Vec<u8>,
+        public_inputs: Vec<F>,
+        metadata: MetadataV2,
+    }
+
+    pub(super) fn encode(proof: &RepIDProof) -> Result<Vec<u8>> {
+        let envelope = EnvelopeV2 {
+            proof_data: proof.proof_data.clone(),
+            public_inputs: proof.public_inputs.clone(),
+            metadata: MetadataV2::new(proof.metadata.clone()),
+        };
+        crate::wire::to_bytes(&envelope)
+    }
+
+    pub(super) fn decode(bytes: &[u8]) -> Result<RepIDProof> {
+        let envelope: EnvelopeV2 = crate::wire::from_bytes(bytes)?;
+        let metadata = envelope.metadata;
+        if metadata.protocol_version != 2 {
+            return Err(ZKPError::SerializationError(format!(
+                "version {} envelope in the version 2 layout",
+                metadata.protocol_version
+            )));
+        }
+        Ok(RepIDProof {
+            proof_data: envelope.proof_data,
+            public_inputs: envelope.public_inputs,
+            metadata: metadata.into_metadata(),
+        })
+    }
+}
+
+#[cfg(feature = "compat")]
+mod v3 {
+    use serde::{Deserialize, Serialize};
+
+    use super::v2::MetadataV2;
+    use crate::custom_stark::StarkParams;
+    use crate::{ProofMetadata, RepIDProof, Result, ZKPError, F};
+
+    /// Version 2 metadata followed by the STARK parameters
+    #[derive(Serialize, Deserialize)]
+    struct MetadataV3 {
+        base: MetadataV2,
+        stark_params: Option<StarkParams>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct EnvelopeV3 {
+        proof_data: Vec<u8>,
+        public_inputs: Vec<F>,
+        metadata: MetadataV3,
+    }
+
+    pub(super) fn encode(proof: &RepIDProof) -> Result<Vec<u8>> {
+        let envelope = EnvelopeV3 {
+            proof_data: proof.proof_data.clone(),
+            public_inputs: proof.public_inputs.clone(),
+            metadata: MetadataV3 {
+                base: MetadataV2::new(proof.metadata.clone()),
+                stark_params: proof.metadata.stark_params,
+            },
+        };
+        crate::wire::to_bytes(&envelope)
+    }
+
+    pub(super) fn decode(bytes: &[u8]) -> Result<RepIDProof> {
+        let envelope: EnvelopeV3 = crate::wire::from_bytes(bytes)?;
+        let metadata = envelope.metadata;
+        if metadata.base.protocol_version != 3 {
+            return Err(ZKPError::SerializationError(format!(
+                "version {} envelope in the version 3 layout",
+                metadata.base.protocol_version
+            )));
+        }
+        Ok(RepIDProof {
+            proof_data: envelope.proof_data,
+            public_inputs: envelope.public_inputs,
+            metadata: ProofMetadata { stark_params: metadata.stark_params, ..metadata.base.into_metadata() },
+        })
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_versions_outside_the_window_are_rejected() {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let proof = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest").unwrap().proof;
+        assert_eq!(proof.metadata.protocol_version, PROTOCOL_VERSION);
+        let decoded = decode_envelope(&encode_envelope(&proof).unwrap()).unwrap();
+        assert!(zkp_system.verify_proof(&decoded, Some(&request)).unwrap());
+
+        // JSON envelopes without a version are legacy envelopes
+        let mut json: serde_json::Value = serde_json::to_value(&proof).unwrap();
+        json["metadata"].as_object_mut().unwrap().remove("protocol_version");
+        let legacy: RepIDProof = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy.metadata.protocol_version, LEGACY_PROTOCOL_VERSION);
+        assert_eq!(zkp_system.verify_proof(&legacy, Some(&request)).unwrap(), cfg!(feature = "compat"));
+
+        let future = RepIDProof {
+            metadata: crate::ProofMetadata { protocol_version: PROTOCOL_VERSION + 1, ..proof.metadata.clone() },
+            ..proof.clone()
+        };
+        assert!(!zkp_system.verify_proof(&future, Some(&request)).unwrap());
+        assert!(matches!(check_version(&future), Err(ZKPError::UnsupportedVersion { current: PROTOCOL_VERSION, .. })));
+
+        // Version 1 bytes lack the trailing version field, version 2 bytes the parameters, version 3 bytes the issuer signatures
+        #[cfg(feature = "compat")]
+        {
+            let bytes = encode_envelope(&legacy).unwrap();
+            let decoded = decode_envelope(&bytes).unwrap();
+            assert_eq!(decoded.metadata.protocol_version, LEGACY_PROTOCOL_VERSION);
+            assert!(zkp_system.verify_proof(&decoded, Some(&request)).unwrap());
+
+            let v2 = RepIDProof {
+                metadata: crate::ProofMetadata { protocol_version: 2, stark_params: None, ..proof.metadata.clone() },
+                ..proof.clone()
+            };
+            let v2_bytes = encode_envelope(&v2).unwrap();
+            assert_eq!(bytes.len() + 4, v2_bytes.len());
+            let v3 = RepIDProof {
+                metadata: crate::ProofMetadata { protocol_version: 3, ..proof.metadata.clone() },
+                ..proof.clone()
+            };
+            let v3_bytes = encode_envelope(&v3).unwrap();
+            assert_eq!(v2_bytes.len() + 1 + 32, v3_bytes.len());
+            let no_signatures = crate::wire::to_bytes(&proof.metadata.issuer_signatures).unwrap();
+            assert_eq!(v3_bytes.len() + no_signatures.len(), encode_envelope(&proof).unwrap().len());
+            let decoded = decode_envelope(&v2_bytes).unwrap();
+            assert_eq!((decoded.metadata.protocol_version, decoded.metadata.stark_params), (2, None));
+            assert!(zkp_system.verify_proof(&decoded, Some(&request)).unwrap());
+            let decoded = decode_envelope(&v3_bytes).unwrap();
+            assert_eq!((decoded.metadata.protocol_version, decoded.metadata.stark_params), (3, proof.metadata.stark_params));
+            assert!(zkp_system.verify_proof(&decoded, Some(&request)).unwrap());
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Public Input Digests
+//!
+//! Collapses any number of public inputs into a wide Poseidon2 digest of eight
+//! field elements, packed into one 32-byte word so on-chain contracts only
+//! store and compare a single slot
+
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2::{self, Digest};
+use crate::F;
+
+/// Ordered public inputs of a proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicInputs {
+    pub values: Vec<F>,
+}
+
+impl PublicInputs {
+    pub fn new(values: Vec<F>) -> Self {
+        Self { values }
+    }
+
+    /// Canonical encoding: every value reduced into [0, p), in proof order
+    pub fn canonical_encoding(&self) -> Vec<F> {
+        self.values.iter().map(|value| F::new(value.0)).collect()
+    }
+
+    /// Poseidon2 digest over the canonical encoding (length is domain-separated)
+    pub fn digest(&self) -> Digest {
+        poseidon2::hash_to_digest(&self.canonical_encoding())
+    }
+
+    /// Digest as a big-endian 32-byte word for EVM storage, four bytes per element
+    pub fn digest_bytes(&self) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        for (chunk, element) in word.chunks_exact_mut(4).zip(self.digest()) {
+            chunk.copy_from_slice(&(element.0 as u32).to_be_bytes());
+        }
+        word
+    }
+}
+
+impl From<Vec<F>> for PublicInputs {
+    fn from(values: Vec<F>) -> Self {
+        Self::new(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_is_order_and_length_sensitive() {
+        let inputs = PublicInputs::new(vec![F::new(100), F::new(86400)]);
+        let swapped = PublicInputs::new(vec![F::new(86400), F::new(100)]);
+        let extended = PublicInputs::new(vec![F::new(100), F::new(86400), F::ZERO]);
+
+        assert_ne!(inputs.digest(), swapped.digest());
+        assert_ne!(inputs.digest(), extended.digest());
+        assert_eq!(inputs.digest(), PublicInputs::new(vec![F(100), F(86400)]).digest());
+        assert_eq!(&inputs.digest_bytes()[28..], &(inputs.digest()[7].0 as u32).to_be_bytes());
+        assert_eq!(inputs.digest()[0], poseidon2::hash_elements(&inputs.values));
+    }
+}
@@ -0,0 +1,276 @@
+//! Proof Publication
+//!
+//! Content addresses for proof envelopes stored on IPFS or Arweave, so contracts
+//! keep only the address while the full proof stays retrievable off-chain
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{RepIDProof, Result, ZKPError};
+
+/// Content-addressed network an envelope is stored on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageNetwork {
+    Ipfs,
+    Arweave,
+}
+
+/// Where a published envelope lives and the hash of the bytes stored there
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentAddress {
+    pub network: StorageNetwork,
+    /// CIDv1 on IPFS, transaction ID on Arweave
+    pub id: String,
+    /// SHA-256 of the stored envelope
+    pub payload_hash: [u8; 32],
+}
+
+impl ContentAddress {
+    /// `ipfs://<cid>` or `ar://<tx id>`
+    pub fn uri(&self) -> String {
+        match self.network {
+            StorageNetwork::Ipfs => format!("ipfs://{}", self.id),
+            StorageNetwork::Arweave => format!("ar://{}", self.id),
+        }
+    }
+}
+
+/// Bytes uploaded for `proof`: the encoded envelope with the address slot cleared
+pub fn published_payload(proof: &RepIDProof) -> Result<Vec<u8>> {
+    let mut unpublished = proof.clone();
+    unpublished.metadata.content_address = None;
+    crate::protocol::encode_envelope(&unpublished)
+}
+
+/// Decode retrieved bytes, checking them against the address they were fetched from
+pub fn open_payload(address: &ContentAddress, payload: &[u8]) -> Result<RepIDProof> {
+    if sha256(payload) != address.payload_hash {
+        return Err(ZKPError::VerificationError(format!("content at {} does not match its hash", address.uri())));
+    }
+    let mut proof = crate::protocol::decode_envelope(payload)?;
+    proof.metadata.content_address = Some(address.clone());
+    Ok(proof)
+}
+
+/// CIDv1 of `payload` stored as a single raw block (multibase base32, sha2-256)
+pub fn ipfs_cid_v1(payload: &[u8]) -> String {
+    // version 1, raw codec, sha2-256 multihash of 32 bytes
+    let mut cid = vec![0x01, 0x55, 0x12, 0x20];
+    cid.extend_from_slice(&sha256(payload));
+    format!("b{}", base32_lower(&cid))
+}
+
+/// RFC 4648 base32, lowercase and unpadded
+fn base32_lower(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+    let mut out = String::with_capacity((data.len() * 8).div_ceil(5));
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    out
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Adapters uploading envelopes through an IPFS pinning service or an Arweave gateway
+#[cfg(feature = "publish")]
+pub mod remote {
+    use std::fmt::Debug;
+
+    use super::*;
+
+    /// Transport to a pinning service or gateway
+    pub trait PublishClient: Debug + Send + Sync {
+        /// Store `payload` and return the ID the network assigned to it
+        fn upload(&self, payload: &[u8], content_type: &str) -> Result<String>;
+
+        fn fetch(&self, id: &str) -> Result<Vec<u8>>;
+    }
+
+    /// Publishes envelopes and records the resulting address in their metadata
+    pub trait Publisher: Debug + Send + Sync {
+        fn network(&self) -> StorageNetwork;
+
+        fn publish(&self, proof: &mut RepIDProof) -> Result<ContentAddress>;
+
+        fn retrieve(&self, address: &ContentAddress) -> Result<RepIDProof>;
+    }
+
+    const CONTENT_TYPE: &str = "application/octet-stream";
+
+    /// IPFS publisher; the client must add payloads as one raw block (CIDv1, raw leaves)
+    #[derive(Debug)]
+    pub struct IpfsPublisher<C: PublishClient> {
+        client: C,
+    }
+
+    impl<C: PublishClient> IpfsPublisher<C> {
+        pub fn new(client: C) -> Self {
+            Self { client }
+        }
+    }
+
+    impl<C: PublishClient> Publisher for IpfsPublisher<C> {
+        fn network(&self) -> StorageNetwork {
+            StorageNetwork::Ipfs
+        }
+
+        fn publish(&self, proof: &mut RepIDProof) -> Result<ContentAddress> {
+            let payload = published_payload(proof)?;
+            let expected = ipfs_cid_v1(&payload);
+            let cid = self.client.upload(&payload, CONTENT_TYPE)?;
+            if cid != expected {
+                return Err(ZKPError::VerificationError(format!(
+                    "pinning service returned CID {}, expected {}",
+                    cid, expected
+                )));
+            }
+            record(proof, StorageNetwork::Ipfs, cid, &payload)
+        }
+
+        fn retrieve(&self, address: &ContentAddress) -> Result<RepIDProof> {
+            retrieve(&self.client, StorageNetwork::Ipfs, address)
+        }
+    }
+
+    /// Arweave publisher; transaction IDs are assigned by the gateway
+    #[derive(Debug)]
+    pub struct ArweavePublisher<C: PublishClient> {
+        client: C,
+    }
+
+    impl<C: PublishClient> ArweavePublisher<C> {
+        pub fn new(client: C) -> Self {
+            Self { client }
+        }
+    }
+
+    impl<C: PublishClient> Publisher for ArweavePublisher<C> {
+        fn network(&self) -> StorageNetwork {
+            StorageNetwork::Arweave
+        }
+
+        fn publish(&self, proof: &mut RepIDProof) -> Result<ContentAddress> {
+            let payload = published_payload(proof)?;
+            let tx_id = self.client.upload(&payload, CONTENT_TYPE)?;
+            record(proof, StorageNetwork::Arweave, tx_id, &payload)
+        }
+
+        fn retrieve(&self, address: &ContentAddress) -> Result<RepIDProof> {
+            retrieve(&self.client, StorageNetwork::Arweave, address)
+        }
+    }
+
+    fn record(proof: &mut RepIDProof, network: StorageNetwork, id: String, payload: &[u8]) -> Result<ContentAddress> {
+        let address = ContentAddress {
+            network,
+            id,
+            payload_hash: sha256(payload),
+        };
+        proof.metadata.content_address = Some(address.clone());
+        Ok(address)
+    }
+
+    fn retrieve(client: &impl PublishClient, network: StorageNetwork, address: &ContentAddress) -> Result<RepIDProof> {
+        if address.network != network {
+            return Err(ZKPError::InvalidInput(format!("{} is not a {:?} address", address.uri(), network)));
+        }
+        open_payload(address, &client.fetch(&address.id)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> RepIDProof {
+        RepIDProof {
+            proof_data: vec![1, 2, 3],
+            public_inputs: Vec::new(),
+            metadata: crate::ProofMetadata {
+                operation_type: "threshold_verification".to_string(),
+                timestamp: 0,
+                wallet_hash: String::new(),
+                proof_size: 3,
+                generation_time_ms: 0,
+                backend: crate::backend::BackendKind::CustomStark,
+                prover_signature: None,
+                tee_attestation: None,
+                content_address: None,
+                protocol_version: crate::protocol::PROTOCOL_VERSION,
+                stark_params: None,
+                issuer_signatures: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_cid_and_payload_round_trip() {
+        // Known CIDv1 (raw, sha2-256) of the empty block
+        assert_eq!(ipfs_cid_v1(b""), "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku");
+
+        let mut proof = envelope();
+        let payload = published_payload(&proof).unwrap();
+        let address = ContentAddress {
+            network: StorageNetwork::Ipfs,
+            id: ipfs_cid_v1(&payload),
+            payload_hash: sha256(&payload),
+        };
+        proof.metadata.content_address = Some(address.clone());
+        assert_eq!(published_payload(&proof).unwrap(), payload);
+
+        let opened = open_payload(&address, &payload).unwrap();
+        assert_eq!(opened.metadata.content_address.as_ref(), Some(&address));
+        assert!(address.uri().starts_with("ipfs://bafkrei"));
+        assert!(matches!(open_payload(&address, &payload[1..]), Err(ZKPError::VerificationError(_))));
+    }
+
+    #[cfg(feature = "publish")]
+    #[test]
+    fn test_arweave_adapter_records_address() {
+        use remote::{ArweavePublisher, IpfsPublisher, PublishClient, Publisher};
+        use std::collections::HashMap;
+        use std::sync::Mutex;
+
+        /// Stand-in gateway keyed by a counter
+        #[derive(Debug, Default)]
+        struct FakeGateway(Mutex<HashMap<String, Vec<u8>>>);
+
+        impl PublishClient for FakeGateway {
+            fn upload(&self, payload: &[u8], _content_type: &str) -> Result<String> {
+                let mut store = self.0.lock().unwrap_or_else(|e| e.into_inner());
+                let id = format!("tx{}", store.len());
+                store.insert(id.clone(), payload.to_vec());
+                Ok(id)
+            }
+
+            fn fetch(&self, id: &str) -> Result<Vec<u8>> {
+                let store = self.0.lock().unwrap_or_else(|e| e.into_inner());
+                store.get(id).cloned().ok_or_else(|| ZKPError::InvalidInput(format!("unknown id {}", id)))
+            }
+        }
+
+        let arweave = ArweavePublisher::new(FakeGateway::default());
+        let mut proof = envelope();
+        let address = arweave.publish(&mut proof).unwrap();
+        assert_eq!(address.uri(), "ar://tx0");
+        assert_eq!(proof.metadata.content_address.as_ref(), Some(&address));
+        assert_eq!(arweave.retrieve(&address).unwrap().proof_data, proof.proof_data);
+
+        // A gateway that does not return the raw-block CID is rejected
+        assert!(IpfsPublisher::new(FakeGateway::default()).publish(&mut envelope()).is_err());
+    }
+}
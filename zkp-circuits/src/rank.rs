@@ -0,0 +1,130 @@
+//! Rank Buckets
+//!
+//! Published percentile cutoffs of the score distribution, committed with
+//! Poseidon2 so users can prove a leaderboard band without their exact standing
+
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2;
+use crate::{RepIDProof, Result, ZKPError, F};
+
+/// Operation type of rank-bucket proofs
+pub const RANK_BUCKET_OPERATION: &str = "rank_bucket";
+
+/// Public commitment to a score distribution (Poseidon2 digest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistributionCommitment(pub F);
+
+/// Minimum score needed to be in the top `top_percent` of the population
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PercentileCutoff {
+    pub top_percent: u8,
+    pub min_score: u32,
+}
+
+/// Percentile cutoffs of a score population, tightest band first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreDistribution {
+    pub cutoffs: Vec<PercentileCutoff>,
+}
+
+impl ScoreDistribution {
+    /// Cutoffs for each band in `top_percents` over a population of scores
+    pub fn from_scores(scores: &[u32], top_percents: &[u8]) -> Result<Self> {
+        if scores.is_empty() {
+            return Err(ZKPError::InvalidInput("Distribution needs at least one score".to_string()));
+        }
+
+        let mut sorted = scores.to_vec();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        let mut bands = top_percents.to_vec();
+        bands.sort_unstable();
+        bands.dedup();
+
+        let mut cutoffs = Vec::with_capacity(bands.len());
+        for top_percent in bands {
+            if top_percent == 0 || top_percent > 100 {
+                return Err(ZKPError::InvalidInput(format!("Band 'top {}%' is out of range", top_percent)));
+            }
+            let rank = (sorted.len() * top_percent as usize).div_ceil(100).max(1);
+            cutoffs.push(PercentileCutoff {
+                top_percent,
+                min_score: sorted[rank - 1],
+            });
+        }
+
+        Ok(Self { cutoffs })
+    }
+
+    /// Canonical hash input: [top_percent_0, min_score_0, top_percent_1, min_score_1, ...]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        self.cutoffs
+            .iter()
+            .flat_map(|c| [F::new(c.top_percent as u64), F::from_u32(c.min_score)])
+            .collect()
+    }
+
+    /// Position of a band's `top_percent` within `to_field_elements`
+    pub fn band_position(&self, top_percent: u8) -> Option<usize> {
+        self.cutoffs.iter().position(|c| c.top_percent == top_percent).map(|index| 2 * index)
+    }
+
+    pub fn cutoff(&self, top_percent: u8) -> Option<u32> {
+        self.cutoffs.iter().find(|c| c.top_percent == top_percent).map(|c| c.min_score)
+    }
+
+    /// Tightest band a score qualifies for
+    pub fn best_bucket(&self, score: u32) -> Option<u8> {
+        self.cutoffs
+            .iter()
+            .filter(|c| score >= c.min_score)
+            .map(|c| c.top_percent)
+            .min()
+    }
+
+    pub fn commit(&self) -> DistributionCommitment {
+        DistributionCommitment(poseidon2::hash_elements(&self.to_field_elements()))
+    }
+}
+
+/// Read the (band, distribution commitment) a rank-bucket proof was made for
+pub fn proof_bucket(proof: &RepIDProof) -> Result<(u8, DistributionCommitment)> {
+    if proof.metadata.operation_type != RANK_BUCKET_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no rank bucket",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(0..2) {
+        Some([top_percent, commitment]) => Ok((top_percent.0 as u8, DistributionCommitment(*commitment))),
+        _ => Err(ZKPError::MalformedProof("Rank proof needs a band and distribution commitment".to_string())),
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel};
+
+    #[test]
+    fn test_rank_bucket_proofs() {
+        let population: Vec<u32> = (1..=100).collect();
+        let distribution = ScoreDistribution::from_scores(&population, &[25, 10]).unwrap();
+        assert_eq!(distribution.cutoff(10), Some(91));
+        assert_eq!(distribution.cutoff(25), Some(76));
+        assert_eq!(distribution.best_bucket(80), Some(25));
+        let commitment = distribution.commit();
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let scores = [(RepIDCategory::Community, 50), (RepIDCategory::DeFi, 30)];
+
+        let proof = zkp_system.prove_rank_bucket(&scores, &distribution, &commitment, 25, "0xtest").unwrap();
+        assert!(zkp_system.verify_proof(&proof, None).unwrap());
+        assert_eq!(proof_bucket(&proof).unwrap(), (25, commitment));
+
+        // 80 is not in the top 10%, and the distribution must match its commitment
+        assert!(zkp_system.prove_rank_bucket(&scores, &distribution, &commitment, 10, "0xtest").is_err());
+        let other = ScoreDistribution::from_scores(&population, &[50]).unwrap().commit();
+        assert!(zkp_system.prove_rank_bucket(&scores, &distribution, &other, 25, "0xtest").is_err());
+    }
+}
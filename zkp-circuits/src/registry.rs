@@ -0,0 +1,554 @@
+//! Proof Registry
+//!
+//! Verifier-side store of received proof envelopes with retention policies.
+//! Compaction drops proofs past their retention and leaves a tombstone per
+//! proof, so auditors can still see what was held and why it was removed.
+//! Compaction runs on demand or on a background thread. Registries opened on
+//! a `Storage` write proofs and tombstones through and restore them on open.
+//! With an integrity key each stored envelope also gets a keyed BLAKE3 MAC,
+//! so bit-rot or tampering in the storage layer shows up on a later read.
+//! `expiring` lists proofs nearing the end of their retention, which
+//! `reproving` renews in the background.
+
+use std::collections::btree_map::{BTreeMap, Entry};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::Clock;
+use crate::storage::{self, Storage};
+use crate::{RepIDProof, Result, ZKPError};
+
+/// Storage namespaces of held proofs and of tombstones
+const PROOFS_NAMESPACE: &str = "registry/proofs";
+const TOMBSTONES_NAMESPACE: &str = "registry/tombstones";
+const MACS_NAMESPACE: &str = "registry/macs";
+
+/// Blake3 of the encoded envelope
+pub type ProofId = [u8; 32];
+
+/// Key of the envelope MACs; distinct from any signing key
+pub type IntegrityKey = [u8; 32];
+
+/// Condition under which a stored proof is dropped
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionRule {
+    /// Stored longer than this many seconds
+    MaxAge(u64),
+    /// Stored in an epoch more than this many epochs before the current one
+    MaxEpochs(u64),
+    /// Of this operation type and stored longer than `max_age` seconds
+    Operation { operation_type: String, max_age: u64 },
+}
+
+impl RetentionRule {
+    fn expires(&self, entry: &StoredProof, now: u64, current_epoch: u64) -> bool {
+        match self {
+            RetentionRule::MaxAge(max_age) => now.saturating_sub(entry.stored_at) > *max_age,
+            RetentionRule::MaxEpochs(keep) => current_epoch.saturating_sub(entry.epoch) > *keep,
+            RetentionRule::Operation { operation_type, max_age } => {
+                entry.proof.metadata.operation_type == *operation_type && now.saturating_sub(entry.stored_at) > *max_age
+            }
+        }
+    }
+}
+
+/// Rules a registry compacts by; a proof is dropped once any rule expires it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub rules: Vec<RetentionRule>,
+}
+
+impl RetentionPolicy {
+    /// Policy keeping every proof
+    pub fn keep_all() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_age(mut self, max_age: u64) -> Self {
+        self.rules.push(RetentionRule::MaxAge(max_age));
+        self
+    }
+
+    pub fn with_max_epochs(mut self, keep: u64) -> Self {
+        self.rules.push(RetentionRule::MaxEpochs(keep));
+        self
+    }
+
+    pub fn with_operation_max_age(mut self, operation_type: &str, max_age: u64) -> Self {
+        self.rules.push(RetentionRule::Operation {
+            operation_type: operation_type.to_string(),
+            max_age,
+        });
+        self
+    }
+
+    /// Last instant a proof stored at `stored_at` is held under the time-based rules
+    ///
+    /// `MaxEpochs` rules depend on the epoch clock rather than time and are
+    /// not reflected; `None` when no time-based rule applies.
+    pub fn valid_until(&self, proof: &RepIDProof, stored_at: u64) -> Option<u64> {
+        self.rules
+            .iter()
+            .filter_map(|rule| match rule {
+                RetentionRule::MaxAge(max_age) => Some(*max_age),
+                RetentionRule::Operation { operation_type, max_age } if proof.metadata.operation_type == *operation_type => {
+                    Some(*max_age)
+                }
+                _ => None,
+            })
+            .min()
+            .map(|max_age| stored_at.saturating_add(max_age))
+    }
+
+    /// First rule expiring `entry`, if any
+    fn expiring_rule(&self, entry: &StoredProof, now: u64, current_epoch: u64) -> Option<&RetentionRule> {
+        self.rules.iter().find(|rule| rule.expires(entry, now, current_epoch))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredProof {
+    proof: RepIDProof,
+    epoch: u64,
+    stored_at: u64,
+}
+
+/// Record of a proof removed from the registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub id: ProofId,
+    pub operation_type: String,
+    /// Generation time from the proof metadata
+    pub proof_timestamp: u64,
+    pub epoch: u64,
+    pub stored_at: u64,
+    pub removed_at: u64,
+    pub rule: RetentionRule,
+}
+
+/// Held proof the retention policy drops soon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiringProof {
+    pub id: ProofId,
+    pub proof: RepIDProof,
+    pub epoch: u64,
+    pub valid_until: u64,
+}
+
+/// Outcome of re-reading every held proof from storage
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub checked: usize,
+    /// Missing, undecodable, or failing their MAC
+    pub corrupted: Vec<ProofId>,
+    /// Stored without a MAC, e.g. before the registry had an integrity key
+    pub unsealed: Vec<ProofId>,
+}
+
+impl IntegrityReport {
+    pub fn is_intact(&self) -> bool {
+        self.corrupted.is_empty() && self.unsealed.is_empty()
+    }
+}
+
+/// Keyed BLAKE3 over a proof id and its stored bytes
+fn envelope_mac(key: &IntegrityKey, id: &ProofId, stored: &[u8]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new_keyed(key);
+    hasher.update(id).update(stored);
+    *hasher.finalize().as_bytes()
+}
+
+/// Proof store with retention
+pub struct ProofRegistry {
+    policy: RetentionPolicy,
+    clock: Arc<dyn Clock>,
+    entries: Mutex<BTreeMap<ProofId, StoredProof>>,
+    tombstones: Mutex<Vec<Tombstone>>,
+    storage: Option<Arc<dyn Storage>>,
+    integrity_key: Option<IntegrityKey>,
+}
+
+impl std::fmt::Debug for ProofRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProofRegistry")
+            .field("policy", &self.policy)
+            .field("clock", &self.clock)
+            .field("entries", &self.entries)
+            .field("tombstones", &self.tombstones)
+            .field("storage", &self.storage)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ProofRegistry {
+    /// Registry held in memory only
+    pub fn new(policy: RetentionPolicy, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            policy,
+            clock,
+            entries: Mutex::new(BTreeMap::new()),
+            tombstones: Mutex::new(Vec::new()),
+            storage: None,
+            integrity_key: None,
+        }
+    }
+
+    /// Registry persisted in `storage`, restoring the proofs and tombstones held there
+    pub fn open(policy: RetentionPolicy, clock: Arc<dyn Clock>, storage: Arc<dyn Storage>) -> Result<Self> {
+        let mut entries = BTreeMap::new();
+        for (key, value) in storage.iterate(PROOFS_NAMESPACE)? {
+            let id = ProofId::try_from(key.as_slice())
+                .map_err(|_| ZKPError::StorageError(format!("Stored proof key has {} bytes", key.len())))?;
+            entries.insert(id, storage::decode(&value)?);
+        }
+        let tombstones = storage.iterate(TOMBSTONES_NAMESPACE)?
+            .iter()
+            .map(|(_, value)| storage::decode(value))
+            .collect::<Result<Vec<Tombstone>>>()?;
+
+        Ok(Self {
+            policy,
+            clock,
+            entries: Mutex::new(entries),
+            tombstones: Mutex::new(tombstones),
+            storage: Some(storage),
+            integrity_key: None,
+        })
+    }
+
+    /// MAC every envelope stored from now on with `key`
+    ///
+    /// Envelopes already in storage stay unsealed until `seal` is called.
+    pub fn with_integrity_key(mut self, key: IntegrityKey) -> Self {
+        self.integrity_key = Some(key);
+        self
+    }
+
+    pub fn policy(&self) -> &RetentionPolicy {
+        &self.policy
+    }
+
+    /// Blake3 over the encoded envelope
+    pub fn proof_id(proof: &RepIDProof) -> ProofId {
+        *blake3::hash(&crate::wire::to_bytes(proof).expect("proof serializes")).as_bytes()
+    }
+
+    /// Store a proof received in `epoch`; storing it again keeps the first copy
+    pub fn insert(&self, proof: RepIDProof, epoch: u64) -> Result<ProofId> {
+        let id = Self::proof_id(&proof);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Entry::Vacant(slot) = entries.entry(id) {
+            let entry = StoredProof { proof, epoch, stored_at: self.clock.now() };
+            if let Some(storage) = &self.storage {
+                let stored = storage::encode(&entry)?;
+                storage.put(PROOFS_NAMESPACE, &id, &stored)?;
+                if let Some(key) = &self.integrity_key {
+                    storage.put(MACS_NAMESPACE, &id, &envelope_mac(key, &id, &stored))?;
+                }
+            }
+            slot.insert(entry);
+        }
+        Ok(id)
+    }
+
+    pub fn get(&self, id: &ProofId) -> Option<RepIDProof> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).get(id).map(|entry| entry.proof.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Last instant the held proof `id` is kept under the time-based rules
+    pub fn valid_until(&self, id: &ProofId) -> Option<u64> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.get(id).and_then(|entry| self.policy.valid_until(&entry.proof, entry.stored_at))
+    }
+
+    /// Held proofs whose `valid_until` falls within `within_secs` from now, soonest first
+    pub fn expiring(&self, within_secs: u64) -> Vec<ExpiringProof> {
+        let horizon = self.clock.now().saturating_add(within_secs);
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut expiring: Vec<ExpiringProof> = entries
+            .iter()
+            .filter_map(|(id, entry)| {
+                let valid_until = self.policy.valid_until(&entry.proof, entry.stored_at)?;
+                (valid_until <= horizon).then(|| ExpiringProof {
+                    id: *id,
+                    proof: entry.proof.clone(),
+                    epoch: entry.epoch,
+                    valid_until,
+                })
+            })
+            .collect();
+        expiring.sort_by_key(|proof| proof.valid_until);
+        expiring
+    }
+
+    /// Drop every proof the policy expires as of `current_epoch`, returning how many were removed
+    ///
+    /// With storage, each tombstone is written before its proof is deleted,
+    /// so a failed pass leaves no proof removed without a record.
+    pub fn compact(&self, current_epoch: u64) -> Result<usize> {
+        let now = self.clock.now();
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut tombstones = self.tombstones.lock().unwrap_or_else(|e| e.into_inner());
+        let expired: Vec<Tombstone> = entries
+            .iter()
+            .filter_map(|(id, entry)| {
+                self.policy.expiring_rule(entry, now, current_epoch).map(|rule| Tombstone {
+                    id: *id,
+                    operation_type: entry.proof.metadata.operation_type.clone(),
+                    proof_timestamp: entry.proof.metadata.timestamp,
+                    epoch: entry.epoch,
+                    stored_at: entry.stored_at,
+                    removed_at: now,
+                    rule: rule.clone(),
+                })
+            })
+            .collect();
+
+        for tombstone in &expired {
+            if let Some(storage) = &self.storage {
+                let sequence = tombstones.len() as u64;
+                storage.put(TOMBSTONES_NAMESPACE, &sequence.to_be_bytes(), &storage::encode(tombstone)?)?;
+                storage.remove(PROOFS_NAMESPACE, &tombstone.id)?;
+                storage.remove(MACS_NAMESPACE, &tombstone.id)?;
+            }
+            entries.remove(&tombstone.id);
+            tombstones.push(tombstone.clone());
+        }
+
+        let count = expired.len();
+        if count > 0 {
+            tracing::debug!(removed = count, epoch = current_epoch, "compacted proof registry");
+        }
+        Ok(count)
+    }
+
+    /// Re-read every held proof from storage and check it against its MAC and the copy in memory
+    ///
+    /// This checks the storage layer only; it does not verify the proofs.
+    /// Registries without storage have nothing to check.
+    pub fn verify_integrity(&self) -> Result<IntegrityReport> {
+        let key = self.integrity_key
+            .ok_or_else(|| ZKPError::ConfigError("Proof registry has no integrity key".to_string()))?;
+        let Some(storage) = &self.storage else {
+            return Ok(IntegrityReport::default());
+        };
+
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut report = IntegrityReport { checked: entries.len(), ..Default::default() };
+        for (id, entry) in entries.iter() {
+            let Some(stored) = storage.get(PROOFS_NAMESPACE, id)? else {
+                report.corrupted.push(*id);
+                continue;
+            };
+            let intact = match storage.get(MACS_NAMESPACE, id)? {
+                Some(mac) => mac == envelope_mac(&key, id, &stored),
+                None => {
+                    report.unsealed.push(*id);
+                    continue;
+                }
+            };
+            let matches_held = storage::decode::<StoredProof>(&stored)
+                .is_ok_and(|decoded| Self::proof_id(&decoded.proof) == *id && decoded.stored_at == entry.stored_at);
+            if !intact || !matches_held {
+                tracing::warn!("Stored proof {} failed its integrity check", hex::encode(id));
+                report.corrupted.push(*id);
+            }
+        }
+        Ok(report)
+    }
+
+    /// MAC every held envelope that has none, returning how many were sealed
+    ///
+    /// Envelopes are re-encoded from memory, so run `verify_integrity` first
+    /// if storage may already have been corrupted.
+    pub fn seal(&self) -> Result<usize> {
+        let (Some(key), Some(storage)) = (&self.integrity_key, &self.storage) else {
+            return Err(ZKPError::ConfigError("Sealing needs an integrity key and storage".to_string()));
+        };
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut sealed = 0;
+        for (id, entry) in entries.iter() {
+            if storage.get(MACS_NAMESPACE, id)?.is_none() {
+                let stored = storage::encode(entry)?;
+                storage.put(PROOFS_NAMESPACE, id, &stored)?;
+                storage.put(MACS_NAMESPACE, id, &envelope_mac(key, id, &stored))?;
+                sealed += 1;
+            }
+        }
+        Ok(sealed)
+    }
+
+    /// Tombstones of every proof removed so far, oldest first
+    pub fn tombstones(&self) -> Vec<Tombstone> {
+        self.tombstones.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Tombstones as a JSON array for audit export
+    pub fn export_tombstones(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.tombstones())
+            .map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    /// Compact every `interval` on a background thread until the handle is stopped or dropped
+    ///
+    /// `current_epoch` is read before each pass, e.g. from the `EpochManager`
+    /// the registry's epochs come from.
+    pub fn spawn_compaction<E>(self: &Arc<Self>, interval: Duration, current_epoch: E) -> CompactionHandle
+    where
+        E: Fn() -> u64 + Send + 'static,
+    {
+        let registry = Arc::clone(self);
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let thread = std::thread::spawn(move || {
+            while !stopped.load(Ordering::SeqCst) {
+                if let Err(e) = registry.compact(current_epoch()) {
+                    tracing::warn!("Proof registry compaction failed: {}", e);
+                }
+                std::thread::park_timeout(interval);
+            }
+        });
+        CompactionHandle { stop, thread: Some(thread) }
+    }
+}
+
+/// Background compaction started by `ProofRegistry::spawn_compaction`
+#[derive(Debug)]
+pub struct CompactionHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CompactionHandle {
+    /// Stop compacting and wait for the current pass to finish
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for CompactionHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn test_retention_rules_leave_tombstones() {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let mut prove = |score| {
+            zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, score)], "0xtest").unwrap().proof
+        };
+        let (old, recent, latest) = (prove(60), prove(70), prove(80));
+
+        let clock = Arc::new(FixedClock::new(1_000 * DAY));
+        let policy = RetentionPolicy::keep_all()
+            .with_max_age(90 * DAY)
+            .with_max_epochs(3)
+            .with_operation_max_age("threshold_verification", 30 * DAY);
+        let registry = Arc::new(ProofRegistry::new(policy, clock.clone()));
+        let old_id = registry.insert(old, 1).unwrap();
+        clock.advance(20 * DAY);
+        let recent_id = registry.insert(recent, 5).unwrap();
+        registry.insert(latest, 6).unwrap();
+        assert_eq!(registry.compact(6).unwrap(), 1);
+        assert!(registry.get(&old_id).is_none());
+        assert_eq!(registry.tombstones()[0].rule, RetentionRule::MaxEpochs(3));
+
+        // Threshold proofs are kept for 30 days, shorter than the 90-day default
+        clock.advance(40 * DAY);
+        let handle = registry.spawn_compaction(Duration::from_millis(10), || 6);
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !registry.is_empty() && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        handle.stop();
+
+        let tombstones = registry.tombstones();
+        assert_eq!(tombstones.len(), 3);
+        assert!(tombstones[1..].iter().all(|t| matches!(t.rule, RetentionRule::Operation { .. })));
+        assert!(tombstones.iter().any(|t| t.id == recent_id && t.removed_at == 1_060 * DAY));
+        let exported: Vec<Tombstone> = serde_json::from_str(&registry.export_tombstones().unwrap()).unwrap();
+        assert_eq!(exported, tombstones);
+    }
+
+    #[test]
+    fn test_integrity_mac_detects_storage_corruption() {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let mut prove = |score| {
+            zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, score)], "0xtest").unwrap().proof
+        };
+        let (early, sealed, tampered) = (prove(60), prove(70), prove(80));
+
+        let storage: Arc<dyn Storage> = Arc::new(crate::storage::MemoryStorage::new());
+        let clock = Arc::new(FixedClock::new(DAY));
+        let unkeyed = ProofRegistry::open(RetentionPolicy::keep_all(), clock.clone(), storage.clone()).unwrap();
+        let early_id = unkeyed.insert(early, 1).unwrap();
+
+        let key = [7u8; 32];
+        let registry = ProofRegistry::open(RetentionPolicy::keep_all(), clock.clone(), storage.clone())
+            .unwrap()
+            .with_integrity_key(key);
+        registry.insert(sealed, 1).unwrap();
+        let tampered_id = registry.insert(tampered, 1).unwrap();
+        let report = registry.verify_integrity().unwrap();
+        assert_eq!((report.checked, report.unsealed.clone()), (3, vec![early_id]));
+        assert!(report.corrupted.is_empty());
+        assert_eq!(registry.seal().unwrap(), 1);
+        assert!(registry.verify_integrity().unwrap().is_intact());
+
+        // Flip one bit of a stored envelope behind the registry's back
+        let mut stored = storage.get(PROOFS_NAMESPACE, &tampered_id).unwrap().unwrap();
+        let last = stored.len() - 1;
+        stored[last] ^= 1;
+        storage.put(PROOFS_NAMESPACE, &tampered_id, &stored).unwrap();
+        assert_eq!(registry.verify_integrity().unwrap().corrupted, vec![tampered_id]);
+
+        // The wrong key rejects every envelope
+        let rekeyed = ProofRegistry::open(RetentionPolicy::keep_all(), clock, storage).unwrap().with_integrity_key([8u8; 32]);
+        assert_eq!(rekeyed.verify_integrity().unwrap().corrupted.len(), 3);
+        assert!(ProofRegistry::new(RetentionPolicy::keep_all(), Arc::new(FixedClock::new(0))).verify_integrity().is_err());
+    }
+}
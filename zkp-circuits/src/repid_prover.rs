@@ -15,6 +15,7 @@ use plonky3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
 use plonky3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
 use plonky3_uni_stark::{prove, StarkConfig};
 use plonky3_util::log2_ceil_usize;
+use sha3::{Digest, Keccak256};
 
 use crate::{
     repid_air::{RepIDAir, BiometricAIR},
@@ -119,7 +120,7 @@ impl RepIDProver {
             metadata: ProofMetadata {
                 operation_type: "threshold_verification".to_string(),
                 timestamp: chrono::Utc::now().timestamp() as u64,
-                wallet_hash: format!("{:x}", md5::compute(wallet_address.as_bytes())),
+                wallet_hash: hex::encode(Keccak256::digest(wallet_address.as_bytes())),
                 proof_size: proof_bytes.len(),
                 generation_time_ms: generation_time,
             },
@@ -11,6 +11,7 @@ use plonky3_merkle_tree::FieldMerkleTreeMmcs;
 use plonky3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
 use plonky3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
 use plonky3_uni_stark::{verify, StarkConfig};
+use sha3::{Digest, Keccak256};
 
 use crate::{
     repid_air::{RepIDAir, BiometricAIR},
@@ -128,9 +129,7 @@ impl RepIDVerifier {
         let public_inputs = self.extract_public_inputs(proof);
         
         // Generate proof hash for on-chain storage
-        let proof_hash = format!("0x{:064x}", 
-            md5::compute(&proof.proof_bytes).iter().fold(0u64, |acc, &b| acc.wrapping_add(b as u64))
-        );
+        let proof_hash = format!("0x{}", hex::encode(Keccak256::digest(&proof.proof_bytes)));
 
         // Create verification metadata
         Ok(SolidityVerificationData {
@@ -0,0 +1,151 @@
+//! Replay Detection
+//!
+//! Tracks recently seen (request hash, wallet, epoch) submissions so verifiers
+//! can reject duplicates without keeping unbounded history. Guards opened on a
+//! `Storage` share their window across restarts and verifier replicas.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use crate::storage::Storage;
+use crate::{Result, ThresholdVerificationRequest, ZKPError};
+
+/// Storage namespace of seen submissions
+const STORAGE_NAMESPACE: &str = "replay/seen";
+
+/// Sliding window of seen submissions, keyed by epoch
+#[derive(Debug, Clone)]
+pub struct ReplayGuard {
+    /// Epochs kept before the newest one seen
+    retained_epochs: u64,
+    seen: BTreeMap<u64, HashSet<([u8; 32], String)>>,
+    storage: Option<Arc<dyn Storage>>,
+}
+
+impl ReplayGuard {
+    /// Guard held in memory only
+    pub fn new(retained_epochs: u64) -> Self {
+        Self {
+            retained_epochs,
+            seen: BTreeMap::new(),
+            storage: None,
+        }
+    }
+
+    /// Guard persisted in `storage`, restoring the submissions recorded there
+    pub fn open(retained_epochs: u64, storage: Arc<dyn Storage>) -> Result<Self> {
+        let mut seen: BTreeMap<u64, HashSet<([u8; 32], String)>> = BTreeMap::new();
+        for (key, _) in storage.iterate(STORAGE_NAMESPACE)? {
+            let (epoch, request_hash, wallet_hash) = parse_key(&key)?;
+            seen.entry(epoch).or_default().insert((request_hash, wallet_hash));
+        }
+        Ok(Self {
+            retained_epochs,
+            seen,
+            storage: Some(storage),
+        })
+    }
+
+    /// Oldest epoch still accepted
+    fn horizon(&self) -> u64 {
+        self.seen.keys().next_back().map_or(0, |newest| newest.saturating_sub(self.retained_epochs))
+    }
+
+    /// Whether the submission was already recorded in its epoch
+    pub fn is_replay(&self, request_hash: &[u8; 32], wallet_hash: &str, epoch: u64) -> bool {
+        self.seen
+            .get(&epoch)
+            .is_some_and(|entries| entries.contains(&(*request_hash, wallet_hash.to_string())))
+    }
+
+    /// Record a submission, rejecting duplicates and epochs older than the window
+    pub fn check_and_record(&mut self, request_hash: [u8; 32], wallet_hash: &str, epoch: u64) -> Result<()> {
+        if epoch < self.horizon() {
+            return Err(ZKPError::PolicyViolation(format!(
+                "Submission for epoch {} is older than the replay window (oldest {})",
+                epoch,
+                self.horizon()
+            )));
+        }
+        if self.is_replay(&request_hash, wallet_hash, epoch) {
+            return Err(ZKPError::PolicyViolation(format!(
+                "Replayed submission for wallet {} in epoch {}",
+                wallet_hash, epoch
+            )));
+        }
+        if let Some(storage) = &self.storage {
+            storage.put(STORAGE_NAMESPACE, &storage_key(epoch, &request_hash, wallet_hash), &[])?;
+        }
+        self.seen.entry(epoch).or_default().insert((request_hash, wallet_hash.to_string()));
+
+        let horizon = self.horizon();
+        let expired: Vec<u64> = self.seen.range(..horizon).map(|(e, _)| *e).collect();
+        for e in expired {
+            if let Some(entries) = self.seen.remove(&e) {
+                if let Some(storage) = &self.storage {
+                    for (request_hash, wallet_hash) in &entries {
+                        storage.remove(STORAGE_NAMESPACE, &storage_key(e, request_hash, wallet_hash))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `check_and_record` keyed by the request's canonical hash
+    pub fn check_request(&mut self, request: &ThresholdVerificationRequest, wallet_hash: &str, epoch: u64) -> Result<()> {
+        self.check_and_record(request.canonical_hash(), wallet_hash, epoch)
+    }
+
+    /// Submissions currently tracked
+    pub fn len(&self) -> usize {
+        self.seen.values().map(HashSet::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+/// Big-endian epoch, request hash, then wallet hash, so keys sort by epoch
+fn storage_key(epoch: u64, request_hash: &[u8; 32], wallet_hash: &str) -> Vec<u8> {
+    [&epoch.to_be_bytes()[..], request_hash, wallet_hash.as_bytes()].concat()
+}
+
+fn parse_key(key: &[u8]) -> Result<(u64, [u8; 32], String)> {
+    if key.len() < 40 {
+        return Err(ZKPError::StorageError(format!("Stored submission key has {} bytes", key.len())));
+    }
+    let epoch = u64::from_be_bytes(key[..8].try_into().expect("8-byte epoch"));
+    let request_hash = key[8..40].try_into().expect("32-byte request hash");
+    let wallet_hash = String::from_utf8(key[40..].to_vec())
+        .map_err(|e| ZKPError::StorageError(format!("Stored wallet hash is not UTF-8: {}", e)))?;
+    Ok((epoch, request_hash, wallet_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RepIDCategory;
+
+    #[test]
+    fn test_replays_rejected_within_window() {
+        let request = ThresholdVerificationRequest::builder()
+            .with_threshold(100)
+            .with_category(RepIDCategory::Governance)
+            .with_time_window(86400)
+            .build()
+            .unwrap();
+        let mut guard = ReplayGuard::new(2);
+
+        guard.check_request(&request, "wallet-a", 10).unwrap();
+        guard.check_request(&request, "wallet-b", 10).unwrap();
+        assert!(guard.is_replay(&request.canonical_hash(), "wallet-a", 10));
+        assert!(matches!(guard.check_request(&request, "wallet-a", 10), Err(ZKPError::PolicyViolation(_))));
+
+        // A new epoch is a fresh submission; epochs beyond the window are pruned and refused
+        guard.check_request(&request, "wallet-a", 13).unwrap();
+        assert_eq!(guard.len(), 1);
+        assert!(guard.check_request(&request, "wallet-a", 10).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+//! Background Re-proving
+//!
+//! Services that must always hold a fresh proof would otherwise run their own
+//! cron job against the registry. `ReprovingScheduler` watches a
+//! `ProofRegistry` for proofs nearing their `valid_until`, asks a `Reprover`
+//! for a replacement at the current epoch and stores it, reporting each
+//! outcome to a `ReprovingObserver`. Failed jobs are retried on later passes
+//! up to `max_attempts`; a renewed proof is not queued again while its
+//! predecessor waits for compaction.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::registry::{ExpiringProof, ProofId, ProofRegistry};
+use crate::{RepIDProof, Result};
+
+/// Attempts per expiring proof before the scheduler gives up on it
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Outcome of one re-proving job
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReprovingEvent {
+    /// A replacement was proven and stored
+    Renewed { previous: ProofId, replacement: ProofId, operation_type: String, epoch: u64, valid_until: Option<u64> },
+    /// The reprover or the registry failed; `gave_up` once attempts are exhausted
+    Failed { previous: ProofId, operation_type: String, epoch: u64, attempt: u32, gave_up: bool, error: String },
+}
+
+/// Receiver of re-proving outcomes
+///
+/// Called on the scheduler's thread; implementations should hand the event
+/// off rather than block.
+pub trait ReprovingObserver: Send + Sync {
+    fn on_event(&self, event: &ReprovingEvent);
+}
+
+impl<C: Fn(&ReprovingEvent) + Send + Sync> ReprovingObserver for C {
+    fn on_event(&self, event: &ReprovingEvent) {
+        self(event)
+    }
+}
+
+/// Stream events over a channel; a dropped receiver just stops the stream
+impl ReprovingObserver for Sender<ReprovingEvent> {
+    fn on_event(&self, event: &ReprovingEvent) {
+        let _ = self.send(event.clone());
+    }
+}
+
+/// Observer streaming into a new channel, with the receiving end
+pub fn channel() -> (Arc<dyn ReprovingObserver>, std::sync::mpsc::Receiver<ReprovingEvent>) {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    (Arc::new(sender), receiver)
+}
+
+/// Proves the replacement of an expiring proof at a fresh epoch
+///
+/// The expiring envelope carries the operation type and wallet label the
+/// caller needs to look up the witness and rerun the matching `prove_*`.
+pub trait Reprover: Send {
+    fn reprove(&mut self, expiring: &ExpiringProof, epoch: u64) -> Result<RepIDProof>;
+}
+
+impl<C: FnMut(&ExpiringProof, u64) -> Result<RepIDProof> + Send> Reprover for C {
+    fn reprove(&mut self, expiring: &ExpiringProof, epoch: u64) -> Result<RepIDProof> {
+        self(expiring, epoch)
+    }
+}
+
+/// Renews registry proofs before their retention runs out
+pub struct ReprovingScheduler {
+    registry: Arc<ProofRegistry>,
+    /// Seconds before `valid_until` a proof is re-proven
+    lead_time: u64,
+    max_attempts: u32,
+    observer: Option<Arc<dyn ReprovingObserver>>,
+    /// Expiring proofs already replaced, by replacement
+    renewed: BTreeMap<ProofId, ProofId>,
+    /// Failed attempts per expiring proof
+    attempts: BTreeMap<ProofId, u32>,
+}
+
+impl std::fmt::Debug for ReprovingScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReprovingScheduler")
+            .field("lead_time", &self.lead_time)
+            .field("max_attempts", &self.max_attempts)
+            .field("renewed", &self.renewed.len())
+            .field("pending_retries", &self.attempts.len())
+            .finish()
+    }
+}
+
+impl ReprovingScheduler {
+    pub fn new(registry: Arc<ProofRegistry>, lead_time: u64) -> Self {
+        Self {
+            registry,
+            lead_time,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            observer: None,
+            renewed: BTreeMap::new(),
+            attempts: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_observer(mut self, observer: Arc<dyn ReprovingObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Re-prove every proof expiring within the lead time, returning the events of this pass
+    pub fn run_once(&mut self, reprover: &mut dyn Reprover, current_epoch: u64) -> Vec<ReprovingEvent> {
+        let expiring = self.registry.expiring(self.lead_time);
+        // Forget proofs compaction has since removed
+        self.renewed.retain(|id, _| expiring.iter().any(|proof| proof.id == *id));
+        self.attempts.retain(|id, _| expiring.iter().any(|proof| proof.id == *id));
+
+        let mut events = Vec::new();
+        for proof in &expiring {
+            let attempt = self.attempts.get(&proof.id).copied().unwrap_or(0) + 1;
+            if self.renewed.contains_key(&proof.id) || attempt > self.max_attempts {
+                continue;
+            }
+            let renewal =
+                reprover.reprove(proof, current_epoch).and_then(|replacement| self.registry.insert(replacement, current_epoch));
+            let event = match renewal {
+                Ok(replacement) => {
+                    self.attempts.remove(&proof.id);
+                    self.renewed.insert(proof.id, replacement);
+                    ReprovingEvent::Renewed {
+                        previous: proof.id,
+                        replacement,
+                        operation_type: proof.proof.metadata.operation_type.clone(),
+                        epoch: current_epoch,
+                        valid_until: self.registry.valid_until(&replacement),
+                    }
+                }
+                Err(e) => {
+                    self.attempts.insert(proof.id, attempt);
+                    ReprovingEvent::Failed {
+                        previous: proof.id,
+                        operation_type: proof.proof.metadata.operation_type.clone(),
+                        epoch: current_epoch,
+                        attempt,
+                        gave_up: attempt == self.max_attempts,
+                        error: e.to_string(),
+                    }
+                }
+            };
+            if let Some(observer) = &self.observer {
+                observer.on_event(&event);
+            }
+            events.push(event);
+        }
+        events
+    }
+
+    /// Run a pass every `interval` on a background thread until the handle is stopped or dropped
+    pub fn spawn<R, E>(mut self, interval: Duration, mut reprover: R, current_epoch: E) -> ReprovingHandle
+    where
+        R: Reprover + 'static,
+        E: Fn() -> u64 + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let thread = std::thread::spawn(move || {
+            while !stopped.load(Ordering::SeqCst) {
+                for event in self.run_once(&mut reprover, current_epoch()) {
+                    if let ReprovingEvent::Failed { gave_up: true, error, .. } = event {
+                        tracing::warn!("Re-proving gave up on an expiring proof: {}", error);
+                    }
+                }
+                std::thread::park_timeout(interval);
+            }
+        });
+        ReprovingHandle { stop, thread: Some(thread) }
+    }
+}
+
+/// Background scheduler started by `ReprovingScheduler::spawn`
+#[derive(Debug)]
+pub struct ReprovingHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ReprovingHandle {
+    /// Stop scheduling and wait for the current pass to finish
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ReprovingHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::registry::RetentionPolicy;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest, ZKPError};
+
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn test_expiring_proofs_are_renewed_once() {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let mut prove = |score| {
+            zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, score)], "0xtest").unwrap().proof
+        };
+        let (stale, spare, doomed) = (prove(60), prove(70), prove(80));
+
+        let clock = Arc::new(FixedClock::new(1_000 * DAY));
+        let registry = Arc::new(ProofRegistry::new(RetentionPolicy::keep_all().with_max_age(30 * DAY), clock.clone()));
+        let stale_id = registry.insert(stale, 1).unwrap();
+        let doomed_id = registry.insert(doomed, 1).unwrap();
+        let (observer, events) = channel();
+        let mut scheduler = ReprovingScheduler::new(registry.clone(), 5 * DAY).with_max_attempts(2).with_observer(observer);
+
+        // The doomed proof's witness is gone; the other is renewed at the fresh epoch
+        let mut spares = vec![spare];
+        let mut reprover = move |expiring: &ExpiringProof, _epoch: u64| {
+            if expiring.id == doomed_id {
+                return Err(ZKPError::InvalidInput(format!("no witness for {}", expiring.proof.metadata.wallet_hash)));
+            }
+            spares.pop().ok_or_else(|| ZKPError::InvalidInput("reproved twice".to_string()))
+        };
+        assert!(scheduler.run_once(&mut reprover, 1).is_empty());
+
+        clock.advance(26 * DAY);
+        let pass = scheduler.run_once(&mut reprover, 2);
+        assert_eq!(pass.len(), 2);
+        let renewed = pass.iter().find_map(|event| match event {
+            ReprovingEvent::Renewed { previous, replacement, epoch, valid_until, .. } if *previous == stale_id => {
+                Some((*replacement, *epoch, *valid_until))
+            }
+            _ => None,
+        });
+        let (replacement, epoch, valid_until) = renewed.unwrap();
+        assert_eq!((epoch, valid_until), (2, Some(1_056 * DAY)));
+        assert!(registry.get(&replacement).is_some());
+        assert!(pass.iter().any(|event| matches!(
+            event,
+            ReprovingEvent::Failed { previous, epoch: 2, attempt: 1, gave_up: false, .. } if *previous == doomed_id
+        )));
+
+        // The renewed proof is not queued again; the failing one is retried until attempts run out
+        let retry = scheduler.run_once(&mut reprover, 2);
+        assert!(matches!(&retry[..], [ReprovingEvent::Failed { attempt: 2, gave_up: true, .. }]));
+        assert!(scheduler.run_once(&mut reprover, 2).is_empty());
+        assert_eq!(events.try_iter().count(), 3);
+
+        // Once compaction drops the originals, the background scheduler has nothing left to renew
+        clock.advance(5 * DAY);
+        assert_eq!(registry.compact(2).unwrap(), 2);
+        let handle = scheduler.spawn(Duration::from_millis(10), reprover, || 2);
+        std::thread::sleep(Duration::from_millis(30));
+        handle.stop();
+        assert_eq!(registry.len(), 1);
+        assert!(events.try_iter().next().is_none());
+    }
+}
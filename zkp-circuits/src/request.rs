@@ -0,0 +1,283 @@
+//! Request Validation
+//!
+//! Builder and canonical hashing for threshold verification requests, rejecting
+//! malformed thresholds, category sets, time windows and decay parameters up front
+
+use crate::custom_stark::BabyBearField;
+use crate::fixed_point::Q16;
+use crate::{DecayParameters, RepIDCategory, ThresholdVerificationRequest, ZKPError};
+
+/// Largest threshold the signed in-circuit comparison can represent
+pub const MAX_THRESHOLD: u32 = ((BabyBearField::MODULUS - 1) / 2) as u32;
+/// Longest accepted scoring window (five years)
+pub const MAX_TIME_WINDOW: u64 = 5 * 365 * 86400;
+/// Latest evaluation instant a single field element can carry
+pub const MAX_AS_OF: u64 = BabyBearField::MODULUS - 1;
+
+/// Reason a threshold verification request was rejected
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RequestValidationError {
+    #[error("threshold must be between 1 and {max}, got {threshold}")]
+    ThresholdOutOfRange { threshold: u32, max: u32 },
+    #[error("at least one category is required")]
+    NoCategories,
+    #[error("category {0:?} is listed more than once")]
+    DuplicateCategory(RepIDCategory),
+    #[error("time window must be between 1 and {max} seconds, got {time_window}")]
+    TimeWindowOutOfRange { time_window: u64, max: u64 },
+    #[error("as_of must be at most {max}, got {as_of}")]
+    AsOfOutOfRange { as_of: u64, max: u64 },
+    #[error("decay rate for {category:?} is {rate} basis points, maximum is 10000")]
+    DecayRateOutOfRange { category: Option<RepIDCategory>, rate: u16 },
+    #[error("multiplicative factor must be non-negative, got {0:?}")]
+    InvalidMultiplicativeFactor(Q16),
+    #[error("decay minimum {min_threshold} is not below the threshold {threshold}")]
+    DecayFloorAboveThreshold { min_threshold: u32, threshold: u32 },
+    #[error("decay override for {0:?}, which is not a requested category")]
+    UnrequestedDecayCategory(RepIDCategory),
+    #[error("{category:?} score {score} is above the configured maximum {max}")]
+    ScoreAboveCap { category: RepIDCategory, score: u32, max: u32 },
+}
+
+impl From<RequestValidationError> for ZKPError {
+    fn from(error: RequestValidationError) -> Self {
+        ZKPError::InvalidInput(error.to_string())
+    }
+}
+
+impl ThresholdVerificationRequest {
+    pub fn builder() -> ThresholdVerificationRequestBuilder {
+        ThresholdVerificationRequestBuilder::default()
+    }
+
+    /// Blake3 hash of a fixed binary encoding, independent of serde format and category order
+    pub fn canonical_hash(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"repid/threshold-request/v2");
+        hasher.update(&self.threshold.to_le_bytes());
+        hasher.update(&self.time_window.to_le_bytes());
+        hasher.update(&self.as_of.to_le_bytes());
+
+        let mut tags: Vec<u64> = self.categories.iter().map(|c| c.field_tag().0).collect();
+        tags.sort_unstable();
+        tags.dedup();
+        hasher.update(&(tags.len() as u64).to_le_bytes());
+        for tag in tags {
+            hasher.update(&tag.to_le_bytes());
+        }
+
+        match &self.decay_params {
+            None => {
+                hasher.update(&[0]);
+            }
+            Some(decay) => {
+                hasher.update(&[1]);
+                hasher.update(&decay.base_decay_rate.to_le_bytes());
+                hasher.update(&decay.multiplicative_factor.raw().to_le_bytes());
+                hasher.update(&decay.min_threshold.to_le_bytes());
+
+                let mut overrides: Vec<_> = decay.category_decay.iter().map(|d| (d.category.field_tag().0, d)).collect();
+                overrides.sort_by_key(|(tag, _)| *tag);
+                hasher.update(&(overrides.len() as u64).to_le_bytes());
+                for (tag, d) in overrides {
+                    hasher.update(&tag.to_le_bytes());
+                    hasher.update(&d.decay_rate.to_le_bytes());
+                    match d.half_life_secs {
+                        None => hasher.update(&[0]),
+                        Some(half_life) => hasher.update(&[1]).update(&half_life.to_le_bytes()),
+                    };
+                    hasher.update(&d.floor.to_le_bytes());
+                }
+            }
+        }
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Check the request against the limits the threshold circuits assume
+    pub fn validate(&self) -> std::result::Result<(), RequestValidationError> {
+        if self.threshold == 0 || self.threshold > MAX_THRESHOLD {
+            return Err(RequestValidationError::ThresholdOutOfRange {
+                threshold: self.threshold,
+                max: MAX_THRESHOLD,
+            });
+        }
+        if self.categories.is_empty() {
+            return Err(RequestValidationError::NoCategories);
+        }
+        for (i, category) in self.categories.iter().enumerate() {
+            if self.categories[..i].contains(category) {
+                return Err(RequestValidationError::DuplicateCategory(category.clone()));
+            }
+        }
+        if self.time_window == 0 || self.time_window > MAX_TIME_WINDOW {
+            return Err(RequestValidationError::TimeWindowOutOfRange {
+                time_window: self.time_window,
+                max: MAX_TIME_WINDOW,
+            });
+        }
+        if self.as_of > MAX_AS_OF {
+            return Err(RequestValidationError::AsOfOutOfRange {
+                as_of: self.as_of,
+                max: MAX_AS_OF,
+            });
+        }
+        if let Some(decay) = &self.decay_params {
+            self.validate_decay(decay)?;
+        }
+        Ok(())
+    }
+
+    fn validate_decay(&self, decay: &DecayParameters) -> std::result::Result<(), RequestValidationError> {
+        if decay.base_decay_rate > 10_000 {
+            return Err(RequestValidationError::DecayRateOutOfRange { category: None, rate: decay.base_decay_rate });
+        }
+        if decay.multiplicative_factor < Q16::ZERO {
+            return Err(RequestValidationError::InvalidMultiplicativeFactor(decay.multiplicative_factor));
+        }
+        if decay.min_threshold >= self.threshold {
+            return Err(RequestValidationError::DecayFloorAboveThreshold {
+                min_threshold: decay.min_threshold,
+                threshold: self.threshold,
+            });
+        }
+        for category_decay in &decay.category_decay {
+            if !self.categories.contains(&category_decay.category) {
+                return Err(RequestValidationError::UnrequestedDecayCategory(category_decay.category.clone()));
+            }
+            if category_decay.decay_rate > 10_000 {
+                return Err(RequestValidationError::DecayRateOutOfRange {
+                    category: Some(category_decay.category.clone()),
+                    rate: category_decay.decay_rate,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validating builder for `ThresholdVerificationRequest`
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdVerificationRequestBuilder {
+    threshold: u32,
+    categories: Vec<RepIDCategory>,
+    time_window: u64,
+    as_of: u64,
+    decay_params: Option<DecayParameters>,
+}
+
+impl ThresholdVerificationRequestBuilder {
+    pub fn with_threshold(mut self, threshold: u32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_category(mut self, category: RepIDCategory) -> Self {
+        self.categories.push(category);
+        self
+    }
+
+    pub fn with_categories(mut self, categories: impl IntoIterator<Item = RepIDCategory>) -> Self {
+        self.categories.extend(categories);
+        self
+    }
+
+    pub fn with_time_window(mut self, time_window: u64) -> Self {
+        self.time_window = time_window;
+        self
+    }
+
+    /// Evaluate scores as of this Unix time instead of the prover's clock
+    pub fn with_as_of(mut self, as_of: u64) -> Self {
+        self.as_of = as_of;
+        self
+    }
+
+    pub fn with_decay(mut self, decay_params: DecayParameters) -> Self {
+        self.decay_params = Some(decay_params);
+        self
+    }
+
+    pub fn build(self) -> std::result::Result<ThresholdVerificationRequest, RequestValidationError> {
+        let request = ThresholdVerificationRequest {
+            threshold: self.threshold,
+            categories: self.categories,
+            time_window: self.time_window,
+            as_of: self.as_of,
+            decay_params: self.decay_params,
+        };
+        request.validate()?;
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decay::CategoryDecay;
+
+    #[test]
+    fn test_builder_rejects_bad_requests() {
+        let base = || {
+            ThresholdVerificationRequest::builder()
+                .with_threshold(100)
+                .with_categories([RepIDCategory::Governance, RepIDCategory::Technical])
+                .with_time_window(86400)
+        };
+        let decay = DecayParameters {
+            base_decay_rate: 100,
+            multiplicative_factor: Q16::ONE,
+            min_threshold: 10,
+            category_decay: Vec::new(),
+        };
+
+        let request = base().with_decay(decay.clone()).build().unwrap();
+        assert_eq!(request.categories.len(), 2);
+
+        assert_eq!(
+            base().with_threshold(0).build().unwrap_err(),
+            RequestValidationError::ThresholdOutOfRange { threshold: 0, max: MAX_THRESHOLD }
+        );
+        assert_eq!(
+            ThresholdVerificationRequest::builder().with_threshold(1).with_time_window(1).build().unwrap_err(),
+            RequestValidationError::NoCategories
+        );
+        assert_eq!(
+            base().with_category(RepIDCategory::Governance).build().unwrap_err(),
+            RequestValidationError::DuplicateCategory(RepIDCategory::Governance)
+        );
+        assert!(matches!(
+            base().with_time_window(MAX_TIME_WINDOW + 1).build(),
+            Err(RequestValidationError::TimeWindowOutOfRange { .. })
+        ));
+        assert!(matches!(
+            base().with_decay(decay.clone().with_category_decay(CategoryDecay::linear(RepIDCategory::DeFi, 50))).build(),
+            Err(RequestValidationError::UnrequestedDecayCategory(RepIDCategory::DeFi))
+        ));
+
+        let error: ZKPError = base().with_threshold(5).with_decay(decay).build().unwrap_err().into();
+        assert!(matches!(error, ZKPError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_canonical_hash_is_format_and_order_independent() {
+        let request = ThresholdVerificationRequest::builder()
+            .with_threshold(100)
+            .with_categories([RepIDCategory::Governance, RepIDCategory::Custom("Rust".to_string())])
+            .with_time_window(86400)
+            .build()
+            .unwrap();
+
+        let json: ThresholdVerificationRequest = serde_json::from_str(&serde_json::to_string(&request).unwrap()).unwrap();
+        let binary: ThresholdVerificationRequest = crate::wire::from_bytes(&crate::wire::to_bytes(&request).unwrap()).unwrap();
+        assert_eq!(json.canonical_hash(), request.canonical_hash());
+        assert_eq!(binary.canonical_hash(), request.canonical_hash());
+
+        let mut reordered = request.clone();
+        reordered.categories.reverse();
+        assert_eq!(reordered.canonical_hash(), request.canonical_hash());
+
+        let mut changed = request.clone();
+        changed.threshold += 1;
+        assert_ne!(changed.canonical_hash(), request.canonical_hash());
+    }
+}
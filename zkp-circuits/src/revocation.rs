@@ -0,0 +1,163 @@
+//! Attestation Revocation
+//!
+//! Issuer-published lists of revoked attestations, and threshold proofs that
+//! none of the attestations backing a score appear in the issuer's list. Lets
+//! an issuer withdraw individual attestations it finds fraudulent without
+//! revoking the identities they were issued to.
+
+use serde::{Deserialize, Serialize};
+
+use crate::freshness::AttestedScore;
+use crate::poseidon2;
+use crate::sparse_merkle::{key_from_field, SmtWitness, SparseMerkleTree};
+use crate::{RepIDProof, Result, ZKPError, F};
+
+/// Operation type of threshold proofs over unrevoked attestations
+pub const UNREVOKED_THRESHOLD_OPERATION: &str = "unrevoked_threshold_verification";
+
+/// Attestation an issuer can revoke individually
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocableAttestation {
+    pub attested: AttestedScore,
+    /// Issuer-assigned serial, unique among the issuer's attestations
+    pub serial: u64,
+}
+
+impl RevocableAttestation {
+    /// Position of the score within `to_field_elements`
+    pub const SCORE_POSITION: usize = 2;
+
+    pub fn new(attested: AttestedScore, serial: u64) -> Self {
+        Self { attested, serial }
+    }
+
+    /// Hash input of the attestation id: [serial, category tag, score, issued_at]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        vec![
+            F::new(self.serial),
+            self.attested.category.field_tag(),
+            F::from_u32(self.attested.score),
+            F::new(self.attested.issued_at),
+        ]
+    }
+
+    /// Identifier the issuer lists when revoking this attestation
+    pub fn id(&self) -> F {
+        poseidon2::hash_elements(&self.to_field_elements())
+    }
+}
+
+/// Attestations an issuer has revoked
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationList {
+    pub issuer: String,
+    /// Revoked attestation ids, in publication order
+    pub revoked: Vec<F>,
+}
+
+impl RevocationList {
+    pub fn new(issuer: &str) -> Self {
+        Self {
+            issuer: issuer.to_string(),
+            revoked: Vec::new(),
+        }
+    }
+
+    /// Add an attestation to the list; revoking it twice has no effect
+    pub fn revoke(&mut self, attestation: &RevocableAttestation) {
+        let id = attestation.id();
+        if !self.revoked.contains(&id) {
+            self.revoked.push(id);
+        }
+    }
+
+    pub fn is_revoked(&self, attestation: &RevocableAttestation) -> bool {
+        self.revoked.contains(&attestation.id())
+    }
+
+    /// Poseidon2 digest of the revoked ids, published by the issuer
+    pub fn root(&self) -> F {
+        poseidon2::hash_elements(&self.revoked)
+    }
+
+    /// Revoked ids as a sparse Merkle tree, for issuers publishing a tree root
+    pub fn sparse_tree(&self) -> SparseMerkleTree {
+        let mut tree = SparseMerkleTree::new();
+        for &id in &self.revoked {
+            tree.insert(key_from_field(id));
+        }
+        tree
+    }
+
+    /// Path showing `attestation` is absent from `sparse_tree`
+    pub fn non_revocation_witness(&self, attestation: &RevocableAttestation) -> Result<SmtWitness> {
+        self.sparse_tree().non_membership_witness(&key_from_field(attestation.id()))
+    }
+}
+
+/// Read the revocation root a verified proof was checked against
+pub fn proof_revocation_root(proof: &RepIDProof) -> Result<F> {
+    if proof.metadata.operation_type != UNREVOKED_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no revocation root",
+            proof.metadata.operation_type
+        )));
+    }
+    proof.public_inputs.get(2).copied()
+        .ok_or_else(|| ZKPError::MalformedProof("Unrevoked threshold proof needs a revocation root input".to_string()))
+}
+
+/// Check a proof was made against the issuer's current revocation list
+///
+/// A proof made before the latest revocations carries an older root and is rejected.
+pub fn check_revocation_root(proof: &RepIDProof, list: &RevocationList) -> Result<()> {
+    if proof_revocation_root(proof)? != list.root() {
+        return Err(ZKPError::VerificationError(format!(
+            "proof was not made against the current revocation list of {}",
+            list.issuer
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_revoked_attestation_cannot_back_a_proof() {
+        let attestation = |category, score, serial| {
+            RevocableAttestation::new(AttestedScore { category, score, issued_at: 1_000 }, serial)
+        };
+        let attestations = [
+            attestation(RepIDCategory::Technical, 60, 1),
+            attestation(RepIDCategory::Governance, 50, 2),
+        ];
+        let mut list = RevocationList::new("did:example:issuer");
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 100,
+            categories: vec![RepIDCategory::Technical, RepIDCategory::Governance],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        assert!(zkp_system.prove_unrevoked_threshold_verification(&request, &attestations, &list, "0xtest").is_ok());
+
+        list.revoke(&attestation(RepIDCategory::Technical, 90, 7));
+        let result = zkp_system.prove_unrevoked_threshold_verification(&request, &attestations, &list, "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+        assert!(check_revocation_root(&result.proof, &list).is_ok());
+
+        // Once the issuer revokes a backing attestation, old proofs fail the root check and new ones can't be made
+        list.revoke(&attestations[1]);
+        assert!(list.is_revoked(&attestations[1]));
+        assert!(check_revocation_root(&result.proof, &list).is_err());
+        let tree_root = list.sparse_tree().root();
+        assert!(list.non_revocation_witness(&attestations[0]).unwrap().verify_non_membership(tree_root));
+        assert!(list.non_revocation_witness(&attestations[1]).is_err());
+        assert!(zkp_system.prove_unrevoked_threshold_verification(&request, &attestations, &list, "0xtest").is_err());
+    }
+}
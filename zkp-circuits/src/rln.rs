@@ -0,0 +1,268 @@
+//! Rate-Limited Nullifiers
+//!
+//! RLN-style signalling for anonymous posting gated by RepID: a member of an
+//! identity set may send up to `k` signals per epoch without revealing which
+//! member sent them. Each signal carries a nullifier and one point on a line
+//! whose intercept is the sender's identity secret.
+//!
+//! The circuit range-checks the message index below `k`, so an identity
+//! sending more than `k` signals in one epoch must reuse an index and with it
+//! a nullifier. Two signals under one nullifier are two points on the same
+//! line, from which `SlashingEvidence` recovers the secret. Verifiers keep a
+//! `NullifierLog` per namespace to spot that.
+
+use std::collections::btree_map::{BTreeMap, Entry};
+
+use serde::{Deserialize, Serialize};
+
+use crate::linkage::IdentitySecret;
+use crate::poseidon2;
+use crate::{RepIDProof, Result, ZKPError, F};
+
+/// Operation type of rate-limited signal proofs
+pub const RATE_LIMITED_OPERATION: &str = "rate_limited_signal";
+/// Bits the message index and its gap below the limit are decomposed into
+pub const MESSAGE_INDEX_BITS: usize = 16;
+/// Largest per-epoch message limit
+pub const MAX_MESSAGE_LIMIT: u32 = 1 << MESSAGE_INDEX_BITS;
+
+/// Up to `message_limit` signals per identity in one epoch of a namespace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Application namespace, e.g. a tenant's `nullifier_namespace`
+    pub namespace: String,
+    pub epoch: u64,
+    pub message_limit: u32,
+}
+
+impl RateLimit {
+    pub fn new(namespace: impl Into<String>, epoch: u64, message_limit: u32) -> Result<Self> {
+        if message_limit == 0 || message_limit > MAX_MESSAGE_LIMIT {
+            return Err(ZKPError::InvalidInput(format!(
+                "Message limit must be between 1 and {}, got {}",
+                MAX_MESSAGE_LIMIT, message_limit
+            )));
+        }
+        Ok(Self { namespace: namespace.into(), epoch, message_limit })
+    }
+
+    /// Poseidon2(namespace tag, epoch), shared by every signal of the epoch
+    pub fn external_nullifier(&self) -> F {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"RepID_Nullifier_Namespace");
+        hasher.update(self.namespace.as_bytes());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hasher.finalize().as_bytes()[..8]);
+        poseidon2::hash_elements(&[F::from_bytes(bytes), F::new(self.epoch)])
+    }
+}
+
+/// Field element a signal is evaluated at
+pub fn signal_hash(signal: &[u8]) -> F {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"RepID_Signal");
+    hasher.update(signal);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hasher.finalize().as_bytes()[..8]);
+    F::from_bytes(bytes)
+}
+
+/// Slope of the line of one message index: Poseidon2(secret, external nullifier, index)
+pub fn message_secret(identity: &IdentitySecret, external_nullifier: F, message_index: u32) -> F {
+    poseidon2::hash_elements(&[identity.0, external_nullifier, F::from_u32(message_index)])
+}
+
+/// Public part of one signal, as verifiers record it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RlnShare {
+    pub external_nullifier: F,
+    /// Poseidon2 of the message secret; equal for signals reusing an index
+    pub nullifier: F,
+    /// Signal hash
+    pub x: F,
+    /// Identity secret plus message secret times `x`
+    pub y: F,
+}
+
+impl RlnShare {
+    pub fn new(identity: &IdentitySecret, limit: &RateLimit, message_index: u32, signal: &[u8]) -> Self {
+        let external_nullifier = limit.external_nullifier();
+        let slope = message_secret(identity, external_nullifier, message_index);
+        let x = signal_hash(signal);
+        Self { external_nullifier, nullifier: poseidon2::hash_elements(&[slope]), x, y: identity.0 + slope * x }
+    }
+}
+
+/// Public statement of a rate-limited signal proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RlnStatement {
+    /// Root of the identity set the sender is a member of
+    pub members_root: F,
+    pub message_limit: u32,
+    pub epoch: u64,
+    pub share: RlnShare,
+}
+
+/// Read the statement a verified rate-limited signal proof was made for
+pub fn proof_statement(proof: &RepIDProof) -> Result<RlnStatement> {
+    if proof.metadata.operation_type != RATE_LIMITED_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no rate-limited signal",
+            proof.metadata.operation_type
+        )));
+    }
+    match proof.public_inputs.get(..8) {
+        Some(&[members_root, message_limit, _timestamp, epoch, external_nullifier, x, y, nullifier]) => Ok(RlnStatement {
+            members_root,
+            message_limit: message_limit.0 as u32,
+            epoch: epoch.0,
+            share: RlnShare { external_nullifier, nullifier, x, y },
+        }),
+        _ => Err(ZKPError::MalformedProof("Rate-limited signal proof needs 8 public inputs".to_string())),
+    }
+}
+
+/// Check a verified proof signals `signal` under `limit` from the set at `members_root`
+///
+/// Returns the share to record in the namespace's `NullifierLog`.
+pub fn check_signal(proof: &RepIDProof, limit: &RateLimit, signal: &[u8], members_root: F) -> Result<RlnShare> {
+    let statement = proof_statement(proof)?;
+    if statement.members_root != members_root {
+        return Err(ZKPError::VerificationError("signal proves membership of another identity set".to_string()));
+    }
+    if statement.epoch != limit.epoch || statement.share.external_nullifier != limit.external_nullifier() {
+        return Err(ZKPError::PolicyViolation(format!("signal is not for epoch {} of '{}'", limit.epoch, limit.namespace)));
+    }
+    if statement.message_limit > limit.message_limit {
+        return Err(ZKPError::PolicyViolation(format!(
+            "signal allows {} messages per epoch, limit is {}",
+            statement.message_limit, limit.message_limit
+        )));
+    }
+    if statement.share.x != signal_hash(signal) {
+        return Err(ZKPError::VerificationError("proof is for a different signal".to_string()));
+    }
+    Ok(statement.share)
+}
+
+/// Two signals reusing one nullifier, which reveal the sender's secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlashingEvidence {
+    pub first: RlnShare,
+    pub second: RlnShare,
+}
+
+impl SlashingEvidence {
+    /// Intercept of the line through both shares
+    pub fn recover_secret(&self) -> Result<IdentitySecret> {
+        let (first, second) = (&self.first, &self.second);
+        if first.nullifier != second.nullifier || first.external_nullifier != second.external_nullifier {
+            return Err(ZKPError::InvalidInput("Shares do not reuse one nullifier".to_string()));
+        }
+        let slope = (second.x - first.x)
+            .inverse()
+            .map(|inverse| (second.y - first.y) * inverse)
+            .ok_or_else(|| ZKPError::InvalidInput("Shares are for the same signal".to_string()))?;
+        Ok(IdentitySecret(first.y - slope * first.x))
+    }
+
+    /// Whether the evidence convicts the identity behind `identity_commitment`
+    pub fn convicts(&self, identity_commitment: F) -> bool {
+        self.recover_secret().is_ok_and(|secret| secret.commitment() == identity_commitment)
+    }
+}
+
+/// What recording a share found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalOutcome {
+    /// First signal under its nullifier
+    Accepted,
+    /// The same share again; the signal should be dropped
+    Duplicate,
+    /// A second signal under the nullifier: the sender exceeded the limit
+    Slashable(SlashingEvidence),
+}
+
+/// Shares seen in one namespace, by external nullifier and nullifier
+#[derive(Debug, Default)]
+pub struct NullifierLog {
+    shares: BTreeMap<(u64, u64), RlnShare>,
+}
+
+impl NullifierLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a checked share
+    pub fn record(&mut self, share: RlnShare) -> SignalOutcome {
+        match self.shares.entry((share.external_nullifier.0, share.nullifier.0)) {
+            Entry::Vacant(slot) => {
+                slot.insert(share);
+                SignalOutcome::Accepted
+            }
+            Entry::Occupied(seen) if seen.get().x == share.x => SignalOutcome::Duplicate,
+            Entry::Occupied(seen) => SignalOutcome::Slashable(SlashingEvidence { first: *seen.get(), second: share }),
+        }
+    }
+
+    /// Drop the shares of a finished epoch
+    pub fn forget(&mut self, external_nullifier: F) {
+        self.shares.retain(|(epoch, _), _| *epoch != external_nullifier.0);
+    }
+
+    pub fn len(&self) -> usize {
+        self.shares.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shares.is_empty()
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::sparse_merkle::{key_from_field, SparseMerkleTree};
+    use crate::{RepIDZKPSystem, SecurityLevel};
+
+    #[test]
+    fn test_exceeding_the_limit_reveals_the_secret() {
+        let member = IdentitySecret::derive(b"poster");
+        let mut members = SparseMerkleTree::new();
+        for identity in [member, IdentitySecret::derive(b"other")] {
+            members.insert(key_from_field(identity.commitment()));
+        }
+        let witness = members.membership_witness(&key_from_field(member.commitment())).unwrap();
+        let limit = RateLimit::new("forum", 7, 2).unwrap();
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let proof = zkp_system.prove_rate_limited_signal(&member, &witness, &limit, 0, b"hello").unwrap();
+        assert!(zkp_system.verify_proof(&proof, None).unwrap());
+        let share = check_signal(&proof, &limit, b"hello", members.root()).unwrap();
+        assert_eq!(share, RlnShare::new(&member, &limit, 0, b"hello"));
+        assert!(check_signal(&proof, &limit, b"other", members.root()).is_err());
+        assert!(check_signal(&proof, &RateLimit::new("forum", 8, 2).unwrap(), b"hello", members.root()).is_err());
+
+        // Indexes at or past the limit cannot be proven
+        assert!(matches!(
+            zkp_system.prove_rate_limited_signal(&member, &witness, &limit, 2, b"third"),
+            Err(ZKPError::InvalidInput(_))
+        ));
+
+        // Two signals per epoch are fine; a third must reuse an index and is caught
+        let mut log = NullifierLog::new();
+        assert_eq!(log.record(share), SignalOutcome::Accepted);
+        assert_eq!(log.record(share), SignalOutcome::Duplicate);
+        assert_eq!(log.record(RlnShare::new(&member, &limit, 1, b"second")), SignalOutcome::Accepted);
+        let SignalOutcome::Slashable(evidence) = log.record(RlnShare::new(&member, &limit, 0, b"third")) else {
+            panic!("a reused index must be slashable");
+        };
+        assert_eq!(evidence.recover_secret().unwrap(), member);
+        assert!(evidence.convicts(member.commitment()));
+        assert!(!evidence.convicts(IdentitySecret::derive(b"other").commitment()));
+
+        log.forget(limit.external_nullifier());
+        assert!(log.is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+//! Category Saturation
+//!
+//! Per-category contribution caps and saturation curves, evaluated in integer
+//! fixed point so the scorer and the circuit agree on every capped value
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::fixed_point::Q16;
+use crate::poseidon2;
+use crate::{RepIDCategory, F};
+
+/// Q16.16 one
+const ONE_Q16: u64 = Q16::ONE.0 as u64;
+/// e^-1 in Q16.16
+pub(crate) const E_INV_Q16: u64 = 24109;
+/// Whole part of x from which e^-x rounds to zero
+pub(crate) const EXP_NEG_CUTOFF: u64 = 16;
+/// Taylor terms of e^-f summed for the fractional part
+pub(crate) const TAYLOR_TERMS: u64 = 8;
+
+/// Shape of a category's approach to its cap
+///
+/// Readable formats tag the curve by `kind`; the wire encoding, which cannot
+/// read internally tagged enums, carries its `(id, parameter)` pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaturationCurve {
+    /// Linear up to the cap, flat after
+    HardCap,
+    /// floor(sqrt(score * cap)), reaching the cap at score = cap
+    Sqrt,
+    /// cap * (2 * sigmoid(score / scale) - 1), half the cap at score ~ 1.1 * scale
+    Logistic { scale: u32 },
+}
+
+/// Readable form of `SaturationCurve`
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "SaturationCurve", rename_all = "snake_case", tag = "kind")]
+enum TaggedCurve {
+    HardCap,
+    Sqrt,
+    Logistic { scale: u32 },
+}
+
+impl Serialize for SaturationCurve {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            TaggedCurve::serialize(self, serializer)
+        } else {
+            (self.id(), self.parameter()).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SaturationCurve {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return TaggedCurve::deserialize(deserializer);
+        }
+        match <(u64, u64)>::deserialize(deserializer)? {
+            (0, 0) => Ok(SaturationCurve::HardCap),
+            (1, 0) => Ok(SaturationCurve::Sqrt),
+            (2, scale) => u32::try_from(scale).map(|scale| SaturationCurve::Logistic { scale }).map_err(de::Error::custom),
+            (id, parameter) => Err(de::Error::custom(format!("unknown saturation curve {} with parameter {}", id, parameter))),
+        }
+    }
+}
+
+impl SaturationCurve {
+    /// Curve identifier committed alongside its parameter
+    fn id(&self) -> u64 {
+        match self {
+            SaturationCurve::HardCap => 0,
+            SaturationCurve::Sqrt => 1,
+            SaturationCurve::Logistic { .. } => 2,
+        }
+    }
+
+    fn parameter(&self) -> u64 {
+        match self {
+            SaturationCurve::Logistic { scale } => *scale as u64,
+            _ => 0,
+        }
+    }
+}
+
+/// Maximum contribution of one category and how scores approach it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryCap {
+    pub category: RepIDCategory,
+    pub max_contribution: u32,
+    pub curve: SaturationCurve,
+}
+
+impl CategoryCap {
+    pub fn new(category: RepIDCategory, max_contribution: u32, curve: SaturationCurve) -> Self {
+        Self {
+            category,
+            max_contribution,
+            curve,
+        }
+    }
+
+    /// Saturated score, never above `max_contribution`
+    pub fn apply(&self, score: u32) -> u32 {
+        let cap = self.max_contribution as u64;
+        let saturated = match self.curve {
+            SaturationCurve::HardCap => (score as u64).min(cap),
+            SaturationCurve::Sqrt => (score as u64 * cap).isqrt(),
+            SaturationCurve::Logistic { scale } => {
+                let x = (score as u64 * ONE_Q16) / (scale.max(1) as u64);
+                let e = exp_neg_q16(x);
+                cap * (ONE_Q16 - e) / (ONE_Q16 + e)
+            }
+        };
+        saturated.min(cap) as u32
+    }
+
+    /// Commitment input: [category_tag, max_contribution, curve_id, curve_parameter]
+    pub fn to_field_elements(&self) -> Vec<F> {
+        vec![
+            self.category.field_tag(),
+            F::from_u32(self.max_contribution),
+            F::new(self.curve.id()),
+            F::new(self.curve.parameter()),
+        ]
+    }
+}
+
+/// Apply the matching cap (if any) to each score
+pub fn apply_caps(caps: &[CategoryCap], scores: &[(RepIDCategory, u32)]) -> Vec<(RepIDCategory, u32)> {
+    scores
+        .iter()
+        .map(|(category, score)| {
+            let saturated = caps.iter().find(|cap| cap.category == *category).map_or(*score, |cap| cap.apply(*score));
+            (category.clone(), saturated)
+        })
+        .collect()
+}
+
+/// Poseidon2 digest of a cap set, published so verifiers know which caps were enforced
+pub fn caps_digest(caps: &[CategoryCap]) -> F {
+    let elements: Vec<F> = caps.iter().flat_map(|cap| cap.to_field_elements()).collect();
+    poseidon2::hash_elements(&elements)
+}
+
+/// e^(-x) for x in Q16.16, result in Q16.16
+fn exp_neg_q16(x: u64) -> u64 {
+    let whole = x >> 16;
+    if whole >= EXP_NEG_CUTOFF {
+        return 0;
+    }
+
+    let mut result = ONE_Q16;
+    for _ in 0..whole {
+        result = (result * E_INV_Q16) >> 16;
+    }
+
+    // Taylor series of e^(-f) for the fractional part f < 1
+    let frac = x & (ONE_Q16 - 1);
+    let mut term = ONE_Q16;
+    let mut sum = ONE_Q16 as i64;
+    for k in 1..=TAYLOR_TERMS {
+        term = term * frac / (ONE_Q16 * k);
+        sum += if k % 2 == 1 { -(term as i64) } else { term as i64 };
+    }
+
+    (result * sum.max(0) as u64) >> 16
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_saturation_curves() {
+        let hard = CategoryCap::new(RepIDCategory::DeFi, 100, SaturationCurve::HardCap);
+        let sqrt = CategoryCap::new(RepIDCategory::DeFi, 100, SaturationCurve::Sqrt);
+        let logistic = CategoryCap::new(RepIDCategory::DeFi, 100, SaturationCurve::Logistic { scale: 50 });
+
+        assert_eq!((hard.apply(40), hard.apply(400)), (40, 100));
+        assert_eq!((sqrt.apply(25), sqrt.apply(100), sqrt.apply(10_000)), (50, 100, 100));
+        assert_eq!(logistic.apply(0), 0);
+        assert!((45..=47).contains(&logistic.apply(50)));
+        assert!(logistic.apply(1_000) >= 99 && logistic.apply(u32::MAX) <= 100);
+    }
+
+    #[test]
+    fn test_curves_round_trip_in_json_and_on_the_wire() {
+        for curve in [SaturationCurve::HardCap, SaturationCurve::Sqrt, SaturationCurve::Logistic { scale: 50 }] {
+            let json = serde_json::to_string(&curve).unwrap();
+            assert_eq!(serde_json::from_str::<SaturationCurve>(&json).unwrap(), curve);
+            let bytes = crate::wire::to_bytes(&curve).unwrap();
+            assert_eq!(crate::wire::from_bytes::<SaturationCurve>(&bytes).unwrap(), curve);
+        }
+        assert_eq!(serde_json::to_string(&SaturationCurve::Logistic { scale: 50 }).unwrap(), r#"{"kind":"logistic","scale":50}"#);
+        assert!(crate::wire::from_bytes::<SaturationCurve>(&crate::wire::to_bytes(&(3u64, 0u64)).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_caps_enforced_in_threshold_proofs() {
+        let caps = vec![CategoryCap::new(RepIDCategory::DeFi, 60, SaturationCurve::HardCap)];
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_category_caps(caps.clone());
+        let request = ThresholdVerificationRequest {
+            threshold: 100,
+            categories: vec![RepIDCategory::DeFi, RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+
+        // DeFi alone cannot carry the aggregate past the threshold
+        let dominated = [(RepIDCategory::DeFi, 500), (RepIDCategory::Community, 20)];
+        assert!(zkp_system.prove_threshold_verification(&request, &dominated, "0xtest").is_err());
+
+        let balanced = [(RepIDCategory::DeFi, 500), (RepIDCategory::Community, 45)];
+        let result = zkp_system.prove_threshold_verification(&request, &balanced, "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert_eq!(result.proof.public_inputs[2], caps_digest(&caps));
+        assert!(zkp_system.verify_proof(&result.proof, None).unwrap());
+
+        // Proofs made without the caps are rejected by a capped verifier
+        let mut uncapped = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let proof = uncapped.prove_threshold_verification(&request, &balanced, "0xtest").unwrap().proof;
+        assert!(!zkp_system.verify_proof(&proof, None).unwrap());
+    }
+}
@@ -0,0 +1,135 @@
+//! Score Deltas
+//!
+//! Proofs that a wallet's total over a set of categories grew by at least Δ
+//! from epoch E1 to a later epoch E2, for "most improved contributor" rewards
+//! that should not reveal either total. Both snapshot roots are public inputs,
+//! so verifiers match them to the published ones with `check_snapshots`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::epoch::SnapshotRoot;
+use crate::{RepIDCategory, RepIDProof, Result, ZKPError, F};
+
+/// Operation type of score delta proofs
+pub const SCORE_DELTA_OPERATION: &str = "score_delta";
+
+/// Public statement of a score delta proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreDeltaStatement {
+    /// Growth of the total over `categories`, at least
+    pub min_delta: u32,
+    /// Earlier snapshot: (epoch, root)
+    pub from: (u64, F),
+    /// Later snapshot: (epoch, root)
+    pub to: (u64, F),
+    /// Field tags of the categories opened in both epochs
+    pub categories: Vec<F>,
+}
+
+impl ScoreDeltaStatement {
+    /// Whether the proof covers exactly `categories`, in any order
+    pub fn covers(&self, categories: &[RepIDCategory]) -> bool {
+        categories.len() == self.categories.len()
+            && categories.iter().all(|category| self.categories.contains(&category.field_tag()))
+    }
+}
+
+/// Read the statement a verified score delta proof was made for
+pub fn proof_statement(proof: &RepIDProof) -> Result<ScoreDeltaStatement> {
+    if proof.metadata.operation_type != SCORE_DELTA_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no score delta statement",
+            proof.metadata.operation_type
+        )));
+    }
+    let malformed = || ZKPError::MalformedProof("Score delta proof needs delta, snapshot and category inputs".to_string());
+    let num_categories = proof.public_inputs.get(1).ok_or_else(malformed)?.0 as usize;
+    let inputs = proof.public_inputs.get(..7 + num_categories).ok_or_else(malformed)?;
+    Ok(ScoreDeltaStatement {
+        min_delta: inputs[0].0 as u32,
+        from: (inputs[3].0, inputs[4]),
+        to: (inputs[5].0, inputs[6]),
+        categories: inputs[7..].to_vec(),
+    })
+}
+
+/// Check a score delta proof was made against these published snapshot roots
+pub fn check_snapshots(proof: &RepIDProof, published: &[SnapshotRoot]) -> Result<()> {
+    let statement = proof_statement(proof)?;
+    for (epoch, root) in [statement.from, statement.to] {
+        match published.iter().find(|snapshot| snapshot.epoch == epoch) {
+            Some(snapshot) if snapshot.root == root => {}
+            Some(_) => return Err(ZKPError::VerificationError(format!("snapshot root of epoch {} does not match", epoch))),
+            None => return Err(ZKPError::PolicyViolation(format!("epoch {} has no published snapshot", epoch))),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::epoch::EpochManager;
+    use crate::{RepIDZKPSystem, SecurityLevel};
+
+    const MONTH: u64 = 30 * 86_400;
+
+    #[test]
+    fn test_improvement_proven_without_totals() {
+        let mut manager = EpochManager::new(0, MONTH).unwrap();
+        let categories = [RepIDCategory::Governance, RepIDCategory::Technical];
+        let wallets = [("0xriser", [20, 30], [45, 40]), ("0xveteran", [90, 90], [92, 90])];
+        for epoch in [0, 2] {
+            for (wallet, before, after) in wallets {
+                let scores = if epoch == 0 { before } else { after };
+                for (category, score) in categories.iter().zip(scores) {
+                    manager.update_score(wallet, category.clone(), score, epoch * MONTH + 10).unwrap();
+                }
+            }
+        }
+        manager.advance_to(3 * MONTH);
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let from = manager.epoch_scores(0, "0xriser", &categories).unwrap();
+        let to = manager.epoch_scores(2, "0xriser", &categories).unwrap();
+        let proof = zkp_system.prove_score_delta(&from, &to, 35, "0xriser").unwrap();
+        assert!(zkp_system.verify_proof(&proof, None).unwrap());
+
+        let statement = proof_statement(&proof).unwrap();
+        assert_eq!((statement.min_delta, statement.from.0, statement.to.0), (35, 0, 2));
+        assert!(statement.covers(&[RepIDCategory::Technical, RepIDCategory::Governance]));
+        assert!(!statement.covers(&[RepIDCategory::Technical]));
+        let published: Vec<SnapshotRoot> = (0..3).map(|epoch| manager.get_snapshot(epoch).unwrap()).collect();
+        assert!(check_snapshots(&proof, &published).is_ok());
+        assert!(check_snapshots(&proof, &published[1..]).is_err());
+        // Totals (50 and 85) stay out of the public inputs
+        assert!(!proof.public_inputs.iter().any(|input| input.0 == 50 || input.0 == 85));
+
+        // The leaves' wallet tag is bound to the public wallet commitment
+        let policy = crate::policy::VerifyPolicy::default();
+        let riser = zkp_system.commit_snapshot_wallet("0xriser").unwrap();
+        assert!(zkp_system.verify_proof_for_wallet(&proof, None, &riser, &policy).unwrap());
+        let veteran = zkp_system.commit_snapshot_wallet("0xveteran").unwrap();
+        assert!(!zkp_system.verify_proof_for_wallet(&proof, None, &veteran, &policy).unwrap());
+
+        // A high total is not an improvement, and growth of 35 does not clear 36
+        let veteran_from = manager.epoch_scores(0, "0xveteran", &categories).unwrap();
+        let veteran_to = manager.epoch_scores(2, "0xveteran", &categories).unwrap();
+        assert!(zkp_system.prove_score_delta(&veteran_from, &veteran_to, 35, "0xveteran").is_err());
+        assert!(zkp_system.prove_score_delta(&from, &to, 36, "0xriser").is_err());
+
+        // Both epochs must open the same categories, in epoch order, against their roots
+        let partial = manager.epoch_scores(0, "0xriser", &categories[..1]).unwrap();
+        assert!(matches!(zkp_system.prove_score_delta(&partial, &to, 10, "0xriser"), Err(ZKPError::InvalidInput(_))));
+        assert!(zkp_system.prove_score_delta(&to, &from, 1, "0xriser").is_err());
+        let repeated = [RepIDCategory::Governance, RepIDCategory::Governance];
+        let (repeated_from, repeated_to) = (
+            manager.epoch_scores(0, "0xriser", &repeated).unwrap(),
+            manager.epoch_scores(2, "0xriser", &repeated).unwrap(),
+        );
+        assert!(matches!(zkp_system.prove_score_delta(&repeated_from, &repeated_to, 30, "0xriser"), Err(ZKPError::InvalidInput(_))));
+        let mut forged = from.clone();
+        forged.leaves[0].score = 0;
+        assert!(matches!(zkp_system.prove_score_delta(&forged, &to, 35, "0xriser"), Err(ZKPError::InvalidInput(_))));
+    }
+}
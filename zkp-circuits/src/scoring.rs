@@ -0,0 +1,263 @@
+//! Score Engine
+//!
+//! Stable entry point to off-chain hierarchical scoring. Enabled by the
+//! `scoring` feature, which compiles the scorer, its profiles and the
+//! differential privacy budget without proof generation:
+//!
+//! ```toml
+//! repid-zkp-circuits = { version = "1", default-features = false, features = ["scoring"] }
+//! ```
+//!
+//! The Q16.16 types in `fixed_point` stay shared with the circuits, so scores
+//! computed here match the ones a prover would prove. The crate's hash and
+//! signature dependencies are not optional and remain in the build.
+//!
+//! `ProfileRollout` swaps profiles at runtime, scoring under a staged profile
+//! in shadow mode first so divergence is visible before proofs move to the
+//! new profile hash.
+
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::hierarchical_scoring::{HierarchicalScorer, ScoreResult, ScoringProfile, UserScores};
+use crate::privacy::PrivacyBudget;
+use crate::slashing::PenaltyEvent;
+use crate::{RepIDCategory, Result, ZKPError};
+
+/// Hierarchical scorer behind an API kept stable across releases
+///
+/// `HierarchicalScorer` exposes its rule tables for the prover and may change
+/// shape; engines are built from versioned `ScoringProfile`s instead.
+#[derive(Debug, Clone, Default)]
+pub struct ScoreEngine {
+    scorer: HierarchicalScorer,
+}
+
+impl ScoreEngine {
+    /// Engine with the default weights, synergies and fuzzy rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Engine scoring under `profile`, rejecting unsupported profile versions
+    pub fn from_profile(profile: ScoringProfile) -> Result<Self> {
+        Ok(Self { scorer: profile.try_into()? })
+    }
+
+    /// Engine scoring under the JSON profile at `path`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_profile(ScoringProfile::load(path)?)
+    }
+
+    /// Versioned snapshot of the rules this engine scores under
+    pub fn profile(&self) -> ScoringProfile {
+        self.scorer.profile()
+    }
+
+    /// Score one user's category scores as of `timestamp`
+    pub fn score(&self, scores: &[(RepIDCategory, u32)], timestamp: u64, time_window: u64) -> ScoreResult {
+        self.scorer.calculate_score(scores, timestamp, time_window, None)
+    }
+
+    /// Score net of slashing penalties
+    pub fn score_with_penalties(
+        &self,
+        scores: &[(RepIDCategory, u32)],
+        penalties: &[PenaltyEvent],
+        timestamp: u64,
+        time_window: u64,
+    ) -> ScoreResult {
+        self.scorer.calculate_score_with_penalties(scores, penalties, timestamp, time_window, None)
+    }
+
+    /// Score and attach a noised analytics view, spending from `budget`
+    pub fn score_for_analytics(
+        &self,
+        scores: &[(RepIDCategory, u32)],
+        timestamp: u64,
+        time_window: u64,
+        budget: &mut PrivacyBudget,
+    ) -> ScoreResult {
+        self.scorer.calculate_score(scores, timestamp, time_window, Some(budget))
+    }
+
+    /// Score many users in parallel
+    pub fn score_batch(&self, users: &[UserScores]) -> Vec<ScoreResult> {
+        self.scorer.calculate_scores_batch(users)
+    }
+
+    /// Underlying scorer, for circuit integration
+    pub fn scorer(&self) -> &HierarchicalScorer {
+        &self.scorer
+    }
+}
+
+impl From<HierarchicalScorer> for ScoreEngine {
+    fn from(scorer: HierarchicalScorer) -> Self {
+        Self { scorer }
+    }
+}
+
+/// Divergence of a shadow profile from the active one
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShadowReport {
+    /// Hex hash of the staged profile
+    pub profile_hash: String,
+    /// Scorings run under both profiles
+    pub samples: u64,
+    /// Scorings where the final scores differed
+    pub diverged: u64,
+    /// Largest final score difference seen
+    pub max_abs_delta: u32,
+    /// Sum of `shadow - active` final scores
+    pub total_delta: i64,
+}
+
+impl ShadowReport {
+    /// Share of scorings that diverged, 0 before any samples
+    pub fn divergence_rate(&self) -> f64 {
+        match self.samples {
+            0 => 0.0,
+            samples => self.diverged as f64 / samples as f64,
+        }
+    }
+
+    fn record(&mut self, active: &ScoreResult, shadow: &ScoreResult) {
+        let delta = shadow.final_score as i64 - active.final_score as i64;
+        self.samples += 1;
+        if delta != 0 {
+            self.diverged += 1;
+        }
+        self.max_abs_delta = self.max_abs_delta.max(delta.unsigned_abs() as u32);
+        self.total_delta += delta;
+    }
+}
+
+#[derive(Debug)]
+struct Shadow {
+    engine: ScoreEngine,
+    report: ShadowReport,
+}
+
+/// Engine whose profile can be replaced at runtime, after a shadow run
+///
+/// Scores always come from the active profile. While a profile is staged,
+/// every scoring also runs under it and the divergence is tallied until the
+/// staged profile is promoted or discarded.
+#[derive(Debug)]
+pub struct ProfileRollout {
+    active: RwLock<Arc<ScoreEngine>>,
+    shadow: RwLock<Option<Shadow>>,
+}
+
+impl ProfileRollout {
+    pub fn new(engine: ScoreEngine) -> Self {
+        Self {
+            active: RwLock::new(Arc::new(engine)),
+            shadow: RwLock::new(None),
+        }
+    }
+
+    /// Engine currently scoring
+    pub fn active(&self) -> Arc<ScoreEngine> {
+        self.active.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Run `profile` in shadow mode, replacing any staged profile and its report
+    pub fn stage(&self, profile: ScoringProfile) -> Result<()> {
+        let report = ShadowReport { profile_hash: profile.hash_hex(), ..ShadowReport::default() };
+        let engine = ScoreEngine::from_profile(profile)?;
+        *self.shadow.write().unwrap_or_else(|e| e.into_inner()) = Some(Shadow { engine, report });
+        Ok(())
+    }
+
+    /// Stage the JSON profile at `path`
+    pub fn stage_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.stage(ScoringProfile::load(path)?)
+    }
+
+    /// Score under the active profile, and under the staged one if any
+    pub fn score(&self, scores: &[(RepIDCategory, u32)], timestamp: u64, time_window: u64) -> ScoreResult {
+        let result = self.active().score(scores, timestamp, time_window);
+        if let Some(shadow) = self.shadow.write().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            let shadow_result = shadow.engine.score(scores, timestamp, time_window);
+            shadow.report.record(&result, &shadow_result);
+        }
+        result
+    }
+
+    /// Divergence so far of the staged profile
+    pub fn shadow_report(&self) -> Option<ShadowReport> {
+        self.shadow.read().unwrap_or_else(|e| e.into_inner()).as_ref().map(|shadow| shadow.report.clone())
+    }
+
+    /// Make the staged profile active, returning it and its final report
+    ///
+    /// Pass the profile to `RepIDZKPSystem::set_scoring_profile` so proofs
+    /// carry the new hash from here on.
+    pub fn promote(&self) -> Result<(ScoringProfile, ShadowReport)> {
+        let shadow = self.shadow.write().unwrap_or_else(|e| e.into_inner()).take()
+            .ok_or_else(|| ZKPError::ConfigError("no scoring profile is staged".to_string()))?;
+        let profile = shadow.engine.profile();
+        *self.active.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(shadow.engine);
+        Ok((profile, shadow.report))
+    }
+
+    /// Drop the staged profile, returning its report
+    pub fn discard(&self) -> Option<ShadowReport> {
+        self.shadow.write().unwrap_or_else(|e| e.into_inner()).take().map(|shadow| shadow.report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_point::Q16;
+
+    #[test]
+    fn test_engine_scores_match_scorer_through_profiles() {
+        let scores = [(RepIDCategory::Governance, 80), (RepIDCategory::Technical, 70)];
+        let mut scorer = HierarchicalScorer::new();
+        scorer.set_category_weight(RepIDCategory::Technical, Q16::from_int(2));
+        let expected = scorer.calculate_score(&scores, 1_000, 86_400, None);
+
+        let engine = ScoreEngine::from_profile(scorer.profile()).unwrap();
+        assert_eq!(engine.score(&scores, 1_000, 86_400).final_score, expected.final_score);
+        assert_eq!(engine.profile().hash(), scorer.profile().hash());
+        assert_ne!(ScoreEngine::new().score(&scores, 1_000, 86_400).final_score, expected.final_score);
+
+        let user = UserScores { scores: scores.to_vec(), penalties: Vec::new(), timestamp: 1_000, time_window: 86_400 };
+        assert_eq!(engine.score_batch(&[user.clone(), user])[1].final_score, expected.final_score);
+
+        let mut profile = engine.profile();
+        profile.version += 1;
+        assert!(ScoreEngine::from_profile(profile).is_err());
+    }
+
+    #[test]
+    fn test_rollout_shadows_before_promotion() {
+        let scores = [(RepIDCategory::Governance, 80), (RepIDCategory::Technical, 70)];
+        let rollout = ProfileRollout::new(ScoreEngine::new());
+        let baseline = rollout.score(&scores, 1_000, 86_400).final_score;
+        assert!(rollout.promote().is_err());
+
+        let mut scorer = HierarchicalScorer::new();
+        scorer.set_category_weight(RepIDCategory::Technical, Q16::from_int(2));
+        rollout.stage(scorer.profile()).unwrap();
+        assert_eq!(rollout.score(&scores, 1_000, 86_400).final_score, baseline);
+        rollout.score(&[(RepIDCategory::Governance, 80)], 1_000, 86_400);
+
+        let report = rollout.shadow_report().unwrap();
+        assert_eq!((report.samples, report.diverged), (2, 1));
+        assert_eq!(report.profile_hash, scorer.profile().hash_hex());
+        assert!(report.max_abs_delta > 0 && report.total_delta > 0);
+
+        let (profile, final_report) = rollout.promote().unwrap();
+        assert_eq!(final_report, report);
+        assert!(rollout.shadow_report().is_none());
+        assert_eq!(rollout.active().profile().hash(), profile.hash());
+        assert_eq!(rollout.score(&scores, 1_000, 86_400).final_score as i64, baseline as i64 + report.total_delta);
+    }
+}
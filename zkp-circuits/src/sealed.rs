@@ -0,0 +1,163 @@
+//! Designated-Verifier Sealing
+//!
+//! Encrypts a proof envelope to one verifier's X25519 public key, for relying
+//! parties whose reputation checks are confidential. Each sealing uses a fresh
+//! ephemeral key; the shared secret derives a ChaCha20-Poly1305 key, and the
+//! ephemeral and recipient keys are authenticated alongside the ciphertext.
+//! An intercepted sealed proof reveals neither its statement nor a verifiable
+//! proof to anyone but the designated verifier.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use serde::{Deserialize, Serialize};
+
+use crate::{protocol, RepIDProof, Result, ZKPError};
+
+const KDF_CONTEXT: &str = "repid/sealed-proof/v1";
+
+/// X25519 public key of a designated verifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VerifierPublicKey(pub [u8; 32]);
+
+/// X25519 secret key of a designated verifier
+#[derive(Clone)]
+pub struct VerifierSecretKey([u8; 32]);
+
+impl std::fmt::Debug for VerifierSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VerifierSecretKey")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+impl VerifierSecretKey {
+    /// Key from 32 bytes of caller-held key material (clamped on use)
+    pub fn from_bytes(secret: [u8; 32]) -> Self {
+        Self(secret)
+    }
+
+    /// Fresh key from `rng`
+    #[cfg(feature = "scoring")]
+    pub fn generate(rng: &dyn crate::entropy::RngProvider) -> Result<Self> {
+        crate::entropy::seed(rng).map(Self)
+    }
+
+    pub fn public_key(&self) -> VerifierPublicKey {
+        VerifierPublicKey(MontgomeryPoint::mul_base_clamped(self.0).to_bytes())
+    }
+
+    /// Decrypt a proof sealed to this key
+    pub fn open(&self, sealed: &SealedProof) -> Result<RepIDProof> {
+        if sealed.recipient != self.public_key() {
+            return Err(ZKPError::VerificationError("Proof is sealed to a different verifier".to_string()));
+        }
+        let shared = MontgomeryPoint(sealed.ephemeral).mul_clamped(self.0);
+        let (key, nonce) = derive_key(&shared, &sealed.ephemeral, &sealed.recipient)?;
+        let plaintext = ChaCha20Poly1305::new(&key)
+            .decrypt(&nonce, Payload { msg: &sealed.ciphertext, aad: &sealed.associated_data() })
+            .map_err(|_| ZKPError::VerificationError("Sealed proof could not be decrypted".to_string()))?;
+        protocol::decode_envelope(&plaintext)
+    }
+}
+
+/// Proof envelope encrypted to one verifier
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedProof {
+    pub recipient: VerifierPublicKey,
+    /// Sender's ephemeral X25519 public key
+    pub ephemeral: [u8; 32],
+    pub ciphertext: Vec<u8>,
+}
+
+impl SealedProof {
+    fn associated_data(&self) -> Vec<u8> {
+        [self.ephemeral.as_slice(), &self.recipient.0].concat()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        crate::wire::to_bytes(self)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        crate::wire::from_bytes(bytes)
+    }
+}
+
+/// Encrypt `proof` so only the holder of `recipient`'s secret key can read or verify it
+#[cfg(feature = "scoring")]
+pub fn seal(proof: &RepIDProof, recipient: &VerifierPublicKey, rng: &dyn crate::entropy::RngProvider) -> Result<SealedProof> {
+    let ephemeral_secret = crate::entropy::seed(rng)?;
+    let ephemeral = MontgomeryPoint::mul_base_clamped(ephemeral_secret).to_bytes();
+    let shared = MontgomeryPoint(recipient.0).mul_clamped(ephemeral_secret);
+    let (key, nonce) = derive_key(&shared, &ephemeral, recipient)?;
+
+    let mut sealed = SealedProof {
+        recipient: *recipient,
+        ephemeral,
+        ciphertext: Vec::new(),
+    };
+    let plaintext = protocol::encode_envelope(proof)?;
+    sealed.ciphertext = ChaCha20Poly1305::new(&key)
+        .encrypt(&nonce, Payload { msg: &plaintext, aad: &sealed.associated_data() })
+        .map_err(|_| ZKPError::SerializationError("Proof encryption failed".to_string()))?;
+    Ok(sealed)
+}
+
+/// AEAD key and nonce from the X25519 shared secret
+///
+/// Every ephemeral key seals one envelope, so a derived nonce is never reused
+/// under its key. Low-order recipient or ephemeral points give an all-zero
+/// shared secret and are rejected.
+fn derive_key(shared: &MontgomeryPoint, ephemeral: &[u8; 32], recipient: &VerifierPublicKey) -> Result<(Key, Nonce)> {
+    if shared.to_bytes() == [0u8; 32] {
+        return Err(ZKPError::InvalidInput("X25519 key agreement produced a low-order point".to_string()));
+    }
+    let mut hasher = blake3::Hasher::new_derive_key(KDF_CONTEXT);
+    hasher.update(shared.as_bytes());
+    hasher.update(ephemeral);
+    hasher.update(&recipient.0);
+    let mut output = [0u8; 44];
+    hasher.finalize_xof().fill(&mut output);
+    Ok((*Key::from_slice(&output[..32]), *Nonce::from_slice(&output[32..])))
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::entropy::DeterministicRng;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_only_the_designated_verifier_can_open() {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let proof = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest").unwrap().proof;
+
+        let rng = DeterministicRng::new([1; 32]);
+        let partner = VerifierSecretKey::generate(&rng).unwrap();
+        let eavesdropper = VerifierSecretKey::generate(&rng).unwrap();
+        let sealed = seal(&proof, &partner.public_key(), &rng).unwrap();
+        let received = SealedProof::from_bytes(&sealed.to_bytes().unwrap()).unwrap();
+
+        let opened = partner.open(&received).unwrap();
+        assert_eq!(opened.proof_data, proof.proof_data);
+        assert!(zkp_system.verify_proof(&opened, Some(&request)).unwrap());
+
+        // Re-addressing the envelope or tampering with it fails authentication
+        assert!(eavesdropper.open(&received).is_err());
+        let readdressed = SealedProof { recipient: eavesdropper.public_key(), ..received.clone() };
+        assert!(eavesdropper.open(&readdressed).is_err());
+        let mut tampered = received.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert!(partner.open(&tampered).is_err());
+        assert!(seal(&proof, &VerifierPublicKey([0; 32]), &rng).is_err());
+    }
+}
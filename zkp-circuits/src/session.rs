@@ -0,0 +1,227 @@
+//! Verification Sessions
+//!
+//! Challenge-response flow for gating access on a fresh proof: the verifier
+//! issues a challenge for a request, the prover answers with a proof bound to
+//! it, and the verifier checks the proof and hands back a receipt. Session
+//! state serializes, so web backends can keep it between the two requests,
+//! or save it to a `Storage` shared by every backend replica.
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::Storage;
+use crate::{protocol, RepIDProof, RepIDZKPSystem, Result, ThresholdVerificationRequest, ZKPError};
+
+/// Storage namespace of sessions, keyed by session id
+const STORAGE_NAMESPACE: &str = "sessions";
+
+/// What the prover receives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionChallenge {
+    pub session_id: String,
+    pub challenge: [u8; 32],
+    pub request: ThresholdVerificationRequest,
+    pub expires_at: u64,
+}
+
+impl SessionChallenge {
+    /// Prove the session's request with a proof bound to its challenge
+    #[cfg(feature = "prover")]
+    pub fn respond(
+        &self,
+        system: &mut RepIDZKPSystem,
+        user_scores: &[(crate::RepIDCategory, u32)],
+        wallet_address: &str,
+    ) -> Result<crate::ThresholdVerificationResult> {
+        system.challenged(&self.challenge, |system| {
+            system.prove_threshold_verification(&self.request, user_scores, wallet_address)
+        })
+    }
+}
+
+/// Proof of a completed session, for the backend to store or sign
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionReceipt {
+    pub session_id: String,
+    pub request_hash: [u8; 32],
+    pub challenge: [u8; 32],
+    pub wallet_hash: String,
+    /// Blake3 of the verified envelope
+    pub proof_hash: [u8; 32],
+    pub verified_at: u64,
+}
+
+impl SessionReceipt {
+    /// Blake3 digest over every receipt field
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"repid/session-receipt/v1");
+        hasher.update(&crate::wire::to_bytes(self).expect("receipt serializes"));
+        *hasher.finalize().as_bytes()
+    }
+}
+
+/// Where a session is in the flow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum SessionState {
+    /// Waiting for the prover's response
+    Challenged,
+    Verified { receipt: SessionReceipt },
+    Rejected { reason: String },
+    /// No response arrived before `expires_at`
+    Expired,
+}
+
+/// Verifier side of one challenge-response exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationSession {
+    pub id: String,
+    pub request: ThresholdVerificationRequest,
+    pub challenge: [u8; 32],
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub state: SessionState,
+}
+
+impl VerificationSession {
+    /// Open a session for `request`, valid for `ttl_secs` after `now`
+    ///
+    /// `nonce` must come from the caller's CSPRNG; the challenge also binds the
+    /// session id, the request and the issue time.
+    pub fn issue(id: &str, request: ThresholdVerificationRequest, nonce: [u8; 32], now: u64, ttl_secs: u64) -> Result<Self> {
+        request.validate()?;
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"repid/session-challenge/v1");
+        hasher.update(&(id.len() as u64).to_le_bytes());
+        hasher.update(id.as_bytes());
+        hasher.update(&nonce);
+        hasher.update(&request.canonical_hash());
+        hasher.update(&now.to_le_bytes());
+        Ok(Self {
+            id: id.to_string(),
+            challenge: *hasher.finalize().as_bytes(),
+            request,
+            issued_at: now,
+            expires_at: now.saturating_add(ttl_secs),
+            state: SessionState::Challenged,
+        })
+    }
+
+    /// Challenge to send to the prover
+    pub fn challenge(&self) -> SessionChallenge {
+        SessionChallenge {
+            session_id: self.id.clone(),
+            challenge: self.challenge,
+            request: self.request.clone(),
+            expires_at: self.expires_at,
+        }
+    }
+
+    /// Check the prover's response and close the session
+    ///
+    /// Only a session still waiting for its response can complete; a rejected
+    /// or expired session stays closed.
+    pub fn complete(&mut self, system: &mut RepIDZKPSystem, proof: &RepIDProof, now: u64) -> Result<SessionReceipt> {
+        if self.state != SessionState::Challenged {
+            return Err(ZKPError::PolicyViolation(format!("session {} is already closed", self.id)));
+        }
+        if now > self.expires_at {
+            self.state = SessionState::Expired;
+            return Err(ZKPError::PolicyViolation(format!("session {} expired at {}", self.id, self.expires_at)));
+        }
+
+        let outcome = system
+            .challenged(&self.challenge, |system| system.verify_proof(proof, Some(&self.request)))
+            .and_then(|valid| match valid {
+                true => Ok(()),
+                false => Err(ZKPError::VerificationError("proof is not bound to this session".to_string())),
+            });
+        if let Err(e) = outcome {
+            self.state = SessionState::Rejected { reason: e.to_string() };
+            return Err(e);
+        }
+
+        let receipt = SessionReceipt {
+            session_id: self.id.clone(),
+            request_hash: self.request.canonical_hash(),
+            challenge: self.challenge,
+            wallet_hash: proof.metadata.wallet_hash.clone(),
+            proof_hash: *blake3::hash(&protocol::encode_envelope(proof)?).as_bytes(),
+            verified_at: now,
+        };
+        self.state = SessionState::Verified { receipt: receipt.clone() };
+        Ok(receipt)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    /// Write the session to `storage`, replacing its previous state
+    pub fn save(&self, storage: &dyn Storage) -> Result<()> {
+        storage.put(STORAGE_NAMESPACE, self.id.as_bytes(), self.to_json()?.as_bytes())
+    }
+
+    /// Session `id` as last saved to `storage`
+    pub fn load(storage: &dyn Storage, id: &str) -> Result<Option<Self>> {
+        storage
+            .get(STORAGE_NAMESPACE, id.as_bytes())?
+            .map(|bytes| {
+                let json = String::from_utf8(bytes).map_err(|e| ZKPError::StorageError(e.to_string()))?;
+                Self::from_json(&json)
+            })
+            .transpose()
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, SecurityLevel};
+
+    #[test]
+    fn test_session_accepts_only_its_own_bound_proof() {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Governance],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let scores = [(RepIDCategory::Governance, 70)];
+        let mut verifier = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let mut prover = RepIDZKPSystem::new(SecurityLevel::Fast);
+
+        let session = VerificationSession::issue("s1", request.clone(), [7; 32], 1_000, 300).unwrap();
+        let challenge = session.challenge();
+        let proof = challenge.respond(&mut prover, &scores, "0xtest").unwrap().proof;
+
+        // The session survives a round trip through the backend's store
+        let store = crate::storage::MemoryStorage::new();
+        session.save(&store).unwrap();
+        let mut restored = VerificationSession::load(&store, "s1").unwrap().unwrap();
+        assert!(VerificationSession::load(&store, "s0").unwrap().is_none());
+        let receipt = restored.complete(&mut verifier, &proof, 1_100).unwrap();
+        assert_eq!(restored.state, SessionState::Verified { receipt: receipt.clone() });
+        assert_eq!(receipt.request_hash, request.canonical_hash());
+        assert!(restored.complete(&mut verifier, &proof, 1_100).is_err());
+
+        // A bound proof replayed into another session, or an unbound proof, is rejected
+        let mut other = VerificationSession::issue("s2", request.clone(), [8; 32], 1_000, 300).unwrap();
+        assert!(other.complete(&mut verifier, &proof, 1_100).is_err());
+        assert!(matches!(other.state, SessionState::Rejected { .. }));
+        assert!(!verifier.verify_proof(&proof, Some(&request)).unwrap());
+        let unbound = prover.prove_threshold_verification(&request, &scores, "0xtest").unwrap().proof;
+        let mut third = VerificationSession::issue("s3", request.clone(), [9; 32], 1_000, 300).unwrap();
+        assert!(third.complete(&mut verifier, &unbound, 1_100).is_err());
+
+        let mut late = VerificationSession::issue("s4", request, [10; 32], 1_000, 300).unwrap();
+        let proof = late.challenge().respond(&mut prover, &scores, "0xtest").unwrap().proof;
+        assert!(late.complete(&mut verifier, &proof, 1_301).is_err());
+        assert_eq!(late.state, SessionState::Expired);
+    }
+}
@@ -0,0 +1,213 @@
+//! Signing Backends
+//!
+//! Every signing operation goes through `Signer`, so keys can live in memory
+//! for development or behind an HSM / KMS in production
+
+use std::fmt::Debug;
+
+use ed25519_dalek::{Signer as _, SigningKey};
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// Signature algorithm a signer produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureScheme {
+    /// Prover identity and attestation signatures
+    Ed25519,
+    /// ECDSA over secp256k1, as used for EIP-712 typed data
+    Secp256k1,
+}
+
+/// A key that signs messages without exposing its material
+pub trait Signer: Debug + Send + Sync {
+    fn scheme(&self) -> SignatureScheme;
+
+    /// Encoded public key (32 bytes for Ed25519, SEC1 for secp256k1)
+    fn public_key(&self) -> Result<Vec<u8>>;
+
+    /// Key ring ID embedded in signatures, if the key is managed
+    fn key_id(&self) -> Option<String> {
+        None
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Ed25519 key held in process memory
+#[derive(Clone)]
+pub struct InMemorySigner {
+    signing_key: SigningKey,
+    key_id: Option<String>,
+}
+
+impl Debug for InMemorySigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemorySigner").field("key_id", &self.key_id).finish_non_exhaustive()
+    }
+}
+
+impl InMemorySigner {
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(&seed),
+            key_id: None,
+        }
+    }
+
+    pub fn with_key_id(mut self, key_id: &str) -> Self {
+        self.key_id = Some(key_id.to_string());
+        self
+    }
+}
+
+impl Signer for InMemorySigner {
+    fn scheme(&self) -> SignatureScheme {
+        SignatureScheme::Ed25519
+    }
+
+    fn public_key(&self) -> Result<Vec<u8>> {
+        Ok(self.signing_key.verifying_key().to_bytes().to_vec())
+    }
+
+    fn key_id(&self) -> Option<String> {
+        self.key_id.clone()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.signing_key.sign(message).to_bytes().to_vec())
+    }
+}
+
+/// Adapter for keys held by an HSM (PKCS#11) or a cloud KMS
+#[cfg(feature = "remote-signer")]
+pub mod remote {
+    use super::*;
+    use crate::ZKPError;
+
+    /// Transport to the device or service holding the key
+    pub trait RemoteKeyClient: Debug + Send + Sync {
+        fn public_key(&self, key_ref: &str) -> Result<Vec<u8>>;
+
+        fn sign(&self, key_ref: &str, scheme: SignatureScheme, message: &[u8]) -> Result<Vec<u8>>;
+    }
+
+    /// `Signer` backed by a remote key, with the public key fetched once on connect
+    #[derive(Debug)]
+    pub struct RemoteSigner<C: RemoteKeyClient> {
+        client: C,
+        key_ref: String,
+        scheme: SignatureScheme,
+        public_key: Vec<u8>,
+        key_id: Option<String>,
+    }
+
+    impl<C: RemoteKeyClient> RemoteSigner<C> {
+        pub fn connect(client: C, key_ref: &str, scheme: SignatureScheme) -> Result<Self> {
+            let public_key = client.public_key(key_ref)?;
+            if scheme == SignatureScheme::Ed25519 && public_key.len() != 32 {
+                return Err(ZKPError::SigningError(format!(
+                    "Remote key '{}' returned a {}-byte Ed25519 public key",
+                    key_ref,
+                    public_key.len()
+                )));
+            }
+            Ok(Self {
+                client,
+                key_ref: key_ref.to_string(),
+                scheme,
+                public_key,
+                key_id: None,
+            })
+        }
+
+        pub fn with_key_id(mut self, key_id: &str) -> Self {
+            self.key_id = Some(key_id.to_string());
+            self
+        }
+    }
+
+    impl<C: RemoteKeyClient> Signer for RemoteSigner<C> {
+        fn scheme(&self) -> SignatureScheme {
+            self.scheme
+        }
+
+        fn public_key(&self) -> Result<Vec<u8>> {
+            Ok(self.public_key.clone())
+        }
+
+        fn key_id(&self) -> Option<String> {
+            self.key_id.clone()
+        }
+
+        fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+            self.client.sign(&self.key_ref, self.scheme, message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::{verify_prover_signature, ProverIdentity};
+    use std::sync::Arc;
+
+    fn envelope() -> crate::RepIDProof {
+        crate::RepIDProof {
+            proof_data: vec![1, 2, 3],
+            public_inputs: Vec::new(),
+            metadata: crate::ProofMetadata {
+                operation_type: "threshold_verification".to_string(),
+                timestamp: 0,
+                wallet_hash: String::new(),
+                proof_size: 3,
+                generation_time_ms: 0,
+                backend: crate::backend::BackendKind::CustomStark,
+                prover_signature: None,
+                tee_attestation: None,
+                content_address: None,
+                protocol_version: crate::protocol::PROTOCOL_VERSION,
+                stark_params: None,
+                issuer_signatures: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_prover_identity_signs_through_signer() {
+        let signer = Arc::new(InMemorySigner::from_seed([3; 32]).with_key_id("k1"));
+        let identity = ProverIdentity::from_signer(signer.clone()).unwrap();
+        let mut proof = envelope();
+        identity.sign(&mut proof).unwrap();
+        assert_eq!(verify_prover_signature(&proof).unwrap().map(|k| k.to_vec()), Some(signer.public_key().unwrap()));
+        assert!(format!("{:?}", signer).contains("k1"));
+    }
+
+    #[cfg(feature = "remote-signer")]
+    #[test]
+    fn test_remote_signer_adapter() {
+        use remote::{RemoteKeyClient, RemoteSigner};
+
+        /// Stand-in for an HSM session holding one Ed25519 key
+        #[derive(Debug)]
+        struct FakeHsm(InMemorySigner);
+
+        impl RemoteKeyClient for FakeHsm {
+            fn public_key(&self, _key_ref: &str) -> Result<Vec<u8>> {
+                self.0.public_key()
+            }
+
+            fn sign(&self, _key_ref: &str, _scheme: SignatureScheme, message: &[u8]) -> Result<Vec<u8>> {
+                self.0.sign(message)
+            }
+        }
+
+        let remote = RemoteSigner::connect(FakeHsm(InMemorySigner::from_seed([4; 32])), "slot-0", SignatureScheme::Ed25519)
+            .unwrap();
+        let identity = ProverIdentity::from_signer(Arc::new(remote)).unwrap();
+        let mut proof = envelope();
+        identity.sign(&mut proof).unwrap();
+        assert!(verify_prover_signature(&proof).unwrap().is_some());
+    }
+}
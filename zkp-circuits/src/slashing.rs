@@ -0,0 +1,80 @@
+//! Slashing Penalties
+//!
+//! Negative score events from governance slashing, carried through the scorer
+//! and into threshold proofs as field-encoded negative contributions
+
+use serde::{Deserialize, Serialize};
+
+use crate::RepIDCategory;
+
+/// A penalty subtracted from a category's contribution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PenaltyEvent {
+    pub category: RepIDCategory,
+    /// Points removed (the score contribution is `-amount`)
+    pub amount: u32,
+    /// Governance reference for the slashing decision
+    pub reason: String,
+    pub timestamp: u64,
+}
+
+impl PenaltyEvent {
+    pub fn new(category: RepIDCategory, amount: u32, reason: &str, timestamp: u64) -> Self {
+        Self {
+            category,
+            amount,
+            reason: reason.to_string(),
+            timestamp,
+        }
+    }
+
+    /// Signed contribution to the aggregate score
+    pub fn signed_amount(&self) -> i64 {
+        -(self.amount as i64)
+    }
+}
+
+/// Total points removed by `penalties`
+pub fn total_penalties(penalties: &[PenaltyEvent]) -> u32 {
+    penalties.iter().fold(0u32, |total, p| total.saturating_add(p.amount))
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::custom_stark::BabyBearField;
+    use crate::hierarchical_scoring::HierarchicalScorer;
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest, ZKPError};
+
+    #[test]
+    fn test_penalties_through_scorer_and_circuit() {
+        assert_eq!(BabyBearField::from_i64(-5).to_i64(), -5);
+        assert_eq!(BabyBearField::from_i64(-5) + BabyBearField::from_u32(8), BabyBearField::from_u32(3));
+
+        let scores = [(RepIDCategory::Governance, 40), (RepIDCategory::Community, 30)];
+        let slash = [PenaltyEvent::new(RepIDCategory::Governance, 100, "proposal-42", 1_000)];
+
+        let scorer = HierarchicalScorer::new();
+        let result = scorer.calculate_score_with_penalties(&scores, &slash, 1_000, 900, None);
+        assert_eq!(result.penalties, 100);
+        assert!(result.net_score < 0);
+        assert_eq!(result.final_score, 0);
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Governance, RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let light = [PenaltyEvent::new(RepIDCategory::Community, 10, "spam", 1_000)];
+        let ok = zkp_system.prove_penalized_threshold_verification(&request, &scores, &light, "0xtest").unwrap();
+        assert!(ok.meets_threshold);
+        assert!(zkp_system.verify_proof(&ok.proof, None).unwrap());
+
+        // A negative net score misses the threshold rather than wrapping around the field
+        let slashed = zkp_system.prove_penalized_threshold_verification(&request, &scores, &slash, "0xtest");
+        assert!(matches!(slashed, Err(ZKPError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,293 @@
+//! Solana / Anchor Consumption
+//!
+//! Borsh payloads and account layouts for Anchor programs that consume RepID
+//! results. `instruction_data` is the `verify_rep_id` instruction the Solana
+//! export of `multichain` submits; `RepIdVerification` is the account such a
+//! program stores the outcome in, and `AnchorAccountLayout` describes that
+//! account (discriminator, field offsets, space to allocate) as an IDL
+//! fragment or as the Rust declaration to paste into the program.
+//!
+//! Fixed-size fields come first so `subject` and `proof_type` sit at fixed
+//! offsets for `getProgramAccounts` `memcmp` filters.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::limits::ProofLimits;
+use crate::multichain::VerificationRecord;
+use crate::{Result, ZKPError};
+
+/// Account type name, which its discriminator is derived from
+pub const ACCOUNT_NAME: &str = "RepIdVerification";
+/// Instruction consuming a `VerificationRecord`
+pub const VERIFY_INSTRUCTION: &str = "verify_rep_id";
+/// Anchor discriminator bytes in front of account and instruction data
+pub const DISCRIMINATOR_SIZE: usize = 8;
+/// Public inputs room allocated when the caller does not size the account
+pub const DEFAULT_MAX_PUBLIC_INPUTS: usize = 16;
+
+/// Anchor account discriminator: `sha256("account:<Name>")[..8]`
+pub fn account_discriminator(name: &str) -> [u8; DISCRIMINATOR_SIZE] {
+    anchor_discriminator("account", name)
+}
+
+/// Anchor instruction discriminator: `sha256("global:<name>")[..8]`
+pub fn instruction_discriminator(name: &str) -> [u8; DISCRIMINATOR_SIZE] {
+    anchor_discriminator("global", name)
+}
+
+fn anchor_discriminator(namespace: &str, name: &str) -> [u8; DISCRIMINATOR_SIZE] {
+    let digest = Sha256::digest(format!("{}:{}", namespace, name).as_bytes());
+    let mut discriminator = [0u8; DISCRIMINATOR_SIZE];
+    discriminator.copy_from_slice(&digest[..DISCRIMINATOR_SIZE]);
+    discriminator
+}
+
+/// `verify_rep_id` instruction data: discriminator, then the Borsh record
+pub fn instruction_data(record: &VerificationRecord) -> Result<Vec<u8>> {
+    let mut data = instruction_discriminator(VERIFY_INSTRUCTION).to_vec();
+    data.extend(borsh::to_vec(record).map_err(|e| ZKPError::SerializationError(e.to_string()))?);
+    Ok(data)
+}
+
+/// Stored outcome of one verified result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct RepIdVerification {
+    /// SHA-256 of the envelope wallet label
+    pub subject: [u8; 32],
+    /// SHA-256 of the operation type
+    pub proof_type: [u8; 32],
+    pub threshold: u32,
+    pub meets_threshold: bool,
+    pub timestamp: u64,
+    pub public_inputs_digest: [u8; 32],
+    pub proof_hash: [u8; 32],
+    pub public_inputs: Vec<u64>,
+}
+
+impl RepIdVerification {
+    pub fn from_record(record: &VerificationRecord) -> Self {
+        Self {
+            subject: Sha256::digest(record.subject.as_bytes()).into(),
+            proof_type: Sha256::digest(record.proof_type.as_bytes()).into(),
+            threshold: record.threshold,
+            meets_threshold: record.meets_threshold,
+            timestamp: record.timestamp,
+            public_inputs_digest: record.public_inputs_digest,
+            proof_hash: record.proof_hash,
+            public_inputs: record.public_inputs.clone(),
+        }
+    }
+
+    /// Account data as allocated under `layout`: discriminator, Borsh, zero padding
+    pub fn to_account_data(&self, layout: &AnchorAccountLayout) -> Result<Vec<u8>> {
+        ProofLimits::check("account public inputs", self.public_inputs.len() as u64, layout.max_public_inputs as u64)?;
+        let mut data = layout.discriminator.to_vec();
+        data.extend(borsh::to_vec(self).map_err(|e| ZKPError::SerializationError(e.to_string()))?);
+        data.resize(layout.space, 0);
+        Ok(data)
+    }
+
+    /// Decode account data, ignoring the unused tail of the allocation
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        if data.len() < DISCRIMINATOR_SIZE || data[..DISCRIMINATOR_SIZE] != account_discriminator(ACCOUNT_NAME) {
+            return Err(ZKPError::SerializationError(format!("account is not a {}", ACCOUNT_NAME)));
+        }
+        BorshDeserialize::deserialize(&mut &data[DISCRIMINATOR_SIZE..]).map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+}
+
+/// Borsh type of an account field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    Bytes32,
+    U32,
+    Bool,
+    U64,
+    /// `Vec<u64>` holding at most the layout's `max_public_inputs`
+    U64Vec,
+}
+
+impl FieldType {
+    fn rust_type(&self) -> &'static str {
+        match self {
+            FieldType::Bytes32 => "[u8; 32]",
+            FieldType::U32 => "u32",
+            FieldType::Bool => "bool",
+            FieldType::U64 => "u64",
+            FieldType::U64Vec => "Vec<u64>",
+        }
+    }
+
+    fn idl_type(&self) -> Value {
+        match self {
+            FieldType::Bytes32 => json!({ "array": ["u8", 32] }),
+            FieldType::U32 => json!("u32"),
+            FieldType::Bool => json!("bool"),
+            FieldType::U64 => json!("u64"),
+            FieldType::U64Vec => json!({ "vec": "u64" }),
+        }
+    }
+
+    fn max_size(&self, max_len: usize) -> usize {
+        match self {
+            FieldType::Bytes32 => 32,
+            FieldType::U32 => 4,
+            FieldType::Bool => 1,
+            FieldType::U64 => 8,
+            FieldType::U64Vec => 4 + 8 * max_len,
+        }
+    }
+
+    fn is_fixed(&self) -> bool {
+        !matches!(self, FieldType::U64Vec)
+    }
+}
+
+/// Fields of `RepIdVerification`, in declaration order
+const ACCOUNT_FIELDS: [(&str, FieldType); 8] = [
+    ("subject", FieldType::Bytes32),
+    ("proof_type", FieldType::Bytes32),
+    ("threshold", FieldType::U32),
+    ("meets_threshold", FieldType::Bool),
+    ("timestamp", FieldType::U64),
+    ("public_inputs_digest", FieldType::Bytes32),
+    ("proof_hash", FieldType::Bytes32),
+    ("public_inputs", FieldType::U64Vec),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountField {
+    pub name: String,
+    pub ty: FieldType,
+    /// Byte offset within the account data, including the discriminator;
+    /// `None` once a variable-length field precedes it
+    pub offset: Option<usize>,
+    pub max_size: usize,
+}
+
+/// Anchor account layout of `RepIdVerification`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnchorAccountLayout {
+    pub name: String,
+    pub discriminator: [u8; DISCRIMINATOR_SIZE],
+    pub fields: Vec<AccountField>,
+    pub max_public_inputs: usize,
+    /// Bytes to allocate, discriminator included
+    pub space: usize,
+}
+
+impl AnchorAccountLayout {
+    /// Layout with room for `max_public_inputs` public inputs
+    pub fn for_verification(max_public_inputs: usize) -> Self {
+        let mut fields = Vec::with_capacity(ACCOUNT_FIELDS.len());
+        let (mut offset, mut space) = (Some(DISCRIMINATOR_SIZE), DISCRIMINATOR_SIZE);
+        for (name, ty) in ACCOUNT_FIELDS {
+            let max_size = ty.max_size(max_public_inputs);
+            fields.push(AccountField { name: name.to_string(), ty, offset, max_size });
+            offset = offset.filter(|_| ty.is_fixed()).map(|start| start + max_size);
+            space += max_size;
+        }
+        Self {
+            name: ACCOUNT_NAME.to_string(),
+            discriminator: account_discriminator(ACCOUNT_NAME),
+            fields,
+            max_public_inputs,
+            space,
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Option<&AccountField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Anchor IDL fragment: the `accounts` entry and its `types` definition
+    pub fn to_idl(&self) -> Value {
+        let fields: Vec<Value> = self
+            .fields
+            .iter()
+            .map(|field| json!({ "name": field.name, "type": field.ty.idl_type() }))
+            .collect();
+        json!({
+            "accounts": [{ "name": self.name, "discriminator": self.discriminator }],
+            "types": [{ "name": self.name, "type": { "kind": "struct", "fields": fields } }],
+        })
+    }
+
+    /// `#[account]` declaration for the consuming program
+    pub fn to_rust(&self) -> String {
+        let mut source = format!(
+            "// {} bytes including the 8-byte discriminator\n#[account]\n#[derive(InitSpace)]\npub struct {} {{\n",
+            self.space, self.name
+        );
+        for field in &self.fields {
+            if !field.ty.is_fixed() {
+                source.push_str(&format!("    #[max_len({})]\n", self.max_public_inputs));
+            }
+            source.push_str(&format!("    pub {}: {},\n", field.name, field.ty.rust_type()));
+        }
+        source.push_str("}\n");
+        source
+    }
+}
+
+impl Default for AnchorAccountLayout {
+    fn default() -> Self {
+        Self::for_verification(DEFAULT_MAX_PUBLIC_INPUTS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(public_inputs: Vec<u64>) -> VerificationRecord {
+        VerificationRecord {
+            subject: "0011223344556677".to_string(),
+            proof_type: "threshold_verification".to_string(),
+            threshold: 50,
+            meets_threshold: true,
+            timestamp: 1_700_000_000,
+            public_inputs,
+            public_inputs_digest: [1; 32],
+            proof_hash: [2; 32],
+        }
+    }
+
+    #[test]
+    fn test_account_layout_matches_borsh_encoding() {
+        let layout = AnchorAccountLayout::for_verification(4);
+        assert_eq!(layout.space, 8 + 4 * 32 + 4 + 1 + 8 + 4 + 4 * 8);
+        assert_eq!(layout.field("subject").unwrap().offset, Some(8));
+        assert_eq!(layout.field("timestamp").unwrap().offset, Some(8 + 64 + 5));
+        assert_eq!(layout.field("public_inputs").unwrap().offset, Some(8 + 64 + 13 + 64));
+
+        // A full account fills the allocation exactly; fields sit where the layout says
+        let account = RepIdVerification::from_record(&record(vec![7, 8, 9, 10]));
+        let data = account.to_account_data(&layout).unwrap();
+        assert_eq!(data.len(), layout.space);
+        assert_eq!(&data[..8], &account_discriminator(ACCOUNT_NAME));
+        let timestamp_at = layout.field("timestamp").unwrap().offset.unwrap();
+        assert_eq!(&data[timestamp_at..timestamp_at + 8], &1_700_000_000u64.to_le_bytes());
+        assert_eq!(RepIdVerification::from_account_data(&data).unwrap(), account);
+
+        // Shorter accounts are zero padded and still decode
+        let short = RepIdVerification::from_record(&record(vec![7]));
+        assert_eq!(RepIdVerification::from_account_data(&short.to_account_data(&layout).unwrap()).unwrap(), short);
+        let oversized = RepIdVerification::from_record(&record(vec![0; 5]));
+        assert!(matches!(oversized.to_account_data(&layout), Err(ZKPError::LimitExceeded { .. })));
+        assert!(RepIdVerification::from_account_data(&data[1..]).is_err());
+
+        let instruction = instruction_data(&record(vec![7])).unwrap();
+        assert_eq!(&instruction[..8], &instruction_discriminator(VERIFY_INSTRUCTION));
+        assert_eq!(VerificationRecord::try_from_slice(&instruction[8..]).unwrap(), record(vec![7]));
+
+        let idl = layout.to_idl();
+        assert_eq!(idl["types"][0]["type"]["fields"].as_array().unwrap().len(), ACCOUNT_FIELDS.len());
+        assert_eq!(idl["types"][0]["type"]["fields"][7]["type"], json!({ "vec": "u64" }));
+        assert!(layout.to_rust().contains("    #[max_len(4)]\n    pub public_inputs: Vec<u64>,\n"));
+    }
+}
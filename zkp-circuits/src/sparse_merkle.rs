@@ -0,0 +1,381 @@
+//! Sparse Merkle Tree
+//!
+//! Poseidon2 tree over the full 256-bit key space, keyed by identity
+//! commitments, with membership and non-membership witnesses and a trace
+//! gadget checking either against a root. Revocation, allowlist and
+//! unique-personhood sets share this tree instead of each building their own.
+//!
+//! The path to a key follows its bits from the least significant upwards. A
+//! present key's leaf is the Poseidon2 hash of its 16-bit limbs, an absent
+//! key's leaf is zero, and untouched subtrees collapse to precomputed empty
+//! hashes, so only the paths of inserted keys are stored.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::air::{ConstraintSystem, Expr};
+use crate::custom_stark::ExecutionTrace;
+use crate::ledger::hash_nodes;
+use crate::poseidon2::{self, Poseidon2Gadget};
+use crate::{Result, ZKPError, F};
+
+/// Levels between a leaf and the root
+pub const DEPTH: usize = 256;
+/// 16-bit limbs a key is hashed as
+pub const KEY_LIMBS: usize = 16;
+const LIMB_BITS: usize = 16;
+
+/// 256-bit identity commitment, big-endian
+pub type SmtKey = [u8; 32];
+
+fn key_bit(key: &SmtKey, level: usize) -> bool {
+    (key[31 - level / 8] >> (level % 8)) & 1 == 1
+}
+
+/// `key` with every bit below `height` cleared: the subtree it falls in
+fn subtree(key: &SmtKey, height: usize) -> SmtKey {
+    let mut prefix = *key;
+    for level in 0..height {
+        prefix[31 - level / 8] &= !(1 << (level % 8));
+    }
+    prefix
+}
+
+/// Key of the sibling subtree at `height`
+fn sibling(key: &SmtKey, height: usize) -> SmtKey {
+    let mut prefix = subtree(key, height);
+    prefix[31 - height / 8] ^= 1 << (height % 8);
+    prefix
+}
+
+/// Key limbs, least significant first
+pub fn key_limbs(key: &SmtKey) -> [F; KEY_LIMBS] {
+    std::array::from_fn(|limb| F::new(u16::from_be_bytes([key[30 - 2 * limb], key[31 - 2 * limb]]) as u64))
+}
+
+/// Leaf of a key present in the tree
+pub fn leaf_hash(key: &SmtKey) -> F {
+    poseidon2::hash_elements(&key_limbs(key))
+}
+
+/// Key embedding a field element, for sets of field-valued ids
+pub fn key_from_field(value: F) -> SmtKey {
+    let mut key = [0u8; 32];
+    key[24..].copy_from_slice(&value.0.to_be_bytes());
+    key
+}
+
+/// Root of an empty subtree at each height
+fn empty_hashes() -> &'static [F] {
+    static EMPTY: OnceLock<Vec<F>> = OnceLock::new();
+    EMPTY.get_or_init(|| {
+        let mut hashes = vec![F::ZERO];
+        for height in 0..DEPTH {
+            hashes.push(hash_nodes(hashes[height], hashes[height]));
+        }
+        hashes
+    })
+}
+
+/// Sparse Merkle tree over 256-bit keys
+#[derive(Debug, Clone, Default)]
+pub struct SparseMerkleTree {
+    keys: BTreeSet<SmtKey>,
+    /// Non-empty nodes by height and subtree
+    nodes: HashMap<(usize, SmtKey), F>,
+}
+
+impl SparseMerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &SmtKey) -> bool {
+        self.keys.contains(key)
+    }
+
+    pub fn root(&self) -> F {
+        self.node(DEPTH, &[0u8; 32])
+    }
+
+    /// Add `key`; returns false if it was already present
+    pub fn insert(&mut self, key: SmtKey) -> bool {
+        if !self.keys.insert(key) {
+            return false;
+        }
+        self.update_path(&key, leaf_hash(&key));
+        true
+    }
+
+    /// Remove `key`; returns false if it was absent
+    pub fn remove(&mut self, key: &SmtKey) -> bool {
+        if !self.keys.remove(key) {
+            return false;
+        }
+        self.update_path(key, F::ZERO);
+        true
+    }
+
+    /// Path showing `key` is in the tree
+    pub fn membership_witness(&self, key: &SmtKey) -> Result<SmtWitness> {
+        if !self.contains(key) {
+            return Err(ZKPError::InvalidInput(format!("key {} is not in the tree", hex::encode(key))));
+        }
+        Ok(self.witness(key))
+    }
+
+    /// Path showing `key` is not in the tree
+    pub fn non_membership_witness(&self, key: &SmtKey) -> Result<SmtWitness> {
+        if self.contains(key) {
+            return Err(ZKPError::InvalidInput(format!("key {} is in the tree", hex::encode(key))));
+        }
+        Ok(self.witness(key))
+    }
+
+    fn witness(&self, key: &SmtKey) -> SmtWitness {
+        SmtWitness {
+            key: *key,
+            siblings: (0..DEPTH).map(|height| self.node(height, &sibling(key, height))).collect(),
+        }
+    }
+
+    fn node(&self, height: usize, prefix: &SmtKey) -> F {
+        self.nodes.get(&(height, *prefix)).copied().unwrap_or(empty_hashes()[height])
+    }
+
+    fn set_node(&mut self, height: usize, prefix: SmtKey, value: F) {
+        if value == empty_hashes()[height] {
+            self.nodes.remove(&(height, prefix));
+        } else {
+            self.nodes.insert((height, prefix), value);
+        }
+    }
+
+    fn update_path(&mut self, key: &SmtKey, leaf: F) {
+        self.set_node(0, *key, leaf);
+        let mut node = leaf;
+        for height in 0..DEPTH {
+            let sibling = self.node(height, &sibling(key, height));
+            node = match key_bit(key, height) {
+                false => hash_nodes(node, sibling),
+                true => hash_nodes(sibling, node),
+            };
+            self.set_node(height + 1, subtree(key, height + 1), node);
+        }
+    }
+}
+
+/// Authentication path of one key, usable for membership or non-membership
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmtWitness {
+    pub key: SmtKey,
+    /// Sibling hashes from the leaf level upwards
+    pub siblings: Vec<F>,
+}
+
+impl SmtWitness {
+    /// Root implied by the path with `leaf` at the key's position
+    pub fn compute_root(&self, leaf: F) -> F {
+        self.siblings.iter().enumerate().fold(leaf, |node, (height, &sibling)| match key_bit(&self.key, height) {
+            false => hash_nodes(node, sibling),
+            true => hash_nodes(sibling, node),
+        })
+    }
+
+    pub fn verify_membership(&self, root: F) -> bool {
+        self.siblings.len() == DEPTH && self.compute_root(leaf_hash(&self.key)) == root
+    }
+
+    pub fn verify_non_membership(&self, root: F) -> bool {
+        self.siblings.len() == DEPTH && self.compute_root(F::ZERO) == root
+    }
+}
+
+/// In-circuit sparse Merkle path check
+///
+/// Occupies a Poseidon2 gadget, a direction column, a limb accumulator and
+/// one column per key limb starting at `column_offset`. The first section
+/// hashes the key limbs, then one node section per level hashes the running
+/// node with its sibling on the side the direction bit selects. The
+/// direction bits recompose the hashed limbs through the accumulator and the
+/// limb columns, which hold each limb down the whole trace, so the path is
+/// the key's own; membership starts from the key's leaf hash, non-membership
+/// from the empty leaf.
+#[derive(Debug, Clone, Copy)]
+pub struct SmtPathGadget {
+    pub column_offset: usize,
+}
+
+impl SmtPathGadget {
+    /// Poseidon2 columns plus the direction, accumulator and limb columns
+    pub const COLUMNS: usize = Poseidon2Gadget::COLUMNS + 2 + KEY_LIMBS;
+
+    pub fn new(column_offset: usize) -> Self {
+        Self { column_offset }
+    }
+
+    /// Trace rows one path occupies
+    pub fn rows() -> usize {
+        Self::node_start(DEPTH)
+    }
+
+    fn node_start(level: usize) -> usize {
+        Poseidon2Gadget::rows_for(KEY_LIMBS) + level * Poseidon2Gadget::rows_for(2)
+    }
+
+    fn hasher(&self) -> Poseidon2Gadget {
+        Poseidon2Gadget::new(self.column_offset)
+    }
+
+    fn direction_column(&self) -> usize {
+        self.column_offset + Poseidon2Gadget::COLUMNS
+    }
+
+    fn accumulator_column(&self) -> usize {
+        self.direction_column() + 1
+    }
+
+    /// Column holding key limb `limb` on every row
+    pub fn limb_column(&self, limb: usize) -> usize {
+        self.direction_column() + 2 + limb
+    }
+
+    /// Trace cell (row offset, column) holding key limb `limb`, for binding the key elsewhere
+    pub fn key_cell(&self, limb: usize) -> (usize, usize) {
+        self.hasher().absorb_cell(limb)
+    }
+
+    /// Fill the path from `start_row` and return the root it reaches
+    pub fn generate_trace(&self, trace: &mut ExecutionTrace, start_row: usize, witness: &SmtWitness, membership: bool) -> F {
+        let gadget = self.hasher();
+        let limbs = key_limbs(&witness.key);
+        let key_digest = gadget.generate_trace(trace, start_row, &limbs);
+        for row in 0..trace.height {
+            for (limb, &value) in limbs.iter().enumerate() {
+                trace.set(row, self.limb_column(limb), value);
+            }
+        }
+
+        let mut node = if membership { key_digest } else { F::ZERO };
+        let mut accumulator = 0u64;
+        for (level, &sibling) in witness.siblings.iter().enumerate().take(DEPTH) {
+            let row = start_row + Self::node_start(level);
+            let bit = key_bit(&witness.key, level);
+            let pair = if bit { [sibling, node] } else { [node, sibling] };
+            node = gadget.generate_trace(trace, row, &pair);
+            trace.set(row, self.direction_column(), F::new(bit as u64));
+
+            // The accumulator restarts with each limb and holds until the next level
+            if level % LIMB_BITS == 0 {
+                accumulator = 0;
+            }
+            accumulator += (bit as u64) << (level % LIMB_BITS);
+            for offset in 0..Poseidon2Gadget::rows_for(2) {
+                trace.set(row + offset, self.accumulator_column(), F::new(accumulator));
+            }
+        }
+        node
+    }
+
+    /// Constrain a path from `start_row` to `root`, labelling with the prefix `name`
+    pub fn constrain(&self, system: &mut ConstraintSystem, name: &str, start_row: usize, membership: bool, root: F) {
+        let gadget = self.hasher();
+        let mut sections = vec![(start_row, KEY_LIMBS)];
+        sections.extend((0..DEPTH).map(|level| (start_row + Self::node_start(level), 2)));
+        gadget.constrain(system, &format!("{}_hash", name), &sections);
+
+        let node_rows = (0..DEPTH).map(|level| start_row + Self::node_start(level));
+        let node = system.selector(node_rows.clone());
+        let limb_starts = system.selector((0..KEY_LIMBS).map(|limb| start_row + Self::node_start(limb * LIMB_BITS)));
+        let weight = system.fixed(node_rows.enumerate().map(|(level, row)| (row, F::new(1 << (level % LIMB_BITS)))));
+        let path = system.selector(start_row + Self::node_start(0)..start_row + Self::rows());
+        let direction = Expr::cell(self.direction_column());
+        let accumulator = Expr::cell(self.accumulator_column());
+        let one = F::ONE;
+
+        // Direction bits, least significant first, accumulate into each limb
+        system.constrain(format!("{}_direction_boolean", name), &direction * (&direction - one));
+        let previous = Expr::rotated(self.accumulator_column(), -1);
+        system.constrain(
+            format!("{}_accumulator", name),
+            &path * (&accumulator - (one - limb_starts) * previous - &direction * weight),
+        );
+        for limb in 0..KEY_LIMBS {
+            system.wire(format!("{}_limb_{}_constant", name, limb), self.limb_column(limb));
+            let (row, column) = gadget.absorb_cell(limb);
+            let hashed = Expr::cell(column) - Expr::cell(self.limb_column(limb));
+            system.constrain_at(start_row + row, format!("{}_limb_{}_hashed", name, limb), hashed);
+            let recomposed = &accumulator - Expr::cell(self.limb_column(limb));
+            system.constrain_at(start_row + Self::node_start(limb * LIMB_BITS + LIMB_BITS - 1), format!("{}_limb_{}", name, limb), recomposed);
+        }
+
+        // Each node enters its parent on the side its direction bit selects;
+        // the child is the digest on the row above, or the empty leaf
+        let child_rows = (0..DEPTH).filter(|&level| membership || level > 0);
+        let child = system.selector(child_rows.map(|level| start_row + Self::node_start(level)));
+        let child = child * Expr::rotated(self.column_offset, -1);
+        let [left, right] = [0, 1].map(|position| Expr::cell(gadget.absorb_cell(position).1));
+        system.constrain(format!("{}_left_child", name), &node * (one - &direction) * (left - &child));
+        system.constrain(format!("{}_right_child", name), &node * &direction * (right - child));
+        system.constrain_at(start_row + Self::rows() - 1, format!("{}_root", name), Expr::cell(self.column_offset) - root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> SmtKey {
+        let mut key = [0u8; 32];
+        key[0] = byte;
+        key[31] = byte.wrapping_mul(7);
+        key
+    }
+
+    #[test]
+    fn test_witnesses_and_gadget_for_both_directions() {
+        let mut tree = SparseMerkleTree::new();
+        let empty_root = tree.root();
+        assert!(tree.insert(key(1)) && tree.insert(key(2)) && !tree.insert(key(1)));
+        assert_eq!(tree.len(), 2);
+
+        let member = tree.membership_witness(&key(1)).unwrap();
+        let absent = tree.non_membership_witness(&key(3)).unwrap();
+        assert!(member.verify_membership(tree.root()) && !member.verify_non_membership(tree.root()));
+        assert!(absent.verify_non_membership(tree.root()) && !absent.verify_membership(tree.root()));
+        assert!(tree.membership_witness(&key(3)).is_err() && tree.non_membership_witness(&key(2)).is_err());
+
+        let gadget = SmtPathGadget::new(1);
+        let satisfied = |witness: &SmtWitness, membership: bool, tamper: bool| {
+            let height = SmtPathGadget::rows().next_power_of_two();
+            let mut trace = ExecutionTrace::new(1 + SmtPathGadget::COLUMNS, height);
+            assert_eq!(gadget.generate_trace(&mut trace, 0, witness, membership), tree.root());
+            if tamper {
+                // Claim a different key by flipping the lowest hashed limb bit
+                let (row, col) = gadget.key_cell(0);
+                trace.set(row, col, trace.get(row, col) + F::ONE);
+            }
+            let mut system = ConstraintSystem::new(height);
+            gadget.constrain(&mut system, "path", 0, membership, tree.root());
+            system.check(&trace).is_ok()
+        };
+        assert!(satisfied(&member, true, false));
+        assert!(satisfied(&absent, false, false));
+        assert!(!satisfied(&member, true, true));
+        assert!(!satisfied(&absent, false, true));
+
+        assert!(tree.remove(&key(1)) && tree.remove(&key(2)));
+        assert_eq!(tree.root(), empty_root);
+        assert!(tree.nodes.is_empty());
+    }
+}
@@ -0,0 +1,62 @@
+//! Stateless Verification
+//!
+//! A pure `verify_stark` over proof bytes with no verifier object, caches or
+//! per-call buffers, written as the reference for on-chain verifier transpilation
+
+use crate::circuits::CircuitShape;
+use crate::custom_stark::{check_stark_proof, StarkParams, StarkProof};
+use crate::decoding::decode_stark_proof;
+use crate::limits::ProofLimits;
+use crate::{Result, F};
+
+/// Verify the operation-independent STARK checks of an encoded proof
+///
+/// Returns `Err` only when the bytes do not decode to a well-formed proof and
+/// `Ok(false)` for any failed check. The constraint system is rebuilt from
+/// the proof's shape and checked at every query. Proofs bound to a tenant are
+/// rejected, as are public inputs that differ from the ones the proof commits
+/// to and proofs of application-defined AIRs. The only shared data read are
+/// the constant Poseidon2 round-constant table and the NTT domain cache.
+pub fn verify_stark(proof_bytes: &[u8], public_inputs: &[F], params: &StarkParams) -> Result<bool> {
+    let proof = decode_stark_proof(proof_bytes, &ProofLimits::default())?;
+    Ok(check_stark(&proof, public_inputs, params))
+}
+
+fn check_stark(proof: &StarkProof, public_inputs: &[F], params: &StarkParams) -> bool {
+    if proof.public_inputs != public_inputs || matches!(proof.shape, CircuitShape::Air { .. }) {
+        return false;
+    }
+    match proof.shape.build(public_inputs) {
+        Ok(circuit) => check_stark_proof(proof, &circuit.system, params, None, None).is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_verify_stark_matches_system_verifier() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest").unwrap();
+        let proof = &result.proof;
+        let params = SecurityLevel::Fast.params();
+
+        assert!(verify_stark(&proof.proof_data, &proof.public_inputs, &params).unwrap());
+
+        let mut tampered_inputs = proof.public_inputs.clone();
+        tampered_inputs[0] += F::ONE;
+        assert!(!verify_stark(&proof.proof_data, &tampered_inputs, &params).unwrap());
+        assert!(!verify_stark(&proof.proof_data, &proof.public_inputs, &SecurityLevel::Standard.params()).unwrap());
+        assert!(verify_stark(&proof.proof_data[..16], &proof.public_inputs, &params).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! Storage
+//!
+//! One key-value interface behind every subsystem that outlives a process:
+//! the proof registry, replay guard, score ledger and verification sessions.
+//! Each subsystem keeps its working set in memory, writes through to a
+//! `Storage` under its own namespace and restores from it on open, so a
+//! deployment picks the backend (in-memory, sled or Postgres) once.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::Result;
+
+/// Namespaced byte store shared by the persistent subsystems
+///
+/// Writes are visible to later reads on the same store at once; `commit`
+/// makes every write before it durable.
+pub trait Storage: Debug + Send + Sync {
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<()>;
+
+    fn remove(&self, namespace: &str, key: &[u8]) -> Result<()>;
+
+    /// Every entry of `namespace`, in byte order of the keys
+    fn iterate(&self, namespace: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>>;
+
+    fn commit(&self) -> Result<()>;
+}
+
+/// Wire encoding of a stored value
+pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    crate::wire::to_bytes(value)
+}
+
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    crate::wire::from_bytes(bytes)
+}
+
+/// Entries of one namespace, in key order
+type Namespace = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Process-local store, for tests and single-process deployments
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    namespaces: Mutex<BTreeMap<String, Namespace>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let namespaces = self.namespaces.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(namespaces.get(namespace).and_then(|entries| entries.get(key)).cloned())
+    }
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        self.namespaces
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(namespace.to_string())
+            .or_default()
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn remove(&self, namespace: &str, key: &[u8]) -> Result<()> {
+        if let Some(entries) = self.namespaces.lock().unwrap_or_else(|e| e.into_inner()).get_mut(namespace) {
+            entries.remove(key);
+        }
+        Ok(())
+    }
+
+    fn iterate(&self, namespace: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let namespaces = self.namespaces.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(namespaces
+            .get(namespace)
+            .map(|entries| entries.iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+            .unwrap_or_default())
+    }
+
+    fn commit(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Embedded sled database, one tree per namespace
+#[cfg(feature = "sled")]
+#[derive(Debug, Clone)]
+pub struct SledStorage {
+    db: sled::Db,
+}
+
+#[cfg(feature = "sled")]
+impl SledStorage {
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        sled::open(path).map(Self::from_db).map_err(storage_error)
+    }
+
+    pub fn from_db(db: sled::Db) -> Self {
+        Self { db }
+    }
+
+    fn tree(&self, namespace: &str) -> Result<sled::Tree> {
+        self.db.open_tree(namespace).map_err(storage_error)
+    }
+}
+
+#[cfg(feature = "sled")]
+impl Storage for SledStorage {
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.tree(namespace)?.get(key).map_err(storage_error)?.map(|value| value.to_vec()))
+    }
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        self.tree(namespace)?.insert(key, value).map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn remove(&self, namespace: &str, key: &[u8]) -> Result<()> {
+        self.tree(namespace)?.remove(key).map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn iterate(&self, namespace: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.tree(namespace)?
+            .iter()
+            .map(|entry| entry.map(|(key, value)| (key.to_vec(), value.to_vec())).map_err(storage_error))
+            .collect()
+    }
+
+    fn commit(&self) -> Result<()> {
+        self.db.flush().map_err(storage_error)?;
+        Ok(())
+    }
+}
+
+/// Postgres table shared by every namespace
+///
+/// Writes open a transaction on the connection that `commit` closes, so
+/// a crash between commits loses the uncommitted writes together.
+#[cfg(feature = "postgres")]
+pub struct PostgresStorage {
+    connection: Mutex<PostgresConnection>,
+}
+
+#[cfg(feature = "postgres")]
+struct PostgresConnection {
+    client: postgres::Client,
+    in_transaction: bool,
+}
+
+#[cfg(feature = "postgres")]
+impl Debug for PostgresStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PostgresStorage").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresStorage {
+    /// Connect without TLS, e.g. to a database on the same host
+    pub fn connect(params: &str) -> Result<Self> {
+        let client = postgres::Client::connect(params, postgres::NoTls).map_err(storage_error)?;
+        Self::from_client(client)
+    }
+
+    /// Store on an open connection, creating the table if needed
+    pub fn from_client(mut client: postgres::Client) -> Result<Self> {
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS repid_storage (
+                    namespace TEXT NOT NULL,
+                    key BYTEA NOT NULL,
+                    value BYTEA NOT NULL,
+                    PRIMARY KEY (namespace, key)
+                )",
+            )
+            .map_err(storage_error)?;
+        Ok(Self {
+            connection: Mutex::new(PostgresConnection { client, in_transaction: false }),
+        })
+    }
+
+    fn write(&self, statement: &str, params: &[&(dyn postgres::types::ToSql + Sync)]) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        if !connection.in_transaction {
+            connection.client.batch_execute("BEGIN").map_err(storage_error)?;
+            connection.in_transaction = true;
+        }
+        connection.client.execute(statement, params).map_err(storage_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl Storage for PostgresStorage {
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let mut connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let row = connection
+            .client
+            .query_opt("SELECT value FROM repid_storage WHERE namespace = $1 AND key = $2", &[&namespace, &key])
+            .map_err(storage_error)?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        self.write(
+            "INSERT INTO repid_storage (namespace, key, value) VALUES ($1, $2, $3)
+             ON CONFLICT (namespace, key) DO UPDATE SET value = EXCLUDED.value",
+            &[&namespace, &key, &value],
+        )
+    }
+
+    fn remove(&self, namespace: &str, key: &[u8]) -> Result<()> {
+        self.write("DELETE FROM repid_storage WHERE namespace = $1 AND key = $2", &[&namespace, &key])
+    }
+
+    fn iterate(&self, namespace: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let mut connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let rows = connection
+            .client
+            .query("SELECT key, value FROM repid_storage WHERE namespace = $1 ORDER BY key", &[&namespace])
+            .map_err(storage_error)?;
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    fn commit(&self) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        if connection.in_transaction {
+            connection.client.batch_execute("COMMIT").map_err(storage_error)?;
+            connection.in_transaction = false;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(any(feature = "sled", feature = "postgres"))]
+fn storage_error(e: impl std::fmt::Display) -> ZKPError {
+    ZKPError::StorageError(e.to_string())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::ledger::{MerkleLedger, ScoreUpdate};
+    use crate::registry::{ProofRegistry, RetentionPolicy};
+    use crate::replay::ReplayGuard;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+    use std::sync::Arc;
+
+    fn check_backend(storage: &dyn Storage) {
+        storage.put("a", b"k2", b"two").unwrap();
+        storage.put("a", b"k1", b"one").unwrap();
+        storage.put("b", b"k1", b"other").unwrap();
+        storage.put("a", b"k2", b"TWO").unwrap();
+        storage.commit().unwrap();
+        assert_eq!(storage.get("a", b"k2").unwrap(), Some(b"TWO".to_vec()));
+        assert_eq!(storage.iterate("a").unwrap(), vec![(b"k1".to_vec(), b"one".to_vec()), (b"k2".to_vec(), b"TWO".to_vec())]);
+        storage.remove("a", b"k1").unwrap();
+        assert_eq!(storage.get("a", b"k1").unwrap(), None);
+        assert_eq!(storage.iterate("b").unwrap().len(), 1);
+        assert!(storage.iterate("missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_subsystems_restore_from_shared_storage() {
+        check_backend(&MemoryStorage::new());
+        #[cfg(feature = "sled")]
+        check_backend(&SledStorage::from_db(sled::Config::new().temporary(true).open().unwrap()));
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let proof = RepIDZKPSystem::new(SecurityLevel::Fast)
+            .prove_threshold_verification(&request, &[(RepIDCategory::Community, 70)], "0xtest")
+            .unwrap()
+            .proof;
+
+        let clock = Arc::new(FixedClock::new(1_000));
+        let registry = ProofRegistry::open(RetentionPolicy::keep_all().with_max_epochs(1), clock.clone(), storage.clone()).unwrap();
+        let id = registry.insert(proof, 1).unwrap();
+        let mut guard = ReplayGuard::open(2, storage.clone()).unwrap();
+        guard.check_request(&request, "wallet-a", 10).unwrap();
+        let mut ledger = MerkleLedger::open(8, storage.clone()).unwrap();
+        for epoch in [1, 2] {
+            ledger.append(ScoreUpdate {
+                wallet_hash: "0xabc".to_string(),
+                category: RepIDCategory::Community,
+                score: 40,
+                timestamp: 1_700_000_000,
+                epoch,
+            }).unwrap();
+        }
+        storage.commit().unwrap();
+
+        // A restarted process sees the same state
+        let reopened = ProofRegistry::open(RetentionPolicy::keep_all().with_max_epochs(1), clock, storage.clone()).unwrap();
+        assert!(reopened.get(&id).is_some());
+        let mut guard = ReplayGuard::open(2, storage.clone()).unwrap();
+        assert!(guard.check_request(&request, "wallet-a", 10).is_err());
+        let restored = MerkleLedger::open(8, storage.clone()).unwrap();
+        assert_eq!((restored.root(), restored.epoch_root(1)), (ledger.root(), ledger.epoch_root(1)));
+
+        // Compaction removes the stored copy and persists its tombstone
+        assert_eq!(reopened.compact(5).unwrap(), 1);
+        let compacted = ProofRegistry::open(RetentionPolicy::keep_all(), Arc::new(FixedClock::new(0)), storage).unwrap();
+        assert!(compacted.is_empty());
+        assert_eq!(compacted.tombstones()[0].id, id);
+    }
+}
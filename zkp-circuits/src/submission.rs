@@ -0,0 +1,360 @@
+//! Batched Result Submission
+//!
+//! Packs many users' verification results into one calldata payload with a
+//! shared SHA-256 Merkle root, so nightly result posting pays the transaction
+//! and signature overhead once. Users later show their own result against the
+//! stored root with an inclusion path.
+//!
+//! The byte layout is fixed-width and big-endian; `decode_spec` describes it
+//! for contract and indexer authors, and `decode_submission` is the reference
+//! decoder.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::evm::{ChainProfile, GasBreakdown};
+use crate::{Result, SolidityVerificationData, ZKPError};
+
+/// Layout version written in the payload header
+pub const SUBMISSION_VERSION: u8 = 2;
+/// Header bytes: version, batch id, entry count, results root
+pub const HEADER_SIZE: usize = 1 + 8 + 4 + 32;
+/// Bytes per packed entry
+pub const ENTRY_SIZE: usize = 32 + 32 + 32 + 32 + 8 + 4 + 1;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// One user's result as packed into a submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmissionEntry {
+    /// SHA-256 of the wallet address
+    pub subject: [u8; 32],
+    /// Keccak-256 of the proof bytes, as in `SolidityVerificationData::proof_hash`
+    pub proof_hash: [u8; 32],
+    pub public_inputs_digest: [u8; 32],
+    /// SHA-256 of the operation type
+    pub proof_type: [u8; 32],
+    pub timestamp: u64,
+    pub proof_size: u32,
+    pub meets_threshold: bool,
+}
+
+impl SubmissionEntry {
+    /// Pack `data` for `wallet_address`
+    pub fn new(wallet_address: &str, data: &SolidityVerificationData, meets_threshold: bool) -> Result<Self> {
+        let proof_size = u32::try_from(data.proof_size)
+            .map_err(|_| ZKPError::InvalidInput(format!("proof size {} does not fit a submission entry", data.proof_size)))?;
+        Ok(Self {
+            subject: Sha256::digest(wallet_address.as_bytes()).into(),
+            proof_hash: decode_hex_word(&data.proof_hash, "proof hash")?,
+            public_inputs_digest: decode_hex_word(&data.public_inputs_digest, "public inputs digest")?,
+            proof_type: Sha256::digest(data.proof_type.as_bytes()).into(),
+            timestamp: data.timestamp,
+            proof_size,
+            meets_threshold,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; ENTRY_SIZE] {
+        let mut bytes = [0u8; ENTRY_SIZE];
+        let fields: [&[u8]; 7] = [
+            &self.subject,
+            &self.proof_hash,
+            &self.public_inputs_digest,
+            &self.proof_type,
+            &self.timestamp.to_be_bytes(),
+            &self.proof_size.to_be_bytes(),
+            &[self.meets_threshold as u8],
+        ];
+        let mut offset = 0;
+        for field in fields {
+            bytes[offset..offset + field.len()].copy_from_slice(field);
+            offset += field.len();
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != ENTRY_SIZE {
+            return Err(ZKPError::MalformedProof(format!("submission entry is {} bytes, expected {}", bytes.len(), ENTRY_SIZE)));
+        }
+        let meets_threshold = match bytes[ENTRY_SIZE - 1] {
+            0 => false,
+            1 => true,
+            flag => return Err(ZKPError::MalformedProof(format!("invalid result flag {}", flag))),
+        };
+        let word = |range: std::ops::Range<usize>| bytes[range].to_vec();
+        Ok(Self {
+            subject: word(0..32).try_into().expect("32-byte slice"),
+            proof_hash: word(32..64).try_into().expect("32-byte slice"),
+            public_inputs_digest: word(64..96).try_into().expect("32-byte slice"),
+            proof_type: word(96..128).try_into().expect("32-byte slice"),
+            timestamp: u64::from_be_bytes(word(128..136).try_into().expect("8-byte slice")),
+            proof_size: u32::from_be_bytes(word(136..140).try_into().expect("4-byte slice")),
+            meets_threshold,
+        })
+    }
+
+    /// Merkle leaf: SHA-256 of `0x00 || entry`
+    pub fn leaf(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([LEAF_PREFIX]);
+        hasher.update(self.to_bytes());
+        hasher.finalize().into()
+    }
+}
+
+fn decode_hex_word<const N: usize>(value: &str, name: &str) -> Result<[u8; N]> {
+    let bytes = hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| ZKPError::InvalidInput(format!("{} is not hex: {}", name, e)))?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| ZKPError::InvalidInput(format!("{} is {} bytes, expected {}", name, bytes.len(), N)))
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Tree levels from the leaves up, padded with zero leaves to a power of two
+fn tree_levels(entries: &[SubmissionEntry]) -> Vec<Vec<[u8; 32]>> {
+    let mut level: Vec<[u8; 32]> = entries.iter().map(SubmissionEntry::leaf).collect();
+    level.resize(entries.len().next_power_of_two(), [0u8; 32]);
+    let mut levels = vec![level];
+    while levels.last().expect("at least the leaf level").len() > 1 {
+        let next = levels.last().expect("at least the leaf level").chunks(2).map(|pair| hash_node(&pair[0], &pair[1])).collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Builder collecting one batch of results
+#[derive(Debug, Clone, Default)]
+pub struct BatchSubmission {
+    batch_id: u64,
+    entries: Vec<SubmissionEntry>,
+}
+
+impl BatchSubmission {
+    pub fn new(batch_id: u64) -> Self {
+        Self {
+            batch_id,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add one user's result
+    pub fn with_result(mut self, wallet_address: &str, data: &SolidityVerificationData, meets_threshold: bool) -> Result<Self> {
+        self.entries.push(SubmissionEntry::new(wallet_address, data, meets_threshold)?);
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Pack the entries and compute their results root
+    pub fn build(self) -> Result<SubmissionPayload> {
+        if self.entries.is_empty() {
+            return Err(ZKPError::InvalidInput("a submission needs at least one result".to_string()));
+        }
+        let count = u32::try_from(self.entries.len())
+            .map_err(|_| ZKPError::InvalidInput(format!("{} results do not fit one submission", self.entries.len())))?;
+        let levels = tree_levels(&self.entries);
+        let results_root = levels.last().expect("at least the leaf level")[0];
+
+        let mut calldata = Vec::with_capacity(HEADER_SIZE + ENTRY_SIZE * self.entries.len());
+        calldata.push(SUBMISSION_VERSION);
+        calldata.extend_from_slice(&self.batch_id.to_be_bytes());
+        calldata.extend_from_slice(&count.to_be_bytes());
+        calldata.extend_from_slice(&results_root);
+        for entry in &self.entries {
+            calldata.extend_from_slice(&entry.to_bytes());
+        }
+
+        Ok(SubmissionPayload {
+            batch_id: self.batch_id,
+            results_root,
+            calldata,
+            levels,
+        })
+    }
+}
+
+/// Packed batch ready to post
+#[derive(Debug, Clone)]
+pub struct SubmissionPayload {
+    pub batch_id: u64,
+    pub results_root: [u8; 32],
+    /// Header followed by the packed entries
+    pub calldata: Vec<u8>,
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl SubmissionPayload {
+    pub fn len(&self) -> usize {
+        (self.calldata.len() - HEADER_SIZE) / ENTRY_SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sibling hashes from the leaf of entry `index` up to the root
+    pub fn inclusion_path(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        if index >= self.len() {
+            return None;
+        }
+        let depth = self.levels.len() - 1;
+        Some((0..depth).map(|level| self.levels[level][(index >> level) ^ 1]).collect())
+    }
+
+    /// Model the cost of posting this batch with an operator signature over the root
+    ///
+    /// Only the root is stored; entries are read back from calldata by indexers.
+    pub fn estimate_gas(&self, chain_profile: &ChainProfile) -> GasBreakdown {
+        let p = chain_profile;
+        // Selector, ABI offset and length words, and a 65-byte signature
+        let abi_overhead = 4 + 2 * 32 + 65;
+        GasBreakdown {
+            calldata: (abi_overhead + self.calldata.len() as u64) * p.calldata_byte,
+            hashing: p.keccak_base + p.keccak_word * 2,
+            storage: p.sstore_new,
+            execution: p.tx_base + p.ecrecover,
+        }
+    }
+}
+
+/// Check `entry` sits at `index` under `results_root`
+pub fn verify_inclusion(results_root: &[u8; 32], entry: &SubmissionEntry, index: usize, path: &[[u8; 32]]) -> bool {
+    let root = path.iter().enumerate().fold(entry.leaf(), |node, (level, sibling)| {
+        match (index >> level) & 1 {
+            0 => hash_node(&node, sibling),
+            _ => hash_node(sibling, &node),
+        }
+    });
+    index >> path.len() == 0 && root == *results_root
+}
+
+/// Decoded submission payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedSubmission {
+    pub batch_id: u64,
+    pub results_root: [u8; 32],
+    pub entries: Vec<SubmissionEntry>,
+}
+
+/// Decode a payload and check its entries hash to the root in its header
+pub fn decode_submission(calldata: &[u8]) -> Result<DecodedSubmission> {
+    if calldata.len() < HEADER_SIZE {
+        return Err(ZKPError::MalformedProof(format!("submission is {} bytes, shorter than its header", calldata.len())));
+    }
+    if calldata[0] != SUBMISSION_VERSION {
+        return Err(ZKPError::MalformedProof(format!("unsupported submission version {}", calldata[0])));
+    }
+    let batch_id = u64::from_be_bytes(calldata[1..9].try_into().expect("8-byte slice"));
+    let count = u32::from_be_bytes(calldata[9..13].try_into().expect("4-byte slice")) as usize;
+    let results_root: [u8; 32] = calldata[13..HEADER_SIZE].try_into().expect("32-byte slice");
+
+    let body = &calldata[HEADER_SIZE..];
+    if count == 0 || body.len() != count * ENTRY_SIZE {
+        return Err(ZKPError::MalformedProof(format!("submission body is {} bytes for {} entries", body.len(), count)));
+    }
+    let entries = body.chunks(ENTRY_SIZE).map(SubmissionEntry::from_bytes).collect::<Result<Vec<_>>>()?;
+    if tree_levels(&entries).last().expect("at least the leaf level")[0] != results_root {
+        return Err(ZKPError::VerificationError("submission entries do not match the results root".to_string()));
+    }
+    Ok(DecodedSubmission { batch_id, results_root, entries })
+}
+
+/// Machine-readable description of the payload layout and tree hashing
+pub fn decode_spec() -> serde_json::Value {
+    let field = |name: &str, ty: &str, bytes: usize, description: &str| {
+        json!({ "name": name, "type": ty, "bytes": bytes, "description": description })
+    };
+    json!({
+        "version": SUBMISSION_VERSION,
+        "encoding": "packed, big-endian, no padding",
+        "header_size": HEADER_SIZE,
+        "header": [
+            field("version", "uint8", 1, "layout version"),
+            field("batchId", "uint64", 8, "submitter-assigned batch id"),
+            field("count", "uint32", 4, "number of entries"),
+            field("resultsRoot", "bytes32", 32, "Merkle root over the entries"),
+        ],
+        "entry_size": ENTRY_SIZE,
+        "entry": [
+            field("subject", "bytes32", 32, "sha256(wallet address)"),
+            field("proofHash", "bytes32", 32, "keccak256(proof bytes)"),
+            field("publicInputsDigest", "bytes32", 32, "digest of the proof's public inputs"),
+            field("proofType", "bytes32", 32, "sha256(operation type)"),
+            field("timestamp", "uint64", 8, "proof generation time, unix seconds"),
+            field("proofSize", "uint32", 4, "proof size in bytes"),
+            field("meetsThreshold", "uint8", 1, "1 if the threshold was met, else 0"),
+        ],
+        "tree": {
+            "hash": "sha256",
+            "leaf": "sha256(0x00 || entry)",
+            "node": "sha256(0x01 || left || right)",
+            "padding": "zero bytes32 leaves up to the next power of two",
+            "path": "siblings from the leaf upward; bit i of the index set means the sibling is on the left"
+        }
+    })
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_batch_round_trips_and_amortizes_gas() {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let mut batch = BatchSubmission::new(7);
+        let mut single_gas = 0;
+        for (i, score) in [75, 60, 90].into_iter().enumerate() {
+            let wallet = format!("0xuser{}", i);
+            let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Community, score)], &wallet).unwrap();
+            let data = zkp_system.extract_solidity_verification_data(&result.proof);
+            single_gas += data.estimate_gas(&ChainProfile::ethereum()).signed_result.total();
+            batch = batch.with_result(&wallet, &data, result.meets_threshold).unwrap();
+        }
+        let payload = batch.build().unwrap();
+        assert_eq!(payload.calldata.len(), HEADER_SIZE + 3 * ENTRY_SIZE);
+        assert!(payload.estimate_gas(&ChainProfile::ethereum()).total() < single_gas);
+
+        let decoded = decode_submission(&payload.calldata).unwrap();
+        assert_eq!((decoded.batch_id, decoded.results_root), (7, payload.results_root));
+        assert_eq!(decoded.entries.iter().map(|e| e.meets_threshold).collect::<Vec<_>>(), vec![true; 3]);
+        assert_eq!(decoded.entries[1].subject, <[u8; 32]>::from(Sha256::digest(b"0xuser1")));
+        for (index, entry) in decoded.entries.iter().enumerate() {
+            assert!(verify_inclusion(&payload.results_root, entry, index, &payload.inclusion_path(index).unwrap()));
+        }
+        let flipped = SubmissionEntry { meets_threshold: false, ..decoded.entries[1] };
+        assert!(!verify_inclusion(&payload.results_root, &flipped, 1, &payload.inclusion_path(1).unwrap()));
+
+        // Tampering with any entry breaks the header root
+        let mut tampered = payload.calldata.clone();
+        tampered[HEADER_SIZE + ENTRY_SIZE - 1] ^= 1;
+        assert!(decode_submission(&tampered).is_err());
+        assert_eq!(decode_spec()["entry_size"], ENTRY_SIZE);
+        assert!(BatchSubmission::new(8).build().is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! Sustained Reputation
+//!
+//! Threshold proofs over the decayed aggregate of a wallet's scores across
+//! the last N epoch snapshots, so "sustained reputation over six months" is
+//! provable against published snapshot roots rather than a single instant
+
+use serde::{Deserialize, Serialize};
+
+use crate::epoch::SnapshotRoot;
+use crate::{RepIDProof, Result, ZKPError, F};
+
+/// Operation type of sustained threshold proofs
+pub const SUSTAINED_THRESHOLD_OPERATION: &str = "sustained_threshold";
+
+/// Fixed-point scale of epoch weights (basis points)
+pub const WEIGHT_SCALE: u32 = 10_000;
+
+/// Weight of each epoch by age (index 0 is the newest), in basis points
+///
+/// Every epoch further back keeps `1 - decay_rate_bps / 10000` of the weight
+/// of the next, rounded down, so the verifier can rederive the weights.
+pub fn epoch_weights(decay_rate_bps: u16, num_epochs: usize) -> Vec<u32> {
+    let retained = WEIGHT_SCALE.saturating_sub(decay_rate_bps as u32);
+    std::iter::successors(Some(WEIGHT_SCALE), |weight| Some(weight * retained / WEIGHT_SCALE))
+        .take(num_epochs)
+        .collect()
+}
+
+/// Public statement of a sustained threshold proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SustainedStatement {
+    /// Decayed aggregate the wallet reaches, at least
+    pub threshold: u32,
+    pub decay_rate_bps: u16,
+    /// Snapshots the scores were opened against, oldest first: (epoch, root)
+    pub snapshots: Vec<(u64, F)>,
+}
+
+/// Read the statement a verified sustained threshold proof was made for
+pub fn proof_statement(proof: &RepIDProof) -> Result<SustainedStatement> {
+    if proof.metadata.operation_type != SUSTAINED_THRESHOLD_OPERATION {
+        return Err(ZKPError::PolicyViolation(format!(
+            "'{}' proofs carry no sustained threshold statement",
+            proof.metadata.operation_type
+        )));
+    }
+    let malformed = || ZKPError::MalformedProof("Sustained proof needs threshold, decay and snapshot inputs".to_string());
+    let num_epochs = proof.public_inputs.get(3).ok_or_else(malformed)?.0 as usize;
+    let snapshots = proof.public_inputs.get(4..4 + 2 * num_epochs).ok_or_else(malformed)?;
+    Ok(SustainedStatement {
+        threshold: proof.public_inputs[0].0 as u32,
+        decay_rate_bps: proof.public_inputs[1].0 as u16,
+        snapshots: snapshots.chunks(2).map(|pair| (pair[0].0, pair[1])).collect(),
+    })
+}
+
+/// Check a sustained proof was made against these published snapshot roots
+pub fn check_snapshots(proof: &RepIDProof, published: &[SnapshotRoot]) -> Result<()> {
+    for (epoch, root) in proof_statement(proof)?.snapshots {
+        match published.iter().find(|snapshot| snapshot.epoch == epoch) {
+            Some(snapshot) if snapshot.root == root => {}
+            Some(_) => return Err(ZKPError::VerificationError(format!("snapshot root of epoch {} does not match", epoch))),
+            None => return Err(ZKPError::PolicyViolation(format!("epoch {} has no published snapshot", epoch))),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::epoch::EpochManager;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel};
+
+    const MONTH: u64 = 30 * 86_400;
+
+    #[test]
+    fn test_sustained_reputation_over_six_epochs() {
+        let mut manager = EpochManager::new(0, MONTH).unwrap();
+        let categories = [RepIDCategory::Governance, RepIDCategory::Technical];
+        for month in 0..6 {
+            manager.update_score("0xsteady", RepIDCategory::Governance, 40, month * MONTH + 10).unwrap();
+            manager.update_score("0xsteady", RepIDCategory::Technical, 20, month * MONTH + 20).unwrap();
+            manager.update_score("0xspike", RepIDCategory::Governance, if month == 5 { 200 } else { 0 }, month * MONTH + 30).unwrap();
+        }
+        manager.advance_to(6 * MONTH);
+        assert_eq!(epoch_weights(1_000, 3), vec![10_000, 9_000, 8_100]);
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let steady = manager.history("0xsteady", &categories, 5, 6).unwrap();
+        let proof = zkp_system.prove_sustained_threshold(&steady, 250, 500, "0xsteady").unwrap();
+        assert!(zkp_system.verify_proof(&proof, None).unwrap());
+
+        let statement = proof_statement(&proof).unwrap();
+        assert_eq!((statement.threshold, statement.decay_rate_bps, statement.snapshots.len()), (250, 500, 6));
+        let published: Vec<SnapshotRoot> = (0..6).map(|epoch| manager.get_snapshot(epoch).unwrap()).collect();
+        assert!(check_snapshots(&proof, &published).is_ok());
+        assert!(check_snapshots(&proof, &published[1..]).is_err());
+
+        // The leaves' wallet tag is bound to the public wallet commitment
+        let policy = crate::policy::VerifyPolicy::default();
+        let steady_wallet = zkp_system.commit_snapshot_wallet("0xsteady").unwrap();
+        assert!(zkp_system.verify_proof_for_wallet(&proof, None, &steady_wallet, &policy).unwrap());
+        let spike_wallet = zkp_system.commit_snapshot_wallet("0xspike").unwrap();
+        assert!(!zkp_system.verify_proof_for_wallet(&proof, None, &spike_wallet, &policy).unwrap());
+
+        // One recent spike is a higher instantaneous score but not sustained reputation
+        let spike = manager.history("0xspike", &categories, 5, 6).unwrap();
+        assert!(zkp_system.prove_sustained_threshold(&spike, 250, 500, "0xspike").is_err());
+
+        // Scores must open against the snapshot they claim
+        let mut forged = steady.clone();
+        forged[0].leaves[0].score = 400;
+        assert!(zkp_system.prove_sustained_threshold(&forged, 250, 500, "0xsteady").is_err());
+    }
+}
@@ -0,0 +1,104 @@
+//! Group Synergies
+//!
+//! Synergy rules spanning three or more categories, with a fixed evaluation
+//! order and a stacking policy against the pairwise synergies they cover
+
+use serde::{Deserialize, Serialize};
+
+use crate::fixed_point::Q16;
+use crate::RepIDCategory;
+
+/// How a group bonus combines with the smaller synergies inside it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SynergyStacking {
+    /// Smaller groups and pairs within the group still apply
+    #[default]
+    Stack,
+    /// Smaller groups and pairs within the group are skipped once it applies
+    Exclusive,
+}
+
+/// Bonus for holding every category of a group at once
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupSynergy {
+    /// Member categories, sorted and deduplicated
+    pub categories: Vec<RepIDCategory>,
+    /// Multiplier on the summed member scores (bonus is `multiplier - 1` of the sum)
+    pub multiplier: Q16,
+    #[serde(default)]
+    pub stacking: SynergyStacking,
+}
+
+impl GroupSynergy {
+    pub fn new(mut categories: Vec<RepIDCategory>, multiplier: Q16) -> Self {
+        categories.sort();
+        categories.dedup();
+        Self {
+            categories,
+            multiplier,
+            stacking: SynergyStacking::Stack,
+        }
+    }
+
+    pub fn with_stacking(mut self, stacking: SynergyStacking) -> Self {
+        self.stacking = stacking;
+        self
+    }
+
+    /// Whether every member category is active
+    pub fn is_active(&self, active: &[RepIDCategory]) -> bool {
+        self.categories.iter().all(|c| active.contains(c))
+    }
+
+    /// Whether `categories` all belong to this group
+    pub fn covers(&self, categories: &[RepIDCategory]) -> bool {
+        categories.iter().all(|c| self.categories.contains(c))
+    }
+}
+
+/// Evaluation order: larger groups first, then configuration order
+pub fn evaluation_order(groups: &[GroupSynergy]) -> Vec<&GroupSynergy> {
+    let mut ordered: Vec<&GroupSynergy> = groups.iter().collect();
+    // Stable sort keeps configuration order among groups of equal size
+    ordered.sort_by_key(|g| std::cmp::Reverse(g.categories.len()));
+    ordered
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::hierarchical_scoring::HierarchicalScorer;
+
+    #[test]
+    fn test_triad_bonus_and_stacking() {
+        let triad = vec![RepIDCategory::Governance, RepIDCategory::Technical, RepIDCategory::Community];
+        let scores = [
+            (RepIDCategory::Governance, 100),
+            (RepIDCategory::Technical, 100),
+            (RepIDCategory::Community, 100),
+        ];
+
+        let plain = HierarchicalScorer::new().calculate_score(&scores, 0, 0, None);
+
+        let mut stacked = HierarchicalScorer::new();
+        stacked.add_group_synergy(GroupSynergy::new(triad.clone(), Q16::from_ratio(11, 10)));
+        let result = stacked.calculate_score(&scores, 0, 0, None);
+        // Governance+Technical pair (60) plus the triad (30)
+        assert_eq!(result.synergy_bonus, plain.synergy_bonus + 30);
+        assert_eq!(result.explain().synergies[0].categories, GroupSynergy::new(triad.clone(), Q16::from_ratio(11, 10)).categories);
+
+        let mut exclusive = HierarchicalScorer::new();
+        exclusive.add_group_synergy(GroupSynergy::new(triad, Q16::from_ratio(11, 10)).with_stacking(SynergyStacking::Exclusive));
+        let result = exclusive.calculate_score(&scores, 0, 0, None);
+        assert_eq!(result.synergy_bonus, 30);
+        assert_eq!(result.explain().synergies.len(), 1);
+
+        // Missing a member category leaves only the pairwise bonus
+        let partial = exclusive.calculate_score(&scores[..2], 0, 0, None);
+        assert_eq!(partial.synergy_bonus, 60);
+
+        let profile = exclusive.profile();
+        assert_eq!(HierarchicalScorer::try_from(profile.clone()).unwrap().profile().hash(), profile.hash());
+    }
+}
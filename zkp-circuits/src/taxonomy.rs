@@ -0,0 +1,175 @@
+//! Category Taxonomy
+//!
+//! Parent links between categories and the roll-up rules that fold
+//! fine-grained app-defined scores into the top-level categories used in proofs
+
+use serde::{Deserialize, Serialize};
+
+use crate::slashing::PenaltyEvent;
+use crate::{RepIDCategory, Result, ZKPError};
+
+/// How a parent combines its own score with its children's
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RollUpRule {
+    #[default]
+    Sum,
+    Max,
+}
+
+impl RollUpRule {
+    fn combine(self, current: u32, incoming: u32) -> u32 {
+        match self {
+            RollUpRule::Sum => current.saturating_add(incoming),
+            RollUpRule::Max => current.max(incoming),
+        }
+    }
+}
+
+/// A child category contributing to its parent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryLink {
+    pub child: RepIDCategory,
+    pub parent: RepIDCategory,
+    /// Share of the child's score passed up, in basis points
+    pub share_bps: u16,
+}
+
+/// Category forest with per-parent roll-up rules
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryTaxonomy {
+    #[serde(default)]
+    pub links: Vec<CategoryLink>,
+    /// Roll-up rule per parent (Sum when unset)
+    #[serde(default)]
+    pub rules: Vec<(RepIDCategory, RollUpRule)>,
+}
+
+impl CategoryTaxonomy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare `parent` as the parent of `child`, passing up `share_bps` of its score
+    pub fn add_parent(&mut self, child: RepIDCategory, parent: RepIDCategory, share_bps: u16) -> Result<()> {
+        if share_bps > 10_000 {
+            return Err(ZKPError::InvalidInput(format!("Roll-up share {} exceeds 10000 basis points", share_bps)));
+        }
+        if self.parent_of(&child).is_some() {
+            return Err(ZKPError::InvalidInput(format!("{:?} already has a parent", child)));
+        }
+        if self.root_of(&parent) == child {
+            return Err(ZKPError::InvalidInput(format!("{:?} -> {:?} would create a cycle", child, parent)));
+        }
+        self.links.push(CategoryLink { child, parent, share_bps });
+        Ok(())
+    }
+
+    /// Set how `parent` aggregates its children
+    pub fn set_rule(&mut self, parent: RepIDCategory, rule: RollUpRule) {
+        self.rules.retain(|(c, _)| *c != parent);
+        self.rules.push((parent, rule));
+    }
+
+    pub fn parent_of(&self, category: &RepIDCategory) -> Option<&CategoryLink> {
+        self.links.iter().find(|link| link.child == *category)
+    }
+
+    /// Top-level ancestor of `category` (itself when it has no parent)
+    pub fn root_of(&self, category: &RepIDCategory) -> RepIDCategory {
+        let mut current = category;
+        while let Some(link) = self.parent_of(current) {
+            current = &link.parent;
+        }
+        current.clone()
+    }
+
+    fn depth(&self, category: &RepIDCategory) -> usize {
+        let mut depth = 0;
+        let mut current = category;
+        while let Some(link) = self.parent_of(current) {
+            current = &link.parent;
+            depth += 1;
+        }
+        depth
+    }
+
+    fn rule_for(&self, parent: &RepIDCategory) -> RollUpRule {
+        self.rules.iter().find(|(c, _)| c == parent).map_or(RollUpRule::Sum, |(_, rule)| *rule)
+    }
+
+    /// Fold every score into its top-level category, deepest children first
+    pub fn roll_up(&self, scores: &[(RepIDCategory, u32)]) -> Vec<(RepIDCategory, u32)> {
+        let mut totals: Vec<(RepIDCategory, u32)> = Vec::with_capacity(scores.len());
+        for (category, score) in scores {
+            match totals.iter_mut().find(|(c, _)| c == category) {
+                Some(entry) => entry.1 = entry.1.saturating_add(*score),
+                None => totals.push((category.clone(), *score)),
+            }
+        }
+
+        while let Some(index) = (0..totals.len())
+            .filter(|&i| self.parent_of(&totals[i].0).is_some())
+            .max_by_key(|&i| self.depth(&totals[i].0))
+        {
+            let (child, score) = totals.remove(index);
+            let link = self.parent_of(&child).expect("filtered on parent");
+            let passed = (score as u64 * link.share_bps as u64 / 10_000) as u32;
+            match totals.iter_mut().find(|(c, _)| *c == link.parent) {
+                Some(entry) => entry.1 = self.rule_for(&link.parent).combine(entry.1, passed),
+                None => totals.push((link.parent.clone(), passed)),
+            }
+        }
+        totals
+    }
+
+    /// Re-file penalties against the top-level category they roll up to
+    pub fn roll_up_penalties(&self, penalties: &[PenaltyEvent]) -> Vec<PenaltyEvent> {
+        penalties
+            .iter()
+            .map(|p| PenaltyEvent {
+                category: self.root_of(&p.category),
+                ..p.clone()
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    fn custom(name: &str) -> RepIDCategory {
+        RepIDCategory::Custom(name.to_string())
+    }
+
+    #[test]
+    fn test_custom_categories_roll_up_into_proofs() {
+        let mut taxonomy = CategoryTaxonomy::new();
+        taxonomy.add_parent(custom("Rust"), RepIDCategory::Technical, 10_000).unwrap();
+        taxonomy.add_parent(custom("Async"), custom("Rust"), 5_000).unwrap();
+        taxonomy.add_parent(custom("Audits"), RepIDCategory::Technical, 10_000).unwrap();
+        assert!(taxonomy.add_parent(RepIDCategory::Technical, custom("Async"), 10_000).is_err());
+        assert!(taxonomy.add_parent(custom("Rust"), RepIDCategory::DeFi, 10_000).is_err());
+
+        let scores = [(custom("Async"), 40), (custom("Rust"), 30), (custom("Audits"), 25), (RepIDCategory::Governance, 10)];
+        let rolled = taxonomy.roll_up(&scores);
+        assert_eq!(rolled, vec![(RepIDCategory::Governance, 10), (RepIDCategory::Technical, 75)]);
+
+        taxonomy.set_rule(RepIDCategory::Technical, RollUpRule::Max);
+        assert_eq!(taxonomy.roll_up(&scores)[1], (RepIDCategory::Technical, 50));
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_taxonomy(taxonomy);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Technical],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = zkp_system.prove_threshold_verification(&request, &scores, "0xtest").unwrap();
+        assert!(result.meets_threshold);
+        assert!(zkp_system.verify_proof(&result.proof, None).unwrap());
+    }
+}
@@ -0,0 +1,209 @@
+//! Enclave Attestation
+//!
+//! SGX / SEV-SNP quotes binding a proof envelope to the prover binary and
+//! configuration that produced it, and the relying-party check over them
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{RepIDProof, Result, ZKPError};
+
+/// Trusted execution environment that produced a quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TeePlatform {
+    Sgx,
+    SevSnp,
+}
+
+/// Quote carried in `ProofMetadata`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeeAttestation {
+    pub platform: TeePlatform,
+    /// Raw quote / attestation report as produced by the platform
+    pub quote: Vec<u8>,
+    /// Blake3 hash of the prover binary
+    pub binary_hash: [u8; 32],
+    /// Blake3 hash of the prover configuration
+    pub config_hash: [u8; 32],
+}
+
+/// Fields a platform verifier extracts from a quote it has authenticated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteClaims {
+    /// Enclave measurement (MRENCLAVE for SGX, launch measurement for SEV-SNP)
+    pub measurement: Vec<u8>,
+    /// User data embedded in the quote; must start with `report_data`
+    pub report_data: Vec<u8>,
+}
+
+/// Produces quotes from inside the enclave (e.g. via the DCAP or SNP guest APIs)
+pub trait QuoteProvider: Debug + Send + Sync {
+    fn platform(&self) -> TeePlatform;
+
+    fn quote(&self, report_data: &[u8; 32]) -> Result<Vec<u8>>;
+}
+
+/// Authenticates quotes against the platform vendor's certificate chain
+pub trait QuoteVerifier: Debug + Send + Sync {
+    fn verify_quote(&self, platform: TeePlatform, quote: &[u8]) -> Result<QuoteClaims>;
+}
+
+/// Relying-party requirements on the proving environment
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeeRequirement {
+    /// Accepted platforms (empty accepts any)
+    #[serde(default)]
+    pub platforms: Vec<TeePlatform>,
+    /// Accepted enclave measurements
+    pub measurements: Vec<Vec<u8>>,
+    /// Accepted prover binary hashes (empty accepts any)
+    #[serde(default)]
+    pub binary_hashes: Vec<[u8; 32]>,
+}
+
+/// Report data binding the quote to this envelope, binary and configuration
+pub fn report_data(proof: &RepIDProof, binary_hash: &[u8; 32], config_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"repid/tee-report/v1");
+    hasher.update(binary_hash);
+    hasher.update(config_hash);
+    hasher.update(proof.metadata.operation_type.as_bytes());
+    hasher.update(&proof.proof_data);
+    hasher.update(&crate::wire::to_bytes(&proof.public_inputs).expect("public inputs serialize"));
+    *hasher.finalize().as_bytes()
+}
+
+/// Enclave the prover runs in, with the binary and configuration it attests to
+#[derive(Debug, Clone)]
+pub struct EnclaveContext {
+    pub provider: Arc<dyn QuoteProvider>,
+    pub binary_hash: [u8; 32],
+    pub config_hash: [u8; 32],
+}
+
+impl EnclaveContext {
+    /// Quote the envelope and attach the attestation
+    pub fn attest(&self, proof: &mut RepIDProof) -> Result<()> {
+        let quote = self.provider.quote(&report_data(proof, &self.binary_hash, &self.config_hash))?;
+        proof.metadata.tee_attestation = Some(TeeAttestation {
+            platform: self.provider.platform(),
+            quote,
+            binary_hash: self.binary_hash,
+            config_hash: self.config_hash,
+        });
+        Ok(())
+    }
+}
+
+impl TeeRequirement {
+    /// Check the envelope's attestation against this requirement
+    pub fn check(&self, proof: &RepIDProof, verifier: &dyn QuoteVerifier) -> Result<()> {
+        let attestation = proof
+            .metadata
+            .tee_attestation
+            .as_ref()
+            .ok_or_else(|| ZKPError::PolicyViolation("proof carries no enclave attestation".to_string()))?;
+
+        if !self.platforms.is_empty() && !self.platforms.contains(&attestation.platform) {
+            return Err(ZKPError::PolicyViolation(format!("{:?} enclaves are not accepted", attestation.platform)));
+        }
+        if !self.binary_hashes.is_empty() && !self.binary_hashes.contains(&attestation.binary_hash) {
+            return Err(ZKPError::PolicyViolation(format!(
+                "prover binary {} is not accepted",
+                hex::encode(attestation.binary_hash)
+            )));
+        }
+
+        let claims = verifier.verify_quote(attestation.platform, &attestation.quote)?;
+        let expected = report_data(proof, &attestation.binary_hash, &attestation.config_hash);
+        if !claims.report_data.starts_with(&expected) {
+            return Err(ZKPError::VerificationError("enclave quote is not bound to this proof".to_string()));
+        }
+        if !self.measurements.contains(&claims.measurement) {
+            return Err(ZKPError::PolicyViolation(format!(
+                "enclave measurement {} is not accepted",
+                hex::encode(&claims.measurement)
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::policy::VerifyPolicy;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    const MEASUREMENT: [u8; 32] = [0xAB; 32];
+    const VENDOR_KEY: [u8; 32] = [0x11; 32];
+
+    /// Quotes are measurement || report data || keyed MAC standing in for the vendor signature
+    #[derive(Debug)]
+    struct FakeEnclave;
+
+    impl QuoteProvider for FakeEnclave {
+        fn platform(&self) -> TeePlatform {
+            TeePlatform::Sgx
+        }
+
+        fn quote(&self, report_data: &[u8; 32]) -> Result<Vec<u8>> {
+            let body = [MEASUREMENT.as_slice(), report_data].concat();
+            Ok([body.as_slice(), blake3::keyed_hash(&VENDOR_KEY, &body).as_bytes()].concat())
+        }
+    }
+
+    impl QuoteVerifier for FakeEnclave {
+        fn verify_quote(&self, _platform: TeePlatform, quote: &[u8]) -> Result<QuoteClaims> {
+            let (body, mac) = quote.split_at(64);
+            if blake3::keyed_hash(&VENDOR_KEY, body).as_bytes() != mac {
+                return Err(ZKPError::VerificationError("bad quote signature".to_string()));
+            }
+            Ok(QuoteClaims {
+                measurement: body[..32].to_vec(),
+                report_data: body[32..].to_vec(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_enclave_attested_proofs() {
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast)
+            .with_enclave(Arc::new(FakeEnclave), [1; 32], [2; 32])
+            .with_quote_verifier(Arc::new(FakeEnclave));
+        let proof = zkp_system
+            .prove_threshold_verification(&request, &[(RepIDCategory::Community, 75)], "0xtest")
+            .unwrap()
+            .proof;
+
+        let requirement = TeeRequirement {
+            platforms: vec![TeePlatform::Sgx],
+            measurements: vec![MEASUREMENT.to_vec()],
+            binary_hashes: vec![[1; 32]],
+        };
+        let policy = VerifyPolicy::strict(SecurityLevel::Fast).with_tee_requirement(requirement.clone());
+        assert!(zkp_system.verify_proof_with_policy(&proof, None, &policy).unwrap());
+
+        // The quote does not transfer to another proof
+        let mut moved = proof.clone();
+        moved.proof_data.push(0);
+        assert!(requirement.check(&moved, &FakeEnclave).is_err());
+
+        let other_enclave = TeeRequirement { measurements: vec![vec![0; 32]], ..requirement };
+        assert!(matches!(other_enclave.check(&proof, &FakeEnclave), Err(ZKPError::PolicyViolation(_))));
+
+        let mut unattested = proof;
+        unattested.metadata.tee_attestation = None;
+        assert!(zkp_system.verify_proof_with_policy(&unattested, None, &policy).is_err());
+    }
+}
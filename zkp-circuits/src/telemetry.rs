@@ -0,0 +1,241 @@
+//! Proving Telemetry
+//!
+//! Opt-in, anonymous aggregation of proving latency, per-stage failures and
+//! proof sizes, exposed as snapshots that fleet operators pull
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::SecurityLevel;
+
+/// Latency samples kept per security level by default
+pub const DEFAULT_WINDOW: usize = 10_000;
+
+/// Point in the proving pipeline where a request failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryStage {
+    /// Rejected by the budget hook
+    Admission,
+    /// Witness, constraint or FRI generation failed
+    Proving,
+    /// Proof serialization failed
+    Encoding,
+    /// Proof exceeded the configured size limit
+    ProofSize,
+    /// Enclave attestation or prover signing failed
+    Signing,
+}
+
+/// Latency percentiles for one security level, in milliseconds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub security_level: SecurityLevel,
+    pub samples: usize,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Failures recorded at one stage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageFailures {
+    pub stage: TelemetryStage,
+    pub count: u64,
+    /// Share of attempted proofs that failed at this stage
+    pub rate: f64,
+}
+
+/// Proofs whose size fell in `(upper_bound_bytes / 2, upper_bound_bytes]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeBucket {
+    pub upper_bound_bytes: usize,
+    pub count: u64,
+}
+
+/// Point-in-time view of the aggregated statistics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetrySnapshot {
+    pub attempts: u64,
+    pub successes: u64,
+    pub latency: Vec<LatencySummary>,
+    pub failures: Vec<StageFailures>,
+    pub proof_sizes: Vec<SizeBucket>,
+}
+
+#[derive(Debug, Default)]
+struct TelemetryState {
+    attempts: u64,
+    successes: u64,
+    latencies: HashMap<SecurityLevel, VecDeque<u64>>,
+    failures: HashMap<TelemetryStage, u64>,
+    /// Power-of-two size buckets, keyed by their upper bound
+    sizes: HashMap<usize, u64>,
+}
+
+/// Thread-safe aggregator shared between a system and the operator's pull endpoint
+#[derive(Debug)]
+pub struct TelemetryCollector {
+    window: usize,
+    state: Mutex<TelemetryState>,
+}
+
+impl Default for TelemetryCollector {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW)
+    }
+}
+
+impl TelemetryCollector {
+    /// Collector keeping the last `window` latency samples per security level
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            state: Mutex::new(TelemetryState::default()),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, TelemetryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// A proof request entered the pipeline
+    pub fn record_attempt(&self) {
+        self.state().attempts += 1;
+    }
+
+    pub fn record_success(&self, security_level: SecurityLevel, latency_ms: u64, proof_bytes: usize) {
+        let mut state = self.state();
+        state.successes += 1;
+        let samples = state.latencies.entry(security_level).or_default();
+        if samples.len() == self.window {
+            samples.pop_front();
+        }
+        samples.push_back(latency_ms);
+        *state.sizes.entry(proof_bytes.max(1).next_power_of_two()).or_default() += 1;
+    }
+
+    pub fn record_failure(&self, stage: TelemetryStage) {
+        *self.state().failures.entry(stage).or_default() += 1;
+    }
+
+    /// Aggregated statistics since creation or the last reset
+    pub fn snapshot(&self) -> TelemetrySnapshot {
+        let state = self.state();
+
+        let mut latency: Vec<LatencySummary> = state
+            .latencies
+            .iter()
+            .map(|(level, samples)| {
+                let mut sorted: Vec<u64> = samples.iter().copied().collect();
+                sorted.sort_unstable();
+                let percentile = |p: usize| sorted[((sorted.len() * p).div_ceil(100)).saturating_sub(1)];
+                LatencySummary {
+                    security_level: *level,
+                    samples: sorted.len(),
+                    p50_ms: percentile(50),
+                    p90_ms: percentile(90),
+                    p99_ms: percentile(99),
+                    max_ms: sorted[sorted.len() - 1],
+                }
+            })
+            .collect();
+        latency.sort_by_key(|summary| summary.security_level.params().num_queries);
+
+        let mut failures: Vec<StageFailures> = state
+            .failures
+            .iter()
+            .map(|(stage, count)| StageFailures {
+                stage: *stage,
+                count: *count,
+                rate: *count as f64 / state.attempts.max(1) as f64,
+            })
+            .collect();
+        failures.sort_by_key(|f| f.stage);
+
+        let mut proof_sizes: Vec<SizeBucket> = state
+            .sizes
+            .iter()
+            .map(|(upper_bound_bytes, count)| SizeBucket {
+                upper_bound_bytes: *upper_bound_bytes,
+                count: *count,
+            })
+            .collect();
+        proof_sizes.sort_by_key(|bucket| bucket.upper_bound_bytes);
+
+        TelemetrySnapshot {
+            attempts: state.attempts,
+            successes: state.successes,
+            latency,
+            failures,
+            proof_sizes,
+        }
+    }
+
+    pub fn reset(&self) {
+        *self.state() = TelemetryState::default();
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::cost::{Admission, BudgetHook, ProofCharge};
+    use crate::{RepIDCategory, RepIDZKPSystem, ThresholdVerificationRequest};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_percentiles_and_buckets() {
+        let collector = TelemetryCollector::new(100);
+        for latency in 1..=200 {
+            collector.record_attempt();
+            collector.record_success(SecurityLevel::Fast, latency, 3_000);
+        }
+        collector.record_failure(TelemetryStage::Proving);
+
+        let snapshot = collector.snapshot();
+        assert_eq!(snapshot.latency[0].samples, 100);
+        assert_eq!((snapshot.latency[0].p50_ms, snapshot.latency[0].p99_ms), (150, 199));
+        assert_eq!(snapshot.proof_sizes, vec![SizeBucket { upper_bound_bytes: 4096, count: 200 }]);
+        assert_eq!(snapshot.failures[0].rate, 1.0 / 200.0);
+    }
+
+    struct RejectAll;
+
+    impl BudgetHook for RejectAll {
+        fn admit(&self, _charge: &ProofCharge) -> Admission {
+            Admission::Reject("closed".to_string())
+        }
+    }
+
+    #[test]
+    fn test_system_reports_to_collector() {
+        let telemetry = Arc::new(TelemetryCollector::default());
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Community],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let scores = [(RepIDCategory::Community, 75)];
+
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_telemetry(telemetry.clone());
+        zkp_system.prove_threshold_verification(&request, &scores, "0xtest").unwrap();
+        zkp_system.prove_penalized_threshold_verification(&request, &scores, &[], "0xtest").unwrap();
+
+        let mut closed = RepIDZKPSystem::new(SecurityLevel::Fast)
+            .with_telemetry(telemetry.clone())
+            .with_budget_hook(Arc::new(RejectAll));
+        assert!(closed.prove_threshold_verification(&request, &scores, "0xtest").is_err());
+
+        let snapshot = telemetry.snapshot();
+        assert_eq!((snapshot.attempts, snapshot.successes), (3, 2));
+        assert_eq!(snapshot.latency[0].security_level, SecurityLevel::Fast);
+        assert_eq!(snapshot.failures[0].stage, TelemetryStage::Admission);
+        assert_eq!(snapshot.proof_sizes.iter().map(|b| b.count).sum::<u64>(), 2);
+    }
+}
@@ -0,0 +1,147 @@
+//! Tenant Configuration
+//!
+//! Per-application profiles layered over `RepIDZKPSystem`; each tenant's tag is
+//! bound into public inputs and transcripts so proofs never cross tenants
+
+use serde::{Deserialize, Serialize};
+
+use crate::custom_stark::BabyBearField as F;
+use crate::{DecayParameters, RepIDCategory, Result, SecurityLevel, ThresholdVerificationRequest, ZKPError};
+
+/// Identifier of an application served by a shared prover instance
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TenantId(pub String);
+
+impl TenantId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Field element binding proofs to this tenant
+    pub fn field_tag(&self) -> F {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"RepID_Tenant");
+        hasher.update(self.0.as_bytes());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hasher.finalize().as_bytes()[..8]);
+        F::from_bytes(bytes)
+    }
+}
+
+impl std::fmt::Display for TenantId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Proving profile for one tenant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantConfig {
+    pub id: TenantId,
+    pub security_level: SecurityLevel,
+    /// Categories requests may reference (`None` allows all)
+    pub allowed_categories: Option<Vec<RepIDCategory>>,
+    /// Decay applied to requests that do not specify their own
+    pub decay_policy: Option<DecayParameters>,
+    /// Namespace separating this tenant's nullifiers from other tenants'
+    pub nullifier_namespace: String,
+}
+
+impl TenantConfig {
+    pub fn new(id: TenantId, security_level: SecurityLevel) -> Self {
+        Self {
+            nullifier_namespace: id.0.clone(),
+            id,
+            security_level,
+            allowed_categories: None,
+            decay_policy: None,
+        }
+    }
+
+    pub fn with_allowed_categories(mut self, categories: Vec<RepIDCategory>) -> Self {
+        self.allowed_categories = Some(categories);
+        self
+    }
+
+    pub fn with_decay_policy(mut self, decay: DecayParameters) -> Self {
+        self.decay_policy = Some(decay);
+        self
+    }
+
+    pub fn with_nullifier_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.nullifier_namespace = namespace.into();
+        self
+    }
+
+    /// Reject requests referencing categories outside the tenant's allow-list
+    pub fn check_request(&self, request: &ThresholdVerificationRequest) -> Result<()> {
+        if let Some(allowed) = &self.allowed_categories {
+            if let Some(category) = request.categories.iter().find(|c| !allowed.contains(c)) {
+                return Err(ZKPError::PolicyViolation(format!(
+                    "category {:?} is not allowed for tenant '{}'",
+                    category, self.id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate a request and fill in the tenant's decay policy
+    pub fn apply(&self, request: &ThresholdVerificationRequest) -> Result<ThresholdVerificationRequest> {
+        self.check_request(request)?;
+        let mut request = request.clone();
+        if request.decay_params.is_none() {
+            request.decay_params = self.decay_policy.clone();
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::RepIDZKPSystem;
+
+    fn request(categories: Vec<RepIDCategory>) -> ThresholdVerificationRequest {
+        ThresholdVerificationRequest {
+            threshold: 50,
+            categories,
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        }
+    }
+
+    #[test]
+    fn test_proofs_are_not_accepted_across_tenants() {
+        let config = |name: &str| TenantConfig::new(TenantId::new(name), SecurityLevel::Fast);
+        let mut alpha = RepIDZKPSystem::for_tenant(config("alpha"));
+        let beta = RepIDZKPSystem::for_tenant(config("beta"));
+        let shared = RepIDZKPSystem::new(SecurityLevel::Fast);
+
+        let proof = alpha
+            .prove_threshold_verification(&request(vec![RepIDCategory::Community]), &[(RepIDCategory::Community, 75)], "0xtest")
+            .unwrap()
+            .proof;
+
+        assert!(alpha.verify_proof(&proof, None).unwrap());
+        assert!(!beta.verify_proof(&proof, None).unwrap());
+        assert!(!shared.verify_proof(&proof, None).unwrap());
+    }
+
+    #[test]
+    fn test_allowed_categories_enforced() {
+        let config = TenantConfig::new(TenantId::new("alpha"), SecurityLevel::Fast)
+            .with_allowed_categories(vec![RepIDCategory::Community]);
+
+        assert!(config.check_request(&request(vec![RepIDCategory::Community])).is_ok());
+        assert!(matches!(
+            config.check_request(&request(vec![RepIDCategory::DeFi])),
+            Err(ZKPError::PolicyViolation(_))
+        ));
+    }
+}
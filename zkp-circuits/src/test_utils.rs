@@ -0,0 +1,310 @@
+//! Test Utilities for Integration Fuzzing
+//!
+//! Proptest strategies and `Arbitrary` impls producing realistic RepID requests,
+//! score sets and proof structures (enabled by the `test-utils` feature)
+
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use sha3::{Digest, Keccak256};
+
+use crate::backend::BackendKind;
+use crate::circuits::CircuitShape;
+use crate::custom_stark::{BabyBearField, FriProof, MerkleOpening, QueryResponse, StarkProof};
+use crate::fixed_point::Q16;
+use crate::policy::KNOWN_OPERATIONS;
+use crate::request::{MAX_AS_OF, MAX_THRESHOLD};
+use crate::{DecayParameters, ProofMetadata, RepIDCategory, RepIDProof, ThresholdVerificationRequest};
+
+/// Largest generated per-category score
+pub const MAX_SCORE: u32 = 1000;
+/// Largest generated time window (one year)
+pub const MAX_TIME_WINDOW: u64 = 365 * 86400;
+
+const BUILTIN_CATEGORIES: [RepIDCategory; 5] = [
+    RepIDCategory::Governance,
+    RepIDCategory::Community,
+    RepIDCategory::Technical,
+    RepIDCategory::FaithTech,
+    RepIDCategory::DeFi,
+];
+
+pub fn category_strategy() -> impl Strategy<Value = RepIDCategory> {
+    prop_oneof![
+        4 => proptest::sample::select(BUILTIN_CATEGORIES.to_vec()),
+        1 => "[A-Za-z][A-Za-z0-9_]{0,15}".prop_map(RepIDCategory::Custom),
+    ]
+}
+
+pub fn decay_parameters_strategy() -> impl Strategy<Value = DecayParameters> {
+    (0u16..=10_000, 0..=5 * Q16::ONE.raw(), 0u32..=100).prop_map(|(base_decay_rate, multiplicative_factor, min_threshold)| {
+        DecayParameters {
+            base_decay_rate,
+            multiplicative_factor: Q16(multiplicative_factor),
+            min_threshold,
+            category_decay: Vec::new(),
+        }
+    })
+}
+
+/// Drop every repeat of an earlier category, keeping the first occurrence
+fn retain_distinct<T>(items: &mut Vec<T>, category: impl Fn(&T) -> &RepIDCategory) {
+    let mut seen = Vec::new();
+    items.retain(|item| {
+        let fresh = !seen.contains(category(item));
+        seen.push(category(item).clone());
+        fresh
+    });
+}
+
+/// Score sets with distinct categories
+pub fn score_set_strategy(max_categories: usize) -> impl Strategy<Value = Vec<(RepIDCategory, u32)>> {
+    vec((category_strategy(), 0u32..=MAX_SCORE), 0..=max_categories).prop_map(|mut scores| {
+        retain_distinct(&mut scores, |(category, _)| category);
+        scores
+    })
+}
+
+pub fn threshold_request_strategy() -> impl Strategy<Value = ThresholdVerificationRequest> {
+    (
+        1u32..=MAX_THRESHOLD,
+        vec(category_strategy(), 1..=5),
+        1u64..=MAX_TIME_WINDOW,
+        0u64..=MAX_AS_OF,
+        proptest::option::of(decay_parameters_strategy()),
+    )
+        .prop_map(|(threshold, mut categories, time_window, as_of, decay_params)| {
+            retain_distinct(&mut categories, |category| category);
+            ThresholdVerificationRequest {
+                threshold,
+                categories,
+                time_window,
+                as_of,
+                decay_params,
+            }
+        })
+}
+
+pub fn field_element_strategy() -> impl Strategy<Value = BabyBearField> {
+    (0..BabyBearField::MODULUS).prop_map(BabyBearField)
+}
+
+pub fn merkle_opening_strategy(max_depth: usize) -> impl Strategy<Value = MerkleOpening> {
+    (vec(field_element_strategy(), 1..=8), vec(any::<[u8; 32]>(), 0..=max_depth))
+        .prop_map(|(values, auth_path)| MerkleOpening { values, auth_path })
+}
+
+pub fn query_response_strategy(max_depth: usize) -> impl Strategy<Value = QueryResponse> {
+    (
+        any::<u16>(),
+        vec(merkle_opening_strategy(max_depth), 1..=3),
+        merkle_opening_strategy(max_depth),
+        vec(merkle_opening_strategy(max_depth), 0..=4),
+    )
+        .prop_map(|(position, rows, quotient, fri_layers)| QueryResponse {
+            position: position as usize,
+            rows,
+            quotient,
+            fri_layers,
+        })
+}
+
+/// Shapes whose constraint systems need no more than their public inputs
+pub fn circuit_shape_strategy() -> impl Strategy<Value = CircuitShape> {
+    prop_oneof![
+        Just(CircuitShape::RateLimitedSignal),
+        Just(CircuitShape::Biometric),
+        (1usize..=8).prop_map(|num_scores| CircuitShape::CategoryCount { num_scores }),
+        (1usize..=8).prop_map(|events| CircuitShape::HistoryThreshold { events }),
+        (1usize..=64).prop_map(|height| CircuitShape::Air { height }),
+    ]
+}
+
+pub fn fri_proof_strategy() -> impl Strategy<Value = FriProof> {
+    (vec(any::<[u8; 32]>(), 0..=8), vec(field_element_strategy(), 1..=8), any::<u64>())
+        .prop_map(|(commitments, final_poly, pow_nonce)| FriProof {
+            commitments,
+            final_poly,
+            pow_nonce,
+        })
+}
+
+/// Structurally plausible (but not valid) STARK proofs
+pub fn stark_proof_strategy() -> impl Strategy<Value = StarkProof> {
+    (
+        circuit_shape_strategy(),
+        proptest::array::uniform3(any::<[u8; 32]>()),
+        fri_proof_strategy(),
+        vec(query_response_strategy(12), 0..=8),
+        vec(field_element_strategy(), 0..=4),
+        proptest::array::uniform8(field_element_strategy()),
+    )
+        .prop_map(|(shape, [trace_root, lde_root, quotient_root], fri_proof, queries, public_inputs, public_inputs_digest)| StarkProof {
+            shape,
+            trace_root,
+            lde_root,
+            quotient_root,
+            fri_proof,
+            queries,
+            public_inputs,
+            public_inputs_digest,
+        })
+}
+
+/// Proof envelopes wrapping arbitrary STARK proofs
+pub fn repid_proof_strategy() -> impl Strategy<Value = RepIDProof> {
+    (
+        stark_proof_strategy(),
+        proptest::sample::select(KNOWN_OPERATIONS),
+        any::<u32>(),
+    )
+        .prop_map(|(stark_proof, operation_type, timestamp)| {
+            let proof_data = crate::wire::to_bytes(&stark_proof).unwrap_or_default();
+            RepIDProof {
+                metadata: ProofMetadata {
+                    operation_type: operation_type.to_string(),
+                    timestamp: timestamp as u64,
+                    wallet_hash: hex::encode(Keccak256::digest(timestamp.to_le_bytes())),
+                    proof_size: proof_data.len(),
+                    generation_time_ms: 0,
+                    backend: BackendKind::CustomStark,
+                    prover_signature: None,
+                    tee_attestation: None,
+                    content_address: None,
+                    protocol_version: crate::protocol::PROTOCOL_VERSION,
+                    stark_params: None,
+                    issuer_signatures: Vec::new(),
+                },
+                proof_data,
+                public_inputs: stark_proof.public_inputs,
+            }
+        })
+}
+
+impl<'a> Arbitrary<'a> for RepIDCategory {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=5u8)? {
+            5 => RepIDCategory::Custom(String::arbitrary(u)?),
+            i => BUILTIN_CATEGORIES[i as usize].clone(),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for DecayParameters {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(DecayParameters {
+            base_decay_rate: u.int_in_range(0..=10_000)?,
+            multiplicative_factor: Q16(u.int_in_range(0..=5 * Q16::ONE.raw())?),
+            min_threshold: u.int_in_range(0..=100)?,
+            category_decay: Vec::new(),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for ThresholdVerificationRequest {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let num_categories = u.int_in_range(1..=5usize)?;
+        let mut categories = Vec::with_capacity(num_categories);
+        for _ in 0..num_categories {
+            categories.push(RepIDCategory::arbitrary(u)?);
+        }
+        retain_distinct(&mut categories, |category| category);
+
+        Ok(ThresholdVerificationRequest {
+            threshold: u.int_in_range(1..=MAX_THRESHOLD)?,
+            categories,
+            time_window: u.int_in_range(1..=MAX_TIME_WINDOW)?,
+            as_of: u.int_in_range(0..=MAX_AS_OF)?,
+            decay_params: Option::<DecayParameters>::arbitrary(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for BabyBearField {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(BabyBearField(u.int_in_range(0..=BabyBearField::MODULUS - 1)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for MerkleOpening {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(MerkleOpening {
+            values: Vec::<BabyBearField>::arbitrary(u)?,
+            auth_path: Vec::<[u8; 32]>::arbitrary(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for QueryResponse {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(QueryResponse {
+            position: u.int_in_range(0..=u16::MAX as usize)?,
+            rows: Vec::<MerkleOpening>::arbitrary(u)?,
+            quotient: MerkleOpening::arbitrary(u)?,
+            fri_layers: Vec::<MerkleOpening>::arbitrary(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for CircuitShape {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=4u8)? {
+            0 => CircuitShape::RateLimitedSignal,
+            1 => CircuitShape::Biometric,
+            2 => CircuitShape::CategoryCount { num_scores: u.int_in_range(1..=8)? },
+            3 => CircuitShape::HistoryThreshold { events: u.int_in_range(1..=8)? },
+            _ => CircuitShape::Air { height: u.int_in_range(1..=64)? },
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for FriProof {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(FriProof {
+            commitments: Vec::<[u8; 32]>::arbitrary(u)?,
+            final_poly: Vec::<BabyBearField>::arbitrary(u)?,
+            pow_nonce: u64::arbitrary(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for StarkProof {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(StarkProof {
+            shape: CircuitShape::arbitrary(u)?,
+            trace_root: <[u8; 32]>::arbitrary(u)?,
+            lde_root: <[u8; 32]>::arbitrary(u)?,
+            quotient_root: <[u8; 32]>::arbitrary(u)?,
+            fri_proof: FriProof::arbitrary(u)?,
+            queries: Vec::<QueryResponse>::arbitrary(u)?,
+            public_inputs: Vec::<BabyBearField>::arbitrary(u)?,
+            public_inputs_digest: <[BabyBearField; 8]>::arbitrary(u)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_stark::CustomStarkVerifier;
+
+    proptest! {
+        #[test]
+        fn test_generated_requests_are_realistic(request in threshold_request_strategy()) {
+            prop_assert!(request.threshold >= 1 && request.threshold <= MAX_THRESHOLD);
+            prop_assert!(!request.categories.is_empty());
+            for (i, category) in request.categories.iter().enumerate() {
+                prop_assert!(!request.categories[..i].contains(category));
+            }
+            prop_assert!(request.time_window >= 1);
+        }
+
+        #[test]
+        fn test_verifier_never_panics_on_generated_proofs(proof in stark_proof_strategy()) {
+            let verifier = CustomStarkVerifier::new(4, 4);
+            for operation in KNOWN_OPERATIONS {
+                let _ = verifier.verify_proof(&proof, operation);
+            }
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Testing Harnesses
+//!
+//! Suites downstream integrators run against their own verifier deployments
+//! (enabled by the `test-utils` feature)
+
+pub mod adversarial;
@@ -0,0 +1,315 @@
+//! Adversarial Proof Mutations
+//!
+//! Takes a proof the verifier accepts and mutates one section at a time:
+//! the trace itself (re-proved from an invalid trace so every opening still
+//! authenticates), the circuit shape, commitment roots, FRI layers and final polynomial,
+//! the proof-of-work nonce, query positions, opened values and
+//! authentication paths, and public inputs (alone and with a recomputed digest, in the STARK proof and the
+//! envelope). Every mutation must be rejected; `run` reports the ones a
+//! verifier still accepts.
+//!
+//! The verifier is a closure, so the suite runs against any deployment:
+//! an in-process `RepIDZKPSystem`, a remote verification service or an
+//! on-chain verifier behind an RPC call.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::circuits::CircuitShape;
+#[cfg(feature = "prover")]
+use crate::custom_stark::CustomStarkProver;
+use crate::custom_stark::{derive_challenges, pow_nonce_is_valid, StarkParams, StarkProof, TRANSCRIPT_DOMAIN};
+use crate::decoding::decode_stark_proof;
+use crate::limits::ProofLimits;
+use crate::public_inputs::PublicInputs;
+use crate::transcript::Transcript;
+use crate::{RepIDProof, Result, ZKPError, F};
+
+/// Section of a proof a mutation tampers with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofSection {
+    /// Whole proof re-committed over a trace violating the constraints
+    Trace,
+    Shape,
+    TraceRoot,
+    LdeRoot,
+    QuotientRoot,
+    FriCommitment,
+    FinalPolynomial,
+    PowNonce,
+    QueryPosition,
+    QueryValue,
+    AuthPath,
+    PublicInput,
+    PublicInputsDigest,
+    /// Public inputs of the envelope, outside the STARK proof
+    EnvelopeInput,
+}
+
+impl fmt::Display for ProofSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProofSection::Trace => "trace",
+            ProofSection::Shape => "shape",
+            ProofSection::TraceRoot => "trace_root",
+            ProofSection::LdeRoot => "lde_root",
+            ProofSection::QuotientRoot => "quotient_root",
+            ProofSection::FriCommitment => "fri_commitment",
+            ProofSection::FinalPolynomial => "final_polynomial",
+            ProofSection::PowNonce => "pow_nonce",
+            ProofSection::QueryPosition => "query_position",
+            ProofSection::QueryValue => "query_value",
+            ProofSection::AuthPath => "auth_path",
+            ProofSection::PublicInput => "public_input",
+            ProofSection::PublicInputsDigest => "public_inputs_digest",
+            ProofSection::EnvelopeInput => "envelope_input",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One tampered copy of a valid proof
+#[derive(Debug, Clone)]
+pub struct Mutation {
+    pub section: ProofSection,
+    /// What was changed, e.g. "query 3 auth path level 5"
+    pub description: String,
+    pub proof: RepIDProof,
+}
+
+/// Whether the verifier rejected one mutation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MutationOutcome {
+    pub section: ProofSection,
+    pub description: String,
+    pub rejected: bool,
+}
+
+/// Outcome of every mutation of one proof
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdversarialReport {
+    pub outcomes: Vec<MutationOutcome>,
+}
+
+impl AdversarialReport {
+    /// Mutations the verifier accepted
+    pub fn survivors(&self) -> Vec<&MutationOutcome> {
+        self.outcomes.iter().filter(|outcome| !outcome.rejected).collect()
+    }
+
+    /// Whether every mutation was rejected
+    pub fn all_rejected(&self) -> bool {
+        self.outcomes.iter().all(|outcome| outcome.rejected)
+    }
+
+    /// `Err` naming the surviving mutations, if any
+    pub fn check(&self) -> Result<()> {
+        let survivors = self.survivors();
+        if survivors.is_empty() {
+            return Ok(());
+        }
+        let names: Vec<String> = survivors.iter().map(|outcome| format!("{} ({})", outcome.description, outcome.section)).collect();
+        Err(ZKPError::VerificationError(format!(
+            "{} of {} mutations verified: {}",
+            survivors.len(),
+            self.outcomes.len(),
+            names.join(", ")
+        )))
+    }
+}
+
+/// Every mutation of a custom STARK proof envelope
+///
+/// Each section is tampered with in isolation; queries rotate through the
+/// authentication path levels so every level is hit once the proof has as
+/// many queries as the path is deep.
+pub fn mutations(proof: &RepIDProof) -> Result<Vec<Mutation>> {
+    let stark = decode_stark_proof(&proof.proof_data, &ProofLimits::default())?;
+    let mut mutations = Vec::new();
+    let mut push = |section: ProofSection, description: String, mutate: &dyn Fn(&mut StarkProof, &mut Vec<F>)| -> Result<()> {
+        let mut tampered = stark.clone();
+        let mut envelope_inputs = proof.public_inputs.clone();
+        mutate(&mut tampered, &mut envelope_inputs);
+        let proof_data = crate::wire::to_bytes(&tampered)?;
+        let proof = RepIDProof { proof_data, public_inputs: envelope_inputs, ..proof.clone() };
+        mutations.push(Mutation { section, description, proof });
+        Ok(())
+    };
+
+    #[cfg(feature = "prover")]
+    if let Some(forged) = forge_invalid_trace(&stark)? {
+        push(ProofSection::Trace, "trace violating the constraints".to_string(), &|p, _| p.clone_from(&forged))?;
+    }
+    push(ProofSection::Shape, "circuit shape".to_string(), &|p, _| {
+        p.shape = match p.shape {
+            CircuitShape::RateLimitedSignal => CircuitShape::Biometric,
+            _ => CircuitShape::RateLimitedSignal,
+        }
+    })?;
+    push(ProofSection::TraceRoot, "trace root".to_string(), &|p, _| p.trace_root[0] ^= 1)?;
+    push(ProofSection::LdeRoot, "LDE root".to_string(), &|p, _| p.lde_root[31] ^= 1)?;
+    push(ProofSection::QuotientRoot, "quotient root".to_string(), &|p, _| p.quotient_root[31] ^= 1)?;
+    for layer in 0..stark.fri_proof.commitments.len() {
+        push(ProofSection::FriCommitment, format!("FRI layer {}", layer), &|p, _| p.fri_proof.commitments[layer][0] ^= 1)?;
+    }
+    for index in 0..stark.fri_proof.final_poly.len() {
+        push(ProofSection::FinalPolynomial, format!("final polynomial {}", index), &|p, _| {
+            p.fri_proof.final_poly[index] += F::ONE
+        })?;
+    }
+    // Another nonce meeting the difficulty of an unbound transcript, as a grinding adversary would find
+    let nonce = stark.fri_proof.pow_nonce;
+    let other_nonce = evident_params(&stark)?
+        .and_then(|(params, lde_height)| {
+            let seed = derive_challenges(&mut Transcript::new(TRANSCRIPT_DOMAIN, false), None, &stark, &params, lde_height).pow_seed;
+            (nonce + 1..nonce.saturating_add(1 << 24)).find(|&candidate| pow_nonce_is_valid(seed, candidate, params.grinding_bits))
+        })
+        .unwrap_or(nonce ^ 1);
+    push(ProofSection::PowNonce, "proof-of-work nonce".to_string(), &|p, _| p.fri_proof.pow_nonce = other_nonce)?;
+
+    for (index, query) in stark.queries.iter().enumerate() {
+        push(ProofSection::QueryPosition, format!("query {} position", index), &|p, _| p.queries[index].position ^= 1)?;
+        push(ProofSection::QueryValue, format!("query {} quotient value", index), &|p, _| {
+            p.queries[index].quotient.values[0] += F::ONE
+        })?;
+        if !query.fri_layers.is_empty() {
+            let layer = index % query.fri_layers.len();
+            push(ProofSection::QueryValue, format!("query {} FRI layer {} value", index, layer), &|p, _| {
+                p.queries[index].fri_layers[layer].values[0] += F::ONE
+            })?;
+        }
+        if let Some(row) = query.rows.first() {
+            push(ProofSection::QueryValue, format!("query {} trace value", index), &|p, _| {
+                p.queries[index].rows[0].values[0] += F::ONE
+            })?;
+            if !row.auth_path.is_empty() {
+                let level = index % row.auth_path.len();
+                push(ProofSection::AuthPath, format!("query {} auth path level {}", index, level), &|p, _| {
+                    p.queries[index].rows[0].auth_path[level][0] ^= 1
+                })?;
+            }
+        }
+    }
+
+    for index in 0..stark.public_inputs.len() {
+        push(ProofSection::PublicInput, format!("public input {}", index), &|p, envelope| {
+            p.public_inputs[index] += F::ONE;
+            envelope.clone_from(&p.public_inputs);
+        })?;
+        push(ProofSection::PublicInput, format!("public input {} with its digest", index), &|p, envelope| {
+            p.public_inputs[index] += F::ONE;
+            p.public_inputs_digest = PublicInputs::new(p.public_inputs.clone()).digest();
+            envelope.clone_from(&p.public_inputs);
+        })?;
+        push(ProofSection::EnvelopeInput, format!("envelope public input {}", index), &|_, envelope| {
+            envelope[index] += F::ONE
+        })?;
+    }
+    push(ProofSection::PublicInputsDigest, "public inputs digest".to_string(), &|p, _| {
+        p.public_inputs_digest[0] += F::ONE
+    })?;
+
+    Ok(mutations)
+}
+
+/// Proof of the same statement over an all-zero trace, committed and folded
+/// with the same parameters so positions, paths and folds are all genuine
+///
+/// `None` for application-defined AIRs, whose constraints the proof alone does
+/// not determine, and for proofs without queries.
+#[cfg(feature = "prover")]
+fn forge_invalid_trace(stark: &StarkProof) -> Result<Option<StarkProof>> {
+    let Some((params, _)) = evident_params(stark)? else {
+        return Ok(None);
+    };
+    let circuit = stark.shape.build(&stark.public_inputs)?;
+    let mut prover = CustomStarkProver::new(params.num_queries, params.blowup_factor);
+    prover.fri_folding_arity = params.fri_folding_arity;
+    prover.prove_unchecked(circuit.trace(), &circuit, stark.shape.clone(), stark.public_inputs.clone()).map(Some)
+}
+
+/// Parameters and LDE height `stark` was generated with, read off its shape and openings
+fn evident_params(stark: &StarkProof) -> Result<Option<(StarkParams, usize)>> {
+    let Some(query) = stark.queries.first() else {
+        return Ok(None);
+    };
+    if matches!(stark.shape, CircuitShape::Air { .. }) {
+        return Ok(None);
+    }
+    let height = stark.shape.build(&stark.public_inputs)?.system.height();
+    let lde_height = 1usize << query.quotient.auth_path.len();
+    let mut params = StarkParams::new(stark.queries.len(), lde_height / height);
+    if let Some(layer) = query.fri_layers.first() {
+        params.fri_folding_arity = layer.values.len();
+    }
+    Ok(Some((params, lde_height)))
+}
+
+/// Run every mutation of `proof` through `verify`
+///
+/// `verify` returns `Ok(true)` only for proofs it accepts; errors count as
+/// rejections. The unmodified proof must verify first, or the suite would
+/// pass against a verifier that rejects everything.
+pub fn run<V>(proof: &RepIDProof, mut verify: V) -> Result<AdversarialReport>
+where
+    V: FnMut(&RepIDProof) -> Result<bool>,
+{
+    if !verify(proof)? {
+        return Err(ZKPError::InvalidInput("Adversarial suite needs a proof the verifier accepts".to_string()));
+    }
+    let outcomes = mutations(proof)?
+        .into_iter()
+        .map(|mutation| MutationOutcome {
+            rejected: !matches!(verify(&mutation.proof), Ok(true)),
+            section: mutation.section,
+            description: mutation.description,
+        })
+        .collect();
+    Ok(AdversarialReport { outcomes })
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::custom_stark::CustomStarkVerifier;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+    #[test]
+    fn test_every_mutation_is_rejected() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Technical],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Technical, 75)], "0xtest").unwrap();
+
+        let report = run(&result.proof, |proof| zkp_system.verify_proof(proof, Some(&request))).unwrap();
+        report.check().unwrap();
+        for section in [ProofSection::Trace, ProofSection::Shape, ProofSection::QuotientRoot, ProofSection::LdeRoot, ProofSection::PowNonce, ProofSection::AuthPath, ProofSection::EnvelopeInput] {
+            assert!(report.outcomes.iter().any(|outcome| outcome.section == section));
+        }
+        let depth = decode_stark_proof(&result.proof.proof_data, &ProofLimits::default()).unwrap().queries[0].rows[0].auth_path.len();
+        let levels = report.outcomes.iter().filter(|outcome| outcome.section == ProofSection::AuthPath).count();
+        assert_eq!(levels, 40);
+        assert!(depth <= levels);
+
+        // The forged trace authenticates everywhere and fails on the constraints or FRI alone
+        let stark = decode_stark_proof(&result.proof.proof_data, &ProofLimits::default()).unwrap();
+        let forged = forge_invalid_trace(&stark).unwrap().unwrap();
+        let params = SecurityLevel::Fast.params();
+        let verifier = CustomStarkVerifier::new(params.num_queries, params.blowup_factor).with_params(&params);
+        let err = verifier.check_proof(&forged, "threshold_verification").unwrap_err();
+        assert!(matches!(&err, ZKPError::VerificationError(message) if message.contains("FRI") || message.contains("Constraints")), "{}", err);
+
+        // A verifier that accepts anything is caught, one that accepts nothing is refused
+        let lenient = run(&result.proof, |_| Ok(true)).unwrap();
+        assert_eq!(lenient.survivors().len(), lenient.outcomes.len());
+        assert!(lenient.check().is_err());
+        assert!(matches!(run(&result.proof, |_| Ok(false)), Err(ZKPError::InvalidInput(_))));
+    }
+}
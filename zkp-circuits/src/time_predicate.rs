@@ -0,0 +1,90 @@
+//! Timestamp Predicates
+//!
+//! Provable statements about private timestamps against public bounds, built
+//! on the limb comparison gadget so freshness, account-age and decay circuits
+//! share one implementation of time logic
+
+use serde::{Deserialize, Serialize};
+
+use crate::F;
+
+/// Predicate over a private Unix timestamp (seconds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimePredicate {
+    /// At or before `bound`
+    Before { bound: u64 },
+    /// At or after `bound`
+    After { bound: u64 },
+    /// Within `[start, end]`
+    WithinRange { start: u64, end: u64 },
+    /// Not in the future at `now` and at least `min_secs` old, e.g. account age
+    ElapsedAtLeast { now: u64, min_secs: u64 },
+    /// Not in the future at `now` and at most `max_secs` old, e.g. attestation freshness
+    ElapsedAtMost { now: u64, max_secs: u64 },
+}
+
+impl TimePredicate {
+    /// 1 when the predicate holds for the timestamp cell, 0 otherwise
+    pub fn eval(&self, timestamp: F) -> F {
+        F::new(self.holds(timestamp.0) as u64)
+    }
+
+    /// Whether `timestamp` satisfies the predicate, as the circuit's limb comparisons see it
+    pub fn holds(&self, timestamp: u64) -> bool {
+        match *self {
+            TimePredicate::Before { bound } => timestamp <= bound,
+            TimePredicate::After { bound } => bound <= timestamp,
+            TimePredicate::WithinRange { start, end } => start <= timestamp && timestamp <= end,
+            TimePredicate::ElapsedAtLeast { now, min_secs } => timestamp <= now && min_secs <= now - timestamp,
+            TimePredicate::ElapsedAtMost { now, max_secs } => timestamp <= now && now - timestamp <= max_secs,
+        }
+    }
+
+    /// Constraint label suffix
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimePredicate::Before { .. } => "before",
+            TimePredicate::After { .. } => "after",
+            TimePredicate::WithinRange { .. } => "within_range",
+            TimePredicate::ElapsedAtLeast { .. } => "elapsed_at_least",
+            TimePredicate::ElapsedAtMost { .. } => "elapsed_at_most",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{check_witness, CustomAir, TimePredicateAir};
+    use crate::custom_stark::ExecutionTrace;
+
+    const NOW: u64 = 1_700_000_000;
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn test_predicates_as_constraints() {
+        let predicates = vec![
+            TimePredicate::ElapsedAtLeast { now: NOW, min_secs: 30 * DAY },
+            TimePredicate::ElapsedAtMost { now: NOW, max_secs: DAY },
+            TimePredicate::WithinRange { start: NOW - 7 * DAY, end: NOW },
+            TimePredicate::Before { bound: NOW },
+        ];
+        let air = TimePredicateAir::new(2, predicates.clone());
+        let mut trace = ExecutionTrace::new(air.width(), 32);
+        let timestamps = [NOW - 90 * DAY, NOW - 3600, NOW - 2 * DAY, NOW];
+        air.fill(&mut trace, &timestamps);
+        assert!(check_witness(&trace, &air).is_ok());
+        assert!(predicates.iter().zip(timestamps).all(|(p, t)| p.holds(t)));
+
+        // A future-dated timestamp satisfies neither elapsed predicate
+        assert!(!predicates[0].holds(NOW + 60 * DAY));
+        assert!(!predicates[1].holds(NOW + 60));
+        assert!(TimePredicate::After { bound: NOW }.holds(NOW + 60));
+
+        // A 29-day-old account fails the age gate at the exact constraint
+        air.fill(&mut trace, &[NOW - 29 * DAY, NOW - 3600, NOW - 2 * DAY, NOW]);
+        let violation = check_witness(&trace, &air).unwrap_err();
+        assert_eq!(violation.label, "timestamp_0_elapsed_at_least");
+    }
+}
@@ -0,0 +1,120 @@
+//! Execution Trace Debugging
+//!
+//! Structured dumps of a trace together with its per-row constraint
+//! evaluations, highlighting exactly which constraints are unsatisfied
+
+use serde::{Deserialize, Serialize};
+
+use crate::custom_stark::{BabyBearField, ExecutionTrace};
+use crate::{Result, ZKPError};
+
+/// Environment variable naming the directory `dump()` writes reports to
+pub const DEBUG_TRACE_DIR_ENV: &str = "REPID_DEBUG_TRACE_DIR";
+
+/// A constraint that evaluated to a non-zero residual
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedConstraint {
+    /// Trace row the constraint was evaluated on
+    pub row: usize,
+    /// Index of the constraint within that row's evaluations
+    pub index: usize,
+    /// Non-zero residual value
+    pub value: u64,
+}
+
+/// Trace, constraint evaluations and failures for one proof attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceDebugReport {
+    pub trace: ExecutionTrace,
+    /// Residual of every constraint, per row
+    pub constraint_evaluations: Vec<Vec<u64>>,
+    pub failed_constraints: Vec<FailedConstraint>,
+}
+
+impl TraceDebugReport {
+    pub fn new(trace: &ExecutionTrace, constraints: &[Vec<BabyBearField>]) -> Self {
+        let mut failed_constraints = Vec::new();
+        for (row, row_constraints) in constraints.iter().enumerate() {
+            for (index, value) in row_constraints.iter().enumerate() {
+                if *value != BabyBearField::ZERO {
+                    failed_constraints.push(FailedConstraint { row, index, value: value.0 });
+                }
+            }
+        }
+
+        Self {
+            trace: trace.clone(),
+            constraint_evaluations: constraints
+                .iter()
+                .map(|row| row.iter().map(|value| value.0).collect())
+                .collect(),
+            failed_constraints,
+        }
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.failed_constraints.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+
+    /// Log failures and write the report to `REPID_DEBUG_TRACE_DIR` (or the temp dir)
+    pub fn dump(&self) {
+        for failure in &self.failed_constraints {
+            tracing::warn!(
+                "Constraint {} failed on row {} (residual {})",
+                failure.index,
+                failure.row,
+                failure.value
+            );
+        }
+
+        let dir = std::env::var_os(DEBUG_TRACE_DIR_ENV)
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir);
+        let file_name = format!(
+            "repid_trace_{}x{}_{}.json",
+            self.trace.width,
+            self.trace.height,
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        );
+
+        match self.to_json() {
+            Ok(json) => {
+                let path = dir.join(file_name);
+                match std::fs::write(&path, json) {
+                    Ok(()) => tracing::debug!("Trace debug report written to {}", path.display()),
+                    Err(e) => tracing::warn!("Failed to write trace debug report: {}", e),
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize trace debug report: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_exports_and_failed_constraints() {
+        let mut trace = ExecutionTrace::new(2, 2);
+        trace.set(0, 0, BabyBearField::new(7));
+        trace.set(1, 1, BabyBearField::new(9));
+
+        assert_eq!(trace.to_csv(), "row,c0,c1\n0,7,0\n1,0,9\n");
+        let parsed: ExecutionTrace = serde_json::from_str(&trace.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.data, trace.data);
+
+        let constraints = vec![
+            vec![BabyBearField::ZERO, BabyBearField::ZERO],
+            vec![BabyBearField::ZERO, BabyBearField::new(3)],
+        ];
+        let report = TraceDebugReport::new(&trace, &constraints);
+        assert!(!report.is_satisfied());
+        assert_eq!(report.failed_constraints, vec![FailedConstraint { row: 1, index: 1, value: 3 }]);
+    }
+}
@@ -0,0 +1,161 @@
+//! Fiat–Shamir Transcript
+//!
+//! The BLAKE3 transcript the core prover derives its challenges from, exposed so
+//! application circuits and audit tools replay exactly the same derivation
+
+use blake3::Hasher;
+use serde::{Deserialize, Serialize};
+
+use crate::custom_stark::BabyBearField;
+use crate::{Result, ZKPError};
+
+/// One recorded Fiat–Shamir operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    /// Bytes absorbed into the transcript (hex encoded)
+    Absorb { label: String, data: String },
+    /// Challenge squeezed from the transcript
+    Challenge { label: String, value: u64 },
+}
+
+/// Full Fiat–Shamir transcript of a proof, for external audits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptLog {
+    /// Transcript domain separator
+    pub domain: String,
+    /// Absorbed values and derived challenges, in order
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl TranscriptLog {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ZKPError::SerializationError(e.to_string()))
+    }
+}
+
+/// BLAKE3-based Fiat–Shamir transcript with optional recording
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    hasher: Hasher,
+    log: Option<TranscriptLog>,
+}
+
+impl Transcript {
+    pub fn new(domain: &str, record: bool) -> Self {
+        let mut hasher = Hasher::new();
+        hasher.update(domain.as_bytes());
+        Self {
+            hasher,
+            log: record.then(|| TranscriptLog {
+                domain: domain.to_string(),
+                entries: Vec::new(),
+            }),
+        }
+    }
+
+    /// Absorb length-framed labelled bytes
+    pub fn absorb(&mut self, label: &str, data: &[u8]) {
+        self.hasher.update(&(label.len() as u64).to_le_bytes());
+        self.hasher.update(label.as_bytes());
+        self.hasher.update(&(data.len() as u64).to_le_bytes());
+        self.hasher.update(data);
+
+        if let Some(log) = &mut self.log {
+            log.entries.push(TranscriptEntry::Absorb {
+                label: label.to_string(),
+                data: hex::encode(data),
+            });
+        }
+    }
+
+    pub fn absorb_field_elements(&mut self, label: &str, elements: &[BabyBearField]) {
+        let bytes: Vec<u8> = elements.iter().flat_map(|e| e.to_bytes()).collect();
+        self.absorb(label, &bytes);
+    }
+
+    /// Squeeze a challenge and ratchet it back into the state
+    pub fn challenge_u64(&mut self, label: &str) -> u64 {
+        let mut squeeze = self.hasher.clone();
+        squeeze.update(b"challenge");
+        squeeze.update(label.as_bytes());
+        let output = squeeze.finalize();
+        self.hasher.update(output.as_bytes());
+
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&output.as_bytes()[..8]);
+        let value = u64::from_le_bytes(bytes);
+
+        if let Some(log) = &mut self.log {
+            log.entries.push(TranscriptEntry::Challenge {
+                label: label.to_string(),
+                value,
+            });
+        }
+
+        value
+    }
+
+    /// Field challenge (a 64-bit squeeze reduced mod p, bias below 2^-32)
+    pub fn challenge_field(&mut self, label: &str) -> BabyBearField {
+        BabyBearField::new(self.challenge_u64(label))
+    }
+
+    /// Index challenge in `0..bound` (`bound` must be positive)
+    pub fn challenge_index(&mut self, label: &str, bound: usize) -> usize {
+        (self.challenge_u64(label) % bound as u64) as usize
+    }
+
+    pub fn challenge_indices(&mut self, label: &str, count: usize, bound: usize) -> Vec<usize> {
+        (0..count).map(|_| self.challenge_index(label, bound)).collect()
+    }
+
+    /// Independent child transcript bound to this state and `label`
+    ///
+    /// The parent is unchanged; children forked under different labels yield
+    /// unrelated challenges. A recording parent records the child separately.
+    pub fn fork(&self, label: &str) -> Self {
+        let mut hasher = self.hasher.clone();
+        hasher.update(b"fork");
+        hasher.update(&(label.len() as u64).to_le_bytes());
+        hasher.update(label.as_bytes());
+        Self {
+            hasher,
+            log: self.log.as_ref().map(|log| TranscriptLog {
+                domain: format!("{}/{}", log.domain, label),
+                entries: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn into_log(self) -> Option<TranscriptLog> {
+        self.log
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenges_are_deterministic_and_forks_independent() {
+        let mut a = Transcript::new("app_circuit", false);
+        let mut b = Transcript::new("app_circuit", true);
+        for t in [&mut a, &mut b] {
+            t.absorb("root", &[7u8; 32]);
+            t.absorb_field_elements("inputs", &[BabyBearField::new(5)]);
+        }
+        let left = a.fork("left").challenge_u64("x");
+        assert_eq!(left, b.fork("left").challenge_u64("x"));
+        assert_ne!(left, a.fork("right").challenge_u64("x"));
+        assert_eq!(a.challenge_field("alpha"), b.challenge_field("alpha"));
+
+        let indices = a.challenge_indices("query", 8, 64);
+        assert!(indices.iter().all(|&i| i < 64));
+
+        let log = b.into_log().unwrap();
+        assert_eq!(log.domain, "app_circuit");
+        assert!(matches!(log.entries.last(), Some(TranscriptEntry::Challenge { label, .. }) if label == "alpha"));
+    }
+}
@@ -0,0 +1,128 @@
+//! Verification Cache
+//!
+//! Verifier-side memo of verification outcomes keyed by proof hash, so a proof
+//! checked by several services pays for the full verification once per TTL
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::policy::VerifyPolicy;
+use crate::{RepIDProof, ThresholdVerificationRequest};
+
+/// Hash identifying a proof together with the request and policy it was checked under
+pub type CacheKey = [u8; 32];
+
+/// Bounded, time-limited cache of verification outcomes
+#[derive(Debug)]
+pub struct VerificationCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<CacheKey, (Instant, bool)>>,
+}
+
+impl VerificationCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Blake3 over the encoded proof, the request's canonical hash and the policy
+    pub fn key(proof: &RepIDProof, request: Option<&ThresholdVerificationRequest>, policy: &VerifyPolicy) -> CacheKey {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&crate::wire::to_bytes(proof).expect("proof serializes"));
+        match request {
+            Some(request) => hasher.update(&[1]).update(&request.canonical_hash()),
+            None => hasher.update(&[0]),
+        };
+        hasher.update(&crate::wire::to_bytes(policy).expect("policy serializes"));
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Cached outcome, if present and younger than the TTL
+    pub fn get(&self, key: &CacheKey) -> Option<bool> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key) {
+            Some((inserted, valid)) if inserted.elapsed() < self.ttl => Some(*valid),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store an outcome, evicting expired and then oldest entries when full
+    pub fn insert(&self, key: CacheKey, valid: bool) {
+        if self.max_entries == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, (inserted, _)| inserted.elapsed() < self.ttl);
+            if entries.len() >= self.max_entries {
+                if let Some(oldest) = entries.iter().min_by_key(|(_, (inserted, _))| *inserted).map(|(k, _)| *k) {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, (Instant::now(), valid));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel};
+
+    #[test]
+    fn test_repeated_verification_hits_cache() {
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_verification_cache(Duration::from_millis(200), 2);
+        let request = ThresholdVerificationRequest {
+            threshold: 50,
+            categories: vec![RepIDCategory::Governance],
+            time_window: 86400,
+            as_of: 0,
+            decay_params: None,
+        };
+        let proof = zkp_system
+            .prove_threshold_verification(&request, &[(RepIDCategory::Governance, 80)], "0xtest")
+            .unwrap()
+            .proof;
+        let key = VerificationCache::key(&proof, None, &VerifyPolicy::default());
+
+        assert!(zkp_system.verify_proof(&proof, None).unwrap());
+        assert!(zkp_system.verify_proof(&proof, None).unwrap());
+        let cache = zkp_system.verification_cache().unwrap();
+        assert_eq!((cache.get(&key), cache.len()), (Some(true), 1));
+
+        // A tampered proof is a different key, and the outcome is cached as invalid
+        let mut stark_proof: crate::custom_stark::StarkProof = crate::wire::from_bytes(&proof.proof_data).unwrap();
+        stark_proof.public_inputs[0] = crate::F::new(51);
+        let mut tampered = proof.clone();
+        tampered.proof_data = crate::wire::to_bytes(&stark_proof).unwrap();
+        assert!(!zkp_system.verify_proof(&tampered, None).unwrap());
+        assert_eq!(cache.get(&VerificationCache::key(&tampered, None, &VerifyPolicy::default())), Some(false));
+
+        // Size bound evicts the oldest entry; TTL expires the rest
+        cache.insert([7; 32], true);
+        assert_eq!((cache.len(), cache.get(&key)), (2, None));
+        std::thread::sleep(Duration::from_millis(250));
+        assert_eq!(cache.get(&[7; 32]), None);
+    }
+}
@@ -0,0 +1,219 @@
+//! Wallet Commitments
+//!
+//! Public identity input of threshold proofs: Poseidon2 over the normalized
+//! wallet address and a salt held by the user. The same address and salt
+//! always give the same commitment, so relying parties can recognize a
+//! returning wallet they were shown the opening for, while different salts
+//! keep one wallet's proofs unlinkable across verifiers.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2::{self, Digest, DIGEST_ELEMENTS};
+use crate::{Result, ZKPError, F};
+
+/// Longest wallet address accepted, in bytes
+pub const MAX_WALLET_ADDRESS_LEN: usize = 128;
+
+/// Bytes packed into each field element, so packing is injective
+const BYTES_PER_ELEMENT: usize = 3;
+
+/// Field elements a packed salt occupies
+pub const SALT_ELEMENTS: usize = 32usize.div_ceil(BYTES_PER_ELEMENT);
+
+/// Per-user salt hiding the address behind a wallet commitment
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletSalt(pub [u8; 32]);
+
+impl WalletSalt {
+    /// Fresh salt drawn from `provider`
+    #[cfg(feature = "scoring")]
+    pub fn generate(provider: &dyn crate::entropy::RngProvider) -> Result<Self> {
+        crate::entropy::seed(provider).map(Self)
+    }
+
+    /// Salt packed into field elements, as commitments absorb it
+    pub fn elements(&self) -> [F; SALT_ELEMENTS] {
+        let mut elements = [F::ZERO; SALT_ELEMENTS];
+        for (element, packed) in elements.iter_mut().zip(pack_bytes(&self.0)) {
+            *element = packed;
+        }
+        elements
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    pub fn from_hex(salt_hex: &str) -> Result<Self> {
+        let bytes = hex::decode(salt_hex).map_err(|e| ZKPError::InvalidInput(format!("Invalid wallet salt: {}", e)))?;
+        let salt = bytes
+            .try_into()
+            .map_err(|_| ZKPError::InvalidInput("Wallet salt must be 32 bytes".to_string()))?;
+        Ok(Self(salt))
+    }
+}
+
+impl fmt::Debug for WalletSalt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalletSalt").finish_non_exhaustive()
+    }
+}
+
+/// Eight-element Poseidon2(len, address, salt) digest over the normalized address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletCommitment(Digest);
+
+impl WalletCommitment {
+    /// Commit to `address` under `salt`, rejecting malformed addresses
+    pub fn new(address: &str, salt: &WalletSalt) -> Result<Self> {
+        let address = normalize_address(address)?;
+        let mut inputs = vec![F::new(address.len() as u64)];
+        inputs.extend(pack_bytes(address.as_bytes()));
+        inputs.extend(salt.elements());
+        Ok(Self(poseidon2::hash_to_digest(&inputs)))
+    }
+
+    /// Commit to the wallet tag epoch snapshot leaves carry, under `salt`
+    ///
+    /// Snapshot proofs recompute this from the tag the leaves they open
+    /// hash, so it is taken over the tag rather than the address.
+    pub fn of_tag(tag: F, salt: &WalletSalt) -> Self {
+        let mut inputs = vec![tag];
+        inputs.extend(salt.elements());
+        Self(poseidon2::hash_to_digest(&inputs))
+    }
+
+    /// Field elements proofs carry as their public identity inputs
+    pub fn elements(&self) -> Digest {
+        self.0
+    }
+
+    /// Whether `address` and `salt` open this commitment
+    pub fn opens(&self, address: &str, salt: &WalletSalt) -> bool {
+        Self::new(address, salt).is_ok_and(|commitment| commitment == *self)
+    }
+
+    /// Hex label recorded as the envelope's `wallet_hash`
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0.iter().flat_map(|element| (element.0 as u32).to_le_bytes()).collect::<Vec<u8>>())
+    }
+
+    pub fn from_hex(commitment_hex: &str) -> Result<Self> {
+        let bytes: [u8; 4 * DIGEST_ELEMENTS] = hex::decode(commitment_hex)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| ZKPError::InvalidInput(format!("'{}' is not a wallet commitment", commitment_hex)))?;
+        let mut digest = [F::ZERO; DIGEST_ELEMENTS];
+        for (element, limb) in digest.iter_mut().zip(bytes.chunks_exact(4)) {
+            *element = F(u32::from_le_bytes([limb[0], limb[1], limb[2], limb[3]]) as u64);
+            if !element.is_canonical() {
+                return Err(ZKPError::InvalidInput("Wallet commitment is not a canonical field element".to_string()));
+            }
+        }
+        Ok(Self(digest))
+    }
+}
+
+impl fmt::Display for WalletCommitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// Validated address in canonical form
+///
+/// Addresses must be 1 to `MAX_WALLET_ADDRESS_LEN` printable ASCII characters
+/// without whitespace. EVM addresses (`0x` and 40 hex digits) are lowercased
+/// so checksummed and plain spellings commit identically.
+pub fn normalize_address(address: &str) -> Result<String> {
+    if address.is_empty() {
+        return Err(ZKPError::InvalidInput("Wallet address is empty".to_string()));
+    }
+    if address.len() > MAX_WALLET_ADDRESS_LEN {
+        return Err(ZKPError::InvalidInput(format!(
+            "Wallet address is {} bytes, at most {} allowed",
+            address.len(),
+            MAX_WALLET_ADDRESS_LEN
+        )));
+    }
+    if !address.bytes().all(|b| b.is_ascii_graphic()) {
+        return Err(ZKPError::InvalidInput("Wallet address must be printable ASCII without whitespace".to_string()));
+    }
+    let is_evm = address.len() == 42
+        && address[..2].eq_ignore_ascii_case("0x")
+        && address[2..].bytes().all(|b| b.is_ascii_hexdigit());
+    Ok(if is_evm { address.to_ascii_lowercase() } else { address.to_string() })
+}
+
+fn pack_bytes(bytes: &[u8]) -> impl Iterator<Item = F> + '_ {
+    bytes.chunks(BYTES_PER_ELEMENT).map(|chunk| {
+        let mut limb = [0u8; 8];
+        limb[..chunk.len()].copy_from_slice(chunk);
+        F::from_bytes(limb)
+    })
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_is_deterministic_and_validated() {
+        let salt = WalletSalt([7; 32]);
+        let checksummed = "0x52908400098527886E0F7030069857D2E4169EE7";
+        let commitment = WalletCommitment::new(checksummed, &salt).unwrap();
+        assert_eq!(commitment, WalletCommitment::new(&checksummed.to_lowercase(), &salt).unwrap());
+        assert_ne!(commitment, WalletCommitment::new(checksummed, &WalletSalt([8; 32])).unwrap());
+        assert!(commitment.opens(checksummed, &salt));
+        assert!(!commitment.opens("0xabc", &salt));
+        assert_eq!(WalletCommitment::from_hex(&commitment.to_hex()).unwrap(), commitment);
+        assert_eq!(WalletSalt::from_hex(&salt.to_hex()).unwrap(), salt);
+
+        // Short addresses commit instead of panicking; malformed ones are rejected
+        assert!(WalletCommitment::new("0x", &salt).is_ok());
+        for address in ["", "0x abc", "wallet\n", &"a".repeat(MAX_WALLET_ADDRESS_LEN + 1)] {
+            assert!(matches!(WalletCommitment::new(address, &salt), Err(ZKPError::InvalidInput(_))));
+        }
+        assert!(WalletCommitment::from_hex(&hex::encode(u64::MAX.to_le_bytes())).is_err());
+        assert!(WalletCommitment::from_hex(&hex::encode([0xff; 4 * DIGEST_ELEMENTS])).is_err());
+    }
+
+    #[test]
+    fn test_threshold_proofs_carry_wallet_commitment() {
+        use crate::policy::VerifyPolicy;
+        use crate::{RepIDCategory, RepIDZKPSystem, SecurityLevel, ThresholdVerificationRequest};
+
+        let salt = WalletSalt([3; 32]);
+        let mut zkp_system = RepIDZKPSystem::new(SecurityLevel::Fast).with_wallet_salt(salt);
+        let request = ThresholdVerificationRequest::builder()
+            .with_threshold(50)
+            .with_category(RepIDCategory::Technical)
+            .with_time_window(86_400)
+            .build()
+            .unwrap();
+        let result = zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Technical, 75)], "0xtest").unwrap();
+        let commitment = WalletCommitment::new("0xtest", &salt).unwrap();
+        assert_eq!(result.proof.metadata.wallet_hash, commitment.to_hex());
+        let inputs = &result.proof.public_inputs;
+        // The commitment sits ahead of the stated category and the shape digest
+        let bound = inputs.len() - DIGEST_ELEMENTS - 1;
+        assert_eq!(inputs[bound], RepIDCategory::Technical.field_tag());
+        assert!(inputs[..bound].ends_with(&commitment.elements()));
+
+        let policy = VerifyPolicy::default();
+        assert!(zkp_system.verify_proof(&result.proof, Some(&request)).unwrap());
+        assert!(zkp_system.verify_proof_for_wallet(&result.proof, Some(&request), &commitment, &policy).unwrap());
+        let other = WalletCommitment::new("0xother", &salt).unwrap();
+        assert!(!zkp_system.verify_proof_for_wallet(&result.proof, Some(&request), &other, &policy).unwrap());
+        let unsalted = WalletCommitment::new("0xtest", &WalletSalt::default()).unwrap();
+        assert!(!zkp_system.verify_proof_for_wallet(&result.proof, Some(&request), &unsalted, &policy).unwrap());
+
+        // Malformed addresses fail before any proving work
+        assert!(matches!(
+            zkp_system.prove_threshold_verification(&request, &[(RepIDCategory::Technical, 75)], ""),
+            Err(ZKPError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,546 @@
+//! Wire Encoding
+//!
+//! The binary layout proofs, envelopes and stored records travel in: fields
+//! in declaration order, integers little-endian at their full width, lengths
+//! as `u64`, `Option` and `bool` as one byte and enum variants as their `u32`
+//! index. The layout is not self-describing, so internally tagged and
+//! untagged serde enums cannot be decoded from it.
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+
+use crate::{Result, ZKPError};
+
+impl ser::Error for ZKPError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ZKPError::SerializationError(msg.to_string())
+    }
+}
+
+impl de::Error for ZKPError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ZKPError::SerializationError(msg.to_string())
+    }
+}
+
+/// Encode `value`
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut encoder = Encoder { output: Vec::new() };
+    value.serialize(&mut encoder)?;
+    Ok(encoder.output)
+}
+
+/// Encoded length of `value` in bytes
+pub fn encoded_len<T: Serialize + ?Sized>(value: &T) -> Result<usize> {
+    to_bytes(value).map(|bytes| bytes.len())
+}
+
+/// Decode a `T` spanning all of `bytes`
+///
+/// Length prefixes beyond the bytes left are rejected before anything is
+/// allocated for them, so untrusted input cannot force large allocations.
+pub fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    let mut decoder = Decoder { input: bytes };
+    let value = T::deserialize(&mut decoder)?;
+    if !decoder.input.is_empty() {
+        return Err(ZKPError::SerializationError(format!("{} trailing bytes", decoder.input.len())));
+    }
+    Ok(value)
+}
+
+struct Encoder {
+    output: Vec<u8>,
+}
+
+impl Encoder {
+    fn length(&mut self, len: Option<usize>) -> Result<()> {
+        let len = len.ok_or_else(|| ZKPError::SerializationError("sequences must know their length".to_string()))?;
+        self.output.extend_from_slice(&(len as u64).to_le_bytes());
+        Ok(())
+    }
+
+    fn variant(&mut self, index: u32) {
+        self.output.extend_from_slice(&index.to_le_bytes());
+    }
+}
+
+macro_rules! encode_le {
+    ($($method:ident: $ty:ty),*) => {
+        $(fn $method(self, value: $ty) -> Result<()> {
+            self.output.extend_from_slice(&value.to_le_bytes());
+            Ok(())
+        })*
+    };
+}
+
+impl ser::Serializer for &mut Encoder {
+    type Ok = ();
+    type Error = ZKPError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    encode_le!(
+        serialize_i8: i8, serialize_i16: i16, serialize_i32: i32, serialize_i64: i64, serialize_i128: i128,
+        serialize_u8: u8, serialize_u16: u16, serialize_u32: u32, serialize_u64: u64, serialize_u128: u128,
+        serialize_f32: f32, serialize_f64: f64
+    );
+
+    fn serialize_bool(self, value: bool) -> Result<()> {
+        self.output.push(value as u8);
+        Ok(())
+    }
+
+    fn serialize_char(self, value: char) -> Result<()> {
+        self.output.extend_from_slice(value.encode_utf8(&mut [0u8; 4]).as_bytes());
+        Ok(())
+    }
+
+    fn serialize_str(self, value: &str) -> Result<()> {
+        self.serialize_bytes(value.as_bytes())
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<()> {
+        self.length(Some(value.len()))?;
+        self.output.extend_from_slice(value);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.output.push(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        self.output.push(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, index: u32, _variant: &'static str) -> Result<()> {
+        self.variant(index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.variant(index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self> {
+        self.length(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, index: u32, _variant: &'static str, _len: usize) -> Result<Self> {
+        self.variant(index);
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self> {
+        self.length(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, index: u32, _variant: &'static str, _len: usize) -> Result<Self> {
+        self.variant(index);
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! encode_elements {
+    ($($trait:ident :: $method:ident),*) => {
+        $(impl ser::$trait for &mut Encoder {
+            type Ok = ();
+            type Error = ZKPError;
+
+            fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+                value.serialize(&mut **self)
+            }
+
+            fn end(self) -> Result<()> {
+                Ok(())
+            }
+        })*
+    };
+}
+
+encode_elements!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+impl ser::SerializeMap for &mut Encoder {
+    type Ok = ();
+    type Error = ZKPError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Encoder {
+    type Ok = ();
+    type Error = ZKPError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Encoder {
+    type Ok = ();
+    type Error = ZKPError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+struct Decoder<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Decoder<'de> {
+    fn take(&mut self, len: usize) -> Result<&'de [u8]> {
+        if len > self.input.len() {
+            return Err(ZKPError::SerializationError(format!(
+                "needed {} bytes, {} left",
+                len,
+                self.input.len()
+            )));
+        }
+        let (taken, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+
+    /// A length prefix, which no encoding of that many elements can fit under
+    fn length(&mut self) -> Result<usize> {
+        let len = u64::from_le_bytes(self.array()?);
+        if len > self.input.len() as u64 {
+            return Err(ZKPError::SerializationError(format!(
+                "length {} exceeds the {} bytes left",
+                len,
+                self.input.len()
+            )));
+        }
+        Ok(len as usize)
+    }
+
+    fn byte_string(&mut self) -> Result<&'de [u8]> {
+        let len = self.length()?;
+        self.take(len)
+    }
+}
+
+macro_rules! decode_le {
+    ($($method:ident: $ty:ty => $visit:ident),*) => {
+        $(fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            visitor.$visit(<$ty>::from_le_bytes(self.array()?))
+        })*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Decoder<'de> {
+    type Error = ZKPError;
+
+    decode_le!(
+        deserialize_i8: i8 => visit_i8, deserialize_i16: i16 => visit_i16, deserialize_i32: i32 => visit_i32,
+        deserialize_i64: i64 => visit_i64, deserialize_i128: i128 => visit_i128,
+        deserialize_u8: u8 => visit_u8, deserialize_u16: u16 => visit_u16, deserialize_u32: u32 => visit_u32,
+        deserialize_u64: u64 => visit_u64, deserialize_u128: u128 => visit_u128,
+        deserialize_f32: f32 => visit_f32, deserialize_f64: f64 => visit_f64
+    );
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+        Err(ZKPError::SerializationError("the wire encoding is not self-describing".to_string()))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.take(1)?[0] {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            flag => Err(ZKPError::SerializationError(format!("invalid bool {}", flag))),
+        }
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let width = match self.input.first() {
+            Some(byte) if *byte < 0x80 => 1,
+            Some(byte) if *byte >> 5 == 0b110 => 2,
+            Some(byte) if *byte >> 4 == 0b1110 => 3,
+            Some(byte) if *byte >> 3 == 0b11110 => 4,
+            _ => return Err(ZKPError::SerializationError("invalid char".to_string())),
+        };
+        let encoded = std::str::from_utf8(self.take(width)?).map_err(|e| ZKPError::SerializationError(e.to_string()))?;
+        visitor.visit_char(encoded.chars().next().expect("one char"))
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let bytes = self.byte_string()?;
+        visitor.visit_borrowed_str(std::str::from_utf8(bytes).map_err(|e| ZKPError::SerializationError(e.to_string()))?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_borrowed_bytes(self.byte_string()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.take(1)?[0] {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            tag => Err(ZKPError::SerializationError(format!("invalid option tag {}", tag))),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let len = self.length()?;
+        visitor.visit_seq(Elements { decoder: self, remaining: len })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Elements { decoder: self, remaining: len })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let len = self.length()?;
+        visitor.visit_map(Elements { decoder: self, remaining: len })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+struct Elements<'a, 'de> {
+    decoder: &'a mut Decoder<'de>,
+    remaining: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Elements<'_, 'de> {
+    type Error = ZKPError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Elements<'_, 'de> {
+    type Error = ZKPError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(&mut *self.decoder)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Decoder<'de> {
+    type Error = ZKPError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let index = u32::from_le_bytes(self.array()?);
+        let variant = seed.deserialize(IntoDeserializer::<ZKPError>::into_deserializer(index))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Decoder<'de> {
+    type Error = ZKPError;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Point,
+        Circle(u32),
+        Rect { width: u16, height: u16 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        id: u32,
+        label: Option<String>,
+        shapes: Vec<Shape>,
+        flag: bool,
+    }
+
+    #[test]
+    fn test_layout_is_fixed_width_little_endian() {
+        let record = Record {
+            id: 7,
+            label: Some("ab".to_string()),
+            shapes: vec![Shape::Point, Shape::Circle(3), Shape::Rect { width: 1, height: 2 }],
+            flag: true,
+        };
+        let bytes = to_bytes(&record).unwrap();
+        let mut expected = vec![7, 0, 0, 0, 1, 2, 0, 0, 0, 0, 0, 0, 0, b'a', b'b', 3, 0, 0, 0, 0, 0, 0, 0];
+        expected.extend([0, 0, 0, 0, 1, 0, 0, 0, 3, 0, 0, 0, 2, 0, 0, 0, 1, 0, 2, 0, 1]);
+        assert_eq!(bytes, expected);
+        assert_eq!(encoded_len(&record).unwrap(), expected.len());
+        assert_eq!(from_bytes::<Record>(&bytes).unwrap(), record);
+    }
+
+    #[test]
+    fn test_rejects_malformed_input() {
+        let bytes = to_bytes(&(true, 5u64)).unwrap();
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(from_bytes::<(bool, u64)>(&trailing).is_err());
+        assert!(from_bytes::<(bool, u64)>(&bytes[..8]).is_err());
+        assert!(from_bytes::<(bool, u64)>(&[[2u8].as_slice(), &bytes[1..]].concat()).is_err());
+
+        // A length prefix claiming more elements than bytes left
+        assert!(from_bytes::<Vec<u8>>(&u64::MAX.to_le_bytes()).is_err());
+        assert!(from_bytes::<Shape>(&9u32.to_le_bytes()).is_err());
+    }
+}